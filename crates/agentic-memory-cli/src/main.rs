@@ -9,11 +9,18 @@ use clap_complete::Shell;
 use serde::{Deserialize, Serialize};
 
 use agentic_memory::cli::commands;
-use agentic_memory::engine::{PatternSort, QueryEngine, TextSearchParams};
+use agentic_memory::engine::{
+    CausalDirection, DeleteFilter, PatternSort, QueryEngine, RedactionCategory, Redactor,
+    TextSearchParams, TimeRange, DEFAULT_EPISODE_MEMBER_LIMIT,
+};
 use agentic_memory::format::AmemReader;
 use agentic_memory::graph::TraversalDirection;
+use agentic_memory::types::tags::parse_tag;
 use agentic_memory::types::{EdgeType, EventType};
+use agentic_memory_mcp::config::ResolvedConfig;
+use agentic_memory_mcp::session::backup;
 use agentic_memory_mcp::session::workspace::{ContextRole, WorkspaceManager};
+use agentic_memory_mcp::session::SessionSettings;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct WorkspaceContext {
@@ -27,6 +34,13 @@ struct WorkspaceState {
     workspaces: HashMap<String, Vec<WorkspaceContext>>,
 }
 
+/// Portable definition of a single workspace, as written by `workspace export`.
+#[derive(Debug, Serialize, Deserialize)]
+struct WorkspaceDefinition {
+    name: String,
+    contexts: Vec<WorkspaceContext>,
+}
+
 fn state_path() -> PathBuf {
     let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
     PathBuf::from(home)
@@ -58,6 +72,174 @@ fn save_state(state: &WorkspaceState) -> agentic_memory::AmemResult<()> {
     Ok(())
 }
 
+fn mcp_error(e: agentic_memory_mcp::types::McpError) -> agentic_memory::AmemError {
+    agentic_memory::AmemError::Io(std::io::Error::new(
+        std::io::ErrorKind::InvalidInput,
+        e.to_string(),
+    ))
+}
+
+/// Parse `START..END`, `session:ID` or `sessions:ID,ID` into a time range.
+fn parse_time_range(raw: &str) -> Option<TimeRange> {
+    let raw = raw.trim();
+    if let Some(id) = raw.strip_prefix("session:") {
+        return id.trim().parse().ok().map(TimeRange::Session);
+    }
+    if let Some(ids) = raw.strip_prefix("sessions:") {
+        let ids: Option<Vec<u32>> = ids.split(',').map(|t| t.trim().parse().ok()).collect();
+        return ids.map(TimeRange::Sessions);
+    }
+    let (start, end) = raw.split_once("..")?;
+    Some(TimeRange::TimeWindow {
+        start: start.trim().parse().ok()?,
+        end: end.trim().parse().ok()?,
+    })
+}
+
+/// Resolve a `--session` value (ID or alias), exiting on an unknown alias.
+fn session_arg(file: &Path, value: &str) -> u32 {
+    match commands::resolve_session_ref(file, value) {
+        Ok(id) => id,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(3);
+        }
+    }
+}
+
+/// Look up comma-separated edge type names in `file`, without registering
+/// custom types. Unknown names are skipped, or exit with an error when
+/// `strict`.
+fn edge_types_arg(file: &Path, list: Option<&str>, strict: bool) -> Vec<EdgeType> {
+    let Some(list) = list else {
+        return Vec::new();
+    };
+    let resolved = match commands::resolve_edge_type_refs(file, list) {
+        Ok(resolved) => resolved,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(3);
+        }
+    };
+    resolved
+        .into_iter()
+        .filter_map(|(name, edge_type)| {
+            if edge_type.is_none() && strict {
+                eprintln!("Invalid edge type: {}", name);
+                process::exit(3);
+            }
+            edge_type
+        })
+        .collect()
+}
+
+/// Parse `type=cost,...` edge type costs, exiting on an unknown type or a
+/// negative cost.
+fn type_cost_arg(file: &Path, raw: Option<&str>) -> HashMap<EdgeType, f32> {
+    let mut costs = HashMap::new();
+    for pair in raw
+        .unwrap_or_default()
+        .split(',')
+        .filter(|p| !p.trim().is_empty())
+    {
+        let parsed = pair.split_once('=').and_then(|(name, cost)| {
+            let edge_type = *edge_types_arg(file, Some(name), false).first()?;
+            let cost: f32 = cost.trim().parse().ok()?;
+            (cost.is_finite() && cost >= 0.0).then_some((edge_type, cost))
+        });
+        match parsed {
+            Some((edge_type, cost)) => {
+                costs.insert(edge_type, cost);
+            }
+            None => {
+                eprintln!(
+                    "Invalid type cost '{}'. Use TYPE=COST with a non-negative cost, e.g. supports=0.5.",
+                    pair.trim()
+                );
+                process::exit(3);
+            }
+        }
+    }
+    costs
+}
+
+/// Parse repeated `key=value` tag arguments, exiting on a malformed one.
+fn tags_arg(values: &[String]) -> Vec<(String, String)> {
+    values
+        .iter()
+        .map(|value| match parse_tag(value) {
+            Some(tag) => tag,
+            None => {
+                eprintln!("Invalid tag '{}'. Use key=value.", value);
+                process::exit(3);
+            }
+        })
+        .collect()
+}
+
+/// Build a redactor from an optional comma-separated category list,
+/// exiting on an unknown category.
+fn redactor_arg(categories: Option<&str>) -> Redactor {
+    let Some(names) = categories else {
+        return Redactor::new();
+    };
+    let mut parsed = Vec::new();
+    for name in names.split(',') {
+        match RedactionCategory::from_name(name) {
+            Some(category) => parsed.push(category),
+            None => {
+                eprintln!(
+                    "Unknown redaction category '{}'. Use secrets, emails, or paths.",
+                    name.trim()
+                );
+                process::exit(3);
+            }
+        }
+    }
+    Redactor::with_categories(parsed)
+}
+
+/// Resolve an optional comma-separated list of session IDs and aliases.
+fn session_list_arg(file: &Path, value: Option<&str>) -> Vec<u32> {
+    match value.map(|v| commands::resolve_session_refs(file, v)) {
+        Some(Ok(ids)) => ids,
+        Some(Err(e)) => {
+            eprintln!("{}", e);
+            process::exit(3);
+        }
+        None => Vec::new(),
+    }
+}
+
+fn absolute_path(path: &Path) -> PathBuf {
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir()
+            .map(|cwd| cwd.join(path))
+            .unwrap_or_else(|_| path.to_path_buf())
+    }
+}
+
+/// Rewrite a context path relative to `base`. Paths outside `base` stay absolute.
+fn relativize_path(path: &str, base: &Path) -> String {
+    let abs = absolute_path(Path::new(path));
+    match abs.strip_prefix(absolute_path(base)) {
+        Ok(rel) => rel.to_string_lossy().to_string(),
+        Err(_) => abs.to_string_lossy().to_string(),
+    }
+}
+
+/// Resolve a relative context path against `base`.
+fn resolve_path(path: &str, base: &Path) -> String {
+    let p = Path::new(path);
+    if p.is_absolute() {
+        path.to_string()
+    } else {
+        absolute_path(&base.join(p)).to_string_lossy().to_string()
+    }
+}
+
 fn load_workspace_manager(
     state: &WorkspaceState,
     workspace: &str,
@@ -135,6 +317,12 @@ struct Cli {
     #[arg(long)]
     verbose: bool,
 
+    /// Encryption key for encrypted .amem files: 64 hex digits, or the name
+    /// of an environment variable holding a hex key or a passphrase
+    #[cfg(feature = "encryption")]
+    #[arg(long)]
+    key: Option<String>,
+
     #[command(subcommand)]
     command: Option<Commands>,
 }
@@ -155,6 +343,17 @@ enum Commands {
         /// Path to the .amem file
         file: PathBuf,
     },
+    /// Repair a file whose header dimension disagrees with its vectors
+    FixDimension {
+        /// Path to the .amem file
+        file: PathBuf,
+        /// Dimension to pad or truncate every vector to
+        #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+        dimension: u32,
+        /// Where to copy the original file (default: <stem>.pre-fix-dimension.amem)
+        #[arg(long)]
+        backup: Option<PathBuf>,
+    },
     /// Add a cognitive event to the graph
     Add {
         /// Path to the .amem file
@@ -164,15 +363,30 @@ enum Commands {
         event_type: String,
         /// The content text
         content: String,
-        /// Session ID
+        /// Session ID or alias
         #[arg(long, default_value = "0")]
-        session: u32,
+        session: String,
         /// Confidence 0.0-1.0
         #[arg(long, default_value = "1.0")]
         confidence: f32,
+        /// Tag the node with key=value metadata (repeatable)
+        #[arg(long = "tag")]
+        tags: Vec<String>,
         /// For corrections: the node ID being corrected
         #[arg(long)]
         supersedes: Option<u64>,
+        /// Feature vector for the new node (comma-separated floats)
+        #[arg(long)]
+        vector: Option<String>,
+        /// Model that produced --vector; recorded in the file if unset
+        #[arg(long, requires = "vector")]
+        embedding_model: Option<String>,
+        /// Fail instead of warning when --embedding-model differs from the file's
+        #[arg(long, requires = "embedding_model")]
+        strict: bool,
+        /// Detect the content language and tokenize the node with its rules
+        #[arg(long)]
+        detect_language: bool,
     },
     /// Add an edge between two nodes
     Link {
@@ -182,11 +396,14 @@ enum Commands {
         source_id: u64,
         /// Target node ID
         target_id: u64,
-        /// Edge type
+        /// Edge type: a built-in type or a custom name such as implements
         edge_type: String,
         /// Edge weight 0.0-1.0
         #[arg(long, default_value = "1.0")]
         weight: f32,
+        /// Also add the reverse edge (for symmetric types such as related_to)
+        #[arg(long)]
+        bidirectional: bool,
     },
     /// Get a specific node by ID
     Get {
@@ -195,6 +412,27 @@ enum Commands {
         /// Node ID
         node_id: u64,
     },
+    /// Pin a node so decay never lowers its score
+    Pin {
+        /// Path to the .amem file
+        file: PathBuf,
+        /// Node ID
+        node_id: u64,
+    },
+    /// Unpin a node so it decays normally again
+    Unpin {
+        /// Path to the .amem file
+        file: PathBuf,
+        /// Node ID
+        node_id: u64,
+    },
+    /// Show a node's access history and decay trajectory
+    History {
+        /// Path to the .amem file
+        file: PathBuf,
+        /// Node ID
+        node_id: u64,
+    },
     /// Run a traversal query from a starting node
     Traverse {
         /// Path to the .amem file
@@ -216,6 +454,44 @@ enum Commands {
         /// Minimum confidence filter
         #[arg(long, default_value = "0.0")]
         min_confidence: f32,
+        /// Follow symmetric edges (related_to, contradicts) both ways
+        #[arg(long)]
+        symmetric: bool,
+    },
+    /// Vector similarity search with optional expansion along edges
+    Similar {
+        /// Path to the .amem file
+        file: PathBuf,
+        /// Use this node's feature vector as the query
+        #[arg(required_unless_present = "vector")]
+        node_id: Option<u64>,
+        /// Query vector (comma-separated floats)
+        #[arg(long, conflicts_with = "node_id")]
+        vector: Option<String>,
+        /// Maximum direct matches
+        #[arg(long, default_value = "10")]
+        top_k: usize,
+        /// Minimum cosine similarity for direct matches
+        #[arg(long, default_value = "0.5")]
+        min_similarity: f32,
+        /// Comma-separated edge types to expand matches along
+        #[arg(long)]
+        expand: Option<String>,
+        /// Hops to expand from each match
+        #[arg(long, default_value = "1")]
+        hops: u32,
+        /// Score multiplier per hop
+        #[arg(long, default_value = "0.5")]
+        decay: f32,
+        /// Neighbors followed per node per hop, strongest edges first
+        #[arg(long, default_value = "10")]
+        max_neighbors: usize,
+        /// Score only this fraction of nodes (0-1] for a quick approximate answer
+        #[arg(long, value_parser = commands::parse_sample_fraction)]
+        sample: Option<f32>,
+        /// Seed for --sample (random if omitted; printed with the results)
+        #[arg(long, requires = "sample")]
+        sample_seed: Option<u64>,
     },
     /// Pattern query — find nodes matching conditions
     #[command(name = "query", alias = "search")]
@@ -225,7 +501,7 @@ enum Commands {
         /// Comma-separated event types to filter
         #[arg(long, name = "type")]
         event_types: Option<String>,
-        /// Comma-separated session IDs
+        /// Comma-separated session IDs or aliases
         #[arg(long)]
         session: Option<String>,
         /// Minimum confidence
@@ -246,6 +522,21 @@ enum Commands {
         /// Maximum results
         #[arg(long, default_value = "20")]
         limit: usize,
+        /// Expand Episode hits into the nodes that are PartOf them
+        #[arg(long)]
+        expand_episodes: bool,
+        /// Maximum nodes listed per expanded Episode [default: 10]
+        #[arg(long, requires = "expand_episodes")]
+        episode_limit: Option<usize>,
+        /// Comma-separated fields to output: id, type, confidence, content, session_id
+        #[arg(long, value_parser = commands::parse_query_fields)]
+        fields: Option<commands::FieldList>,
+        /// Show which filters each result passed and its sort key
+        #[arg(long)]
+        explain: bool,
+        /// Print a per-phase timing breakdown to stderr
+        #[arg(long)]
+        profile: bool,
     },
     /// Run causal impact analysis on a node
     Impact {
@@ -256,6 +547,9 @@ enum Commands {
         /// Maximum depth
         #[arg(long, default_value = "10")]
         max_depth: u32,
+        /// Trace what the node is based on, back to its root causes
+        #[arg(long)]
+        upstream: bool,
     },
     /// Follow SUPERSEDES chain to find the latest version of a node
     Resolve {
@@ -271,17 +565,89 @@ enum Commands {
         /// Maximum sessions to show
         #[arg(long, default_value = "20")]
         limit: usize,
+        /// Show last activity and flag likely in-progress sessions: newest
+        /// node within the window and no closing episode
+        #[arg(long)]
+        active: bool,
+        /// Recency window for --active, in minutes
+        #[arg(long, default_value = "30", requires = "active")]
+        active_window: u64,
     },
-    /// Export the graph as JSON
+    /// Show, assign, or remove a stable session alias
+    SessionAlias {
+        /// Path to the .amem file
+        file: PathBuf,
+        /// Session ID or current alias
+        session: String,
+        /// Alias to assign (omit to show the current alias)
+        alias: Option<String>,
+        /// Remove the session's alias
+        #[arg(long, conflicts_with = "alias")]
+        remove: bool,
+    },
+    /// Compare knowledge across two time ranges or sessions
+    ///
+    /// Ranges are START..END (Unix microseconds), session:ID, or sessions:ID,ID.
+    /// Both default to the whole timeline.
+    Temporal {
+        /// Path to the .amem file
+        file: PathBuf,
+        /// First range
+        #[arg(long = "range-a")]
+        range_a: Option<String>,
+        /// Second range (defaults to the first)
+        #[arg(long = "range-b")]
+        range_b: Option<String>,
+        /// Group results by session with per-session summaries
+        #[arg(long)]
+        group_by_session: bool,
+    },
+    /// Export the graph as JSON, Graphviz DOT, Parquet or Markdown notes
     Export {
         /// Path to the .amem file
         file: PathBuf,
         /// Export only nodes, no edges
         #[arg(long)]
         nodes_only: bool,
-        /// Export only nodes from a specific session
+        /// Export only nodes from a specific session (ID or alias)
+        #[arg(long)]
+        session: Option<String>,
+        /// Export only nodes of these event types, comma-separated
+        #[arg(long = "type")]
+        event_types: Option<String>,
+        /// Export only nodes whose PageRank is at least this value
+        #[arg(long)]
+        min_centrality: Option<f32>,
+        /// With --min-centrality, also keep nodes that connect retained nodes
+        #[arg(long, requires = "min_centrality")]
+        keep_connectors: bool,
+        /// Export only edges between different sessions, with their endpoints
+        #[arg(long, conflicts_with = "session")]
+        cross_session: bool,
+        /// Scrub secrets from node content in the output (the file is unchanged)
+        #[arg(long)]
+        redact: bool,
+        /// Categories to redact, comma-separated: secrets, emails, paths (default: all)
+        #[arg(long, requires = "redact")]
+        redact_categories: Option<String>,
+        /// Replace node IDs with a shuffled range, keeping edges consistent
         #[arg(long)]
-        session: Option<u32>,
+        anonymize_ids: bool,
+        /// Seed for a reproducible --anonymize-ids mapping
+        #[arg(long, requires = "anonymize_ids")]
+        anonymize_seed: Option<u64>,
+        /// Write the anonymized-to-original ID mapping to this file
+        #[arg(long, requires = "anonymize_ids")]
+        emit_id_map: Option<PathBuf>,
+        /// Output format: json, dot, parquet or markdown
+        #[arg(long, default_value = "json")]
+        format: String,
+        /// Parquet file for the nodes, or directory for the Markdown notes
+        #[arg(long)]
+        output: Option<PathBuf>,
+        /// Parquet file to write the edges to (--format parquet only)
+        #[arg(long, requires = "output")]
+        edges: Option<PathBuf>,
         /// Pretty-print JSON
         #[arg(long)]
         pretty: bool,
@@ -327,6 +693,55 @@ enum Commands {
         file: PathBuf,
         /// Path to the JSON file
         json_file: PathBuf,
+        /// What to do with content that already exists: append, skip-duplicate, update-confidence, supersede
+        #[arg(long, default_value = "append")]
+        merge_strategy: String,
+        /// Token similarity at or above which imported content counts as a duplicate
+        #[arg(long, default_value = "0.9")]
+        duplicate_threshold: f32,
+    },
+    /// Import Markdown notes: headings as episodes, list items as facts, quotes as inferences
+    ImportMarkdown {
+        /// Path to the Markdown file
+        markdown_file: PathBuf,
+        /// Path to the .amem file
+        file: PathBuf,
+        /// Session ID or alias for the imported nodes (default: a new session)
+        #[arg(long)]
+        session: Option<String>,
+        /// Event type of list items: fact or decision
+        #[arg(long = "type", default_value = "fact")]
+        list_type: String,
+    },
+    /// Merge two .amem files into a new one
+    Merge {
+        /// First .amem file; its node IDs and sessions come first
+        file_a: PathBuf,
+        /// Second .amem file; its sessions are shifted past the first's
+        file_b: PathBuf,
+        /// Where to write the merged file
+        #[arg(short, long)]
+        output: PathBuf,
+        /// How to combine nodes: deduplicate, union, intersect
+        #[arg(long, default_value = "deduplicate")]
+        strategy: String,
+    },
+    /// Compare two .amem files node by node
+    Diff {
+        /// First (older) .amem file
+        file_a: PathBuf,
+        /// Second (newer) .amem file
+        file_b: PathBuf,
+        /// Output format: text, json, or patch (for apply-patch)
+        #[arg(long, default_value = "text")]
+        format: String,
+    },
+    /// Apply a patch written by `diff --format patch`
+    ApplyPatch {
+        /// Path to the .amem file to patch
+        file: PathBuf,
+        /// Patch file
+        patch: PathBuf,
     },
     /// Run decay calculations
     Decay {
@@ -336,10 +751,39 @@ enum Commands {
         #[arg(long, default_value = "0.1")]
         threshold: f32,
     },
+    /// Print the most recently created nodes across all sessions, newest first
+    TailSession {
+        /// Path to the .amem file
+        file: PathBuf,
+        /// Number of nodes to show
+        #[arg(short = 'n', long = "lines", default_value = "20")]
+        lines: usize,
+        /// Keep polling the file and print new nodes as they appear
+        #[arg(short, long)]
+        follow: bool,
+        /// Seconds between polls with --follow
+        #[arg(long, default_value = "2", requires = "follow")]
+        interval: u64,
+        /// Stop after this many polls with --follow
+        #[arg(long, requires = "follow")]
+        count: Option<usize>,
+    },
     /// Detailed statistics about the graph
     Stats {
         /// Path to the .amem file
         file: PathBuf,
+        /// Re-read the file and redraw until interrupted
+        #[arg(long)]
+        watch: bool,
+        /// Seconds between refreshes with --watch
+        #[arg(long, default_value = "2", requires = "watch")]
+        interval: u64,
+        /// Stop after this many refreshes with --watch
+        #[arg(long, requires = "watch")]
+        count: Option<usize>,
+        /// Print stats next to those of a baseline file, with deltas
+        #[arg(long, conflicts_with = "watch")]
+        compare: Option<PathBuf>,
     },
     /// Graph health and memory quality report
     Quality {
@@ -365,9 +809,9 @@ enum Commands {
         /// Maximum directory depth for scan
         #[arg(long, default_value = "4")]
         max_depth: u32,
-        /// Session ID for episode write (0 = latest session)
+        /// Session ID or alias for episode write (0 = latest session)
         #[arg(long, default_value = "0")]
-        session: u32,
+        session: String,
         /// Persist a sync snapshot as an Episode node
         #[arg(long)]
         write_episode: bool,
@@ -392,7 +836,7 @@ enum Commands {
         /// Comma-separated event types to filter
         #[arg(long, name = "type")]
         event_types: Option<String>,
-        /// Comma-separated session IDs
+        /// Comma-separated session IDs or aliases
         #[arg(long)]
         session: Option<String>,
         /// Maximum results
@@ -401,6 +845,78 @@ enum Commands {
         /// Minimum BM25 score
         #[arg(long, default_value = "0.0")]
         min_score: f32,
+        /// Only return nodes containing every query term
+        #[arg(long)]
+        all: bool,
+        /// Weight node confidence into the score (0.0 = pure BM25, 1.0 = fully weighted)
+        #[arg(long, default_value = "0.0")]
+        confidence_weight: f32,
+        /// Expand Episode hits into the nodes that are PartOf them
+        #[arg(long)]
+        expand_episodes: bool,
+        /// Maximum nodes listed per expanded Episode [default: 10]
+        #[arg(long, requires = "expand_episodes")]
+        episode_limit: Option<usize>,
+        /// Show each result's per-term BM25 scores, confidence factor and filters
+        #[arg(long)]
+        explain: bool,
+    },
+    /// Typo-tolerant search by Jaro-Winkler similarity of query and content words
+    FuzzySearch {
+        /// Path to the .amem file
+        file: PathBuf,
+        /// Search query text
+        query: String,
+        /// Comma-separated event types to filter
+        #[arg(long, name = "type")]
+        event_types: Option<String>,
+        /// Maximum results
+        #[arg(long, default_value = "20")]
+        limit: usize,
+        /// Minimum Jaro-Winkler similarity (0.0-1.0) for a word to match
+        #[arg(long, default_value = "0.8")]
+        min_similarity: f32,
+    },
+    /// Group nodes into fixed time windows, oldest first
+    TemporalClusters {
+        /// Path to the .amem file
+        file: PathBuf,
+        /// Window width, e.g. 30m, 1h, 1d (a bare number means days)
+        #[arg(long, value_parser = commands::parse_age_secs)]
+        window: u64,
+        /// Comma-separated event types to filter
+        #[arg(long = "type")]
+        event_types: Option<String>,
+        /// Comma-separated session IDs or aliases
+        #[arg(long)]
+        session: Option<String>,
+    },
+    /// grep-style search: nodes whose content matches a regular expression
+    RegexSearch {
+        /// Path to the .amem file
+        file: PathBuf,
+        /// Regular expression; ^ and $ match at line breaks
+        pattern: String,
+        /// Match letter case exactly
+        #[arg(long)]
+        case_sensitive: bool,
+        /// Comma-separated event types to filter
+        #[arg(long = "type")]
+        event_types: Option<String>,
+        /// Maximum results
+        #[arg(long, default_value = "20")]
+        limit: usize,
+    },
+    /// Show BM25 term statistics: frequent terms, or the nodes holding one term
+    Terms {
+        /// Path to the .amem file
+        file: PathBuf,
+        /// Number of most frequent terms to show
+        #[arg(long, default_value = "50")]
+        top: usize,
+        /// Show the nodes containing this term instead
+        #[arg(long)]
+        term: Option<String>,
     },
     /// Combined BM25 + vector search with RRF fusion
     HybridSearch {
@@ -420,14 +936,23 @@ enum Commands {
         /// Comma-separated event types to filter
         #[arg(long, name = "type")]
         event_types: Option<String>,
+        /// Expand Episode hits into the nodes that are PartOf them
+        #[arg(long)]
+        expand_episodes: bool,
+        /// Maximum nodes listed per expanded Episode [default: 10]
+        #[arg(long, requires = "expand_episodes")]
+        episode_limit: Option<usize>,
     },
     /// Compute node importance scores
     Centrality {
         /// Path to the .amem file
         file: PathBuf,
-        /// Algorithm: pagerank, degree, or betweenness
+        /// Algorithm: pagerank, degree, betweenness, or kmeans (PageRank plus semantic clusters)
         #[arg(long, default_value = "pagerank")]
         algorithm: String,
+        /// Number of k-means clusters for --algorithm kmeans
+        #[arg(long, default_value = "5")]
+        k: usize,
         /// PageRank damping factor
         #[arg(long, default_value = "0.85")]
         damping: f32,
@@ -440,9 +965,24 @@ enum Commands {
         /// Top N results
         #[arg(long, default_value = "20")]
         limit: usize,
-        /// Max iterations for PageRank
+        /// Max iterations for PageRank (and k-means rounds)
         #[arg(long, default_value = "100")]
         iterations: u32,
+        /// Comma-separated node IDs to personalize PageRank around
+        #[arg(long)]
+        seeds: Option<String>,
+        /// Personalize PageRank around nodes matching this text query
+        #[arg(long)]
+        seed_query: Option<String>,
+        /// Let PageRank flow both ways along symmetric edges (related_to, contradicts)
+        #[arg(long)]
+        symmetric: bool,
+        /// Estimate from this fraction of nodes (0-1] for a quick approximate answer
+        #[arg(long, value_parser = commands::parse_sample_fraction)]
+        sample: Option<f32>,
+        /// Seed for --sample (random if omitted; printed with the results)
+        #[arg(long, requires = "sample")]
+        sample_seed: Option<u64>,
     },
     /// Find shortest path between two nodes
     Path {
@@ -464,6 +1004,9 @@ enum Commands {
         /// Use edge weights for path cost
         #[arg(long)]
         weighted: bool,
+        /// Per-edge-type cost multipliers, e.g. supports=0.5,related_to=2.0
+        #[arg(long, requires = "weighted")]
+        type_cost: Option<String>,
     },
     /// Belief revision — counterfactual analysis
     Revise {
@@ -513,7 +1056,7 @@ enum Commands {
         /// Minimum structural match
         #[arg(long, default_value = "0.3")]
         min_similarity: f32,
-        /// Comma-separated sessions to exclude
+        /// Comma-separated sessions (IDs or aliases) to exclude
         #[arg(long)]
         exclude_session: Option<String>,
         /// Context depth
@@ -552,6 +1095,150 @@ enum Commands {
         #[arg(long)]
         backup: Option<PathBuf>,
     },
+    /// Sign a file with an Ed25519 private key (replaces any existing signature)
+    #[cfg(feature = "signing")]
+    Sign {
+        /// Path to the .amem file
+        file: PathBuf,
+        /// PKCS#8 PEM private key
+        #[arg(long)]
+        key: PathBuf,
+    },
+    /// Check that a file parses and report whether it is signed
+    #[cfg(feature = "signing")]
+    Verify {
+        /// Path to the .amem file
+        file: PathBuf,
+        /// Require a valid signature made with the --pub key
+        #[arg(long, requires = "pub_key")]
+        signatures: bool,
+        /// SPKI PEM public key
+        #[arg(long = "pub", requires = "signatures")]
+        pub_key: Option<PathBuf>,
+    },
+    /// List nodes written by MCP auto-capture and what triggered them
+    CaptureLog {
+        /// Path to the .amem file
+        file: PathBuf,
+        /// Number of captures to list, newest first
+        #[arg(long, default_value = "50")]
+        limit: usize,
+        /// Remove every auto-captured node instead of listing them (dry-run by default)
+        #[arg(long)]
+        purge: bool,
+        /// Apply the purge
+        #[arg(long, requires = "purge")]
+        confirm: bool,
+        /// Where to copy the original before purging (default: <stem>.pre-purge.amem)
+        #[arg(long, requires = "purge")]
+        backup: Option<PathBuf>,
+    },
+    /// Remove nodes not created or accessed within a time window, or all but the most important (dry-run by default)
+    Compact {
+        /// Path to the .amem file
+        file: PathBuf,
+        /// Age window, e.g. 90d, 12h, 2w (a bare number means days)
+        #[arg(long, value_parser = commands::parse_age_secs, required_unless_present = "keep_top")]
+        older_than: Option<u64>,
+        /// Keep only the N most important nodes instead of compacting by age
+        #[arg(long, conflicts_with = "older_than")]
+        keep_top: Option<usize>,
+        /// Comma-separated node IDs that --keep-top never removes
+        #[arg(long, requires = "keep_top")]
+        pin: Option<String>,
+        /// Redirect edges into cut nodes to their session's episode instead of dropping them
+        #[arg(long, requires = "keep_top")]
+        rewire_to_episode: bool,
+        /// Comma-separated event types never removed, e.g. fact,skill
+        #[arg(long)]
+        keep_types: Option<String>,
+        /// Actually remove the nodes (default: dry-run)
+        #[arg(long)]
+        confirm: bool,
+        /// Where to copy the original file (default: <stem>.pre-compact.amem)
+        #[arg(long)]
+        backup: Option<PathBuf>,
+    },
+    /// Delete every node matching all given filters, with its edges (dry-run by default)
+    Delete {
+        /// Path to the .amem file
+        file: PathBuf,
+        /// Comma-separated event types to delete
+        #[arg(long = "type")]
+        event_types: Option<String>,
+        /// Only nodes whose decay score is below this
+        #[arg(long)]
+        decay_below: Option<f32>,
+        /// Only nodes whose confidence is below this
+        #[arg(long)]
+        confidence_below: Option<f32>,
+        /// Comma-separated session IDs or aliases
+        #[arg(long)]
+        session: Option<String>,
+        /// Only nodes created before this time (Unix microseconds)
+        #[arg(long)]
+        created_before: Option<u64>,
+        /// Actually delete the nodes (default: dry-run)
+        #[arg(long)]
+        yes: bool,
+        /// Where to copy the original file (default: <stem>.pre-delete.amem)
+        #[arg(long)]
+        backup: Option<PathBuf>,
+    },
+    /// Rewrite a file at the current storage version, checkpointing the original
+    Migrate {
+        /// Path to the .amem file
+        file: PathBuf,
+        /// Report what the migration would do without writing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Check the graph for dangling edges, SUPERSEDES cycles and index drift
+    Fsck {
+        /// Path to the .amem file
+        file: PathBuf,
+        /// Break SUPERSEDES cycles by removing the edge that closes each one
+        #[arg(long)]
+        repair: bool,
+    },
+    /// Review near-duplicate facts pair by pair, or replay a saved review
+    Dedup {
+        /// Path to the .amem file
+        file: PathBuf,
+        /// Prompt for each candidate pair on stdin
+        #[arg(
+            long,
+            required_unless_present = "apply_plan",
+            conflicts_with = "apply_plan"
+        )]
+        interactive: bool,
+        /// Minimum cosine similarity for a pair to be reviewed
+        #[arg(long, default_value = "0.80")]
+        floor: f32,
+        /// Save the decisions to a JSON plan
+        #[arg(long)]
+        plan_out: Option<PathBuf>,
+        /// Apply decisions from a saved plan without prompting
+        #[arg(long)]
+        apply_plan: Option<PathBuf>,
+        /// Review and save the plan without changing the file
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Propose Supports edges from facts to the inferences drawn from them (dry-run by default)
+    LinkInferences {
+        /// Path to the .amem file
+        file: PathBuf,
+        /// Minimum token overlap (Jaccard) for a fact to be proposed
+        #[arg(long, default_value = "0.3")]
+        min_score: f32,
+        /// Most facts proposed per inference
+        #[arg(long, default_value = "3")]
+        max_per_inference: usize,
+        /// Add the proposed edges (default: list them only)
+        #[arg(long)]
+        apply: bool,
+    },
     /// Track how beliefs about a topic evolved
     Drift {
         /// Path to the .amem file
@@ -565,6 +1252,28 @@ enum Commands {
         #[arg(long, default_value = "0.5")]
         min_relevance: f32,
     },
+    /// Restore a brain from its auto-backups (full copies or incremental chains)
+    Restore {
+        /// Path to the .amem file to restore
+        file: PathBuf,
+        /// Backup file to restore to (a .amem.bak base or .amem.delta); defaults to the newest
+        #[arg(long)]
+        from: Option<PathBuf>,
+        /// Write the restored brain here instead of to the .amem file
+        #[arg(long)]
+        output: Option<PathBuf>,
+        /// List available restore points and exit
+        #[arg(long)]
+        list: bool,
+        /// Overwrite the destination if it already exists
+        #[arg(long)]
+        force: bool,
+    },
+    /// Inspect the resolved server configuration
+    Config {
+        #[command(subcommand)]
+        subcommand: ConfigCommands,
+    },
     /// Generate shell completion scripts
     ///
     /// Examples:
@@ -577,6 +1286,22 @@ enum Commands {
     },
 }
 
+#[derive(Subcommand)]
+enum ConfigCommands {
+    /// Print every effective setting and its source (flag/env/file/default)
+    Show {
+        /// Path to the .amem file, as passed to the server's --memory
+        #[arg(long)]
+        memory: Option<String>,
+        /// Configuration file, as passed to the server's --config
+        #[arg(long)]
+        config: Option<String>,
+        /// Emit JSON regardless of --format
+        #[arg(long)]
+        json: bool,
+    },
+}
+
 #[derive(Subcommand)]
 enum WorkspaceCommands {
     /// Create a workspace
@@ -608,6 +1333,27 @@ enum WorkspaceCommands {
     },
     /// Cross-reference an item across workspace contexts
     Xref { workspace: String, item: String },
+    /// Print a workspace definition as portable JSON
+    Export {
+        workspace: String,
+        /// Write context paths relative to this directory
+        #[arg(long)]
+        relative_to: Option<PathBuf>,
+    },
+    /// Import a workspace definition written by `workspace export`
+    Import {
+        file: PathBuf,
+        /// Resolve relative context paths against this directory
+        /// (default: the definition file's directory)
+        #[arg(long)]
+        relative_to: Option<PathBuf>,
+        /// Import under a different workspace name
+        #[arg(long)]
+        name: Option<String>,
+        /// Replace an existing workspace with the same name
+        #[arg(long)]
+        force: bool,
+    },
 }
 
 fn main() {
@@ -619,6 +1365,19 @@ fn main() {
         eprintln!("Verbose mode enabled");
     }
 
+    #[cfg(feature = "encryption")]
+    if let Some(arg) = &cli.key {
+        match agentic_memory::types::encryption::EncryptionKey::from_arg(arg)
+            .and_then(commands::set_encryption_key)
+        {
+            Ok(()) => {}
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                process::exit(3);
+            }
+        }
+    }
+
     let result = match cli.command {
         // No subcommand → launch interactive REPL
         None => match agentic_memory::cli::repl::run() {
@@ -635,15 +1394,160 @@ fn main() {
             Ok(())
         }
 
-        Some(Commands::Create { file, dimension }) => commands::cmd_create(&file, dimension),
+        Some(Commands::Restore {
+            file,
+            from,
+            output,
+            list,
+            force,
+        }) => (|| -> agentic_memory::AmemResult<()> {
+            let settings = SessionSettings::resolve(None);
+            let (backups_dir, _) = settings.backups_dir(&file);
+            let points = backup::restore_points(&backups_dir, &file).map_err(mcp_error)?;
+
+            if list {
+                if json {
+                    let items: Vec<serde_json::Value> = points
+                        .iter()
+                        .map(|p| {
+                            serde_json::json!({
+                                "path": p.path.display().to_string(),
+                                "base": p.chain.base.display().to_string(),
+                                "sequence": p.sequence,
+                            })
+                        })
+                        .collect();
+                    println!(
+                        "{}",
+                        serde_json::to_string_pretty(&serde_json::json!({
+                            "backups_dir": backups_dir.display().to_string(),
+                            "restore_points": items,
+                        }))
+                        .unwrap_or_default()
+                    );
+                } else if points.is_empty() {
+                    println!("No backups found in {}", backups_dir.display());
+                } else {
+                    println!("Restore points in {}:", backups_dir.display());
+                    for p in &points {
+                        let kind = if p.sequence == 0 { "full" } else { "delta" };
+                        println!(
+                            "  {:<6} {}",
+                            kind,
+                            p.path.file_name().unwrap_or_default().to_string_lossy()
+                        );
+                    }
+                }
+                return Ok(());
+            }
+
+            let point = match &from {
+                Some(wanted) => points
+                    .iter()
+                    .find(|p| p.path == *wanted || p.path.file_name() == wanted.file_name()),
+                None => points.last(),
+            }
+            .ok_or_else(|| {
+                agentic_memory::AmemError::Io(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!("No matching backup in {}", backups_dir.display()),
+                ))
+            })?;
+
+            let target = output.unwrap_or_else(|| file.clone());
+            if target.exists() && !force {
+                return Err(agentic_memory::AmemError::Io(std::io::Error::new(
+                    std::io::ErrorKind::AlreadyExists,
+                    format!("{} exists (use --force to overwrite)", target.display()),
+                )));
+            }
+            let node_count = backup::restore_to(
+                point,
+                &target,
+                #[cfg(feature = "encryption")]
+                settings.encryption_key(),
+            )
+            .map_err(mcp_error)?;
+
+            if json {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&serde_json::json!({
+                        "restored_from": point.path.display().to_string(),
+                        "deltas_applied": point.sequence,
+                        "output": target.display().to_string(),
+                        "nodes": node_count,
+                    }))
+                    .unwrap_or_default()
+                );
+            } else {
+                println!(
+                    "Restored {} nodes from {} ({} deltas applied) to {}",
+                    node_count,
+                    point.path.display(),
+                    point.sequence,
+                    target.display()
+                );
+            }
+            Ok(())
+        })(),
+
+        Some(Commands::Config { subcommand }) => match subcommand {
+            ConfigCommands::Show {
+                memory,
+                config,
+                json: json_flag,
+            } => (|| -> agentic_memory::AmemResult<()> {
+                let resolved = ResolvedConfig::resolve(memory.as_deref(), config.as_deref())
+                    .map_err(mcp_error)?;
+                if json || json_flag {
+                    println!(
+                        "{}",
+                        serde_json::to_string_pretty(&resolved.to_json()).unwrap_or_default()
+                    );
+                } else {
+                    println!("Memory file: {}", resolved.memory_path);
+                    println!(
+                        "Config file: {}",
+                        resolved
+                            .config_file
+                            .as_ref()
+                            .map(|p| p.display().to_string())
+                            .unwrap_or_else(|| "-".to_string())
+                    );
+                    println!();
+                    for setting in resolved.settings() {
+                        println!(
+                            "  {:<38} {:<24} [{}]",
+                            setting.key,
+                            setting.value,
+                            setting.source.as_str()
+                        );
+                    }
+                }
+                Ok(())
+            })(),
+        },
+
+        Some(Commands::Create { file, dimension }) => commands::cmd_create(&file, dimension, json),
         Some(Commands::Info { file }) => commands::cmd_info(&file, json),
+        Some(Commands::FixDimension {
+            file,
+            dimension,
+            backup,
+        }) => commands::cmd_fix_dimension(&file, dimension as usize, backup, json),
         Some(Commands::Add {
             file,
             event_type,
             content,
             session,
             confidence,
+            tags,
             supersedes,
+            vector,
+            embedding_model,
+            strict,
+            detect_language,
         }) => {
             let et = match EventType::from_name(&event_type) {
                 Some(et) => et,
@@ -652,7 +1556,35 @@ fn main() {
                     process::exit(3);
                 }
             };
-            commands::cmd_add(&file, et, &content, session, confidence, supersedes, json)
+            let feature_vec = match vector
+                .map(|s| {
+                    s.split(',')
+                        .map(|t| t.trim().parse::<f32>())
+                        .collect::<Result<Vec<f32>, _>>()
+                })
+                .transpose()
+            {
+                Ok(v) => v,
+                Err(e) => {
+                    eprintln!("Invalid vector: {}", e);
+                    process::exit(3);
+                }
+            };
+            let session = session_arg(&file, &session);
+            commands::cmd_add(
+                &file,
+                et,
+                &content,
+                session,
+                confidence,
+                &tags_arg(&tags),
+                supersedes,
+                feature_vec,
+                embedding_model.as_deref(),
+                strict,
+                detect_language,
+                json,
+            )
         }
         Some(Commands::Link {
             file,
@@ -660,33 +1592,31 @@ fn main() {
             target_id,
             edge_type,
             weight,
-        }) => {
-            let et = match EdgeType::from_name(&edge_type) {
-                Some(et) => et,
-                None => {
-                    eprintln!("Invalid edge type: {}", edge_type);
-                    process::exit(3);
-                }
-            };
-            commands::cmd_link(&file, source_id, target_id, et, weight, json)
-        }
+            bidirectional,
+        }) => commands::cmd_link(
+            &file,
+            source_id,
+            target_id,
+            &edge_type,
+            weight,
+            bidirectional,
+            json,
+        ),
         Some(Commands::Get { file, node_id }) => commands::cmd_get(&file, node_id, json),
+        Some(Commands::Pin { file, node_id }) => commands::cmd_pin(&file, node_id, true, json),
+        Some(Commands::Unpin { file, node_id }) => commands::cmd_pin(&file, node_id, false, json),
+        Some(Commands::History { file, node_id }) => commands::cmd_history(&file, node_id, json),
         Some(Commands::Traverse {
             file,
             start_id,
             edge_types,
             direction,
-            max_depth,
-            max_results,
-            min_confidence,
-        }) => {
-            let ets: Vec<EdgeType> = edge_types
-                .map(|s| {
-                    s.split(',')
-                        .filter_map(|t| EdgeType::from_name(t.trim()))
-                        .collect()
-                })
-                .unwrap_or_default();
+            max_depth,
+            max_results,
+            min_confidence,
+            symmetric,
+        }) => {
+            let ets = edge_types_arg(&file, edge_types.as_deref(), false);
             let dir = match direction.as_str() {
                 "forward" => TraversalDirection::Forward,
                 "backward" => TraversalDirection::Backward,
@@ -700,6 +1630,49 @@ fn main() {
                 max_depth,
                 max_results,
                 min_confidence,
+                symmetric,
+                json,
+            )
+        }
+        Some(Commands::Similar {
+            file,
+            node_id,
+            vector,
+            top_k,
+            min_similarity,
+            expand,
+            hops,
+            decay,
+            max_neighbors,
+            sample,
+            sample_seed,
+        }) => {
+            let query_vec = match vector
+                .map(|s| {
+                    s.split(',')
+                        .map(|t| t.trim().parse::<f32>())
+                        .collect::<Result<Vec<f32>, _>>()
+                })
+                .transpose()
+            {
+                Ok(v) => v,
+                Err(e) => {
+                    eprintln!("Invalid vector: {}", e);
+                    process::exit(3);
+                }
+            };
+            let expand_types = edge_types_arg(&file, expand.as_deref(), true);
+            commands::cmd_similar(
+                &file,
+                node_id,
+                query_vec,
+                top_k,
+                min_similarity,
+                expand_types,
+                hops,
+                decay,
+                max_neighbors,
+                commands::node_sample(sample, sample_seed),
                 json,
             )
         }
@@ -713,6 +1686,11 @@ fn main() {
             before,
             sort,
             limit,
+            expand_episodes,
+            episode_limit,
+            fields,
+            explain,
+            profile,
         }) => {
             let ets: Vec<EventType> = event_types
                 .map(|s| {
@@ -721,15 +1699,15 @@ fn main() {
                         .collect()
                 })
                 .unwrap_or_default();
-            let sids: Vec<u32> = session
-                .map(|s| s.split(',').filter_map(|t| t.trim().parse().ok()).collect())
-                .unwrap_or_default();
+            let sids = session_list_arg(&file, session.as_deref());
             let sort_by = match sort.as_str() {
                 "confidence" => PatternSort::HighestConfidence,
                 "accessed" => PatternSort::MostAccessed,
                 "importance" => PatternSort::MostImportant,
                 _ => PatternSort::MostRecent,
             };
+            let episodes =
+                expand_episodes.then(|| episode_limit.unwrap_or(DEFAULT_EPISODE_MEMBER_LIMIT));
             commands::cmd_search(
                 &file,
                 ets,
@@ -740,6 +1718,10 @@ fn main() {
                 before,
                 sort_by,
                 limit,
+                episodes,
+                fields.as_ref(),
+                explain,
+                profile,
                 json,
             )
         }
@@ -747,15 +1729,138 @@ fn main() {
             file,
             node_id,
             max_depth,
-        }) => commands::cmd_impact(&file, node_id, max_depth, json),
+            upstream,
+        }) => {
+            let direction = if upstream {
+                CausalDirection::Upstream
+            } else {
+                CausalDirection::Downstream
+            };
+            commands::cmd_impact(&file, node_id, max_depth, direction, json)
+        }
         Some(Commands::Resolve { file, node_id }) => commands::cmd_resolve(&file, node_id, json),
-        Some(Commands::Sessions { file, limit }) => commands::cmd_sessions(&file, limit, json),
+        Some(Commands::Sessions {
+            file,
+            limit,
+            active,
+            active_window,
+        }) => commands::cmd_sessions(
+            &file,
+            limit,
+            active.then(|| active_window.saturating_mul(60)),
+            json,
+        ),
+        Some(Commands::SessionAlias {
+            file,
+            session,
+            alias,
+            remove,
+        }) => commands::cmd_session_alias(&file, &session, alias.as_deref(), remove, json),
+        Some(Commands::Temporal {
+            file,
+            range_a,
+            range_b,
+            group_by_session,
+        }) => {
+            let parse = |raw: Option<&str>| match raw {
+                None => TimeRange::TimeWindow {
+                    start: 0,
+                    end: u64::MAX,
+                },
+                Some(raw) => match parse_time_range(raw) {
+                    Some(range) => range,
+                    None => {
+                        eprintln!("Invalid time range: {}", raw);
+                        process::exit(3);
+                    }
+                },
+            };
+            let a = parse(range_a.as_deref());
+            let b = parse(range_b.as_deref().or(range_a.as_deref()));
+            commands::cmd_temporal(&file, a, b, group_by_session, json)
+        }
         Some(Commands::Export {
             file,
             nodes_only,
             session,
+            event_types,
+            min_centrality,
+            keep_connectors,
+            cross_session,
+            redact,
+            redact_categories,
+            anonymize_ids,
+            anonymize_seed,
+            emit_id_map,
+            format,
+            output,
+            edges,
             pretty,
-        }) => commands::cmd_export(&file, nodes_only, session, pretty),
+        }) => {
+            let export_format = match commands::ExportFormat::from_name(&format) {
+                Some(f) => f,
+                None => {
+                    eprintln!(
+                        "Unknown export format '{}'. Use json, dot, parquet or markdown.",
+                        format
+                    );
+                    std::process::exit(3);
+                }
+            };
+            if export_format == commands::ExportFormat::Dot && (nodes_only || anonymize_ids) {
+                eprintln!("--format dot cannot be combined with --nodes-only or --anonymize-ids");
+                std::process::exit(3);
+            }
+            let writes_files = matches!(
+                export_format,
+                commands::ExportFormat::Parquet | commands::ExportFormat::Markdown
+            );
+            if writes_files {
+                if output.is_none() {
+                    eprintln!("--format {} requires --output", export_format.name());
+                    std::process::exit(3);
+                }
+                if nodes_only || anonymize_ids {
+                    eprintln!(
+                        "--format {} cannot be combined with --nodes-only or --anonymize-ids",
+                        export_format.name()
+                    );
+                    std::process::exit(3);
+                }
+            } else if output.is_some() {
+                eprintln!("--output only applies to --format parquet or markdown");
+                std::process::exit(3);
+            }
+            if edges.is_some() && export_format != commands::ExportFormat::Parquet {
+                eprintln!("--edges only applies to --format parquet");
+                std::process::exit(3);
+            }
+            let ets: Vec<EventType> = event_types
+                .map(|s| {
+                    s.split(',')
+                        .filter_map(|t| EventType::from_name(t.trim()))
+                        .collect()
+                })
+                .unwrap_or_default();
+            let redactor = redact.then(|| redactor_arg(redact_categories.as_deref()));
+            commands::cmd_export(
+                &file,
+                nodes_only,
+                session.map(|s| session_arg(&file, &s)),
+                &ets,
+                min_centrality,
+                keep_connectors,
+                cross_session,
+                redactor.as_ref(),
+                anonymize_ids,
+                anonymize_seed,
+                emit_id_map.as_deref(),
+                export_format,
+                output.as_deref(),
+                edges.as_deref(),
+                pretty,
+            )
+        }
         Some(Commands::Ground {
             file,
             claim,
@@ -773,6 +1878,7 @@ fn main() {
                     event_types: Vec::new(),
                     session_ids: Vec::new(),
                     min_score: 0.0,
+                    confidence_weight: 0.0,
                 },
             )?;
             let evidence: Vec<_> = matches.iter().filter(|m| m.score >= threshold).collect();
@@ -861,6 +1967,7 @@ fn main() {
                         event_types: Vec::new(),
                         session_ids: Vec::new(),
                         min_score: 0.0,
+                        confidence_weight: 0.0,
                     },
                 )?;
                 let rows: Vec<_> = matches
@@ -1127,11 +2234,232 @@ fn main() {
                     }
                     Ok(())
                 }
+                WorkspaceCommands::Export {
+                    workspace,
+                    relative_to,
+                } => {
+                    let state = load_state()?;
+                    let contexts = state.workspaces.get(&workspace).ok_or_else(|| {
+                        agentic_memory::AmemError::Io(std::io::Error::new(
+                            std::io::ErrorKind::NotFound,
+                            format!("workspace '{}' not found", workspace),
+                        ))
+                    })?;
+                    let contexts = contexts
+                        .iter()
+                        .map(|ctx| WorkspaceContext {
+                            path: match &relative_to {
+                                Some(base) => relativize_path(&ctx.path, base),
+                                None => absolute_path(Path::new(&ctx.path))
+                                    .to_string_lossy()
+                                    .to_string(),
+                            },
+                            role: ctx.role.clone(),
+                            label: ctx.label.clone(),
+                        })
+                        .collect();
+                    let definition = WorkspaceDefinition {
+                        name: workspace,
+                        contexts,
+                    };
+                    let raw = serde_json::to_string_pretty(&definition).map_err(|e| {
+                        agentic_memory::AmemError::Io(std::io::Error::new(
+                            std::io::ErrorKind::InvalidData,
+                            e,
+                        ))
+                    })?;
+                    println!("{}", raw);
+                    Ok(())
+                }
+                WorkspaceCommands::Import {
+                    file,
+                    relative_to,
+                    name,
+                    force,
+                } => {
+                    let raw = std::fs::read_to_string(&file)?;
+                    let definition: WorkspaceDefinition =
+                        serde_json::from_str(&raw).map_err(|e| {
+                            agentic_memory::AmemError::Io(std::io::Error::new(
+                                std::io::ErrorKind::InvalidData,
+                                e,
+                            ))
+                        })?;
+                    let base = relative_to.unwrap_or_else(|| {
+                        file.parent()
+                            .map(Path::to_path_buf)
+                            .unwrap_or_else(|| PathBuf::from("."))
+                    });
+                    let workspace = name.unwrap_or(definition.name);
+
+                    let mut state = load_state()?;
+                    if state.workspaces.contains_key(&workspace) && !force {
+                        eprintln!(
+                            "Workspace '{}' already exists. Use --force to replace it.",
+                            workspace
+                        );
+                        process::exit(3);
+                    }
+
+                    let mut contexts: Vec<WorkspaceContext> = Vec::new();
+                    let mut missing = Vec::new();
+                    for ctx in definition.contexts {
+                        if ContextRole::parse_str(&ctx.role).is_none() {
+                            eprintln!("Unknown context role '{}' for {}", ctx.role, ctx.path);
+                            process::exit(3);
+                        }
+                        let path = resolve_path(&ctx.path, &base);
+                        if contexts.iter().any(|c| c.path == path) {
+                            continue;
+                        }
+                        if !Path::new(&path).exists() {
+                            eprintln!("Warning: memory file not found: {}", path);
+                            missing.push(path.clone());
+                        }
+                        contexts.push(WorkspaceContext {
+                            path,
+                            role: ctx.role.to_ascii_lowercase(),
+                            label: ctx.label,
+                        });
+                    }
+                    let count = contexts.len();
+                    state.workspaces.insert(workspace.clone(), contexts);
+                    save_state(&state)?;
+
+                    if json {
+                        println!(
+                            "{}",
+                            serde_json::to_string_pretty(&serde_json::json!({
+                                "workspace": workspace,
+                                "contexts": count,
+                                "missing": missing,
+                                "imported": true
+                            }))
+                            .unwrap_or_default()
+                        );
+                    } else {
+                        println!(
+                            "Imported workspace '{}' ({} contexts, {} missing)",
+                            workspace,
+                            count,
+                            missing.len()
+                        );
+                    }
+                    Ok(())
+                }
             }
         })(),
-        Some(Commands::Import { file, json_file }) => commands::cmd_import(&file, &json_file),
+        Some(Commands::Import {
+            file,
+            json_file,
+            merge_strategy,
+            duplicate_threshold,
+        }) => {
+            let strategy = match commands::MergeStrategy::from_name(&merge_strategy) {
+                Some(s) => s,
+                None => {
+                    eprintln!(
+                        "Unknown merge strategy '{}'. Use append, skip-duplicate, update-confidence, or supersede.",
+                        merge_strategy
+                    );
+                    process::exit(3);
+                }
+            };
+            commands::cmd_import(
+                &file,
+                &json_file,
+                strategy,
+                duplicate_threshold.clamp(0.0, 1.0),
+                json,
+            )
+        }
+        Some(Commands::ImportMarkdown {
+            markdown_file,
+            file,
+            session,
+            list_type,
+        }) => {
+            let list_type = match EventType::from_name(&list_type) {
+                Some(t @ (EventType::Fact | EventType::Decision)) => t,
+                _ => {
+                    eprintln!(
+                        "Invalid list item type '{}'. Use fact or decision.",
+                        list_type
+                    );
+                    process::exit(3);
+                }
+            };
+            commands::cmd_import_markdown(
+                &file,
+                &markdown_file,
+                session.map(|s| session_arg(&file, &s)),
+                list_type,
+                json,
+            )
+        }
+        Some(Commands::Merge {
+            file_a,
+            file_b,
+            output,
+            strategy,
+        }) => {
+            let strategy = match agentic_memory::MergeStrategy::from_name(&strategy) {
+                Some(s) => s,
+                None => {
+                    eprintln!(
+                        "Unknown merge strategy '{}'. Use deduplicate, union, or intersect.",
+                        strategy
+                    );
+                    process::exit(3);
+                }
+            };
+            commands::cmd_merge(&file_a, &file_b, &output, strategy, json)
+        }
+        Some(Commands::Diff {
+            file_a,
+            file_b,
+            format,
+        }) => {
+            let diff_format = match commands::DiffFormat::from_name(&format) {
+                // The global --format json applies unless a format is chosen here.
+                Some(commands::DiffFormat::Text) if json => commands::DiffFormat::Json,
+                Some(f) => f,
+                None => {
+                    eprintln!(
+                        "Unknown diff format '{}'. Use text, json, or patch.",
+                        format
+                    );
+                    process::exit(3);
+                }
+            };
+            commands::cmd_diff(&file_a, &file_b, diff_format)
+        }
+        Some(Commands::ApplyPatch { file, patch }) => {
+            commands::cmd_apply_patch(&file, &patch, json)
+        }
         Some(Commands::Decay { file, threshold }) => commands::cmd_decay(&file, threshold, json),
-        Some(Commands::Stats { file }) => commands::cmd_stats(&file, json),
+        Some(Commands::TailSession {
+            file,
+            lines,
+            follow,
+            interval,
+            count,
+        }) => commands::cmd_tail_session(&file, lines, follow, interval, count, json),
+        Some(Commands::Stats {
+            file,
+            watch,
+            interval,
+            count,
+            compare,
+        }) => {
+            if watch {
+                commands::cmd_stats_watch(&file, interval, count, json)
+            } else if let Some(baseline) = compare {
+                commands::cmd_stats_compare(&file, &baseline, json)
+            } else {
+                commands::cmd_stats(&file, json)
+            }
+        }
         Some(Commands::Quality {
             file,
             low_confidence,
@@ -1145,6 +2473,7 @@ fn main() {
             session,
             write_episode,
         }) => {
+            let session = session_arg(&file, &session);
             commands::cmd_runtime_sync(&file, &workspace, max_depth, session, write_episode, json)
         }
         Some(Commands::Budget {
@@ -1152,6 +2481,9 @@ fn main() {
             max_bytes,
             horizon_years,
         }) => commands::cmd_budget(&file, max_bytes, horizon_years, json),
+        Some(Commands::Terms { file, top, term }) => {
+            commands::cmd_terms(&file, top, term.as_deref(), json)
+        }
         Some(Commands::TextSearch {
             file,
             query,
@@ -1159,7 +2491,66 @@ fn main() {
             session,
             limit,
             min_score,
+            all,
+            confidence_weight,
+            expand_episodes,
+            episode_limit,
+            explain,
+        }) => {
+            let ets: Vec<EventType> = event_types
+                .map(|s| {
+                    s.split(',')
+                        .filter_map(|t| EventType::from_name(t.trim()))
+                        .collect()
+                })
+                .unwrap_or_default();
+            let sids = session_list_arg(&file, session.as_deref());
+            let episodes =
+                expand_episodes.then(|| episode_limit.unwrap_or(DEFAULT_EPISODE_MEMBER_LIMIT));
+            commands::cmd_text_search(
+                &file,
+                &query,
+                ets,
+                sids,
+                limit,
+                min_score,
+                all,
+                confidence_weight,
+                episodes,
+                explain,
+                json,
+            )
+        }
+        Some(Commands::FuzzySearch {
+            file,
+            query,
+            event_types,
+            limit,
+            min_similarity,
+        }) => {
+            if !(0.0..=1.0).contains(&min_similarity) {
+                eprintln!("--min-similarity must be between 0.0 and 1.0");
+                process::exit(3);
+            }
+            let ets: Vec<EventType> = event_types
+                .map(|s| {
+                    s.split(',')
+                        .filter_map(|t| EventType::from_name(t.trim()))
+                        .collect()
+                })
+                .unwrap_or_default();
+            commands::cmd_fuzzy_search(&file, &query, ets, limit, min_similarity, json)
+        }
+        Some(Commands::TemporalClusters {
+            file,
+            window,
+            event_types,
+            session,
         }) => {
+            if window == 0 {
+                eprintln!("--window must be greater than zero");
+                process::exit(3);
+            }
             let ets: Vec<EventType> = event_types
                 .map(|s| {
                     s.split(',')
@@ -1167,10 +2558,24 @@ fn main() {
                         .collect()
                 })
                 .unwrap_or_default();
-            let sids: Vec<u32> = session
-                .map(|s| s.split(',').filter_map(|t| t.trim().parse().ok()).collect())
+            let sids = session_list_arg(&file, session.as_deref());
+            commands::cmd_temporal_clusters(&file, window, ets, sids, json)
+        }
+        Some(Commands::RegexSearch {
+            file,
+            pattern,
+            case_sensitive,
+            event_types,
+            limit,
+        }) => {
+            let ets: Vec<EventType> = event_types
+                .map(|s| {
+                    s.split(',')
+                        .filter_map(|t| EventType::from_name(t.trim()))
+                        .collect()
+                })
                 .unwrap_or_default();
-            commands::cmd_text_search(&file, &query, ets, sids, limit, min_score, json)
+            commands::cmd_regex_search(&file, &pattern, case_sensitive, ets, limit, json)
         }
         Some(Commands::HybridSearch {
             file,
@@ -1179,6 +2584,8 @@ fn main() {
             vec_weight,
             limit,
             event_types,
+            expand_episodes,
+            episode_limit,
         }) => {
             let ets: Vec<EventType> = event_types
                 .map(|s| {
@@ -1187,7 +2594,18 @@ fn main() {
                         .collect()
                 })
                 .unwrap_or_default();
-            commands::cmd_hybrid_search(&file, &query, text_weight, vec_weight, limit, ets, json)
+            let episodes =
+                expand_episodes.then(|| episode_limit.unwrap_or(DEFAULT_EPISODE_MEMBER_LIMIT));
+            commands::cmd_hybrid_search(
+                &file,
+                &query,
+                text_weight,
+                vec_weight,
+                limit,
+                ets,
+                episodes,
+                json,
+            )
         }
         Some(Commands::Centrality {
             file,
@@ -1197,7 +2615,17 @@ fn main() {
             event_types,
             limit,
             iterations,
+            seeds,
+            seed_query,
+            symmetric,
+            sample,
+            sample_seed,
+            k,
         }) => {
+            if algorithm == "kmeans" && k == 0 {
+                eprintln!("--k must be at least 1");
+                process::exit(3);
+            }
             let ets: Vec<EventType> = event_types
                 .map(|s| {
                     s.split(',')
@@ -1205,15 +2633,35 @@ fn main() {
                         .collect()
                 })
                 .unwrap_or_default();
-            let edts: Vec<EdgeType> = edge_types
+            let edts = edge_types_arg(&file, edge_types.as_deref(), false);
+            let seeds: Vec<u64> = match seeds
                 .map(|s| {
                     s.split(',')
-                        .filter_map(|t| EdgeType::from_name(t.trim()))
-                        .collect()
+                        .map(|t| t.trim().parse::<u64>())
+                        .collect::<Result<Vec<_>, _>>()
                 })
-                .unwrap_or_default();
+                .transpose()
+            {
+                Ok(v) => v.unwrap_or_default(),
+                Err(e) => {
+                    eprintln!("Invalid seeds: {}", e);
+                    process::exit(3);
+                }
+            };
             commands::cmd_centrality(
-                &file, &algorithm, damping, edts, ets, limit, iterations, json,
+                &file,
+                &algorithm,
+                damping,
+                edts,
+                ets,
+                limit,
+                iterations,
+                seeds,
+                seed_query.as_deref(),
+                symmetric,
+                commands::node_sample(sample, sample_seed),
+                k,
+                json,
             )
         }
         Some(Commands::Path {
@@ -1224,21 +2672,17 @@ fn main() {
             direction,
             max_depth,
             weighted,
+            type_cost,
         }) => {
-            let edts: Vec<EdgeType> = edge_types
-                .map(|s| {
-                    s.split(',')
-                        .filter_map(|t| EdgeType::from_name(t.trim()))
-                        .collect()
-                })
-                .unwrap_or_default();
+            let type_cost = type_cost_arg(&file, type_cost.as_deref());
+            let edts = edge_types_arg(&file, edge_types.as_deref(), false);
             let dir = match direction.as_str() {
                 "forward" => TraversalDirection::Forward,
                 "backward" => TraversalDirection::Backward,
                 _ => TraversalDirection::Both,
             };
             commands::cmd_path(
-                &file, source_id, target_id, edts, dir, max_depth, weighted, json,
+                &file, source_id, target_id, edts, dir, max_depth, weighted, type_cost, json,
             )
         }
         Some(Commands::Revise {
@@ -1259,8 +2703,8 @@ fn main() {
             let session_range = session.and_then(|s| {
                 let parts: Vec<&str> = s.split(':').collect();
                 if parts.len() == 2 {
-                    let start: u32 = parts[0].trim().parse().ok()?;
-                    let end: u32 = parts[1].trim().parse().ok()?;
+                    let start = session_arg(&file, parts[0]);
+                    let end = session_arg(&file, parts[1]);
                     Some((start, end))
                 } else {
                     None
@@ -1284,9 +2728,7 @@ fn main() {
             exclude_session,
             depth,
         }) => {
-            let exclude: Vec<u32> = exclude_session
-                .map(|s| s.split(',').filter_map(|t| t.trim().parse().ok()).collect())
-                .unwrap_or_default();
+            let exclude = session_list_arg(&file, exclude_session.as_deref());
             commands::cmd_analogy(
                 &file,
                 &description,
@@ -1321,6 +2763,145 @@ fn main() {
             backup,
             json,
         ),
+        #[cfg(feature = "signing")]
+        Some(Commands::Sign { file, key }) => commands::cmd_sign(&file, &key, json),
+        #[cfg(feature = "signing")]
+        Some(Commands::Verify {
+            file,
+            signatures: _,
+            pub_key,
+        }) => commands::cmd_verify(&file, pub_key.as_deref(), json),
+        Some(Commands::CaptureLog {
+            file,
+            limit,
+            purge,
+            confirm,
+            backup,
+        }) => commands::cmd_capture_log(&file, limit, purge, confirm, backup, json),
+        Some(Commands::Compact {
+            file,
+            older_than,
+            keep_top,
+            pin,
+            rewire_to_episode,
+            keep_types,
+            confirm,
+            backup,
+        }) => {
+            // `requires` is not enforced for flags, so check it here.
+            if rewire_to_episode && keep_top.is_none() {
+                eprintln!("--rewire-to-episode requires --keep-top");
+                process::exit(3);
+            }
+            let mut kept = Vec::new();
+            for name in keep_types.iter().flat_map(|s| s.split(',')) {
+                let name = name.trim();
+                if name.is_empty() {
+                    continue;
+                }
+                match EventType::from_name(name) {
+                    Some(et) => kept.push(et),
+                    None => {
+                        eprintln!("Invalid event type: {}", name);
+                        process::exit(3);
+                    }
+                }
+            }
+            let pinned: Vec<u64> = match pin
+                .map(|s| {
+                    s.split(',')
+                        .map(|t| t.trim().parse::<u64>())
+                        .collect::<Result<Vec<_>, _>>()
+                })
+                .transpose()
+            {
+                Ok(v) => v.unwrap_or_default(),
+                Err(e) => {
+                    eprintln!("Invalid pin: {}", e);
+                    process::exit(3);
+                }
+            };
+            match keep_top {
+                Some(keep) => commands::cmd_compact_keep_top(
+                    &file,
+                    keep,
+                    &kept,
+                    &pinned,
+                    rewire_to_episode,
+                    confirm,
+                    backup,
+                    json,
+                ),
+                None => commands::cmd_compact(
+                    &file,
+                    older_than.unwrap_or_default(),
+                    &kept,
+                    confirm,
+                    backup,
+                    json,
+                ),
+            }
+        }
+        Some(Commands::Delete {
+            file,
+            event_types,
+            decay_below,
+            confidence_below,
+            session,
+            created_before,
+            yes,
+            backup,
+        }) => {
+            let mut types = Vec::new();
+            for name in event_types.iter().flat_map(|s| s.split(',')) {
+                let name = name.trim();
+                if name.is_empty() {
+                    continue;
+                }
+                match EventType::from_name(name) {
+                    Some(et) => types.push(et),
+                    None => {
+                        eprintln!("Invalid event type: {}", name);
+                        process::exit(3);
+                    }
+                }
+            }
+            let filter = DeleteFilter {
+                event_types: types,
+                decay_below,
+                confidence_below,
+                session_ids: session_list_arg(&file, session.as_deref()),
+                created_before,
+            };
+            if filter.is_empty() {
+                eprintln!("At least one filter is required (see amem delete --help)");
+                process::exit(3);
+            }
+            commands::cmd_delete(&file, &filter, yes, backup, json)
+        }
+        Some(Commands::Migrate { file, dry_run }) => commands::cmd_migrate(&file, dry_run, json),
+        Some(Commands::Fsck { file, repair }) => commands::cmd_fsck(&file, repair, json),
+        Some(Commands::Dedup {
+            file,
+            interactive: _,
+            floor,
+            plan_out,
+            apply_plan,
+            dry_run,
+        }) => commands::cmd_dedup(
+            &file,
+            floor,
+            apply_plan.as_deref(),
+            plan_out.as_deref(),
+            dry_run,
+            json,
+        ),
+        Some(Commands::LinkInferences {
+            file,
+            min_score,
+            max_per_inference,
+            apply,
+        }) => commands::cmd_link_inferences(&file, min_score, max_per_inference, apply, json),
         Some(Commands::Drift {
             file,
             topic,
@@ -1336,9 +2917,18 @@ fn main() {
             agentic_memory::AmemError::InvalidMagic
             | agentic_memory::AmemError::UnsupportedVersion(_)
             | agentic_memory::AmemError::Truncated
-            | agentic_memory::AmemError::Corrupt(_) => 2,
+            | agentic_memory::AmemError::Corrupt(_)
+            | agentic_memory::AmemError::SupersedesCycle { .. }
+            | agentic_memory::AmemError::IntegrityCheckFailed(_)
+            | agentic_memory::AmemError::SignatureMismatch(_) => 2,
             agentic_memory::AmemError::NodeNotFound(_)
             | agentic_memory::AmemError::InvalidEdgeTarget(_) => 4,
+            agentic_memory::AmemError::EmbeddingModelMismatch { .. }
+            | agentic_memory::AmemError::InvalidKey(_)
+            | agentic_memory::AmemError::PatchConflict(_)
+            | agentic_memory::AmemError::InvalidPattern(_)
+            | agentic_memory::AmemError::InvalidEdgeType(_)
+            | agentic_memory::AmemError::TooManyCustomEdgeTypes(_) => 3,
             _ => 5,
         };
         process::exit(code);
@@ -4,22 +4,37 @@ use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
 
+use super::resolved::SettingSource;
 use crate::types::McpResult;
 
 /// Server configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServerConfig {
-    /// Path to the .amem memory file.
-    pub memory_path: String,
+    /// Path to the .amem memory file (overridden by `--memory` and `AMEM_BRAIN`).
+    #[serde(default)]
+    pub memory_path: Option<String>,
     /// Transport type ("stdio" or "sse").
     #[serde(default = "default_transport")]
     pub transport: String,
     /// SSE listen address (only used when transport is "sse").
     #[serde(default = "default_sse_addr")]
     pub sse_addr: String,
-    /// Auto-save interval in seconds.
-    #[serde(default = "default_auto_save_interval")]
-    pub auto_save_interval: u64,
+    /// Auto-save interval in seconds (overridden by `AMEM_AUTOSAVE_SECS`;
+    /// unset falls back to the autonomic profile default).
+    #[serde(default)]
+    pub auto_save_interval: Option<u64>,
+    /// Byte budget for a single tool result (overridden by
+    /// `AMEM_TOOL_RESULT_MAX_BYTES`; 0 disables the cap).
+    #[serde(default)]
+    pub max_response_bytes: Option<usize>,
+    /// Per-request timeout in seconds for outbound HTTP calls (`net`
+    /// feature; overridden by `AMEM_HTTP_TIMEOUT_SECS`).
+    #[serde(default)]
+    pub http_timeout_secs: Option<u64>,
+    /// Requests per second allowed per connection by `serve-http` and
+    /// `serve-ws` (per user in multi-tenant mode; overridden by `--rate-limit`).
+    #[serde(default)]
+    pub rate_limit_rps: Option<u32>,
     /// Log level.
     #[serde(default = "default_log_level")]
     pub log_level: String,
@@ -33,10 +48,6 @@ fn default_sse_addr() -> String {
     "127.0.0.1:3000".to_string()
 }
 
-fn default_auto_save_interval() -> u64 {
-    30
-}
-
 fn default_log_level() -> String {
     "info".to_string()
 }
@@ -44,10 +55,13 @@ fn default_log_level() -> String {
 impl Default for ServerConfig {
     fn default() -> Self {
         Self {
-            memory_path: resolve_default_memory_path(),
+            memory_path: None,
             transport: default_transport(),
             sse_addr: default_sse_addr(),
-            auto_save_interval: default_auto_save_interval(),
+            auto_save_interval: None,
+            max_response_bytes: None,
+            http_timeout_secs: None,
+            rate_limit_rps: None,
             log_level: default_log_level(),
         }
     }
@@ -70,15 +84,33 @@ pub fn load_config(path: &str) -> McpResult<ServerConfig> {
 /// 2. AMEM_BRAIN environment variable
 /// 3. ~/.brain.amem (global default)
 pub fn resolve_memory_path(explicit: Option<&str>) -> String {
+    resolve_memory_path_with_source(explicit, None).0
+}
+
+/// Resolve the memory file path and report which layer supplied it.
+///
+/// Same order as [`resolve_memory_path`], with the config file's
+/// `memory_path` consulted between the environment and the default.
+pub fn resolve_memory_path_with_source(
+    explicit: Option<&str>,
+    file: Option<&str>,
+) -> (String, SettingSource) {
     if let Some(path) = explicit {
-        return absolutize_path(path);
+        return (absolutize_path(path), SettingSource::Flag);
     }
 
     if let Ok(env_path) = std::env::var("AMEM_BRAIN") {
-        return absolutize_path(&env_path);
+        return (absolutize_path(&env_path), SettingSource::Env);
+    }
+
+    if let Some(path) = file {
+        return (absolutize_path(path), SettingSource::File);
     }
 
-    absolutize_path(&resolve_default_memory_path())
+    (
+        absolutize_path(&resolve_default_memory_path()),
+        SettingSource::Default,
+    )
 }
 
 fn resolve_default_memory_path() -> String {
@@ -1,5 +1,7 @@
 //! Configuration loading and resolution.
 
 pub mod loader;
+pub mod resolved;
 
-pub use loader::{load_config, resolve_memory_path, ServerConfig};
+pub use loader::{load_config, resolve_memory_path, resolve_memory_path_with_source, ServerConfig};
+pub use resolved::{ResolvedConfig, ResolvedSetting, SettingSource};
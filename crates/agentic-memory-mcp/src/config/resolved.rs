@@ -0,0 +1,124 @@
+//! Authoritative resolution of the effective server configuration.
+//!
+//! Both the server startup path and the `config show` diagnostics call
+//! [`ResolvedConfig::resolve`], so the dump can never disagree with what the
+//! server actually runs with.
+
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use super::loader::{load_config, resolve_memory_path_with_source, ServerConfig};
+use crate::session::settings::SessionSettings;
+use crate::types::McpResult;
+
+/// Where an effective setting value came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SettingSource {
+    /// Explicit command-line flag.
+    Flag,
+    /// Environment variable.
+    Env,
+    /// TOML configuration file.
+    File,
+    /// Built-in or autonomic-profile default.
+    Default,
+}
+
+impl SettingSource {
+    /// Short label used in text output.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Flag => "flag",
+            Self::Env => "env",
+            Self::File => "file",
+            Self::Default => "default",
+        }
+    }
+}
+
+/// One effective setting together with its origin.
+#[derive(Debug, Clone, Serialize)]
+pub struct ResolvedSetting {
+    /// Setting name (environment variable name where one exists).
+    pub key: String,
+    /// Effective value after parsing and clamping.
+    pub value: String,
+    /// Where the value came from.
+    pub source: SettingSource,
+}
+
+impl ResolvedSetting {
+    pub(crate) fn new(key: impl Into<String>, value: impl ToString, source: SettingSource) -> Self {
+        Self {
+            key: key.into(),
+            value: value.to_string(),
+            source,
+        }
+    }
+}
+
+/// Fully resolved configuration for one brain file.
+#[derive(Debug, Clone)]
+pub struct ResolvedConfig {
+    /// Absolute path of the memory file.
+    pub memory_path: String,
+    /// Config file that was loaded, if any.
+    pub config_file: Option<PathBuf>,
+    /// Runtime settings handed to the session manager.
+    pub session: SessionSettings,
+    memory_path_source: SettingSource,
+}
+
+impl ResolvedConfig {
+    /// Resolve every setting from CLI flags, environment, config file and defaults.
+    ///
+    /// Precedence is flag > env > file > default for each individual setting.
+    pub fn resolve(explicit_memory: Option<&str>, config_file: Option<&str>) -> McpResult<Self> {
+        let file: Option<ServerConfig> = match config_file {
+            Some(path) => Some(load_config(path)?),
+            None => None,
+        };
+        let (memory_path, memory_path_source) = resolve_memory_path_with_source(
+            explicit_memory,
+            file.as_ref().and_then(|f| f.memory_path.as_deref()),
+        );
+        let session = SessionSettings::resolve(file.as_ref());
+
+        Ok(Self {
+            memory_path,
+            config_file: config_file.map(PathBuf::from),
+            session,
+            memory_path_source,
+        })
+    }
+
+    /// Every effective setting in a stable order, memory path first.
+    pub fn settings(&self) -> Vec<ResolvedSetting> {
+        let mut out = vec![ResolvedSetting::new(
+            "AMEM_BRAIN",
+            &self.memory_path,
+            self.memory_path_source,
+        )];
+        let (backups_dir, backups_source) = self
+            .session
+            .backups_dir(std::path::Path::new(&self.memory_path));
+        out.push(ResolvedSetting::new(
+            "AMEM_AUTO_BACKUP_DIR",
+            backups_dir.display(),
+            backups_source,
+        ));
+        out.extend(self.session.entries().iter().cloned());
+        out
+    }
+
+    /// JSON representation used by `config show --json` and the MCP `config` command.
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "memory_path": self.memory_path,
+            "config_file": self.config_file.as_ref().map(|p| p.display().to_string()),
+            "settings": self.settings(),
+        })
+    }
+}
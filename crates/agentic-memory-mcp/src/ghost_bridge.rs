@@ -104,6 +104,8 @@ async fn build_context_from_v2(
                 created_after: None,
                 created_before: None,
                 min_decay_score: None,
+                required_tags: Default::default(),
+                any_tags: Default::default(),
                 max_results: 10,
                 sort_by: PatternSort::MostRecent,
             },
@@ -122,6 +124,8 @@ async fn build_context_from_v2(
                 created_after: None,
                 created_before: None,
                 min_decay_score: None,
+                required_tags: Default::default(),
+                any_tags: Default::default(),
                 max_results: 10,
                 sort_by: PatternSort::MostRecent,
             },
@@ -140,6 +144,8 @@ async fn build_context_from_v2(
                 created_after: None,
                 created_before: None,
                 min_decay_score: None,
+                required_tags: Default::default(),
+                any_tags: Default::default(),
                 max_results: 1,
                 sort_by: PatternSort::MostRecent,
             },
@@ -0,0 +1,132 @@
+//! Shared outbound HTTP client (`net` feature).
+//!
+//! Every HTTP-based integration builds its client here so timeouts,
+//! connection reuse and retry policy are configured in one place. Build one
+//! client per process and clone it; clones share the connection pool.
+
+use std::time::Duration;
+
+use reqwest::{Client, Response, StatusCode};
+
+use crate::config::ServerConfig;
+
+/// Default TCP connect timeout.
+pub const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 5;
+/// Default timeout for a whole request, response body included.
+pub const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 30;
+/// Default number of retries after the first attempt of an idempotent GET.
+pub const DEFAULT_MAX_RETRIES: u32 = 2;
+/// Default delay before the first retry; doubled on each further retry.
+pub const DEFAULT_RETRY_BACKOFF_MS: u64 = 200;
+/// Idle pooled connections are closed after this long.
+const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+/// Idle connections kept per host.
+const POOL_MAX_IDLE_PER_HOST: usize = 8;
+/// Upper bound on a single backoff delay.
+const MAX_BACKOFF: Duration = Duration::from_secs(10);
+
+/// Timeout and retry policy for outbound HTTP calls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HttpClientConfig {
+    /// TCP connect timeout.
+    pub connect_timeout: Duration,
+    /// Timeout for a whole request, response body included.
+    pub request_timeout: Duration,
+    /// Retries after the first attempt of an idempotent GET.
+    pub max_retries: u32,
+    /// Delay before the first retry; doubled on each further retry.
+    pub retry_backoff: Duration,
+}
+
+impl Default for HttpClientConfig {
+    fn default() -> Self {
+        Self {
+            connect_timeout: Duration::from_secs(DEFAULT_CONNECT_TIMEOUT_SECS),
+            request_timeout: Duration::from_secs(DEFAULT_REQUEST_TIMEOUT_SECS),
+            max_retries: DEFAULT_MAX_RETRIES,
+            retry_backoff: Duration::from_millis(DEFAULT_RETRY_BACKOFF_MS),
+        }
+    }
+}
+
+impl HttpClientConfig {
+    /// Resolve the policy from the environment and an optional config file.
+    ///
+    /// `AMEM_HTTP_TIMEOUT_SECS` overrides the file's `http_timeout_secs`;
+    /// `AMEM_HTTP_CONNECT_TIMEOUT_SECS`, `AMEM_HTTP_MAX_RETRIES` and
+    /// `AMEM_HTTP_RETRY_BACKOFF_MS` are environment-only.
+    pub fn resolve(file: Option<&ServerConfig>) -> Self {
+        let request_secs = env_number("AMEM_HTTP_TIMEOUT_SECS")
+            .or_else(|| file.and_then(|f| f.http_timeout_secs))
+            .unwrap_or(DEFAULT_REQUEST_TIMEOUT_SECS)
+            .max(1);
+        let connect_secs = env_number("AMEM_HTTP_CONNECT_TIMEOUT_SECS")
+            .unwrap_or(DEFAULT_CONNECT_TIMEOUT_SECS)
+            .clamp(1, request_secs);
+        let max_retries = env_number("AMEM_HTTP_MAX_RETRIES")
+            .unwrap_or(DEFAULT_MAX_RETRIES)
+            .min(10);
+        let backoff_ms =
+            env_number("AMEM_HTTP_RETRY_BACKOFF_MS").unwrap_or(DEFAULT_RETRY_BACKOFF_MS);
+        Self {
+            connect_timeout: Duration::from_secs(connect_secs),
+            request_timeout: Duration::from_secs(request_secs),
+            max_retries,
+            retry_backoff: Duration::from_millis(backoff_ms),
+        }
+    }
+
+    /// Delay before retry number `retry` (0-based), capped at 10 s.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        self.retry_backoff
+            .saturating_mul(1u32 << retry.min(16))
+            .min(MAX_BACKOFF)
+    }
+}
+
+fn env_number<T: std::str::FromStr>(name: &str) -> Option<T> {
+    std::env::var(name).ok().and_then(|v| v.trim().parse().ok())
+}
+
+/// Build a pooled client with the timeouts from `config`.
+pub fn build_client(config: &HttpClientConfig) -> reqwest::Result<Client> {
+    Client::builder()
+        .connect_timeout(config.connect_timeout)
+        .timeout(config.request_timeout)
+        .pool_idle_timeout(POOL_IDLE_TIMEOUT)
+        .pool_max_idle_per_host(POOL_MAX_IDLE_PER_HOST)
+        .tcp_keepalive(POOL_IDLE_TIMEOUT)
+        .user_agent(concat!("agentic-memory-mcp/", env!("CARGO_PKG_VERSION")))
+        .build()
+}
+
+/// GET `url`, retrying connection failures, timeouts, 5xx and 429 responses
+/// with exponential backoff. Other responses are returned as they are, so
+/// callers still check the status of a 4xx.
+pub async fn get_with_retry(
+    client: &Client,
+    config: &HttpClientConfig,
+    url: &str,
+) -> reqwest::Result<Response> {
+    let mut retry = 0;
+    loop {
+        let last_attempt = retry >= config.max_retries;
+        match client.get(url).send().await {
+            Ok(response) if last_attempt || !is_retryable_status(response.status()) => {
+                return Ok(response)
+            }
+            Err(e) if last_attempt || !is_retryable_error(&e) => return Err(e),
+            _ => {}
+        }
+        tokio::time::sleep(config.backoff(retry)).await;
+        retry += 1;
+    }
+}
+
+fn is_retryable_status(status: StatusCode) -> bool {
+    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+fn is_retryable_error(e: &reqwest::Error) -> bool {
+    e.is_connect() || e.is_timeout() || e.is_request()
+}
@@ -4,11 +4,15 @@
 //! AgenticMemory functionality to any MCP-compatible LLM client.
 
 pub mod config;
+#[cfg(feature = "net")]
+pub mod http_client;
 pub mod prompts;
 pub mod protocol;
 pub mod resources;
 pub mod session;
 pub mod streaming;
+#[cfg(feature = "otel")]
+pub mod telemetry;
 pub mod tools;
 pub mod transport;
 pub mod types;
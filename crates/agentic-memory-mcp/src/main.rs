@@ -11,20 +11,30 @@ use tokio::sync::Mutex;
 use clap::{Args, Parser, Subcommand};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::Layer;
 
-use agentic_memory_mcp::config::resolve_memory_path;
+use agentic_memory::{RedactionCategory, RedactionCounts, Redactor};
+use agentic_memory_mcp::config::{resolve_memory_path, ResolvedConfig};
 use agentic_memory_mcp::protocol::ProtocolHandler;
 use agentic_memory_mcp::session::autosave::spawn_maintenance;
-use agentic_memory_mcp::session::SessionManager;
+#[cfg(feature = "sse")]
+use agentic_memory_mcp::session::autosave::spawn_reload_watcher;
+use agentic_memory_mcp::session::{SessionManager, SessionSettings};
 use agentic_memory_mcp::tools::ToolRegistry;
 use agentic_memory_mcp::transport::capture::{
     self, CaptureDirection, CaptureWalStatus, CapturedTransportEntry,
 };
-use agentic_memory_mcp::transport::StdioTransport;
+use agentic_memory_mcp::transport::{wal_recovery, StdioTransport};
 use agentic_memory_mcp::types::MemoryMode;
 
 mod daemon;
 
+/// How often `serve-http --dev` checks the brain file for external changes.
+#[cfg(feature = "sse")]
+const DEV_RELOAD_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 struct ClientIdentity {
     name: String,
@@ -202,6 +212,27 @@ fn extract_layer2_records(
     out
 }
 
+/// Rebuild a missing brain from the transport WAL and report the outcome on
+/// stderr (stdout carries the MCP protocol).
+async fn recover_brain_from_wal(session: &Arc<Mutex<SessionManager>>) -> anyhow::Result<()> {
+    let wal_path = capture::default_wal_path();
+    let entries = capture::read_entries(&wal_path, None)?;
+    let report = wal_recovery::recover_from_wal(&entries, session).await?;
+    session.lock().await.save()?;
+
+    eprintln!(
+        "WAL recovery from {}: {} calls replayed, {} already present, {} unrecoverable",
+        wal_path.display(),
+        report.replayed(),
+        report.recovered.len() - report.replayed(),
+        report.unrecoverable.len()
+    );
+    for call in &report.unrecoverable {
+        eprintln!("  seq={} {}: {}", call.sequence, call.tool, call.reason);
+    }
+    Ok(())
+}
+
 fn print_replay_line(record: &Layer2Record) {
     let actor = record
         .client_family
@@ -680,6 +711,15 @@ struct Cli {
     #[arg(long, default_value = "info")]
     log_level: String,
 
+    /// Never rewrite a legacy memory file on open; migrate explicitly with `amem migrate`.
+    #[arg(long, global = true)]
+    no_auto_migrate: bool,
+
+    /// OTLP/gRPC collector to export tool-call spans to, e.g. http://localhost:4317.
+    #[cfg(feature = "otel")]
+    #[arg(long, global = true)]
+    otlp_endpoint: Option<String>,
+
     #[command(subcommand)]
     command: Option<Commands>,
 }
@@ -704,6 +744,11 @@ enum Commands {
         /// full (save everything). Default: smart.
         #[arg(long, default_value = "smart")]
         mode: String,
+
+        /// If the brain file is missing, rebuild it from the memory_add and
+        /// memory_correct calls in the transport WAL before serving.
+        #[arg(long)]
+        recover_from_wal: bool,
     },
 
     /// Start MCP server over HTTP.
@@ -742,6 +787,62 @@ enum Commands {
         /// Each user gets {data-dir}/{user-id}.amem.
         #[arg(long)]
         data_dir: Option<String>,
+
+        /// Maximum tenant brains kept open at once (0 = unlimited).
+        /// The least recently used one is saved and closed to make room.
+        #[arg(long, default_value_t = agentic_memory_mcp::session::tenant::DEFAULT_MAX_OPEN_TENANTS)]
+        max_open_tenants: usize,
+
+        /// Largest request body accepted, in bytes (0 = unlimited).
+        /// Bigger requests get HTTP 413.
+        #[arg(long, default_value_t = agentic_memory_mcp::transport::sse::DEFAULT_MAX_BODY_BYTES)]
+        max_body_bytes: usize,
+
+        /// Development mode (single-user only): log each request with its
+        /// timing, pretty-print JSON responses, and reload the brain file
+        /// when it changes on disk.
+        #[arg(long, conflicts_with = "multi_tenant")]
+        dev: bool,
+
+        /// Requests per second allowed per connection, or per X-User-ID in
+        /// multi-tenant mode (0 = unlimited). Defaults to the config file's
+        /// `rate_limit_rps`.
+        #[arg(long)]
+        rate_limit: Option<u32>,
+    },
+
+    /// Start MCP server over WebSocket.
+    #[cfg(feature = "ws")]
+    ServeWs {
+        /// Listen address (host:port).
+        #[arg(long, default_value = "127.0.0.1:3001")]
+        addr: String,
+
+        /// Path to .amem memory file.
+        #[arg(short, long)]
+        memory: Option<String>,
+
+        /// Configuration file path.
+        #[arg(short, long)]
+        config: Option<String>,
+
+        /// Log level (trace, debug, info, warn, error).
+        #[arg(long)]
+        log_level: Option<String>,
+
+        /// Memory mode: minimal, smart, full. Default: smart.
+        #[arg(long, default_value = "smart")]
+        mode: String,
+
+        /// Bearer token required on the upgrade request.
+        /// Also reads from AGENTIC_TOKEN env var.
+        #[arg(long)]
+        token: Option<String>,
+
+        /// Messages per second allowed per connection (0 = unlimited).
+        /// Defaults to the config file's `rate_limit_rps`.
+        #[arg(long)]
+        rate_limit: Option<u32>,
     },
 
     /// Validate a memory file.
@@ -750,6 +851,9 @@ enum Commands {
     /// Print server capabilities as JSON.
     Info,
 
+    /// Print the resolved configuration, with the source of every setting, as JSON.
+    Config,
+
     /// Delete a specific memory node by ID.
     Delete {
         /// Node ID to delete.
@@ -761,11 +865,27 @@ enum Commands {
         yes: bool,
     },
 
-    /// Export all memories to stdout.
+    /// Export all memories to stdout, or to Parquet files.
     Export {
-        /// Output format: json or csv.
+        /// Output format: json, csv or parquet.
         #[arg(long, default_value = "json")]
         format: String,
+
+        /// Parquet file to write the nodes to (required for --format parquet).
+        #[arg(long)]
+        output: Option<PathBuf>,
+
+        /// Parquet file to write the edges to (--format parquet only).
+        #[arg(long, requires = "output")]
+        edges: Option<PathBuf>,
+
+        /// Scrub secrets from node content in the output (the file is unchanged).
+        #[arg(long)]
+        redact: bool,
+
+        /// Categories to redact, comma-separated: secrets, emails, paths (default: all).
+        #[arg(long, requires = "redact")]
+        redact_categories: Option<String>,
     },
 
     /// Remove low-scoring nodes (compaction).
@@ -828,6 +948,14 @@ enum Commands {
 
     /// Print graph statistics.
     Stats,
+
+    /// List the AI coding assistants the Ghost Writer would sync to (read-only).
+    #[cfg(feature = "v3")]
+    Clients {
+        /// Print JSON instead of text.
+        #[arg(long)]
+        json: bool,
+    },
 }
 
 fn enforce_mode_runtime_policy(mode: MemoryMode) {
@@ -844,6 +972,75 @@ fn enforce_mode_runtime_policy(mode: MemoryMode) {
     );
 }
 
+/// Bearer token for the network transports: CLI flag > AGENTIC_TOKEN env var.
+#[cfg(any(feature = "sse", feature = "ws"))]
+fn effective_token(flag: Option<String>) -> Option<String> {
+    flag.or_else(|| std::env::var("AGENTIC_TOKEN").ok())
+}
+
+/// Rate limit for the network transports: CLI flag > config file's
+/// `rate_limit_rps`. Zero means unlimited.
+#[cfg(any(feature = "sse", feature = "ws"))]
+fn effective_rate_limit(
+    flag: Option<u32>,
+    config: Option<&str>,
+) -> agentic_memory_mcp::types::McpResult<Option<u32>> {
+    let configured = match (flag, config) {
+        (Some(rps), _) => Some(rps),
+        (None, Some(path)) => agentic_memory_mcp::config::load_config(path)?.rate_limit_rps,
+        (None, None) => None,
+    };
+    Ok(configured.filter(|&rps| rps > 0))
+}
+
+/// Write `graph` as Parquet, with node content replaced by `contents`
+/// (the possibly redacted text, in node order).
+#[cfg(feature = "parquet")]
+fn export_parquet(
+    graph: &agentic_memory::MemoryGraph,
+    contents: &[String],
+    output: &Path,
+    edges: Option<&Path>,
+) -> agentic_memory::AmemResult<()> {
+    let changed = graph
+        .nodes()
+        .iter()
+        .zip(contents)
+        .any(|(n, content)| n.content != *content);
+    if !changed {
+        return agentic_memory::write_parquet(graph, output, edges);
+    }
+    let mut redacted = graph.clone();
+    for (id, content) in graph.nodes().iter().map(|n| n.id).zip(contents) {
+        if let Some(node) = redacted.get_node_mut(id) {
+            node.content = content.clone();
+        }
+    }
+    agentic_memory::write_parquet(&redacted, output, edges)
+}
+
+#[cfg(not(feature = "parquet"))]
+fn export_parquet(
+    _graph: &agentic_memory::MemoryGraph,
+    _contents: &[String],
+    _output: &Path,
+    _edges: Option<&Path>,
+) -> agentic_memory::AmemResult<()> {
+    Err(agentic_memory::AmemError::Export(
+        "Parquet support is not compiled in (build with the `parquet` feature)".to_string(),
+    ))
+}
+
+/// Runtime settings for one-shot commands, honouring `--no-auto-migrate`.
+fn session_settings(no_auto_migrate: bool) -> SessionSettings {
+    let settings = SessionSettings::resolve(None);
+    if no_auto_migrate {
+        settings.without_auto_migrate()
+    } else {
+        settings
+    }
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
@@ -852,37 +1049,71 @@ async fn main() -> anyhow::Result<()> {
     let filter = tracing_subscriber::EnvFilter::try_from_default_env()
         .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new(&cli.log_level));
 
-    tracing_subscriber::fmt()
-        .with_env_filter(filter)
+    let fmt_layer = tracing_subscriber::fmt::layer()
         .with_writer(std::io::stderr)
+        .with_filter(filter);
+
+    #[cfg(feature = "otel")]
+    let (otel_layer, _otel_guard) = match cli.otlp_endpoint.as_deref() {
+        Some(endpoint) => match agentic_memory_mcp::telemetry::otlp_layer(endpoint) {
+            Ok((layer, guard)) => (Some(layer), Some(guard)),
+            Err(e) => {
+                eprintln!("Error: cannot export spans to {endpoint}: {e}");
+                std::process::exit(1);
+            }
+        },
+        None => (None, None),
+    };
+    #[cfg(feature = "otel")]
+    tracing_subscriber::registry()
+        .with(otel_layer)
+        .with(fmt_layer)
         .init();
+    #[cfg(not(feature = "otel"))]
+    tracing_subscriber::registry().with(fmt_layer).init();
 
     match cli.command.unwrap_or(Commands::Serve {
         memory: None,
         config: None,
         log_level: None,
         mode: "smart".to_string(),
+        recover_from_wal: false,
     }) {
         Commands::Serve {
             memory,
-            config: _,
+            config,
             log_level: _,
             mode,
+            recover_from_wal,
         } => {
             let effective_memory = memory.or(cli.memory);
-            let memory_path = resolve_memory_path(effective_memory.as_deref());
+            let effective_config = config.or(cli.config);
             let memory_mode = MemoryMode::parse(&mode).unwrap_or_else(|| {
                 tracing::warn!("Unknown mode '{mode}', falling back to 'smart'");
                 MemoryMode::Smart
             });
             enforce_mode_runtime_policy(memory_mode);
+            let mut resolved =
+                ResolvedConfig::resolve(effective_memory.as_deref(), effective_config.as_deref())?;
+            if cli.no_auto_migrate {
+                resolved.session = resolved.session.without_auto_migrate();
+            }
+            let memory_path = resolved.memory_path.clone();
             tracing::info!("AgenticMemory MCP server");
             tracing::info!("Brain: {memory_path}");
             tracing::info!("Mode: {mode}");
-            let mut session = SessionManager::open(&memory_path)?;
+            let brain_missing = !Path::new(&memory_path).exists();
+            let mut session = SessionManager::open_with_settings(&memory_path, resolved.session)?;
             session.apply_memory_mode(memory_mode);
             let maintenance_interval = session.maintenance_interval();
             let session = Arc::new(Mutex::new(session));
+            if recover_from_wal {
+                if brain_missing {
+                    recover_brain_from_wal(&session).await?;
+                } else {
+                    tracing::info!("Brain exists; skipping WAL recovery");
+                }
+            }
             let _maintenance_task = spawn_maintenance(session.clone(), maintenance_interval);
 
             // V3 Ghost Writer: background sync to Claude, Cursor, Windsurf, Cody
@@ -901,12 +1132,16 @@ async fn main() -> anyhow::Result<()> {
         Commands::ServeHttp {
             addr,
             memory,
-            config: _,
+            config,
             log_level: _,
             mode,
             token,
             multi_tenant,
             data_dir,
+            max_open_tenants,
+            max_body_bytes,
+            dev,
+            rate_limit,
         } => {
             use agentic_memory_mcp::session::tenant::TenantRegistry;
             use agentic_memory_mcp::transport::sse::{ServerMode, SseTransport};
@@ -917,8 +1152,9 @@ async fn main() -> anyhow::Result<()> {
             });
             enforce_mode_runtime_policy(memory_mode);
 
-            // Resolve token: CLI flag > env var
-            let effective_token = token.or_else(|| std::env::var("AGENTIC_TOKEN").ok());
+            let effective_token = effective_token(token);
+            let effective_config = config.or(cli.config);
+            let rate_limit = effective_rate_limit(rate_limit, effective_config.as_deref())?;
 
             let server_mode = if multi_tenant {
                 let dir = data_dir.unwrap_or_else(|| {
@@ -928,23 +1164,38 @@ async fn main() -> anyhow::Result<()> {
                 let dir = std::path::PathBuf::from(&dir);
                 tracing::info!("AgenticMemory MCP server (multi-tenant)");
                 tracing::info!("Data dir: {}", dir.display());
+                tracing::info!("Max open tenants: {max_open_tenants}");
                 tracing::info!("Mode: {mode}");
                 ServerMode::MultiTenant {
                     data_dir: dir.clone(),
-                    registry: Arc::new(Mutex::new(TenantRegistry::new(&dir))),
+                    registry: Arc::new(Mutex::new(
+                        TenantRegistry::new(&dir).with_max_open(max_open_tenants),
+                    )),
                     memory_mode,
                 }
             } else {
                 let effective_memory = memory.or(cli.memory);
-                let memory_path = resolve_memory_path(effective_memory.as_deref());
+                let mut resolved = ResolvedConfig::resolve(
+                    effective_memory.as_deref(),
+                    effective_config.as_deref(),
+                )?;
+                if cli.no_auto_migrate {
+                    resolved.session = resolved.session.without_auto_migrate();
+                }
+                let memory_path = resolved.memory_path.clone();
                 tracing::info!("AgenticMemory MCP server");
                 tracing::info!("Brain: {memory_path}");
                 tracing::info!("Mode: {mode}");
-                let mut session = SessionManager::open(&memory_path)?;
+                let mut session =
+                    SessionManager::open_with_settings(&memory_path, resolved.session)?;
                 session.apply_memory_mode(memory_mode);
                 let maintenance_interval = session.maintenance_interval();
                 let session = Arc::new(Mutex::new(session));
                 let _maintenance_task = spawn_maintenance(session.clone(), maintenance_interval);
+                if dev {
+                    tracing::info!("Dev mode: request logging, pretty JSON, live reload");
+                    let _reload_task = spawn_reload_watcher(session.clone(), DEV_RELOAD_INTERVAL);
+                }
                 let handler = ProtocolHandler::with_mode(session, memory_mode);
                 ServerMode::Single(Arc::new(handler))
             };
@@ -952,14 +1203,71 @@ async fn main() -> anyhow::Result<()> {
             if effective_token.is_some() {
                 tracing::info!("Auth: bearer token required");
             }
+            if let Some(rps) = rate_limit {
+                tracing::info!("Rate limit: {rps} requests per second");
+            }
+
+            let transport = SseTransport::with_config(effective_token, server_mode)
+                .with_max_body_bytes(max_body_bytes)
+                .with_dev(dev)
+                .with_rate_limit(rate_limit);
+            transport.run(&addr).await?;
+        }
+
+        #[cfg(feature = "ws")]
+        Commands::ServeWs {
+            addr,
+            memory,
+            config,
+            log_level: _,
+            mode,
+            token,
+            rate_limit,
+        } => {
+            use agentic_memory_mcp::transport::WsTransport;
+
+            let memory_mode = MemoryMode::parse(&mode).unwrap_or_else(|| {
+                tracing::warn!("Unknown mode '{mode}', falling back to 'smart'");
+                MemoryMode::Smart
+            });
+            enforce_mode_runtime_policy(memory_mode);
+
+            let effective_token = effective_token(token);
+            let effective_memory = memory.or(cli.memory);
+            let effective_config = config.or(cli.config);
+            let rate_limit = effective_rate_limit(rate_limit, effective_config.as_deref())?;
+            let mut resolved =
+                ResolvedConfig::resolve(effective_memory.as_deref(), effective_config.as_deref())?;
+            if cli.no_auto_migrate {
+                resolved.session = resolved.session.without_auto_migrate();
+            }
+            let memory_path = resolved.memory_path.clone();
+            tracing::info!("AgenticMemory MCP server (WebSocket)");
+            tracing::info!("Brain: {memory_path}");
+            tracing::info!("Mode: {mode}");
+            let mut session = SessionManager::open_with_settings(&memory_path, resolved.session)?;
+            session.apply_memory_mode(memory_mode);
+            let maintenance_interval = session.maintenance_interval();
+            let session = Arc::new(Mutex::new(session));
+            let _maintenance_task = spawn_maintenance(session.clone(), maintenance_interval);
+
+            if effective_token.is_some() {
+                tracing::info!("Auth: bearer token required");
+            }
+            if let Some(rps) = rate_limit {
+                tracing::info!("Rate limit: {rps} messages per second");
+            }
 
-            let transport = SseTransport::with_config(effective_token, server_mode);
+            let handler = ProtocolHandler::with_mode(session, memory_mode);
+            let transport = WsTransport::new(handler)
+                .with_token(effective_token)
+                .with_rate_limit(rate_limit);
             transport.run(&addr).await?;
         }
 
         Commands::Validate => {
             let memory_path = resolve_memory_path(cli.memory.as_deref());
-            match SessionManager::open(&memory_path) {
+            match SessionManager::open_read_only(&memory_path) {
                 Ok(session) => {
                     let graph = session.graph();
                     println!("Valid memory file: {memory_path}");
@@ -988,9 +1296,21 @@ async fn main() -> anyhow::Result<()> {
             println!("{}", serde_json::to_string_pretty(&info)?);
         }
 
+        Commands::Config => {
+            let mut resolved =
+                ResolvedConfig::resolve(cli.memory.as_deref(), cli.config.as_deref())?;
+            if cli.no_auto_migrate {
+                resolved.session = resolved.session.without_auto_migrate();
+            }
+            println!("{}", serde_json::to_string_pretty(&resolved.to_json())?);
+        }
+
         Commands::Delete { node_id, yes } => {
             let memory_path = resolve_memory_path(cli.memory.as_deref());
-            let mut session = match SessionManager::open(&memory_path) {
+            let mut session = match SessionManager::open_with_settings(
+                &memory_path,
+                session_settings(cli.no_auto_migrate),
+            ) {
                 Ok(s) => s,
                 Err(e) => {
                     eprintln!("Error: {e}");
@@ -1044,9 +1364,41 @@ async fn main() -> anyhow::Result<()> {
             }
         }
 
-        Commands::Export { format } => {
+        Commands::Export {
+            format,
+            output,
+            edges,
+            redact,
+            redact_categories,
+        } => {
+            if output.is_some() && format != "parquet" {
+                eprintln!("Error: --output and --edges only apply to --format parquet.");
+                std::process::exit(1);
+            }
+            let redactor = if redact {
+                let mut categories = Vec::new();
+                for name in redact_categories
+                    .as_deref()
+                    .unwrap_or("secrets,emails,paths")
+                    .split(',')
+                {
+                    match RedactionCategory::from_name(name) {
+                        Some(category) => categories.push(category),
+                        None => {
+                            eprintln!(
+                                "Error: unknown redaction category '{}'. Use secrets, emails, or paths.",
+                                name.trim()
+                            );
+                            std::process::exit(1);
+                        }
+                    }
+                }
+                Some(Redactor::with_categories(categories))
+            } else {
+                None
+            };
             let memory_path = resolve_memory_path(cli.memory.as_deref());
-            let session = match SessionManager::open(&memory_path) {
+            let session = match SessionManager::open_read_only(&memory_path) {
                 Ok(s) => s,
                 Err(e) => {
                     eprintln!("Error: {e}");
@@ -1056,12 +1408,23 @@ async fn main() -> anyhow::Result<()> {
 
             let graph = session.graph();
 
+            let mut redactions = RedactionCounts::default();
+            let contents: Vec<String> = graph
+                .nodes()
+                .iter()
+                .map(|n| match &redactor {
+                    Some(redactor) => redactor.redact_counted(&n.content, &mut redactions),
+                    None => n.content.clone(),
+                })
+                .collect();
+
             match format.as_str() {
                 "json" => {
                     let nodes_json: Vec<serde_json::Value> = graph
                         .nodes()
                         .iter()
-                        .map(|n| {
+                        .zip(&contents)
+                        .map(|(n, content)| {
                             serde_json::json!({
                                 "id": n.id,
                                 "event_type": n.event_type.name(),
@@ -1071,7 +1434,7 @@ async fn main() -> anyhow::Result<()> {
                                 "access_count": n.access_count,
                                 "last_accessed": n.last_accessed,
                                 "decay_score": n.decay_score,
-                                "content": n.content,
+                                "content": content,
                             })
                         })
                         .collect();
@@ -1083,17 +1446,29 @@ async fn main() -> anyhow::Result<()> {
                             serde_json::json!({
                                 "source_id": e.source_id,
                                 "target_id": e.target_id,
-                                "edge_type": e.edge_type.name(),
+                                "edge_type": graph.edge_type_name(e.edge_type),
                                 "weight": e.weight,
                                 "created_at": e.created_at,
                             })
                         })
                         .collect();
 
-                    let output = serde_json::json!({
+                    let mut output = serde_json::json!({
                         "nodes": nodes_json,
                         "edges": edges_json,
                     });
+                    if let Some(redactor) = &redactor {
+                        let by_category: serde_json::Map<String, Value> = redactor
+                            .categories()
+                            .iter()
+                            .map(|&c| (c.name().to_string(), serde_json::json!(redactions.get(c))))
+                            .collect();
+                        output["redaction"] = serde_json::json!({
+                            "categories": redactor.categories().iter().map(|c| c.name()).collect::<Vec<_>>(),
+                            "total": redactions.total(),
+                            "by_category": by_category,
+                        });
+                    }
                     println!(
                         "{}",
                         serde_json::to_string_pretty(&output).unwrap_or_default()
@@ -1101,9 +1476,9 @@ async fn main() -> anyhow::Result<()> {
                 }
                 "csv" => {
                     println!("id,event_type,created_at,session_id,confidence,access_count,last_accessed,decay_score,content");
-                    for n in graph.nodes() {
+                    for (n, content) in graph.nodes().iter().zip(&contents) {
                         // Escape content for CSV: double-quote, escape inner quotes
-                        let escaped = n.content.replace('"', "\"\"");
+                        let escaped = content.replace('"', "\"\"");
                         println!(
                             "{},{},{},{},{:.4},{},{},{:.4},\"{}\"",
                             n.id,
@@ -1118,16 +1493,40 @@ async fn main() -> anyhow::Result<()> {
                         );
                     }
                 }
+                "parquet" => {
+                    let Some(output) = output.as_deref() else {
+                        eprintln!("Error: --format parquet requires --output.");
+                        std::process::exit(1);
+                    };
+                    if let Err(e) = export_parquet(graph, &contents, output, edges.as_deref()) {
+                        eprintln!("Error: {e}");
+                        std::process::exit(1);
+                    }
+                    eprintln!("Wrote {} nodes to {}", contents.len(), output.display());
+                }
                 _ => {
-                    eprintln!("Error: unknown format '{format}'. Use 'json' or 'csv'.");
+                    eprintln!("Error: unknown format '{format}'. Use 'json', 'csv' or 'parquet'.");
                     std::process::exit(1);
                 }
             }
+            if redactor.is_some() {
+                eprintln!(
+                    "Redacted {} tokens across {} exported nodes ({} secrets, {} emails, {} paths)",
+                    redactions.total(),
+                    contents.len(),
+                    redactions.secrets,
+                    redactions.emails,
+                    redactions.paths
+                );
+            }
         }
 
         Commands::Compact { keep_above, yes } => {
             let memory_path = resolve_memory_path(cli.memory.as_deref());
-            let mut session = match SessionManager::open(&memory_path) {
+            let mut session = match SessionManager::open_with_settings(
+                &memory_path,
+                session_settings(cli.no_auto_migrate),
+            ) {
                 Ok(s) => s,
                 Err(e) => {
                     eprintln!("Error: {e}");
@@ -1336,7 +1735,7 @@ async fn main() -> anyhow::Result<()> {
 
         Commands::Stats => {
             let memory_path = resolve_memory_path(cli.memory.as_deref());
-            let session = match SessionManager::open(&memory_path) {
+            let session = match SessionManager::open_read_only(&memory_path) {
                 Ok(s) => s,
                 Err(e) => {
                     eprintln!("Error: {e}");
@@ -1389,6 +1788,45 @@ async fn main() -> anyhow::Result<()> {
                 }
             }
         }
+
+        #[cfg(feature = "v3")]
+        Commands::Clients { json } => {
+            let probes = agentic_memory::v3::GhostWriter::probe_clients();
+
+            if json {
+                let clients: Vec<_> = probes
+                    .iter()
+                    .map(|p| {
+                        serde_json::json!({
+                            "client": p.client_type.display_name(),
+                            "status": p.status(),
+                            "memory_dir": p.memory_dir,
+                            "config_file": p.target_file,
+                            "initialized": p.initialized,
+                            "writable": p.writable,
+                            "prior_ghost_write": p.prior_write,
+                        })
+                    })
+                    .collect();
+                let payload = serde_json::json!({
+                    "count": clients.len(),
+                    "clients": clients,
+                });
+                println!("{}", serde_json::to_string_pretty(&payload)?);
+            } else if probes.is_empty() {
+                println!("No AI coding assistants detected. Ghost Writer sync would be disabled.");
+            } else {
+                for p in &probes {
+                    println!("{} ({})", p.client_type.display_name(), p.status());
+                    println!("  Config file: {}", p.target_file.display());
+                    println!("  Writable:    {}", if p.writable { "yes" } else { "no" });
+                    println!(
+                        "  Prior write: {}",
+                        if p.prior_write { "yes" } else { "no" }
+                    );
+                }
+            }
+        }
     }
 
     Ok(())
@@ -0,0 +1,196 @@
+//! Tool-result size cap.
+//!
+//! A single `memory_query` can return dozens of long memories, which is enough
+//! to exhaust a client's context window in one response. When a serialized
+//! tool result exceeds the byte budget, its `content` fields are shortened,
+//! longest first, until it fits. Every entry is kept; the result is marked
+//! with `"truncated": true` and `"full_count"` (the number of entries, all
+//! still present).
+
+use serde_json::Value;
+
+use crate::types::{ToolCallResult, ToolContent};
+
+/// Appended to every shortened content field.
+const ELLIPSIS: &str = "...";
+
+/// Give up refining the cut after this many passes.
+const MAX_PASSES: usize = 16;
+
+/// Shrink `result` to fit in `max_bytes` when serialized. Returns `true` if
+/// anything was truncated. A budget of 0 disables the cap; error results are
+/// left untouched.
+pub fn cap_tool_result(result: &mut ToolCallResult, max_bytes: usize) -> bool {
+    if max_bytes == 0 || result.is_error == Some(true) {
+        return false;
+    }
+    let mut size = serialized_len(result);
+    if size <= max_bytes {
+        return false;
+    }
+
+    let mut truncated = false;
+    for i in 0..result.content.len() {
+        let ToolContent::Text { text } = &result.content[i] else {
+            continue;
+        };
+        let overshoot = size - max_bytes;
+        let shortened = match serde_json::from_str::<Value>(text) {
+            Ok(Value::Object(map)) => truncate_json_contents(Value::Object(map), text, overshoot),
+            _ => truncate_plain_text(text, overshoot),
+        };
+        if let Some(shortened) = shortened {
+            result.content[i] = ToolContent::Text { text: shortened };
+            truncated = true;
+            size = serialized_len(result);
+            if size <= max_bytes {
+                break;
+            }
+        }
+    }
+    truncated
+}
+
+fn serialized_len(result: &ToolCallResult) -> usize {
+    serde_json::to_vec(result).map(|v| v.len()).unwrap_or(0)
+}
+
+/// Shorten the `content` strings of a JSON object result so its pretty text
+/// shrinks by at least `overshoot` bytes once re-escaped into the response.
+fn truncate_json_contents(value: Value, original: &str, overshoot: usize) -> Option<String> {
+    let originals: Vec<String> = {
+        let mut probe = value.clone();
+        let mut fields = Vec::new();
+        content_fields(&mut probe, &mut fields);
+        fields.into_iter().map(|s| s.clone()).collect()
+    };
+    if originals.is_empty() {
+        return None;
+    }
+
+    let full = escaped_text_len(original);
+    let lengths: Vec<usize> = originals.iter().map(String::len).collect();
+    let mut target = overshoot;
+    for _ in 0..MAX_PASSES {
+        let cap = water_level(&lengths, target);
+        let mut out = value.clone();
+        {
+            let mut fields = Vec::new();
+            content_fields(&mut out, &mut fields);
+            for (field, original) in fields.into_iter().zip(&originals) {
+                if original.len() > cap + ELLIPSIS.len() {
+                    *field = format!("{}{ELLIPSIS}", prefix_at_most(original, cap));
+                }
+            }
+        }
+        if let Value::Object(map) = &mut out {
+            map.insert("truncated".to_string(), Value::Bool(true));
+            map.insert("full_count".to_string(), Value::from(originals.len()));
+        }
+        let text = serde_json::to_string_pretty(&out).unwrap_or_default();
+
+        // Escaping inside the response can make the saving smaller than the
+        // raw byte count, so measure the escaped sizes and tighten if needed.
+        let saved = full.saturating_sub(escaped_text_len(&text));
+        if saved >= overshoot || cap == 0 {
+            return Some(text);
+        }
+        target += overshoot - saved;
+    }
+    None
+}
+
+/// Shorten a plain-text result by at least `overshoot` escaped bytes.
+fn truncate_plain_text(text: &str, overshoot: usize) -> Option<String> {
+    let full = escaped_text_len(text);
+    let mut keep = text.len().saturating_sub(overshoot);
+    loop {
+        let notice = format!("\n[truncated: {keep} of {} bytes shown]", text.len());
+        let shortened = format!("{}{notice}", prefix_at_most(text, keep));
+        if full.saturating_sub(escaped_text_len(&shortened)) >= overshoot || keep == 0 {
+            return Some(shortened);
+        }
+        keep = keep.saturating_sub(notice.len().max(64));
+    }
+}
+
+/// Collect every string stored under a `content` key, depth first.
+fn content_fields<'a>(value: &'a mut Value, out: &mut Vec<&'a mut String>) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                if key != "content" || !child.is_string() {
+                    content_fields(child, out);
+                } else if let Value::String(s) = child {
+                    out.push(s);
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                content_fields(item, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Largest per-field length `cap` such that cutting every longer field down
+/// to `cap` (plus the ellipsis) removes at least `needed` bytes. Trimming
+/// down to a common level takes from the longest fields first.
+fn water_level(lengths: &[usize], needed: usize) -> usize {
+    let mut sorted = lengths.to_vec();
+    sorted.sort_unstable_by(|a, b| b.cmp(a));
+
+    let mut sum = 0usize;
+    for k in 1..=sorted.len() {
+        sum += sorted[k - 1];
+        let next = sorted.get(k).copied().unwrap_or(0);
+        // Cutting the k longest fields to `cap` saves sum - k * (cap + ellipsis).
+        let budget = sum.saturating_sub(needed);
+        let cap = (budget / k).saturating_sub(ELLIPSIS.len());
+        if cap >= next {
+            return cap;
+        }
+    }
+    0
+}
+
+/// Longest prefix of `s` that is at most `max` bytes and ends on a char boundary.
+fn prefix_at_most(s: &str, max: usize) -> &str {
+    if s.len() <= max {
+        return s;
+    }
+    let mut end = max;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    &s[..end]
+}
+
+/// Size of `text` once embedded as a JSON string.
+fn escaped_text_len(text: &str) -> usize {
+    serde_json::to_vec(text)
+        .map(|v| v.len())
+        .unwrap_or(text.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefix_respects_char_boundaries() {
+        let s = "héllo";
+        assert_eq!(prefix_at_most(s, 2), "h");
+        assert_eq!(prefix_at_most(s, 3), "hé");
+        assert_eq!(prefix_at_most(s, 10), s);
+    }
+
+    #[test]
+    fn water_level_trims_longest_first() {
+        // Removing 100 bytes from [300, 50] only touches the long field.
+        let cap = water_level(&[300, 50], 100);
+        assert!(cap >= 50 && cap <= 200 - ELLIPSIS.len());
+    }
+}
@@ -5,10 +5,12 @@ use std::sync::Arc;
 use tokio::sync::Mutex;
 
 use serde_json::{json, Value};
+use tracing::Instrument;
 
 use crate::prompts::PromptRegistry;
 use crate::resources::ResourceRegistry;
 use crate::session::SessionManager;
+use crate::streaming::chunked;
 #[cfg(feature = "v3")]
 use crate::tools::v3_tools::{self, SharedEngine};
 use crate::tools::ToolRegistry;
@@ -19,6 +21,7 @@ use crate::v3_auto_capture::AutoCaptureMiddleware;
 use agentic_memory::v3::{EngineConfig, MemoryEngineV3};
 
 use super::negotiation::NegotiatedCapabilities;
+use super::timeout::ToolTimeouts;
 use super::validator::validate_request;
 
 /// The main protocol handler that dispatches incoming JSON-RPC messages.
@@ -32,6 +35,9 @@ pub struct ProtocolHandler {
     /// Tracks which session has already had its deterministic resume hook executed.
     last_resumed_session: Arc<Mutex<Option<u32>>>,
     tool_surface: ToolSurface,
+    tool_timeouts: ToolTimeouts,
+    /// Tenant this handler serves in multi-tenant mode; tagged on tool spans.
+    tenant: Option<String>,
     /// V3 engine for immortal capture/retrieval tools.
     #[cfg(feature = "v3")]
     v3_engine: SharedEngine,
@@ -74,6 +80,8 @@ impl ProtocolHandler {
             auto_session_started: AtomicBool::new(false),
             last_resumed_session: Arc::new(Mutex::new(None)),
             tool_surface: ToolSurface::from_env(),
+            tool_timeouts: ToolTimeouts::from_env(),
+            tenant: None,
             #[cfg(feature = "v3")]
             v3_engine,
             #[cfg(feature = "v3")]
@@ -95,6 +103,8 @@ impl ProtocolHandler {
             auto_session_started: AtomicBool::new(false),
             last_resumed_session: Arc::new(Mutex::new(None)),
             tool_surface: ToolSurface::from_env(),
+            tool_timeouts: ToolTimeouts::from_env(),
+            tenant: None,
             #[cfg(feature = "v3")]
             v3_engine,
             #[cfg(feature = "v3")]
@@ -102,6 +112,18 @@ impl ProtocolHandler {
         }
     }
 
+    /// Replace the per-tool timeouts resolved from the environment.
+    pub fn with_tool_timeouts(mut self, timeouts: ToolTimeouts) -> Self {
+        self.tool_timeouts = timeouts;
+        self
+    }
+
+    /// Tag this handler's tool-call spans with `tenant`.
+    pub fn with_tenant(mut self, tenant: impl Into<String>) -> Self {
+        self.tenant = Some(tenant.into());
+        self
+    }
+
     /// Returns true once a shutdown request has been handled.
     pub fn shutdown_requested(&self) -> bool {
         self.shutdown_requested.load(Ordering::Relaxed)
@@ -123,6 +145,20 @@ impl ProtocolHandler {
         }
     }
 
+    /// Handle an incoming message for a transport that can emit several
+    /// JSON lines per request. When the client negotiated streamed results,
+    /// a large `tools/call` result comes back as chunk notifications followed
+    /// by the response; otherwise this matches `handle_message`.
+    pub async fn handle_message_streamed(&self, msg: JsonRpcMessage) -> Vec<Value> {
+        let chunk_size = self.capabilities.lock().await.stream_chunk_size;
+        match (msg, chunk_size) {
+            (JsonRpcMessage::Request(req), Some(chunk_size)) if req.method == "tools/call" => {
+                self.handle_streamed_tools_call(req, chunk_size).await
+            }
+            (msg, _) => self.handle_message(msg).await.into_iter().collect(),
+        }
+    }
+
     /// Cleanup on transport close (EOF). Auto-ends session if one was started.
     pub async fn cleanup(&self) {
         if !self.auto_session_started.load(Ordering::Relaxed) {
@@ -274,11 +310,104 @@ impl ProtocolHandler {
     }
 
     async fn handle_tools_call(&self, params: Option<Value>) -> McpResult<Value> {
+        let (name, mut result) = self.call_tool(params).await?;
+        self.cap_result(&name, &mut result).await;
+        serde_json::to_value(result).map_err(|e| McpError::InternalError(e.to_string()))
+    }
+
+    /// Like `tools/call`, but large array results are split into
+    /// `notifications/tools/resultChunk` messages emitted ahead of the response.
+    async fn handle_streamed_tools_call(
+        &self,
+        request: JsonRpcRequest,
+        chunk_size: usize,
+    ) -> Vec<Value> {
+        if let Err(e) = validate_request(&request) {
+            return vec![serde_json::to_value(e.to_json_rpc_error(request.id)).unwrap_or_default()];
+        }
+
+        let id = request.id.clone();
+        let (name, mut result) = match self.call_tool(request.params).await {
+            Ok(called) => called,
+            Err(e) => {
+                return vec![serde_json::to_value(e.to_json_rpc_error(id)).unwrap_or_default()]
+            }
+        };
+
+        // Streamed chunks bypass the size cap: the client asked for the full result.
+        let chunks = chunked::stream_tool_result(&id, &mut result, chunk_size);
+        if chunks.is_empty() {
+            self.cap_result(&name, &mut result).await;
+        } else {
+            tracing::debug!("Streaming {name} result in {} chunks", chunks.len());
+        }
+
+        let mut messages: Vec<Value> = chunks
+            .into_iter()
+            .map(|chunk| serde_json::to_value(chunk).unwrap_or_default())
+            .collect();
+        let response = match serde_json::to_value(result) {
+            Ok(value) => serde_json::to_value(JsonRpcResponse::new(id, value)),
+            Err(e) => {
+                serde_json::to_value(McpError::InternalError(e.to_string()).to_json_rpc_error(id))
+            }
+        };
+        messages.push(response.unwrap_or_default());
+        messages
+    }
+
+    /// Shrink `result` to the session's tool-result byte budget.
+    async fn cap_result(&self, name: &str, result: &mut ToolCallResult) {
+        let max_bytes = self.session.lock().await.tool_result_max_bytes();
+        if super::budget::cap_tool_result(result, max_bytes) {
+            tracing::debug!("Truncated {name} result to fit {max_bytes} byte budget");
+        }
+    }
+
+    /// Run a tool call and its capture hooks inside a `tool_call` span,
+    /// returning the tool name and the uncapped result.
+    async fn call_tool(&self, params: Option<Value>) -> McpResult<(String, ToolCallResult)> {
         let call_params: ToolCallParams = params
             .map(serde_json::from_value)
             .transpose()
             .map_err(|e| McpError::InvalidParams(e.to_string()))?
             .ok_or_else(|| McpError::InvalidParams("Tool call params required".to_string()))?;
+
+        // Only the tool name and tenant go on the span, never arguments or
+        // results: spans may be exported off the host.
+        let span = tracing::info_span!(
+            "tool_call",
+            tool = %call_params.name,
+            tenant = tracing::field::Empty,
+            is_error = tracing::field::Empty,
+        );
+        if let Some(tenant) = &self.tenant {
+            span.record("tenant", tenant.as_str());
+        }
+        let name = call_params.name.clone();
+        let run = self.run_tool(call_params).instrument(span.clone());
+        let outcome = match self.tool_timeouts.for_tool(&name) {
+            Some(limit) => match tokio::time::timeout(limit, run).await {
+                Ok(outcome) => outcome,
+                Err(_) => {
+                    tracing::warn!("Tool {name} timed out after {limit:?}; abandoning the call");
+                    Err(McpError::RequestTimeout {
+                        tool: name,
+                        timeout_ms: limit.as_millis() as u64,
+                    })
+                }
+            },
+            None => run.await,
+        };
+        let is_error = match &outcome {
+            Ok((_, result)) => result.is_error.unwrap_or(false),
+            Err(_) => true,
+        };
+        span.record("is_error", is_error);
+        outcome
+    }
+
+    async fn run_tool(&self, call_params: ToolCallParams) -> McpResult<(String, ToolCallResult)> {
         let tool_input = call_params
             .arguments
             .clone()
@@ -306,57 +435,50 @@ impl ProtocolHandler {
             .on_tool_call(&call_params.name, &tool_input)
             .await;
 
+        // The tool body runs on its own task so a timeout in `call_tool` can
+        // abandon it even mid-computation; dropping the guard aborts it.
+        #[cfg(feature = "v3")]
+        let dispatch = dispatch_tool(
+            call_params.name.clone(),
+            call_params.arguments.clone(),
+            tool_input.clone(),
+            self.session.clone(),
+            self.v3_engine.clone(),
+        );
+        #[cfg(not(feature = "v3"))]
+        let dispatch = dispatch_tool(
+            call_params.name.clone(),
+            call_params.arguments.clone(),
+            tool_input.clone(),
+            self.session.clone(),
+        );
+        let task = AbortOnDrop(tokio::spawn(dispatch.in_current_span()));
+        let dispatched = match task.join().await {
+            Ok(dispatched) => dispatched,
+            Err(e) => Err(McpError::InternalError(format!(
+                "Tool {} failed: {e}",
+                call_params.name
+            ))),
+        };
+
         // Classify errors: protocol errors (ToolNotFound etc.) become JSON-RPC errors;
         // tool execution errors (NodeNotFound, InvalidGraphOp, etc.) become isError: true.
-        let result = {
-            #[cfg(feature = "v3")]
-            let v3_try =
-                v3_tools::dispatch_v3_tool(&call_params.name, tool_input.clone(), &self.v3_engine)
+        let result = match dispatched {
+            Ok(r) => r,
+            Err(e) if e.is_protocol_error() => {
+                #[cfg(feature = "v3")]
+                self.v3_auto_capture
+                    .on_tool_result(
+                        &call_params.name,
+                        tool_input.clone(),
+                        json!({"error": e.to_string(), "protocol_error": true}),
+                        started.elapsed().as_millis() as u64,
+                        false,
+                    )
                     .await;
-
-            #[cfg(not(feature = "v3"))]
-            let v3_try: Option<McpResult<ToolCallResult>> = None;
-
-            match v3_try {
-                Some(Ok(r)) => r,
-                Some(Err(e)) if e.is_protocol_error() => {
-                    #[cfg(feature = "v3")]
-                    self.v3_auto_capture
-                        .on_tool_result(
-                            &call_params.name,
-                            tool_input.clone(),
-                            json!({"error": e.to_string(), "protocol_error": true}),
-                            started.elapsed().as_millis() as u64,
-                            false,
-                        )
-                        .await;
-                    return Err(e);
-                }
-                Some(Err(e)) => ToolCallResult::error(e.to_string()),
-                None => match ToolRegistry::call(
-                    &call_params.name,
-                    call_params.arguments,
-                    &self.session,
-                )
-                .await
-                {
-                    Ok(r) => r,
-                    Err(e) if e.is_protocol_error() => {
-                        #[cfg(feature = "v3")]
-                        self.v3_auto_capture
-                            .on_tool_result(
-                                &call_params.name,
-                                tool_input.clone(),
-                                json!({"error": e.to_string(), "protocol_error": true}),
-                                started.elapsed().as_millis() as u64,
-                                false,
-                            )
-                            .await;
-                        return Err(e);
-                    }
-                    Err(e) => ToolCallResult::error(e.to_string()),
-                },
+                return Err(e);
             }
+            Err(e) => ToolCallResult::error(e.to_string()),
         };
 
         #[cfg(feature = "v3")]
@@ -377,7 +499,7 @@ impl ProtocolHandler {
             self.ensure_resume_hook_for_active_session().await;
         }
 
-        serde_json::to_value(result).map_err(|e| McpError::InternalError(e.to_string()))
+        Ok((call_params.name, result))
     }
 
     async fn handle_resources_list(&self) -> McpResult<Value> {
@@ -662,6 +784,39 @@ fn is_trivial_text(input: &str) -> bool {
     )
 }
 
+/// Route a tool call to the V3 tools, falling back to the registry.
+async fn dispatch_tool(
+    name: String,
+    arguments: Option<Value>,
+    tool_input: Value,
+    session: Arc<Mutex<SessionManager>>,
+    #[cfg(feature = "v3")] v3_engine: SharedEngine,
+) -> McpResult<ToolCallResult> {
+    #[cfg(feature = "v3")]
+    if let Some(result) = v3_tools::dispatch_v3_tool(&name, tool_input, &v3_engine).await {
+        return result;
+    }
+    #[cfg(not(feature = "v3"))]
+    let _ = tool_input;
+    ToolRegistry::call(&name, arguments, &session).await
+}
+
+/// Aborts the wrapped task when dropped, e.g. when a timeout abandons the
+/// call awaiting it.
+struct AbortOnDrop<T>(tokio::task::JoinHandle<T>);
+
+impl<T> AbortOnDrop<T> {
+    async fn join(mut self) -> Result<T, tokio::task::JoinError> {
+        (&mut self.0).await
+    }
+}
+
+impl<T> Drop for AbortOnDrop<T> {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
 #[cfg(feature = "v3")]
 fn init_v3_engine_from_env() -> SharedEngine {
     let data_dir = std::env::var("AMEM_V3_DATA_DIR")
@@ -1,7 +1,10 @@
 //! MCP protocol layer — message handling, validation, and capability negotiation.
 
+pub mod budget;
 pub mod handler;
 pub mod negotiation;
+pub mod timeout;
 pub mod validator;
 
 pub use handler::ProtocolHandler;
+pub use timeout::ToolTimeouts;
@@ -1,5 +1,8 @@
 //! MCP capability negotiation during initialization.
 
+use serde_json::json;
+
+use crate::streaming::chunked::{DEFAULT_CHUNK_SIZE, RESULT_CHUNK_METHOD, STREAMING_CAPABILITY};
 use crate::types::{
     ClientCapabilities, InitializeParams, InitializeResult, McpError, McpResult, MemoryMode,
     MCP_VERSION,
@@ -14,6 +17,8 @@ pub struct NegotiatedCapabilities {
     pub initialized: bool,
     /// Memory saving mode.
     pub mode: MemoryMode,
+    /// Items per chunk for streamed tool results, if the client opted in.
+    pub stream_chunk_size: Option<usize>,
 }
 
 impl Default for NegotiatedCapabilities {
@@ -22,6 +27,7 @@ impl Default for NegotiatedCapabilities {
             client: ClientCapabilities::default(),
             initialized: false,
             mode: MemoryMode::Smart,
+            stream_chunk_size: None,
         }
     }
 }
@@ -46,6 +52,18 @@ impl NegotiatedCapabilities {
             );
         }
 
+        self.stream_chunk_size = params
+            .capabilities
+            .experimental
+            .as_ref()
+            .and_then(|exp| exp.get(STREAMING_CAPABILITY))
+            .map(|opts| {
+                opts.get("chunkSize")
+                    .and_then(|v| v.as_u64())
+                    .filter(|&n| n > 0)
+                    .map(|n| n as usize)
+                    .unwrap_or(DEFAULT_CHUNK_SIZE)
+            });
         self.client = params.capabilities;
 
         tracing::info!(
@@ -54,7 +72,16 @@ impl NegotiatedCapabilities {
             params.client_info.version
         );
 
-        Ok(InitializeResult::with_mode(self.mode))
+        let mut result = InitializeResult::with_mode(self.mode);
+        result
+            .capabilities
+            .experimental
+            .get_or_insert_with(Default::default)
+            .insert(
+                STREAMING_CAPABILITY.to_string(),
+                json!({ "chunkMethod": RESULT_CHUNK_METHOD }),
+            );
+        Ok(result)
     }
 
     /// Mark the handshake as complete (after receiving `initialized` notification).
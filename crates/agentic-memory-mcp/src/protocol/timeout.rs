@@ -0,0 +1,115 @@
+//! Per-tool request timeouts.
+//!
+//! A pathological call (a traversal over a huge graph, say) would otherwise
+//! hold its request — and on stdio, every request behind it — indefinitely.
+//! The handler runs each tool body on its own task and gives up once the
+//! tool's timeout elapses: the client gets a timeout error and the task is
+//! aborted at its next await point. Synchronous engine work already under way
+//! finishes in the background before the session lock is released; calls
+//! still waiting for that lock are abandoned cleanly. Tools that query a
+//! [graph snapshot](crate::session::snapshot) release the lock first, so their
+//! leftover work does not hold up other calls.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Default time a tool call may run before the handler gives up on it.
+pub const DEFAULT_TOOL_TIMEOUT_MS: u64 = 60_000;
+
+/// Timeout per tool name, with a default for unlisted tools. A timeout of
+/// zero means no limit.
+#[derive(Debug, Clone)]
+pub struct ToolTimeouts {
+    default_ms: u64,
+    overrides: HashMap<String, u64>,
+}
+
+impl ToolTimeouts {
+    /// Apply `default_ms` to every tool (0 disables the limit).
+    pub fn new(default_ms: u64) -> Self {
+        Self {
+            default_ms,
+            overrides: HashMap::new(),
+        }
+    }
+
+    /// Give `tool` its own timeout (0 disables the limit for it).
+    pub fn with_tool(mut self, tool: impl Into<String>, timeout_ms: u64) -> Self {
+        self.overrides.insert(tool.into(), timeout_ms);
+        self
+    }
+
+    /// Resolve from `AMEM_TOOL_TIMEOUT_MS` (the default) and
+    /// `AMEM_TOOL_TIMEOUTS` (`name=ms` pairs, comma-separated).
+    pub fn from_env() -> Self {
+        let default_ms = std::env::var("AMEM_TOOL_TIMEOUT_MS")
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+            .unwrap_or(DEFAULT_TOOL_TIMEOUT_MS);
+        let mut timeouts = Self::new(default_ms);
+        if let Ok(raw) = std::env::var("AMEM_TOOL_TIMEOUTS") {
+            timeouts.overrides = parse_overrides(&raw);
+        }
+        timeouts
+    }
+
+    /// Time `tool` may run, or `None` when it is unlimited.
+    pub fn for_tool(&self, tool: &str) -> Option<Duration> {
+        let ms = self.overrides.get(tool).copied().unwrap_or(self.default_ms);
+        (ms > 0).then(|| Duration::from_millis(ms))
+    }
+}
+
+impl Default for ToolTimeouts {
+    fn default() -> Self {
+        Self::new(DEFAULT_TOOL_TIMEOUT_MS)
+    }
+}
+
+/// Parse `name=ms,name=ms`. Malformed entries are skipped with a warning.
+fn parse_overrides(raw: &str) -> HashMap<String, u64> {
+    let mut overrides = HashMap::new();
+    for entry in raw.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        match entry
+            .split_once('=')
+            .and_then(|(name, ms)| Some((name.trim(), ms.trim().parse::<u64>().ok()?)))
+        {
+            Some((name, ms)) if !name.is_empty() => {
+                overrides.insert(name.to_string(), ms);
+            }
+            _ => tracing::warn!("Ignoring malformed AMEM_TOOL_TIMEOUTS entry: {entry}"),
+        }
+    }
+    overrides
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overrides_take_precedence_and_zero_disables() {
+        let timeouts = ToolTimeouts::new(1_000)
+            .with_tool("memory_traverse", 5_000)
+            .with_tool("memory_stats", 0);
+        assert_eq!(
+            timeouts.for_tool("memory_query"),
+            Some(Duration::from_millis(1_000))
+        );
+        assert_eq!(
+            timeouts.for_tool("memory_traverse"),
+            Some(Duration::from_millis(5_000))
+        );
+        assert_eq!(timeouts.for_tool("memory_stats"), None);
+        assert_eq!(ToolTimeouts::new(0).for_tool("memory_query"), None);
+    }
+
+    #[test]
+    fn parse_overrides_skips_malformed_entries() {
+        let parsed =
+            parse_overrides("memory_traverse=120000, bogus, =5, memory_causal=x,memory_query=0");
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed["memory_traverse"], 120_000);
+        assert_eq!(parsed["memory_query"], 0);
+    }
+}
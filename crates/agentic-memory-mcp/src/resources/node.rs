@@ -24,7 +24,7 @@ pub async fn read_node(
         .map(|e| {
             json!({
                 "target_id": e.target_id,
-                "edge_type": e.edge_type.name(),
+                "edge_type": graph.edge_type_name(e.edge_type),
                 "weight": e.weight,
             })
         })
@@ -36,7 +36,7 @@ pub async fn read_node(
         .map(|e| {
             json!({
                 "source_id": e.source_id,
-                "edge_type": e.edge_type.name(),
+                "edge_type": graph.edge_type_name(e.edge_type),
                 "weight": e.weight,
             })
         })
@@ -22,3 +22,21 @@ pub fn spawn_maintenance(
         }
     })
 }
+
+/// Spawn a background task that reloads the memory file whenever it is
+/// modified by another process (see [`SessionManager::reload_if_changed`]).
+pub fn spawn_reload_watcher(
+    session: Arc<Mutex<SessionManager>>,
+    interval: Duration,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            let mut session = session.lock().await;
+            if let Err(e) = session.reload_if_changed() {
+                tracing::warn!("Live reload failed: {e}");
+            }
+        }
+    })
+}
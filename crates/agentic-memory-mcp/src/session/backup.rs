@@ -0,0 +1,290 @@
+//! Backup files for a brain: full copies and incremental delta chains.
+//!
+//! A full backup is a plain copy named `{stem}.{timestamp}.amem.bak`. In
+//! incremental mode that copy acts as the base of a chain, and each later
+//! backup only records what changed since the previous one in
+//! `{stem}.{timestamp}.d{sequence}.amem.delta`. Restoring replays the base
+//! plus every delta up to the chosen point.
+
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+use agentic_memory::{GraphDelta, MemoryGraph};
+
+use super::manager::{memory_writer, read_memory_file};
+use crate::types::{McpError, McpResult};
+
+const BASE_SUFFIX: &str = ".amem.bak";
+const DELTA_SUFFIX: &str = ".amem.delta";
+
+/// A full base backup together with the deltas recorded on top of it.
+#[derive(Debug, Clone)]
+pub struct BackupChain {
+    /// Full backup the chain starts from.
+    pub base: PathBuf,
+    /// Delta files in sequence order (sequence 1 first).
+    pub deltas: Vec<PathBuf>,
+}
+
+impl BackupChain {
+    /// Number of backup files in the chain, base included.
+    pub fn file_count(&self) -> usize {
+        1 + self.deltas.len()
+    }
+
+    /// Reconstruct the graph after applying the first `upto` deltas
+    /// (all of them when `None`). An encrypted base is decrypted with `key`.
+    pub fn reconstruct(
+        &self,
+        upto: Option<usize>,
+        #[cfg(feature = "encryption")] key: Option<
+            &agentic_memory::types::encryption::EncryptionKey,
+        >,
+    ) -> McpResult<MemoryGraph> {
+        let base_name = file_name(&self.base);
+        let mut graph = read_memory_file(
+            &self.base,
+            #[cfg(feature = "encryption")]
+            key,
+        )
+        .map_err(|e| {
+            McpError::AgenticMemory(format!("Failed to read base {}: {e}", self.base.display()))
+        })?;
+
+        let count = upto.unwrap_or(self.deltas.len()).min(self.deltas.len());
+        for (i, path) in self.deltas.iter().take(count).enumerate() {
+            let delta = GraphDelta::read_from_file(path).map_err(|e| {
+                McpError::AgenticMemory(format!("Failed to read delta {}: {e}", path.display()))
+            })?;
+            if delta.base != base_name || delta.sequence as usize != i + 1 {
+                return Err(McpError::AgenticMemory(format!(
+                    "Broken backup chain at {}: expected base {} sequence {}, found base {} sequence {}",
+                    path.display(),
+                    base_name,
+                    i + 1,
+                    delta.base,
+                    delta.sequence
+                )));
+            }
+            graph = delta.apply(&graph)?;
+        }
+        Ok(graph)
+    }
+
+    fn delta_path(&self, sequence: u32) -> PathBuf {
+        let name = file_name(&self.base);
+        let prefix = name.strip_suffix(BASE_SUFFIX).unwrap_or(&name);
+        self.base
+            .with_file_name(format!("{prefix}.d{sequence:04}{DELTA_SUFFIX}"))
+    }
+}
+
+/// One state a brain can be restored to.
+#[derive(Debug, Clone)]
+pub struct RestorePoint {
+    /// Backup file for this point (the base or a delta).
+    pub path: PathBuf,
+    /// Chain the point belongs to.
+    pub chain: BackupChain,
+    /// Number of deltas replayed on top of the base (0 for the base itself).
+    pub sequence: usize,
+}
+
+impl RestorePoint {
+    /// Reconstruct the graph at this point.
+    pub fn reconstruct(
+        &self,
+        #[cfg(feature = "encryption")] key: Option<
+            &agentic_memory::types::encryption::EncryptionKey,
+        >,
+    ) -> McpResult<MemoryGraph> {
+        self.chain.reconstruct(
+            Some(self.sequence),
+            #[cfg(feature = "encryption")]
+            key,
+        )
+    }
+}
+
+/// Path for a new full backup of `memory_path`.
+pub(crate) fn base_backup_path(backups_dir: &Path, memory_path: &Path) -> PathBuf {
+    let ts = chrono::Utc::now().format("%Y%m%d%H%M%S");
+    backups_dir.join(format!("{}.{ts}{BASE_SUFFIX}", brain_stem(memory_path)))
+}
+
+/// Backup chains for `memory_path`, oldest first.
+///
+/// Deltas whose base file no longer exists are not part of any chain.
+pub fn list_chains(backups_dir: &Path, memory_path: &Path) -> McpResult<Vec<BackupChain>> {
+    if !backups_dir.exists() {
+        return Ok(Vec::new());
+    }
+    let stem = brain_stem(memory_path);
+
+    let mut bases: Vec<String> = Vec::new();
+    let mut deltas: Vec<(String, u32, PathBuf)> = Vec::new();
+    for entry in std::fs::read_dir(backups_dir).map_err(McpError::Io)? {
+        let entry = entry.map_err(McpError::Io)?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if let Some(prefix) = name.strip_suffix(BASE_SUFFIX) {
+            if backup_stem(prefix) == Some(stem.as_str()) {
+                bases.push(prefix.to_string());
+            }
+        } else if let Some((prefix, sequence)) = parse_delta_name(&name) {
+            if backup_stem(prefix) == Some(stem.as_str()) {
+                deltas.push((prefix.to_string(), sequence, entry.path()));
+            }
+        }
+    }
+
+    // Timestamps are fixed-width, so name order is chronological.
+    bases.sort();
+    deltas.sort_by_key(|(_, sequence, _)| *sequence);
+
+    Ok(bases
+        .into_iter()
+        .map(|prefix| BackupChain {
+            base: backups_dir.join(format!("{prefix}{BASE_SUFFIX}")),
+            deltas: deltas
+                .iter()
+                .filter(|(p, _, _)| *p == prefix)
+                .map(|(_, _, path)| path.clone())
+                .collect(),
+        })
+        .collect())
+}
+
+/// Every restore point for `memory_path`, oldest first.
+pub fn restore_points(backups_dir: &Path, memory_path: &Path) -> McpResult<Vec<RestorePoint>> {
+    let mut points = Vec::new();
+    for chain in list_chains(backups_dir, memory_path)? {
+        points.push(RestorePoint {
+            path: chain.base.clone(),
+            chain: chain.clone(),
+            sequence: 0,
+        });
+        for (i, delta) in chain.deltas.iter().enumerate() {
+            points.push(RestorePoint {
+                path: delta.clone(),
+                chain: chain.clone(),
+                sequence: i + 1,
+            });
+        }
+    }
+    Ok(points)
+}
+
+/// Write an incremental backup of `memory_path`.
+///
+/// Starts a new chain with a full copy when there is none yet or the newest
+/// chain already holds `full_every` deltas; otherwise records a delta against
+/// the chain's latest state. Returns `None` when nothing changed.
+pub(crate) fn write_incremental_backup(
+    memory_path: &Path,
+    backups_dir: &Path,
+    full_every: u32,
+    #[cfg(feature = "encryption")] key: Option<&agentic_memory::types::encryption::EncryptionKey>,
+) -> McpResult<Option<PathBuf>> {
+    let chains = list_chains(backups_dir, memory_path)?;
+    let chain = match chains.last() {
+        Some(chain) if chain.deltas.len() < full_every as usize => chain,
+        _ => {
+            let base = base_backup_path(backups_dir, memory_path);
+            std::fs::copy(memory_path, &base).map_err(McpError::Io)?;
+            return Ok(Some(base));
+        }
+    };
+
+    let previous = chain.reconstruct(
+        None,
+        #[cfg(feature = "encryption")]
+        key,
+    )?;
+    let current = read_memory_file(
+        memory_path,
+        #[cfg(feature = "encryption")]
+        key,
+    )?;
+    let sequence = chain.deltas.len() as u32 + 1;
+    let delta = GraphDelta::diff(&previous, &current, file_name(&chain.base), sequence)?;
+    if delta.is_empty() {
+        return Ok(None);
+    }
+
+    let path = chain.delta_path(sequence);
+    delta.write_to_file(&path)?;
+    Ok(Some(path))
+}
+
+/// Remove the oldest whole chains while more than `retention` backup files
+/// remain. The newest chain is always kept, and a base is never removed
+/// while deltas that depend on it survive.
+pub(crate) fn prune_chains(
+    backups_dir: &Path,
+    memory_path: &Path,
+    retention: usize,
+) -> McpResult<()> {
+    let chains = list_chains(backups_dir, memory_path)?;
+    let mut total: usize = chains.iter().map(BackupChain::file_count).sum();
+    for chain in chains.iter().take(chains.len().saturating_sub(1)) {
+        if total <= retention {
+            break;
+        }
+        for delta in chain.deltas.iter().rev() {
+            let _ = std::fs::remove_file(delta);
+        }
+        let _ = std::fs::remove_file(&chain.base);
+        total -= chain.file_count();
+    }
+    Ok(())
+}
+
+/// Write the graph at `point` to `output`. Returns the restored node count.
+///
+/// With a `key`, encrypted backups are decrypted and `output` is written
+/// encrypted, as the server would save it.
+pub fn restore_to(
+    point: &RestorePoint,
+    output: &Path,
+    #[cfg(feature = "encryption")] key: Option<&agentic_memory::types::encryption::EncryptionKey>,
+) -> McpResult<usize> {
+    let graph = point.reconstruct(
+        #[cfg(feature = "encryption")]
+        key,
+    )?;
+    memory_writer(
+        graph.dimension(),
+        #[cfg(feature = "encryption")]
+        key,
+    )
+    .write_to_file(&graph, output)
+    .map_err(|e| McpError::AgenticMemory(format!("Failed to write restored file: {e}")))?;
+    Ok(graph.node_count())
+}
+
+fn brain_stem(memory_path: &Path) -> String {
+    memory_path
+        .file_stem()
+        .and_then(OsStr::to_str)
+        .unwrap_or("brain")
+        .to_string()
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// Brain stem of a backup name prefix `{stem}.{timestamp}`.
+fn backup_stem(prefix: &str) -> Option<&str> {
+    let (stem, ts) = prefix.rsplit_once('.')?;
+    (ts.len() == 14 && ts.bytes().all(|b| b.is_ascii_digit())).then_some(stem)
+}
+
+/// Split `{stem}.{timestamp}.d{sequence}.amem.delta` into prefix and sequence.
+fn parse_delta_name(name: &str) -> Option<(&str, u32)> {
+    let rest = name.strip_suffix(DELTA_SUFFIX)?;
+    let (prefix, seq) = rest.rsplit_once(".d")?;
+    Some((prefix, seq.parse().ok()?))
+}
@@ -0,0 +1,88 @@
+//! Client-supplied idempotency keys for retry-safe writes.
+//!
+//! A retried `memory_add` carrying a key already seen in the current session
+//! resolves to the node the first attempt created. Keys live in memory only,
+//! are cleared when a new session starts, and are bounded: once full, the
+//! oldest key is forgotten first.
+
+use std::collections::{HashMap, VecDeque};
+
+/// Maximum number of keys remembered per session.
+pub const MAX_IDEMPOTENCY_KEYS: usize = 1024;
+
+/// Bounded key → node ID map, evicting in insertion order.
+#[derive(Debug, Clone)]
+pub struct IdempotencyKeys {
+    capacity: usize,
+    ids: HashMap<String, u64>,
+    order: VecDeque<String>,
+}
+
+impl IdempotencyKeys {
+    /// Remember at most `capacity` keys (at least one).
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            ids: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    /// Node recorded for `key`, if any.
+    pub fn get(&self, key: &str) -> Option<u64> {
+        self.ids.get(key).copied()
+    }
+
+    /// Record `key` → `node_id`, evicting the oldest key when full.
+    pub fn insert(&mut self, key: &str, node_id: u64) {
+        if self.ids.insert(key.to_string(), node_id).is_some() {
+            return;
+        }
+        self.order.push_back(key.to_string());
+        while self.order.len() > self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.ids.remove(&oldest);
+            }
+        }
+    }
+
+    /// Forget every key.
+    pub fn clear(&mut self) {
+        self.ids.clear();
+        self.order.clear();
+    }
+
+    /// Number of keys currently remembered.
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Whether no keys are remembered.
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+}
+
+impl Default for IdempotencyKeys {
+    fn default() -> Self {
+        Self::new(MAX_IDEMPOTENCY_KEYS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evicts_oldest_key_when_full() {
+        let mut keys = IdempotencyKeys::new(2);
+        keys.insert("a", 1);
+        keys.insert("b", 2);
+        keys.insert("a", 1);
+        keys.insert("c", 3);
+        assert_eq!(keys.get("a"), None);
+        assert_eq!(keys.get("b"), Some(2));
+        assert_eq!(keys.get("c"), Some(3));
+        assert_eq!(keys.len(), 2);
+    }
+}
@@ -1,208 +1,34 @@
 //! Graph lifecycle management, file I/O, and session tracking.
 
 use std::collections::HashMap;
-use std::ffi::OsStr;
 use std::fs::OpenOptions;
-use std::io::Read as _;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::{Duration, Instant, SystemTime};
 
+use agentic_memory::format::migration;
 use agentic_memory::{
-    AmemReader, AmemWriter, CognitiveEventBuilder, Edge, EdgeType, EventType, MemoryGraph,
-    PatternParams, PatternSort, QueryEngine, WriteEngine,
+    AmemReader, AmemWriter, Clock, CognitiveEventBuilder, ContentNormalizer, DeleteFilter,
+    DeleteReport, Edge, EdgeType, EventType, GraphBackend, MemoryGraph, PatternParams, PatternSort,
+    QueryEngine, Redactor, Tombstone, WriteEngine,
 };
 use serde_json::Value;
 
+use super::backup;
+use super::idempotency::IdempotencyKeys;
+use super::settings::{
+    read_env_string, resolve_backups_dir, AutoCaptureMode, AutonomicProfile, BackupMode,
+    SessionSettings, StorageBudgetMode, StorageMigrationPolicy,
+};
+use super::snapshot::GraphSnapshot;
 use crate::types::{McpError, McpResult, MemoryMode};
 
-/// Default auto-save interval.
-const DEFAULT_AUTO_SAVE_SECS: u64 = 30;
-/// Default backup interval.
-const DEFAULT_BACKUP_INTERVAL_SECS: u64 = 900;
-/// Default number of backups to retain per brain file.
-const DEFAULT_BACKUP_RETENTION: usize = 24;
-/// Default maintenance sleep-cycle interval.
-const DEFAULT_SLEEP_CYCLE_SECS: u64 = 1800;
-/// Minimum completed-session size before auto-archive.
-const DEFAULT_ARCHIVE_MIN_SESSION_NODES: usize = 25;
-/// Default hot-tier threshold (decay score).
-const DEFAULT_HOT_MIN_DECAY: f32 = 0.7;
-/// Default warm-tier threshold (decay score).
-const DEFAULT_WARM_MIN_DECAY: f32 = 0.3;
-/// Default sustained mutation rate threshold before throttling heavy maintenance.
-const DEFAULT_SLA_MAX_MUTATIONS_PER_MIN: u32 = 240;
-/// Default interval for writing health-ledger snapshots.
-const DEFAULT_HEALTH_LEDGER_EMIT_SECS: u64 = 30;
-/// Default long-horizon storage budget target (2 GiB over 20 years).
-const DEFAULT_STORAGE_BUDGET_BYTES: u64 = 2 * 1024 * 1024 * 1024;
-/// Default storage budget projection horizon.
-const DEFAULT_STORAGE_BUDGET_HORIZON_YEARS: u32 = 20;
-/// Default maximum chars persisted for one auto-captured prompt/feedback item.
-const DEFAULT_AUTO_CAPTURE_MAX_CHARS: usize = 2048;
 /// Current `.amem` storage version used by this server.
 const CURRENT_AMEM_VERSION: u32 = 1;
 
-#[derive(Debug, Clone, Copy)]
-enum AutonomicProfile {
-    Desktop,
-    Cloud,
-    Aggressive,
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum StorageMigrationPolicy {
-    AutoSafe,
-    Strict,
-    Off,
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum StorageBudgetMode {
-    AutoRollup,
-    Warn,
-    Off,
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum AutoCaptureMode {
-    /// Capture prompt-focused events and feedback context.
-    Safe,
-    /// Capture broader tool input text (except explicit memory_add payload duplication).
-    Full,
-    /// Disable automatic capture.
-    Off,
-}
-
-#[derive(Debug, Clone, Copy)]
-struct ProfileDefaults {
-    auto_save_secs: u64,
-    backup_secs: u64,
-    backup_retention: usize,
-    sleep_cycle_secs: u64,
-    sleep_idle_secs: u64,
-    archive_min_session_nodes: usize,
-    hot_min_decay: f32,
-    warm_min_decay: f32,
-    sla_max_mutations_per_min: u32,
-}
-
-impl AutonomicProfile {
-    fn from_env(name: &str) -> Self {
-        let raw = read_env_string(name).unwrap_or_else(|| "desktop".to_string());
-        match raw.trim().to_ascii_lowercase().as_str() {
-            "cloud" => Self::Cloud,
-            "aggressive" => Self::Aggressive,
-            _ => Self::Desktop,
-        }
-    }
-
-    fn defaults(self) -> ProfileDefaults {
-        match self {
-            Self::Desktop => ProfileDefaults {
-                auto_save_secs: DEFAULT_AUTO_SAVE_SECS,
-                backup_secs: DEFAULT_BACKUP_INTERVAL_SECS,
-                backup_retention: DEFAULT_BACKUP_RETENTION,
-                sleep_cycle_secs: DEFAULT_SLEEP_CYCLE_SECS,
-                sleep_idle_secs: 180,
-                archive_min_session_nodes: DEFAULT_ARCHIVE_MIN_SESSION_NODES,
-                hot_min_decay: DEFAULT_HOT_MIN_DECAY,
-                warm_min_decay: DEFAULT_WARM_MIN_DECAY,
-                sla_max_mutations_per_min: DEFAULT_SLA_MAX_MUTATIONS_PER_MIN,
-            },
-            Self::Cloud => ProfileDefaults {
-                auto_save_secs: 15,
-                backup_secs: 600,
-                backup_retention: 48,
-                sleep_cycle_secs: 900,
-                sleep_idle_secs: 90,
-                archive_min_session_nodes: 50,
-                hot_min_decay: 0.75,
-                warm_min_decay: 0.4,
-                sla_max_mutations_per_min: 600,
-            },
-            Self::Aggressive => ProfileDefaults {
-                auto_save_secs: 10,
-                backup_secs: 300,
-                backup_retention: 16,
-                sleep_cycle_secs: 300,
-                sleep_idle_secs: 45,
-                archive_min_session_nodes: 15,
-                hot_min_decay: 0.8,
-                warm_min_decay: 0.5,
-                sla_max_mutations_per_min: 900,
-            },
-        }
-    }
-
-    fn as_str(self) -> &'static str {
-        match self {
-            Self::Desktop => "desktop",
-            Self::Cloud => "cloud",
-            Self::Aggressive => "aggressive",
-        }
-    }
-}
-
-impl StorageMigrationPolicy {
-    fn from_env(name: &str) -> Self {
-        let raw = read_env_string(name).unwrap_or_else(|| "auto-safe".to_string());
-        match raw.trim().to_ascii_lowercase().as_str() {
-            "strict" => Self::Strict,
-            "off" | "disabled" | "none" => Self::Off,
-            _ => Self::AutoSafe,
-        }
-    }
-
-    fn as_str(self) -> &'static str {
-        match self {
-            Self::AutoSafe => "auto-safe",
-            Self::Strict => "strict",
-            Self::Off => "off",
-        }
-    }
-}
-
-impl StorageBudgetMode {
-    fn from_env(name: &str) -> Self {
-        let raw = read_env_string(name).unwrap_or_else(|| "auto-rollup".to_string());
-        match raw.trim().to_ascii_lowercase().as_str() {
-            "warn" => Self::Warn,
-            "off" | "disabled" | "none" => Self::Off,
-            _ => Self::AutoRollup,
-        }
-    }
-
-    fn as_str(self) -> &'static str {
-        match self {
-            Self::AutoRollup => "auto-rollup",
-            Self::Warn => "warn",
-            Self::Off => "off",
-        }
-    }
-}
-
-impl AutoCaptureMode {
-    fn from_env(name: &str) -> Self {
-        let raw = read_env_string(name).unwrap_or_else(|| "safe".to_string());
-        match raw.trim().to_ascii_lowercase().as_str() {
-            "full" => Self::Full,
-            "off" | "disabled" | "none" => Self::Off,
-            _ => Self::Safe,
-        }
-    }
-
-    fn as_str(self) -> &'static str {
-        match self {
-            Self::Safe => "safe",
-            Self::Full => "full",
-            Self::Off => "off",
-        }
-    }
-}
-
 /// Manages the memory graph lifecycle, file I/O, and session state.
 pub struct SessionManager {
-    graph: MemoryGraph,
+    graph: Arc<MemoryGraph>,
     query_engine: QueryEngine,
     write_engine: WriteEngine,
     file_path: PathBuf,
@@ -212,9 +38,16 @@ pub struct SessionManager {
     dirty: bool,
     last_save: Instant,
     auto_save_interval: Duration,
+    /// Saves wait this long after the first unsaved mutation so a burst of
+    /// mutations is written once (zero disables the debounce).
+    save_debounce: Duration,
+    /// When the first mutation not yet on disk was recorded.
+    pending_save_since: Option<Instant>,
     backup_interval: Duration,
     backup_retention: usize,
     backups_dir: PathBuf,
+    backup_mode: BackupMode,
+    backup_full_every: u32,
     save_generation: u64,
     last_backup_generation: u64,
     last_backup: Instant,
@@ -240,35 +73,125 @@ pub struct SessionManager {
     auto_capture_redact: bool,
     auto_capture_max_chars: usize,
     auto_capture_count: u64,
+    /// Target false-positive rate for term bloom filters written on save (0 = off).
+    term_bloom_fp_rate: f32,
+    /// Byte budget for one serialized tool result (0 = unlimited).
+    tool_result_max_bytes: usize,
+    /// How long deletion tombstones are kept before pruning.
+    tombstone_retention: Duration,
+    /// Nodes decayed per maintenance tick (0 = whole graph in one pass).
+    decay_chunk_size: usize,
+    /// Resume point of a chunked decay sweep that has not finished yet.
+    decay_cursor: Option<u64>,
+    /// Check graph integrity before every save and refuse to write a broken graph.
+    verify_on_save: bool,
+    /// Key from `AMEM_SIGNING_KEY`; every save re-signs the file with it.
+    #[cfg(feature = "signing")]
+    signing_key: Option<agentic_memory::format::signing::SigningKey>,
+    /// Key the file is decrypted with on load and encrypted with on save.
+    #[cfg(feature = "encryption")]
+    encryption_key: Option<agentic_memory::types::encryption::EncryptionKey>,
     /// ID of the last node added to the temporal chain in this session.
     /// Used to create TemporalNext edges between consecutive captures.
     last_temporal_node_id: Option<u64>,
+    /// Idempotency keys seen by `memory_add` in this session.
+    idempotency_keys: IdempotencyKeys,
+    /// Nodes forgotten in this session, keyed by their original content so a
+    /// merge can erase them again in the disk copy.
+    forgotten: Vec<Tombstone>,
+    /// Bumped on every change to the graph; stamps read snapshots.
+    graph_generation: u64,
     /// Last known file modification time (for detecting external writes).
     last_file_mtime: Option<SystemTime>,
     /// Multi-context workspace manager for cross-memory queries.
     workspace_manager: super::workspace::WorkspaceManager,
+    /// Store the graph is loaded from and saved to in place of the .amem file.
+    backend: Option<Box<dyn GraphBackend>>,
 }
 
 impl SessionManager {
     /// Open or create a memory file at the given path.
     pub fn open(path: &str) -> McpResult<Self> {
+        Self::open_with_settings(path, SessionSettings::resolve(None))
+    }
+
+    /// Open a memory file for inspection. A legacy file is never migrated,
+    /// whatever the configured migration policy.
+    pub fn open_read_only(path: &str) -> McpResult<Self> {
+        Self::open_with_settings(path, SessionSettings::resolve(None).without_auto_migrate())
+    }
+
+    /// Open or create a memory file using already-resolved runtime settings.
+    pub fn open_with_settings(path: &str, settings: SessionSettings) -> McpResult<Self> {
+        Self::open_inner(path, settings, None)
+    }
+
+    /// Open or create an AES-256-GCM encrypted memory file.
+    ///
+    /// Every save writes the file encrypted with `key`; a plain file opened
+    /// this way is encrypted by its next save.
+    #[cfg(feature = "encryption")]
+    pub fn open_encrypted(
+        path: &str,
+        key: agentic_memory::types::encryption::EncryptionKey,
+    ) -> McpResult<Self> {
+        Self::open_with_settings(
+            path,
+            SessionSettings::resolve(None).with_encryption_key(key),
+        )
+    }
+
+    /// Open a session over a graph store such as `SqliteGraph`.
+    ///
+    /// The graph is loaded from the store, and every save writes only the
+    /// nodes and edges changed since the previous one back there, through
+    /// [`GraphBackend::apply_changes`], instead of writing an .amem file.
+    /// `path` is the store's file; it names the lock and backup directory,
+    /// but backups, legacy migration and reloading after external writes
+    /// are skipped.
+    pub fn open_with_backend(path: &str, backend: Box<dyn GraphBackend>) -> McpResult<Self> {
+        Self::open_inner(path, SessionSettings::resolve(None), Some(backend))
+    }
+
+    fn open_inner(
+        path: &str,
+        settings: SessionSettings,
+        backend: Option<Box<dyn GraphBackend>>,
+    ) -> McpResult<Self> {
         let file_path = PathBuf::from(path);
         let dimension = agentic_memory::DEFAULT_DIMENSION;
-        let file_existed = file_path.exists();
-        let profile = AutonomicProfile::from_env("AMEM_AUTONOMIC_PROFILE");
-        let defaults = profile.defaults();
-        let migration_policy = StorageMigrationPolicy::from_env("AMEM_STORAGE_MIGRATION_POLICY");
-        let detected_version = if file_existed {
-            read_storage_version(&file_path)
+        let file_existed = backend.is_some() || file_path.exists();
+        let profile = settings.profile;
+        let migration_policy = settings.migration_policy;
+        let detected_version = if file_existed && backend.is_none() {
+            migration::storage_version(&file_path).ok()
         } else {
             None
         };
         let legacy_version = detected_version.filter(|v| *v < CURRENT_AMEM_VERSION);
 
-        let graph = if file_existed {
+        let graph = if let Some(backend) = &backend {
+            tracing::info!("Opening memory store: {}", file_path.display());
+            let mut graph = backend.to_memory_graph().map_err(|e| {
+                McpError::AgenticMemory(format!("Failed to load memory store: {e}"))
+            })?;
+            // Saves write back only what changed since the last one.
+            graph.track_changes();
+            graph
+        } else if file_existed {
             tracing::info!("Opening existing memory file: {}", file_path.display());
-            match AmemReader::read_from_file(&file_path) {
+            match read_memory_file(
+                &file_path,
+                #[cfg(feature = "encryption")]
+                settings.encryption_key.as_ref(),
+            ) {
                 Ok(g) => g,
+                // A wrong or missing key is not corruption; keep the file.
+                Err(e) if AmemReader::is_encrypted_file(&file_path).unwrap_or(false) => {
+                    return Err(McpError::AgenticMemory(format!(
+                        "Failed to open encrypted memory file: {e}"
+                    )));
+                }
                 Err(e) => {
                     tracing::error!("Corrupt memory file, backing up and starting fresh: {e}");
                     let backup = format!("{}.corrupt.bak", file_path.display());
@@ -314,59 +237,69 @@ impl SessionManager {
             migration_policy.as_str()
         );
 
-        let auto_save_secs = read_env_u64("AMEM_AUTOSAVE_SECS", defaults.auto_save_secs);
-        let backup_secs = read_env_u64("AMEM_AUTO_BACKUP_SECS", defaults.backup_secs).max(30);
-        let backup_retention =
-            read_env_usize("AMEM_AUTO_BACKUP_RETENTION", defaults.backup_retention).max(1);
-        let backups_dir = resolve_backups_dir(&file_path);
-        let sleep_cycle_secs =
-            read_env_u64("AMEM_SLEEP_CYCLE_SECS", defaults.sleep_cycle_secs).max(60);
-        let sleep_idle_secs =
-            read_env_u64("AMEM_SLEEP_IDLE_SECS", defaults.sleep_idle_secs).max(30);
-        let archive_min_session_nodes = read_env_usize(
-            "AMEM_ARCHIVE_MIN_SESSION_NODES",
-            defaults.archive_min_session_nodes,
-        )
-        .max(1);
-        let hot_min_decay =
-            read_env_f32("AMEM_TIER_HOT_MIN_DECAY", defaults.hot_min_decay).clamp(0.0, 1.0);
-        let warm_min_decay = read_env_f32("AMEM_TIER_WARM_MIN_DECAY", defaults.warm_min_decay)
-            .clamp(0.0, 1.0)
-            .min(hot_min_decay);
-        let sla_max_mutations_per_min = read_env_u32(
-            "AMEM_SLA_MAX_MUTATIONS_PER_MIN",
-            defaults.sla_max_mutations_per_min,
-        )
-        .max(1);
-        let health_ledger_emit_interval = Duration::from_secs(
-            read_env_u64(
-                "AMEM_HEALTH_LEDGER_EMIT_SECS",
-                DEFAULT_HEALTH_LEDGER_EMIT_SECS,
+        #[cfg(feature = "signing")]
+        let signing_key = match read_env_string("AMEM_SIGNING_KEY").filter(|v| !v.is_empty()) {
+            Some(key_path) => Some(
+                agentic_memory::format::signing::load_signing_key(Path::new(&key_path))
+                    .map_err(|e| McpError::AgenticMemory(format!("AMEM_SIGNING_KEY: {e}")))?,
+            ),
+            None => None,
+        };
+
+        #[cfg(not(feature = "encryption"))]
+        let backup_mode = settings.backup_mode;
+        #[cfg(feature = "encryption")]
+        let backup_mode = if settings.encryption_key.is_some()
+            && settings.backup_mode == BackupMode::Incremental
+        {
+            // Deltas are computed from the plain file; copy the ciphertext instead.
+            tracing::warn!("Incremental backups are not supported for encrypted files; using full");
+            BackupMode::Full
+        } else {
+            settings.backup_mode
+        };
+
+        let auto_save_secs = settings.auto_save_secs;
+        let backup_secs = settings.backup_secs;
+        let backup_retention = settings.backup_retention;
+        let (backups_dir, _) = resolve_backups_dir(&file_path);
+        let sleep_cycle_secs = settings.sleep_cycle_secs;
+        let sleep_idle_secs = settings.sleep_idle_secs;
+        let archive_min_session_nodes = settings.archive_min_session_nodes;
+        let hot_min_decay = settings.hot_min_decay;
+        let warm_min_decay = settings.warm_min_decay;
+        let sla_max_mutations_per_min = settings.sla_max_mutations_per_min;
+        let health_ledger_emit_interval = Duration::from_secs(settings.health_ledger_emit_secs);
+        let storage_budget_mode = settings.storage_budget_mode;
+        let storage_budget_max_bytes = settings.storage_budget_max_bytes;
+        let storage_budget_horizon_years = settings.storage_budget_horizon_years;
+        let storage_budget_target_fraction = settings.storage_budget_target_fraction;
+        let auto_capture_mode = settings.auto_capture_mode;
+        let auto_capture_redact = settings.auto_capture_redact;
+        let auto_capture_max_chars = settings.auto_capture_max_chars;
+        let term_bloom_fp_rate = settings.term_bloom_fp_rate;
+        let tool_result_max_bytes = settings.tool_result_max_bytes;
+        let tombstone_retention =
+            Duration::from_secs(settings.tombstone_retention_days.saturating_mul(86_400));
+
+        let mut write_engine = WriteEngine::new(dimension)
+            .with_language_detection(settings.detect_language)
+            .with_max_events_per_episode(
+                (settings.max_events_per_episode > 0).then_some(settings.max_events_per_episode),
             )
-            .max(5),
-        );
-        let storage_budget_mode = StorageBudgetMode::from_env("AMEM_STORAGE_BUDGET_MODE");
-        let storage_budget_max_bytes =
-            read_env_u64("AMEM_STORAGE_BUDGET_BYTES", DEFAULT_STORAGE_BUDGET_BYTES).max(1);
-        let storage_budget_horizon_years = read_env_u32(
-            "AMEM_STORAGE_BUDGET_HORIZON_YEARS",
-            DEFAULT_STORAGE_BUDGET_HORIZON_YEARS,
-        )
-        .max(1);
-        let storage_budget_target_fraction =
-            read_env_f32("AMEM_STORAGE_BUDGET_TARGET_FRACTION", 0.85).clamp(0.50, 0.99);
-        let auto_capture_mode = AutoCaptureMode::from_env("AMEM_AUTO_CAPTURE_MODE");
-        let auto_capture_redact = read_env_bool("AMEM_AUTO_CAPTURE_REDACT", true);
-        let auto_capture_max_chars = read_env_usize(
-            "AMEM_AUTO_CAPTURE_MAX_CHARS",
-            DEFAULT_AUTO_CAPTURE_MAX_CHARS,
-        )
-        .clamp(256, 16384);
+            .with_normalizer(if settings.normalize_content {
+                ContentNormalizer::standard()
+            } else {
+                ContentNormalizer::none()
+            });
+        if let Some(decay_function) = settings.decay_function {
+            write_engine = write_engine.with_decay_function(decay_function);
+        }
 
         let mut manager = Self {
-            graph,
+            graph: Arc::new(graph),
             query_engine: QueryEngine::new(),
-            write_engine: WriteEngine::new(dimension),
+            write_engine,
             file_path,
             current_session,
             profile,
@@ -374,9 +307,13 @@ impl SessionManager {
             dirty: false,
             last_save: Instant::now(),
             auto_save_interval: Duration::from_secs(auto_save_secs),
+            save_debounce: Duration::from_millis(settings.save_debounce_ms),
+            pending_save_since: None,
             backup_interval: Duration::from_secs(backup_secs),
             backup_retention,
             backups_dir,
+            backup_mode,
+            backup_full_every: settings.backup_full_every,
             save_generation: if file_existed { 1 } else { 0 },
             last_backup_generation: 0,
             last_backup: Instant::now(),
@@ -404,13 +341,31 @@ impl SessionManager {
             auto_capture_redact,
             auto_capture_max_chars,
             auto_capture_count: 0,
+            term_bloom_fp_rate,
+            tool_result_max_bytes,
+            tombstone_retention,
+            decay_chunk_size: settings.decay_chunk_size,
+            decay_cursor: None,
+            verify_on_save: settings.verify_on_save,
+            #[cfg(feature = "signing")]
+            signing_key,
+            #[cfg(feature = "encryption")]
+            encryption_key: settings.encryption_key.clone(),
             last_temporal_node_id: None,
-            last_file_mtime: if file_existed {
+            idempotency_keys: IdempotencyKeys::default(),
+            forgotten: Vec::new(),
+            graph_generation: 0,
+            last_file_mtime: if file_existed && backend.is_none() {
                 std::fs::metadata(path).and_then(|m| m.modified()).ok()
             } else {
                 None
             },
+            #[cfg(not(feature = "encryption"))]
             workspace_manager: super::workspace::WorkspaceManager::new(),
+            #[cfg(feature = "encryption")]
+            workspace_manager: super::workspace::WorkspaceManager::new()
+                .with_encryption_key(settings.encryption_key.clone()),
+            backend,
         };
 
         if let Some(version) = legacy_version {
@@ -450,17 +405,41 @@ impl SessionManager {
         Ok(manager)
     }
 
+    /// Read time from `clock` instead of the wall clock.
+    ///
+    /// Affects node and edge timestamps, access stamps and sleep-cycle decay;
+    /// maintenance intervals still run on real time.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.write_engine = self.write_engine.clone().with_clock(clock);
+        self
+    }
+
     /// Get an immutable reference to the graph.
     pub fn graph(&self) -> &MemoryGraph {
         &self.graph
     }
 
+    /// Immutable snapshot of the graph for a long-running read.
+    ///
+    /// The snapshot shares the session's graph rather than copying it; the
+    /// next write copies the graph only if a snapshot is still held. Take the
+    /// snapshot, release the session lock, then query it: see
+    /// [`super::snapshot`] for the isolation this gives.
+    pub fn snapshot(&self) -> GraphSnapshot {
+        GraphSnapshot::new(self.graph_generation, Arc::clone(&self.graph))
+    }
+
+    /// Generation of the current graph; changes whenever the graph does.
+    pub fn graph_generation(&self) -> u64 {
+        self.graph_generation
+    }
+
     /// Get a mutable reference to the graph and mark as dirty.
     pub fn graph_mut(&mut self) -> &mut MemoryGraph {
         self.dirty = true;
         self.last_activity = Instant::now();
         self.record_mutation();
-        &mut self.graph
+        Arc::make_mut(&mut self.graph)
     }
 
     /// Get the query engine.
@@ -488,6 +467,23 @@ impl SessionManager {
         self.current_session
     }
 
+    /// Resolve a session reference (numeric ID or alias) to a session ID.
+    pub fn resolve_session(&self, reference: &str) -> McpResult<u32> {
+        self.graph
+            .resolve_session(reference)
+            .map_err(|e| McpError::InvalidParams(e.to_string()))
+    }
+
+    /// Give a session a stable alias that survives restarts.
+    pub fn alias_session(&mut self, session_id: u32, alias: &str) -> McpResult<()> {
+        Arc::make_mut(&mut self.graph)
+            .rename_session(session_id, alias)
+            .map_err(|e| McpError::InvalidParams(e.to_string()))?;
+        self.dirty = true;
+        self.record_mutation();
+        self.maybe_auto_save()
+    }
+
     /// Apply runtime capture policy based on negotiated memory mode.
     ///
     /// This ties mode to server behavior (not just client instructions):
@@ -518,6 +514,7 @@ impl SessionManager {
 
         self.current_session = session_id;
         self.last_temporal_node_id = None;
+        self.idempotency_keys.clear();
         self.last_activity = Instant::now();
         tracing::info!("Started session {session_id}");
         Ok(session_id)
@@ -525,24 +522,47 @@ impl SessionManager {
 
     /// End a session and optionally create an episode summary.
     pub fn end_session_with_episode(&mut self, session_id: u32, summary: &str) -> McpResult<u64> {
-        let episode_id = self
-            .write_engine
-            .compress_session(&mut self.graph, session_id, summary)
+        let episodes = self.end_session_with_episodes(session_id, summary, None)?;
+        Ok(episodes[0])
+    }
+
+    /// End a session, compressing it into one episode per chunk of at most
+    /// `max_events_per_episode` nodes (`None` uses the configured
+    /// `AMEM_MAX_EVENTS_PER_EPISODE`). Returns the episode IDs in order.
+    pub fn end_session_with_episodes(
+        &mut self,
+        session_id: u32,
+        summary: &str,
+        max_events_per_episode: Option<usize>,
+    ) -> McpResult<Vec<u64>> {
+        let engine = match max_events_per_episode {
+            Some(max) => self
+                .write_engine
+                .clone()
+                .with_max_events_per_episode(Some(max)),
+            None => self.write_engine.clone(),
+        };
+        let episodes = engine
+            .compress_session_episodes(Arc::make_mut(&mut self.graph), session_id, summary)
             .map_err(|e| McpError::AgenticMemory(format!("Failed to compress session: {e}")))?;
+        let episode_id = episodes[0];
 
         self.dirty = true;
         self.last_activity = Instant::now();
         self.record_mutation();
         self.save()?;
 
-        tracing::info!("Ended session {session_id}, created episode node {episode_id}");
+        tracing::info!(
+            "Ended session {session_id}, created episode node {episode_id} ({} total)",
+            episodes.len()
+        );
 
         // Write auto-context files for next session bootstrap.
         if let Err(e) = self.write_context_files(summary) {
             tracing::warn!("Failed to write context files: {e}");
         }
 
-        Ok(episode_id)
+        Ok(episodes)
     }
 
     /// Write session context to file(s) for the next session to bootstrap from.
@@ -569,6 +589,8 @@ impl SessionManager {
                     created_after: None,
                     created_before: None,
                     min_decay_score: None,
+                    required_tags: HashMap::new(),
+                    any_tags: Vec::new(),
                     max_results: 5,
                     sort_by: PatternSort::MostRecent,
                 },
@@ -587,6 +609,8 @@ impl SessionManager {
                     created_after: None,
                     created_before: None,
                     min_decay_score: None,
+                    required_tags: HashMap::new(),
+                    any_tags: Vec::new(),
                     max_results: 5,
                     sort_by: PatternSort::MostRecent,
                 },
@@ -658,11 +682,34 @@ impl SessionManager {
     /// 2. Checks if the file was modified externally (by another instance)
     /// 3. If so, re-reads the disk graph and merges our session's new nodes
     /// 4. Writes the merged graph and releases the lock
+    ///
+    /// With `AMEM_VERIFY_ON_SAVE` the graph is checked first, and its indexes
+    /// compared against a rebuild; if either is off the save is aborted, leaving both the file and the
+    /// in-memory graph as they were. With the `signing` feature and
+    /// `AMEM_SIGNING_KEY` set, the written file is signed.
     pub fn save(&mut self) -> McpResult<()> {
         if !self.dirty {
             return Ok(());
         }
 
+        if self.verify_on_save {
+            let violations = self.graph.check_integrity();
+            if let Some(first) = violations.first() {
+                for violation in &violations {
+                    tracing::error!("Integrity check failed: {violation}");
+                }
+                return Err(McpError::AgenticMemory(format!(
+                    "Save aborted: {} integrity violation(s), first: {first}",
+                    violations.len()
+                )));
+            }
+            let report = self.graph.verify_indexes();
+            if !report.is_consistent() {
+                tracing::error!("Index verification failed: {report}");
+                return Err(McpError::AgenticMemory(format!("Save aborted: {report}")));
+            }
+        }
+
         let _lock = FileLock::acquire(&self.file_path)?;
 
         // Detect external modifications from concurrent sessions.
@@ -678,31 +725,111 @@ impl SessionManager {
             }
         }
 
-        let writer = AmemWriter::new(self.graph.dimension());
-        writer
-            .write_to_file(&self.graph, &self.file_path)
-            .map_err(|e| McpError::AgenticMemory(format!("Failed to write memory file: {e}")))?;
+        let cutoff = self
+            .write_engine
+            .now_micros()
+            .saturating_sub(self.tombstone_retention.as_micros() as u64);
+        let pruned = Arc::make_mut(&mut self.graph).prune_tombstones(cutoff);
+        if pruned > 0 {
+            self.graph_changed();
+            tracing::debug!("Pruned {pruned} expired tombstones");
+        }
+
+        if let Some(backend) = &mut self.backend {
+            if let Some(changes) = self.graph.changes() {
+                backend.apply_changes(&self.graph, changes).map_err(|e| {
+                    McpError::AgenticMemory(format!("Failed to write memory store: {e}"))
+                })?;
+            }
+            Arc::make_mut(&mut self.graph).take_changes();
+        } else {
+            let mut writer = AmemWriter::new(self.graph.dimension());
+            if self.term_bloom_fp_rate > 0.0 {
+                writer = writer.with_term_bloom(self.term_bloom_fp_rate);
+            }
+            #[cfg(feature = "signing")]
+            if let Some(key) = &self.signing_key {
+                writer = writer.with_signing_key(key.clone());
+            }
+            #[cfg(feature = "encryption")]
+            if let Some(key) = &self.encryption_key {
+                writer = writer.with_encryption_key(key.clone());
+            }
+            writer
+                .write_to_file(&self.graph, &self.file_path)
+                .map_err(|e| {
+                    McpError::AgenticMemory(format!("Failed to write memory file: {e}"))
+                })?;
 
-        // Update our mtime tracking after successful write.
-        self.last_file_mtime = std::fs::metadata(&self.file_path)
-            .and_then(|m| m.modified())
-            .ok();
+            // Update our mtime tracking after successful write.
+            self.last_file_mtime = std::fs::metadata(&self.file_path)
+                .and_then(|m| m.modified())
+                .ok();
+        }
 
         self.dirty = false;
+        self.pending_save_since = None;
         self.last_save = Instant::now();
         self.save_generation = self.save_generation.saturating_add(1);
         tracing::debug!("Saved memory file: {}", self.file_path.display());
         Ok(())
     }
 
+    /// Re-read the memory file if it was modified outside this process.
+    ///
+    /// Only a clean session is reloaded; unsaved changes are left alone and
+    /// merged with the disk state by the next [`save`](Self::save). Returns
+    /// whether the graph was replaced.
+    pub fn reload_if_changed(&mut self) -> McpResult<bool> {
+        if self.dirty || self.backend.is_some() {
+            return Ok(false);
+        }
+        let Ok(current) = std::fs::metadata(&self.file_path).and_then(|m| m.modified()) else {
+            return Ok(false);
+        };
+        if self.last_file_mtime.is_some_and(|last| current <= last) {
+            return Ok(false);
+        }
+
+        let _lock = FileLock::acquire(&self.file_path)?;
+        let graph = read_memory_file(
+            &self.file_path,
+            #[cfg(feature = "encryption")]
+            self.encryption_key.as_ref(),
+        )
+        .map_err(|e| McpError::AgenticMemory(format!("Failed to reload memory file: {e}")))?;
+        self.graph = Arc::new(graph);
+        self.graph_changed();
+        if let Some(prev) = self.last_temporal_node_id {
+            if self.graph.get_node(prev).is_none() {
+                self.last_temporal_node_id = None;
+            }
+        }
+        self.last_file_mtime = std::fs::metadata(&self.file_path)
+            .and_then(|m| m.modified())
+            .ok();
+        tracing::info!(
+            "Reloaded memory file after external change: {} ({} nodes)",
+            self.file_path.display(),
+            self.graph.node_count()
+        );
+        Ok(true)
+    }
+
     /// Merge our session's nodes/edges with the latest disk state.
     ///
     /// This handles the case where another MCP instance wrote to the same file
     /// since we last read it. We re-read the disk, then re-add our session's
-    /// nodes on top of the latest state.
+    /// nodes on top of the latest state. Tombstones from both sides are
+    /// honoured: nodes we deleted are removed from the disk state, and nodes
+    /// the other instance deleted are not re-added.
     fn merge_with_disk(&mut self) -> McpResult<()> {
-        let disk_graph = AmemReader::read_from_file(&self.file_path)
-            .map_err(|e| McpError::AgenticMemory(format!("Failed to re-read for merge: {e}")))?;
+        let disk_graph = read_memory_file(
+            &self.file_path,
+            #[cfg(feature = "encryption")]
+            self.encryption_key.as_ref(),
+        )
+        .map_err(|e| McpError::AgenticMemory(format!("Failed to re-read for merge: {e}")))?;
 
         // Collect our session's nodes (those we created in this process).
         let our_nodes: Vec<_> = self
@@ -723,19 +850,73 @@ impl SessionManager {
             .cloned()
             .collect();
 
+        let our_aliases: Vec<(String, u32)> = self
+            .graph
+            .session_index()
+            .aliases()
+            .into_iter()
+            .map(|(alias, sid)| (alias.to_string(), sid))
+            .collect();
+
+        let our_embedding_model = self.graph.embedding_model().map(str::to_string);
+        let our_tombstones = self.graph.tombstones().to_vec();
+
         // Replace our graph with the latest disk state.
-        self.graph = disk_graph;
+        self.graph = Arc::new(disk_graph);
+        self.graph_changed();
+
+        // Keep the embedding model we recorded if the disk copy has none.
+        if self.graph.embedding_model().is_none() {
+            Arc::make_mut(&mut self.graph).set_embedding_model(our_embedding_model);
+        }
+
+        // Carry over aliases; one assigned on disk by another instance wins.
+        for (alias, sid) in our_aliases {
+            if let Err(e) = Arc::make_mut(&mut self.graph).rename_session(sid, &alias) {
+                tracing::warn!("Dropped session alias during merge: {e}");
+            }
+        }
+
+        // Deletions made here win over the disk copy.
+        let removed = Arc::make_mut(&mut self.graph).apply_tombstones(&our_tombstones);
+        if removed > 0 {
+            tracing::info!("Removed {removed} nodes deleted by this session from disk state");
+        }
 
-        // Re-add our session's nodes with fresh IDs from the merged graph.
+        // So does forgetting: the disk copy still holds the original content.
+        let reforget: Vec<(u64, u64)> = self
+            .graph
+            .nodes()
+            .iter()
+            .filter_map(|n| {
+                self.forgotten
+                    .iter()
+                    .find(|t| t.matches(n))
+                    .map(|t| (n.id, t.deleted_at))
+            })
+            .collect();
+        for (id, at) in reforget {
+            Arc::make_mut(&mut self.graph)
+                .forget_node(id, forgotten_marker(at))
+                .map_err(|e| McpError::AgenticMemory(format!("Merge forget failed: {e}")))?;
+        }
+
+        // Re-add our session's nodes with fresh IDs from the merged graph,
+        // skipping any another instance has deleted.
         let mut id_map: HashMap<u64, u64> = HashMap::new();
+        let mut deleted: std::collections::HashSet<u64> = std::collections::HashSet::new();
         for node in &our_nodes {
+            if self.graph.is_tombstoned(node) {
+                deleted.insert(node.id);
+                continue;
+            }
             let event = CognitiveEventBuilder::new(node.event_type, node.content.clone())
                 .session_id(self.current_session)
                 .confidence(node.confidence)
                 .build();
             let result = self
                 .write_engine
-                .ingest(&mut self.graph, vec![event], vec![])
+                .ingest(Arc::make_mut(&mut self.graph), vec![event], vec![])
                 .map_err(|e| McpError::AgenticMemory(format!("Merge node re-add failed: {e}")))?;
             if let Some(&new_id) = result.new_node_ids.first() {
                 id_map.insert(node.id, new_id);
@@ -744,6 +925,9 @@ impl SessionManager {
 
         // Re-add our session's edges with remapped IDs.
         for edge in &our_edges {
+            if deleted.contains(&edge.source_id) || deleted.contains(&edge.target_id) {
+                continue;
+            }
             let source = id_map
                 .get(&edge.source_id)
                 .copied()
@@ -753,14 +937,14 @@ impl SessionManager {
                 .copied()
                 .unwrap_or(edge.target_id);
             let new_edge = Edge::new(source, target, edge.edge_type, edge.weight);
-            if let Err(e) = self.graph.add_edge(new_edge) {
+            if let Err(e) = Arc::make_mut(&mut self.graph).add_edge(new_edge) {
                 tracing::warn!("Merge edge re-add skipped: {e}");
             }
         }
 
         tracing::info!(
             "Merged {} nodes and {} edges from session {} into disk state",
-            our_nodes.len(),
+            our_nodes.len() - deleted.len(),
             our_edges.len(),
             self.current_session
         );
@@ -768,13 +952,25 @@ impl SessionManager {
     }
 
     /// Check if auto-save is needed and save if so.
+    ///
+    /// A save is held back while the current burst of mutations is younger
+    /// than the debounce window. [`save`](Self::save) itself never waits, so
+    /// shutdown and explicit saves flush immediately.
     pub fn maybe_auto_save(&mut self) -> McpResult<()> {
-        if self.dirty && self.last_save.elapsed() >= self.auto_save_interval {
+        if self.dirty
+            && self.last_save.elapsed() >= self.auto_save_interval
+            && !self.save_debounced()
+        {
             self.save()?;
         }
         Ok(())
     }
 
+    fn save_debounced(&self) -> bool {
+        self.pending_save_since
+            .is_some_and(|since| since.elapsed() < self.save_debounce)
+    }
+
     /// Runs autonomous maintenance: sleep-cycle, auto-save, and periodic backup.
     pub fn run_maintenance_tick(&mut self) -> McpResult<()> {
         if self.should_throttle_maintenance() {
@@ -798,19 +994,46 @@ impl SessionManager {
     }
 
     /// Run a periodic sleep-cycle: decay refresh + tier balancing + auto-archive.
+    ///
+    /// With a decay chunk size set, decay is spread over consecutive ticks and
+    /// the rest of the cycle runs once the sweep has visited every node.
     pub fn maybe_run_sleep_cycle(&mut self) -> McpResult<()> {
-        if self.last_sleep_cycle.elapsed() < self.sleep_cycle_interval {
+        // A partial decay sweep continues on the next tick.
+        if self.decay_cursor.is_none()
+            && self.last_sleep_cycle.elapsed() < self.sleep_cycle_interval
+        {
             return Ok(());
         }
         if self.last_activity.elapsed() < self.sleep_idle_min {
             return Ok(());
         }
 
-        let now = agentic_memory::now_micros();
-        let decay_report = self
-            .write_engine
-            .run_decay(&mut self.graph, now)
-            .map_err(|e| McpError::AgenticMemory(format!("Sleep-cycle decay failed: {e}")))?;
+        let decay_report = if self.decay_chunk_size == 0 {
+            self.write_engine
+                .run_decay_now(Arc::make_mut(&mut self.graph))
+        } else {
+            let now = self.write_engine.now_micros();
+            self.write_engine.run_decay_chunk(
+                Arc::make_mut(&mut self.graph),
+                now,
+                self.decay_cursor,
+                self.decay_chunk_size,
+            )
+        }
+        .map_err(|e| McpError::AgenticMemory(format!("Sleep-cycle decay failed: {e}")))?;
+        self.graph_changed();
+        self.decay_cursor = decay_report.cursor;
+        if !decay_report.complete {
+            if decay_report.nodes_decayed > 0 {
+                self.dirty = true;
+            }
+            tracing::debug!(
+                "Sleep-cycle decay chunk: visited={} decayed={}",
+                decay_report.nodes_visited,
+                decay_report.nodes_decayed
+            );
+            return Ok(());
+        }
         let archived_sessions = self.auto_archive_completed_sessions()?;
 
         if decay_report.nodes_decayed > 0 || archived_sessions > 0 {
@@ -839,17 +1062,32 @@ impl SessionManager {
         if self.save_generation <= self.last_backup_generation {
             return Ok(());
         }
-        if !self.file_path.exists() {
+        if !self.file_path.exists() || self.backend.is_some() {
             return Ok(());
         }
 
         std::fs::create_dir_all(&self.backups_dir).map_err(McpError::Io)?;
-        let backup_path = self.next_backup_path();
-        std::fs::copy(&self.file_path, &backup_path).map_err(McpError::Io)?;
+        let backup_path = match self.backup_mode {
+            BackupMode::Full => {
+                let path = self.next_backup_path();
+                std::fs::copy(&self.file_path, &path).map_err(McpError::Io)?;
+                Some(path)
+            }
+            BackupMode::Incremental => backup::write_incremental_backup(
+                &self.file_path,
+                &self.backups_dir,
+                self.backup_full_every,
+                #[cfg(feature = "encryption")]
+                self.encryption_key.as_ref(),
+            )?,
+        };
         self.last_backup_generation = self.save_generation;
         self.last_backup = Instant::now();
         self.prune_old_backups()?;
-        tracing::info!("Auto-backup written: {}", backup_path.display());
+        match backup_path {
+            Some(path) => tracing::info!("Auto-backup written: {}", path.display()),
+            None => tracing::debug!("Auto-backup skipped: no changes since last backup"),
+        }
         Ok(())
     }
 
@@ -875,21 +1113,56 @@ impl SessionManager {
         self.last_temporal_node_id = Some(node_id);
     }
 
+    /// Node previously added in this session under `key`, if it still exists.
+    pub fn idempotent_node(&self, key: &str) -> Option<u64> {
+        let node_id = self.idempotency_keys.get(key)?;
+        self.graph
+            .get_node(node_id)
+            .filter(|n| n.session_id == self.current_session)
+            .map(|_| node_id)
+    }
+
+    /// Record that `key` created `node_id` in this session.
+    pub fn remember_idempotency_key(&mut self, key: &str, node_id: u64) {
+        self.idempotency_keys.insert(key, node_id);
+    }
+
     /// Create a TemporalNext edge from `prev_id` to `next_id` (forward in time).
     pub fn link_temporal(&mut self, prev_id: u64, next_id: u64) -> McpResult<()> {
-        let edge = Edge::new(prev_id, next_id, EdgeType::TemporalNext, 1.0);
-        self.graph
+        let edge = Edge::with_timestamp(
+            prev_id,
+            next_id,
+            EdgeType::TemporalNext,
+            1.0,
+            self.write_engine.now_micros(),
+        );
+        Arc::make_mut(&mut self.graph)
             .add_edge(edge)
             .map_err(|e| McpError::AgenticMemory(format!("Failed to add temporal edge: {e}")))?;
         self.dirty = true;
+        self.graph_changed();
         Ok(())
     }
 
+    /// Byte budget for one serialized tool result (0 = unlimited).
+    pub fn tool_result_max_bytes(&self) -> usize {
+        self.tool_result_max_bytes
+    }
+
     /// Background maintenance loop interval.
+    ///
+    /// With a debounce window set, the loop also wakes often enough to flush
+    /// a deferred save shortly after a burst of mutations ends.
     pub fn maintenance_interval(&self) -> Duration {
-        self.auto_save_interval
+        let interval = self
+            .auto_save_interval
             .min(self.backup_interval)
-            .min(self.sleep_cycle_interval)
+            .min(self.sleep_cycle_interval);
+        if self.save_debounce.is_zero() {
+            interval
+        } else {
+            interval.min(self.save_debounce)
+        }
     }
 
     /// Capture a prompt template invocation (`prompts/get`) into memory.
@@ -936,15 +1209,31 @@ impl SessionManager {
         confidence: f32,
         edges: Vec<(u64, EdgeType, f32)>,
     ) -> McpResult<(u64, usize)> {
+        self.add_event_with(event_type, content, confidence, edges, false)
+    }
+
+    /// Add a cognitive event to the graph, pinning it against decay when
+    /// `pinned` is set.
+    pub fn add_event_with(
+        &mut self,
+        event_type: EventType,
+        content: &str,
+        confidence: f32,
+        edges: Vec<(u64, EdgeType, f32)>,
+        pinned: bool,
+    ) -> McpResult<(u64, usize)> {
+        let now = self.write_engine.now_micros();
         let event = CognitiveEventBuilder::new(event_type, content.to_string())
             .session_id(self.current_session)
             .confidence(confidence)
+            .created_at(now)
+            .pinned(pinned)
             .build();
 
         // First, add the node to get its assigned ID
         let result = self
             .write_engine
-            .ingest(&mut self.graph, vec![event], vec![])
+            .ingest(Arc::make_mut(&mut self.graph), vec![event], vec![])
             .map_err(|e| McpError::AgenticMemory(format!("Failed to add event: {e}")))?;
 
         let node_id = result.new_node_ids.first().copied().ok_or_else(|| {
@@ -954,8 +1243,8 @@ impl SessionManager {
         // Then add edges with the correct source_id
         let mut edge_count = 0;
         for (target_id, edge_type, weight) in &edges {
-            let edge = Edge::new(node_id, *target_id, *edge_type, *weight);
-            self.graph
+            let edge = Edge::with_timestamp(node_id, *target_id, *edge_type, *weight, now);
+            Arc::make_mut(&mut self.graph)
                 .add_edge(edge)
                 .map_err(|e| McpError::AgenticMemory(format!("Failed to add edge: {e}")))?;
             edge_count += 1;
@@ -971,15 +1260,38 @@ impl SessionManager {
 
     /// Correct a previous belief.
     pub fn correct_node(&mut self, old_node_id: u64, new_content: &str) -> McpResult<u64> {
+        self.correct_node_with(old_node_id, new_content, 1.0, None, false)
+    }
+
+    /// Correct a previous belief, setting the new node's confidence and,
+    /// optionally, its feature vector in the same step.
+    ///
+    /// Correcting a node to its own content is a no-op that returns
+    /// `old_node_id`, unless `reassert` asks for a new node anyway.
+    pub fn correct_node_with(
+        &mut self,
+        old_node_id: u64,
+        new_content: &str,
+        confidence: f32,
+        feature_vec: Option<Vec<f32>>,
+        reassert: bool,
+    ) -> McpResult<u64> {
         let new_id = self
             .write_engine
-            .correct(
-                &mut self.graph,
+            .clone()
+            .with_reassert_corrections(reassert)
+            .correct_with(
+                Arc::make_mut(&mut self.graph),
                 old_node_id,
                 new_content,
                 self.current_session,
+                confidence,
+                feature_vec,
             )
             .map_err(|e| McpError::AgenticMemory(format!("Failed to correct node: {e}")))?;
+        if new_id == old_node_id {
+            return Ok(new_id);
+        }
 
         self.dirty = true;
         self.last_activity = Instant::now();
@@ -989,12 +1301,91 @@ impl SessionManager {
         Ok(new_id)
     }
 
+    /// Erase a node's content, keeping the node and its edges.
+    ///
+    /// The content becomes `[forgotten at <time>]` and the node's vector and
+    /// term index entries are cleared, so the text is gone from the file after
+    /// the next save while causal chains and the fact that something was
+    /// removed stay visible. Returns the marker written.
+    pub fn forget_node(&mut self, node_id: u64) -> McpResult<String> {
+        let node = self
+            .graph
+            .get_node(node_id)
+            .ok_or(McpError::NodeNotFound(node_id))?;
+        let at = self.write_engine.now_micros();
+        let record = Tombstone::for_node(node, at);
+        let marker = forgotten_marker(at);
+        Arc::make_mut(&mut self.graph)
+            .forget_node(node_id, marker.clone())
+            .map_err(|e| McpError::AgenticMemory(format!("Failed to forget node: {e}")))?;
+        self.forgotten.push(record);
+
+        self.dirty = true;
+        self.last_activity = Instant::now();
+        self.record_mutation();
+        self.maybe_auto_save()?;
+
+        Ok(marker)
+    }
+
+    /// Delete every node matching `filter`, with all edges touching it.
+    ///
+    /// Unlike [`forget_node`](Self::forget_node), the nodes are gone from the
+    /// graph; each leaves a tombstone. A filter with no criteria is refused.
+    pub fn delete_where(&mut self, filter: DeleteFilter) -> McpResult<DeleteReport> {
+        if filter.is_empty() {
+            return Err(McpError::InvalidParams(
+                "delete_where needs at least one filter criterion".to_string(),
+            ));
+        }
+        let report = self
+            .query_engine
+            .delete_where(Arc::make_mut(&mut self.graph), &filter)
+            .map_err(|e| McpError::AgenticMemory(format!("Failed to delete nodes: {e}")))?;
+        if report.deleted_count == 0 {
+            return Ok(report);
+        }
+
+        self.dirty = true;
+        self.last_activity = Instant::now();
+        self.record_mutation();
+        self.maybe_auto_save()?;
+
+        Ok(report)
+    }
+
+    /// Check `model` against the embedding model recorded for the file,
+    /// recording it if none is set yet. A mismatch is an error when `strict`;
+    /// otherwise the file's model is returned so the caller can warn.
+    pub fn check_embedding_model(
+        &mut self,
+        model: &str,
+        strict: bool,
+    ) -> McpResult<Option<String>> {
+        let before = self.graph.embedding_model().map(str::to_string);
+        let mismatch = Arc::make_mut(&mut self.graph)
+            .check_embedding_model(model, strict)
+            .map_err(|e| McpError::InvalidParams(e.to_string()))?;
+        if before.is_none() && self.graph.embedding_model().is_some() {
+            self.dirty = true;
+            self.graph_changed();
+        }
+        Ok(mismatch)
+    }
+
+    /// Note that the graph changed, so later snapshots get a new generation.
+    fn graph_changed(&mut self) {
+        self.graph_generation = self.graph_generation.wrapping_add(1);
+    }
+
     fn record_mutation(&mut self) {
+        self.graph_changed();
         if self.mutation_window_started.elapsed() >= Duration::from_secs(60) {
             self.mutation_window_started = Instant::now();
             self.mutation_window_count = 0;
         }
         self.mutation_window_count = self.mutation_window_count.saturating_add(1);
+        self.pending_save_since.get_or_insert_with(Instant::now);
     }
 
     fn mutation_rate_per_min(&self) -> u32 {
@@ -1044,6 +1435,7 @@ impl SessionManager {
                 "dirty": self.dirty,
                 "save_generation": self.save_generation,
                 "backup_retention": self.backup_retention,
+                "backup_mode": self.backup_mode.as_str(),
             },
             "storage_budget": {
                 "mode": self.storage_budget_mode.as_str(),
@@ -1149,12 +1541,13 @@ impl SessionManager {
                 session_id, event_nodes, hot, warm, cold
             );
             self.write_engine
-                .compress_session(&mut self.graph, session_id, &summary)
+                .compress_session(Arc::make_mut(&mut self.graph), session_id, &summary)
                 .map_err(|e| {
                     McpError::AgenticMemory(format!(
                         "Auto-archive failed for session {session_id}: {e}"
                     ))
                 })?;
+            self.graph_changed();
             archived = archived.saturating_add(1);
         }
 
@@ -1257,7 +1650,7 @@ impl SessionManager {
         }
 
         if self.auto_capture_redact {
-            text = redact_sensitive_tokens(&text);
+            text = Redactor::new().redact(&text);
         }
 
         if text.len() > self.auto_capture_max_chars {
@@ -1329,31 +1722,22 @@ impl SessionManager {
             return Ok(None);
         }
 
-        let migration_dir = resolve_migration_dir(&self.file_path);
-        std::fs::create_dir_all(&migration_dir).map_err(McpError::Io)?;
-
-        let ts = chrono::Utc::now().format("%Y%m%d%H%M%S");
-        let stem = self
-            .file_path
-            .file_stem()
-            .and_then(OsStr::to_str)
-            .unwrap_or("brain");
-        let checkpoint = migration_dir.join(format!("{stem}.v{from_version}.{ts}.amem.checkpoint"));
-        std::fs::copy(&self.file_path, &checkpoint).map_err(McpError::Io)?;
-        Ok(Some(checkpoint))
+        migration::create_checkpoint(&self.file_path, from_version)
+            .map(Some)
+            .map_err(|e| {
+                McpError::AgenticMemory(format!("Failed to checkpoint before migration: {e}"))
+            })
     }
 
     fn next_backup_path(&self) -> PathBuf {
-        let ts = chrono::Utc::now().format("%Y%m%d%H%M%S");
-        let stem = self
-            .file_path
-            .file_stem()
-            .and_then(OsStr::to_str)
-            .unwrap_or("brain");
-        self.backups_dir.join(format!("{stem}.{ts}.amem.bak"))
+        backup::base_backup_path(&self.backups_dir, &self.file_path)
     }
 
     fn prune_old_backups(&self) -> McpResult<()> {
+        if self.backup_mode == BackupMode::Incremental {
+            return backup::prune_chains(&self.backups_dir, &self.file_path, self.backup_retention);
+        }
+
         let mut entries = std::fs::read_dir(&self.backups_dir)
             .map_err(McpError::Io)?
             .filter_map(Result::ok)
@@ -1385,77 +1769,39 @@ impl SessionManager {
     }
 }
 
-fn resolve_backups_dir(memory_path: &std::path::Path) -> PathBuf {
-    if let Ok(custom) = std::env::var("AMEM_AUTO_BACKUP_DIR") {
-        let trimmed = custom.trim();
-        if !trimmed.is_empty() {
-            return PathBuf::from(trimmed);
+/// Read an .amem file, decrypting it with `key` when it is encrypted.
+pub(crate) fn read_memory_file(
+    path: &Path,
+    #[cfg(feature = "encryption")] key: Option<&agentic_memory::types::encryption::EncryptionKey>,
+) -> agentic_memory::AmemResult<MemoryGraph> {
+    #[cfg(feature = "encryption")]
+    if let Some(key) = key {
+        if AmemReader::is_encrypted_file(path)? {
+            return AmemReader::read_encrypted(path, key);
         }
     }
-
-    let parent = memory_path.parent().unwrap_or(std::path::Path::new("."));
-    parent.join(".amem-backups")
+    AmemReader::read_from_file(path)
 }
 
-fn resolve_migration_dir(memory_path: &Path) -> PathBuf {
-    let parent = memory_path.parent().unwrap_or(std::path::Path::new("."));
-    parent.join(".amem-migrations")
+/// A writer whose files are encrypted with `key` when one is given.
+pub(crate) fn memory_writer(
+    dimension: usize,
+    #[cfg(feature = "encryption")] key: Option<&agentic_memory::types::encryption::EncryptionKey>,
+) -> AmemWriter {
+    let writer = AmemWriter::new(dimension);
+    #[cfg(feature = "encryption")]
+    if let Some(key) = key {
+        return writer.with_encryption_key(key.clone());
+    }
+    writer
 }
 
-fn read_storage_version(path: &Path) -> Option<u32> {
-    let mut file = std::fs::File::open(path).ok()?;
-    let mut header = [0u8; 8];
-    file.read_exact(&mut header).ok()?;
-    if &header[0..4] != b"AMEM" {
-        return None;
-    }
-    Some(u32::from_le_bytes([
-        header[4], header[5], header[6], header[7],
-    ]))
-}
-
-fn read_env_u64(name: &str, default_value: u64) -> u64 {
-    std::env::var(name)
-        .ok()
-        .and_then(|v| v.parse::<u64>().ok())
-        .unwrap_or(default_value)
-}
-
-fn read_env_u32(name: &str, default_value: u32) -> u32 {
-    std::env::var(name)
-        .ok()
-        .and_then(|v| v.parse::<u32>().ok())
-        .unwrap_or(default_value)
-}
-
-fn read_env_usize(name: &str, default_value: usize) -> usize {
-    std::env::var(name)
-        .ok()
-        .and_then(|v| v.parse::<usize>().ok())
-        .unwrap_or(default_value)
-}
-
-fn read_env_f32(name: &str, default_value: f32) -> f32 {
-    std::env::var(name)
-        .ok()
-        .and_then(|v| v.parse::<f32>().ok())
-        .unwrap_or(default_value)
-}
-
-fn read_env_bool(name: &str, default_value: bool) -> bool {
-    std::env::var(name)
-        .ok()
-        .map(|v| {
-            matches!(
-                v.trim().to_ascii_lowercase().as_str(),
-                "1" | "true" | "yes" | "on"
-            )
-        })
-        .unwrap_or(default_value)
-}
-
-fn read_env_string(name: &str) -> Option<String> {
-    std::env::var(name).ok().map(|v| v.trim().to_string())
+/// Content left in place of a forgotten node's text.
+fn forgotten_marker(at_micros: u64) -> String {
+    let at = chrono::DateTime::from_timestamp_micros(at_micros as i64)
+        .map(|t| t.to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+        .unwrap_or_else(|| at_micros.to_string());
+    format!("[forgotten at {at}]")
 }
 
 fn resolve_health_ledger_dir() -> PathBuf {
@@ -1697,49 +2043,6 @@ fn value_to_compact_string(value: &Value) -> Option<String> {
     }
 }
 
-fn redact_sensitive_tokens(text: &str) -> String {
-    text.split_whitespace()
-        .map(redact_token)
-        .collect::<Vec<_>>()
-        .join(" ")
-}
-
-fn redact_token(token: &str) -> String {
-    let trimmed = token.trim_matches(|c: char| c == '"' || c == '\'' || c == ',' || c == ';');
-    let lower = trimmed.to_ascii_lowercase();
-    if trimmed.starts_with("/Users/")
-        || trimmed.starts_with("C:\\Users\\")
-        || trimmed.contains("/Users/")
-        || trimmed.contains("C:\\Users\\")
-    {
-        return "[REDACTED_PATH]".to_string();
-    }
-    if trimmed.contains('@') && trimmed.contains('.') {
-        return "[REDACTED_EMAIL]".to_string();
-    }
-    if lower.starts_with("sk-")
-        || lower.contains("api_key")
-        || lower.contains("access_token")
-        || lower.contains("bearer")
-        || lower.contains("authorization")
-    {
-        return "[REDACTED_SECRET]".to_string();
-    }
-    if looks_like_long_secret(trimmed) {
-        return "[REDACTED_SECRET]".to_string();
-    }
-    token.to_string()
-}
-
-fn looks_like_long_secret(token: &str) -> bool {
-    if token.len() < 24 {
-        return false;
-    }
-    token
-        .chars()
-        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.')
-}
-
 /// File-based exclusive lock for concurrent `.amem` access.
 ///
 /// Uses a sidecar `.amem.lock` file with `create_new` (O_EXCL) for atomic
@@ -1752,10 +2055,12 @@ struct FileLock {
 impl FileLock {
     /// Acquire an exclusive lock for the given data file.
     /// Spins with a 50ms backoff until the lock is available.
+    /// Fails with [`McpError::LockTimeout`] if the lock stays held.
     fn acquire(data_path: &Path) -> McpResult<Self> {
         let lock_path = data_path.with_extension("amem.lock");
         let stale_threshold = Duration::from_secs(60);
         let max_attempts = 200; // 200 * 50ms = 10 seconds max wait
+        let started = Instant::now();
 
         for attempt in 0..max_attempts {
             match OpenOptions::new()
@@ -1796,10 +2101,10 @@ impl FileLock {
             }
         }
 
-        Err(McpError::AgenticMemory(format!(
-            "Timed out waiting for file lock: {}",
-            lock_path.display()
-        )))
+        Err(McpError::LockTimeout {
+            path: lock_path,
+            waited: started.elapsed(),
+        })
     }
 }
 
@@ -1830,6 +2135,80 @@ mod tests {
     use super::*;
     use serde_json::json;
 
+    #[test]
+    fn read_only_open_never_migrates_legacy_file() {
+        let dir = tempfile::tempdir().expect("test fixture");
+        let brain = dir.path().join("legacy.amem");
+        let path = brain.to_str().expect("test fixture");
+        {
+            let mut manager = SessionManager::open(path).expect("test fixture");
+            manager
+                .add_event(EventType::Fact, "kept as-is", 0.9, vec![])
+                .expect("test fixture");
+            manager.save().expect("test fixture");
+        }
+        let mut bytes = std::fs::read(&brain).expect("test fixture");
+        bytes[4..8].copy_from_slice(&0u32.to_le_bytes());
+        std::fs::write(&brain, &bytes).expect("test fixture");
+        let checkpoints = dir.path().join(migration::MIGRATION_DIR);
+
+        let manager = SessionManager::open_read_only(path).expect("test fixture");
+        assert_eq!(manager.graph().node_count(), 1);
+        drop(manager);
+        assert_eq!(std::fs::read(&brain).expect("test fixture"), bytes);
+        assert!(!checkpoints.exists());
+
+        let settings = SessionSettings::resolve(None);
+        assert_eq!(settings.migration_policy, StorageMigrationPolicy::AutoSafe);
+        let manager = SessionManager::open_with_settings(path, settings).expect("test fixture");
+        drop(manager);
+        assert_eq!(
+            migration::storage_version(&brain).expect("test fixture"),
+            agentic_memory::types::FORMAT_VERSION
+        );
+        assert_eq!(
+            std::fs::read_dir(&checkpoints)
+                .expect("test fixture")
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn snapshot_is_shared_until_write_and_isolated_from_it() {
+        let dir = tempfile::tempdir().expect("test fixture");
+        let brain = dir.path().join("snapshot.amem");
+        let mut manager =
+            SessionManager::open(brain.to_str().expect("test fixture")).expect("test fixture");
+        manager
+            .add_event(EventType::Fact, "before the snapshot", 0.9, vec![])
+            .expect("test fixture");
+
+        let first = manager.snapshot();
+        let again = manager.snapshot();
+        assert!(first.shares_graph_with(&again));
+        assert_eq!(first.generation(), manager.graph_generation());
+
+        manager
+            .add_event(EventType::Fact, "after the snapshot", 0.9, vec![])
+            .expect("test fixture");
+        assert_eq!(first.graph().node_count(), 1);
+        assert_eq!(manager.graph().node_count(), 2);
+
+        let fresh = manager.snapshot();
+        assert!(!fresh.shares_graph_with(&first));
+        assert!(fresh.generation() > first.generation());
+        assert_eq!(fresh.graph().node_count(), 2);
+
+        // Once every snapshot is dropped, a write updates the graph in place.
+        drop((first, again, fresh));
+        let shared = Arc::as_ptr(&manager.graph);
+        manager
+            .add_event(EventType::Fact, "after the readers", 0.9, vec![])
+            .expect("test fixture");
+        assert_eq!(Arc::as_ptr(&manager.graph), shared);
+    }
+
     #[test]
     fn budget_projection_available_with_timeline() {
         let dir = tempfile::tempdir().expect("test fixture");
@@ -1848,6 +2227,7 @@ mod tests {
             let graph = manager.graph_mut();
             let old = graph.get_node_mut(id_a).expect("test fixture");
             old.created_at = old.created_at.saturating_sub(15 * 24 * 3600 * 1_000_000);
+            graph.rebuild_indexes();
         }
         manager.save().expect("test fixture");
         let size = manager.current_file_size_bytes();
@@ -2024,4 +2404,257 @@ mod tests {
         });
         assert!(has_edge, "memory_add node should be linked into chain");
     }
+
+    #[test]
+    fn debounced_autosave_coalesces_mutation_bursts() {
+        let dir = tempfile::tempdir().expect("test fixture");
+        let brain = dir.path().join("debounce.amem");
+        let mut manager =
+            SessionManager::open(brain.to_str().expect("test fixture")).expect("test fixture");
+        // Save on every mutation were it not for the debounce.
+        manager.auto_save_interval = Duration::ZERO;
+        manager.save_debounce = Duration::from_millis(300);
+
+        let before = manager.save_generation;
+        for i in 0..100 {
+            manager
+                .add_event(EventType::Fact, &format!("burst fact {i}"), 0.8, vec![])
+                .expect("test fixture");
+        }
+        let writes = manager.save_generation - before;
+        assert!(writes <= 5, "100 mutations caused {writes} writes");
+
+        // Once the window passes, the next check flushes the burst.
+        std::thread::sleep(Duration::from_millis(350));
+        manager.maybe_auto_save().expect("test fixture");
+        assert!(!manager.dirty);
+        assert_eq!(
+            AmemReader::read_from_file(&brain)
+                .expect("test fixture")
+                .node_count(),
+            100
+        );
+
+        // An explicit save (as on shutdown) ignores the window.
+        manager
+            .add_event(EventType::Fact, "last words", 0.8, vec![])
+            .expect("test fixture");
+        assert!(manager.dirty);
+        manager.save().expect("test fixture");
+        assert!(!manager.dirty);
+    }
+
+    #[test]
+    fn verify_on_save_aborts_and_keeps_prior_file() {
+        let dir = tempfile::tempdir().expect("test fixture");
+        let brain = dir.path().join("verify.amem");
+        let path = brain.to_str().expect("test fixture");
+        let mut settings = SessionSettings::resolve(None);
+        settings.verify_on_save = true;
+        let mut manager = SessionManager::open_with_settings(path, settings).expect("test fixture");
+        let (id, _) = manager
+            .add_event(EventType::Fact, "consistent", 0.9, vec![])
+            .expect("test fixture");
+        manager.save().expect("test fixture");
+        let saved = std::fs::read(&brain).expect("test fixture");
+
+        // Retype behind the type index's back, as a buggy tool might.
+        manager
+            .graph_mut()
+            .get_node_mut(id)
+            .expect("test fixture")
+            .event_type = EventType::Decision;
+        manager.mark_dirty();
+        let err = manager
+            .save()
+            .expect_err("inconsistent graph must not be saved");
+        assert!(err.to_string().contains("type index"), "{err}");
+        assert!(manager.dirty);
+        assert_eq!(std::fs::read(&brain).expect("test fixture"), saved);
+        assert_eq!(
+            manager.graph().get_node(id).map(|n| n.event_type),
+            Some(EventType::Decision)
+        );
+    }
+
+    #[test]
+    fn reload_if_changed_picks_up_external_writes() {
+        let dir = tempfile::tempdir().expect("test fixture");
+        let brain = dir.path().join("reload.amem");
+        let path = brain.to_str().expect("test fixture");
+        let mut manager = SessionManager::open(path).expect("test fixture");
+        manager
+            .add_event(EventType::Fact, "first", 0.9, vec![])
+            .expect("test fixture");
+        manager.save().expect("test fixture");
+        assert!(!manager.reload_if_changed().expect("test fixture"));
+
+        let mut other = SessionManager::open(path).expect("test fixture");
+        other
+            .add_event(EventType::Fact, "written elsewhere", 0.9, vec![])
+            .expect("test fixture");
+        other.save().expect("test fixture");
+        // Coarse filesystem clocks may give both writes the same mtime.
+        manager.last_file_mtime = manager.last_file_mtime.map(|t| t - Duration::from_secs(1));
+
+        // Unsaved changes are left for save() to merge.
+        manager.mark_dirty();
+        assert!(!manager.reload_if_changed().expect("test fixture"));
+        manager.dirty = false;
+
+        assert!(manager.reload_if_changed().expect("test fixture"));
+        assert_eq!(manager.graph().node_count(), 2);
+        assert!(!manager.reload_if_changed().expect("test fixture"));
+    }
+
+    #[test]
+    fn forget_node_erases_content_but_keeps_node_and_edges() {
+        let dir = tempfile::tempdir().expect("test fixture");
+        let brain = dir.path().join("forget.amem");
+        let mut manager =
+            SessionManager::open(brain.to_str().expect("test fixture")).expect("test fixture");
+        let (cause, _) = manager
+            .add_event(
+                EventType::Fact,
+                "user lives at 42 zanzibar lane",
+                0.9,
+                vec![],
+            )
+            .expect("test fixture");
+        let (effect, _) = manager
+            .add_event(
+                EventType::Decision,
+                "ship to the home address",
+                0.9,
+                vec![(cause, EdgeType::CausedBy, 1.0)],
+            )
+            .expect("test fixture");
+        manager
+            .graph_mut()
+            .get_node_mut(cause)
+            .expect("test fixture")
+            .feature_vec
+            .iter_mut()
+            .for_each(|v| *v = 0.5);
+
+        let marker = manager.forget_node(cause).expect("test fixture");
+        assert!(marker.starts_with("[forgotten at "), "{marker}");
+        manager.save().expect("test fixture");
+
+        let bytes = std::fs::read(&brain).expect("test fixture");
+        assert!(!bytes.windows(8).any(|w| w == b"zanzibar"));
+        let graph = AmemReader::read_from_file(&brain).expect("test fixture");
+        let node = graph.get_node(cause).expect("forgotten node is kept");
+        assert_eq!(node.content, marker);
+        assert!(node.feature_vec.iter().all(|v| *v == 0.0));
+        assert!(graph
+            .edges_from(effect)
+            .iter()
+            .any(|e| e.target_id == cause && e.edge_type == EdgeType::CausedBy));
+        let index = graph.term_index().expect("test fixture");
+        assert!(index.get("zanzibar").is_empty());
+        assert!(graph.tombstones().is_empty());
+
+        assert!(matches!(
+            manager.forget_node(999),
+            Err(McpError::NodeNotFound(999))
+        ));
+    }
+
+    #[test]
+    fn incremental_backup_keeps_pinned_flag() {
+        let dir = tempfile::tempdir().expect("test fixture");
+        let brain = dir.path().join("pinned.amem");
+        let path = brain.to_str().expect("test fixture");
+        let mut settings = SessionSettings::resolve(None);
+        settings.backup_mode = BackupMode::Incremental;
+        let mut manager = SessionManager::open_with_settings(path, settings).expect("test fixture");
+        manager.backups_dir = dir.path().join("backups");
+        manager.backup_interval = Duration::ZERO;
+        let (id, _) = manager
+            .add_event(EventType::Fact, "keep me", 0.9, vec![])
+            .expect("test fixture");
+        manager.save().expect("test fixture");
+        manager.maybe_auto_backup().expect("test fixture");
+
+        // Pinning is the only change, so the delta must carry it alone.
+        manager
+            .graph_mut()
+            .get_node_mut(id)
+            .expect("test fixture")
+            .pinned = true;
+        manager.mark_dirty();
+        manager.save().expect("test fixture");
+        manager.maybe_auto_backup().expect("test fixture");
+
+        let points = backup::restore_points(&manager.backups_dir, &brain).expect("test fixture");
+        assert_eq!(points.len(), 2, "a base and one delta");
+        let restored = dir.path().join("restored.amem");
+        backup::restore_to(
+            &points[1],
+            &restored,
+            #[cfg(feature = "encryption")]
+            None,
+        )
+        .expect("test fixture");
+        let graph = AmemReader::read_from_file(&restored).expect("test fixture");
+        assert!(graph.get_node(id).expect("test fixture").pinned);
+    }
+
+    #[test]
+    fn restored_incremental_backup_keeps_tombstones_for_merge() {
+        let dir = tempfile::tempdir().expect("test fixture");
+        let brain = dir.path().join("restore.amem");
+        let path = brain.to_str().expect("test fixture");
+        let mut settings = SessionSettings::resolve(None);
+        settings.backup_mode = BackupMode::Incremental;
+        let mut manager = SessionManager::open_with_settings(path, settings).expect("test fixture");
+        manager.backups_dir = dir.path().join("backups");
+        manager.backup_interval = Duration::ZERO;
+        manager
+            .add_event(EventType::Fact, "keep me", 0.9, vec![])
+            .expect("test fixture");
+        let (doomed, _) = manager
+            .add_event(EventType::Fact, "delete me", 0.9, vec![])
+            .expect("test fixture");
+        manager.save().expect("test fixture");
+        manager.maybe_auto_backup().expect("test fixture");
+
+        // A second session still holds the node deleted after the base.
+        let mut stale = SessionManager::open(path).expect("test fixture");
+        manager
+            .graph_mut()
+            .remove_node(doomed)
+            .expect("test fixture");
+        manager.mark_dirty();
+        manager.save().expect("test fixture");
+        manager.maybe_auto_backup().expect("test fixture");
+
+        let points = backup::restore_points(&manager.backups_dir, &brain).expect("test fixture");
+        assert_eq!(points.len(), 2, "a base and one delta");
+        std::thread::sleep(Duration::from_millis(50));
+        backup::restore_to(
+            &points[1],
+            &brain,
+            #[cfg(feature = "encryption")]
+            None,
+        )
+        .expect("test fixture");
+        let restored = AmemReader::read_from_file(&brain).expect("test fixture");
+        assert_eq!(restored.tombstones().len(), 1);
+
+        // Merging the stale copy into the restored file must not bring it back.
+        std::thread::sleep(Duration::from_millis(50));
+        stale
+            .add_event(EventType::Fact, "added after restore", 0.9, vec![])
+            .expect("test fixture");
+        stale.save().expect("test fixture");
+        let on_disk = AmemReader::read_from_file(&brain).expect("test fixture");
+        let contents: Vec<&str> = on_disk.nodes().iter().map(|n| n.content.as_str()).collect();
+        assert!(
+            !contents.contains(&"delete me"),
+            "resurrected: {contents:?}"
+        );
+        assert!(contents.contains(&"added after restore"));
+    }
 }
@@ -1,12 +1,19 @@
 //! Session management: graph lifecycle, transactions, and auto-save.
 
 pub mod autosave;
+pub mod backup;
+pub mod idempotency;
 pub mod manager;
+pub mod settings;
+pub mod snapshot;
 #[cfg(feature = "sse")]
 pub mod tenant;
 pub mod transaction;
 pub mod workspace;
 
+pub use idempotency::IdempotencyKeys;
 pub use manager::SessionManager;
+pub use settings::SessionSettings;
+pub use snapshot::GraphSnapshot;
 pub use transaction::Transaction;
 pub use workspace::WorkspaceManager;
@@ -0,0 +1,602 @@
+//! Runtime settings for the session manager, resolved from the environment,
+//! an optional config file, and autonomic-profile defaults.
+
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use agentic_memory::DecayFunction;
+
+use crate::config::{ResolvedSetting, ServerConfig, SettingSource};
+
+/// Default auto-save interval.
+pub(crate) const DEFAULT_AUTO_SAVE_SECS: u64 = 30;
+/// Default window over which bursts of mutations are coalesced into one save.
+pub(crate) const DEFAULT_SAVE_DEBOUNCE_MS: u64 = 2000;
+/// Default backup interval.
+pub(crate) const DEFAULT_BACKUP_INTERVAL_SECS: u64 = 900;
+/// Default number of backups to retain per brain file.
+pub(crate) const DEFAULT_BACKUP_RETENTION: usize = 24;
+/// Default number of incremental deltas recorded before a new full base.
+pub(crate) const DEFAULT_BACKUP_FULL_EVERY: u32 = 12;
+/// Default maintenance sleep-cycle interval.
+pub(crate) const DEFAULT_SLEEP_CYCLE_SECS: u64 = 1800;
+/// Minimum completed-session size before auto-archive.
+pub(crate) const DEFAULT_ARCHIVE_MIN_SESSION_NODES: usize = 25;
+/// Default hot-tier threshold (decay score).
+pub(crate) const DEFAULT_HOT_MIN_DECAY: f32 = 0.7;
+/// Default warm-tier threshold (decay score).
+pub(crate) const DEFAULT_WARM_MIN_DECAY: f32 = 0.3;
+/// Default sustained mutation rate threshold before throttling heavy maintenance.
+pub(crate) const DEFAULT_SLA_MAX_MUTATIONS_PER_MIN: u32 = 240;
+/// Default interval for writing health-ledger snapshots.
+pub(crate) const DEFAULT_HEALTH_LEDGER_EMIT_SECS: u64 = 30;
+/// Default long-horizon storage budget target (2 GiB over 20 years).
+pub(crate) const DEFAULT_STORAGE_BUDGET_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+/// Default storage budget projection horizon.
+pub(crate) const DEFAULT_STORAGE_BUDGET_HORIZON_YEARS: u32 = 20;
+/// Default maximum chars persisted for one auto-captured prompt/feedback item.
+pub(crate) const DEFAULT_AUTO_CAPTURE_MAX_CHARS: usize = 2048;
+/// Default byte budget for one serialized tool result.
+pub(crate) const DEFAULT_TOOL_RESULT_MAX_BYTES: usize = 256 * 1024;
+/// Default number of days deletion tombstones are kept before pruning.
+pub(crate) const DEFAULT_TOMBSTONE_RETENTION_DAYS: u64 = 30;
+/// Default number of nodes decayed per maintenance tick (0 = whole graph at once).
+pub(crate) const DEFAULT_DECAY_CHUNK_SIZE: usize = 0;
+/// Default cap on nodes summarized by one episode (0 = one episode per session).
+pub(crate) const DEFAULT_MAX_EVENTS_PER_EPISODE: usize = 0;
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum AutonomicProfile {
+    Desktop,
+    Cloud,
+    Aggressive,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum StorageMigrationPolicy {
+    AutoSafe,
+    Strict,
+    Off,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum StorageBudgetMode {
+    AutoRollup,
+    Warn,
+    Off,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BackupMode {
+    /// Copy the whole brain file on every backup.
+    Full,
+    /// Record only changed nodes and edges on top of a periodic full base.
+    Incremental,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AutoCaptureMode {
+    /// Capture prompt-focused events and feedback context.
+    Safe,
+    /// Capture broader tool input text (except explicit memory_add payload duplication).
+    Full,
+    /// Disable automatic capture.
+    Off,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ProfileDefaults {
+    auto_save_secs: u64,
+    backup_secs: u64,
+    backup_retention: usize,
+    sleep_cycle_secs: u64,
+    sleep_idle_secs: u64,
+    archive_min_session_nodes: usize,
+    hot_min_decay: f32,
+    warm_min_decay: f32,
+    sla_max_mutations_per_min: u32,
+}
+
+impl AutonomicProfile {
+    fn parse(raw: &str) -> Self {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "cloud" => Self::Cloud,
+            "aggressive" => Self::Aggressive,
+            _ => Self::Desktop,
+        }
+    }
+
+    fn defaults(self) -> ProfileDefaults {
+        match self {
+            Self::Desktop => ProfileDefaults {
+                auto_save_secs: DEFAULT_AUTO_SAVE_SECS,
+                backup_secs: DEFAULT_BACKUP_INTERVAL_SECS,
+                backup_retention: DEFAULT_BACKUP_RETENTION,
+                sleep_cycle_secs: DEFAULT_SLEEP_CYCLE_SECS,
+                sleep_idle_secs: 180,
+                archive_min_session_nodes: DEFAULT_ARCHIVE_MIN_SESSION_NODES,
+                hot_min_decay: DEFAULT_HOT_MIN_DECAY,
+                warm_min_decay: DEFAULT_WARM_MIN_DECAY,
+                sla_max_mutations_per_min: DEFAULT_SLA_MAX_MUTATIONS_PER_MIN,
+            },
+            Self::Cloud => ProfileDefaults {
+                auto_save_secs: 15,
+                backup_secs: 600,
+                backup_retention: 48,
+                sleep_cycle_secs: 900,
+                sleep_idle_secs: 90,
+                archive_min_session_nodes: 50,
+                hot_min_decay: 0.75,
+                warm_min_decay: 0.4,
+                sla_max_mutations_per_min: 600,
+            },
+            Self::Aggressive => ProfileDefaults {
+                auto_save_secs: 10,
+                backup_secs: 300,
+                backup_retention: 16,
+                sleep_cycle_secs: 300,
+                sleep_idle_secs: 45,
+                archive_min_session_nodes: 15,
+                hot_min_decay: 0.8,
+                warm_min_decay: 0.5,
+                sla_max_mutations_per_min: 900,
+            },
+        }
+    }
+
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Desktop => "desktop",
+            Self::Cloud => "cloud",
+            Self::Aggressive => "aggressive",
+        }
+    }
+}
+
+impl StorageMigrationPolicy {
+    fn parse(raw: &str) -> Self {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "strict" => Self::Strict,
+            "off" | "disabled" | "none" => Self::Off,
+            _ => Self::AutoSafe,
+        }
+    }
+
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::AutoSafe => "auto-safe",
+            Self::Strict => "strict",
+            Self::Off => "off",
+        }
+    }
+}
+
+impl StorageBudgetMode {
+    fn parse(raw: &str) -> Self {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "warn" => Self::Warn,
+            "off" | "disabled" | "none" => Self::Off,
+            _ => Self::AutoRollup,
+        }
+    }
+
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::AutoRollup => "auto-rollup",
+            Self::Warn => "warn",
+            Self::Off => "off",
+        }
+    }
+}
+
+impl BackupMode {
+    fn parse(raw: &str) -> Self {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "incremental" | "delta" => Self::Incremental,
+            _ => Self::Full,
+        }
+    }
+
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Full => "full",
+            Self::Incremental => "incremental",
+        }
+    }
+}
+
+impl AutoCaptureMode {
+    fn parse(raw: &str) -> Self {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "full" => Self::Full,
+            "off" | "disabled" | "none" => Self::Off,
+            _ => Self::Safe,
+        }
+    }
+
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Safe => "safe",
+            Self::Full => "full",
+            Self::Off => "off",
+        }
+    }
+}
+
+/// Effective runtime settings for a [`SessionManager`](super::SessionManager).
+///
+/// Each value remembers where it came from so the resolved configuration can
+/// be dumped for diagnostics.
+#[derive(Debug, Clone)]
+pub struct SessionSettings {
+    pub(crate) profile: AutonomicProfile,
+    pub(crate) migration_policy: StorageMigrationPolicy,
+    pub(crate) auto_save_secs: u64,
+    pub(crate) save_debounce_ms: u64,
+    pub(crate) backup_secs: u64,
+    pub(crate) backup_retention: usize,
+    pub(crate) backup_mode: BackupMode,
+    pub(crate) backup_full_every: u32,
+    pub(crate) sleep_cycle_secs: u64,
+    pub(crate) sleep_idle_secs: u64,
+    pub(crate) archive_min_session_nodes: usize,
+    pub(crate) hot_min_decay: f32,
+    pub(crate) warm_min_decay: f32,
+    pub(crate) sla_max_mutations_per_min: u32,
+    pub(crate) health_ledger_emit_secs: u64,
+    pub(crate) storage_budget_mode: StorageBudgetMode,
+    pub(crate) storage_budget_max_bytes: u64,
+    pub(crate) storage_budget_horizon_years: u32,
+    pub(crate) storage_budget_target_fraction: f32,
+    pub(crate) auto_capture_mode: AutoCaptureMode,
+    pub(crate) auto_capture_redact: bool,
+    pub(crate) auto_capture_max_chars: usize,
+    pub(crate) term_bloom_fp_rate: f32,
+    pub(crate) tool_result_max_bytes: usize,
+    pub(crate) tombstone_retention_days: u64,
+    pub(crate) decay_chunk_size: usize,
+    pub(crate) max_events_per_episode: usize,
+    pub(crate) decay_function: Option<DecayFunction>,
+    pub(crate) detect_language: bool,
+    pub(crate) normalize_content: bool,
+    pub(crate) verify_on_save: bool,
+    /// Key the brain file is read and written with. Never listed in
+    /// [`entries`](Self::entries).
+    #[cfg(feature = "encryption")]
+    pub(crate) encryption_key: Option<agentic_memory::types::encryption::EncryptionKey>,
+    entries: Vec<ResolvedSetting>,
+}
+
+impl SessionSettings {
+    /// Resolve settings from the environment, falling back to the config file
+    /// (where it has a matching key) and then to the autonomic profile defaults.
+    pub fn resolve(file: Option<&ServerConfig>) -> Self {
+        let mut r = Resolver::default();
+
+        let profile = r.choice(
+            "AMEM_AUTONOMIC_PROFILE",
+            "desktop",
+            AutonomicProfile::parse,
+            AutonomicProfile::as_str,
+        );
+        let defaults = profile.defaults();
+        let migration_policy = r.choice(
+            "AMEM_STORAGE_MIGRATION_POLICY",
+            "auto-safe",
+            StorageMigrationPolicy::parse,
+            StorageMigrationPolicy::as_str,
+        );
+
+        let auto_save_fallback = match file.and_then(|f| f.auto_save_interval) {
+            Some(secs) => (secs, SettingSource::File),
+            None => (defaults.auto_save_secs, SettingSource::Default),
+        };
+        let auto_save_secs = r.number("AMEM_AUTOSAVE_SECS", auto_save_fallback, |v| v);
+        let save_debounce_ms = r.number(
+            "AMEM_SAVE_DEBOUNCE_MS",
+            dflt(DEFAULT_SAVE_DEBOUNCE_MS),
+            |v| v.min(60_000),
+        );
+        let backup_secs = r.number("AMEM_AUTO_BACKUP_SECS", dflt(defaults.backup_secs), |v| {
+            v.max(30)
+        });
+        let backup_retention = r.number(
+            "AMEM_AUTO_BACKUP_RETENTION",
+            dflt(defaults.backup_retention),
+            |v| v.max(1),
+        );
+        let backup_mode = r.choice(
+            "AMEM_BACKUP_MODE",
+            "full",
+            BackupMode::parse,
+            BackupMode::as_str,
+        );
+        let backup_full_every = r.number(
+            "AMEM_BACKUP_FULL_EVERY",
+            dflt(DEFAULT_BACKUP_FULL_EVERY),
+            |v| v.max(1),
+        );
+        let sleep_cycle_secs = r.number(
+            "AMEM_SLEEP_CYCLE_SECS",
+            dflt(defaults.sleep_cycle_secs),
+            |v| v.max(60),
+        );
+        let sleep_idle_secs = r.number(
+            "AMEM_SLEEP_IDLE_SECS",
+            dflt(defaults.sleep_idle_secs),
+            |v| v.max(30),
+        );
+        let archive_min_session_nodes = r.number(
+            "AMEM_ARCHIVE_MIN_SESSION_NODES",
+            dflt(defaults.archive_min_session_nodes),
+            |v| v.max(1),
+        );
+        let hot_min_decay = r.number(
+            "AMEM_TIER_HOT_MIN_DECAY",
+            dflt(defaults.hot_min_decay),
+            |v| v.clamp(0.0, 1.0),
+        );
+        let warm_min_decay = r.number(
+            "AMEM_TIER_WARM_MIN_DECAY",
+            dflt(defaults.warm_min_decay),
+            |v| v.clamp(0.0, 1.0).min(hot_min_decay),
+        );
+        let sla_max_mutations_per_min = r.number(
+            "AMEM_SLA_MAX_MUTATIONS_PER_MIN",
+            dflt(defaults.sla_max_mutations_per_min),
+            |v| v.max(1),
+        );
+        let health_ledger_emit_secs = r.number(
+            "AMEM_HEALTH_LEDGER_EMIT_SECS",
+            dflt(DEFAULT_HEALTH_LEDGER_EMIT_SECS),
+            |v| v.max(5),
+        );
+        let storage_budget_mode = r.choice(
+            "AMEM_STORAGE_BUDGET_MODE",
+            "auto-rollup",
+            StorageBudgetMode::parse,
+            StorageBudgetMode::as_str,
+        );
+        let storage_budget_max_bytes = r.number(
+            "AMEM_STORAGE_BUDGET_BYTES",
+            dflt(DEFAULT_STORAGE_BUDGET_BYTES),
+            |v| v.max(1),
+        );
+        let storage_budget_horizon_years = r.number(
+            "AMEM_STORAGE_BUDGET_HORIZON_YEARS",
+            dflt(DEFAULT_STORAGE_BUDGET_HORIZON_YEARS),
+            |v| v.max(1),
+        );
+        let storage_budget_target_fraction =
+            r.number("AMEM_STORAGE_BUDGET_TARGET_FRACTION", dflt(0.85f32), |v| {
+                v.clamp(0.50, 0.99)
+            });
+        let auto_capture_mode = r.choice(
+            "AMEM_AUTO_CAPTURE_MODE",
+            "safe",
+            AutoCaptureMode::parse,
+            AutoCaptureMode::as_str,
+        );
+        let auto_capture_redact = r.boolean("AMEM_AUTO_CAPTURE_REDACT", true);
+        let auto_capture_max_chars = r.number(
+            "AMEM_AUTO_CAPTURE_MAX_CHARS",
+            dflt(DEFAULT_AUTO_CAPTURE_MAX_CHARS),
+            |v| v.clamp(256, 16384),
+        );
+        let term_bloom_fp_rate = r.number("AMEM_TERM_BLOOM_FP_RATE", dflt(0.0f32), |v| {
+            v.clamp(0.0, 0.5)
+        });
+        let tool_result_fallback = match file.and_then(|f| f.max_response_bytes) {
+            Some(bytes) => (bytes, SettingSource::File),
+            None => (DEFAULT_TOOL_RESULT_MAX_BYTES, SettingSource::Default),
+        };
+        // 0 disables the cap; anything else gets room for at least a small result.
+        let tool_result_max_bytes =
+            r.number("AMEM_TOOL_RESULT_MAX_BYTES", tool_result_fallback, |v| {
+                if v == 0 {
+                    0
+                } else {
+                    v.max(1024)
+                }
+            });
+
+        let tombstone_retention_days = r.number(
+            "AMEM_TOMBSTONE_RETENTION_DAYS",
+            dflt(DEFAULT_TOMBSTONE_RETENTION_DAYS),
+            |v| v.max(1),
+        );
+        let decay_chunk_size = r.number(
+            "AMEM_DECAY_CHUNK_SIZE",
+            dflt(DEFAULT_DECAY_CHUNK_SIZE),
+            |v| v,
+        );
+        let max_events_per_episode = r.number(
+            "AMEM_MAX_EVENTS_PER_EPISODE",
+            dflt(DEFAULT_MAX_EVENTS_PER_EPISODE),
+            |v| v,
+        );
+        let decay_function = r.decay_function("AMEM_DECAY_FUNCTION");
+        let detect_language = r.boolean("AMEM_DETECT_LANGUAGE", false);
+        let normalize_content = r.boolean("AMEM_NORMALIZE_CONTENT", false);
+        let verify_on_save = r.boolean("AMEM_VERIFY_ON_SAVE", false);
+        #[cfg(feature = "encryption")]
+        let encryption_key = read_env_string("AMEM_ENCRYPTION_KEY")
+            .filter(|v| !v.is_empty())
+            .map(|v| agentic_memory::types::encryption::EncryptionKey::from_secret(&v));
+
+        Self {
+            profile,
+            migration_policy,
+            auto_save_secs,
+            save_debounce_ms,
+            backup_secs,
+            backup_retention,
+            backup_mode,
+            backup_full_every,
+            sleep_cycle_secs,
+            sleep_idle_secs,
+            archive_min_session_nodes,
+            hot_min_decay,
+            warm_min_decay,
+            sla_max_mutations_per_min,
+            health_ledger_emit_secs,
+            storage_budget_mode,
+            storage_budget_max_bytes,
+            storage_budget_horizon_years,
+            storage_budget_target_fraction,
+            auto_capture_mode,
+            auto_capture_redact,
+            auto_capture_max_chars,
+            term_bloom_fp_rate,
+            tool_result_max_bytes,
+            tombstone_retention_days,
+            decay_chunk_size,
+            max_events_per_episode,
+            decay_function,
+            detect_language,
+            normalize_content,
+            verify_on_save,
+            #[cfg(feature = "encryption")]
+            encryption_key,
+            entries: r.entries,
+        }
+    }
+
+    /// Turn off automatic migration of legacy files (the `--no-auto-migrate`
+    /// flag). A `strict` policy is kept: it already refuses to migrate.
+    pub fn without_auto_migrate(mut self) -> Self {
+        if self.migration_policy == StorageMigrationPolicy::AutoSafe {
+            self.migration_policy = StorageMigrationPolicy::Off;
+            let policy = StorageMigrationPolicy::Off.as_str();
+            if let Some(entry) = self
+                .entries
+                .iter_mut()
+                .find(|e| e.key == "AMEM_STORAGE_MIGRATION_POLICY")
+            {
+                *entry = ResolvedSetting::new(
+                    "AMEM_STORAGE_MIGRATION_POLICY",
+                    policy,
+                    SettingSource::Flag,
+                );
+            }
+        }
+        self
+    }
+
+    /// Read and write the brain file encrypted with `key`, overriding
+    /// `AMEM_ENCRYPTION_KEY`.
+    #[cfg(feature = "encryption")]
+    pub fn with_encryption_key(
+        mut self,
+        key: agentic_memory::types::encryption::EncryptionKey,
+    ) -> Self {
+        self.encryption_key = Some(key);
+        self
+    }
+
+    /// Key the brain file is read and written with, if any.
+    #[cfg(feature = "encryption")]
+    pub fn encryption_key(&self) -> Option<&agentic_memory::types::encryption::EncryptionKey> {
+        self.encryption_key.as_ref()
+    }
+
+    /// Resolved settings with their sources, in resolution order.
+    pub fn entries(&self) -> &[ResolvedSetting] {
+        &self.entries
+    }
+
+    /// Backup directory for the given brain file, with its source.
+    pub fn backups_dir(&self, memory_path: &Path) -> (PathBuf, SettingSource) {
+        resolve_backups_dir(memory_path)
+    }
+}
+
+fn dflt<T>(value: T) -> (T, SettingSource) {
+    (value, SettingSource::Default)
+}
+
+/// Reads environment variables while recording the effective value and source.
+#[derive(Default)]
+struct Resolver {
+    entries: Vec<ResolvedSetting>,
+}
+
+impl Resolver {
+    fn number<T>(
+        &mut self,
+        name: &'static str,
+        fallback: (T, SettingSource),
+        adjust: impl FnOnce(T) -> T,
+    ) -> T
+    where
+        T: FromStr + Display + Copy,
+    {
+        let (value, source) = match std::env::var(name).ok().and_then(|v| v.parse::<T>().ok()) {
+            Some(v) => (v, SettingSource::Env),
+            None => fallback,
+        };
+        let value = adjust(value);
+        self.entries.push(ResolvedSetting::new(name, value, source));
+        value
+    }
+
+    fn boolean(&mut self, name: &'static str, default_value: bool) -> bool {
+        let (value, source) = match std::env::var(name) {
+            Ok(v) => (
+                matches!(
+                    v.trim().to_ascii_lowercase().as_str(),
+                    "1" | "true" | "yes" | "on"
+                ),
+                SettingSource::Env,
+            ),
+            Err(_) => (default_value, SettingSource::Default),
+        };
+        self.entries.push(ResolvedSetting::new(name, value, source));
+        value
+    }
+
+    /// A [`DecayFunction`]; unset or unparsable keeps the built-in formula.
+    fn decay_function(&mut self, name: &'static str) -> Option<DecayFunction> {
+        let value = read_env_string(name).and_then(|v| DecayFunction::parse(&v));
+        let entry = match value {
+            Some(function) => ResolvedSetting::new(name, function, SettingSource::Env),
+            None => ResolvedSetting::new(name, "default", SettingSource::Default),
+        };
+        self.entries.push(entry);
+        value
+    }
+
+    fn choice<T: Copy>(
+        &mut self,
+        name: &'static str,
+        default_raw: &str,
+        parse: fn(&str) -> T,
+        label: fn(T) -> &'static str,
+    ) -> T {
+        let (raw, source) = match read_env_string(name) {
+            Some(v) => (v, SettingSource::Env),
+            None => (default_raw.to_string(), SettingSource::Default),
+        };
+        let value = parse(&raw);
+        self.entries
+            .push(ResolvedSetting::new(name, label(value), source));
+        value
+    }
+}
+
+pub(crate) fn resolve_backups_dir(memory_path: &Path) -> (PathBuf, SettingSource) {
+    if let Ok(custom) = std::env::var("AMEM_AUTO_BACKUP_DIR") {
+        let trimmed = custom.trim();
+        if !trimmed.is_empty() {
+            return (PathBuf::from(trimmed), SettingSource::Env);
+        }
+    }
+
+    let parent = memory_path.parent().unwrap_or(Path::new("."));
+    (parent.join(".amem-backups"), SettingSource::Default)
+}
+
+pub(crate) fn read_env_string(name: &str) -> Option<String> {
+    std::env::var(name).ok().map(|v| v.trim().to_string())
+}
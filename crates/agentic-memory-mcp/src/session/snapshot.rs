@@ -0,0 +1,48 @@
+//! Read snapshots of the memory graph for long-running queries.
+//!
+//! A snapshot is a shared, immutable copy of the graph stamped with the
+//! generation it was taken at. Read tools take one under a brief session
+//! lock, release the lock, and run against the snapshot, so writers are not
+//! blocked for the length of a traversal.
+//!
+//! Isolation level: snapshot isolation per tool call. A query sees every
+//! write that completed before its snapshot was taken and none made after;
+//! it never sees a half-applied write. Snapshots are not refreshed during a
+//! call, so results may be stale by the time they are returned.
+//!
+//! Taking a snapshot copies nothing: it shares the session's graph. The
+//! session copies the graph only when it writes while a snapshot is still
+//! held, so a write after the readers are done costs no copy at all.
+
+use std::sync::Arc;
+
+use agentic_memory::MemoryGraph;
+
+/// Immutable view of the graph as of one generation.
+#[derive(Clone)]
+pub struct GraphSnapshot {
+    generation: u64,
+    graph: Arc<MemoryGraph>,
+}
+
+impl GraphSnapshot {
+    /// Share the graph as of `generation`.
+    pub fn new(generation: u64, graph: Arc<MemoryGraph>) -> Self {
+        Self { generation, graph }
+    }
+
+    /// Generation of the graph this snapshot was taken from.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// The snapshotted graph.
+    pub fn graph(&self) -> &MemoryGraph {
+        &self.graph
+    }
+
+    /// Whether `other` shares this snapshot's copy of the graph.
+    pub fn shares_graph_with(&self, other: &GraphSnapshot) -> bool {
+        Arc::ptr_eq(&self.graph, &other.graph)
+    }
+}
@@ -5,15 +5,34 @@ use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
 
 use super::autosave::spawn_maintenance;
 use super::SessionManager;
 use crate::types::{McpResult, MemoryMode};
 
+/// Default cap on simultaneously open tenant sessions.
+pub const DEFAULT_MAX_OPEN_TENANTS: usize = 256;
+
+/// An open tenant session and its background maintenance task.
+struct TenantEntry {
+    session: Arc<Mutex<SessionManager>>,
+    maintenance: JoinHandle<()>,
+    /// Registry access tick of the most recent use, for LRU eviction.
+    last_used: u64,
+}
+
 /// Registry of per-user sessions for multi-tenant mode.
+///
+/// At most `max_open` sessions are kept open. Opening one more evicts the
+/// least recently used tenant, saving its session first; the next request
+/// for that tenant re-opens it from disk.
 pub struct TenantRegistry {
     data_dir: PathBuf,
-    sessions: HashMap<String, Arc<Mutex<SessionManager>>>,
+    sessions: HashMap<String, TenantEntry>,
+    /// Maximum open sessions (0 = unlimited).
+    max_open: usize,
+    tick: u64,
 }
 
 impl TenantRegistry {
@@ -22,19 +41,29 @@ impl TenantRegistry {
         Self {
             data_dir: data_dir.to_path_buf(),
             sessions: HashMap::new(),
+            max_open: DEFAULT_MAX_OPEN_TENANTS,
+            tick: 0,
         }
     }
 
+    /// Set the maximum number of open tenant sessions (0 = unlimited).
+    pub fn with_max_open(mut self, max_open: usize) -> Self {
+        self.max_open = max_open;
+        self
+    }
+
     /// Get or create a session for the given user ID.
     ///
     /// On first access, creates `{data_dir}/{user_id}.amem` and opens a session.
-    pub fn get_or_create(
+    pub async fn get_or_create(
         &mut self,
         user_id: &str,
         memory_mode: MemoryMode,
     ) -> McpResult<Arc<Mutex<SessionManager>>> {
-        if let Some(session) = self.sessions.get(user_id) {
-            return Ok(session.clone());
+        self.tick += 1;
+        if let Some(entry) = self.sessions.get_mut(user_id) {
+            entry.last_used = self.tick;
+            return Ok(entry.session.clone());
         }
 
         // Ensure data directory exists
@@ -45,6 +74,14 @@ impl TenantRegistry {
             ))
         })?;
 
+        if self.max_open > 0 {
+            while self.sessions.len() >= self.max_open {
+                if !self.evict_lru().await {
+                    break;
+                }
+            }
+        }
+
         let brain_path = self.data_dir.join(format!("{user_id}.amem"));
         let path_str = brain_path.display().to_string();
 
@@ -54,14 +91,67 @@ impl TenantRegistry {
         session.apply_memory_mode(memory_mode);
         let maintenance_interval = session.maintenance_interval();
         let session = Arc::new(Mutex::new(session));
-        let _maintenance_task = spawn_maintenance(session.clone(), maintenance_interval);
-        self.sessions.insert(user_id.to_string(), session.clone());
+        let maintenance = spawn_maintenance(session.clone(), maintenance_interval);
+        self.sessions.insert(
+            user_id.to_string(),
+            TenantEntry {
+                session: session.clone(),
+                maintenance,
+                last_used: self.tick,
+            },
+        );
 
         Ok(session)
     }
 
+    /// Save and close the least recently used tenant session. Returns `false`
+    /// if nothing could be evicted.
+    ///
+    /// The session is flushed while holding its lock, so a request already
+    /// holding it finishes first; anything it writes afterwards is saved when
+    /// its last handle drops. A save failure keeps the tenant open.
+    async fn evict_lru(&mut self) -> bool {
+        let mut candidates: Vec<(u64, String)> = self
+            .sessions
+            .iter()
+            .map(|(user_id, entry)| (entry.last_used, user_id.clone()))
+            .collect();
+        candidates.sort();
+
+        for (_, user_id) in candidates {
+            let Some(entry) = self.sessions.remove(&user_id) else {
+                continue;
+            };
+            let saved = entry.session.lock().await.save();
+            match saved {
+                Ok(()) => {
+                    entry.maintenance.abort();
+                    tracing::info!("Evicted brain for user '{user_id}'");
+                    return true;
+                }
+                Err(e) => {
+                    tracing::error!(
+                        "Failed to save brain for user '{user_id}', keeping it open: {e}"
+                    );
+                    self.sessions.insert(user_id, entry);
+                }
+            }
+        }
+        false
+    }
+
     /// Number of active tenant sessions.
     pub fn count(&self) -> usize {
         self.sessions.len()
     }
+
+    /// Maximum number of open tenant sessions (0 = unlimited).
+    pub fn max_open(&self) -> usize {
+        self.max_open
+    }
+
+    /// Whether a session for `user_id` is currently open.
+    pub fn is_open(&self, user_id: &str) -> bool {
+        self.sessions.contains_key(user_id)
+    }
 }
@@ -4,8 +4,9 @@ use std::collections::HashMap;
 use std::path::Path;
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use agentic_memory::{AmemReader, MemoryGraph, QueryEngine, TextSearchParams};
+use agentic_memory::{MemoryGraph, QueryEngine, TextSearchParams};
 
+use super::manager::read_memory_file;
 use crate::types::{McpError, McpResult};
 
 /// Role of a context within a workspace.
@@ -95,6 +96,9 @@ pub struct CrossReference {
 pub struct WorkspaceManager {
     workspaces: HashMap<String, MemoryWorkspace>,
     next_id: u64,
+    /// Key encrypted context files are decrypted with.
+    #[cfg(feature = "encryption")]
+    encryption_key: Option<agentic_memory::types::encryption::EncryptionKey>,
 }
 
 impl WorkspaceManager {
@@ -102,9 +106,21 @@ impl WorkspaceManager {
         Self {
             workspaces: HashMap::new(),
             next_id: 1,
+            #[cfg(feature = "encryption")]
+            encryption_key: None,
         }
     }
 
+    /// Decrypt encrypted context files with `key`.
+    #[cfg(feature = "encryption")]
+    pub fn with_encryption_key(
+        mut self,
+        key: Option<agentic_memory::types::encryption::EncryptionKey>,
+    ) -> Self {
+        self.encryption_key = key;
+        self
+    }
+
     /// Create a new workspace.
     pub fn create(&mut self, name: &str) -> String {
         let id = format!("ws_{}", self.next_id);
@@ -133,6 +149,19 @@ impl WorkspaceManager {
         path: &str,
         role: ContextRole,
         label: Option<String>,
+    ) -> McpResult<String> {
+        self.add_context_checked(workspace_id, path, role, label, false)
+    }
+
+    /// Add a context, checking its embedding model against the contexts
+    /// already loaded. A mismatch is logged, or rejected when `strict`.
+    pub fn add_context_checked(
+        &mut self,
+        workspace_id: &str,
+        path: &str,
+        role: ContextRole,
+        label: Option<String>,
+        strict: bool,
     ) -> McpResult<String> {
         let workspace = self.workspaces.get_mut(workspace_id).ok_or_else(|| {
             McpError::InvalidParams(format!("Workspace not found: {workspace_id}"))
@@ -144,8 +173,30 @@ impl WorkspaceManager {
             return Err(McpError::InvalidParams(format!("File not found: {path}")));
         }
 
-        let graph = AmemReader::read_from_file(file_path)
-            .map_err(|e| McpError::AgenticMemory(format!("Failed to parse {path}: {e}")))?;
+        let graph = read_memory_file(
+            file_path,
+            #[cfg(feature = "encryption")]
+            self.encryption_key.as_ref(),
+        )
+        .map_err(|e| McpError::AgenticMemory(format!("Failed to parse {path}: {e}")))?;
+
+        if let Some(model) = graph.embedding_model() {
+            let conflict = workspace.contexts.iter().find_map(|ctx| {
+                ctx.graph
+                    .embedding_model()
+                    .filter(|other| *other != model)
+                    .map(|other| (ctx.id.clone(), other.to_string()))
+            });
+            if let Some((other_ctx, other_model)) = conflict {
+                let msg = format!(
+                    "Embedding model mismatch: {path} uses {model:?}, {other_ctx} uses {other_model:?}"
+                );
+                if strict {
+                    return Err(McpError::InvalidParams(msg));
+                }
+                tracing::warn!("{msg}");
+            }
+        }
 
         let ctx_id = format!("ctx_{}_{}", workspace.contexts.len() + 1, workspace_id);
 
@@ -166,6 +217,31 @@ impl WorkspaceManager {
         Ok(ctx_id)
     }
 
+    /// Describe contexts whose embedding model differs from the first context
+    /// that records one. Vectors from different models are not comparable.
+    pub fn embedding_model_warnings(&self, workspace_id: &str) -> McpResult<Vec<String>> {
+        let workspace = self.workspaces.get(workspace_id).ok_or_else(|| {
+            McpError::InvalidParams(format!("Workspace not found: {workspace_id}"))
+        })?;
+
+        let mut reference: Option<(&str, &str)> = None;
+        let mut warnings = Vec::new();
+        for ctx in &workspace.contexts {
+            let Some(model) = ctx.graph.embedding_model() else {
+                continue;
+            };
+            match reference {
+                None => reference = Some((ctx.id.as_str(), model)),
+                Some((ref_ctx, ref_model)) if ref_model != model => warnings.push(format!(
+                    "{} uses embedding model {model:?}, {ref_ctx} uses {ref_model:?}",
+                    ctx.id
+                )),
+                Some(_) => {}
+            }
+        }
+        Ok(warnings)
+    }
+
     /// List contexts in a workspace.
     pub fn list(&self, workspace_id: &str) -> McpResult<&[MemoryContext]> {
         let workspace = self.workspaces.get(workspace_id).ok_or_else(|| {
@@ -205,6 +281,7 @@ impl WorkspaceManager {
                         event_types: Vec::new(),
                         session_ids: Vec::new(),
                         min_score: 0.0,
+                        confidence_weight: 0.0,
                     },
                 )
                 .unwrap_or_default();
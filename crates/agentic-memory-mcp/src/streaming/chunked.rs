@@ -1,8 +1,90 @@
 //! Chunked result streaming for large traversals.
+//!
+//! Clients that declare the `streamingResults` experimental capability get
+//! large array results (e.g. the `nodes` of a `memory_query`) as a series of
+//! `notifications/tools/resultChunk` messages, one JSON line each, followed by
+//! the normal `tools/call` response with the array emptied and a `streamed`
+//! summary attached. Clients reassemble the array by concatenating the chunk
+//! `items` in `index` order.
 
-use serde_json::Value;
+use serde_json::{json, Value};
+
+use crate::types::{JsonRpcNotification, RequestId, ToolCallResult, ToolContent};
+
+/// Experimental capability key clients use to opt in to streamed results.
+pub const STREAMING_CAPABILITY: &str = "streamingResults";
+
+/// Notification method carrying one chunk of a streamed result.
+pub const RESULT_CHUNK_METHOD: &str = "notifications/tools/resultChunk";
+
+/// Items per chunk when the client does not ask for a size.
+pub const DEFAULT_CHUNK_SIZE: usize = 50;
 
 /// Split a large result set into chunks for streaming.
 pub fn chunk_results(results: Vec<Value>, chunk_size: usize) -> Vec<Vec<Value>> {
     results.chunks(chunk_size).map(|c| c.to_vec()).collect()
 }
+
+/// Pull the largest top-level array out of a JSON tool result and turn it
+/// into chunk notifications tied to `request_id`.
+///
+/// Returns an empty list, leaving `result` untouched, when the result is an
+/// error, is not a JSON object, or has no array longer than `chunk_size`.
+/// Otherwise the array is replaced by `[]` in `result` and a
+/// `"streamed": {"field", "chunks", "count"}` summary is added.
+pub fn stream_tool_result(
+    request_id: &RequestId,
+    result: &mut ToolCallResult,
+    chunk_size: usize,
+) -> Vec<JsonRpcNotification> {
+    let chunk_size = chunk_size.max(1);
+    if result.is_error == Some(true) || result.content.len() != 1 {
+        return Vec::new();
+    }
+    let ToolContent::Text { text } = &result.content[0] else {
+        return Vec::new();
+    };
+    let Ok(Value::Object(mut map)) = serde_json::from_str::<Value>(text) else {
+        return Vec::new();
+    };
+
+    let field = map
+        .iter()
+        .filter_map(|(key, value)| value.as_array().map(|items| (key, items.len())))
+        .filter(|(_, len)| *len > chunk_size)
+        .max_by_key(|(_, len)| *len)
+        .map(|(key, _)| key.clone());
+    let Some(field) = field else {
+        return Vec::new();
+    };
+    let Some(Value::Array(items)) = map.insert(field.clone(), Value::Array(Vec::new())) else {
+        return Vec::new();
+    };
+
+    let count = items.len();
+    let chunks = chunk_results(items, chunk_size);
+    let total = chunks.len();
+    let notifications = chunks
+        .into_iter()
+        .enumerate()
+        .map(|(index, items)| {
+            JsonRpcNotification::new(
+                RESULT_CHUNK_METHOD.to_string(),
+                Some(json!({
+                    "requestId": request_id,
+                    "field": field,
+                    "index": index,
+                    "total": total,
+                    "items": items,
+                })),
+            )
+        })
+        .collect();
+
+    map.insert(
+        "streamed".to_string(),
+        json!({ "field": field, "chunks": total, "count": count }),
+    );
+    *result = ToolCallResult::json(&Value::Object(map));
+    notifications
+}
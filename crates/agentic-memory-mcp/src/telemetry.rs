@@ -0,0 +1,66 @@
+//! OTLP export of tool-call spans (`otel` feature).
+//!
+//! Every `tools/call` runs in a `tool_call` span carrying the tool name, the
+//! tenant in multi-tenant mode, and whether the call failed. With an OTLP
+//! endpoint configured these spans are exported, so memory-server latency
+//! shows up in the calling agent's distributed traces.
+
+use opentelemetry::trace::{TraceError, TracerProvider as _};
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::trace::{Config, Tracer, TracerProvider};
+use opentelemetry_sdk::{runtime, Resource};
+use tracing::Level;
+use tracing_subscriber::filter::filter_fn;
+use tracing_subscriber::{Layer, Registry};
+
+/// Service name reported to the collector.
+pub const SERVICE_NAME: &str = "agentic-memory-mcp";
+
+/// Flushes pending spans and stops the exporter when dropped.
+pub struct OtlpGuard {
+    provider: TracerProvider,
+}
+
+impl Drop for OtlpGuard {
+    fn drop(&mut self) {
+        if let Err(e) = self.provider.shutdown() {
+            eprintln!("Warning: OTLP exporter shutdown failed: {e}");
+        }
+    }
+}
+
+/// Layer exporting this crate's spans to the OTLP/gRPC collector at
+/// `endpoint`, e.g. `http://localhost:4317`. Keep the guard alive for as
+/// long as spans should be exported.
+pub fn otlp_layer(
+    endpoint: &str,
+) -> Result<(impl Layer<Registry> + Send + Sync, OtlpGuard), TraceError> {
+    let provider = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(endpoint),
+        )
+        .with_trace_config(
+            Config::default()
+                .with_resource(Resource::new([KeyValue::new("service.name", SERVICE_NAME)])),
+        )
+        .install_batch(runtime::Tokio)?;
+    let layer = span_layer(provider.tracer(SERVICE_NAME));
+    Ok((layer, OtlpGuard { provider }))
+}
+
+/// Layer turning this crate's info-level spans into spans of `tracer`.
+/// Log events are left out, since their messages may quote content. It is
+/// filtered on its own, so a quiet `--log-level` does not stop span export.
+pub fn span_layer(tracer: Tracer) -> impl Layer<Registry> + Send + Sync {
+    tracing_opentelemetry::layer()
+        .with_tracer(tracer)
+        .with_filter(filter_fn(|meta| {
+            meta.is_span()
+                && meta.target().starts_with("agentic_memory_mcp")
+                && *meta.level() <= Level::INFO
+        }))
+}
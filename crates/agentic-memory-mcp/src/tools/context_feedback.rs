@@ -0,0 +1,143 @@
+//! Tool: context_feedback — Re-query using relevance feedback on earlier results.
+
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use agentic_memory::RelevanceFeedbackParams;
+
+use crate::session::SessionManager;
+use crate::types::{McpError, McpResult, ToolCallResult, ToolDefinition};
+
+#[derive(Debug, Deserialize)]
+struct FeedbackParams {
+    #[serde(default)]
+    query: String,
+    #[serde(default)]
+    useful_ids: Vec<u64>,
+    #[serde(default)]
+    not_useful_ids: Vec<u64>,
+    #[serde(default = "default_max")]
+    max_results: usize,
+}
+
+fn default_max() -> usize {
+    10
+}
+
+/// Return the tool definition for context_feedback.
+pub fn definition() -> ToolDefinition {
+    ToolDefinition {
+        name: "context_feedback".to_string(),
+        description: Some(
+            "Refine a text query with feedback on earlier results: terms from useful nodes \
+             are boosted, terms from not-useful nodes are down-weighted, and the refined \
+             query is run. Returns the results and the weighted query terms"
+                .to_string(),
+        ),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "The query the earlier results came from"
+                },
+                "useful_ids": {
+                    "type": "array",
+                    "items": { "type": "integer" },
+                    "description": "Node IDs of earlier results that were useful"
+                },
+                "not_useful_ids": {
+                    "type": "array",
+                    "items": { "type": "integer" },
+                    "description": "Node IDs of earlier results that were not useful"
+                },
+                "max_results": {
+                    "type": "integer",
+                    "default": 10,
+                    "description": "Maximum number of results to return"
+                }
+            }
+        }),
+    }
+}
+
+/// Execute the context_feedback tool.
+pub async fn execute(
+    args: Value,
+    session: &Arc<Mutex<SessionManager>>,
+) -> McpResult<ToolCallResult> {
+    let params: FeedbackParams =
+        serde_json::from_value(args).map_err(|e| McpError::InvalidParams(e.to_string()))?;
+
+    if params.query.trim().is_empty() && params.useful_ids.is_empty() {
+        return Err(McpError::InvalidParams(
+            "context_feedback needs a query or at least one useful_id".to_string(),
+        ));
+    }
+
+    let session = session.lock().await;
+    let graph = session.graph();
+
+    let unknown_ids: Vec<u64> = params
+        .useful_ids
+        .iter()
+        .chain(&params.not_useful_ids)
+        .copied()
+        .filter(|&id| graph.get_node(id).is_none())
+        .collect();
+
+    let refined = session
+        .query_engine()
+        .relevance_feedback_search(
+            graph,
+            graph.term_index.as_ref(),
+            graph.doc_lengths.as_ref(),
+            RelevanceFeedbackParams {
+                query: params.query.clone(),
+                useful_ids: params.useful_ids,
+                not_useful_ids: params.not_useful_ids,
+                max_results: params.max_results,
+            },
+        )
+        .map_err(|e| McpError::AgenticMemory(format!("Feedback search failed: {e}")))?;
+
+    let terms: Vec<Value> = refined
+        .terms
+        .iter()
+        .map(|t| {
+            json!({
+                "term": t.term,
+                "weight": t.weight,
+                "from_query": t.from_query,
+            })
+        })
+        .collect();
+
+    let results: Vec<Value> = refined
+        .matches
+        .iter()
+        .filter_map(|m| {
+            graph.get_node(m.node_id).map(|node| {
+                json!({
+                    "node_id": node.id,
+                    "event_type": node.event_type.name(),
+                    "content": node.content,
+                    "confidence": node.confidence,
+                    "score": m.score,
+                    "matched_terms": m.matched_terms,
+                })
+            })
+        })
+        .collect();
+
+    Ok(ToolCallResult::json(&json!({
+        "query": params.query,
+        "refined_terms": terms,
+        "unknown_ids": unknown_ids,
+        "count": results.len(),
+        "results": results
+    })))
+}
@@ -115,12 +115,12 @@ pub async fn execute_immortal_prove(
     let outgoing: Vec<Value> = graph
         .edges_from(node_id)
         .iter()
-        .map(|e| json!({"target":e.target_id,"type":e.edge_type.name(),"weight":e.weight}))
+        .map(|e| json!({"target":e.target_id,"type":graph.edge_type_name(e.edge_type),"weight":e.weight}))
         .collect();
     let incoming: Vec<Value> = graph
         .edges_to(node_id)
         .iter()
-        .map(|e| json!({"source":e.source_id,"type":e.edge_type.name(),"weight":e.weight}))
+        .map(|e| json!({"source":e.source_id,"type":graph.edge_type_name(e.edge_type),"weight":e.weight}))
         .collect();
     let support_count = incoming.iter().filter(|e| e["type"] == "supports").count()
         + outgoing.iter().filter(|e| e["type"] == "supports").count();
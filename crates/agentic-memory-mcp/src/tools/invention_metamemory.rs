@@ -592,7 +592,7 @@ pub async fn execute_dream_insights(
     let mut edge_types: HashMap<String, usize> = HashMap::new();
     for edge in graph.edges() {
         *edge_types
-            .entry(edge.edge_type.name().to_string())
+            .entry(graph.edge_type_name(edge.edge_type).to_string())
             .or_insert(0) += 1;
     }
     for (etype, count) in &edge_types {
@@ -117,7 +117,7 @@ pub async fn execute_archaeology_dig(
                     related.push(json!({
                         "artifact_type": "RelatedTrace",
                         "node_id": target.id,
-                        "edge_type": edge.edge_type.name(),
+                        "edge_type": graph.edge_type_name(edge.edge_type),
                         "distance": depth + 1,
                         "content_preview": &target.content[..target.content.len().min(80)],
                     }));
@@ -476,7 +476,7 @@ pub async fn execute_holographic_reconstruct(
         if let Some(target) = graph.get_node(edge.target_id) {
             shards.push(json!({
                 "shard_type": "outgoing",
-                "edge_type": edge.edge_type.name(),
+                "edge_type": graph.edge_type_name(edge.edge_type),
                 "node_id": target.id,
                 "content_preview": &target.content[..target.content.len().min(80)],
             }));
@@ -486,7 +486,7 @@ pub async fn execute_holographic_reconstruct(
         if let Some(source) = graph.get_node(edge.source_id) {
             shards.push(json!({
                 "shard_type": "incoming",
-                "edge_type": edge.edge_type.name(),
+                "edge_type": graph.edge_type_name(edge.edge_type),
                 "node_id": source.id,
                 "content_preview": &source.content[..source.content.len().min(80)],
             }));
@@ -987,7 +987,7 @@ pub async fn execute_phoenix_gather(
                 "trace_type": "EdgeRecord",
                 "source_id": edge.source_id,
                 "target_id": edge.target_id,
-                "edge_type": edge.edge_type.name(),
+                "edge_type": graph.edge_type_name(edge.edge_type),
                 "weight": edge.weight,
             }));
         }
@@ -20,6 +20,10 @@ struct AddParams {
     confidence: f32,
     #[serde(default)]
     edges: Vec<EdgeParam>,
+    #[serde(default)]
+    idempotency_key: Option<String>,
+    #[serde(default)]
+    pinned: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -70,12 +74,21 @@ pub fn definition() -> ToolDefinition {
                             "target_id": { "type": "integer" },
                             "edge_type": {
                                 "type": "string",
-                                "enum": ["caused_by", "derived_from", "supports", "contradicts", "supersedes", "related_to", "part_of", "temporal_next"]
+                                "description": "caused_by, derived_from, supports, contradicts, supersedes, related_to, part_of, temporal_next, or a custom relationship name such as implements"
                             },
                             "weight": { "type": "number", "default": 1.0 }
                         },
                         "required": ["target_id", "edge_type"]
                     }
+                },
+                "idempotency_key": {
+                    "type": "string",
+                    "description": "Client-chosen key that makes retries safe: repeating a key already used in this session returns the existing node instead of adding another"
+                },
+                "pinned": {
+                    "type": "boolean",
+                    "default": false,
+                    "description": "Pin the memory so decay never lowers its importance (for critical decisions and verified facts)"
                 }
             },
             "required": ["event_type", "content"]
@@ -103,20 +116,49 @@ pub async fn execute(
         McpError::InvalidParams(format!("Unknown event type: {}", params.event_type))
     })?;
 
+    let key = params
+        .idempotency_key
+        .as_deref()
+        .map(str::trim)
+        .filter(|k| !k.is_empty());
+
+    let mut session = session.lock().await;
+    if let Some(node_id) = key.and_then(|k| session.idempotent_node(k)) {
+        return Ok(ToolCallResult::json(&json!({
+            "node_id": node_id,
+            "event_type": params.event_type,
+            "edges_created": 0,
+            "created": false
+        })));
+    }
+
+    // New custom edge type names are registered in this session's graph.
     let edges: Vec<(u64, EdgeType, f32)> = params
         .edges
         .iter()
         .map(|e| {
-            let edge_type = EdgeType::from_name(&e.edge_type).ok_or_else(|| {
-                McpError::InvalidParams(format!("Unknown edge type: {}", e.edge_type))
-            })?;
+            let name = e.edge_type.trim();
+            let edge_type = match session.graph().edge_type(name) {
+                Some(edge_type) => edge_type,
+                None => session
+                    .graph_mut()
+                    .register_edge_type(name)
+                    .map_err(|err| McpError::InvalidParams(err.to_string()))?,
+            };
             Ok((e.target_id, edge_type, e.weight))
         })
         .collect::<McpResult<Vec<_>>>()?;
 
-    let mut session = session.lock().await;
-    let (node_id, mut edges_created) =
-        session.add_event(event_type, &params.content, params.confidence, edges)?;
+    let (node_id, mut edges_created) = session.add_event_with(
+        event_type,
+        &params.content,
+        params.confidence,
+        edges,
+        params.pinned,
+    )?;
+    if let Some(key) = key {
+        session.remember_idempotency_key(key, node_id);
+    }
 
     // Splice this explicit add into the temporal chain.
     if let Some(prev_id) = session.last_temporal_node_id() {
@@ -129,6 +171,7 @@ pub async fn execute(
     Ok(ToolCallResult::json(&json!({
         "node_id": node_id,
         "event_type": params.event_type,
-        "edges_created": edges_created
+        "edges_created": edges_created,
+        "created": true
     })))
 }
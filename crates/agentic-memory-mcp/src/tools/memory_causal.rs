@@ -1,4 +1,5 @@
-//! Tool: memory_causal — Impact analysis: what depends on this node?
+//! Tool: memory_causal — Impact analysis: what depends on this node, or
+//! upstream, what is it based on?
 
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -6,7 +7,7 @@ use tokio::sync::Mutex;
 use serde::Deserialize;
 use serde_json::{json, Value};
 
-use agentic_memory::{CausalParams, EdgeType};
+use agentic_memory::{CausalDirection, CausalParams, EdgeType, QueryEngine};
 
 use crate::session::SessionManager;
 use crate::types::{McpError, McpResult, ToolCallResult, ToolDefinition};
@@ -16,6 +17,8 @@ struct CausalInputParams {
     node_id: u64,
     #[serde(default = "default_max_depth")]
     max_depth: u32,
+    #[serde(default)]
+    direction: Option<String>,
 }
 
 fn default_max_depth() -> u32 {
@@ -27,13 +30,19 @@ pub fn definition() -> ToolDefinition {
     ToolDefinition {
         name: "memory_causal".to_string(),
         description: Some(
-            "Impact analysis — find everything that depends on a given node".to_string(),
+            "Impact analysis — find everything that depends on a given node, or with direction=upstream, trace what it is based on back to its root causes".to_string(),
         ),
         input_schema: json!({
             "type": "object",
             "properties": {
                 "node_id": { "type": "integer" },
-                "max_depth": { "type": "integer", "default": 5 }
+                "max_depth": { "type": "integer", "default": 5 },
+                "direction": {
+                    "type": "string",
+                    "enum": ["downstream", "upstream"],
+                    "default": "downstream",
+                    "description": "downstream: nodes that depend on this one; upstream: the causes and evidence it rests on"
+                }
             },
             "required": ["node_id"]
         }),
@@ -48,24 +57,34 @@ pub async fn execute(
     let params: CausalInputParams =
         serde_json::from_value(args).map_err(|e| McpError::InvalidParams(e.to_string()))?;
 
+    let direction = match params.direction.as_deref() {
+        None => CausalDirection::Downstream,
+        Some(name) => CausalDirection::from_name(name).ok_or_else(|| {
+            McpError::InvalidParams(format!(
+                "Unknown direction '{name}'. Use downstream or upstream."
+            ))
+        })?,
+    };
     let causal_params = CausalParams {
         node_id: params.node_id,
         max_depth: params.max_depth,
         dependency_types: vec![EdgeType::CausedBy, EdgeType::Supports],
+        direction,
     };
 
-    let session = session.lock().await;
+    // Run on a snapshot so writers are not held up by the walk.
+    let snapshot = session.lock().await.snapshot();
+    let graph = snapshot.graph();
 
-    let result = session
-        .query_engine()
-        .causal(session.graph(), causal_params)
+    let result = QueryEngine::new()
+        .causal(graph, causal_params)
         .map_err(|e| McpError::AgenticMemory(format!("Causal analysis failed: {e}")))?;
 
     let dependents: Vec<Value> = result
         .dependents
         .iter()
         .filter_map(|id| {
-            session.graph().get_node(*id).map(|node| {
+            graph.get_node(*id).map(|node| {
                 json!({
                     "id": node.id,
                     "event_type": node.event_type.name(),
@@ -76,11 +95,16 @@ pub async fn execute(
         })
         .collect();
 
-    Ok(ToolCallResult::json(&json!({
+    let mut output = json!({
         "root_id": result.root_id,
+        "direction": direction.name(),
         "dependent_count": result.dependents.len(),
         "affected_decisions": result.affected_decisions,
         "affected_inferences": result.affected_inferences,
         "dependents": dependents,
-    })))
+    });
+    if direction == CausalDirection::Upstream {
+        output["root_causes"] = json!(result.leaves);
+    }
+    Ok(ToolCallResult::json(&output))
 }
@@ -14,11 +14,11 @@ use crate::types::{McpError, McpResult, ToolCallResult, ToolDefinition};
 use super::{
     conversation_log, invention_collective, invention_infinite, invention_metamemory,
     invention_prophetic, invention_resurrection, invention_transcendent, memory_add, memory_causal,
-    memory_context, memory_correct, memory_evidence, memory_ground, memory_quality, memory_query,
-    memory_resolve, memory_session_resume, memory_similar, memory_stats, memory_suggest,
-    memory_temporal, memory_traverse, memory_workspace_add, memory_workspace_compare,
-    memory_workspace_create, memory_workspace_list, memory_workspace_query, memory_workspace_xref,
-    session_end, session_start,
+    memory_context, memory_correct, memory_delete_where, memory_evidence, memory_forget,
+    memory_ground, memory_quality, memory_query, memory_resolve, memory_session_resume,
+    memory_similar, memory_stats, memory_suggest, memory_temporal, memory_traverse,
+    memory_workspace_add, memory_workspace_compare, memory_workspace_create, memory_workspace_list,
+    memory_workspace_query, memory_workspace_xref, session_end, session_start,
 };
 
 fn op_schema(ops: &[String], description: &str) -> Value {
@@ -50,7 +50,7 @@ pub fn definitions() -> Vec<ToolDefinition> {
         ToolDefinition {
             name: "memory_core".to_string(),
             description: Some(
-                "Compact core facade: add/query/quality/traverse/correct/resolve/context/similar/causal/temporal/stats/conversation_log".to_string(),
+                "Compact core facade: add/query/quality/traverse/correct/forget/delete_where/resolve/context/similar/causal/temporal/stats/conversation_log".to_string(),
             ),
             input_schema: op_schema(
                 &[
@@ -60,6 +60,8 @@ pub fn definitions() -> Vec<ToolDefinition> {
                     "quality".to_string(),
                     "traverse".to_string(),
                     "correct".to_string(),
+                    "forget".to_string(),
+                    "delete_where".to_string(),
                     "resolve".to_string(),
                     "context".to_string(),
                     "similar".to_string(),
@@ -219,6 +221,8 @@ pub async fn try_execute(
             "quality" => memory_quality::execute(params, session).await,
             "traverse" => memory_traverse::execute(params, session).await,
             "correct" => memory_correct::execute(params, session).await,
+            "forget" => memory_forget::execute(params, session).await,
+            "delete_where" => memory_delete_where::execute(params, session).await,
             "resolve" => memory_resolve::execute(params, session).await,
             "context" => memory_context::execute(params, session).await,
             "similar" => memory_similar::execute(params, session).await,
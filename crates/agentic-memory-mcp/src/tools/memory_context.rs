@@ -72,7 +72,7 @@ pub async fn execute(
             json!({
                 "source_id": e.source_id,
                 "target_id": e.target_id,
-                "edge_type": e.edge_type.name(),
+                "edge_type": session.graph().edge_type_name(e.edge_type),
                 "weight": e.weight,
             })
         })
@@ -16,6 +16,12 @@ struct CorrectParams {
     new_content: String,
     #[serde(default = "default_confidence")]
     confidence: f32,
+    feature_vec: Option<Vec<f32>>,
+    embedding_model: Option<String>,
+    #[serde(default)]
+    strict: bool,
+    #[serde(default)]
+    reassert: bool,
     reason: Option<String>,
 }
 
@@ -36,7 +42,11 @@ pub fn definition() -> ToolDefinition {
             "properties": {
                 "old_node_id": { "type": "integer", "description": "ID of the node being corrected" },
                 "new_content": { "type": "string", "description": "The correct information" },
-                "confidence": { "type": "number", "default": 0.95 },
+                "confidence": { "type": "number", "default": 0.95, "description": "Confidence of the corrected node" },
+                "feature_vec": { "type": "array", "items": { "type": "number" }, "description": "Optional feature vector for the corrected node" },
+                "embedding_model": { "type": "string", "description": "Identifier of the model that produced feature_vec; recorded in the file if unset" },
+                "strict": { "type": "boolean", "default": false, "description": "Reject feature_vec when embedding_model differs from the file's model instead of warning" },
+                "reassert": { "type": "boolean", "default": false, "description": "Create the correction even if new_content matches the node's current content" },
                 "reason": { "type": "string", "description": "Optional explanation for the correction" }
             },
             "required": ["old_node_id", "new_content"]
@@ -59,12 +69,33 @@ pub async fn execute(
         return Err(McpError::NodeNotFound(params.old_node_id));
     }
 
-    let new_id = session.correct_node(params.old_node_id, &params.new_content)?;
+    let model_warning = match (&params.feature_vec, params.embedding_model.as_deref()) {
+        (Some(_), Some(model)) => {
+            session
+                .check_embedding_model(model, params.strict)?
+                .map(|file_model| {
+                    format!("file vectors come from {file_model:?}, this vector from {model:?}")
+                })
+        }
+        _ => None,
+    };
+
+    let new_id = session.correct_node_with(
+        params.old_node_id,
+        &params.new_content,
+        params.confidence,
+        params.feature_vec,
+        params.reassert,
+    )?;
+    let no_change = new_id == params.old_node_id;
 
     Ok(ToolCallResult::json(&json!({
         "new_node_id": new_id,
         "old_node_id": params.old_node_id,
-        "supersedes": true,
+        "supersedes": !no_change,
+        "no_change": no_change,
+        "confidence": params.confidence,
         "reason": params.reason,
+        "embedding_model_warning": model_warning,
     })))
 }
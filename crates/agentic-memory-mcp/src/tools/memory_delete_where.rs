@@ -0,0 +1,103 @@
+//! Tool: memory_delete_where — Delete every node matching filter criteria.
+
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use agentic_memory::{DeleteFilter, EventType};
+
+use crate::session::SessionManager;
+use crate::types::{McpError, McpResult, ToolCallResult, ToolDefinition};
+
+#[derive(Debug, Deserialize)]
+struct DeleteWhereParams {
+    #[serde(default)]
+    event_types: Vec<String>,
+    decay_below: Option<f32>,
+    confidence_below: Option<f32>,
+    #[serde(default)]
+    session_ids: Vec<u32>,
+    created_before: Option<u64>,
+    #[serde(default)]
+    dry_run: bool,
+}
+
+/// Return the tool definition for memory_delete_where.
+pub fn definition() -> ToolDefinition {
+    ToolDefinition {
+        name: "memory_delete_where".to_string(),
+        description: Some(
+            "Delete every node matching all given criteria, with the edges touching them, e.g. stale inferences with a low decay score. At least one criterion is required"
+                .to_string(),
+        ),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "event_types": { "type": "array", "items": { "type": "string" }, "description": "Event types to delete (default: any)" },
+                "decay_below": { "type": "number", "description": "Only nodes whose decay score is below this" },
+                "confidence_below": { "type": "number", "description": "Only nodes whose confidence is below this" },
+                "session_ids": { "type": "array", "items": { "type": "integer" }, "description": "Sessions to delete from (default: any)" },
+                "created_before": { "type": "integer", "description": "Only nodes created before this time (Unix microseconds)" },
+                "dry_run": { "type": "boolean", "default": false, "description": "Report the matching nodes without deleting them" }
+            }
+        }),
+    }
+}
+
+/// Execute the memory_delete_where tool.
+pub async fn execute(
+    args: Value,
+    session: &Arc<Mutex<SessionManager>>,
+) -> McpResult<ToolCallResult> {
+    let params: DeleteWhereParams =
+        serde_json::from_value(args).map_err(|e| McpError::InvalidParams(e.to_string()))?;
+
+    // An unknown type would otherwise widen the filter to every type.
+    let event_types = params
+        .event_types
+        .iter()
+        .map(|name| {
+            EventType::from_name(name)
+                .ok_or_else(|| McpError::InvalidParams(format!("Unknown event type '{name}'")))
+        })
+        .collect::<McpResult<Vec<_>>>()?;
+    let filter = DeleteFilter {
+        event_types,
+        decay_below: params.decay_below,
+        confidence_below: params.confidence_below,
+        session_ids: params.session_ids,
+        created_before: params.created_before,
+    };
+    if filter.is_empty() {
+        return Err(McpError::InvalidParams(
+            "memory_delete_where needs at least one filter criterion".to_string(),
+        ));
+    }
+
+    let mut session = session.lock().await;
+    if params.dry_run {
+        let mut ids: Vec<u64> = session
+            .graph()
+            .nodes()
+            .iter()
+            .filter(|n| filter.matches(n))
+            .map(|n| n.id)
+            .collect();
+        ids.sort_unstable();
+        return Ok(ToolCallResult::json(&json!({
+            "dry_run": true,
+            "deleted_count": ids.len(),
+            "deleted_ids": ids,
+        })));
+    }
+
+    let report = session.delete_where(filter)?;
+    Ok(ToolCallResult::json(&json!({
+        "dry_run": false,
+        "deleted_count": report.deleted_count,
+        "deleted_ids": report.deleted_ids,
+        "edges_removed": report.edges_removed,
+    })))
+}
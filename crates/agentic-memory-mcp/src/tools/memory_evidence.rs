@@ -79,6 +79,7 @@ pub async fn execute(
                 event_types: Vec::new(),
                 session_ids: Vec::new(),
                 min_score: 0.0,
+                confidence_weight: 0.0,
             },
         )
         .map_err(|e| McpError::AgenticMemory(format!("Evidence search failed: {e}")))?;
@@ -94,7 +95,7 @@ pub async fn execute(
                     .map(|e| {
                         json!({
                             "target_id": e.target_id,
-                            "edge_type": e.edge_type.name(),
+                            "edge_type": graph.edge_type_name(e.edge_type),
                             "weight": e.weight,
                         })
                     })
@@ -106,7 +107,7 @@ pub async fn execute(
                     .map(|e| {
                         json!({
                             "source_id": e.source_id,
-                            "edge_type": e.edge_type.name(),
+                            "edge_type": graph.edge_type_name(e.edge_type),
                             "weight": e.weight,
                         })
                     })
@@ -0,0 +1,61 @@
+//! Tool: memory_forget — Erase a node's content but keep the node and its edges.
+
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::session::SessionManager;
+use crate::types::{McpError, McpResult, ToolCallResult, ToolDefinition};
+
+#[derive(Debug, Deserialize)]
+struct ForgetParams {
+    node_id: u64,
+    reason: Option<String>,
+}
+
+/// Return the tool definition for memory_forget.
+pub fn definition() -> ToolDefinition {
+    ToolDefinition {
+        name: "memory_forget".to_string(),
+        description: Some(
+            "Forget a node's content on request: the text and vector are erased and replaced by a \"[forgotten at T]\" marker, while the node and its edges stay so the graph and the fact of the removal remain visible"
+                .to_string(),
+        ),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "node_id": { "type": "integer", "description": "ID of the node whose content to forget" },
+                "reason": { "type": "string", "description": "Optional explanation, echoed in the response and not stored" }
+            },
+            "required": ["node_id"]
+        }),
+    }
+}
+
+/// Execute the memory_forget tool.
+pub async fn execute(
+    args: Value,
+    session: &Arc<Mutex<SessionManager>>,
+) -> McpResult<ToolCallResult> {
+    let params: ForgetParams =
+        serde_json::from_value(args).map_err(|e| McpError::InvalidParams(e.to_string()))?;
+
+    let mut session = session.lock().await;
+    let marker = session.forget_node(params.node_id)?;
+    let graph = session.graph();
+    let event_type = graph
+        .get_node(params.node_id)
+        .map(|n| n.event_type.name())
+        .unwrap_or("unknown");
+    let edges_kept = graph.edges_from(params.node_id).len() + graph.edges_to(params.node_id).len();
+
+    Ok(ToolCallResult::json(&json!({
+        "node_id": params.node_id,
+        "event_type": event_type,
+        "content": marker,
+        "edges_kept": edges_kept,
+        "reason": params.reason,
+    })))
+}
@@ -1,4 +1,7 @@
 //! Tool: memory_ground — Verify a claim has memory backing (anti-hallucination).
+//!
+//! Accepts a single `claim` or a `claims` array. Batch mode checks every claim
+//! under one session lock and reports per-claim results plus aggregate counts.
 
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -6,14 +9,17 @@ use tokio::sync::Mutex;
 use serde::Deserialize;
 use serde_json::{json, Value};
 
-use agentic_memory::TextSearchParams;
+use agentic_memory::{MemoryGraph, TextSearchParams};
 
 use crate::session::SessionManager;
 use crate::types::{McpError, McpResult, ToolCallResult, ToolDefinition};
 
 #[derive(Debug, Deserialize)]
 struct GroundParams {
-    claim: String,
+    #[serde(default)]
+    claim: Option<String>,
+    #[serde(default)]
+    claims: Option<Vec<String>>,
     #[serde(default = "default_threshold")]
     threshold: f32,
 }
@@ -28,17 +34,22 @@ pub fn definition() -> ToolDefinition {
         name: "memory_ground".to_string(),
         description: Some(
             "Verify a claim has memory backing. Returns verified/partial/ungrounded status \
-             to prevent hallucination about what was previously remembered"
+             to prevent hallucination about what was previously remembered. Pass `claims` \
+             to check several claims in one call"
                 .to_string(),
         ),
         input_schema: json!({
             "type": "object",
-            "required": ["claim"],
             "properties": {
                 "claim": {
                     "type": "string",
                     "description": "The claim to verify against stored memories"
                 },
+                "claims": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Several claims to verify in one call (instead of `claim`)"
+                },
                 "threshold": {
                     "type": "number",
                     "default": 0.3,
@@ -56,17 +67,45 @@ pub async fn execute(
 ) -> McpResult<ToolCallResult> {
     let params: GroundParams =
         serde_json::from_value(args).map_err(|e| McpError::InvalidParams(e.to_string()))?;
+    let threshold = params.threshold;
 
-    if params.claim.trim().is_empty() {
+    if let Some(claims) = params.claims {
+        let session = session.lock().await;
+        let mut results = Vec::with_capacity(claims.len());
+        for claim in &claims {
+            results.push(ground_claim(&session, claim, threshold)?);
+        }
+        let grounded = results.iter().filter(|r| r["status"] == "verified").count();
         return Ok(ToolCallResult::json(&json!({
+            "total": claims.len(),
+            "grounded": grounded,
+            "ungrounded": claims.len() - grounded,
+            "threshold": threshold,
+            "summary": format!("{grounded} of {} grounded", claims.len()),
+            "results": results
+        })));
+    }
+
+    let claim = params.claim.ok_or_else(|| {
+        McpError::InvalidParams("Either 'claim' or 'claims' is required".to_string())
+    })?;
+    let session = session.lock().await;
+    Ok(ToolCallResult::json(&ground_claim(
+        &session, &claim, threshold,
+    )?))
+}
+
+/// Ground one claim against the session graph.
+fn ground_claim(session: &SessionManager, claim: &str, threshold: f32) -> McpResult<Value> {
+    if claim.trim().is_empty() {
+        return Ok(json!({
             "status": "ungrounded",
-            "claim": params.claim,
+            "claim": claim,
             "reason": "Empty claim",
             "suggestions": []
-        })));
+        }));
     }
 
-    let session = session.lock().await;
     let graph = session.graph();
 
     // Use BM25 text search to find matching memories
@@ -77,30 +116,29 @@ pub async fn execute(
             graph.term_index.as_ref(),
             graph.doc_lengths.as_ref(),
             TextSearchParams {
-                query: params.claim.clone(),
+                query: claim.to_string(),
                 max_results: 10,
                 event_types: Vec::new(),
                 session_ids: Vec::new(),
                 min_score: 0.0,
+                confidence_weight: 0.0,
             },
         )
         .map_err(|e| McpError::AgenticMemory(format!("Grounding search failed: {e}")))?;
 
-    let threshold = params.threshold;
-
     // Classify results
     let strong: Vec<&agentic_memory::TextMatch> =
         results.iter().filter(|m| m.score >= threshold).collect();
 
     if strong.is_empty() {
         // Try fuzzy suggestions
-        let suggestions = suggest_similar_content(graph, &params.claim);
-        return Ok(ToolCallResult::json(&json!({
+        let suggestions = suggest_similar_content(graph, claim);
+        return Ok(json!({
             "status": "ungrounded",
-            "claim": params.claim,
+            "claim": claim,
             "reason": "No memory nodes match this claim",
             "suggestions": suggestions
-        })));
+        }));
     }
 
     // Build evidence from strong matches
@@ -125,17 +163,17 @@ pub async fn execute(
     let avg_score: f32 = strong.iter().map(|m| m.score).sum::<f32>() / strong.len() as f32;
     let confidence = (avg_score / (avg_score + 1.0)).min(1.0);
 
-    Ok(ToolCallResult::json(&json!({
+    Ok(json!({
         "status": "verified",
-        "claim": params.claim,
+        "claim": claim,
         "confidence": confidence,
         "evidence_count": evidence.len(),
         "evidence": evidence
-    })))
+    }))
 }
 
 /// Find memory content that is similar to the query (for suggestions).
-fn suggest_similar_content(graph: &agentic_memory::MemoryGraph, query: &str) -> Vec<String> {
+fn suggest_similar_content(graph: &MemoryGraph, query: &str) -> Vec<String> {
     let query_lower = query.to_lowercase();
     let query_words: Vec<&str> = query_lower.split_whitespace().collect();
     let mut suggestions: Vec<(f32, String)> = Vec::new();
@@ -35,7 +35,7 @@ pub fn definition() -> ToolDefinition {
     ToolDefinition {
         name: "memory_quality".to_string(),
         description: Some(
-            "Evaluate memory reliability: confidence, staleness, orphan nodes, and unsupported decisions"
+            "Evaluate memory reliability: confidence, staleness and their overlap, orphan nodes, and unsupported decisions"
                 .to_string(),
         ),
         input_schema: json!({
@@ -56,8 +56,9 @@ pub async fn execute(
     let params: QualityParams = serde_json::from_value(args)
         .map_err(|e| McpError::InvalidParams(format!("invalid params: {e}")))?;
 
-    let session = session.lock().await;
-    let graph = session.graph();
+    // Run on a snapshot so writers are not held up by the scan.
+    let snapshot = session.lock().await.snapshot();
+    let graph = snapshot.graph();
     let qe = QueryEngine::new();
     let report = qe
         .memory_quality(
@@ -82,11 +83,18 @@ pub async fn execute(
             "contradiction_edges": report.contradiction_edges,
             "supersedes_edges": report.supersedes_edges
         },
+        "confidence_decay": {
+            "confident_fresh": report.quadrants.confident_fresh,
+            "confident_stale": report.quadrants.confident_stale,
+            "weak_fresh": report.quadrants.weak_fresh,
+            "weak_stale": report.quadrants.weak_stale
+        },
         "examples": {
             "low_confidence": report.low_confidence_examples,
             "stale": report.stale_examples,
             "orphan": report.orphan_examples,
-            "unsupported_decisions": report.unsupported_decision_examples
+            "unsupported_decisions": report.unsupported_decision_examples,
+            "confident_stale": report.confident_stale_examples
         }
     })))
 }
@@ -6,7 +6,8 @@ use tokio::sync::Mutex;
 use serde::Deserialize;
 use serde_json::{json, Value};
 
-use agentic_memory::{EventType, PatternParams, PatternSort};
+use agentic_memory::types::tags::parse_tag;
+use agentic_memory::{EventType, PatternParams, PatternSort, DEFAULT_EPISODE_MEMBER_LIMIT};
 
 use crate::session::SessionManager;
 use crate::types::{McpError, McpResult, ToolCallResult, ToolDefinition};
@@ -21,10 +22,57 @@ struct QueryParams {
     session_ids: Vec<u32>,
     created_after: Option<u64>,
     created_before: Option<u64>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default = "default_tag_match")]
+    tag_match: String,
     #[serde(default = "default_max_results")]
     max_results: usize,
     #[serde(default = "default_sort")]
     sort_by: String,
+    #[serde(default)]
+    expand_episodes: bool,
+    #[serde(default = "default_episode_limit")]
+    episode_limit: usize,
+    fields: Option<Vec<String>>,
+}
+
+/// Node fields `fields` can keep in each result.
+const NODE_FIELDS: &[&str] = &[
+    "id",
+    "event_type",
+    "content",
+    "confidence",
+    "session_id",
+    "created_at",
+    "decay_score",
+    "access_count",
+];
+
+/// Check a `fields` projection against [`NODE_FIELDS`].
+fn validate_fields(fields: &[String]) -> McpResult<()> {
+    if fields.is_empty() {
+        return Err(McpError::InvalidParams(
+            "fields must name at least one field".to_string(),
+        ));
+    }
+    match fields.iter().find(|f| !NODE_FIELDS.contains(&f.as_str())) {
+        Some(unknown) => Err(McpError::InvalidParams(format!(
+            "Unknown field '{unknown}' (expected one of: {})",
+            NODE_FIELDS.join(", ")
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Drop the node fields not listed in `fields`; annotations such as
+/// `expanded_from_episode` are kept.
+fn project_fields(entry: &mut Value, fields: &[String]) {
+    if let Some(object) = entry.as_object_mut() {
+        object.retain(|key, _| {
+            !NODE_FIELDS.contains(&key.as_str()) || fields.iter().any(|f| f == key)
+        });
+    }
 }
 
 fn default_max_results() -> usize {
@@ -35,6 +83,41 @@ fn default_sort() -> String {
     "most_recent".to_string()
 }
 
+fn default_tag_match() -> String {
+    "all".to_string()
+}
+
+pub(crate) fn default_episode_limit() -> usize {
+    DEFAULT_EPISODE_MEMBER_LIMIT
+}
+
+/// Expand the Episode nodes among `hit_ids` into their `PartOf` members,
+/// each marked with `expanded_from_episode`.
+pub(crate) fn episode_member_entries(
+    session: &SessionManager,
+    hit_ids: &[u64],
+    episode_limit: usize,
+) -> Vec<Value> {
+    let graph = session.graph();
+    session
+        .query_engine()
+        .expand_episodes(graph, hit_ids, episode_limit)
+        .into_iter()
+        .filter_map(|member| {
+            let node = graph.get_node(member.node_id)?;
+            Some(json!({
+                "id": node.id,
+                "event_type": node.event_type.name(),
+                "content": node.content,
+                "confidence": node.confidence,
+                "session_id": node.session_id,
+                "created_at": node.created_at,
+                "expanded_from_episode": member.episode_id,
+            }))
+        })
+        .collect()
+}
+
 /// Return the tool definition for memory_query.
 pub fn definition() -> ToolDefinition {
     ToolDefinition {
@@ -49,11 +132,37 @@ pub fn definition() -> ToolDefinition {
                 "session_ids": { "type": "array", "items": { "type": "integer" } },
                 "created_after": { "type": "integer" },
                 "created_before": { "type": "integer" },
+                "tags": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Only return nodes carrying these key=value tags, combined as tag_match says"
+                },
+                "tag_match": {
+                    "type": "string",
+                    "enum": ["all", "any"],
+                    "default": "all",
+                    "description": "all: a node must carry every tag; any: at least one of them"
+                },
                 "max_results": { "type": "integer", "default": 20 },
                 "sort_by": {
                     "type": "string",
                     "enum": ["most_recent", "highest_confidence", "most_accessed", "most_important"],
                     "default": "most_recent"
+                },
+                "expand_episodes": {
+                    "type": "boolean",
+                    "default": false,
+                    "description": "Also return the nodes that are PartOf each Episode hit, marked with expanded_from_episode"
+                },
+                "episode_limit": {
+                    "type": "integer",
+                    "default": 10,
+                    "description": "Maximum nodes returned per expanded Episode"
+                },
+                "fields": {
+                    "type": "array",
+                    "items": { "type": "string", "enum": NODE_FIELDS },
+                    "description": "Only return these node fields (default: all)"
                 }
             }
         }),
@@ -67,6 +176,9 @@ pub async fn execute(
 ) -> McpResult<ToolCallResult> {
     let params: QueryParams =
         serde_json::from_value(args).map_err(|e| McpError::InvalidParams(e.to_string()))?;
+    if let Some(fields) = &params.fields {
+        validate_fields(fields)?;
+    }
 
     let event_types: Vec<EventType> = params
         .event_types
@@ -74,6 +186,35 @@ pub async fn execute(
         .filter_map(|name| EventType::from_name(name))
         .collect();
 
+    let tags: Vec<(String, String)> = params
+        .tags
+        .iter()
+        .map(|tag| {
+            parse_tag(tag).ok_or_else(|| {
+                McpError::InvalidParams(format!("Invalid tag '{tag}' (expected key=value)"))
+            })
+        })
+        .collect::<McpResult<_>>()?;
+    let match_any = match params.tag_match.as_str() {
+        "all" => false,
+        "any" => true,
+        other => {
+            return Err(McpError::InvalidParams(format!(
+                "Unknown tag_match '{other}' (expected all or any)"
+            )))
+        }
+    };
+    if !match_any
+        && tags
+            .iter()
+            .enumerate()
+            .any(|(i, (k, _))| tags[..i].iter().any(|(earlier, _)| earlier == k))
+    {
+        return Err(McpError::InvalidParams(
+            "tag_match all cannot require two values for one key".to_string(),
+        ));
+    }
+
     let sort_by = match params.sort_by.as_str() {
         "highest_confidence" => PatternSort::HighestConfidence,
         "most_accessed" => PatternSort::MostAccessed,
@@ -89,6 +230,12 @@ pub async fn execute(
         created_after: params.created_after,
         created_before: params.created_before,
         min_decay_score: None,
+        required_tags: if match_any {
+            Default::default()
+        } else {
+            tags.iter().cloned().collect()
+        },
+        any_tags: if match_any { tags.clone() } else { Vec::new() },
         max_results: params.max_results,
         sort_by,
     };
@@ -99,7 +246,7 @@ pub async fn execute(
         .pattern(session.graph(), pattern)
         .map_err(|e| McpError::AgenticMemory(format!("Pattern query failed: {e}")))?;
 
-    let nodes: Vec<Value> = results
+    let mut nodes: Vec<Value> = results
         .iter()
         .map(|event| {
             json!({
@@ -114,9 +261,40 @@ pub async fn execute(
             })
         })
         .collect();
+    let count = nodes.len();
+    // How many results carry each requested tag.
+    let tag_counts: serde_json::Map<String, Value> = tags
+        .iter()
+        .map(|(key, value)| {
+            let n = results
+                .iter()
+                .filter(|event| event.tags.get(key) == Some(value))
+                .count();
+            (format!("{key}={value}"), json!(n))
+        })
+        .collect();
 
-    Ok(ToolCallResult::json(&json!({
-        "count": nodes.len(),
+    if params.expand_episodes {
+        let hit_ids: Vec<u64> = results.iter().map(|event| event.id).collect();
+        nodes.extend(episode_member_entries(
+            &session,
+            &hit_ids,
+            params.episode_limit,
+        ));
+    }
+    if let Some(fields) = &params.fields {
+        for entry in &mut nodes {
+            project_fields(entry, fields);
+        }
+    }
+
+    let mut out = json!({
+        "count": count,
+        "expanded_count": nodes.len() - count,
         "nodes": nodes
-    })))
+    });
+    if !tag_counts.is_empty() {
+        out["tag_counts"] = Value::Object(tag_counts);
+    }
+    Ok(ToolCallResult::json(&out))
 }
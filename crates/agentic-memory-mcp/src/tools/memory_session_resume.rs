@@ -3,7 +3,8 @@
 //! Solves the "bootstrap problem": the agent starts blank each conversation but
 //! memories from previous sessions exist in the graph.  This tool retrieves the
 //! last session episode, recent decisions, and high-confidence facts so the agent
-//! can resume with full context.
+//! can resume with full context. The `left_off` block briefs the agent on the most
+//! recent session: its last few nodes in temporal order and the decisions made.
 
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -11,7 +12,7 @@ use tokio::sync::Mutex;
 use serde::Deserialize;
 use serde_json::{json, Value};
 
-use agentic_memory::{EventType, PatternParams, PatternSort};
+use agentic_memory::{EventType, MemoryGraph, PatternParams, PatternSort};
 
 use crate::session::SessionManager;
 use crate::types::{McpError, McpResult, ToolCallResult, ToolDefinition};
@@ -21,12 +22,85 @@ struct ResumeParams {
     /// Maximum number of recent memories to include (default 15).
     #[serde(default = "default_limit")]
     limit: usize,
+    /// Number of trailing nodes of the last session to show (default 5).
+    #[serde(default = "default_last_nodes")]
+    last_nodes: usize,
 }
 
 fn default_limit() -> usize {
     15
 }
 
+fn default_last_nodes() -> usize {
+    5
+}
+
+/// Most recent session other than `current` that holds nodes, judged by its
+/// newest node. Falls back to `current` when no earlier session has any.
+fn last_active_session(graph: &MemoryGraph, current: u32) -> Option<u32> {
+    let index = graph.session_index();
+    let newest = |sid: u32| {
+        index
+            .get_session(sid)
+            .iter()
+            .filter_map(|&id| graph.get_node(id))
+            .map(|n| n.created_at)
+            .max()
+    };
+    index
+        .session_ids()
+        .into_iter()
+        .filter(|&sid| sid != current)
+        .filter_map(|sid| newest(sid).map(|t| (t, sid)))
+        .max()
+        .map(|(_, sid)| sid)
+        .or_else(|| newest(current).map(|_| current))
+}
+
+/// Where the agent left off in `session_id`.
+fn left_off_summary(graph: &MemoryGraph, session_id: u32, last_nodes: usize) -> Value {
+    let mut nodes: Vec<_> = graph
+        .session_index()
+        .get_session(session_id)
+        .iter()
+        .filter_map(|&id| graph.get_node(id))
+        .collect();
+    nodes.sort_by_key(|n| (n.created_at, n.id));
+
+    let tail: Vec<Value> = nodes[nodes.len().saturating_sub(last_nodes)..]
+        .iter()
+        .map(|n| {
+            json!({
+                "id": n.id,
+                "event_type": n.event_type.name(),
+                "content": n.content,
+                "created_at": n.created_at,
+            })
+        })
+        .collect();
+    let decisions: Vec<Value> = nodes
+        .iter()
+        .filter(|n| n.event_type == EventType::Decision)
+        .map(|n| {
+            json!({
+                "id": n.id,
+                "content": n.content,
+                "confidence": n.confidence,
+                "created_at": n.created_at,
+            })
+        })
+        .collect();
+
+    json!({
+        "session_id": session_id,
+        "session_alias": graph.session_index().alias_of(session_id),
+        "node_count": nodes.len(),
+        "last_nodes": tail,
+        "decisions": decisions,
+        "node_ids": nodes.iter().map(|n| n.id).collect::<Vec<_>>(),
+    })
+}
+
 /// Return the tool definition for memory_session_resume.
 pub fn definition() -> ToolDefinition {
     ToolDefinition {
@@ -34,7 +108,8 @@ pub fn definition() -> ToolDefinition {
         description: Some(
             "Load context from previous sessions. Call this at the start of every \
              conversation to restore prior context. Returns the last session summary, \
-             recent decisions, and key facts"
+             recent decisions, and key facts, plus a `left_off` briefing of the most \
+             recent session: its last nodes in order, its decisions, and all its node IDs"
                 .to_string(),
         ),
         input_schema: json!({
@@ -44,6 +119,11 @@ pub fn definition() -> ToolDefinition {
                     "type": "integer",
                     "default": 15,
                     "description": "Maximum number of recent memories to load"
+                },
+                "last_nodes": {
+                    "type": "integer",
+                    "default": 5,
+                    "description": "Trailing nodes of the most recent session to include in left_off"
                 }
             }
         }),
@@ -71,6 +151,8 @@ pub async fn execute(
         created_after: None,
         created_before: None,
         min_decay_score: None,
+        required_tags: Default::default(),
+        any_tags: Default::default(),
         max_results: 1,
         sort_by: PatternSort::MostRecent,
     };
@@ -96,6 +178,8 @@ pub async fn execute(
         created_after: None,
         created_before: None,
         min_decay_score: None,
+        required_tags: Default::default(),
+        any_tags: Default::default(),
         max_results: params.limit / 3,
         sort_by: PatternSort::MostRecent,
     };
@@ -125,6 +209,8 @@ pub async fn execute(
         created_after: None,
         created_before: None,
         min_decay_score: None,
+        required_tags: Default::default(),
+        any_tags: Default::default(),
         max_results: params.limit / 3,
         sort_by: PatternSort::MostRecent,
     };
@@ -154,6 +240,8 @@ pub async fn execute(
         created_after: None,
         created_before: None,
         min_decay_score: None,
+        required_tags: Default::default(),
+        any_tags: Default::default(),
         max_results: params.limit / 3,
         sort_by: PatternSort::MostRecent,
     };
@@ -186,6 +274,8 @@ pub async fn execute(
         created_after: None,
         created_before: None,
         min_decay_score: None,
+        required_tags: Default::default(),
+        any_tags: Default::default(),
         max_results: remaining.max(3),
         sort_by: PatternSort::MostRecent,
     };
@@ -219,6 +309,23 @@ pub async fn execute(
     let total_loaded =
         decision_nodes.len() + fact_nodes.len() + inference_nodes.len() + recent_nodes.len();
 
+    // 7. Where the agent left off.
+    let left_off = last_active_session(graph, current_session)
+        .map(|sid| left_off_summary(graph, sid, params.last_nodes));
+
+    let message = if graph.node_count() == 0 {
+        "Memory is empty; nothing to resume yet. Add memories as you work and they \
+         will be here next time"
+            .to_string()
+    } else if last_episode.is_some() {
+        format!(
+            "Resumed with {} memories from previous sessions",
+            total_loaded
+        )
+    } else {
+        "No previous session context found (first session)".to_string()
+    };
+
     Ok(ToolCallResult::json(&json!({
         "current_session": current_session,
         "previous_session": prev_session_id,
@@ -227,11 +334,8 @@ pub async fn execute(
         "recent_facts": fact_nodes,
         "recent_inferences": inference_nodes,
         "recent_other": recent_nodes,
+        "left_off": left_off,
         "total_loaded": total_loaded,
-        "message": if last_episode.is_some() {
-            format!("Resumed with {} memories from previous sessions", total_loaded)
-        } else {
-            "No previous session context found (first session)".to_string()
-        }
+        "message": message,
     })))
 }
@@ -8,6 +8,7 @@ use serde_json::{json, Value};
 
 use agentic_memory::{EventType, SimilarityParams, TextSearchParams};
 
+use super::memory_query::{default_episode_limit, episode_member_entries};
 use crate::session::SessionManager;
 use crate::types::{McpError, McpResult, ToolCallResult, ToolDefinition};
 
@@ -21,6 +22,10 @@ struct SimilarParams {
     min_similarity: f32,
     #[serde(default)]
     event_types: Vec<String>,
+    #[serde(default)]
+    expand_episodes: bool,
+    #[serde(default = "default_episode_limit")]
+    episode_limit: usize,
 }
 
 fn default_top_k() -> usize {
@@ -43,7 +48,17 @@ pub fn definition() -> ToolDefinition {
                 "query_vec": { "type": "array", "items": { "type": "number" } },
                 "top_k": { "type": "integer", "default": 10 },
                 "min_similarity": { "type": "number", "default": 0.5 },
-                "event_types": { "type": "array", "items": { "type": "string" } }
+                "event_types": { "type": "array", "items": { "type": "string" } },
+                "expand_episodes": {
+                    "type": "boolean",
+                    "default": false,
+                    "description": "Also return the nodes that are PartOf each Episode match, marked with expanded_from_episode"
+                },
+                "episode_limit": {
+                    "type": "integer",
+                    "default": 10,
+                    "description": "Maximum nodes returned per expanded Episode"
+                }
             }
         }),
     }
@@ -72,6 +87,7 @@ pub async fn execute(
             min_similarity: params.min_similarity,
             event_types,
             skip_zero_vectors: true,
+            expand_via_edges: None,
         };
 
         let results = session
@@ -79,7 +95,7 @@ pub async fn execute(
             .similarity(session.graph(), similarity_params)
             .map_err(|e| McpError::AgenticMemory(format!("Similarity search failed: {e}")))?;
 
-        let matches: Vec<Value> = results
+        let mut matches: Vec<Value> = results
             .iter()
             .filter_map(|m| {
                 session.graph().get_node(m.node_id).map(|node| {
@@ -93,10 +109,20 @@ pub async fn execute(
                 })
             })
             .collect();
+        let count = matches.len();
+        if params.expand_episodes {
+            let hit_ids: Vec<u64> = results.iter().map(|m| m.node_id).collect();
+            matches.extend(episode_member_entries(
+                &session,
+                &hit_ids,
+                params.episode_limit,
+            ));
+        }
 
         return Ok(ToolCallResult::json(&json!({
             "mode": "vector",
-            "count": matches.len(),
+            "count": count,
+            "expanded_count": matches.len() - count,
             "matches": matches,
         })));
     }
@@ -117,11 +143,12 @@ pub async fn execute(
                 event_types,
                 session_ids: Vec::new(),
                 min_score: 0.0,
+                confidence_weight: 0.0,
             },
         )
         .map_err(|e| McpError::AgenticMemory(format!("Text similarity fallback failed: {e}")))?;
 
-    let matches: Vec<Value> = text_results
+    let mut matches: Vec<Value> = text_results
         .iter()
         .filter_map(|m| {
             session.graph().get_node(m.node_id).map(|node| {
@@ -137,10 +164,20 @@ pub async fn execute(
             })
         })
         .collect();
+    let count = matches.len();
+    if params.expand_episodes {
+        let hit_ids: Vec<u64> = text_results.iter().map(|m| m.node_id).collect();
+        matches.extend(episode_member_entries(
+            &session,
+            &hit_ids,
+            params.episode_limit,
+        ));
+    }
 
     Ok(ToolCallResult::json(&json!({
         "mode": "text_fallback",
-        "count": matches.len(),
+        "count": count,
+        "expanded_count": matches.len() - count,
         "matches": matches,
     })))
 }
@@ -81,6 +81,7 @@ pub async fn execute(
                 event_types: Vec::new(),
                 session_ids: Vec::new(),
                 min_score: 0.0,
+                confidence_weight: 0.0,
             },
         )
         .map_err(|e| McpError::AgenticMemory(format!("Suggest search failed: {e}")))?;
@@ -15,6 +15,8 @@ use crate::types::{McpError, McpResult, ToolCallResult, ToolDefinition};
 struct TemporalInputParams {
     range_a: RangeSpec,
     range_b: RangeSpec,
+    #[serde(default)]
+    group_by_session: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -113,6 +115,11 @@ pub fn definition() -> ToolDefinition {
                             "additionalProperties": false
                         }
                     ]
+                },
+                "group_by_session": {
+                    "type": "boolean",
+                    "default": false,
+                    "description": "Also return per-session summaries (node count, dominant type, time span). A session is included if any of its nodes falls in either range."
                 }
             },
             "required": ["range_a", "range_b"]
@@ -131,6 +138,7 @@ pub async fn execute(
     let temporal_params = TemporalParams {
         range_a: params.range_a.to_time_range(),
         range_b: params.range_b.to_time_range(),
+        group_by_session: params.group_by_session,
     };
 
     let session = session.lock().await;
@@ -140,6 +148,23 @@ pub async fn execute(
         .temporal(session.graph(), temporal_params)
         .map_err(|e| McpError::AgenticMemory(format!("Temporal comparison failed: {e}")))?;
 
+    let session_groups = result.session_groups.as_ref().map(|groups| {
+        groups
+            .iter()
+            .map(|g| {
+                json!({
+                    "session_id": g.session_id,
+                    "node_count": g.node_count,
+                    "in_range": g.in_range,
+                    "dominant_type": g.dominant_type.name(),
+                    "started_at": g.started_at,
+                    "ended_at": g.ended_at,
+                    "partial": g.partial,
+                })
+            })
+            .collect::<Vec<_>>()
+    });
+
     Ok(ToolCallResult::json(&json!({
         "added": result.added,
         "corrected": result.corrected,
@@ -150,6 +175,7 @@ pub async fn execute(
             "corrected_count": result.corrected.len(),
             "unchanged_count": result.unchanged.len(),
             "stale_count": result.potentially_stale.len(),
-        }
+        },
+        "session_groups": session_groups,
     })))
 }
@@ -1,12 +1,16 @@
 //! Tool: memory_traverse — Walk the graph from a starting node.
 
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
 use serde::Deserialize;
 use serde_json::{json, Value};
 
-use agentic_memory::{EdgeType, TraversalDirection, TraversalParams};
+use agentic_memory::{
+    AmemError, AmemResult, Edge, EdgeType, MemoryGraph, QueryEngine, TraversalDirection,
+    TraversalParams, TraversalResult,
+};
 
 use crate::session::SessionManager;
 use crate::types::{McpError, McpResult, ToolCallResult, ToolDefinition};
@@ -23,6 +27,8 @@ struct TraverseParams {
     #[serde(default = "default_max_results")]
     max_results: usize,
     min_confidence: Option<f32>,
+    #[serde(default = "default_sample_per_level")]
+    sample_per_level: usize,
 }
 
 fn default_direction() -> String {
@@ -37,6 +43,10 @@ fn default_max_results() -> usize {
     20
 }
 
+fn default_sample_per_level() -> usize {
+    10
+}
+
 /// Return the tool definition for memory_traverse.
 pub fn definition() -> ToolDefinition {
     ToolDefinition {
@@ -52,7 +62,12 @@ pub fn definition() -> ToolDefinition {
                 "direction": { "type": "string", "enum": ["forward", "backward", "both"], "default": "forward" },
                 "max_depth": { "type": "integer", "default": 5 },
                 "max_results": { "type": "integer", "default": 20 },
-                "min_confidence": { "type": "number" }
+                "min_confidence": { "type": "number" },
+                "sample_per_level": {
+                    "type": "integer",
+                    "default": 10,
+                    "description": "Keep at most this many neighbors per depth, highest edge weight then confidence first (0 = no sampling)"
+                }
             },
             "required": ["start_id"]
         }),
@@ -67,21 +82,17 @@ pub async fn execute(
     let params: TraverseParams =
         serde_json::from_value(args).map_err(|e| McpError::InvalidParams(e.to_string()))?;
 
+    // Run on a snapshot so writers are not held up by the walk.
+    let snapshot = session.lock().await.snapshot();
+    let graph = snapshot.graph();
+
     let edge_types: Vec<EdgeType> = if params.edge_types.is_empty() {
-        vec![
-            EdgeType::CausedBy,
-            EdgeType::Supports,
-            EdgeType::Contradicts,
-            EdgeType::Supersedes,
-            EdgeType::RelatedTo,
-            EdgeType::PartOf,
-            EdgeType::TemporalNext,
-        ]
+        graph.edge_types()
     } else {
         params
             .edge_types
             .iter()
-            .filter_map(|name| EdgeType::from_name(name))
+            .filter_map(|name| graph.edge_type(name))
             .collect()
     };
 
@@ -98,19 +109,25 @@ pub async fn execute(
         max_depth: params.max_depth,
         max_results: params.max_results,
         min_confidence: params.min_confidence.unwrap_or(0.0),
+        symmetric_undirected: false,
     };
 
-    let session = session.lock().await;
-    let result = session
-        .query_engine()
-        .traverse(session.graph(), traversal)
-        .map_err(|e| McpError::AgenticMemory(format!("Traversal failed: {e}")))?;
+    let (result, sampling) = if params.sample_per_level > 0 {
+        let (result, stats) = sampled_traverse(graph, &traversal, params.sample_per_level)
+            .map_err(|e| McpError::AgenticMemory(format!("Traversal failed: {e}")))?;
+        (result, Some(stats))
+    } else {
+        let result = QueryEngine::new()
+            .traverse(graph, traversal)
+            .map_err(|e| McpError::AgenticMemory(format!("Traversal failed: {e}")))?;
+        (result, None)
+    };
 
     let visited: Vec<Value> = result
         .visited
         .iter()
         .filter_map(|id| {
-            session.graph().get_node(*id).map(|node| {
+            graph.get_node(*id).map(|node| {
                 json!({
                     "id": node.id,
                     "event_type": node.event_type.name(),
@@ -129,16 +146,134 @@ pub async fn execute(
             json!({
                 "source_id": e.source_id,
                 "target_id": e.target_id,
-                "edge_type": e.edge_type.name(),
+                "edge_type": graph.edge_type_name(e.edge_type),
                 "weight": e.weight,
             })
         })
         .collect();
 
-    Ok(ToolCallResult::json(&json!({
+    let mut response = json!({
         "start_id": params.start_id,
         "visited_count": visited.len(),
         "visited": visited,
         "edges_traversed": edges,
-    })))
+    });
+    if let Some(stats) = sampling {
+        response["sampled"] = json!(stats.sampled);
+        response["total_available"] = json!(stats.total_available);
+        response["levels"] = json!(stats
+            .levels
+            .iter()
+            .map(|(depth, available, kept)| {
+                json!({"depth": depth, "available": available, "kept": kept})
+            })
+            .collect::<Vec<_>>());
+    }
+
+    Ok(ToolCallResult::json(&response))
+}
+
+/// What a sampled walk left out.
+struct SampleStats {
+    /// `(depth, neighbors available, neighbors kept)` for each expanded level.
+    levels: Vec<(u32, usize, usize)>,
+    /// Sum of neighbors available across levels. Dropped neighbors are not
+    /// expanded, so this is a lower bound on the full neighborhood.
+    total_available: usize,
+    /// Whether any level dropped neighbors.
+    sampled: bool,
+}
+
+/// Breadth-first walk that, at each depth, keeps only the `per_level`
+/// neighbors with the highest edge weight (ties broken by node confidence).
+/// Unlike the engine's `traverse`, the whole frontier is ranked before
+/// choosing, so a hub node cannot crowd out the rest of the level.
+fn sampled_traverse(
+    graph: &MemoryGraph,
+    params: &TraversalParams,
+    per_level: usize,
+) -> AmemResult<(TraversalResult, SampleStats)> {
+    if graph.get_node(params.start_id).is_none() {
+        return Err(AmemError::NodeNotFound(params.start_id));
+    }
+
+    let edge_set: HashSet<EdgeType> = params.edge_types.iter().copied().collect();
+    let follow_forward = params.direction != TraversalDirection::Backward;
+    let follow_backward = params.direction != TraversalDirection::Forward;
+
+    let mut result = TraversalResult {
+        visited: vec![params.start_id],
+        edges_traversed: Vec::new(),
+        depths: HashMap::from([(params.start_id, 0)]),
+    };
+    let mut stats = SampleStats {
+        levels: Vec::new(),
+        total_available: 0,
+        sampled: false,
+    };
+    let mut seen: HashSet<u64> = HashSet::from([params.start_id]);
+    let mut frontier = vec![params.start_id];
+    let mut depth = 0;
+
+    while depth < params.max_depth
+        && !frontier.is_empty()
+        && result.visited.len() < params.max_results
+    {
+        // Best edge into each not-yet-visited neighbor of the frontier.
+        let mut candidates: HashMap<u64, Edge> = HashMap::new();
+        for &id in &frontier {
+            let mut incident: Vec<(u64, &Edge)> = Vec::new();
+            if follow_forward {
+                incident.extend(graph.edges_from(id).iter().map(|e| (e.target_id, e)));
+            }
+            if follow_backward {
+                incident.extend(graph.edges_to(id).into_iter().map(|e| (e.source_id, e)));
+            }
+            for (neighbor, edge) in incident {
+                if seen.contains(&neighbor) || !edge_set.contains(&edge.edge_type) {
+                    continue;
+                }
+                match graph.get_node(neighbor) {
+                    Some(node) if node.confidence >= params.min_confidence => {}
+                    _ => continue,
+                }
+                candidates
+                    .entry(neighbor)
+                    .and_modify(|best| {
+                        if edge.weight > best.weight {
+                            *best = *edge;
+                        }
+                    })
+                    .or_insert(*edge);
+            }
+        }
+
+        let confidence = |id: u64| graph.get_node(id).map(|n| n.confidence).unwrap_or(0.0);
+        let mut ranked: Vec<(u64, Edge)> = candidates.into_iter().collect();
+        ranked.sort_by(|(a_id, a), (b_id, b)| {
+            b.weight
+                .total_cmp(&a.weight)
+                .then_with(|| confidence(*b_id).total_cmp(&confidence(*a_id)))
+                .then_with(|| a_id.cmp(b_id))
+        });
+
+        let available = ranked.len();
+        let room = params.max_results - result.visited.len();
+        let keep = available.min(per_level).min(room);
+        depth += 1;
+        stats.levels.push((depth, available, keep));
+        stats.total_available += available;
+        stats.sampled |= keep < available;
+
+        frontier.clear();
+        for (id, edge) in ranked.into_iter().take(keep) {
+            seen.insert(id);
+            result.visited.push(id);
+            result.depths.insert(id, depth);
+            result.edges_traversed.push(edge);
+            frontier.push(id);
+        }
+    }
+
+    Ok((result, stats))
 }
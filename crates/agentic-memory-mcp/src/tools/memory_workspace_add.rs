@@ -17,6 +17,8 @@ struct AddParams {
     #[serde(default = "default_role")]
     role: String,
     label: Option<String>,
+    #[serde(default)]
+    strict: bool,
 }
 
 fn default_role() -> String {
@@ -53,6 +55,11 @@ pub fn definition() -> ToolDefinition {
                 "label": {
                     "type": "string",
                     "description": "Optional human-readable label for this context"
+                },
+                "strict": {
+                    "type": "boolean",
+                    "default": false,
+                    "description": "Reject the file if its embedding model differs from the workspace's"
                 }
             }
         }),
@@ -75,18 +82,23 @@ pub async fn execute(
     })?;
 
     let mut session = session.lock().await;
-    let ctx_id = session.workspace_manager_mut().add_context(
+    let ctx_id = session.workspace_manager_mut().add_context_checked(
         &params.workspace_id,
         &params.path,
         role,
         params.label,
+        params.strict,
     )?;
+    let warnings = session
+        .workspace_manager()
+        .embedding_model_warnings(&params.workspace_id)?;
 
     Ok(ToolCallResult::json(&json!({
         "context_id": ctx_id,
         "workspace_id": params.workspace_id,
         "path": params.path,
         "role": role.label(),
-        "status": "added"
+        "status": "added",
+        "embedding_model_warnings": warnings,
     })))
 }
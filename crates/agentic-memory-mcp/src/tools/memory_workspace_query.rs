@@ -66,6 +66,9 @@ pub async fn execute(
         &params.query,
         params.max_per_context,
     )?;
+    let warnings = session
+        .workspace_manager()
+        .embedding_model_warnings(&params.workspace_id)?;
 
     let context_results: Vec<Value> = results
         .iter()
@@ -100,6 +103,7 @@ pub async fn execute(
         "query": params.query,
         "total_matches": total_matches,
         "contexts_searched": context_results.len(),
-        "results": context_results
+        "results": context_results,
+        "embedding_model_warnings": warnings,
     })))
 }
@@ -1,12 +1,15 @@
 //! MCP tool implementations — the primary way LLMs interact with memory.
 
+pub mod context_feedback;
 pub mod conversation_log;
 pub mod memory_add;
 pub mod memory_causal;
 pub mod memory_compact;
 pub mod memory_context;
 pub mod memory_correct;
+pub mod memory_delete_where;
 pub mod memory_evidence;
+pub mod memory_forget;
 pub mod memory_ground;
 pub mod memory_quality;
 pub mod memory_query;
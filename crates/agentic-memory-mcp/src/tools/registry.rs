@@ -32,6 +32,7 @@ fn inject_token_conservation_params(tools: &mut [ToolDefinition]) {
 }
 
 use super::{
+    context_feedback,
     conversation_log,
     invention_collective,
     // 24 Inventions — INFINITUS
@@ -45,7 +46,9 @@ use super::{
     memory_compact,
     memory_context,
     memory_correct,
+    memory_delete_where,
     memory_evidence,
+    memory_forget,
     memory_ground,
     memory_quality,
     memory_query,
@@ -79,8 +82,11 @@ impl ToolRegistry {
             memory_quality::definition(),
             memory_traverse::definition(),
             memory_correct::definition(),
+            memory_forget::definition(),
+            memory_delete_where::definition(),
             memory_resolve::definition(),
             memory_context::definition(),
+            context_feedback::definition(),
             memory_similar::definition(),
             memory_causal::definition(),
             memory_temporal::definition(),
@@ -147,8 +153,11 @@ impl ToolRegistry {
             "memory_quality" => memory_quality::execute(args, session).await,
             "memory_traverse" => memory_traverse::execute(args, session).await,
             "memory_correct" => memory_correct::execute(args, session).await,
+            "memory_forget" => memory_forget::execute(args, session).await,
+            "memory_delete_where" => memory_delete_where::execute(args, session).await,
             "memory_resolve" => memory_resolve::execute(args, session).await,
             "memory_context" => memory_context::execute(args, session).await,
+            "context_feedback" => context_feedback::execute(args, session).await,
             "memory_similar" => memory_similar::execute(args, session).await,
             "memory_causal" => memory_causal::execute(args, session).await,
             "memory_temporal" => memory_temporal::execute(args, session).await,
@@ -15,6 +15,7 @@ struct EndParams {
     #[serde(default = "default_create_episode")]
     create_episode: bool,
     summary: Option<String>,
+    max_events_per_episode: Option<usize>,
 }
 
 fn default_create_episode() -> bool {
@@ -33,7 +34,12 @@ pub fn definition() -> ToolDefinition {
             "properties": {
                 "session_id": { "type": "integer" },
                 "create_episode": { "type": "boolean", "default": true },
-                "summary": { "type": "string", "description": "Episode summary content" }
+                "summary": { "type": "string", "description": "Episode summary content" },
+                "max_events_per_episode": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "Split large sessions into several chained episodes of at most this many nodes (default: AMEM_MAX_EVENTS_PER_EPISODE, unlimited)"
+                }
             }
         }),
     }
@@ -57,11 +63,16 @@ pub async fn execute(
             .summary
             .unwrap_or_else(|| format!("Session {session_id} completed"));
 
-        let episode_id = session.end_session_with_episode(session_id, &summary)?;
+        let episodes = session.end_session_with_episodes(
+            session_id,
+            &summary,
+            params.max_events_per_episode,
+        )?;
 
         Ok(ToolCallResult::json(&json!({
             "session_id": session_id,
-            "episode_node_id": episode_id,
+            "episode_node_id": episodes[0],
+            "episode_node_ids": episodes,
             "summary": summary,
         })))
     } else {
@@ -69,6 +80,7 @@ pub async fn execute(
         Ok(ToolCallResult::json(&json!({
             "session_id": session_id,
             "episode_node_id": null,
+            "episode_node_ids": [],
             "message": "Session ended without episode",
         })))
     }
@@ -19,6 +19,7 @@ use crate::types::{McpError, McpResult, ToolCallResult, ToolDefinition};
 #[allow(dead_code)]
 struct StartParams {
     session_id: Option<u32>,
+    alias: Option<String>,
     metadata: Option<Value>,
 }
 
@@ -31,6 +32,7 @@ pub fn definition() -> ToolDefinition {
             "type": "object",
             "properties": {
                 "session_id": { "type": "integer", "description": "Optional explicit session ID" },
+                "alias": { "type": "string", "description": "Stable session alias (e.g. \"project-alpha\"); resumes the aliased session if it exists, otherwise names the new one" },
                 "metadata": { "type": "object", "description": "Optional session metadata" }
             }
         }),
@@ -46,7 +48,22 @@ pub async fn execute(
         serde_json::from_value(args).map_err(|e| McpError::InvalidParams(e.to_string()))?;
 
     let mut session = session.lock().await;
-    let session_id = session.start_session(params.session_id)?;
+    let aliased = params
+        .alias
+        .as_deref()
+        .and_then(|alias| session.graph().session_index().resolve_alias(alias));
+    if let (Some(explicit), Some(existing)) = (params.session_id, aliased) {
+        if explicit != existing {
+            return Err(McpError::InvalidParams(format!(
+                "Alias {:?} belongs to session {existing}, not {explicit}",
+                params.alias.as_deref().unwrap_or_default()
+            )));
+        }
+    }
+    let session_id = session.start_session(aliased.or(params.session_id))?;
+    if let Some(alias) = params.alias.as_deref() {
+        session.alias_session(session_id, alias)?;
+    }
 
     // ── Retrieve last session context (bootstrap problem solver) ──────────
     //
@@ -64,6 +81,8 @@ pub async fn execute(
         created_after: None,
         created_before: None,
         min_decay_score: None,
+        required_tags: Default::default(),
+        any_tags: Default::default(),
         max_results: 1,
         sort_by: PatternSort::MostRecent,
     };
@@ -92,6 +111,8 @@ pub async fn execute(
 
     Ok(ToolCallResult::json(&json!({
         "session_id": session_id,
+        "alias": graph.session_index().alias_of(session_id),
+        "resumed_alias": aliased.is_some(),
         "message": format!("Session {session_id} started"),
         "total_sessions": total_sessions,
         "previous_session": prev_session,
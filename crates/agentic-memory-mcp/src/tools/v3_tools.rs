@@ -371,7 +371,7 @@ fn default_token_budget() -> u32 {
     50000
 }
 fn default_strategy() -> String {
-    "balanced".to_string()
+    RetrievalStrategy::default().name().to_string()
 }
 
 pub fn retrieve_def() -> ToolDefinition {
@@ -389,7 +389,7 @@ pub fn retrieve_def() -> ToolDefinition {
                     "type": "string",
                     "enum": ["recency", "relevance", "causal", "balanced"],
                     "default": "balanced",
-                    "description": "Retrieval strategy"
+                    "description": "How candidates from each index are weighted: recency favours the newest blocks, relevance favours text and entity matches, causal favours decision chains"
                 }
             },
             "required": ["query"]
@@ -401,12 +401,12 @@ pub async fn retrieve_exec(args: Value, engine: &SharedEngine) -> McpResult<Tool
     let params: RetrieveParams =
         serde_json::from_value(args).map_err(|e| McpError::InvalidParams(e.to_string()))?;
 
-    let strategy = match params.strategy.as_str() {
-        "recency" => RetrievalStrategy::Recency,
-        "relevance" => RetrievalStrategy::Relevance,
-        "causal" => RetrievalStrategy::Causal,
-        _ => RetrievalStrategy::Balanced,
-    };
+    let strategy = RetrievalStrategy::from_name(&params.strategy).ok_or_else(|| {
+        McpError::InvalidParams(format!(
+            "Unknown strategy '{}' (expected recency, relevance, causal or balanced)",
+            params.strategy
+        ))
+    })?;
 
     let eng = engine.lock().await;
     let eng = require_engine(&eng)?;
@@ -429,6 +429,10 @@ pub async fn retrieve_exec(args: Value, engine: &SharedEngine) -> McpResult<Tool
         "context": context.join("\n\n"),
         "blocks_used": result.blocks.len(),
         "tokens_used": result.tokens_used,
+        "strategy": strategy.name(),
+        "ranking": result.ranking,
+        "coverage": result.coverage,
+        "contributions": result.contributions,
         "retrieval_ms": result.retrieval_ms
     })))
 }
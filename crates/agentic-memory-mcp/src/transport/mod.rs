@@ -1,13 +1,32 @@
-//! Transport layer — I/O for stdio and SSE.
+//! Transport layer — I/O for stdio, SSE and WebSocket.
 
 pub mod capture;
 pub mod framing;
 pub mod stdio;
+pub mod wal_recovery;
 
+#[cfg(any(feature = "sse", feature = "ws"))]
+pub mod rate_limit;
 #[cfg(feature = "sse")]
 pub mod sse;
+#[cfg(feature = "ws")]
+pub mod ws;
 
 pub use stdio::StdioTransport;
 
 #[cfg(feature = "sse")]
 pub use sse::SseTransport;
+#[cfg(feature = "ws")]
+pub use ws::WsTransport;
+
+/// Whether an `Authorization` header value carries the expected bearer
+/// token. Everything is authorized when no token is configured.
+#[cfg(any(feature = "sse", feature = "ws"))]
+pub(crate) fn bearer_authorized(header: Option<&str>, expected: Option<&str>) -> bool {
+    let Some(expected) = expected else {
+        return true;
+    };
+    header
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .is_some_and(|token| token == expected)
+}
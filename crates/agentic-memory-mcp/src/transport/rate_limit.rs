@@ -0,0 +1,151 @@
+//! Token-bucket rate limiting for the network transports.
+
+use std::collections::HashMap;
+use std::time::Instant;
+
+use crate::types::{mcp_error_codes, JsonRpcError, RequestId};
+
+/// A token bucket allowing `rps` requests per second, in bursts of up to
+/// `rps`. It starts full and refills continuously.
+#[derive(Debug, Clone)]
+pub struct TokenBucket {
+    rate: f64,
+    tokens: f64,
+    last: Instant,
+}
+
+impl TokenBucket {
+    /// A full bucket for `rps` requests per second (at least 1).
+    pub fn new(rps: u32) -> Self {
+        let rate = f64::from(rps.max(1));
+        Self {
+            rate,
+            tokens: rate,
+            last: Instant::now(),
+        }
+    }
+
+    /// Take one token, or return false when the bucket is empty.
+    pub fn try_acquire(&mut self) -> bool {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last).as_secs_f64();
+        self.last = now;
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.rate);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl TokenBucket {
+    /// Whether the bucket has refilled by `now`, so that it behaves exactly
+    /// like a new one.
+    fn is_full_at(&self, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.last).as_secs_f64();
+        self.tokens + elapsed * self.rate >= self.rate
+    }
+}
+
+/// Most users [`UserBuckets`] tracks by default.
+pub const MAX_TRACKED_USERS: usize = 10_000;
+
+/// Token buckets keyed by user ID, at most `max_users` of them.
+///
+/// When a new user would go over the cap, buckets that have refilled are
+/// dropped first, since a fresh bucket is the same; if none has, the least
+/// recently used one goes.
+#[derive(Debug, Clone)]
+pub struct UserBuckets {
+    rps: u32,
+    max_users: usize,
+    buckets: HashMap<String, TokenBucket>,
+}
+
+impl UserBuckets {
+    /// No buckets yet, each user allowed `rps` requests per second.
+    pub fn new(rps: u32, max_users: usize) -> Self {
+        Self {
+            rps,
+            max_users: max_users.max(1),
+            buckets: HashMap::new(),
+        }
+    }
+
+    /// Take one token from `user_id`'s bucket, or return false when it is
+    /// empty.
+    pub fn try_acquire(&mut self, user_id: &str) -> bool {
+        if !self.buckets.contains_key(user_id) && self.buckets.len() >= self.max_users {
+            self.evict();
+        }
+        let rps = self.rps;
+        self.buckets
+            .entry(user_id.to_string())
+            .or_insert_with(|| TokenBucket::new(rps))
+            .try_acquire()
+    }
+
+    fn evict(&mut self) {
+        let now = Instant::now();
+        self.buckets.retain(|_, bucket| !bucket.is_full_at(now));
+        if self.buckets.len() < self.max_users {
+            return;
+        }
+        let oldest = self
+            .buckets
+            .iter()
+            .min_by_key(|(_, bucket)| bucket.last)
+            .map(|(user_id, _)| user_id.clone());
+        if let Some(user_id) = oldest {
+            self.buckets.remove(&user_id);
+        }
+    }
+}
+
+/// Error message for a request refused by a limit of `rps`.
+pub(crate) fn rate_limited_message(rps: u32) -> String {
+    format!("Rate limit exceeded ({rps} requests per second)")
+}
+
+/// JSON-RPC error response for a request refused by a limit of `rps`.
+pub(crate) fn rate_limited_error(id: RequestId, rps: u32) -> JsonRpcError {
+    JsonRpcError::new(id, mcp_error_codes::RATE_LIMITED, rate_limited_message(rps))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bucket_allows_a_burst_of_rps_then_refuses() {
+        let mut bucket = TokenBucket::new(3);
+        assert!((0..3).all(|_| bucket.try_acquire()));
+        assert!(!bucket.try_acquire());
+
+        bucket.last -= std::time::Duration::from_millis(400);
+        assert!(bucket.try_acquire());
+        assert!(!bucket.try_acquire());
+    }
+
+    #[test]
+    fn user_buckets_stay_within_their_cap() {
+        let mut users = UserBuckets::new(1, 2);
+        assert!(users.try_acquire("alice"));
+        assert!(users.try_acquire("bob"));
+        assert!(!users.try_acquire("bob"));
+
+        // Neither bucket has refilled, so the least recently used one goes.
+        assert!(users.try_acquire("carol"));
+        assert_eq!(users.buckets.len(), 2);
+        assert!(!users.buckets.contains_key("alice"));
+        assert!(!users.try_acquire("bob"));
+
+        // A refilled bucket is dropped ahead of a busier one.
+        users.buckets.get_mut("bob").unwrap().last -= std::time::Duration::from_secs(2);
+        assert!(users.try_acquire("dave"));
+        assert!(users.buckets.contains_key("carol"));
+        assert!(!users.buckets.contains_key("bob"));
+    }
+}
@@ -1,14 +1,19 @@
 //! SSE transport — HTTP server with auth, multi-tenant routing, and /health.
 
+#[cfg(feature = "sse")]
+use std::convert::Infallible;
 #[cfg(feature = "sse")]
 use std::path::PathBuf;
 #[cfg(feature = "sse")]
 use std::sync::Arc;
+#[cfg(feature = "sse")]
+use std::task::{Context, Poll};
 
 #[cfg(feature = "sse")]
 use axum::{
-    extract::State,
-    http::{HeaderMap, StatusCode},
+    body::Body,
+    extract::{DefaultBodyLimit, State},
+    http::{header::CONTENT_LENGTH, HeaderMap, StatusCode},
     middleware,
     response::{IntoResponse, Json as AxumJson, Response},
     routing::{get, post},
@@ -23,7 +28,15 @@ use crate::protocol::ProtocolHandler;
 #[cfg(feature = "sse")]
 use crate::session::tenant::TenantRegistry;
 #[cfg(feature = "sse")]
-use crate::types::{McpResult, MemoryMode};
+use crate::types::{McpResult, MemoryMode, RequestId};
+
+#[cfg(feature = "sse")]
+use super::rate_limit::{rate_limited_error, TokenBucket, UserBuckets, MAX_TRACKED_USERS};
+
+/// Default cap on one HTTP request body: well above the largest node
+/// content (`MAX_CONTENT_SIZE`) plus its feature vector, but bounded.
+#[cfg(feature = "sse")]
+pub const DEFAULT_MAX_BODY_BYTES: usize = 4 * 1024 * 1024;
 
 /// Server operating mode.
 #[cfg(feature = "sse")]
@@ -49,6 +62,9 @@ pub struct ServerState {
 #[cfg(feature = "sse")]
 pub struct SseTransport {
     state: Arc<ServerState>,
+    max_body_bytes: usize,
+    dev: bool,
+    rate_limit_rps: Option<u32>,
 }
 
 #[cfg(feature = "sse")]
@@ -60,6 +76,9 @@ impl SseTransport {
                 token: None,
                 mode: ServerMode::Single(Arc::new(handler)),
             }),
+            max_body_bytes: DEFAULT_MAX_BODY_BYTES,
+            dev: false,
+            rate_limit_rps: None,
         }
     }
 
@@ -67,33 +86,178 @@ impl SseTransport {
     pub fn with_config(token: Option<String>, mode: ServerMode) -> Self {
         Self {
             state: Arc::new(ServerState { token, mode }),
+            max_body_bytes: DEFAULT_MAX_BODY_BYTES,
+            dev: false,
+            rate_limit_rps: None,
         }
     }
 
+    /// Reject `/mcp` request bodies larger than `max_body_bytes` with HTTP
+    /// 413 (0 = no limit).
+    pub fn with_max_body_bytes(mut self, max_body_bytes: usize) -> Self {
+        self.max_body_bytes = max_body_bytes;
+        self
+    }
+
+    /// Development mode: log every `/mcp` request with its timing and
+    /// pretty-print JSON responses.
+    pub fn with_dev(mut self, dev: bool) -> Self {
+        self.dev = dev;
+        self
+    }
+
+    /// Allow `rps` `/mcp` requests per second per connection, or per
+    /// X-User-ID in multi-tenant mode. Requests over the limit get HTTP 429.
+    pub fn with_rate_limit(mut self, rps: Option<u32>) -> Self {
+        self.rate_limit_rps = rps;
+        self
+    }
+
     /// Run the HTTP server on the given address.
     pub async fn run(&self, addr: &str) -> McpResult<()> {
+        let listener = tokio::net::TcpListener::bind(addr)
+            .await
+            .map_err(crate::types::McpError::Io)?;
+        tracing::info!("HTTP transport listening on {addr}");
+        self.serve(listener).await
+    }
+
+    /// Serve requests on an already bound listener.
+    pub async fn serve(&self, listener: tokio::net::TcpListener) -> McpResult<()> {
         let state = self.state.clone();
 
-        let app = Router::new()
-            .route("/mcp", post(handle_request))
+        let mut app = Router::new().route("/mcp", post(handle_request));
+        if self.dev {
+            app = app.layer(middleware::from_fn(dev_layer));
+        }
+        // Per-user limits sit behind auth, so a caller without the token
+        // cannot drain another user's bucket.
+        let per_connection = match (self.rate_limit_rps, &self.state.mode) {
+            (Some(rps), ServerMode::MultiTenant { .. }) => {
+                let limiter = RateLimiter::PerUser {
+                    rps,
+                    buckets: Arc::new(std::sync::Mutex::new(UserBuckets::new(
+                        rps,
+                        MAX_TRACKED_USERS,
+                    ))),
+                };
+                app = app.layer(middleware::from_fn_with_state(limiter, rate_limit_layer));
+                None
+            }
+            (rps, _) => rps,
+        };
+        let app = app
             .layer(middleware::from_fn_with_state(state.clone(), auth_layer))
+            .layer(middleware::from_fn_with_state(
+                self.max_body_bytes,
+                body_limit_layer,
+            ))
+            .layer(DefaultBodyLimit::disable())
             .route("/health", get(handle_health))
             .with_state(state);
 
-        let listener = tokio::net::TcpListener::bind(addr)
-            .await
-            .map_err(crate::types::McpError::Io)?;
+        let served = match per_connection {
+            Some(rps) => axum::serve(listener, PerConnectionLimit { router: app, rps }).await,
+            None => axum::serve(listener, app).await,
+        };
+        served.map_err(|e| crate::types::McpError::Transport(e.to_string()))?;
 
-        tracing::info!("HTTP transport listening on {addr}");
+        Ok(())
+    }
+}
 
-        axum::serve(listener, app)
-            .await
-            .map_err(|e| crate::types::McpError::Transport(e.to_string()))?;
+/// Where the rate limit's token buckets live.
+#[cfg(feature = "sse")]
+#[derive(Clone)]
+enum RateLimiter {
+    /// One bucket for one connection, created when it is accepted.
+    Connection {
+        rps: u32,
+        bucket: Arc<std::sync::Mutex<TokenBucket>>,
+    },
+    /// One bucket per X-User-ID, shared by that user's connections.
+    PerUser {
+        rps: u32,
+        buckets: Arc<std::sync::Mutex<UserBuckets>>,
+    },
+}
 
-        Ok(())
+/// Make-service giving each accepted connection the router with a rate
+/// limit layer of its own, so connections never contend for a bucket.
+#[cfg(feature = "sse")]
+struct PerConnectionLimit {
+    router: Router,
+    rps: u32,
+}
+
+#[cfg(feature = "sse")]
+impl<'a> tower::Service<axum::serve::IncomingStream<'a>> for PerConnectionLimit {
+    type Response = Router;
+    type Error = Infallible;
+    type Future = std::future::Ready<Result<Router, Infallible>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _stream: axum::serve::IncomingStream<'a>) -> Self::Future {
+        let limiter = RateLimiter::Connection {
+            rps: self.rps,
+            bucket: Arc::new(std::sync::Mutex::new(TokenBucket::new(self.rps))),
+        };
+        std::future::ready(Ok(self
+            .router
+            .clone()
+            .layer(middleware::from_fn_with_state(limiter, rate_limit_layer))))
     }
 }
 
+/// Rate limit middleware — refuses `/mcp` requests over the limit with
+/// HTTP 429. /health is never limited, and multi-tenant requests without
+/// X-User-ID pass through to be rejected by the handler.
+#[cfg(feature = "sse")]
+async fn rate_limit_layer(
+    State(limiter): State<RateLimiter>,
+    request: axum::extract::Request,
+    next: middleware::Next,
+) -> Response {
+    if request.uri().path() != "/mcp" {
+        return next.run(request).await;
+    }
+    let (rps, allowed) = match &limiter {
+        RateLimiter::Connection { rps, bucket } => (
+            *rps,
+            bucket
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .try_acquire(),
+        ),
+        RateLimiter::PerUser { rps, buckets } => {
+            let user_id = request
+                .headers()
+                .get("x-user-id")
+                .and_then(|v| v.to_str().ok());
+            let allowed = match user_id {
+                Some(user_id) => buckets
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .try_acquire(user_id),
+                None => true,
+            };
+            (*rps, allowed)
+        }
+    };
+    if !allowed {
+        return (
+            StatusCode::TOO_MANY_REQUESTS,
+            AxumJson(rate_limited_error(RequestId::Null, rps)),
+        )
+            .into_response();
+    }
+
+    next.run(request).await
+}
+
 /// Auth middleware — checks Bearer token if configured.
 /// /health is handled by a separate route that bypasses this layer.
 #[cfg(feature = "sse")]
@@ -103,32 +267,122 @@ async fn auth_layer(
     request: axum::extract::Request,
     next: middleware::Next,
 ) -> Response {
-    if let Some(expected) = &state.token {
-        let authorized = headers
-            .get("authorization")
-            .and_then(|v| v.to_str().ok())
-            .and_then(|v| v.strip_prefix("Bearer "))
-            .is_some_and(|token| token == expected);
-
-        if !authorized {
-            return (
-                StatusCode::UNAUTHORIZED,
-                AxumJson(serde_json::json!({
-                    "jsonrpc": "2.0",
-                    "id": null,
-                    "error": {
-                        "code": -32900,
-                        "message": "Unauthorized"
-                    }
-                })),
-            )
-                .into_response();
-        }
+    let header = headers.get("authorization").and_then(|v| v.to_str().ok());
+    if !super::bearer_authorized(header, state.token.as_deref()) {
+        return (
+            StatusCode::UNAUTHORIZED,
+            AxumJson(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": null,
+                "error": {
+                    "code": -32900,
+                    "message": "Unauthorized"
+                }
+            })),
+        )
+            .into_response();
     }
 
     next.run(request).await
 }
 
+/// Body size limit — rejects an oversized request with 413 before it is
+/// buffered: up front when Content-Length declares too much, otherwise as
+/// soon as the streamed body passes the limit.
+#[cfg(feature = "sse")]
+async fn body_limit_layer(
+    State(max_body_bytes): State<usize>,
+    request: axum::extract::Request,
+    next: middleware::Next,
+) -> Response {
+    if max_body_bytes == 0 {
+        return next.run(request).await;
+    }
+
+    let declared = request
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok());
+    if declared.is_some_and(|len| len > max_body_bytes as u64) {
+        return payload_too_large(max_body_bytes);
+    }
+
+    let (parts, body) = request.into_parts();
+    match axum::body::to_bytes(body, max_body_bytes).await {
+        Ok(bytes) => {
+            next.run(axum::extract::Request::from_parts(parts, Body::from(bytes)))
+                .await
+        }
+        Err(_) => payload_too_large(max_body_bytes),
+    }
+}
+
+#[cfg(feature = "sse")]
+fn payload_too_large(max_body_bytes: usize) -> Response {
+    (
+        StatusCode::PAYLOAD_TOO_LARGE,
+        AxumJson(serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": null,
+            "error": {
+                "code": -32801,
+                "message": format!("Request body exceeds the {max_body_bytes}-byte limit")
+            }
+        })),
+    )
+        .into_response()
+}
+
+/// Development logging — logs each request's method, id, status and
+/// duration, and re-serializes JSON responses pretty-printed.
+#[cfg(feature = "sse")]
+async fn dev_layer(request: axum::extract::Request, next: middleware::Next) -> Response {
+    let started = std::time::Instant::now();
+    let (parts, body) = request.into_parts();
+    let Ok(bytes) = axum::body::to_bytes(body, usize::MAX).await else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+    let parsed = serde_json::from_slice::<serde_json::Value>(&bytes).ok();
+    let method = parsed
+        .as_ref()
+        .and_then(|v| v.get("method"))
+        .and_then(|m| m.as_str())
+        .unwrap_or("-")
+        .to_string();
+    let id = parsed
+        .as_ref()
+        .and_then(|v| v.get("id"))
+        .map(|id| id.to_string())
+        .unwrap_or_else(|| "-".to_string());
+    if let Some(params) = parsed.as_ref().and_then(|v| v.get("params")) {
+        tracing::debug!("{method} id={id} params={params}");
+    }
+
+    let response = next
+        .run(axum::extract::Request::from_parts(parts, Body::from(bytes)))
+        .await;
+    let (parts, body) = response.into_parts();
+    let bytes = axum::body::to_bytes(body, usize::MAX)
+        .await
+        .unwrap_or_default();
+    tracing::info!(
+        "{method} id={id} -> {} in {:.1}ms",
+        parts.status.as_u16(),
+        started.elapsed().as_secs_f64() * 1000.0
+    );
+
+    let body = match serde_json::from_slice::<serde_json::Value>(&bytes) {
+        Ok(value) => serde_json::to_vec_pretty(&value)
+            .map(Body::from)
+            .unwrap_or_else(|_| Body::from(bytes)),
+        Err(_) => Body::from(bytes),
+    };
+    let mut response = Response::from_parts(parts, body);
+    response.headers_mut().remove(CONTENT_LENGTH);
+    response
+}
+
 /// Handle JSON-RPC requests. In multi-tenant mode, routes by X-User-ID header.
 #[cfg(feature = "sse")]
 async fn handle_request(
@@ -163,8 +417,10 @@ async fn handle_request(
 
             let session = {
                 let mut reg = registry.lock().await;
-                reg.get_or_create(user_id, *memory_mode).map_err(|e| {
-                    (
+                reg.get_or_create(user_id, *memory_mode)
+                    .await
+                    .map_err(|e| {
+                        (
                         StatusCode::INTERNAL_SERVER_ERROR,
                         AxumJson(serde_json::json!({
                             "jsonrpc": "2.0",
@@ -176,10 +432,10 @@ async fn handle_request(
                         })),
                     )
                         .into_response()
-                })?
+                    })?
             };
 
-            Arc::new(ProtocolHandler::with_mode(session, *memory_mode))
+            Arc::new(ProtocolHandler::with_mode(session, *memory_mode).with_tenant(user_id))
         }
     };
 
@@ -233,7 +489,128 @@ async fn handle_health(State(state): State<Arc<ServerState>>) -> AxumJson<serde_
     if let ServerMode::MultiTenant { registry, .. } = &state.mode {
         let reg = registry.lock().await;
         health["users"] = serde_json::json!(reg.count());
+        health["max_open_tenants"] = serde_json::json!(reg.max_open());
     }
 
     AxumJson(health)
 }
+
+#[cfg(all(test, feature = "sse"))]
+mod tests {
+    use super::*;
+    use tower::ServiceExt;
+
+    async fn status(max_body_bytes: usize, body: Vec<u8>, declared: Option<usize>) -> StatusCode {
+        let app = Router::new()
+            .route(
+                "/mcp",
+                post(|body: String| async move { body.len().to_string() }),
+            )
+            .layer(middleware::from_fn_with_state(
+                max_body_bytes,
+                body_limit_layer,
+            ))
+            .layer(DefaultBodyLimit::disable());
+        let mut request = axum::http::Request::post("/mcp");
+        if let Some(len) = declared {
+            request = request.header(CONTENT_LENGTH, len);
+        }
+        let request = request.body(Body::from(body)).expect("test fixture");
+        app.oneshot(request).await.expect("test fixture").status()
+    }
+
+    #[tokio::test]
+    async fn oversized_bodies_get_413() {
+        assert_eq!(status(16, vec![b'x'; 16], None).await, StatusCode::OK);
+        assert_eq!(
+            status(16, vec![b'x'; 17], None).await,
+            StatusCode::PAYLOAD_TOO_LARGE
+        );
+        assert_eq!(
+            status(16, vec![b'x'; 4], Some(17)).await,
+            StatusCode::PAYLOAD_TOO_LARGE
+        );
+        assert_eq!(status(0, vec![b'x'; 1024], None).await, StatusCode::OK);
+    }
+
+    async fn limited_status(
+        app: &Router,
+        user_id: Option<&str>,
+    ) -> (StatusCode, serde_json::Value) {
+        let mut request = axum::http::Request::post("/mcp");
+        if let Some(user_id) = user_id {
+            request = request.header("x-user-id", user_id);
+        }
+        let request = request.body(Body::empty()).expect("test fixture");
+        let response = app.clone().oneshot(request).await.expect("test fixture");
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("test fixture");
+        (status, serde_json::from_slice(&body).unwrap_or_default())
+    }
+
+    #[tokio::test]
+    async fn requests_over_the_connection_limit_get_429() {
+        let limiter = RateLimiter::Connection {
+            rps: 3,
+            bucket: Arc::new(std::sync::Mutex::new(TokenBucket::new(3))),
+        };
+        let app = Router::new()
+            .route("/mcp", post(|| async { "ok" }))
+            .route("/health", get(|| async { "ok" }))
+            .layer(middleware::from_fn_with_state(limiter, rate_limit_layer));
+
+        for _ in 0..3 {
+            assert_eq!(limited_status(&app, None).await.0, StatusCode::OK);
+        }
+        let (status, body) = limited_status(&app, None).await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(body["error"]["code"], -32805);
+
+        let health = axum::http::Request::get("/health")
+            .body(Body::empty())
+            .expect("test fixture");
+        let response = app.clone().oneshot(health).await.expect("test fixture");
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn multi_tenant_limits_each_user_separately() {
+        let limiter = RateLimiter::PerUser {
+            rps: 2,
+            buckets: Arc::new(std::sync::Mutex::new(UserBuckets::new(2, 16))),
+        };
+        let app = Router::new()
+            .route("/mcp", post(|| async { "ok" }))
+            .layer(middleware::from_fn_with_state(limiter, rate_limit_layer));
+
+        for _ in 0..2 {
+            assert_eq!(limited_status(&app, Some("alice")).await.0, StatusCode::OK);
+        }
+        let (status, body) = limited_status(&app, Some("alice")).await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(body["error"]["code"], -32805);
+        assert_eq!(limited_status(&app, Some("bob")).await.0, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn dev_layer_pretty_prints_json() {
+        let app = Router::new()
+            .route(
+                "/mcp",
+                post(|| async { AxumJson(serde_json::json!({"jsonrpc": "2.0", "id": 1})) }),
+            )
+            .layer(middleware::from_fn(dev_layer));
+        let request = axum::http::Request::post("/mcp")
+            .body(Body::from(r#"{"jsonrpc":"2.0","id":1,"method":"ping"}"#))
+            .expect("test fixture");
+        let response = app.oneshot(request).await.expect("test fixture");
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("test fixture");
+        let text = String::from_utf8(body.to_vec()).expect("test fixture");
+        assert!(text.contains("\n  \"id\": 1"), "{text}");
+    }
+}
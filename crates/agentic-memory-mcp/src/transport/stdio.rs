@@ -125,8 +125,9 @@ impl StdioTransport {
     ) -> McpResult<bool> {
         match framing::parse_message(input.trim()) {
             Ok(msg) => {
-                if let Some(response) = self.handler.handle_message(msg).await {
-                    self.write_response(stdout, &response, framed_output, capture)
+                // Streamed results arrive as chunk notifications before the response.
+                for message in self.handler.handle_message_streamed(msg).await {
+                    self.write_response(stdout, &message, framed_output, capture)
                         .await?;
                 }
                 if self.handler.shutdown_requested() {
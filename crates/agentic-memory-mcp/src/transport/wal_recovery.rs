@@ -0,0 +1,428 @@
+//! Recovery of explicit writes from the transport WAL after brain loss.
+//!
+//! When a brain file is lost but the transport WAL survives, the
+//! `memory_add` and `memory_correct` calls recorded in it can be replayed
+//! into a fresh file. Only entries from the WAL's most recent session ID are
+//! used, and calls the server answered with an error are skipped. Replay goes
+//! through the tool registry, so recovered nodes are formed the same way the
+//! original calls formed them.
+//!
+//! Node IDs from the lost file mean nothing in the new one. A correction, or
+//! an edge of a recovered `memory_add`, is kept only when the node it points
+//! to was itself recovered earlier in the replay; everything else is reported
+//! as unrecoverable. Calls whose node already exists with the same type and
+//! content are not replayed again, so running recovery twice is harmless.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde::Serialize;
+use serde_json::Value;
+use tokio::sync::Mutex;
+
+use agentic_memory::types::content_hash;
+use agentic_memory::EventType;
+
+use crate::session::SessionManager;
+use crate::tools::ToolRegistry;
+use crate::types::{McpResult, ToolCallResult, ToolContent};
+
+use super::capture::{CaptureDirection, CapturedTransportEntry};
+
+/// Tools whose calls are replayed.
+const RECOVERABLE_TOOLS: [&str; 2] = ["memory_add", "memory_correct"];
+
+/// A call that was replayed, or found already present, in the new brain.
+#[derive(Debug, Clone, Serialize)]
+pub struct RecoveredCall {
+    /// WAL sequence number of the original request.
+    pub sequence: u64,
+    /// Tool name.
+    pub tool: String,
+    /// Node ID the call produced in the lost brain, if its response survived.
+    pub original_node_id: Option<u64>,
+    /// Node ID in the new brain.
+    pub node_id: u64,
+    /// Whether the node was already present and the call was not replayed.
+    pub already_present: bool,
+}
+
+/// A call, or part of one, that could not be recovered.
+#[derive(Debug, Clone, Serialize)]
+pub struct UnrecoverableCall {
+    /// WAL sequence number of the original request.
+    pub sequence: u64,
+    /// Tool name.
+    pub tool: String,
+    /// Why it was not recovered.
+    pub reason: String,
+}
+
+/// Outcome of a WAL recovery.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct WalRecoveryReport {
+    /// WAL session the calls were taken from.
+    pub wal_session_id: Option<String>,
+    /// Calls replayed or found already present, in WAL order.
+    pub recovered: Vec<RecoveredCall>,
+    /// Calls or edges that were not recovered.
+    pub unrecoverable: Vec<UnrecoverableCall>,
+}
+
+impl WalRecoveryReport {
+    /// Number of calls that created a node in this run.
+    pub fn replayed(&self) -> usize {
+        self.recovered.iter().filter(|c| !c.already_present).count()
+    }
+}
+
+struct PendingCall {
+    sequence: u64,
+    tool: String,
+    arguments: Value,
+    response: Option<Value>,
+}
+
+/// Replay the recoverable calls in `entries` into `session`.
+///
+/// The caller decides when recovery applies (typically: the brain file does
+/// not exist yet) and saves the session afterwards.
+pub async fn recover_from_wal(
+    entries: &[CapturedTransportEntry],
+    session: &Arc<Mutex<SessionManager>>,
+) -> McpResult<WalRecoveryReport> {
+    let mut report = WalRecoveryReport::default();
+    let Some(last) = entries.last() else {
+        return Ok(report);
+    };
+    report.wal_session_id = Some(last.session_id_string());
+
+    let calls = collect_calls(entries, last.session_id);
+    let mut id_map: HashMap<u64, u64> = HashMap::new();
+
+    for call in calls {
+        let unrecoverable = |reason: String| UnrecoverableCall {
+            sequence: call.sequence,
+            tool: call.tool.clone(),
+            reason,
+        };
+
+        let response = call.response.as_ref();
+        if let Some(message) = response.and_then(response_error) {
+            report
+                .unrecoverable
+                .push(unrecoverable(format!("original call failed: {message}")));
+            continue;
+        }
+        let original = response.and_then(response_output);
+        let mut arguments = call.arguments.clone();
+
+        let (event_type, content, original_node_id) = match call.tool.as_str() {
+            "memory_add" => {
+                // Keep only edges to nodes recovered earlier in the replay.
+                if let Some(edges) = arguments.get_mut("edges").and_then(Value::as_array_mut) {
+                    edges.retain_mut(|edge| {
+                        let Some(target) = edge.get("target_id").and_then(Value::as_u64) else {
+                            return false;
+                        };
+                        match id_map.get(&target) {
+                            Some(&mapped) => {
+                                edge["target_id"] = Value::from(mapped);
+                                true
+                            }
+                            None => {
+                                report.unrecoverable.push(unrecoverable(format!(
+                                    "edge to node {target}, which was not recovered"
+                                )));
+                                false
+                            }
+                        }
+                    });
+                }
+                let event_type = arguments
+                    .get("event_type")
+                    .and_then(Value::as_str)
+                    .and_then(EventType::from_name);
+                let content = arguments.get("content").and_then(Value::as_str);
+                let original_node_id = original
+                    .as_ref()
+                    .and_then(|o| o.get("node_id"))
+                    .and_then(Value::as_u64);
+                (event_type, content.map(str::to_string), original_node_id)
+            }
+            _ => {
+                let Some(old_id) = arguments.get("old_node_id").and_then(Value::as_u64) else {
+                    report
+                        .unrecoverable
+                        .push(unrecoverable("missing old_node_id".to_string()));
+                    continue;
+                };
+                let Some(&mapped) = id_map.get(&old_id) else {
+                    report.unrecoverable.push(unrecoverable(format!(
+                        "corrects node {old_id}, which was not recovered"
+                    )));
+                    continue;
+                };
+                arguments["old_node_id"] = Value::from(mapped);
+                let content = arguments.get("new_content").and_then(Value::as_str);
+                let original_node_id = original
+                    .as_ref()
+                    .and_then(|o| o.get("new_node_id"))
+                    .and_then(Value::as_u64);
+                (
+                    Some(EventType::Correction),
+                    content.map(str::to_string),
+                    original_node_id,
+                )
+            }
+        };
+        let (Some(event_type), Some(content)) = (event_type, content) else {
+            report
+                .unrecoverable
+                .push(unrecoverable("malformed arguments".to_string()));
+            continue;
+        };
+
+        let existing = {
+            let session = session.lock().await;
+            let hash = content_hash(&content);
+            session
+                .graph()
+                .nodes()
+                .iter()
+                .find(|n| n.event_type == event_type && content_hash(&n.content) == hash)
+                .map(|n| n.id)
+        };
+        let (node_id, already_present) = match existing {
+            Some(id) => (id, true),
+            None => {
+                let result = match ToolRegistry::call(&call.tool, Some(arguments), session).await {
+                    Ok(result) => result,
+                    Err(e) => {
+                        report
+                            .unrecoverable
+                            .push(unrecoverable(format!("replay failed: {e}")));
+                        continue;
+                    }
+                };
+                let key = if call.tool == "memory_add" {
+                    "node_id"
+                } else {
+                    "new_node_id"
+                };
+                match result_json(&result).and_then(|v| v.get(key).and_then(Value::as_u64)) {
+                    Some(id) => (id, false),
+                    None => {
+                        report
+                            .unrecoverable
+                            .push(unrecoverable("replay returned no node ID".to_string()));
+                        continue;
+                    }
+                }
+            }
+        };
+
+        if let Some(original) = original_node_id {
+            id_map.insert(original, node_id);
+        }
+        report.recovered.push(RecoveredCall {
+            sequence: call.sequence,
+            tool: call.tool,
+            original_node_id,
+            node_id,
+            already_present,
+        });
+    }
+
+    Ok(report)
+}
+
+/// Recoverable `tools/call` requests of one WAL session, each paired with
+/// the first response carrying its request ID.
+fn collect_calls(entries: &[CapturedTransportEntry], session_id: [u8; 16]) -> Vec<PendingCall> {
+    let mut calls: Vec<PendingCall> = Vec::new();
+    let mut awaiting: HashMap<String, usize> = HashMap::new();
+
+    for entry in entries.iter().filter(|e| e.session_id == session_id) {
+        let Ok(message) = serde_json::from_slice::<Value>(&entry.data) else {
+            continue;
+        };
+        let Some(id) = message.get("id").filter(|id| !id.is_null()) else {
+            continue;
+        };
+        let id = id.to_string();
+        match entry.direction {
+            CaptureDirection::Inbound => {
+                if message.get("method").and_then(Value::as_str) != Some("tools/call") {
+                    continue;
+                }
+                let params = message.get("params");
+                let tool = params
+                    .and_then(|p| p.get("name"))
+                    .and_then(Value::as_str)
+                    .unwrap_or_default();
+                if !RECOVERABLE_TOOLS.contains(&tool) {
+                    continue;
+                }
+                awaiting.insert(id, calls.len());
+                calls.push(PendingCall {
+                    sequence: entry.sequence,
+                    tool: tool.to_string(),
+                    arguments: params
+                        .and_then(|p| p.get("arguments"))
+                        .cloned()
+                        .unwrap_or_else(|| Value::Object(Default::default())),
+                    response: None,
+                });
+            }
+            CaptureDirection::Outbound => {
+                if let Some(index) = awaiting.remove(&id) {
+                    calls[index].response = Some(message);
+                }
+            }
+        }
+    }
+    calls
+}
+
+/// Error message of a failed response, if it failed.
+fn response_error(response: &Value) -> Option<String> {
+    if let Some(error) = response.get("error") {
+        return Some(
+            error
+                .get("message")
+                .and_then(Value::as_str)
+                .unwrap_or("error")
+                .to_string(),
+        );
+    }
+    let result = response.get("result")?;
+    if result.get("isError").and_then(Value::as_bool) == Some(true) {
+        let text = result
+            .pointer("/content/0/text")
+            .and_then(Value::as_str)
+            .unwrap_or("tool error");
+        return Some(text.to_string());
+    }
+    None
+}
+
+/// JSON output of a successful tool response.
+fn response_output(response: &Value) -> Option<Value> {
+    let text = response.pointer("/result/content/0/text")?.as_str()?;
+    serde_json::from_str(text).ok()
+}
+
+fn result_json(result: &ToolCallResult) -> Option<Value> {
+    match result.content.first()? {
+        ToolContent::Text { text } => serde_json::from_str(text).ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    fn entry(sequence: u64, direction: CaptureDirection, message: Value) -> CapturedTransportEntry {
+        CapturedTransportEntry {
+            timestamp_nanos: sequence as i64,
+            sequence,
+            direction,
+            session_id: [7; 16],
+            data: serde_json::to_vec(&message).expect("test fixture"),
+        }
+    }
+
+    fn call(sequence: u64, id: u64, tool: &str, arguments: Value) -> CapturedTransportEntry {
+        entry(
+            sequence,
+            CaptureDirection::Inbound,
+            json!({"jsonrpc": "2.0", "id": id, "method": "tools/call",
+                   "params": {"name": tool, "arguments": arguments}}),
+        )
+    }
+
+    fn reply(sequence: u64, id: u64, output: Value) -> CapturedTransportEntry {
+        entry(
+            sequence,
+            CaptureDirection::Outbound,
+            json!({"jsonrpc": "2.0", "id": id, "result": {
+                "content": [{"type": "text", "text": output.to_string()}]}}),
+        )
+    }
+
+    fn wal() -> Vec<CapturedTransportEntry> {
+        vec![
+            call(
+                0,
+                1,
+                "memory_add",
+                json!({"event_type": "fact", "content": "Rust is fast"}),
+            ),
+            reply(1, 1, json!({"node_id": 5})),
+            call(
+                2,
+                2,
+                "memory_add",
+                json!({"event_type": "fact", "content": "rejected"}),
+            ),
+            entry(
+                3,
+                CaptureDirection::Outbound,
+                json!({"jsonrpc": "2.0", "id": 2, "error": {"code": -32602, "message": "bad"}}),
+            ),
+            call(
+                4,
+                3,
+                "memory_correct",
+                json!({"old_node_id": 5, "new_content": "Rust is very fast"}),
+            ),
+            reply(5, 3, json!({"new_node_id": 9})),
+            call(
+                6,
+                4,
+                "memory_correct",
+                json!({"old_node_id": 77, "new_content": "unknown"}),
+            ),
+        ]
+    }
+
+    #[tokio::test]
+    async fn recovers_writes_and_is_idempotent() {
+        let dir = tempdir().expect("test fixture");
+        let path = dir.path().join("brain.amem");
+        let session =
+            SessionManager::open(path.to_str().expect("test fixture")).expect("test fixture");
+        let session = Arc::new(Mutex::new(session));
+
+        let report = recover_from_wal(&wal(), &session)
+            .await
+            .expect("test fixture");
+        assert_eq!(report.replayed(), 2);
+        assert_eq!(report.recovered[0].original_node_id, Some(5));
+        assert_eq!(report.recovered[1].tool, "memory_correct");
+        assert_eq!(report.unrecoverable.len(), 2);
+        assert!(report.unrecoverable[0]
+            .reason
+            .contains("original call failed"));
+        assert!(report.unrecoverable[1].reason.contains("node 77"));
+        let nodes = session.lock().await.graph().node_count();
+        assert!(session
+            .lock()
+            .await
+            .graph()
+            .nodes()
+            .iter()
+            .any(|n| n.event_type == EventType::Correction && n.content == "Rust is very fast"));
+
+        let again = recover_from_wal(&wal(), &session)
+            .await
+            .expect("test fixture");
+        assert_eq!(again.replayed(), 0);
+        assert_eq!(again.recovered.len(), 2);
+        assert_eq!(session.lock().await.graph().node_count(), nodes);
+    }
+}
@@ -0,0 +1,162 @@
+//! WebSocket transport — JSON-RPC 2.0 over text frames, with bearer auth.
+
+use std::sync::Arc;
+
+use futures_util::{SinkExt, StreamExt};
+use serde_json::Value;
+use tokio::net::{TcpListener, TcpStream};
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::http::StatusCode;
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::protocol::ProtocolHandler;
+use crate::types::{JsonRpcError, JsonRpcMessage, McpError, McpResult, RequestId, JSONRPC_VERSION};
+
+use super::rate_limit::{rate_limited_error, TokenBucket};
+use super::{bearer_authorized, framing};
+
+/// WebSocket transport for clients that keep a bidirectional connection.
+///
+/// Each text frame carries one JSON-RPC message and each response goes
+/// back as its own text frame, so a client may pipeline requests. All
+/// connections share one handler and brain.
+pub struct WsTransport {
+    handler: Arc<ProtocolHandler>,
+    token: Option<String>,
+    rate_limit_rps: Option<u32>,
+}
+
+impl WsTransport {
+    /// Create a WebSocket transport with the given handler.
+    pub fn new(handler: ProtocolHandler) -> Self {
+        Self {
+            handler: Arc::new(handler),
+            token: None,
+            rate_limit_rps: None,
+        }
+    }
+
+    /// Require `Authorization: Bearer <token>` on the upgrade request.
+    pub fn with_token(mut self, token: Option<String>) -> Self {
+        self.token = token;
+        self
+    }
+
+    /// Allow each connection `rps` messages per second. Requests over the
+    /// limit get a `RATE_LIMITED` error; notifications are dropped.
+    pub fn with_rate_limit(mut self, rps: Option<u32>) -> Self {
+        self.rate_limit_rps = rps;
+        self
+    }
+
+    /// Listen on the given address and serve connections until an error.
+    pub async fn run(&self, addr: &str) -> McpResult<()> {
+        let listener = TcpListener::bind(addr).await.map_err(McpError::Io)?;
+        tracing::info!("WebSocket transport listening on ws://{addr}");
+        self.serve(listener).await
+    }
+
+    /// Serve connections accepted from an already bound listener.
+    pub async fn serve(&self, listener: TcpListener) -> McpResult<()> {
+        loop {
+            let (stream, peer) = listener.accept().await.map_err(McpError::Io)?;
+            let handler = self.handler.clone();
+            let token = self.token.clone();
+            let rate_limit_rps = self.rate_limit_rps;
+            tokio::spawn(async move {
+                if let Err(e) = serve_connection(stream, handler, token, rate_limit_rps).await {
+                    tracing::warn!("WebSocket connection from {peer} closed: {e}");
+                }
+            });
+        }
+    }
+}
+
+fn transport_error(e: impl std::fmt::Display) -> McpError {
+    McpError::Transport(e.to_string())
+}
+
+async fn serve_connection(
+    stream: TcpStream,
+    handler: Arc<ProtocolHandler>,
+    token: Option<String>,
+    rate_limit_rps: Option<u32>,
+) -> McpResult<()> {
+    // tungstenite's handshake callback fixes the error type.
+    #[allow(clippy::result_large_err)]
+    let check_auth = |request: &Request, response: Response| {
+        let header = request
+            .headers()
+            .get("authorization")
+            .and_then(|v| v.to_str().ok());
+        if bearer_authorized(header, token.as_deref()) {
+            Ok(response)
+        } else {
+            let mut error = ErrorResponse::new(Some("Unauthorized".to_string()));
+            *error.status_mut() = StatusCode::UNAUTHORIZED;
+            Err(error)
+        }
+    };
+    let mut ws = tokio_tungstenite::accept_hdr_async(stream, check_auth)
+        .await
+        .map_err(transport_error)?;
+
+    // The bucket belongs to this connection, so connections never contend.
+    let mut limit = rate_limit_rps.map(|rps| (rps, TokenBucket::new(rps)));
+    while let Some(frame) = ws.next().await {
+        let text = match frame.map_err(transport_error)? {
+            Message::Text(text) => text,
+            Message::Close(_) => break,
+            // Pings are answered by tungstenite; binary frames carry no JSON-RPC.
+            _ => continue,
+        };
+        let limited = limit
+            .as_mut()
+            .and_then(|(rps, bucket)| (!bucket.try_acquire()).then_some(*rps));
+        for reply in respond(&handler, &text, limited).await? {
+            let json = serde_json::to_string(&reply).map_err(McpError::Json)?;
+            ws.send(Message::Text(json))
+                .await
+                .map_err(transport_error)?;
+        }
+    }
+    Ok(())
+}
+
+/// Replies to one text frame: the handler's messages, or a parse error.
+/// `limited` holds the exceeded rate limit when the frame is over it.
+async fn respond(
+    handler: &ProtocolHandler,
+    text: &str,
+    limited: Option<u32>,
+) -> McpResult<Vec<Value>> {
+    match framing::parse_message(text) {
+        Ok(msg) => match (limited, msg) {
+            (None, msg) => {
+                // Streamed results arrive as chunk notifications before the response.
+                Ok(handler.handle_message_streamed(msg).await)
+            }
+            (Some(rps), JsonRpcMessage::Request(request)) => {
+                let value = serde_json::to_value(rate_limited_error(request.id, rps))
+                    .map_err(|err| McpError::InternalError(err.to_string()))?;
+                Ok(vec![value])
+            }
+            (Some(_), _) => Ok(Vec::new()),
+        },
+        Err(e) => {
+            tracing::warn!("Parse error: {e}");
+            let error_response = JsonRpcError {
+                jsonrpc: JSONRPC_VERSION.to_string(),
+                id: RequestId::Null,
+                error: crate::types::JsonRpcErrorObject {
+                    code: e.code(),
+                    message: e.to_string(),
+                    data: None,
+                },
+            };
+            let value = serde_json::to_value(error_response)
+                .map_err(|err| McpError::InternalError(err.to_string()))?;
+            Ok(vec![value])
+        }
+    }
+}
@@ -1,5 +1,8 @@
 //! Error types and JSON-RPC error codes for the MCP server.
 
+use std::path::PathBuf;
+use std::time::Duration;
+
 use super::message::{JsonRpcError, JsonRpcErrorObject, RequestId, JSONRPC_VERSION};
 
 /// Standard JSON-RPC 2.0 error codes.
@@ -28,19 +31,24 @@ pub mod mcp_error_codes {
     pub const TOOL_NOT_FOUND: i32 = -32803;
     /// Prompt not found.
     pub const PROMPT_NOT_FOUND: i32 = -32804;
+    /// The request was refused by the rate limit.
+    pub const RATE_LIMITED: i32 = -32805;
+    /// The request exceeded its time limit.
+    pub const REQUEST_TIMEOUT: i32 = -32806;
     /// AgenticMemory specific: Node not found.
     pub const NODE_NOT_FOUND: i32 = -32850;
     /// AgenticMemory specific: Session not found.
     pub const SESSION_NOT_FOUND: i32 = -32851;
     /// AgenticMemory specific: Invalid graph operation.
     pub const INVALID_GRAPH_OP: i32 = -32852;
+    /// AgenticMemory specific: The brain file is locked by another process.
+    /// Transient; the request can be retried.
+    pub const LOCK_TIMEOUT: i32 = -32853;
 
     /// Server: Unauthorized (missing or invalid bearer token).
     pub const UNAUTHORIZED: i32 = -32900;
     /// Server: User not found (multi-tenant, missing X-User-ID header).
     pub const USER_NOT_FOUND: i32 = -32901;
-    /// Server: Rate limited.
-    pub const RATE_LIMITED: i32 = -32902;
 }
 
 /// All errors that can occur in the MCP server.
@@ -70,6 +78,15 @@ pub enum McpError {
     #[error("Request cancelled")]
     RequestCancelled,
 
+    /// A tool call ran past its timeout and was abandoned.
+    #[error("Request timed out: {tool} exceeded {timeout_ms} ms")]
+    RequestTimeout {
+        /// Tool that timed out.
+        tool: String,
+        /// Timeout that applied.
+        timeout_ms: u64,
+    },
+
     /// Content exceeds size limits.
     #[error("Content too large: {size} bytes exceeds {max} bytes")]
     ContentTooLarge {
@@ -103,6 +120,15 @@ pub enum McpError {
     #[error("Invalid graph operation: {0}")]
     InvalidGraphOp(String),
 
+    /// Timed out waiting for another process to release the brain file lock.
+    #[error("Timed out after {} ms waiting for file lock: {}", waited.as_millis(), path.display())]
+    LockTimeout {
+        /// Lock file that stayed held.
+        path: PathBuf,
+        /// How long we waited.
+        waited: Duration,
+    },
+
     /// Transport-level error.
     #[error("Transport error: {0}")]
     Transport(String),
@@ -140,6 +166,8 @@ impl McpError {
                 | McpError::MethodNotFound(_)
                 | McpError::ToolNotFound(_)
                 | McpError::RequestCancelled
+                | McpError::RequestTimeout { .. }
+                | McpError::LockTimeout { .. }
                 | McpError::ContentTooLarge { .. }
                 | McpError::ResourceNotFound(_)
                 | McpError::PromptNotFound(_)
@@ -159,6 +187,7 @@ impl McpError {
             McpError::InvalidParams(_) => INVALID_PARAMS,
             McpError::InternalError(_) => INTERNAL_ERROR,
             McpError::RequestCancelled => REQUEST_CANCELLED,
+            McpError::RequestTimeout { .. } => REQUEST_TIMEOUT,
             McpError::ContentTooLarge { .. } => CONTENT_TOO_LARGE,
             McpError::ResourceNotFound(_) => RESOURCE_NOT_FOUND,
             McpError::ToolNotFound(_) => TOOL_NOT_FOUND,
@@ -166,6 +195,7 @@ impl McpError {
             McpError::NodeNotFound(_) => NODE_NOT_FOUND,
             McpError::SessionNotFound(_) => SESSION_NOT_FOUND,
             McpError::InvalidGraphOp(_) => INVALID_GRAPH_OP,
+            McpError::LockTimeout { .. } => LOCK_TIMEOUT,
             McpError::Transport(_) => INTERNAL_ERROR,
             McpError::Io(_) => INTERNAL_ERROR,
             McpError::Json(_) => PARSE_ERROR,
@@ -175,7 +205,14 @@ impl McpError {
         }
     }
 
-    /// Convert this error into a JSON-RPC error response.
+    /// Returns true if the failure is transient and the same request may
+    /// succeed when retried later.
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, McpError::LockTimeout { .. })
+    }
+
+    /// Convert this error into a JSON-RPC error response. Retryable errors
+    /// carry `{"retryable": true}` in `data`.
     pub fn to_json_rpc_error(&self, id: RequestId) -> JsonRpcError {
         JsonRpcError {
             jsonrpc: JSONRPC_VERSION.to_string(),
@@ -183,7 +220,9 @@ impl McpError {
             error: JsonRpcErrorObject {
                 code: self.code(),
                 message: self.to_string(),
-                data: None,
+                data: self
+                    .is_retryable()
+                    .then(|| serde_json::json!({ "retryable": true })),
             },
         }
     }
@@ -191,7 +230,12 @@ impl McpError {
 
 impl From<agentic_memory::AmemError> for McpError {
     fn from(e: agentic_memory::AmemError) -> Self {
-        McpError::AgenticMemory(e.to_string())
+        match e {
+            agentic_memory::AmemError::LockTimeout { path, waited } => {
+                McpError::LockTimeout { path, waited }
+            }
+            e => McpError::AgenticMemory(e.to_string()),
+        }
     }
 }
 
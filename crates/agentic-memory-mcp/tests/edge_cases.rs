@@ -355,6 +355,64 @@ async fn test_memory_query_with_nonexistent_type() {
     assert_eq!(parsed["count"], 0);
 }
 
+#[tokio::test]
+async fn test_memory_query_tags_match_all_and_any() {
+    let session = create_test_session();
+    for (content, project) in [("a", "foo"), ("b", "bar"), ("c", "baz")] {
+        ToolRegistry::call(
+            "memory_add",
+            Some(json!({"event_type": "fact", "content": content})),
+            &session,
+        )
+        .await
+        .unwrap();
+        let mut guard = session.lock().await;
+        let graph = guard.graph_mut();
+        let id = graph.node_count() as u64 - 1;
+        let tags = [
+            ("project".to_string(), project.to_string()),
+            ("role".to_string(), "planner".to_string()),
+        ];
+        graph.set_node_tags(id, tags.into()).unwrap();
+    }
+
+    let query = |args: serde_json::Value| {
+        let session = session.clone();
+        async move {
+            let result = ToolRegistry::call("memory_query", Some(args), &session)
+                .await
+                .unwrap();
+            let text = match &result.content[0] {
+                ToolContent::Text { text } => text.clone(),
+                _ => panic!("Expected text"),
+            };
+            serde_json::from_str::<serde_json::Value>(&text).unwrap()
+        }
+    };
+
+    let parsed = query(json!({"tags": ["project=foo", "role=planner"]})).await;
+    assert_eq!(parsed["count"], 1);
+    assert_eq!(parsed["tag_counts"]["role=planner"], 1);
+
+    let parsed = query(json!({"tags": ["project=foo", "project=bar"], "tag_match": "any"})).await;
+    assert_eq!(parsed["count"], 2);
+    assert_eq!(parsed["tag_counts"]["project=foo"], 1);
+    assert_eq!(parsed["tag_counts"]["project=bar"], 1);
+
+    let parsed = query(json!({})).await;
+    assert!(parsed.get("tag_counts").is_none());
+
+    for bad in [
+        json!({"tags": ["no-equals"]}),
+        json!({"tags": ["project=foo"], "tag_match": "some"}),
+        json!({"tags": ["project=foo", "project=bar"]}),
+    ] {
+        assert!(ToolRegistry::call("memory_query", Some(bad), &session)
+            .await
+            .is_err());
+    }
+}
+
 #[tokio::test]
 async fn test_memory_query_null_params() {
     let session = create_test_session();
@@ -407,6 +465,120 @@ async fn test_memory_correct_missing_new_content() {
     assert!(result.is_err());
 }
 
+#[tokio::test]
+async fn test_memory_correct_sets_confidence() {
+    let session = create_test_session();
+    ToolRegistry::call(
+        "memory_add",
+        Some(json!({"event_type": "fact", "content": "old", "confidence": 0.5})),
+        &session,
+    )
+    .await
+    .unwrap();
+
+    ToolRegistry::call(
+        "memory_correct",
+        Some(json!({"old_node_id": 0, "new_content": "new", "confidence": 0.7})),
+        &session,
+    )
+    .await
+    .unwrap();
+
+    let session = session.lock().await;
+    let new_node = session.graph().get_node(1).unwrap();
+    assert!((new_node.confidence - 0.7).abs() < f32::EPSILON);
+}
+
+#[tokio::test]
+async fn test_memory_correct_same_content_is_noop() {
+    let session = create_test_session();
+    ToolRegistry::call(
+        "memory_add",
+        Some(json!({"event_type": "fact", "content": "the build uses cargo"})),
+        &session,
+    )
+    .await
+    .unwrap();
+
+    let correct = |reassert: bool| {
+        let session = session.clone();
+        let args = json!({
+            "old_node_id": 0,
+            "new_content": "the build uses cargo",
+            "reassert": reassert,
+        });
+        async move {
+            let result = ToolRegistry::call("memory_correct", Some(args), &session)
+                .await
+                .unwrap();
+            let text = match &result.content[0] {
+                ToolContent::Text { text } => text.clone(),
+                _ => panic!("Expected text"),
+            };
+            serde_json::from_str::<serde_json::Value>(&text).unwrap()
+        }
+    };
+
+    let parsed = correct(false).await;
+    assert_eq!(parsed["no_change"], true);
+    assert_eq!(parsed["new_node_id"], 0);
+    {
+        let session = session.lock().await;
+        assert_eq!(session.graph().node_count(), 1);
+        assert!(session.graph().get_node(0).unwrap().confidence > 0.0);
+    }
+
+    let parsed = correct(true).await;
+    assert_eq!(parsed["no_change"], false);
+    assert_eq!(parsed["new_node_id"], 1);
+    assert_eq!(session.lock().await.graph().node_count(), 2);
+}
+
+#[tokio::test]
+async fn test_memory_correct_embedding_model_mismatch() {
+    let session = create_test_session();
+    let dim = session.lock().await.graph().dimension();
+    ToolRegistry::call(
+        "memory_add",
+        Some(json!({"event_type": "fact", "content": "old"})),
+        &session,
+    )
+    .await
+    .unwrap();
+
+    let correct = |model: &str, strict: bool| {
+        json!({
+            "old_node_id": 0,
+            "new_content": "new",
+            "feature_vec": vec![0.5f32; dim],
+            "embedding_model": model,
+            "strict": strict,
+        })
+    };
+
+    ToolRegistry::call("memory_correct", Some(correct("embed-a", false)), &session)
+        .await
+        .unwrap();
+    assert_eq!(
+        session.lock().await.graph().embedding_model(),
+        Some("embed-a")
+    );
+
+    let result = ToolRegistry::call("memory_correct", Some(correct("embed-b", false)), &session)
+        .await
+        .unwrap();
+    let text = match &result.content[0] {
+        ToolContent::Text { text } => text,
+        _ => panic!("Expected text"),
+    };
+    let parsed: serde_json::Value = serde_json::from_str(text).unwrap();
+    assert!(parsed["embedding_model_warning"].is_string());
+
+    let result =
+        ToolRegistry::call("memory_correct", Some(correct("embed-b", true)), &session).await;
+    assert!(result.is_err());
+}
+
 // ============================================================
 // memory_resolve Edge Cases
 // ============================================================
@@ -505,6 +677,80 @@ async fn test_memory_traverse_single_node_no_edges() {
     assert!(!traversed["visited"].as_array().unwrap().is_empty());
 }
 
+#[tokio::test]
+async fn test_memory_traverse_samples_hub_neighbors() {
+    let session = create_test_session();
+    let add = |args: serde_json::Value| {
+        let session = session.clone();
+        async move {
+            let result = ToolRegistry::call("memory_add", Some(args), &session)
+                .await
+                .unwrap();
+            match &result.content[0] {
+                ToolContent::Text { text } => serde_json::from_str::<serde_json::Value>(text)
+                    .unwrap()["node_id"]
+                    .as_u64()
+                    .unwrap(),
+                _ => panic!("Expected text"),
+            }
+        }
+    };
+    let hub = add(json!({"event_type": "fact", "content": "hub"})).await;
+    for i in 0..15u32 {
+        add(json!({
+            "event_type": "fact",
+            "content": format!("spoke {i}"),
+            "edges": [{"target_id": hub, "edge_type": "related_to", "weight": (i + 1) as f32 / 15.0}]
+        }))
+        .await;
+    }
+
+    let call = |args: serde_json::Value| {
+        let session = session.clone();
+        async move {
+            let result = ToolRegistry::call("memory_traverse", Some(args), &session)
+                .await
+                .unwrap();
+            match &result.content[0] {
+                ToolContent::Text { text } => {
+                    serde_json::from_str::<serde_json::Value>(text).unwrap()
+                }
+                _ => panic!("Expected text"),
+            }
+        }
+    };
+
+    // Default cap keeps the 10 highest-weight spokes. Only the spoke edges
+    // are followed, not the session's own links between the nodes.
+    let sampled = call(json!({
+        "start_id": hub,
+        "direction": "backward",
+        "edge_types": ["related_to"]
+    }))
+    .await;
+    assert_eq!(sampled["visited_count"], 11);
+    assert_eq!(sampled["sampled"], true);
+    assert_eq!(sampled["total_available"], 15);
+    let min_weight = sampled["edges_traversed"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["weight"].as_f64().unwrap())
+        .fold(f64::MAX, f64::min);
+    assert!(min_weight > 5.0 / 15.0);
+
+    // 0 disables sampling for exhaustive traversal.
+    let full = call(json!({
+        "start_id": hub,
+        "direction": "backward",
+        "edge_types": ["related_to"],
+        "sample_per_level": 0
+    }))
+    .await;
+    assert_eq!(full["visited_count"], 16);
+    assert!(full.get("sampled").is_none());
+}
+
 // ============================================================
 // memory_context Edge Cases
 // ============================================================
@@ -0,0 +1,91 @@
+//! Phase 12: OTLP span export (`otel` feature).
+#![cfg(feature = "otel")]
+
+mod common;
+
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_sdk::export::trace::SpanData;
+use opentelemetry_sdk::testing::trace::InMemorySpanExporter;
+use opentelemetry_sdk::trace::TracerProvider;
+use serde_json::json;
+use tracing_subscriber::layer::SubscriberExt;
+
+use agentic_memory_mcp::protocol::ProtocolHandler;
+use agentic_memory_mcp::telemetry::span_layer;
+use agentic_memory_mcp::types::*;
+
+use common::fixtures::create_test_session;
+
+fn tool_call(id: i64, name: &str, arguments: serde_json::Value) -> JsonRpcMessage {
+    JsonRpcMessage::Request(JsonRpcRequest {
+        jsonrpc: "2.0".to_string(),
+        id: RequestId::Number(id),
+        method: "tools/call".to_string(),
+        params: Some(json!({ "name": name, "arguments": arguments })),
+    })
+}
+
+fn attribute(span: &SpanData, key: &str) -> Option<String> {
+    span.attributes
+        .iter()
+        .find(|kv| kv.key.as_str() == key)
+        .map(|kv| kv.value.to_string())
+}
+
+#[tokio::test]
+async fn test_span_exported_per_tool_call() {
+    let exporter = InMemorySpanExporter::default();
+    let provider = TracerProvider::builder()
+        .with_simple_exporter(exporter.clone())
+        .build();
+    let subscriber = tracing_subscriber::registry().with(span_layer(provider.tracer("test")));
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let handler = ProtocolHandler::new(create_test_session()).with_tenant("alice");
+    let secret = "launch codes are 0000";
+    handler
+        .handle_message(tool_call(
+            1,
+            "memory_add",
+            json!({"event_type": "fact", "content": secret}),
+        ))
+        .await
+        .unwrap();
+    handler
+        .handle_message(tool_call(2, "memory_query", json!({"max_results": 5})))
+        .await
+        .unwrap();
+    handler
+        .handle_message(tool_call(3, "memory_get_nothing", json!({})))
+        .await
+        .unwrap();
+
+    let _ = provider.force_flush();
+    let spans = exporter.get_finished_spans().unwrap();
+    let tool_spans: Vec<&SpanData> = spans.iter().filter(|s| s.name == "tool_call").collect();
+    let tools: Vec<String> = tool_spans
+        .iter()
+        .filter_map(|s| attribute(s, "tool"))
+        .collect();
+    assert_eq!(
+        tools,
+        vec!["memory_add", "memory_query", "memory_get_nothing"]
+    );
+
+    for span in &tool_spans {
+        assert_eq!(attribute(span, "tenant").as_deref(), Some("alice"));
+        // Content never leaves the host on a span.
+        assert!(span
+            .attributes
+            .iter()
+            .all(|kv| !kv.value.to_string().contains(secret)));
+    }
+    assert_eq!(
+        attribute(tool_spans[0], "is_error").as_deref(),
+        Some("false")
+    );
+    assert_eq!(
+        attribute(tool_spans[2], "is_error").as_deref(),
+        Some("true")
+    );
+}
@@ -0,0 +1,103 @@
+//! Phase 13: shared outbound HTTP client (`net` feature).
+#![cfg(feature = "net")]
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+use agentic_memory_mcp::http_client::{build_client, get_with_retry, HttpClientConfig};
+
+/// Serve canned responses in order, one per connection, and count requests.
+async fn mock_server(responses: Vec<&'static str>) -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let hits = Arc::new(AtomicUsize::new(0));
+    let counter = hits.clone();
+    tokio::spawn(async move {
+        for body in responses {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = socket.read(&mut buf).await;
+            counter.fetch_add(1, Ordering::SeqCst);
+            let response =
+                format!("HTTP/1.1 {body}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+            let _ = socket.write_all(response.as_bytes()).await;
+        }
+    });
+    (format!("http://{addr}/"), hits)
+}
+
+fn fast_retries() -> HttpClientConfig {
+    HttpClientConfig {
+        retry_backoff: Duration::from_millis(1),
+        ..HttpClientConfig::default()
+    }
+}
+
+#[tokio::test]
+async fn test_get_retries_server_errors() {
+    let (url, hits) = mock_server(vec!["503 Service Unavailable", "200 OK"]).await;
+    let config = fast_retries();
+    let client = build_client(&config).unwrap();
+
+    let response = get_with_retry(&client, &config, &url).await.unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(hits.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_get_does_not_retry_client_errors() {
+    let (url, hits) = mock_server(vec!["404 Not Found", "200 OK"]).await;
+    let config = fast_retries();
+    let client = build_client(&config).unwrap();
+
+    let response = get_with_retry(&client, &config, &url).await.unwrap();
+    assert_eq!(response.status(), 404);
+    assert_eq!(hits.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_get_gives_up_after_max_retries() {
+    let (url, hits) = mock_server(vec!["500 Internal Server Error"; 3]).await;
+    let config = fast_retries();
+    let client = build_client(&config).unwrap();
+
+    let response = get_with_retry(&client, &config, &url).await.unwrap();
+    assert_eq!(response.status(), 500);
+    assert_eq!(hits.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn test_request_timeout_applies() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        // Accept and never answer.
+        let mut held = Vec::new();
+        while let Ok((socket, _)) = listener.accept().await {
+            held.push(socket);
+        }
+    });
+    let config = HttpClientConfig {
+        request_timeout: Duration::from_millis(100),
+        max_retries: 0,
+        ..HttpClientConfig::default()
+    };
+    let client = build_client(&config).unwrap();
+
+    let err = get_with_retry(&client, &config, &format!("http://{addr}/"))
+        .await
+        .unwrap_err();
+    assert!(err.is_timeout());
+}
+
+#[test]
+fn test_backoff_doubles_and_caps() {
+    let config = HttpClientConfig::default();
+    assert_eq!(config.backoff(0), Duration::from_millis(200));
+    assert_eq!(config.backoff(1), Duration::from_millis(400));
+    assert_eq!(config.backoff(20), Duration::from_secs(10));
+}
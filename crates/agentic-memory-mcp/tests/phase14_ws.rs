@@ -0,0 +1,144 @@
+//! Phase 14: WebSocket transport (`ws` feature).
+#![cfg(feature = "ws")]
+
+mod common;
+
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+use agentic_memory_mcp::protocol::ProtocolHandler;
+use agentic_memory_mcp::transport::WsTransport;
+
+use common::fixtures::create_test_session;
+
+type Client = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Start a server on an ephemeral port and return its `ws://` URL.
+async fn start_server(token: Option<&str>) -> String {
+    start_limited_server(token, None).await
+}
+
+/// Like `start_server`, allowing each connection `rps` messages per second.
+async fn start_limited_server(token: Option<&str>, rps: Option<u32>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let transport = WsTransport::new(ProtocolHandler::new(create_test_session()))
+        .with_token(token.map(str::to_string))
+        .with_rate_limit(rps);
+    tokio::spawn(async move { transport.serve(listener).await });
+    format!("ws://{addr}/")
+}
+
+/// Send one request and return the response with the same ID.
+async fn call(client: &mut Client, request: Value) -> Value {
+    client
+        .send(Message::Text(request.to_string()))
+        .await
+        .unwrap();
+    loop {
+        let Message::Text(text) = client.next().await.unwrap().unwrap() else {
+            continue;
+        };
+        let value: Value = serde_json::from_str(&text).unwrap();
+        if value["id"] == request["id"] {
+            return value;
+        }
+    }
+}
+
+fn tool_call(id: i64, name: &str, arguments: Value) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "method": "tools/call",
+        "params": { "name": name, "arguments": arguments }
+    })
+}
+
+fn tool_text(response: &Value) -> Value {
+    let text = response["result"]["content"][0]["text"].as_str().unwrap();
+    serde_json::from_str(text).unwrap()
+}
+
+#[tokio::test]
+async fn test_ws_tools_call_round_trip() {
+    let url = start_server(None).await;
+    let (mut client, _) = tokio_tungstenite::connect_async(url).await.unwrap();
+
+    let added = call(
+        &mut client,
+        tool_call(
+            1,
+            "memory_add",
+            json!({ "event_type": "fact", "content": "User prefers Rust" }),
+        ),
+    )
+    .await;
+    assert!(tool_text(&added)["node_id"].as_u64().is_some());
+
+    let stats = call(&mut client, tool_call(2, "memory_stats", json!({}))).await;
+    assert_eq!(tool_text(&stats)["node_count"], 1);
+
+    client.close(None).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_ws_parse_error_gets_json_rpc_error() {
+    let url = start_server(None).await;
+    let (mut client, _) = tokio_tungstenite::connect_async(url).await.unwrap();
+
+    client
+        .send(Message::Text("{not json".to_string()))
+        .await
+        .unwrap();
+    let Message::Text(text) = client.next().await.unwrap().unwrap() else {
+        panic!("expected a text frame");
+    };
+    let value: Value = serde_json::from_str(&text).unwrap();
+    assert!(value["id"].is_null());
+    assert!(value["error"]["code"].as_i64().is_some());
+}
+
+#[tokio::test]
+async fn test_ws_requires_bearer_token() {
+    let url = start_server(Some("secret")).await;
+
+    let rejected = tokio_tungstenite::connect_async(url.as_str()).await;
+    assert!(rejected.is_err(), "upgrade without a token must fail");
+
+    let mut request = url.as_str().into_client_request().unwrap();
+    request
+        .headers_mut()
+        .insert("authorization", "Bearer secret".parse().unwrap());
+    let (mut client, _) = tokio_tungstenite::connect_async(request).await.unwrap();
+
+    let stats = call(&mut client, tool_call(1, "memory_stats", json!({}))).await;
+    assert_eq!(tool_text(&stats)["node_count"], 0);
+}
+
+#[tokio::test]
+async fn test_ws_rate_limit_per_connection() {
+    let url = start_limited_server(None, Some(3)).await;
+    let (mut client, _) = tokio_tungstenite::connect_async(url.as_str())
+        .await
+        .unwrap();
+
+    for id in 1..=3 {
+        let stats = call(&mut client, tool_call(id, "memory_stats", json!({}))).await;
+        assert_eq!(tool_text(&stats)["node_count"], 0);
+    }
+    let limited = call(&mut client, tool_call(4, "memory_stats", json!({}))).await;
+    assert_eq!(limited["error"]["code"], -32805);
+    assert!(limited.get("result").is_none());
+
+    // A second connection has a bucket of its own.
+    let (mut other, _) = tokio_tungstenite::connect_async(url.as_str())
+        .await
+        .unwrap();
+    let stats = call(&mut other, tool_call(1, "memory_stats", json!({}))).await;
+    assert_eq!(tool_text(&stats)["node_count"], 0);
+}
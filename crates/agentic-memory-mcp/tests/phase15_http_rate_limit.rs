@@ -0,0 +1,142 @@
+//! Phase 15: rate limiting on the `/mcp` HTTP endpoint (`sse` feature).
+#![cfg(feature = "sse")]
+
+mod common;
+
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Mutex;
+
+use agentic_memory_mcp::protocol::ProtocolHandler;
+use agentic_memory_mcp::session::tenant::TenantRegistry;
+use agentic_memory_mcp::transport::sse::ServerMode;
+use agentic_memory_mcp::transport::SseTransport;
+use agentic_memory_mcp::types::{mcp_error_codes, MemoryMode};
+
+use common::fixtures::create_test_session;
+
+/// Serve `transport` on an ephemeral port and return its address.
+async fn start_server(transport: SseTransport) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { transport.serve(listener).await });
+    addr.to_string()
+}
+
+/// A keep-alive HTTP/1.1 connection, so consecutive requests share it.
+struct Connection {
+    stream: BufReader<TcpStream>,
+}
+
+impl Connection {
+    async fn open(addr: &str) -> Self {
+        Self {
+            stream: BufReader::new(TcpStream::connect(addr).await.unwrap()),
+        }
+    }
+
+    /// POST one JSON-RPC request to `/mcp`; returns the status and body.
+    async fn post(&mut self, user_id: Option<&str>, body: &Value) -> (u16, Value) {
+        let body = body.to_string();
+        let user_header = user_id
+            .map(|id| format!("X-User-ID: {id}\r\n"))
+            .unwrap_or_default();
+        let request = format!(
+            "POST /mcp HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\n\
+             {user_header}Content-Length: {}\r\n\r\n{body}",
+            body.len()
+        );
+        self.stream
+            .get_mut()
+            .write_all(request.as_bytes())
+            .await
+            .unwrap();
+
+        let mut status_line = String::new();
+        self.stream.read_line(&mut status_line).await.unwrap();
+        let status = status_line
+            .split_whitespace()
+            .nth(1)
+            .unwrap()
+            .parse()
+            .unwrap();
+        let mut content_length = 0;
+        loop {
+            let mut line = String::new();
+            self.stream.read_line(&mut line).await.unwrap();
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().unwrap();
+                }
+            }
+        }
+        let mut body = vec![0u8; content_length];
+        self.stream.read_exact(&mut body).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or_default())
+    }
+}
+
+fn stats(id: i64) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "method": "tools/call",
+        "params": { "name": "memory_stats", "arguments": {} }
+    })
+}
+
+#[tokio::test]
+async fn test_http_rate_limit_per_connection() {
+    let transport =
+        SseTransport::new(ProtocolHandler::new(create_test_session())).with_rate_limit(Some(3));
+    let addr = start_server(transport).await;
+    let mut client = Connection::open(&addr).await;
+
+    for id in 1..=3 {
+        let (status, body) = client.post(None, &stats(id)).await;
+        assert_eq!(status, 200);
+        assert!(body.get("result").is_some(), "{body}");
+    }
+    let (status, body) = client.post(None, &stats(4)).await;
+    assert_eq!(status, 429);
+    assert_eq!(body["error"]["code"], mcp_error_codes::RATE_LIMITED);
+    assert_eq!(body["error"]["code"], -32805);
+
+    // A second connection has a bucket of its own.
+    let mut other = Connection::open(&addr).await;
+    let (status, _) = other.post(None, &stats(1)).await;
+    assert_eq!(status, 200);
+}
+
+#[tokio::test]
+async fn test_http_rate_limit_per_user_in_multi_tenant_mode() {
+    let dir = tempfile::tempdir().unwrap();
+    let mode = ServerMode::MultiTenant {
+        data_dir: dir.path().to_path_buf(),
+        registry: Arc::new(Mutex::new(TenantRegistry::new(dir.path()))),
+        memory_mode: MemoryMode::Smart,
+    };
+    let transport = SseTransport::with_config(None, mode).with_rate_limit(Some(2));
+    let addr = start_server(transport).await;
+
+    // The bucket belongs to the user, not the connection.
+    let mut first = Connection::open(&addr).await;
+    let mut second = Connection::open(&addr).await;
+    assert_eq!(first.post(Some("alice"), &stats(1)).await.0, 200);
+    assert_eq!(second.post(Some("alice"), &stats(2)).await.0, 200);
+    let (status, body) = first.post(Some("alice"), &stats(3)).await;
+    assert_eq!(status, 429);
+    assert_eq!(body["error"]["code"], -32805);
+
+    // Another user is unaffected.
+    let (status, body) = second.post(Some("bob"), &stats(1)).await;
+    assert_eq!(status, 200);
+    assert!(body.get("result").is_some(), "{body}");
+}
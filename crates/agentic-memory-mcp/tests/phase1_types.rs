@@ -90,6 +90,30 @@ fn test_mcp_error_to_json_rpc() {
     assert!(rpc_err.error.message.contains("unknown_tool"));
 }
 
+#[test]
+fn test_lock_timeout_is_retryable() {
+    let err = McpError::from(agentic_memory::AmemError::LockTimeout {
+        path: std::path::PathBuf::from("/tmp/brain.amem.lock"),
+        waited: std::time::Duration::from_secs(10),
+    });
+    assert!(matches!(err, McpError::LockTimeout { .. }));
+    assert!(err.is_protocol_error());
+    assert!(err.is_retryable());
+
+    let rpc_err = err.to_json_rpc_error(RequestId::Number(7));
+    assert_eq!(rpc_err.error.code, mcp_error_codes::LOCK_TIMEOUT);
+    assert!(rpc_err.error.message.contains("10000 ms"));
+    assert_eq!(rpc_err.error.data, Some(json!({"retryable": true})));
+
+    let err = McpError::Io(std::io::Error::other("disk gone"));
+    assert!(!err.is_retryable());
+    assert!(err
+        .to_json_rpc_error(RequestId::Number(8))
+        .error
+        .data
+        .is_none());
+}
+
 #[test]
 fn test_server_capabilities() {
     let caps = ServerCapabilities::default_capabilities();
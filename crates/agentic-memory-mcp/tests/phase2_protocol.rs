@@ -161,3 +161,74 @@ async fn test_shutdown() {
     assert!(response.get("result").is_some());
     assert!(handler.shutdown_requested());
 }
+
+#[tokio::test]
+async fn test_tool_call_times_out_and_server_recovers() {
+    use agentic_memory_mcp::protocol::ToolTimeouts;
+
+    let session = create_test_session();
+    let handler = ProtocolHandler::new(session.clone()).with_tool_timeouts(ToolTimeouts::new(100));
+    let call = |id| {
+        make_request(
+            id,
+            "tools/call",
+            Some(json!({ "name": "memory_stats", "arguments": {} })),
+        )
+    };
+
+    // A stuck call elsewhere holds the session: this one gives up instead of hanging.
+    let stuck = session.lock().await;
+    let response = tokio::time::timeout(
+        std::time::Duration::from_secs(5),
+        handler.handle_message(call(1)),
+    )
+    .await
+    .expect("handler should not hang past the tool timeout")
+    .unwrap();
+    assert_eq!(response["error"]["code"], mcp_error_codes::REQUEST_TIMEOUT);
+    assert!(response["error"]["message"]
+        .as_str()
+        .unwrap()
+        .contains("memory_stats"));
+    drop(stuck);
+
+    let response = handler.handle_message(call(2)).await.unwrap();
+    assert!(response.get("error").is_none(), "{response}");
+    assert!(response["result"]["content"].is_array());
+}
+
+#[test]
+fn test_tool_result_cap_truncates_content() {
+    use agentic_memory_mcp::protocol::budget::cap_tool_result;
+
+    let nodes: Vec<serde_json::Value> = (0..40)
+        .map(|i| {
+            json!({
+                "id": i,
+                "event_type": "fact",
+                // Multibyte characters make naive byte slicing panic.
+                "content": "héllo wörld — ".repeat(20 + i * 10),
+            })
+        })
+        .collect();
+    let mut result = ToolCallResult::json(&json!({"count": nodes.len(), "nodes": nodes}));
+
+    let budget = 8 * 1024;
+    assert!(serde_json::to_vec(&result).unwrap().len() > budget);
+    assert!(cap_tool_result(&mut result, budget));
+    assert!(serde_json::to_vec(&result).unwrap().len() <= budget);
+
+    let text = match &result.content[0] {
+        ToolContent::Text { text } => text,
+        _ => panic!("Expected text content"),
+    };
+    let parsed: serde_json::Value = serde_json::from_str(text).unwrap();
+    assert_eq!(parsed["truncated"], true);
+    assert_eq!(parsed["full_count"], 40);
+    assert_eq!(parsed["nodes"].as_array().unwrap().len(), 40);
+
+    // Small results and a disabled cap are left alone.
+    let mut small = ToolCallResult::json(&json!({"nodes": [{"content": "short"}]}));
+    assert!(!cap_tool_result(&mut small, budget));
+    assert!(!cap_tool_result(&mut small, 0));
+}
@@ -33,6 +33,32 @@ async fn test_memory_add_fact() {
     assert_eq!(parsed["event_type"], "fact");
 }
 
+#[tokio::test]
+async fn test_memory_add_pinned() {
+    let session = create_test_session();
+    let result = ToolRegistry::call(
+        "memory_add",
+        Some(json!({
+            "event_type": "decision",
+            "content": "Ship on Fridays only after review",
+            "pinned": true
+        })),
+        &session,
+    )
+    .await
+    .unwrap();
+
+    let text = match &result.content[0] {
+        agentic_memory_mcp::types::ToolContent::Text { text } => text,
+        _ => panic!("Expected text content"),
+    };
+    let parsed: serde_json::Value = serde_json::from_str(text).unwrap();
+    let node_id = parsed["node_id"].as_u64().unwrap();
+
+    let session = session.lock().await;
+    assert!(session.graph().get_node(node_id).unwrap().pinned);
+}
+
 #[tokio::test]
 async fn test_memory_add_with_edges() {
     let session = create_test_session();
@@ -78,6 +104,45 @@ async fn test_memory_add_with_edges() {
     assert_eq!(parsed2["edges_created"], 2);
 }
 
+#[tokio::test]
+async fn test_memory_add_idempotency_key_dedupes_retries() {
+    let session = create_test_session();
+    let add = |content: &str, key: &str| {
+        let session = session.clone();
+        let args = json!({"event_type": "fact", "content": content, "idempotency_key": key});
+        async move {
+            let result = ToolRegistry::call("memory_add", Some(args), &session)
+                .await
+                .unwrap();
+            let text = match &result.content[0] {
+                agentic_memory_mcp::types::ToolContent::Text { text } => text.clone(),
+                _ => panic!("Expected text"),
+            };
+            serde_json::from_str::<serde_json::Value>(&text).unwrap()
+        }
+    };
+
+    let first = add("Deploy target is eu-west", "req-1").await;
+    let retry = add("Deploy target is eu-west", "req-1").await;
+    assert_eq!(first["created"], true);
+    assert_eq!(retry["created"], false);
+    assert_eq!(retry["node_id"], first["node_id"]);
+    assert_eq!(session.lock().await.graph().node_count(), 1);
+
+    // A different key is a different write.
+    let other = add("Deploy target is eu-west", "req-2").await;
+    assert_eq!(other["created"], true);
+    assert_ne!(other["node_id"], first["node_id"]);
+
+    // Keys are scoped to the session that used them.
+    ToolRegistry::call("session_start", Some(json!({})), &session)
+        .await
+        .unwrap();
+    let next_session = add("Deploy target is eu-west", "req-1").await;
+    assert_eq!(next_session["created"], true);
+    assert_eq!(session.lock().await.graph().node_count(), 3);
+}
+
 #[tokio::test]
 async fn test_memory_query_empty() {
     let session = create_test_session();
@@ -93,6 +158,73 @@ async fn test_memory_query_empty() {
     assert_eq!(parsed["count"], 0);
 }
 
+#[tokio::test]
+async fn test_memory_query_expand_episodes() {
+    let session = create_test_session();
+    let call = |args: serde_json::Value| {
+        let session = session.clone();
+        async move {
+            let result = ToolRegistry::call("memory_query", Some(args), &session)
+                .await
+                .unwrap();
+            let text = match &result.content[0] {
+                agentic_memory_mcp::types::ToolContent::Text { text } => text.clone(),
+                _ => panic!("Expected text"),
+            };
+            serde_json::from_str::<serde_json::Value>(&text).unwrap()
+        }
+    };
+
+    let result = ToolRegistry::call(
+        "memory_add",
+        Some(json!({"event_type": "episode", "content": "Debugged the deploy"})),
+        &session,
+    )
+    .await
+    .unwrap();
+    let text = match &result.content[0] {
+        agentic_memory_mcp::types::ToolContent::Text { text } => text,
+        _ => panic!("Expected text"),
+    };
+    let episode_id = serde_json::from_str::<serde_json::Value>(text).unwrap()["node_id"]
+        .as_u64()
+        .unwrap();
+    for content in [
+        "Deploy failed",
+        "Cause was a stale cache",
+        "Cleared the cache",
+    ] {
+        ToolRegistry::call(
+            "memory_add",
+            Some(json!({
+                "event_type": "fact",
+                "content": content,
+                "edges": [{"target_id": episode_id, "edge_type": "part_of"}]
+            })),
+            &session,
+        )
+        .await
+        .unwrap();
+    }
+
+    let plain = call(json!({"event_types": ["episode"]})).await;
+    assert_eq!(plain["count"], 1);
+    assert_eq!(plain["nodes"].as_array().unwrap().len(), 1);
+
+    let expanded = call(json!({
+        "event_types": ["episode"],
+        "expand_episodes": true,
+        "episode_limit": 2
+    }))
+    .await;
+    assert_eq!(expanded["count"], 1);
+    assert_eq!(expanded["expanded_count"], 2);
+    let nodes = expanded["nodes"].as_array().unwrap();
+    assert_eq!(nodes[1]["content"], "Deploy failed");
+    assert_eq!(nodes[1]["expanded_from_episode"], episode_id);
+    assert!(nodes[0].get("expanded_from_episode").is_none());
+}
+
 #[tokio::test]
 async fn test_memory_query_with_results() {
     let session = create_test_session();
@@ -143,6 +275,115 @@ async fn test_memory_query_with_results() {
     assert_eq!(parsed["count"], 1);
 }
 
+#[tokio::test]
+async fn test_memory_query_fields_projection() {
+    let session = create_test_session();
+    ToolRegistry::call(
+        "memory_add",
+        Some(json!({"event_type": "fact", "content": "A long fact body"})),
+        &session,
+    )
+    .await
+    .unwrap();
+
+    let result = ToolRegistry::call(
+        "memory_query",
+        Some(json!({"fields": ["id", "event_type"]})),
+        &session,
+    )
+    .await
+    .unwrap();
+    let text = match &result.content[0] {
+        agentic_memory_mcp::types::ToolContent::Text { text } => text,
+        _ => panic!("Expected text"),
+    };
+    let parsed: serde_json::Value = serde_json::from_str(text).unwrap();
+    let node = parsed["nodes"][0].as_object().unwrap();
+    assert_eq!(node.len(), 2);
+    assert_eq!(node["event_type"], "fact");
+    assert!(node.contains_key("id"));
+
+    let err = ToolRegistry::call(
+        "memory_query",
+        Some(json!({"fields": ["id", "body"]})),
+        &session,
+    )
+    .await
+    .unwrap_err();
+    assert!(err.to_string().contains("Unknown field 'body'"));
+}
+
+#[tokio::test]
+async fn test_context_feedback_refines_query() {
+    let session = create_test_session();
+    let mut ids = Vec::new();
+    for content in [
+        "Deploy pipeline uses cargo release builds",
+        "Deploy freeze during holidays",
+        "Cargo workspace builds each crate once",
+    ] {
+        let result = ToolRegistry::call(
+            "memory_add",
+            Some(json!({"event_type": "fact", "content": content})),
+            &session,
+        )
+        .await
+        .unwrap();
+        let text = match &result.content[0] {
+            agentic_memory_mcp::types::ToolContent::Text { text } => text,
+            _ => panic!("Expected text"),
+        };
+        ids.push(
+            serde_json::from_str::<serde_json::Value>(text).unwrap()["node_id"]
+                .as_u64()
+                .unwrap(),
+        );
+    }
+
+    let result = ToolRegistry::call(
+        "context_feedback",
+        Some(json!({
+            "query": "deploy",
+            "useful_ids": [ids[0]],
+            "not_useful_ids": [ids[1]],
+        })),
+        &session,
+    )
+    .await
+    .unwrap();
+    let text = match &result.content[0] {
+        agentic_memory_mcp::types::ToolContent::Text { text } => text,
+        _ => panic!("Expected text"),
+    };
+    let parsed: serde_json::Value = serde_json::from_str(text).unwrap();
+    let terms: Vec<&str> = parsed["refined_terms"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|t| t["term"].as_str().unwrap())
+        .collect();
+    assert!(terms.contains(&"deploy"));
+    assert!(
+        terms.contains(&"cargo"),
+        "useful terms are added: {terms:?}"
+    );
+    assert!(!terms.contains(&"holidays"), "not-useful terms are dropped");
+    // The cargo node is now reachable without mentioning deploy.
+    let result_ids: Vec<u64> = parsed["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["node_id"].as_u64().unwrap())
+        .collect();
+    assert_eq!(result_ids[0], ids[0]);
+    assert!(result_ids.contains(&ids[2]));
+
+    let err = ToolRegistry::call("context_feedback", Some(json!({})), &session)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("query or at least one useful_id"));
+}
+
 #[tokio::test]
 async fn test_memory_correct() {
     let session = create_test_session();
@@ -342,3 +583,126 @@ async fn test_memory_context() {
     assert_eq!(parsed["center_id"], id);
     assert!(parsed["node_count"].as_u64().unwrap() >= 1);
 }
+
+#[tokio::test]
+async fn test_memory_session_resume_left_off() {
+    let session = create_test_session();
+    let call = |name: &'static str, args: serde_json::Value| {
+        let session = session.clone();
+        async move {
+            let result = ToolRegistry::call(name, Some(args), &session)
+                .await
+                .unwrap();
+            let text = match &result.content[0] {
+                agentic_memory_mcp::types::ToolContent::Text { text } => text.clone(),
+                _ => panic!("Expected text"),
+            };
+            serde_json::from_str::<serde_json::Value>(&text).unwrap()
+        }
+    };
+
+    // Cold start: nothing to resume.
+    let parsed = call("memory_session_resume", json!({})).await;
+    assert!(parsed["left_off"].is_null());
+    assert!(parsed["message"].as_str().unwrap().contains("empty"));
+
+    let first_session = parsed["current_session"].as_u64().unwrap();
+    let mut ids = Vec::new();
+    for (event_type, content) in [
+        ("fact", "CI takes 40 minutes"),
+        ("decision", "Split the test job in two"),
+        ("fact", "Half of the split is done"),
+    ] {
+        let added = call(
+            "memory_add",
+            json!({"event_type": event_type, "content": content}),
+        )
+        .await;
+        ids.push(added["node_id"].as_u64().unwrap());
+    }
+
+    // A restarted agent begins a fresh session and asks where it left off.
+    call("session_start", json!({})).await;
+    let parsed = call("memory_session_resume", json!({"last_nodes": 2})).await;
+    let left_off = &parsed["left_off"];
+    assert_eq!(left_off["session_id"], first_session);
+    assert_eq!(left_off["node_count"], 3);
+    assert_eq!(left_off["node_ids"], json!(ids));
+    let tail: Vec<u64> = left_off["last_nodes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|n| n["id"].as_u64().unwrap())
+        .collect();
+    assert_eq!(tail, ids[1..]);
+    assert_eq!(left_off["decisions"][0]["id"], ids[1]);
+    assert_eq!(left_off["decisions"].as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_memory_delete_where() {
+    let session = create_test_session();
+    let mut ids = Vec::new();
+    for (event_type, content, confidence) in [
+        ("inference", "Shaky guess", 0.2),
+        ("inference", "Solid inference", 0.9),
+        ("fact", "Shaky fact", 0.2),
+    ] {
+        let result = ToolRegistry::call(
+            "memory_add",
+            Some(json!({
+                "event_type": event_type,
+                "content": content,
+                "confidence": confidence
+            })),
+            &session,
+        )
+        .await
+        .unwrap();
+        let agentic_memory_mcp::types::ToolContent::Text { text } = &result.content[0] else {
+            panic!("Expected text content");
+        };
+        let parsed: serde_json::Value = serde_json::from_str(text).unwrap();
+        ids.push(parsed["node_id"].as_u64().unwrap());
+    }
+
+    for dry_run in [true, false] {
+        let result = ToolRegistry::call(
+            "memory_delete_where",
+            Some(json!({
+                "event_types": ["inference"],
+                "confidence_below": 0.5,
+                "dry_run": dry_run
+            })),
+            &session,
+        )
+        .await
+        .unwrap();
+        let agentic_memory_mcp::types::ToolContent::Text { text } = &result.content[0] else {
+            panic!("Expected text content");
+        };
+        let parsed: serde_json::Value = serde_json::from_str(text).unwrap();
+        assert_eq!(parsed["dry_run"], dry_run);
+        assert_eq!(parsed["deleted_count"], 1);
+        assert_eq!(parsed["deleted_ids"], json!([ids[0]]));
+    }
+    {
+        let session = session.lock().await;
+        assert!(session.graph().get_node(ids[0]).is_none());
+        assert!(session.graph().get_node(ids[1]).is_some());
+        assert!(session.graph().get_node(ids[2]).is_some());
+    }
+
+    assert!(
+        ToolRegistry::call("memory_delete_where", Some(json!({})), &session)
+            .await
+            .is_err()
+    );
+    assert!(ToolRegistry::call(
+        "memory_delete_where",
+        Some(json!({ "event_types": ["infrence"] })),
+        &session
+    )
+    .await
+    .is_err());
+}
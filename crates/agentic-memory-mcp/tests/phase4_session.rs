@@ -199,3 +199,327 @@ async fn test_drop_saves_dirty() {
     let session = agentic_memory_mcp::session::SessionManager::open(&path_str).unwrap();
     assert_eq!(session.graph().node_count(), 1);
 }
+
+#[tokio::test]
+async fn test_session_alias_survives_restart() {
+    let dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let path_str = dir.path().join("alias.amem").display().to_string();
+
+    let start = |args: serde_json::Value| {
+        let path_str = path_str.clone();
+        async move {
+            let session = std::sync::Arc::new(tokio::sync::Mutex::new(
+                agentic_memory_mcp::session::SessionManager::open(&path_str).unwrap(),
+            ));
+            let result = ToolRegistry::call("session_start", Some(args), &session)
+                .await
+                .unwrap();
+            ToolRegistry::call(
+                "memory_add",
+                Some(json!({"event_type": "fact", "content": "alpha work"})),
+                &session,
+            )
+            .await
+            .unwrap();
+            session.lock().await.save().unwrap();
+            match &result.content[0] {
+                agentic_memory_mcp::types::ToolContent::Text { text } => {
+                    serde_json::from_str::<serde_json::Value>(text).unwrap()
+                }
+                _ => panic!("Expected text content"),
+            }
+        }
+    };
+
+    let first = start(json!({"alias": "project-alpha"})).await;
+    assert_eq!(first["alias"], "project-alpha");
+    assert_eq!(first["resumed_alias"], false);
+
+    // A restart picks a fresh PID-based ID, but the alias finds the old one.
+    let second = start(json!({"alias": "project-alpha"})).await;
+    assert_eq!(second["session_id"], first["session_id"]);
+    assert_eq!(second["resumed_alias"], true);
+
+    let session = agentic_memory_mcp::session::SessionManager::open(&path_str).unwrap();
+    let sid = session.resolve_session("project-alpha").unwrap();
+    assert_eq!(json!(sid), first["session_id"]);
+    assert_eq!(session.graph().session_index().node_count(sid), 2);
+}
+
+#[test]
+fn test_session_manager_uses_injected_clock() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("clock.amem");
+    let clock = agentic_memory::MockClock::shared(1_700_000_000_000_000);
+
+    let mut session =
+        agentic_memory_mcp::session::SessionManager::open(&path.display().to_string())
+            .unwrap()
+            .with_clock(clock.clone());
+    let (first, _) = session
+        .add_event(agentic_memory::EventType::Fact, "first", 0.9, vec![])
+        .unwrap();
+    clock.advance_secs(60);
+    let (second, _) = session
+        .add_event(agentic_memory::EventType::Fact, "second", 0.9, vec![])
+        .unwrap();
+
+    let graph = session.graph();
+    assert_eq!(
+        graph.get_node(first).unwrap().created_at,
+        1_700_000_000_000_000
+    );
+    assert_eq!(
+        graph.get_node(second).unwrap().created_at,
+        1_700_000_060_000_000
+    );
+}
+
+#[test]
+fn test_resolved_config_reports_sources() {
+    use agentic_memory_mcp::config::{ResolvedConfig, SettingSource};
+
+    let dir = tempfile::tempdir().unwrap();
+    let brain = dir.path().join("flag.amem");
+    let config_path = dir.path().join("server.toml");
+    std::fs::write(&config_path, "auto_save_interval = 7\n").unwrap();
+
+    let resolved = ResolvedConfig::resolve(
+        Some(brain.to_str().unwrap()),
+        Some(config_path.to_str().unwrap()),
+    )
+    .unwrap();
+
+    let settings = resolved.settings();
+    let brain_setting = settings.iter().find(|s| s.key == "AMEM_BRAIN").unwrap();
+    assert_eq!(brain_setting.source, SettingSource::Flag);
+    assert_eq!(resolved.memory_path, brain.display().to_string());
+
+    if std::env::var("AMEM_AUTOSAVE_SECS").is_err() {
+        let autosave = settings
+            .iter()
+            .find(|s| s.key == "AMEM_AUTOSAVE_SECS")
+            .unwrap();
+        assert_eq!(autosave.source, SettingSource::File);
+        assert_eq!(autosave.value, "7");
+    }
+
+    let json = resolved.to_json();
+    assert!(json["settings"].as_array().unwrap().len() > 10);
+}
+
+#[test]
+fn test_merge_does_not_resurrect_deleted_node() {
+    use agentic_memory::{AmemReader, EventType};
+    use agentic_memory_mcp::session::SessionManager;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("shared.amem");
+    let path = path.to_str().unwrap();
+
+    // Session A writes two nodes and keeps the graph in memory.
+    let mut a = SessionManager::open(path).unwrap();
+    a.add_event(EventType::Fact, "keep me", 1.0, vec![])
+        .unwrap();
+    let (doomed, _) = a
+        .add_event(EventType::Fact, "delete me", 1.0, vec![])
+        .unwrap();
+    a.save().unwrap();
+
+    // Session B deletes one of A's nodes and saves.
+    std::thread::sleep(std::time::Duration::from_millis(50));
+    let mut b = SessionManager::open(path).unwrap();
+    b.graph_mut().remove_node(doomed).unwrap();
+    b.save().unwrap();
+
+    // A, still holding the node, saves again and merges with B's state.
+    std::thread::sleep(std::time::Duration::from_millis(50));
+    a.add_event(EventType::Fact, "added after delete", 1.0, vec![])
+        .unwrap();
+    a.save().unwrap();
+
+    let on_disk = AmemReader::read_from_file(std::path::Path::new(path)).unwrap();
+    let contents: Vec<&str> = on_disk.nodes().iter().map(|n| n.content.as_str()).collect();
+    assert!(
+        !contents.contains(&"delete me"),
+        "resurrected: {contents:?}"
+    );
+    assert!(contents.contains(&"keep me"));
+    assert!(contents.contains(&"added after delete"));
+    assert_eq!(on_disk.tombstones().len(), 1);
+    assert!(!a.graph().nodes().iter().any(|n| n.content == "delete me"));
+}
+
+#[cfg(feature = "sse")]
+#[tokio::test]
+async fn test_tenant_registry_evicts_least_recently_used() {
+    use agentic_memory::EventType;
+    use agentic_memory_mcp::session::tenant::TenantRegistry;
+    use agentic_memory_mcp::types::MemoryMode;
+
+    let dir = tempfile::tempdir().unwrap();
+    let mut registry = TenantRegistry::new(dir.path()).with_max_open(2);
+
+    let alice = registry
+        .get_or_create("alice", MemoryMode::Smart)
+        .await
+        .unwrap();
+    alice
+        .lock()
+        .await
+        .add_event(EventType::Fact, "alice likes tea", 1.0, vec![])
+        .unwrap();
+    drop(alice);
+    let bob = registry
+        .get_or_create("bob", MemoryMode::Smart)
+        .await
+        .unwrap();
+    bob.lock()
+        .await
+        .add_event(EventType::Fact, "bob likes coffee", 1.0, vec![])
+        .unwrap();
+    drop(bob);
+
+    // alice is now least recently used and gets saved and closed.
+    registry
+        .get_or_create("carol", MemoryMode::Smart)
+        .await
+        .unwrap();
+    assert_eq!(registry.count(), 2);
+    assert!(!registry.is_open("alice"));
+    assert!(registry.is_open("bob") && registry.is_open("carol"));
+
+    // Re-opening alice evicts bob, now the least recently used, and finds the
+    // node alice's eviction flushed.
+    let alice = registry
+        .get_or_create("alice", MemoryMode::Smart)
+        .await
+        .unwrap();
+    assert!(!registry.is_open("bob"));
+    assert!(alice
+        .lock()
+        .await
+        .graph()
+        .nodes()
+        .iter()
+        .any(|n| n.content == "alice likes tea"));
+    let bob_file =
+        agentic_memory::AmemReader::read_from_file(&dir.path().join("bob.amem")).unwrap();
+    assert!(bob_file
+        .nodes()
+        .iter()
+        .any(|n| n.content == "bob likes coffee"));
+}
+
+#[cfg(feature = "sqlite-backend")]
+#[test]
+fn test_session_saves_to_sqlite_backend() {
+    use agentic_memory::{EventType, GraphBackend, SqliteGraph};
+    use agentic_memory_mcp::session::SessionManager;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("brain.sqlite");
+    let db = SqliteGraph::open_with_dimension(&path, agentic_memory::DEFAULT_DIMENSION).unwrap();
+
+    let path_str = path.display().to_string();
+    let mut session = SessionManager::open_with_backend(&path_str, Box::new(db)).unwrap();
+    session
+        .add_event(EventType::Fact, "stored in sqlite", 1.0, vec![])
+        .unwrap();
+    session.save().unwrap();
+    drop(session);
+
+    let db = SqliteGraph::open(&path).unwrap();
+    let nodes = db.nodes().unwrap();
+    assert!(nodes.iter().any(|n| n.content == "stored in sqlite"));
+
+    let mut session = SessionManager::open_with_backend(&path_str, Box::new(db)).unwrap();
+    let kept = session
+        .graph()
+        .nodes()
+        .iter()
+        .find(|n| n.content == "stored in sqlite")
+        .map(|n| n.id)
+        .unwrap();
+
+    // Later saves write only what changed: a new node and a removed one.
+    let (added, _) = session
+        .add_event(EventType::Fact, "added later", 1.0, vec![])
+        .unwrap();
+    session.graph_mut().remove_node(kept).unwrap();
+    session.mark_dirty();
+    session.save().unwrap();
+    drop(session);
+
+    let db = SqliteGraph::open(&path).unwrap();
+    assert!(db.get_node(kept).unwrap().is_none());
+    assert_eq!(db.get_node(added).unwrap().unwrap().content, "added later");
+}
+
+#[cfg(feature = "encryption")]
+#[test]
+fn test_session_open_encrypted_round_trip() {
+    use agentic_memory::types::encryption::EncryptionKey;
+    use agentic_memory::{AmemReader, EventType};
+    use agentic_memory_mcp::session::SessionManager;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("brain.amem");
+    let path_str = path.display().to_string();
+    let key = EncryptionKey::from_passphrase("open sesame");
+
+    let mut session = SessionManager::open_encrypted(&path_str, key.clone()).unwrap();
+    session
+        .add_event(EventType::Fact, "kept secret", 1.0, vec![])
+        .unwrap();
+    session.save().unwrap();
+    drop(session);
+
+    assert!(AmemReader::is_encrypted_file(&path).unwrap());
+    let wrong = EncryptionKey::from_passphrase("wrong");
+    assert!(SessionManager::open_encrypted(&path_str, wrong).is_err());
+    assert!(path.exists(), "a wrong key must not move the file aside");
+
+    let session = SessionManager::open_encrypted(&path_str, key).unwrap();
+    assert!(session
+        .graph()
+        .nodes()
+        .iter()
+        .any(|n| n.content == "kept secret"));
+}
+
+#[cfg(feature = "encryption")]
+#[test]
+fn test_restore_encrypted_backup_stays_encrypted() {
+    use agentic_memory::types::encryption::EncryptionKey;
+    use agentic_memory::{AmemReader, EventType};
+    use agentic_memory_mcp::session::{backup, SessionManager};
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("brain.amem");
+    let key = EncryptionKey::from_passphrase("open sesame");
+    let mut session =
+        SessionManager::open_encrypted(&path.display().to_string(), key.clone()).unwrap();
+    session
+        .add_event(EventType::Fact, "kept secret", 1.0, vec![])
+        .unwrap();
+    session.save().unwrap();
+    drop(session);
+
+    // A full backup is a copy of the encrypted file.
+    let backups = dir.path().join("backups");
+    std::fs::create_dir_all(&backups).unwrap();
+    std::fs::copy(&path, backups.join("brain.20260101000000.amem.bak")).unwrap();
+    let points = backup::restore_points(&backups, &path).unwrap();
+    assert_eq!(points.len(), 1);
+
+    let restored = dir.path().join("restored.amem");
+    assert_eq!(
+        backup::restore_to(&points[0], &restored, Some(&key)).unwrap(),
+        1
+    );
+    assert!(AmemReader::is_encrypted_file(&restored).unwrap());
+    let graph = AmemReader::read_encrypted(&restored, &key).unwrap();
+    assert_eq!(graph.get_node(0).unwrap().content, "kept secret");
+    assert!(backup::restore_to(&points[0], &restored, None).is_err());
+}
@@ -1,10 +1,16 @@
 //! Phase 4: Streaming and progress tracking tests.
 
+mod common;
+
+use serde_json::{json, Value};
 use tokio::sync::mpsc;
 
+use agentic_memory_mcp::protocol::ProtocolHandler;
 use agentic_memory_mcp::streaming::chunked::chunk_results;
 use agentic_memory_mcp::streaming::ProgressTracker;
-use agentic_memory_mcp::types::JsonRpcNotification;
+use agentic_memory_mcp::types::*;
+
+use common::fixtures::create_test_session;
 
 #[tokio::test]
 async fn test_progress_tracking_lifecycle() {
@@ -115,3 +121,84 @@ async fn test_multiple_concurrent_progress_tokens() {
     assert!(tracker.is_cancelled(&token1).await);
     assert!(tracker.is_cancelled(&token2).await); // completed = removed = treated as cancelled
 }
+
+fn request(id: i64, method: &str, params: Value) -> JsonRpcMessage {
+    JsonRpcMessage::Request(JsonRpcRequest {
+        jsonrpc: "2.0".to_string(),
+        id: RequestId::Number(id),
+        method: method.to_string(),
+        params: Some(params),
+    })
+}
+
+async fn query_facts(experimental: Value) -> Vec<Value> {
+    let handler = ProtocolHandler::new(create_test_session());
+    handler
+        .handle_message(request(
+            1,
+            "initialize",
+            json!({
+                "protocolVersion": "2024-11-05",
+                "capabilities": { "experimental": experimental },
+                "clientInfo": { "name": "test", "version": "1.0" }
+            }),
+        ))
+        .await
+        .unwrap();
+    for i in 0..7 {
+        handler
+            .handle_message(request(
+                10 + i,
+                "tools/call",
+                json!({"name": "memory_add", "arguments": {"event_type": "fact", "content": format!("fact number {i}")}}),
+            ))
+            .await
+            .unwrap();
+    }
+    handler
+        .handle_message_streamed(request(
+            99,
+            "tools/call",
+            json!({"name": "memory_query", "arguments": {"event_types": ["fact"], "max_results": 50}}),
+        ))
+        .await
+}
+
+#[tokio::test]
+async fn test_streamed_tool_result_chunks_for_opted_in_client() {
+    let messages = query_facts(json!({"streamingResults": {"chunkSize": 3}})).await;
+    let (response, chunks) = messages.split_last().unwrap();
+
+    assert_eq!(response["id"], 99);
+    let text = response["result"]["content"][0]["text"].as_str().unwrap();
+    let summary: Value = serde_json::from_str(text).unwrap();
+    assert_eq!(summary["nodes"], json!([]));
+    assert_eq!(summary["streamed"]["field"], "nodes");
+    let count = summary["streamed"]["count"].as_u64().unwrap() as usize;
+    assert!(count >= 7);
+    assert_eq!(chunks.len(), count.div_ceil(3));
+
+    let mut nodes = Vec::new();
+    for (i, chunk) in chunks.iter().enumerate() {
+        assert_eq!(chunk["method"], "notifications/tools/resultChunk");
+        assert!(chunk.get("id").is_none());
+        assert_eq!(chunk["params"]["requestId"], 99);
+        assert_eq!(chunk["params"]["index"], i);
+        nodes.extend(chunk["params"]["items"].as_array().unwrap().iter().cloned());
+    }
+    assert_eq!(nodes.len(), count);
+    assert_eq!(summary["count"].as_u64().unwrap() as usize, count);
+}
+
+#[tokio::test]
+async fn test_streamed_tool_result_single_response_without_opt_in() {
+    let messages = query_facts(json!({})).await;
+    assert_eq!(messages.len(), 1);
+
+    let text = messages[0]["result"]["content"][0]["text"]
+        .as_str()
+        .unwrap();
+    let result: Value = serde_json::from_str(text).unwrap();
+    assert!(result.get("streamed").is_none());
+    assert!(result["nodes"].as_array().unwrap().len() >= 7);
+}
@@ -1,6 +1,6 @@
 //! Phase 7: V2 stress tests — grounding (anti-hallucination) and multi-context workspaces.
 //!
-//! Tests: memory_ground (12), memory_workspace_* (13), integration (5) — 30 total.
+//! Tests: memory_ground (14), memory_workspace_* (13), integration (5) — 32 total.
 
 mod common;
 
@@ -52,7 +52,7 @@ async fn create_seeded_amem(filename: &str, memories: &[(&str, &str)]) -> Seeded
 }
 
 // ============================================================================
-// 1. Grounding — memory_ground (12 tests)
+// 1. Grounding — memory_ground (14 tests)
 // ============================================================================
 
 #[tokio::test]
@@ -386,6 +386,81 @@ async fn test_grounding_multiple_evidence() {
     );
 }
 
+#[tokio::test]
+async fn test_grounding_batch_claims() {
+    let session = create_test_session();
+
+    ToolRegistry::call(
+        "memory_add",
+        Some(json!({"event_type": "fact", "content": "User prefers dark mode"})),
+        &session,
+    )
+    .await
+    .unwrap();
+    ToolRegistry::call(
+        "memory_add",
+        Some(json!({"event_type": "decision", "content": "Decided to use Axum framework"})),
+        &session,
+    )
+    .await
+    .unwrap();
+
+    let result = ToolRegistry::call(
+        "memory_ground",
+        Some(json!({"claims": [
+            "User prefers dark mode",
+            "Decided to use Axum framework",
+            "quantum teleportation protocol",
+            ""
+        ]})),
+        &session,
+    )
+    .await
+    .unwrap();
+
+    let parsed = result_json(&result);
+    assert_eq!(parsed["total"], 4);
+    assert_eq!(parsed["grounded"], 2);
+    assert_eq!(parsed["ungrounded"], 2);
+    assert_eq!(parsed["summary"], "2 of 4 grounded");
+    let results = parsed["results"].as_array().unwrap();
+    assert_eq!(results.len(), 4);
+    assert_eq!(results[0]["status"], "verified");
+    assert_eq!(results[1]["status"], "verified");
+    assert_eq!(results[2]["status"], "ungrounded");
+    assert_eq!(results[3]["reason"], "Empty claim");
+}
+
+#[tokio::test]
+async fn test_grounding_batch_threshold_and_missing_claim() {
+    let session = create_test_session();
+
+    ToolRegistry::call(
+        "memory_add",
+        Some(json!({"event_type": "fact", "content": "User prefers dark mode"})),
+        &session,
+    )
+    .await
+    .unwrap();
+
+    let result = ToolRegistry::call(
+        "memory_ground",
+        Some(json!({"claims": ["User prefers dark mode"], "threshold": 1000.0})),
+        &session,
+    )
+    .await
+    .unwrap();
+    let parsed = result_json(&result);
+    assert_eq!(parsed["grounded"], 0);
+    assert_eq!(parsed["results"][0]["status"], "ungrounded");
+
+    let result = ToolRegistry::call("memory_ground", Some(json!({})), &session).await;
+    assert!(
+        result.is_err(),
+        "Neither claim nor claims should be rejected"
+    );
+}
+
 // ============================================================================
 // 2. Workspace — memory_workspace_* (13 tests)
 // ============================================================================
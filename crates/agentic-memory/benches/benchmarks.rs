@@ -1,6 +1,8 @@
 //! Criterion benchmarks for AgenticMemory.
 
-use criterion::{criterion_group, criterion_main, Criterion};
+use std::collections::HashMap;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use rand::Rng;
 use tempfile::NamedTempFile;
 
@@ -14,6 +16,8 @@ use agentic_memory::types::{
     CognitiveEvent, CognitiveEventBuilder, Edge, EdgeType, EventType, DEFAULT_DIMENSION,
 };
 
+use agentic_memory::{cosine_similarity, cosine_similarity_scalar};
+
 // v0.2 query expansion imports
 use agentic_memory::{
     AnalogicalAnchor, AnalogicalParams, BeliefRevisionParams, CentralityAlgorithm,
@@ -156,6 +160,7 @@ fn bench_traverse_depth_5(c: &mut Criterion) {
                 max_depth: 5,
                 max_results: 100,
                 min_confidence: 0.0,
+                symmetric_undirected: false,
             };
             let _ = query_engine.traverse(&graph, params);
         })
@@ -176,6 +181,8 @@ fn bench_pattern_query(c: &mut Criterion) {
                 created_after: None,
                 created_before: None,
                 min_decay_score: None,
+                required_tags: HashMap::new(),
+                any_tags: Vec::new(),
                 max_results: 50,
                 sort_by: PatternSort::MostRecent,
             };
@@ -200,12 +207,38 @@ fn bench_similarity_search_100k(c: &mut Criterion) {
                 min_similarity: 0.0,
                 event_types: vec![],
                 skip_zero_vectors: true,
+                expand_via_edges: None,
             };
             let _ = query_engine.similarity(&graph, params);
         })
     });
 }
 
+/// Best match over 10k node vectors, scalar loop against `cosine_similarity`.
+/// Run with `--features simd` for the SIMD kernels; without it both paths use
+/// the scalar loop.
+fn bench_cosine_scalar_vs_simd_10k(c: &mut Criterion) {
+    let graph = make_large_graph(10_000, 0);
+    let mut rng = rand::thread_rng();
+    let query: Vec<f32> = (0..DEFAULT_DIMENSION)
+        .map(|_| rng.gen_range(-1.0..1.0))
+        .collect();
+    let best = |similarity: fn(&[f32], &[f32]) -> f32| {
+        graph
+            .nodes()
+            .iter()
+            .map(|n| similarity(&query, &n.feature_vec))
+            .fold(f32::MIN, f32::max)
+    };
+
+    let mut group = c.benchmark_group("cosine_lookup_10k_128dim");
+    group.bench_function("scalar", |b| {
+        b.iter(|| black_box(best(cosine_similarity_scalar)))
+    });
+    group.bench_function("simd", |b| b.iter(|| black_box(best(cosine_similarity))));
+    group.finish();
+}
+
 fn bench_write_file_10k(c: &mut Criterion) {
     let graph = make_large_graph(10_000, 3);
     let writer = AmemWriter::new(DEFAULT_DIMENSION);
@@ -362,6 +395,7 @@ fn bench_bm25_text_search_100k(c: &mut Criterion) {
                 event_types: vec![],
                 session_ids: vec![],
                 min_score: 0.0,
+                confidence_weight: 0.0,
             };
             let _ = query_engine.text_search(&graph, Some(&term_index), Some(&doc_lengths), params);
         })
@@ -380,6 +414,7 @@ fn bench_bm25_slow_path_100k(c: &mut Criterion) {
                 event_types: vec![],
                 session_ids: vec![],
                 min_score: 0.0,
+                confidence_weight: 0.0,
             };
             let _ = query_engine.text_search(&graph, None, None, params);
         })
@@ -427,6 +462,8 @@ fn bench_pagerank_100k(c: &mut Criterion) {
                 top_k: 10,
                 event_types: vec![],
                 edge_types: vec![],
+                seeds: vec![],
+                symmetric_undirected: false,
             };
             let _ = query_engine.centrality(&graph, params);
         })
@@ -446,12 +483,24 @@ fn bench_degree_centrality_100k(c: &mut Criterion) {
                 top_k: 10,
                 event_types: vec![],
                 edge_types: vec![],
+                seeds: vec![],
+                symmetric_undirected: false,
             };
             let _ = query_engine.centrality(&graph, params);
         })
     });
 }
 
+fn bench_connected_components_100k(c: &mut Criterion) {
+    let graph = make_large_graph(100_000, 3);
+
+    c.bench_function("connected_components_100k", |b| {
+        b.iter(|| {
+            let _ = graph.connected_components();
+        })
+    });
+}
+
 fn bench_betweenness_centrality_100k(c: &mut Criterion) {
     let graph = make_large_graph(100_000, 3);
     let query_engine = QueryEngine::new();
@@ -465,6 +514,8 @@ fn bench_betweenness_centrality_100k(c: &mut Criterion) {
                 top_k: 10,
                 event_types: vec![],
                 edge_types: vec![],
+                seeds: vec![],
+                symmetric_undirected: false,
             };
             let _ = query_engine.centrality(&graph, params);
         })
@@ -484,6 +535,7 @@ fn bench_shortest_path_bfs_100k(c: &mut Criterion) {
                 direction: TraversalDirection::Forward,
                 max_depth: 20,
                 weighted: false,
+                type_cost: HashMap::new(),
             };
             let _ = query_engine.shortest_path(&graph, params);
         })
@@ -503,6 +555,7 @@ fn bench_shortest_path_dijkstra_100k(c: &mut Criterion) {
                 direction: TraversalDirection::Forward,
                 max_depth: 20,
                 weighted: true,
+                type_cost: HashMap::new(),
             };
             let _ = query_engine.shortest_path(&graph, params);
         })
@@ -607,6 +660,7 @@ criterion_group!(
     bench_traverse_depth_5,
     bench_pattern_query,
     bench_similarity_search_100k,
+    bench_cosine_scalar_vs_simd_10k,
     bench_write_file_10k,
     bench_read_file_10k,
     bench_mmap_node_access,
@@ -622,6 +676,7 @@ criterion_group!(
     bench_hybrid_search_100k,
     bench_pagerank_100k,
     bench_degree_centrality_100k,
+    bench_connected_components_100k,
     bench_betweenness_centrality_100k,
     bench_shortest_path_bfs_100k,
     bench_shortest_path_dijkstra_100k,
@@ -35,6 +35,7 @@ fn main() -> AmemResult<()> {
             max_depth: 5,
             max_results: 50,
             min_confidence: 0.0,
+            symmetric_undirected: false,
         },
     )?;
 
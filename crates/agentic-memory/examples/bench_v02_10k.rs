@@ -1,4 +1,5 @@
 use rand::Rng;
+use std::collections::HashMap;
 use std::time::Instant;
 
 use agentic_memory::engine::QueryEngine;
@@ -98,6 +99,7 @@ fn main() {
                 event_types: vec![],
                 session_ids: vec![],
                 min_score: 0.0,
+                confidence_weight: 0.0,
             },
         );
     }
@@ -116,6 +118,7 @@ fn main() {
                 event_types: vec![],
                 session_ids: vec![],
                 min_score: 0.0,
+                confidence_weight: 0.0,
             },
         );
     }
@@ -153,6 +156,8 @@ fn main() {
                 top_k: 10,
                 event_types: vec![],
                 edge_types: vec![],
+                seeds: vec![],
+                symmetric_undirected: false,
             },
         );
     }
@@ -170,6 +175,8 @@ fn main() {
                 top_k: 10,
                 event_types: vec![],
                 edge_types: vec![],
+                seeds: vec![],
+                symmetric_undirected: false,
             },
         );
     }
@@ -186,6 +193,8 @@ fn main() {
             top_k: 10,
             event_types: vec![],
             edge_types: vec![],
+            seeds: vec![],
+            symmetric_undirected: false,
         },
     );
     println!("betweenness_10k: {:?}", s.elapsed());
@@ -202,6 +211,7 @@ fn main() {
                 direction: TraversalDirection::Forward,
                 max_depth: 20,
                 weighted: false,
+                type_cost: HashMap::new(),
             },
         );
     }
@@ -219,6 +229,7 @@ fn main() {
                 direction: TraversalDirection::Forward,
                 max_depth: 20,
                 weighted: true,
+                type_cost: HashMap::new(),
             },
         );
     }
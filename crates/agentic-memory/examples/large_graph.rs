@@ -91,6 +91,7 @@ fn main() -> AmemResult<()> {
             max_depth: 5,
             max_results: 100,
             min_confidence: 0.0,
+            symmetric_undirected: false,
         },
     )?;
     println!(
@@ -111,6 +112,8 @@ fn main() -> AmemResult<()> {
             created_after: None,
             created_before: None,
             min_decay_score: None,
+            required_tags: Default::default(),
+            any_tags: Default::default(),
             max_results: 50,
             sort_by: PatternSort::MostRecent,
         },
@@ -2,36 +2,110 @@
 
 use std::path::Path;
 
+use crate::engine::decay::calculate_decay;
+use crate::engine::diff::{diff_graphs, GraphPatch};
 use crate::engine::{
-    AnalogicalAnchor, AnalogicalParams, BeliefRevisionParams, CausalParams, CentralityAlgorithm,
-    CentralityParams, ConsolidationOp, ConsolidationParams, DriftParams, GapDetectionParams,
-    GapSeverity, HybridSearchParams, MemoryQualityParams, PatternParams, PatternSort, QueryEngine,
-    ShortestPathParams, TextSearchParams, TraversalParams, WriteEngine,
+    bm25_idf, AnalogicalAnchor, AnalogicalParams, BeliefRevisionParams, CausalDirection,
+    CausalParams, CentralityAlgorithm, CentralityCoreParams, CentralityParams, CompactionParams,
+    ConsolidationOp, ConsolidationParams, DedupDecision, DeleteFilter, DriftParams,
+    DuplicateCandidate, DuplicateIndex, EdgeExpansion, EpisodeMember, FuzzySearchParams,
+    GapDetectionParams, GapSeverity, HybridSearchParams, IdAnonymizer, KeepTopParams,
+    MemoryQualityParams, NodeSample, PatternParams, PatternSort, PhaseTimer, QueryEngine,
+    QueryProfile, RedactionCategory, RedactionCounts, Redactor, RegexSearchParams,
+    ShortestPathParams, SimilarityParams, TemporalClusterParams, TemporalParams, TextSearchParams,
+    TimeRange, Tokenizer, TraversalParams, WriteEngine, BM25_B, BM25_K1,
 };
 use crate::format::{AmemReader, AmemWriter};
 use crate::graph::traversal::TraversalDirection;
 use crate::graph::MemoryGraph;
+use crate::index::{ClusterAssignment, ClusterMap, DocLengths, TermIndex};
 use crate::types::{AmemResult, CognitiveEvent, CognitiveEventBuilder, Edge, EdgeType, EventType};
 
+/// Key set by `amem --key`, used for every file the commands read and write.
+#[cfg(feature = "encryption")]
+static ENCRYPTION_KEY: std::sync::OnceLock<crate::types::encryption::EncryptionKey> =
+    std::sync::OnceLock::new();
+
+/// Read and write encrypted files with `key` for the rest of the process.
+///
+/// Plain files still read as before, and are encrypted when written back.
+/// Setting the same key again is a no-op; a different key is refused, since
+/// the commands run so far have already used the first one.
+#[cfg(feature = "encryption")]
+pub fn set_encryption_key(key: crate::types::encryption::EncryptionKey) -> AmemResult<()> {
+    if *ENCRYPTION_KEY.get_or_init(|| key.clone()) != key {
+        return Err(crate::types::AmemError::InvalidKey(
+            "a different encryption key is already set".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Read an .amem file, decrypting it when it is encrypted and a key is set.
+fn read_graph(path: &Path) -> AmemResult<MemoryGraph> {
+    #[cfg(feature = "encryption")]
+    if let Some(key) = ENCRYPTION_KEY.get() {
+        if AmemReader::is_encrypted_file(path)? {
+            return AmemReader::read_encrypted(path, key);
+        }
+    }
+    AmemReader::read_from_file(path)
+}
+
+/// Raw .amem bytes of a file, decrypted when it is encrypted and a key is set.
+fn read_plain_bytes(path: &Path) -> AmemResult<Vec<u8>> {
+    let data = std::fs::read(path)?;
+    #[cfg(feature = "encryption")]
+    if let Some(key) = ENCRYPTION_KEY.get() {
+        if crate::types::encryption::is_encrypted(&data) {
+            return crate::types::encryption::decrypt(&data, key);
+        }
+    }
+    Ok(data)
+}
+
+/// A writer whose files are encrypted when a key is set.
+fn graph_writer(dimension: usize) -> AmemWriter {
+    let writer = AmemWriter::new(dimension);
+    #[cfg(feature = "encryption")]
+    if let Some(key) = ENCRYPTION_KEY.get() {
+        return writer.with_encryption_key(key.clone());
+    }
+    writer
+}
+
 /// Create a new empty .amem file.
-pub fn cmd_create(path: &Path, dimension: usize) -> AmemResult<()> {
+pub fn cmd_create(path: &Path, dimension: usize, json: bool) -> AmemResult<()> {
     let graph = MemoryGraph::new(dimension);
-    let writer = AmemWriter::new(dimension);
+    let writer = graph_writer(dimension);
     writer.write_to_file(&graph, path)?;
-    println!("Created {}", path.display());
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "created": path.display().to_string(),
+                "dimension": dimension,
+            }))
+            .unwrap_or_default()
+        );
+    } else {
+        println!("Created {}", path.display());
+    }
     Ok(())
 }
 
 /// Display information about an .amem file.
 pub fn cmd_info(path: &Path, json: bool) -> AmemResult<()> {
-    let graph = AmemReader::read_from_file(path)?;
+    let graph = read_graph(path)?;
     let file_size = std::fs::metadata(path)?.len();
+    let version = crate::format::migration::storage_version(path)?;
 
     if json {
         let info = serde_json::json!({
             "file": path.display().to_string(),
-            "version": 1,
+            "version": version,
             "dimension": graph.dimension(),
+            "embedding_model": graph.embedding_model(),
             "nodes": graph.node_count(),
             "edges": graph.edge_count(),
             "sessions": graph.session_index().session_count(),
@@ -51,8 +125,12 @@ pub fn cmd_info(path: &Path, json: bool) -> AmemResult<()> {
         );
     } else {
         println!("File: {}", path.display());
-        println!("Version: 1");
+        println!("Version: {}", version);
         println!("Dimension: {}", graph.dimension());
+        println!(
+            "Embedding model: {}",
+            graph.embedding_model().unwrap_or("unknown")
+        );
         println!("Nodes: {}", graph.node_count());
         println!("Edges: {}", graph.edge_count());
         println!("Sessions: {}", graph.session_index().session_count());
@@ -80,83 +158,256 @@ pub fn cmd_info(path: &Path, json: bool) -> AmemResult<()> {
     Ok(())
 }
 
+/// Rewrite a file whose header dimension disagrees with its stored vectors.
+///
+/// Every vector is zero-padded or truncated to `dimension`. The original file
+/// is copied to `backup` (default `<stem>.pre-fix-dimension.amem`) first.
+pub fn cmd_fix_dimension(
+    path: &Path,
+    dimension: usize,
+    backup: Option<std::path::PathBuf>,
+    json: bool,
+) -> AmemResult<()> {
+    let data = read_plain_bytes(path)?;
+    let (graph, stored) = AmemReader::read_resized(&mut data.as_slice(), dimension)?;
+    let declared =
+        crate::types::header::FileHeader::read_from(&mut data.as_slice())?.dimension as usize;
+
+    let changed = declared != dimension || stored != dimension;
+    let backup_path = if changed {
+        let bp = backup.unwrap_or_else(|| {
+            let mut p = path.to_path_buf();
+            let name = p
+                .file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string();
+            p.set_file_name(format!("{}.pre-fix-dimension.amem", name));
+            p
+        });
+        std::fs::copy(path, &bp)?;
+        graph_writer(dimension).write_to_file(&graph, path)?;
+        Some(bp)
+    } else {
+        None
+    };
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "file": path.display().to_string(),
+                "declared_dimension": declared,
+                "stored_dimension": stored,
+                "dimension": dimension,
+                "nodes": graph.node_count(),
+                "changed": changed,
+                "backup_path": backup_path.map(|p| p.display().to_string()),
+            }))
+            .unwrap_or_default()
+        );
+    } else if let Some(bp) = &backup_path {
+        println!(
+            "Rewrote {} vectors in {} to dimension {} (header said {}, stored {})",
+            graph.node_count(),
+            path.display(),
+            dimension,
+            declared,
+            stored
+        );
+        println!("  Backup: {}", bp.display());
+    } else {
+        println!(
+            "{} already has dimension {}; nothing to do",
+            path.display(),
+            dimension
+        );
+    }
+    Ok(())
+}
+
 /// Add a cognitive event to the graph.
+#[allow(clippy::too_many_arguments)]
 pub fn cmd_add(
     path: &Path,
     event_type: EventType,
     content: &str,
     session_id: u32,
     confidence: f32,
+    tags: &[(String, String)],
     supersedes: Option<u64>,
+    feature_vec: Option<Vec<f32>>,
+    embedding_model: Option<&str>,
+    strict: bool,
+    detect_language: bool,
     json: bool,
 ) -> AmemResult<()> {
-    let mut graph = AmemReader::read_from_file(path)?;
-    let write_engine = WriteEngine::new(graph.dimension());
+    let mut graph = read_graph(path)?;
+    let write_engine = WriteEngine::new(graph.dimension()).with_language_detection(detect_language);
+
+    if let (Some(_), Some(model)) = (&feature_vec, embedding_model) {
+        if let Some(file_model) = graph.check_embedding_model(model, strict)? {
+            eprintln!(
+                "Warning: {} uses embedding model {:?}, this vector is from {:?}",
+                path.display(),
+                file_model,
+                model
+            );
+        }
+    }
 
     let id = if let Some(old_id) = supersedes {
-        write_engine.correct(&mut graph, old_id, content, session_id)?
+        write_engine.correct_with(
+            &mut graph,
+            old_id,
+            content,
+            session_id,
+            confidence,
+            feature_vec,
+        )?
     } else {
-        let event = CognitiveEventBuilder::new(event_type, content)
+        let event = tags
+            .iter()
+            .fold(
+                CognitiveEventBuilder::new(event_type, content),
+                |builder, (k, v)| builder.tag(k.clone(), v.clone()),
+            )
             .session_id(session_id)
             .confidence(confidence)
+            // Without a vector the builder would size zeros to the default
+            // dimension, not this file's.
+            .feature_vec(feature_vec.unwrap_or_else(|| vec![0.0; graph.dimension()]))
             .build();
-        graph.add_node(event)?
+        write_engine
+            .ingest(&mut graph, vec![event], vec![])?
+            .new_node_ids[0]
     };
+    let language = graph.node_language(id);
+    let no_change = supersedes == Some(id);
+    if supersedes.is_some() && !no_change {
+        if let Some(mut merged) = graph.get_node(id).map(|n| n.tags.clone()) {
+            merged.extend(tags.iter().cloned());
+            graph.set_node_tags(id, merged)?;
+        }
+    }
 
-    let writer = AmemWriter::new(graph.dimension());
-    writer.write_to_file(&graph, path)?;
+    if !no_change {
+        let writer = graph_writer(graph.dimension());
+        writer.write_to_file(&graph, path)?;
+    }
 
     if json {
+        let mut out = serde_json::json!({"id": id, "type": event_type.name()});
+        if let Some(language) = language {
+            out["language"] = serde_json::json!(language.tag());
+        }
+        if !tags.is_empty() && !no_change {
+            out["tags"] = serde_json::json!(graph.get_node(id).map(|n| &n.tags));
+        }
+        if supersedes.is_some() {
+            out["no_change"] = serde_json::json!(no_change);
+        }
+        println!("{}", out);
+    } else if no_change {
         println!(
-            "{}",
-            serde_json::json!({"id": id, "type": event_type.name()})
+            "Node {} already has this content; no correction added to {}",
+            id,
+            path.display()
         );
     } else {
+        let language = language
+            .map(|l| format!(", language {}", l.tag()))
+            .unwrap_or_default();
         println!(
-            "Added node {} ({}) to {}",
+            "Added node {} ({}{}) to {}",
             id,
             event_type.name(),
+            language,
             path.display()
         );
     }
     Ok(())
 }
 
-/// Add an edge between two nodes.
+/// Add an edge between two nodes, or one in each direction with `bidirectional`.
+/// An `edge_type` that is neither built in nor known to the file is
+/// registered as a new custom type.
 pub fn cmd_link(
     path: &Path,
     source_id: u64,
     target_id: u64,
-    edge_type: EdgeType,
+    edge_type: &str,
     weight: f32,
+    bidirectional: bool,
     json: bool,
 ) -> AmemResult<()> {
-    let mut graph = AmemReader::read_from_file(path)?;
-    let edge = Edge::new(source_id, target_id, edge_type, weight);
-    graph.add_edge(edge)?;
+    let mut graph = read_graph(path)?;
+    let edge_type = graph.register_edge_type(edge_type.trim())?;
+    graph.add_edge(Edge::new(source_id, target_id, edge_type, weight))?;
+    if bidirectional {
+        graph.add_edge(Edge::new(target_id, source_id, edge_type, weight))?;
+    }
 
-    let writer = AmemWriter::new(graph.dimension());
+    let writer = graph_writer(graph.dimension());
     writer.write_to_file(&graph, path)?;
 
     if json {
         println!(
             "{}",
-            serde_json::json!({"source": source_id, "target": target_id, "type": edge_type.name()})
+            serde_json::json!({
+                "source": source_id,
+                "target": target_id,
+                "type": graph.edge_type_name(edge_type),
+                "bidirectional": bidirectional,
+            })
         );
     } else {
+        let arrow = if bidirectional { "<--" } else { "--" };
         println!(
-            "Linked {} --{}--> {}",
+            "Linked {} {}{}--> {}",
             source_id,
-            edge_type.name(),
+            arrow,
+            graph.edge_type_name(edge_type),
             target_id
         );
     }
     Ok(())
 }
 
+/// Pin or unpin a node.
+pub fn cmd_pin(path: &Path, node_id: u64, pinned: bool, json: bool) -> AmemResult<()> {
+    let mut graph = read_graph(path)?;
+    let node = graph
+        .get_node_mut(node_id)
+        .ok_or(crate::types::AmemError::NodeNotFound(node_id))?;
+    let changed = node.pinned != pinned;
+    node.pinned = pinned;
+
+    if changed {
+        let writer = graph_writer(graph.dimension());
+        writer.write_to_file(&graph, path)?;
+    }
+
+    if json {
+        println!(
+            "{}",
+            serde_json::json!({
+                "node_id": node_id,
+                "pinned": pinned,
+                "changed": changed,
+            })
+        );
+    } else {
+        let state = if pinned { "Pinned" } else { "Unpinned" };
+        let note = if changed { "" } else { " (unchanged)" };
+        println!("{state} node {node_id}{note}");
+    }
+    Ok(())
+}
+
 /// Get a specific node by ID.
 pub fn cmd_get(path: &Path, node_id: u64, json: bool) -> AmemResult<()> {
-    let graph = AmemReader::read_from_file(path)?;
+    let graph = read_graph(path)?;
     let node = graph
         .get_node(node_id)
         .ok_or(crate::types::AmemError::NodeNotFound(node_id))?;
@@ -173,6 +424,7 @@ pub fn cmd_get(path: &Path, node_id: u64, json: bool) -> AmemResult<()> {
             "confidence": node.confidence,
             "access_count": node.access_count,
             "decay_score": node.decay_score,
+            "pinned": node.pinned,
             "content": node.content,
             "edges_out": edges_out,
             "edges_in": edges_in,
@@ -189,6 +441,9 @@ pub fn cmd_get(path: &Path, node_id: u64, json: bool) -> AmemResult<()> {
         println!("  Confidence: {:.2}", node.confidence);
         println!("  Access count: {}", node.access_count);
         println!("  Decay score: {:.2}", node.decay_score);
+        if node.pinned {
+            println!("  Pinned: yes");
+        }
         println!("  Content: {:?}", node.content);
         println!("  Edges out: {}", edges_out);
         println!("  Edges in: {}", edges_in);
@@ -196,6 +451,86 @@ pub fn cmd_get(path: &Path, node_id: u64, json: bool) -> AmemResult<()> {
     Ok(())
 }
 
+/// Show a node's access history and decay trajectory.
+///
+/// The trajectory re-evaluates the decay formula at creation, at each logged
+/// access (with the access count the node had reached by then), and now.
+/// Accesses older than the log window only show up in the counts.
+pub fn cmd_history(path: &Path, node_id: u64, json: bool) -> AmemResult<()> {
+    let graph = read_graph(path)?;
+    let node = graph
+        .get_node(node_id)
+        .ok_or(crate::types::AmemError::NodeNotFound(node_id))?;
+    let accesses = graph.access_log(node_id);
+    let now = crate::types::now_micros();
+
+    let decay_at = |access_count: u32, last_accessed: u64, at: u64| {
+        let mut state = node.clone();
+        state.access_count = access_count;
+        state.last_accessed = last_accessed;
+        calculate_decay(&state, at)
+    };
+    let unlogged = node.access_count.saturating_sub(accesses.len() as u32);
+    // (event, timestamp, access count, decay score)
+    let mut trajectory = vec![(
+        "created",
+        node.created_at,
+        0,
+        decay_at(0, node.created_at, node.created_at),
+    )];
+    for (i, &at) in accesses.iter().enumerate() {
+        let count = unlogged + i as u32 + 1;
+        trajectory.push(("access", at, count, decay_at(count, at, at)));
+    }
+    trajectory.push(("now", now, node.access_count, calculate_decay(node, now)));
+
+    if json {
+        let info = serde_json::json!({
+            "id": node.id,
+            "type": node.event_type.name(),
+            "created_at": node.created_at,
+            "access_count": node.access_count,
+            "last_accessed": node.last_accessed,
+            "decay_score": node.decay_score,
+            "accesses": accesses,
+            "unlogged_accesses": unlogged,
+            "trajectory": trajectory
+                .iter()
+                .map(|(event, at, count, decay)| serde_json::json!({
+                    "event": event,
+                    "at": at,
+                    "access_count": count,
+                    "decay_score": decay,
+                }))
+                .collect::<Vec<_>>(),
+        });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&info).unwrap_or_default()
+        );
+    } else {
+        println!("Node {} ({})", node.id, node.event_type.name());
+        println!("  Created: {}", format_timestamp(node.created_at));
+        println!("  Access count: {}", node.access_count);
+        println!("  Last accessed: {}", format_timestamp(node.last_accessed));
+        println!("  Stored decay score: {:.2}", node.decay_score);
+        if unlogged > 0 {
+            println!("  {} earlier accesses are outside the log window", unlogged);
+        }
+        println!("  Trajectory:");
+        for (event, at, count, decay) in &trajectory {
+            println!(
+                "    {:<7} {}  accesses={:<4} decay={:.3}",
+                event,
+                format_timestamp(*at),
+                count,
+                decay
+            );
+        }
+    }
+    Ok(())
+}
+
 /// Run a traversal query.
 #[allow(clippy::too_many_arguments)]
 pub fn cmd_traverse(
@@ -206,21 +541,14 @@ pub fn cmd_traverse(
     max_depth: u32,
     max_results: usize,
     min_confidence: f32,
+    symmetric: bool,
     json: bool,
 ) -> AmemResult<()> {
-    let graph = AmemReader::read_from_file(path)?;
+    let graph = read_graph(path)?;
     let query_engine = QueryEngine::new();
 
     let et = if edge_types.is_empty() {
-        vec![
-            EdgeType::CausedBy,
-            EdgeType::Supports,
-            EdgeType::Contradicts,
-            EdgeType::Supersedes,
-            EdgeType::RelatedTo,
-            EdgeType::PartOf,
-            EdgeType::TemporalNext,
-        ]
+        graph.edge_types()
     } else {
         edge_types
     };
@@ -234,6 +562,7 @@ pub fn cmd_traverse(
             max_depth,
             max_results,
             min_confidence,
+            symmetric_undirected: symmetric,
         },
     )?;
 
@@ -278,6 +607,219 @@ pub fn cmd_traverse(
     Ok(())
 }
 
+/// Parse a `--sample` fraction: a number greater than 0 and at most 1.
+pub fn parse_sample_fraction(raw: &str) -> Result<f32, String> {
+    let fraction: f32 = raw
+        .trim()
+        .parse()
+        .map_err(|_| format!("'{raw}' is not a number"))?;
+    if fraction > 0.0 && fraction <= 1.0 {
+        Ok(fraction)
+    } else {
+        Err(format!("{fraction} is outside (0, 1]"))
+    }
+}
+
+/// The sample for `--sample`/`--sample-seed`, drawing a random seed when
+/// none is given. `None` when no fraction was given.
+pub fn node_sample(fraction: Option<f32>, seed: Option<u64>) -> Option<NodeSample> {
+    fraction.map(|f| {
+        NodeSample::new(
+            f,
+            seed.unwrap_or_else(|| uuid::Uuid::new_v4().as_u64_pair().0),
+        )
+    })
+}
+
+fn sample_percent(sample: &NodeSample) -> f32 {
+    (sample.fraction * 10_000.0).round() / 100.0
+}
+
+/// JSON description of an approximate result, or null for an exact one.
+fn sample_json(sample: Option<&NodeSample>) -> serde_json::Value {
+    sample.map_or(serde_json::Value::Null, |s| {
+        serde_json::json!({
+            "sampled_percent": sample_percent(s),
+            "seed": s.seed,
+        })
+    })
+}
+
+fn print_sample_notice(sample: Option<&NodeSample>) {
+    if let Some(s) = sample {
+        println!(
+            "  approximate (sampled {}%, seed {})",
+            sample_percent(s),
+            s.seed
+        );
+    }
+}
+
+/// Vector similarity search, optionally spreading to connected neighbors.
+///
+/// The query is either an existing node's feature vector (the node itself is
+/// left out of the results) or an explicit vector. With `expand`, each match
+/// pulls in up to `max_neighbors` neighbors per hop over those edge types.
+/// With `sample`, only that share of the nodes is scored and the output is
+/// marked approximate.
+#[allow(clippy::too_many_arguments)]
+pub fn cmd_similar(
+    path: &Path,
+    node_id: Option<u64>,
+    query_vec: Option<Vec<f32>>,
+    top_k: usize,
+    min_similarity: f32,
+    expand: Vec<EdgeType>,
+    hops: u32,
+    decay: f32,
+    max_neighbors: usize,
+    sample: Option<NodeSample>,
+    json: bool,
+) -> AmemResult<()> {
+    let graph = read_graph(path)?;
+    let query_engine = QueryEngine::new();
+
+    let query_vec = match (query_vec, node_id) {
+        (Some(v), _) => v,
+        (None, Some(id)) => graph
+            .get_node(id)
+            .ok_or(crate::types::AmemError::NodeNotFound(id))?
+            .feature_vec
+            .clone(),
+        (None, None) => Vec::new(),
+    };
+    if query_vec.len() != graph.dimension() {
+        return Err(crate::types::AmemError::DimensionMismatch {
+            expected: graph.dimension(),
+            got: query_vec.len(),
+        });
+    }
+
+    let expand_via_edges = (!expand.is_empty() && hops > 0).then_some(EdgeExpansion {
+        edge_types: expand,
+        hops,
+        decay,
+        max_neighbors,
+        max_added: top_k,
+    });
+    // Ask for one extra match so dropping the query node still leaves top_k.
+    let params = SimilarityParams {
+        query_vec,
+        top_k: top_k + usize::from(node_id.is_some()),
+        min_similarity,
+        event_types: Vec::new(),
+        skip_zero_vectors: true,
+        expand_via_edges,
+    };
+    let matches = match sample {
+        Some(sample) => query_engine.similarity_sampled(&graph, params, sample)?,
+        None => query_engine.similarity(&graph, params)?,
+    };
+    let matches: Vec<_> = matches
+        .into_iter()
+        .filter(|m| Some(m.node_id) != node_id)
+        .collect();
+
+    if json {
+        let entries: Vec<serde_json::Value> = matches
+            .iter()
+            .filter_map(|m| {
+                graph.get_node(m.node_id).map(|node| {
+                    serde_json::json!({
+                        "node_id": m.node_id,
+                        "similarity": m.similarity,
+                        "type": node.event_type.name(),
+                        "content": node.content,
+                        "expanded_from": m.expanded_from.map(|src| serde_json::json!({
+                            "seed_id": src.seed_id,
+                            "edge_type": graph.edge_type_name(src.edge_type),
+                            "hops": src.hops,
+                        })),
+                    })
+                })
+            })
+            .collect();
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "query_node": node_id,
+                "approximate": sample_json(sample.as_ref()),
+                "count": entries.len(),
+                "matches": entries,
+            }))
+            .unwrap_or_default()
+        );
+    } else {
+        print_sample_notice(sample.as_ref());
+        if matches.is_empty() {
+            println!("No similar nodes found.");
+        }
+        for m in &matches {
+            let Some(node) = graph.get_node(m.node_id) else {
+                continue;
+            };
+            let via = m
+                .expanded_from
+                .map(|src| {
+                    format!(
+                        "  <- {} from node {} ({} hop{})",
+                        graph.edge_type_name(src.edge_type),
+                        src.seed_id,
+                        src.hops,
+                        if src.hops == 1 { "" } else { "s" }
+                    )
+                })
+                .unwrap_or_default();
+            println!(
+                "  Node {} ({}) [{:.3}]: {:?}{}",
+                m.node_id,
+                node.event_type.name(),
+                m.similarity,
+                node.content,
+                via
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Node fields `amem query --fields` can keep, in output order.
+pub const QUERY_FIELDS: &[&str] = &["id", "type", "confidence", "content", "session_id"];
+
+/// A validated `--fields` projection, in the order the fields were given.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldList(pub Vec<String>);
+
+impl FieldList {
+    /// Keep only the listed keys of a JSON object.
+    fn project(&self, entry: &mut serde_json::Value) {
+        if let Some(object) = entry.as_object_mut() {
+            object.retain(|key, _| self.0.iter().any(|f| f == key));
+        }
+    }
+}
+
+/// Parse a comma-separated `--fields` list such as `id,type,confidence`,
+/// rejecting names outside [`QUERY_FIELDS`].
+pub fn parse_query_fields(raw: &str) -> Result<FieldList, String> {
+    let mut fields: Vec<String> = Vec::new();
+    for name in raw.split(',').map(str::trim).filter(|f| !f.is_empty()) {
+        if !QUERY_FIELDS.contains(&name) {
+            return Err(format!(
+                "unknown field '{name}' (expected one of: {})",
+                QUERY_FIELDS.join(", ")
+            ));
+        }
+        if !fields.iter().any(|f| f == name) {
+            fields.push(name.to_string());
+        }
+    }
+    if fields.is_empty() {
+        return Err("no fields given".to_string());
+    }
+    Ok(FieldList(fields))
+}
+
 /// Pattern search.
 #[allow(clippy::too_many_arguments)]
 pub fn cmd_search(
@@ -290,61 +832,164 @@ pub fn cmd_search(
     created_before: Option<u64>,
     sort_by: PatternSort,
     limit: usize,
+    expand_episodes: Option<usize>,
+    fields: Option<&FieldList>,
+    explain: bool,
+    profile: bool,
     json: bool,
 ) -> AmemResult<()> {
-    let graph = AmemReader::read_from_file(path)?;
+    let mut query_profile = profile.then(QueryProfile::new);
+    let mut timer = PhaseTimer::start(query_profile.as_mut());
+    let graph = read_graph(path)?;
+    timer.lap("load");
     let query_engine = QueryEngine::new();
 
-    let results = query_engine.pattern(
-        &graph,
-        PatternParams {
-            event_types,
-            min_confidence,
-            max_confidence,
-            session_ids,
-            created_after,
-            created_before,
-            min_decay_score: None,
-            max_results: limit,
-            sort_by,
-        },
-    )?;
+    let params = PatternParams {
+        event_types,
+        min_confidence,
+        max_confidence,
+        session_ids,
+        created_after,
+        created_before,
+        min_decay_score: None,
+        required_tags: Default::default(),
+        any_tags: Default::default(),
+        max_results: limit,
+        sort_by,
+    };
+    let results = query_engine.pattern_profiled(&graph, params.clone(), query_profile.as_mut())?;
 
-    if json {
-        let nodes: Vec<serde_json::Value> = results
+    let members = match expand_episodes {
+        Some(max_per_episode) => {
+            let mut timer = PhaseTimer::start(query_profile.as_mut());
+            let hit_ids: Vec<u64> = results.iter().map(|n| n.id).collect();
+            let members = query_engine.expand_episodes(&graph, &hit_ids, max_per_episode);
+            timer.lap("episode expansion");
+            members
+        }
+        None => Vec::new(),
+    };
+
+    // Render before printing so serialization can be timed apart from I/O.
+    let mut timer = PhaseTimer::start(query_profile.as_mut());
+    let output = if json {
+        let node_json = |node: &CognitiveEvent| {
+            let mut entry = query_node_json(node);
+            if let Some(fields) = fields {
+                fields.project(&mut entry);
+            }
+            entry
+        };
+        let mut nodes: Vec<serde_json::Value> = results
             .iter()
-            .map(|node| {
-                serde_json::json!({
-                    "id": node.id,
-                    "type": node.event_type.name(),
-                    "confidence": node.confidence,
-                    "content": node.content,
-                    "session_id": node.session_id,
-                })
+            .map(|n| {
+                let mut entry = node_json(n);
+                if explain {
+                    entry["explain"] = serde_json::json!({
+                        "predicates": params.matched_predicates(n),
+                        "sort_key": {
+                            "name": sort_by.key_name(),
+                            "value": sort_by.key_value(n),
+                        },
+                    });
+                }
+                entry
             })
             .collect();
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&nodes).unwrap_or_default()
-        );
+        for member in &members {
+            if let Some(node) = graph.get_node(member.node_id) {
+                let mut entry = node_json(node);
+                entry["expanded_from_episode"] = member.episode_id.into();
+                nodes.push(entry);
+            }
+        }
+        serde_json::to_string_pretty(&nodes).unwrap_or_default()
     } else {
+        let mut out = String::new();
         for node in &results {
-            println!(
-                "Node {} ({}, confidence: {:.2}): {:?}",
-                node.id,
-                node.event_type.name(),
-                node.confidence,
-                node.content
-            );
+            match fields {
+                Some(fields) => out.push_str(&projected_line(node, fields)),
+                None => out.push_str(&format!(
+                    "Node {} ({}, confidence: {:.2}): {:?}\n",
+                    node.id,
+                    node.event_type.name(),
+                    node.confidence,
+                    node.content
+                )),
+            }
+            if explain {
+                let mut reasons = params.matched_predicates(node);
+                reasons.push(format!(
+                    "sort {} = {}",
+                    sort_by.key_name(),
+                    sort_by.key_value(node)
+                ));
+                out.push_str(&format!("    why: {}\n", reasons.join("; ")));
+            }
+            for member in members.iter().filter(|m| m.episode_id == node.id) {
+                if let Some(part) = graph.get_node(member.node_id) {
+                    match fields {
+                        Some(fields) => {
+                            out.push_str(&format!("  - {}", projected_line(part, fields)))
+                        }
+                        None => out.push_str(&format!(
+                            "  - Node {} ({}, confidence: {:.2}): {:?}\n",
+                            part.id,
+                            part.event_type.name(),
+                            part.confidence,
+                            part.content
+                        )),
+                    }
+                }
+            }
+        }
+        out.push_str(&format!("\n{} results", results.len()));
+        if !members.is_empty() {
+            out.push_str(&format!(" (+{} from episodes)", members.len()));
         }
-        println!("\n{} results", results.len());
+        out
+    };
+    timer.lap("serialize");
+    println!("{}", output);
+
+    if let Some(query_profile) = &query_profile {
+        print_profile(query_profile, graph.node_count(), results.len());
     }
     Ok(())
 }
 
-/// Causal impact analysis.
-pub fn cmd_impact(path: &Path, node_id: u64, max_depth: u32, json: bool) -> AmemResult<()> {
-    let graph = AmemReader::read_from_file(path)?;
+/// Every [`QUERY_FIELDS`] entry of a query result.
+fn query_node_json(node: &CognitiveEvent) -> serde_json::Value {
+    serde_json::json!({
+        "id": node.id,
+        "type": node.event_type.name(),
+        "confidence": node.confidence,
+        "content": node.content,
+        "session_id": node.session_id,
+    })
+}
+
+/// One text line holding only the requested fields, as `name=value` pairs.
+fn projected_line(node: &CognitiveEvent, fields: &FieldList) -> String {
+    let entry = query_node_json(node);
+    let pairs: Vec<String> = fields
+        .0
+        .iter()
+        .map(|f| format!("{f}={}", entry[f.as_str()]))
+        .collect();
+    format!("{}\n", pairs.join(" "))
+}
+
+/// Causal impact analysis, or with [`CausalDirection::Upstream`] the
+/// provenance of a node back to its root causes.
+pub fn cmd_impact(
+    path: &Path,
+    node_id: u64,
+    max_depth: u32,
+    direction: CausalDirection,
+    json: bool,
+) -> AmemResult<()> {
+    let graph = read_graph(path)?;
     let query_engine = QueryEngine::new();
 
     let result = query_engine.causal(
@@ -353,9 +998,50 @@ pub fn cmd_impact(path: &Path, node_id: u64, max_depth: u32, json: bool) -> Amem
             node_id,
             max_depth,
             dependency_types: vec![EdgeType::CausedBy, EdgeType::Supports],
+            direction,
         },
     )?;
 
+    if direction == CausalDirection::Upstream {
+        let direct = result
+            .dependency_tree
+            .get(&node_id)
+            .map(|v| v.len())
+            .unwrap_or(0);
+        if json {
+            let info = serde_json::json!({
+                "root_id": result.root_id,
+                "direction": direction.name(),
+                "direct_causes": direct,
+                "total_causes": result.dependents.len(),
+                "root_causes": result.leaves,
+                "decisions": result.affected_decisions,
+                "inferences": result.affected_inferences,
+                "causes": result.dependents,
+            });
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&info).unwrap_or_default()
+            );
+        } else {
+            println!("Provenance of node {}", node_id);
+            println!("  Direct causes: {}", direct);
+            println!("  Total causes: {}", result.dependents.len());
+            let roots: Vec<String> = result.leaves.iter().map(|id| id.to_string()).collect();
+            if roots.is_empty() {
+                println!("  Root causes: none within depth {}", max_depth);
+            } else {
+                println!("  Root causes: {}", roots.join(", "));
+            }
+
+            if !result.dependents.is_empty() {
+                println!("\nCause tree:");
+                print_dependency_tree(&graph, &result.dependency_tree, node_id, 1, "->");
+            }
+        }
+        return Ok(());
+    }
+
     if json {
         let info = serde_json::json!({
             "root_id": result.root_id,
@@ -383,7 +1069,7 @@ pub fn cmd_impact(path: &Path, node_id: u64, max_depth: u32, json: bool) -> Amem
 
         if !result.dependents.is_empty() {
             println!("\nDependency tree:");
-            print_dependency_tree(&graph, &result.dependency_tree, node_id, 1);
+            print_dependency_tree(&graph, &result.dependency_tree, node_id, 1, "<-");
         }
     }
     Ok(())
@@ -394,27 +1080,29 @@ fn print_dependency_tree(
     tree: &std::collections::HashMap<u64, Vec<(u64, EdgeType)>>,
     node_id: u64,
     depth: usize,
+    arrow: &str,
 ) {
     if let Some(deps) = tree.get(&node_id) {
         for (dep_id, edge_type) in deps {
             let indent = "  ".repeat(depth);
             if let Some(node) = graph.get_node(*dep_id) {
                 println!(
-                    "{}<- Node {} ({}, {})",
+                    "{}{} Node {} ({}, {})",
                     indent,
+                    arrow,
                     dep_id,
                     node.event_type.name(),
-                    edge_type.name()
+                    graph.edge_type_name(*edge_type)
                 );
             }
-            print_dependency_tree(graph, tree, *dep_id, depth + 1);
+            print_dependency_tree(graph, tree, *dep_id, depth + 1, arrow);
         }
     }
 }
 
 /// Resolve a node through SUPERSEDES chains.
 pub fn cmd_resolve(path: &Path, node_id: u64, json: bool) -> AmemResult<()> {
-    let graph = AmemReader::read_from_file(path)?;
+    let graph = read_graph(path)?;
     let query_engine = QueryEngine::new();
 
     let resolved = query_engine.resolve(&graph, node_id)?;
@@ -464,9 +1152,18 @@ pub fn cmd_resolve(path: &Path, node_id: u64, json: bool) -> AmemResult<()> {
     Ok(())
 }
 
-/// List sessions.
-pub fn cmd_sessions(path: &Path, limit: usize, json: bool) -> AmemResult<()> {
-    let graph = AmemReader::read_from_file(path)?;
+/// List sessions. With `active_window_secs`, classify each one as active or
+/// completed instead (see [`QueryEngine::session_activity`]).
+pub fn cmd_sessions(
+    path: &Path,
+    limit: usize,
+    active_window_secs: Option<u64>,
+    json: bool,
+) -> AmemResult<()> {
+    let graph = read_graph(path)?;
+    if let Some(window_secs) = active_window_secs {
+        return print_session_activity(path, &graph, limit, window_secs, json);
+    }
     let session_ids = graph.session_index().session_ids();
 
     if json {
@@ -477,6 +1174,7 @@ pub fn cmd_sessions(path: &Path, limit: usize, json: bool) -> AmemResult<()> {
             .map(|&sid| {
                 serde_json::json!({
                     "session_id": sid,
+                    "alias": graph.session_index().alias_of(sid),
                     "node_count": graph.session_index().node_count(sid),
                 })
             })
@@ -489,57 +1187,589 @@ pub fn cmd_sessions(path: &Path, limit: usize, json: bool) -> AmemResult<()> {
         println!("Sessions in {}:", path.display());
         for &sid in session_ids.iter().rev().take(limit) {
             let count = graph.session_index().node_count(sid);
-            println!("  Session {}: {} nodes", sid, count);
+            match graph.session_index().alias_of(sid) {
+                Some(alias) => println!("  Session {} ({}): {} nodes", sid, alias, count),
+                None => println!("  Session {}: {} nodes", sid, count),
+            }
         }
         println!("  Total: {} sessions", session_ids.len());
     }
     Ok(())
 }
 
-/// Export graph as JSON.
+/// `sessions --active`: last activity and active/completed status per session.
+fn print_session_activity(
+    path: &Path,
+    graph: &MemoryGraph,
+    limit: usize,
+    window_secs: u64,
+    json: bool,
+) -> AmemResult<()> {
+    let activity =
+        QueryEngine::new().session_activity(graph, crate::types::now_micros(), window_secs);
+    let active_count = activity.iter().filter(|a| a.active).count();
+    let status = |active: bool| if active { "active" } else { "completed" };
+
+    if json {
+        let sessions: Vec<serde_json::Value> = activity
+            .iter()
+            .rev()
+            .take(limit)
+            .map(|a| {
+                serde_json::json!({
+                    "session_id": a.session_id,
+                    "alias": graph.session_index().alias_of(a.session_id),
+                    "node_count": a.node_count,
+                    "last_activity": a.last_activity,
+                    "closed": a.closed,
+                    "status": status(a.active),
+                })
+            })
+            .collect();
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "window_secs": window_secs,
+                "active": active_count,
+                "sessions": sessions,
+            }))
+            .unwrap_or_default()
+        );
+    } else {
+        println!(
+            "Session activity in {} (window {}):",
+            path.display(),
+            format_window(window_secs)
+        );
+        for a in activity.iter().rev().take(limit) {
+            let name = match graph.session_index().alias_of(a.session_id) {
+                Some(alias) => format!("{} ({})", a.session_id, alias),
+                None => a.session_id.to_string(),
+            };
+            println!(
+                "  Session {}: {} nodes, last activity {}, {}{}",
+                name,
+                a.node_count,
+                format_timestamp(a.last_activity),
+                status(a.active),
+                if a.closed { " (closed by episode)" } else { "" }
+            );
+        }
+        println!("  {} active of {} sessions", active_count, activity.len());
+    }
+    Ok(())
+}
+
+fn format_window(secs: u64) -> String {
+    if secs.is_multiple_of(3600) && secs > 0 {
+        format!("{}h", secs / 3600)
+    } else if secs.is_multiple_of(60) && secs > 0 {
+        format!("{}m", secs / 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+/// Resolve a `--session` value — a numeric ID or an alias — against the
+/// file. The file is only read when the value is not a number.
+pub fn resolve_session_ref(path: &Path, reference: &str) -> AmemResult<u32> {
+    if let Ok(id) = reference.trim().parse::<u32>() {
+        return Ok(id);
+    }
+    read_graph(path)?.resolve_session(reference)
+}
+
+/// Look up comma-separated edge type names against the file, without
+/// registering any. Each name comes back with its type, or `None` when the
+/// file has no such type. The file is only read for names that are not
+/// built in.
+pub fn resolve_edge_type_refs<'a>(
+    path: &Path,
+    list: &'a str,
+) -> AmemResult<Vec<(&'a str, Option<EdgeType>)>> {
+    let names: Vec<&str> = list
+        .split(',')
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .collect();
+    if names.iter().all(|n| EdgeType::from_name(n).is_some()) {
+        return Ok(names
+            .into_iter()
+            .map(|n| (n, EdgeType::from_name(n)))
+            .collect());
+    }
+    let graph = read_graph(path)?;
+    Ok(names.into_iter().map(|n| (n, graph.edge_type(n))).collect())
+}
+
+/// Resolve a comma-separated list of session IDs and aliases.
+pub fn resolve_session_refs(path: &Path, list: &str) -> AmemResult<Vec<u32>> {
+    let refs: Vec<&str> = list
+        .split(',')
+        .map(str::trim)
+        .filter(|r| !r.is_empty())
+        .collect();
+    let numeric: Option<Vec<u32>> = refs.iter().map(|r| r.parse().ok()).collect();
+    if let Some(ids) = numeric {
+        return Ok(ids);
+    }
+    let graph = read_graph(path)?;
+    refs.iter().map(|r| graph.resolve_session(r)).collect()
+}
+
+/// Show, assign, or remove the alias of a session.
+pub fn cmd_session_alias(
+    path: &Path,
+    session: &str,
+    alias: Option<&str>,
+    remove: bool,
+    json: bool,
+) -> AmemResult<()> {
+    let mut graph = read_graph(path)?;
+    let session_id = graph.resolve_session(session)?;
+    let previous = graph
+        .session_index()
+        .alias_of(session_id)
+        .map(str::to_string);
+
+    let changed = if remove {
+        if let Some(prev) = &previous {
+            graph.remove_session_alias(prev);
+        }
+        previous.is_some()
+    } else if let Some(alias) = alias {
+        graph.rename_session(session_id, alias)?;
+        previous.as_deref() != Some(alias.trim())
+    } else {
+        false
+    };
+    if changed {
+        graph_writer(graph.dimension()).write_to_file(&graph, path)?;
+    }
+
+    let current = graph.session_index().alias_of(session_id);
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "session_id": session_id,
+                "alias": current,
+                "previous_alias": previous,
+                "changed": changed,
+            }))
+            .unwrap_or_default()
+        );
+    } else {
+        match current {
+            Some(alias) => println!("Session {}: {}", session_id, alias),
+            None => println!("Session {}: (no alias)", session_id),
+        }
+    }
+    Ok(())
+}
+
+/// Compare two time ranges, optionally grouped by session.
+pub fn cmd_temporal(
+    path: &Path,
+    range_a: TimeRange,
+    range_b: TimeRange,
+    group_by_session: bool,
+    json: bool,
+) -> AmemResult<()> {
+    let graph = read_graph(path)?;
+    let query_engine = QueryEngine::new();
+    let result = query_engine.temporal(
+        &graph,
+        TemporalParams {
+            range_a,
+            range_b,
+            group_by_session,
+        },
+    )?;
+
+    if json {
+        let sessions: Option<Vec<serde_json::Value>> =
+            result.session_groups.as_ref().map(|groups| {
+                groups
+                    .iter()
+                    .map(|g| {
+                        serde_json::json!({
+                            "session_id": g.session_id,
+                            "node_count": g.node_count,
+                            "in_range": g.in_range,
+                            "dominant_type": g.dominant_type.name(),
+                            "started_at": g.started_at,
+                            "ended_at": g.ended_at,
+                            "partial": g.partial,
+                        })
+                    })
+                    .collect()
+            });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "added": result.added,
+                "corrected": result.corrected,
+                "unchanged": result.unchanged,
+                "potentially_stale": result.potentially_stale,
+                "session_groups": sessions,
+            }))
+            .unwrap_or_default()
+        );
+    } else {
+        println!("Temporal comparison for {}:", path.display());
+        println!("  Added:             {}", result.added.len());
+        println!("  Corrected:         {}", result.corrected.len());
+        println!("  Unchanged:         {}", result.unchanged.len());
+        println!("  Potentially stale: {}", result.potentially_stale.len());
+        if let Some(groups) = &result.session_groups {
+            println!();
+            println!("Sessions ({}):", groups.len());
+            for g in groups {
+                println!(
+                    "  Session {}: {} nodes ({} in range{}), mostly {}, {} -> {}",
+                    g.session_id,
+                    g.node_count,
+                    g.in_range.len(),
+                    if g.partial { ", partial" } else { "" },
+                    g.dominant_type.name(),
+                    format_timestamp(g.started_at),
+                    format_timestamp(g.ended_at)
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Group nodes into fixed time windows of `window_secs` seconds.
+///
+/// JSON output is an array of clusters, oldest first.
+pub fn cmd_temporal_clusters(
+    path: &Path,
+    window_secs: u64,
+    event_types: Vec<EventType>,
+    session_ids: Vec<u32>,
+    json: bool,
+) -> AmemResult<()> {
+    let graph = read_graph(path)?;
+    let clusters = QueryEngine::new().temporal_clusters(
+        &graph,
+        TemporalClusterParams {
+            window: std::time::Duration::from_secs(window_secs),
+            event_types,
+            session_ids,
+        },
+    )?;
+
+    if json {
+        let items: Vec<serde_json::Value> = clusters
+            .iter()
+            .map(|c| {
+                serde_json::json!({
+                    "start_micros": c.start_micros,
+                    "end_micros": c.end_micros,
+                    "node_ids": c.node_ids,
+                    "dominant_type": c.dominant_type.name(),
+                    "mean_confidence": c.mean_confidence,
+                })
+            })
+            .collect();
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&items).unwrap_or_default()
+        );
+    } else {
+        println!("Temporal clusters in {}:", path.display());
+        for c in &clusters {
+            println!(
+                "  {} -> {}: {} nodes, mostly {}, mean confidence {:.2}",
+                format_timestamp(c.start_micros),
+                format_timestamp(c.end_micros),
+                c.node_ids.len(),
+                c.dominant_type.name(),
+                c.mean_confidence
+            );
+        }
+        println!("  {} clusters", clusters.len());
+    }
+    Ok(())
+}
+
+/// Output format of `export`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExportFormat {
+    /// Nodes and edges as JSON, re-importable with `import`.
+    #[default]
+    Json,
+    /// Graphviz DOT, see [`write_dot`](crate::format::write_dot).
+    Dot,
+    /// Parquet node and edge files; needs the `parquet` feature.
+    Parquet,
+    /// One Markdown note per session, see
+    /// [`write_markdown`](crate::format::write_markdown).
+    Markdown,
+}
+
+impl ExportFormat {
+    /// Parse a format name (`json`, `dot`, `parquet`, `markdown`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "json" => Some(Self::Json),
+            "dot" | "graphviz" => Some(Self::Dot),
+            "parquet" => Some(Self::Parquet),
+            "markdown" | "md" => Some(Self::Markdown),
+            _ => None,
+        }
+    }
+
+    /// Canonical name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Dot => "dot",
+            Self::Parquet => "parquet",
+            Self::Markdown => "markdown",
+        }
+    }
+}
+
+/// Write `graph` as Parquet and report what was written.
+#[cfg(feature = "parquet")]
+fn export_parquet(
+    graph: &MemoryGraph,
+    output: &Path,
+    edges_output: Option<&Path>,
+) -> AmemResult<()> {
+    crate::format::write_parquet(graph, output, edges_output)?;
+    eprintln!("Wrote {} nodes to {}", graph.node_count(), output.display());
+    if let Some(edges_output) = edges_output {
+        eprintln!(
+            "Wrote {} edges to {}",
+            graph.edge_count(),
+            edges_output.display()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "parquet"))]
+fn export_parquet(
+    _graph: &MemoryGraph,
+    _output: &Path,
+    _edges_output: Option<&Path>,
+) -> AmemResult<()> {
+    Err(crate::types::AmemError::Export(
+        "Parquet support is not compiled in (build with the `parquet` feature)".to_string(),
+    ))
+}
+
+/// Export graph as JSON, DOT, Parquet or Markdown, optionally only its
+/// high-centrality core. Parquet writes the nodes to `output`, and the edges
+/// to `edges_output` if given, instead of printing; Markdown writes one note
+/// per session into the `output` directory. With a redactor, node
+/// content is scrubbed in the output only; the file is not modified. With `anonymize_ids` (JSON only), node IDs are replaced by
+/// a shuffled `0..n` range (reproducible with `anonymize_seed`) and the
+/// mapping back to the original IDs is written to `id_map`, if given.
+#[allow(clippy::too_many_arguments)]
 pub fn cmd_export(
     path: &Path,
     nodes_only: bool,
     session: Option<u32>,
+    event_types: &[EventType],
+    min_centrality: Option<f32>,
+    keep_connectors: bool,
+    cross_session: bool,
+    redactor: Option<&Redactor>,
+    anonymize_ids: bool,
+    anonymize_seed: Option<u64>,
+    id_map: Option<&Path>,
+    format: ExportFormat,
+    output: Option<&Path>,
+    edges_output: Option<&Path>,
     pretty: bool,
 ) -> AmemResult<()> {
-    let graph = AmemReader::read_from_file(path)?;
+    let mut graph = read_graph(path)?;
 
-    let nodes: Vec<&CognitiveEvent> = if let Some(sid) = session {
-        let ids = graph.session_index().get_session(sid);
-        ids.iter().filter_map(|&id| graph.get_node(id)).collect()
-    } else {
-        graph.nodes().iter().collect()
-    };
+    let mut core_summary = None;
+    if let Some(min_centrality) = min_centrality {
+        let core = QueryEngine::new().centrality_core(
+            &graph,
+            CentralityCoreParams {
+                min_centrality,
+                keep_connectors,
+                damping: 0.85,
+                max_iterations: 100,
+            },
+        )?;
+        eprintln!(
+            "Kept {} of {} nodes ({} above centrality {}, {} connectors)",
+            core.nodes.len(),
+            core.total_nodes,
+            core.core_ids.len(),
+            min_centrality,
+            core.connector_ids.len()
+        );
+        core_summary = Some(serde_json::json!({
+            "min_centrality": min_centrality,
+            "total_nodes": core.total_nodes,
+            "retained_nodes": core.nodes.len(),
+            "core_nodes": core.core_ids.len(),
+            "connector_ids": core.connector_ids,
+        }));
+        let names = graph.edge_type_names().clone();
+        graph = MemoryGraph::from_parts(core.nodes, core.edges, graph.dimension())?;
+        graph.set_edge_type_names(names);
+    }
 
-    let nodes_json: Vec<serde_json::Value> = nodes
-        .iter()
-        .map(|n| {
-            serde_json::json!({
-                "id": n.id,
-                "event_type": n.event_type.name(),
-                "created_at": n.created_at,
-                "session_id": n.session_id,
-                "confidence": n.confidence,
-                "access_count": n.access_count,
-                "last_accessed": n.last_accessed,
-                "decay_score": n.decay_score,
-                "content": n.content,
+    let mut cross_summary = None;
+    if cross_session {
+        let total_edges = graph.edge_count();
+        let view = QueryEngine::new().cross_session(&graph);
+        eprintln!(
+            "Kept {} of {} edges crossing sessions ({} endpoint nodes, {} session pairs)",
+            view.edges.len(),
+            total_edges,
+            view.nodes.len(),
+            view.session_pairs.len()
+        );
+        let pairs_json: Vec<serde_json::Value> = view
+            .session_pairs
+            .iter()
+            .map(|&((from, to), count)| {
+                serde_json::json!({
+                    "source_session": from,
+                    "target_session": to,
+                    "edges": count,
+                })
             })
-        })
-        .collect();
+            .collect();
+        cross_summary = Some(serde_json::json!({
+            "edges": view.edges.len(),
+            "total_edges": total_edges,
+            "session_pairs": pairs_json,
+        }));
+        let names = graph.edge_type_names().clone();
+        graph = MemoryGraph::from_parts(view.nodes, view.edges, graph.dimension())?;
+        graph.set_edge_type_names(names);
+    }
+
+    // Markdown picks the session's note itself, keeping links to other sessions.
+    if let Some(sid) = session.filter(|_| format != ExportFormat::Markdown) {
+        let ids: std::collections::HashSet<u64> = graph
+            .session_index()
+            .get_session(sid)
+            .iter()
+            .copied()
+            .collect();
+        graph = graph.subgraph(&ids);
+    }
+    if !event_types.is_empty() {
+        let ids: std::collections::HashSet<u64> = graph
+            .nodes()
+            .iter()
+            .filter(|n| event_types.contains(&n.event_type))
+            .map(|n| n.id)
+            .collect();
+        graph = graph.subgraph(&ids);
+    }
 
-    let output = if nodes_only {
+    if format != ExportFormat::Json {
+        if let Some(redactor) = redactor {
+            let mut redactions = RedactionCounts::default();
+            let ids: Vec<u64> = graph.nodes().iter().map(|n| n.id).collect();
+            for id in ids {
+                if let Some(node) = graph.get_node_mut(id) {
+                    node.content = redactor.redact_counted(&node.content, &mut redactions);
+                }
+            }
+            eprintln!(
+                "Redacted {} tokens across {} exported nodes",
+                redactions.total(),
+                graph.node_count()
+            );
+        }
+        if format == ExportFormat::Parquet {
+            let output = output.ok_or_else(|| {
+                crate::types::AmemError::Export("Parquet export needs an output path".to_string())
+            })?;
+            return export_parquet(&graph, output, edges_output);
+        }
+        if format == ExportFormat::Markdown {
+            let output = output.ok_or_else(|| {
+                crate::types::AmemError::Export(
+                    "Markdown export needs an output directory".to_string(),
+                )
+            })?;
+            crate::format::write_markdown(&graph, output, session)?;
+            let sessions = match session {
+                Some(_) => 1,
+                None => graph.session_index().session_count(),
+            };
+            eprintln!("Wrote {} session notes to {}", sessions, output.display());
+            return Ok(());
+        }
+        let stdout = std::io::stdout();
+        return crate::format::write_dot(&graph, &mut stdout.lock());
+    }
+
+    let nodes: Vec<&CognitiveEvent> = graph.nodes().iter().collect();
+
+    let anonymizer = anonymize_ids.then(|| {
+        let ids = graph.nodes().iter().map(|n| n.id);
+        match anonymize_seed {
+            Some(seed) => IdAnonymizer::new(ids, seed),
+            None => IdAnonymizer::random(ids),
+        }
+    });
+    let export_id = |id: u64| match &anonymizer {
+        Some(a) => a.get(id).unwrap_or(id),
+        None => id,
+    };
+
+    let mut redactions = RedactionCounts::default();
+    let mut nodes_json: Vec<serde_json::Value> = Vec::with_capacity(nodes.len());
+    for n in &nodes {
+        let content = match redactor {
+            Some(redactor) => redactor.redact_counted(&n.content, &mut redactions),
+            None => n.content.clone(),
+        };
+        nodes_json.push(serde_json::json!({
+            "id": export_id(n.id),
+            "event_type": n.event_type.name(),
+            "created_at": n.created_at,
+            "session_id": n.session_id,
+            "confidence": n.confidence,
+            "access_count": n.access_count,
+            "last_accessed": n.last_accessed,
+            "decay_score": n.decay_score,
+            "content": content,
+            "tags": n.tags,
+        }));
+    }
+    if anonymizer.is_some() {
+        // Output order would otherwise reveal the original ID order.
+        nodes_json.sort_by_key(|n| n["id"].as_u64());
+    }
+
+    let mut output = if nodes_only {
         serde_json::json!({"nodes": nodes_json})
     } else {
-        let edges_json: Vec<serde_json::Value> = graph
+        let mut edges: Vec<(u64, u64, &Edge)> = graph
             .edges()
             .iter()
-            .map(|e| {
+            .map(|e| (export_id(e.source_id), export_id(e.target_id), e))
+            .collect();
+        if anonymizer.is_some() {
+            edges.sort_by_key(|&(source, target, e)| (source, target, e.edge_type));
+        }
+        let edges_json: Vec<serde_json::Value> = edges
+            .into_iter()
+            .map(|(source, target, e)| {
                 serde_json::json!({
-                    "source_id": e.source_id,
-                    "target_id": e.target_id,
-                    "edge_type": e.edge_type.name(),
+                    "source_id": source,
+                    "target_id": target,
+                    "edge_type": graph.edge_type_name(e.edge_type),
                     "weight": e.weight,
                     "created_at": e.created_at,
                 })
@@ -547,6 +1777,53 @@ pub fn cmd_export(
             .collect();
         serde_json::json!({"nodes": nodes_json, "edges": edges_json})
     };
+    if let Some(mut summary) = core_summary {
+        if let Some(ids) = summary["connector_ids"].as_array_mut() {
+            for id in ids.iter_mut() {
+                if let Some(original) = id.as_u64() {
+                    *id = serde_json::json!(export_id(original));
+                }
+            }
+        }
+        output["centrality_filter"] = summary;
+    }
+    if let Some(summary) = cross_summary {
+        output["cross_session"] = summary;
+    }
+    if let Some(redactor) = redactor {
+        eprintln!(
+            "Redacted {} tokens across {} exported nodes ({} secrets, {} emails, {} paths)",
+            redactions.total(),
+            nodes.len(),
+            redactions.secrets,
+            redactions.emails,
+            redactions.paths
+        );
+        output["redaction"] = redaction_json(redactor, &redactions);
+    }
+    if let Some(anonymizer) = &anonymizer {
+        eprintln!("Anonymized {} node IDs", anonymizer.len());
+        output["anonymized_ids"] = serde_json::json!(true);
+        if let Some(map_path) = id_map {
+            let pairs: Vec<serde_json::Value> = anonymizer
+                .mapping()
+                .into_iter()
+                .map(|(anonymized, original)| {
+                    serde_json::json!({"anonymized": anonymized, "original": original})
+                })
+                .collect();
+            let map_json = serde_json::json!({
+                "source": path.display().to_string(),
+                "seed": anonymizer.seed(),
+                "ids": pairs,
+            });
+            std::fs::write(
+                map_path,
+                serde_json::to_string_pretty(&map_json).unwrap_or_default(),
+            )?;
+            eprintln!("Wrote ID map to {}", map_path.display());
+        }
+    }
 
     if pretty {
         println!(
@@ -559,14 +1836,84 @@ pub fn cmd_export(
     Ok(())
 }
 
-/// Import nodes and edges from JSON.
-pub fn cmd_import(path: &Path, json_path: &Path) -> AmemResult<()> {
-    let mut graph = AmemReader::read_from_file(path)?;
+/// Summary of a redaction pass: enabled categories and per-category counts.
+pub fn redaction_json(redactor: &Redactor, counts: &RedactionCounts) -> serde_json::Value {
+    let categories: Vec<&str> = redactor.categories().iter().map(|c| c.name()).collect();
+    let by_category: serde_json::Map<String, serde_json::Value> = redactor
+        .categories()
+        .iter()
+        .map(|&c| (c.name().to_string(), serde_json::json!(counts.get(c))))
+        .collect();
+    serde_json::json!({
+        "categories": categories,
+        "total": counts.total(),
+        "by_category": by_category,
+    })
+}
+
+/// How `import` treats content that already exists in the target file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// Always add imported nodes.
+    Append,
+    /// Skip imported nodes that duplicate an existing node.
+    SkipDuplicate,
+    /// Keep the existing node, raising its confidence if the import is higher.
+    UpdateConfidence,
+    /// Add the import as a Correction that supersedes the existing node.
+    Supersede,
+}
+
+impl MergeStrategy {
+    /// Parse a strategy name (`append`, `skip-duplicate`, `update-confidence`, `supersede`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().replace('_', "-").as_str() {
+            "append" => Some(Self::Append),
+            "skip-duplicate" | "skip" => Some(Self::SkipDuplicate),
+            "update-confidence" => Some(Self::UpdateConfidence),
+            "supersede" => Some(Self::Supersede),
+            _ => None,
+        }
+    }
+
+    /// Canonical name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Append => "append",
+            Self::SkipDuplicate => "skip-duplicate",
+            Self::UpdateConfidence => "update-confidence",
+            Self::Supersede => "supersede",
+        }
+    }
+}
+
+/// Import nodes and edges from a JSON export.
+///
+/// Edge endpoints that refer to exported node IDs are remapped to the node
+/// each one was merged into.
+pub fn cmd_import(
+    path: &Path,
+    json_path: &Path,
+    strategy: MergeStrategy,
+    duplicate_threshold: f32,
+    json: bool,
+) -> AmemResult<()> {
+    let mut graph = read_graph(path)?;
     let json_data = std::fs::read_to_string(json_path)?;
     let parsed: serde_json::Value = serde_json::from_str(&json_data)
         .map_err(|e| crate::types::AmemError::Compression(e.to_string()))?;
 
+    let write_engine = WriteEngine::new(graph.dimension());
+    let mut duplicates = match strategy {
+        MergeStrategy::Append => None,
+        _ => Some(DuplicateIndex::build(&graph, duplicate_threshold)),
+    };
+    let mut id_map: std::collections::HashMap<u64, u64> = std::collections::HashMap::new();
+
     let mut added_nodes = 0;
+    let mut skipped_nodes = 0;
+    let mut updated_nodes = 0;
+    let mut superseded_nodes = 0;
     let mut added_edges = 0;
 
     if let Some(nodes) = parsed.get("nodes").and_then(|v| v.as_array()) {
@@ -588,13 +1935,63 @@ pub fn cmd_import(path: &Path, json_path: &Path) -> AmemResult<()> {
                 .get("confidence")
                 .and_then(|v| v.as_f64())
                 .unwrap_or(1.0) as f32;
+            let source_id = node_val.get("id").and_then(|v| v.as_u64());
+            let tags = node_val
+                .get("tags")
+                .and_then(|v| v.as_object())
+                .into_iter()
+                .flatten()
+                .filter_map(|(k, v)| Some((k.clone(), v.as_str()?.to_string())));
 
-            let event = CognitiveEventBuilder::new(event_type, content)
-                .session_id(session_id)
-                .confidence(confidence)
-                .build();
-            graph.add_node(event)?;
-            added_nodes += 1;
+            let existing = duplicates
+                .as_ref()
+                .and_then(|d| d.find(event_type, content))
+                .map(|(id, _)| id);
+
+            let id = match (strategy, existing) {
+                (MergeStrategy::SkipDuplicate, Some(old_id)) => {
+                    skipped_nodes += 1;
+                    old_id
+                }
+                (MergeStrategy::UpdateConfidence, Some(old_id)) => {
+                    match graph.get_node_mut(old_id) {
+                        Some(node) if confidence > node.confidence => {
+                            node.confidence = confidence;
+                            updated_nodes += 1;
+                        }
+                        _ => skipped_nodes += 1,
+                    }
+                    old_id
+                }
+                (MergeStrategy::Supersede, Some(old_id)) => {
+                    let new_id = write_engine.correct(&mut graph, old_id, content, session_id)?;
+                    if new_id == old_id {
+                        skipped_nodes += 1;
+                    } else {
+                        superseded_nodes += 1;
+                    }
+                    new_id
+                }
+                _ => {
+                    let event = tags
+                        .fold(
+                            CognitiveEventBuilder::new(event_type, content),
+                            |builder, (k, v)| builder.tag(k, v),
+                        )
+                        .session_id(session_id)
+                        .confidence(confidence)
+                        .build();
+                    let new_id = graph.add_node(event)?;
+                    if let Some(d) = duplicates.as_mut() {
+                        d.insert(new_id, event_type, content);
+                    }
+                    added_nodes += 1;
+                    new_id
+                }
+            };
+            if let Some(source_id) = source_id {
+                id_map.insert(source_id, id);
+            }
         }
     }
 
@@ -611,13 +2008,15 @@ pub fn cmd_import(path: &Path, json_path: &Path) -> AmemResult<()> {
             let edge_type = edge_val
                 .get("edge_type")
                 .and_then(|v| v.as_str())
-                .and_then(EdgeType::from_name)
+                .and_then(|name| graph.register_edge_type(name).ok())
                 .unwrap_or(EdgeType::RelatedTo);
             let weight = edge_val
                 .get("weight")
                 .and_then(|v| v.as_f64())
                 .unwrap_or(1.0) as f32;
 
+            let source_id = id_map.get(&source_id).copied().unwrap_or(source_id);
+            let target_id = id_map.get(&target_id).copied().unwrap_or(target_id);
             let edge = Edge::new(source_id, target_id, edge_type, weight);
             if graph.add_edge(edge).is_ok() {
                 added_edges += 1;
@@ -625,21 +2024,285 @@ pub fn cmd_import(path: &Path, json_path: &Path) -> AmemResult<()> {
         }
     }
 
-    let writer = AmemWriter::new(graph.dimension());
+    let writer = graph_writer(graph.dimension());
     writer.write_to_file(&graph, path)?;
 
-    println!("Imported {} nodes and {} edges", added_nodes, added_edges);
-    Ok(())
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "merge_strategy": strategy.name(),
+                "nodes_added": added_nodes,
+                "nodes_skipped": skipped_nodes,
+                "nodes_updated": updated_nodes,
+                "nodes_superseded": superseded_nodes,
+                "edges_added": added_edges,
+            }))
+            .unwrap_or_default()
+        );
+    } else {
+        println!("Imported {} nodes and {} edges", added_nodes, added_edges);
+        if strategy != MergeStrategy::Append {
+            println!(
+                "  Merge strategy {}: {} skipped, {} updated, {} superseded",
+                strategy.name(),
+                skipped_nodes,
+                updated_nodes,
+                superseded_nodes
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Import the notes in a Markdown file as nodes of one session.
+///
+/// See [`parse_markdown`](super::import_markdown::parse_markdown) for how
+/// lines map to event types. Each node under a heading gets a `part_of`
+/// edge to that heading's episode. Without `session`, the nodes go into a
+/// new session after the highest existing one.
+pub fn cmd_import_markdown(
+    path: &Path,
+    markdown_path: &Path,
+    session: Option<u32>,
+    list_type: EventType,
+    json: bool,
+) -> AmemResult<()> {
+    let mut graph = read_graph(path)?;
+    let text = std::fs::read_to_string(markdown_path)?;
+    let items = super::import_markdown::parse_markdown(&text, list_type);
+
+    let session_id = session.unwrap_or_else(|| {
+        graph
+            .session_index()
+            .session_ids()
+            .into_iter()
+            .max()
+            .map_or(0, |max| max.saturating_add(1))
+    });
+
+    let mut ids = Vec::with_capacity(items.len());
+    let mut by_type: std::collections::BTreeMap<&str, usize> = std::collections::BTreeMap::new();
+    let mut added_edges = 0;
+    for item in &items {
+        let event = CognitiveEventBuilder::new(item.event_type, item.content.as_str())
+            .session_id(session_id)
+            .build();
+        let id = graph.add_node(event)?;
+        if let Some(heading) = item.heading {
+            graph.add_edge(Edge::new(id, ids[heading], EdgeType::PartOf, 1.0))?;
+            added_edges += 1;
+        }
+        ids.push(id);
+        *by_type.entry(item.event_type.name()).or_default() += 1;
+    }
+
+    let writer = graph_writer(graph.dimension());
+    writer.write_to_file(&graph, path)?;
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "source": markdown_path.display().to_string(),
+                "session_id": session_id,
+                "nodes_added": ids.len(),
+                "edges_added": added_edges,
+                "by_type": by_type,
+                "node_ids": ids,
+            }))
+            .unwrap_or_default()
+        );
+    } else {
+        println!(
+            "Imported {} nodes and {} edges from {} into session {}",
+            ids.len(),
+            added_edges,
+            markdown_path.display(),
+            session_id
+        );
+        for (name, count) in &by_type {
+            println!("  {}: {}", name, count);
+        }
+    }
+    Ok(())
+}
+
+/// Merge two files into a new one at `output`.
+pub fn cmd_merge(
+    path_a: &Path,
+    path_b: &Path,
+    output: &Path,
+    strategy: crate::engine::MergeStrategy,
+    json: bool,
+) -> AmemResult<()> {
+    let a = read_graph(path_a)?;
+    let b = read_graph(path_b)?;
+    let (merged, report) =
+        WriteEngine::new(a.dimension()).merge_graphs_with_report(&a, &b, strategy)?;
+    graph_writer(merged.dimension()).write_to_file(&merged, output)?;
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "output": output.display().to_string(),
+                "strategy": strategy.name(),
+                "nodes_a": report.nodes_a,
+                "nodes_b": report.nodes_b,
+                "merged_nodes": report.merged_nodes,
+                "duplicates_removed": report.duplicates_removed,
+                "unshared_dropped": report.unshared_dropped,
+                "edges_merged": report.edges_merged,
+                "session_offset": report.session_offset,
+            }))
+            .unwrap_or_default()
+        );
+    } else {
+        println!(
+            "Merged {} ({} nodes) and {} ({} nodes) into {}",
+            path_a.display(),
+            report.nodes_a,
+            path_b.display(),
+            report.nodes_b,
+            output.display()
+        );
+        println!(
+            "  {} nodes, {} edges ({} strategy)",
+            report.merged_nodes,
+            report.edges_merged,
+            strategy.name()
+        );
+        println!("  Duplicates removed: {}", report.duplicates_removed);
+        if strategy == crate::engine::MergeStrategy::Intersect {
+            println!("  Unshared nodes dropped: {}", report.unshared_dropped);
+        }
+        if report.session_offset > 0 {
+            println!(
+                "  Sessions from {} shifted by {}",
+                path_b.display(),
+                report.session_offset
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Output format of `diff`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DiffFormat {
+    /// `-`/`+` lines in the style of a unified diff.
+    #[default]
+    Text,
+    /// The diff as a JSON object.
+    Json,
+    /// A JSON patch for `apply-patch`.
+    Patch,
+}
+
+impl DiffFormat {
+    /// Parse a format name (`text`, `json`, `patch`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "text" => Some(Self::Text),
+            "json" => Some(Self::Json),
+            "patch" => Some(Self::Patch),
+            _ => None,
+        }
+    }
+}
+
+/// Compare two .amem files node by node, matching nodes by ID.
+pub fn cmd_diff(path_a: &Path, path_b: &Path, format: DiffFormat) -> AmemResult<()> {
+    let graph_a = read_graph(path_a)?;
+    let graph_b = read_graph(path_b)?;
+    let diff = diff_graphs(&graph_a, &graph_b);
+
+    match format {
+        DiffFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&diff).unwrap_or_default()
+        ),
+        DiffFormat::Patch => println!(
+            "{}",
+            serde_json::to_string_pretty(&GraphPatch::from_diff(&diff, &graph_b))
+                .unwrap_or_default()
+        ),
+        DiffFormat::Text if diff.is_empty() => println!("No differences"),
+        DiffFormat::Text => {
+            println!("--- {}", path_a.display());
+            println!("+++ {}", path_b.display());
+            for node in &diff.only_in_a {
+                println!(
+                    "-{} [{}] {:?}",
+                    node.id,
+                    node.event_type.name(),
+                    node.content
+                );
+            }
+            for node in &diff.only_in_b {
+                println!(
+                    "+{} [{}] {:?}",
+                    node.id,
+                    node.event_type.name(),
+                    node.content
+                );
+            }
+            for change in &diff.modified {
+                println!("@@ node {} {} @@", change.id, change.field.name());
+                println!("-{}", change.before);
+                println!("+{}", change.after);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Apply a patch written by `diff --format patch` and save the file.
+pub fn cmd_apply_patch(path: &Path, patch_path: &Path, json: bool) -> AmemResult<()> {
+    let mut graph = read_graph(path)?;
+    let data = std::fs::read_to_string(patch_path)?;
+    let patch: GraphPatch = serde_json::from_str(&data)
+        .map_err(|e| crate::types::AmemError::PatchConflict(format!("invalid patch file: {e}")))?;
+
+    patch.apply(&mut graph)?;
+    if !patch.is_empty() {
+        let writer = graph_writer(graph.dimension());
+        writer.write_to_file(&graph, path)?;
+    }
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "removed": patch.remove.len(),
+                "added": patch.add.len(),
+                "edges_added": patch.edges.len(),
+                "modified": patch.modify.len(),
+            }))
+            .unwrap_or_default()
+        );
+    } else {
+        println!("Applied patch to {}:", path.display());
+        println!("  Removed: {} nodes", patch.remove.len());
+        println!(
+            "  Added: {} nodes, {} edges",
+            patch.add.len(),
+            patch.edges.len()
+        );
+        println!("  Modified: {} fields", patch.modify.len());
+    }
+    Ok(())
 }
 
 /// Run decay calculations.
 pub fn cmd_decay(path: &Path, threshold: f32, json: bool) -> AmemResult<()> {
-    let mut graph = AmemReader::read_from_file(path)?;
+    let mut graph = read_graph(path)?;
     let write_engine = WriteEngine::new(graph.dimension());
     let current_time = crate::types::now_micros();
     let report = write_engine.run_decay(&mut graph, current_time)?;
 
-    let writer = AmemWriter::new(graph.dimension());
+    let writer = graph_writer(graph.dimension());
     writer.write_to_file(&graph, path)?;
 
     let low: Vec<u64> = report
@@ -657,6 +2320,7 @@ pub fn cmd_decay(path: &Path, threshold: f32, json: bool) -> AmemResult<()> {
     if json {
         let info = serde_json::json!({
             "nodes_decayed": report.nodes_decayed,
+            "pinned_count": report.pinned_count,
             "low_importance_count": low.len(),
             "low_importance_nodes": low,
         });
@@ -667,6 +2331,7 @@ pub fn cmd_decay(path: &Path, threshold: f32, json: bool) -> AmemResult<()> {
     } else {
         println!("Decay complete:");
         println!("  Nodes updated: {}", report.nodes_decayed);
+        println!("  Pinned (skipped): {}", report.pinned_count);
         println!(
             "  Low importance (below {}): {} nodes",
             threshold,
@@ -678,7 +2343,7 @@ pub fn cmd_decay(path: &Path, threshold: f32, json: bool) -> AmemResult<()> {
 
 /// Detailed statistics.
 pub fn cmd_stats(path: &Path, json: bool) -> AmemResult<()> {
-    let graph = AmemReader::read_from_file(path)?;
+    let graph = read_graph(path)?;
     let file_size = std::fs::metadata(path)?.len();
 
     let node_count = graph.node_count();
@@ -700,6 +2365,8 @@ pub fn cmd_stats(path: &Path, json: bool) -> AmemResult<()> {
     } else {
         0.0
     };
+    let components = graph.connected_components();
+    let largest_component = components.iter().map(Vec::len).max().unwrap_or(0);
 
     // Confidence distribution
     let mut conf_buckets = [0usize; 5];
@@ -715,6 +2382,8 @@ pub fn cmd_stats(path: &Path, json: bool) -> AmemResult<()> {
             "avg_edges_per_node": avg_edges,
             "max_edges_per_node": max_edges,
             "sessions": session_count,
+            "components": components.len(),
+            "largest_component": largest_component,
             "file_size": file_size,
         });
         println!(
@@ -729,6 +2398,11 @@ pub fn cmd_stats(path: &Path, json: bool) -> AmemResult<()> {
         println!("  Max edges per node: {}", max_edges);
         println!("  Sessions: {}", session_count);
         println!("  Avg nodes per session: {:.0}", avg_nodes_per_session);
+        println!(
+            "  Disconnected components: {} (largest: {} nodes)",
+            components.len(),
+            largest_component
+        );
         println!();
         println!("  Confidence distribution:");
         println!("    0.0-0.2: {} nodes", conf_buckets[0]);
@@ -738,119 +2412,663 @@ pub fn cmd_stats(path: &Path, json: bool) -> AmemResult<()> {
         println!("    0.8-1.0: {} nodes", conf_buckets[4]);
         println!();
         println!("  Edge type distribution:");
-        for et_val in 0u8..=6 {
-            if let Some(et) = EdgeType::from_u8(et_val) {
-                let count = graph.edges().iter().filter(|e| e.edge_type == et).count();
-                if count > 0 {
-                    println!("    {}: {}", et.name(), count);
-                }
+        for et in graph.edge_types() {
+            let count = graph.edges().iter().filter(|e| e.edge_type == et).count();
+            if count > 0 {
+                println!("    {}: {}", graph.edge_type_name(et), count);
             }
         }
     }
     Ok(())
 }
 
-/// Graph-wide quality report (confidence, staleness, structural health).
-pub fn cmd_quality(
-    path: &Path,
-    low_confidence: f32,
-    stale_decay: f32,
-    limit: usize,
-    json: bool,
-) -> AmemResult<()> {
-    let graph = AmemReader::read_from_file(path)?;
-    let query_engine = QueryEngine::new();
-    let report = query_engine.memory_quality(
-        &graph,
-        MemoryQualityParams {
-            low_confidence_threshold: low_confidence.clamp(0.0, 1.0),
-            stale_decay_threshold: stale_decay.clamp(0.0, 1.0),
-            max_examples: limit.max(1),
-        },
-    )?;
+/// Statistics of one file that `stats --compare` puts side by side.
+struct StatsSummary {
+    version: u32,
+    dimension: usize,
+    embedding_model: Option<String>,
+    nodes: usize,
+    edges: usize,
+    sessions: usize,
+    file_size: u64,
+    avg_confidence: f64,
+    by_event_type: Vec<(EventType, usize)>,
+    /// Edge counts keyed by name, since custom type ids are per file.
+    by_edge_type: Vec<(String, usize)>,
+}
+
+impl StatsSummary {
+    fn read(path: &Path) -> AmemResult<Self> {
+        let data = read_plain_bytes(path)?;
+        let header = crate::types::header::FileHeader::read_from(&mut data.as_slice())?;
+        let graph = AmemReader::read_from(&mut data.as_slice())?;
+        let nodes = graph.node_count();
+        let avg_confidence = if nodes > 0 {
+            graph
+                .nodes()
+                .iter()
+                .map(|n| n.confidence as f64)
+                .sum::<f64>()
+                / nodes as f64
+        } else {
+            0.0
+        };
+        Ok(Self {
+            version: header.version,
+            dimension: graph.dimension(),
+            embedding_model: graph.embedding_model().map(str::to_string),
+            nodes,
+            edges: graph.edge_count(),
+            sessions: graph.session_index().session_count(),
+            file_size: std::fs::metadata(path)?.len(),
+            avg_confidence,
+            by_event_type: (0u8..=5)
+                .filter_map(EventType::from_u8)
+                .map(|et| (et, graph.type_index().count(et)))
+                .collect(),
+            by_edge_type: graph
+                .edge_types()
+                .into_iter()
+                .map(|et| {
+                    (
+                        graph.edge_type_name(et).to_string(),
+                        graph.edges().iter().filter(|e| e.edge_type == et).count(),
+                    )
+                })
+                .collect(),
+        })
+    }
+
+    fn to_json(&self, path: &Path) -> serde_json::Value {
+        let event_types: serde_json::Map<String, serde_json::Value> = self
+            .by_event_type
+            .iter()
+            .map(|(et, n)| (et.name().to_string(), serde_json::json!(n)))
+            .collect();
+        let edge_types: serde_json::Map<String, serde_json::Value> = self
+            .by_edge_type
+            .iter()
+            .map(|(name, n)| (name.clone(), serde_json::json!(n)))
+            .collect();
+        serde_json::json!({
+            "file": path.display().to_string(),
+            "version": self.version,
+            "dimension": self.dimension,
+            "embedding_model": self.embedding_model,
+            "nodes": self.nodes,
+            "edges": self.edges,
+            "sessions": self.sessions,
+            "file_size": self.file_size,
+            "avg_confidence": self.avg_confidence,
+            "event_types": event_types,
+            "edge_types": edge_types,
+        })
+    }
+}
+
+/// Share of `count` in `total`, in percent.
+fn share_pct(count: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        count as f64 * 100.0 / total as f64
+    }
+}
+
+/// Print the stats of `path` next to those of `baseline`, with deltas
+/// (`path` minus `baseline`). Counts are always compared; differences in
+/// format version, dimension or embedding model are reported as notes.
+pub fn cmd_stats_compare(path: &Path, baseline: &Path, json: bool) -> AmemResult<()> {
+    let current = StatsSummary::read(path)?;
+    let base = StatsSummary::read(baseline)?;
+
+    let mut notes = Vec::new();
+    if current.version != base.version {
+        notes.push(format!(
+            "format version differs ({} vs {}); counts are compared as stored",
+            current.version, base.version
+        ));
+    }
+    if current.dimension != base.dimension {
+        notes.push(format!(
+            "dimension differs ({} vs {}); feature vectors are not comparable",
+            current.dimension, base.dimension
+        ));
+    }
+    if current.embedding_model != base.embedding_model {
+        notes.push(format!(
+            "embedding model differs ({} vs {}); feature vectors are not comparable",
+            current.embedding_model.as_deref().unwrap_or("none"),
+            base.embedding_model.as_deref().unwrap_or("none")
+        ));
+    }
+
+    let delta = |a: usize, b: usize| a as i64 - b as i64;
+    let type_rows: Vec<(&str, usize, usize)> = current
+        .by_event_type
+        .iter()
+        .zip(&base.by_event_type)
+        .map(|(&(et, a), &(_, b))| (et.name(), a, b))
+        .collect();
+    // Custom edge types may exist in only one of the two files, and their
+    // ids are per file, so rows are matched by name.
+    let edge_count = |summary: &StatsSummary, name: &str| {
+        summary
+            .by_edge_type
+            .iter()
+            .find(|(t, _)| t == name)
+            .map_or(0, |&(_, n)| n)
+    };
+    let mut edge_names: Vec<&str> = Vec::new();
+    for (name, _) in current.by_edge_type.iter().chain(&base.by_edge_type) {
+        if !edge_names.contains(&name.as_str()) {
+            edge_names.push(name);
+        }
+    }
+    let edge_rows: Vec<(&str, usize, usize)> = edge_names
+        .into_iter()
+        .map(|name| (name, edge_count(&current, name), edge_count(&base, name)))
+        .collect();
 
     if json {
-        let out = serde_json::json!({
-            "status": report.status,
-            "summary": {
-                "nodes": report.node_count,
-                "edges": report.edge_count,
-                "low_confidence_count": report.low_confidence_count,
-                "stale_count": report.stale_count,
-                "orphan_count": report.orphan_count,
-                "decisions_without_support_count": report.decisions_without_support_count,
-                "contradiction_edges": report.contradiction_edges,
-                "supersedes_edges": report.supersedes_edges,
+        let shifts = |rows: &[(&str, usize, usize)], total_a: usize, total_b: usize| {
+            rows.iter()
+                .map(|&(name, a, b)| {
+                    (
+                        name.to_string(),
+                        serde_json::json!({
+                            "count": delta(a, b),
+                            "share_points": share_pct(a, total_a) - share_pct(b, total_b),
+                        }),
+                    )
+                })
+                .collect::<serde_json::Map<String, serde_json::Value>>()
+        };
+        let info = serde_json::json!({
+            "current": current.to_json(path),
+            "baseline": base.to_json(baseline),
+            "delta": {
+                "nodes": delta(current.nodes, base.nodes),
+                "edges": delta(current.edges, base.edges),
+                "sessions": delta(current.sessions, base.sessions),
+                "file_size": current.file_size as i64 - base.file_size as i64,
+                "avg_confidence": current.avg_confidence - base.avg_confidence,
+                "event_types": shifts(&type_rows, current.nodes, base.nodes),
+                "edge_types": shifts(&edge_rows, current.edges, base.edges),
             },
-            "examples": {
-                "low_confidence": report.low_confidence_examples,
-                "stale": report.stale_examples,
-                "orphan": report.orphan_examples,
-                "unsupported_decisions": report.unsupported_decision_examples,
-            }
+            "notes": notes,
         });
-        println!("{}", serde_json::to_string_pretty(&out).unwrap_or_default());
-    } else {
-        println!("Memory quality report for {}", path.display());
-        println!("  Status: {}", report.status.to_uppercase());
-        println!("  Nodes: {}", report.node_count);
-        println!("  Edges: {}", report.edge_count);
-        println!(
-            "  Weak confidence (<{:.2}): {}",
-            low_confidence, report.low_confidence_count
-        );
-        println!("  Stale (<{:.2}): {}", stale_decay, report.stale_count);
-        println!("  Orphan nodes: {}", report.orphan_count);
         println!(
-            "  Decisions without support edges: {}",
-            report.decisions_without_support_count
+            "{}",
+            serde_json::to_string_pretty(&info).unwrap_or_default()
         );
-        println!("  Contradiction edges: {}", report.contradiction_edges);
-        println!("  Supersedes edges: {}", report.supersedes_edges);
-        if !report.low_confidence_examples.is_empty() {
-            println!(
-                "  Low-confidence examples: {:?}",
-                report.low_confidence_examples
-            );
+        return Ok(());
+    }
+
+    println!(
+        "Comparing {} against {}:",
+        path.display(),
+        baseline.display()
+    );
+    println!(
+        "  {:<22} {:>12} {:>12} {:>10}",
+        "", "current", "baseline", "delta"
+    );
+    let row = |label: &str, a: usize, b: usize| {
+        println!("  {:<22} {:>12} {:>12} {:>+10}", label, a, b, delta(a, b));
+    };
+    row("Nodes", current.nodes, base.nodes);
+    row("Edges", current.edges, base.edges);
+    row("Sessions", current.sessions, base.sessions);
+    row(
+        "File size (bytes)",
+        current.file_size as usize,
+        base.file_size as usize,
+    );
+    println!(
+        "  {:<22} {:>12.3} {:>12.3} {:>+10.3}",
+        "Avg confidence",
+        current.avg_confidence,
+        base.avg_confidence,
+        current.avg_confidence - base.avg_confidence
+    );
+    println!();
+    println!("  Event types:");
+    for &(name, a, b) in &type_rows {
+        if a > 0 || b > 0 {
+            row(&format!("  {}", name), a, b);
         }
-        if !report.unsupported_decision_examples.is_empty() {
-            println!(
-                "  Unsupported decision examples: {:?}",
-                report.unsupported_decision_examples
-            );
+    }
+    println!();
+    println!("  Edge types:");
+    for &(name, a, b) in &edge_rows {
+        if a > 0 || b > 0 {
+            row(&format!("  {}", name), a, b);
+        }
+    }
+    if !notes.is_empty() {
+        println!();
+        println!("  Notes:");
+        for note in &notes {
+            println!("    - {}", note);
         }
-        println!(
-            "  Next: amem runtime-sync {} --workspace . --write-episode",
-            path.display()
-        );
     }
-
     Ok(())
 }
 
-#[derive(Default)]
-struct ArtifactScanReport {
-    amem_files: Vec<std::path::PathBuf>,
-    acb_files: Vec<std::path::PathBuf>,
-    avis_files: Vec<std::path::PathBuf>,
-    io_errors: usize,
+/// Window used for the "recent activity" rate in `stats --watch`.
+const WATCH_RECENT_WINDOW_SECS: u64 = 300;
+
+/// Counts shown by one `stats --watch` refresh.
+struct WatchSnapshot {
+    nodes: usize,
+    edges: usize,
+    sessions: usize,
+    file_size: u64,
+    by_type: Vec<(EventType, usize)>,
+    /// Nodes created within the last [`WATCH_RECENT_WINDOW_SECS`].
+    recent_nodes: usize,
 }
 
-/// Scan a workspace for sister artifacts and optionally write an episode memory.
-pub fn cmd_runtime_sync(
+impl WatchSnapshot {
+    /// Read the file fresh. A writer may be replacing it mid-read, so a
+    /// failed read is retried briefly before the error is reported.
+    fn read(path: &Path) -> AmemResult<Self> {
+        let mut attempt = 0u64;
+        let graph = loop {
+            match read_graph(path) {
+                Ok(graph) => break graph,
+                Err(e) if attempt >= 2 => return Err(e),
+                Err(_) => {
+                    attempt += 1;
+                    std::thread::sleep(std::time::Duration::from_millis(100 * attempt));
+                }
+            }
+        };
+        let file_size = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+        let cutoff =
+            crate::types::now_micros().saturating_sub(WATCH_RECENT_WINDOW_SECS * 1_000_000);
+
+        Ok(Self {
+            nodes: graph.node_count(),
+            edges: graph.edge_count(),
+            sessions: graph.session_index().session_count(),
+            file_size,
+            by_type: (0u8..=5)
+                .filter_map(EventType::from_u8)
+                .map(|et| (et, graph.type_index().count(et)))
+                .collect(),
+            recent_nodes: graph
+                .nodes()
+                .iter()
+                .filter(|n| n.created_at >= cutoff)
+                .count(),
+        })
+    }
+}
+
+/// Live statistics: re-read the file every `interval_secs` and redraw.
+///
+/// Text mode clears the screen with plain ANSI escapes when stdout is a
+/// terminal and changes no other terminal state, so Ctrl-C leaves the last
+/// frame in place. JSON mode prints one object per line instead. `count`
+/// stops after that many refreshes; `None` runs until interrupted.
+pub fn cmd_stats_watch(
     path: &Path,
-    workspace: &Path,
-    max_depth: u32,
-    session_id: u32,
-    write_episode: bool,
+    interval_secs: u64,
+    count: Option<usize>,
     json: bool,
 ) -> AmemResult<()> {
-    let mut graph = AmemReader::read_from_file(path)?;
-    let report = scan_workspace_artifacts(workspace, max_depth);
+    use std::io::{IsTerminal, Write};
 
-    let mut episode_id = None;
-    if write_episode {
-        let sid = if session_id == 0 {
+    let interval = std::time::Duration::from_secs(interval_secs.max(1));
+    let clear = !json && std::io::stdout().is_terminal();
+    let mut previous: Option<(WatchSnapshot, std::time::Instant)> = None;
+    let mut refreshes = 0usize;
+
+    loop {
+        let now = std::time::Instant::now();
+        match WatchSnapshot::read(path) {
+            Ok(snap) => {
+                let delta = previous.as_ref().map(|(prev, at)| {
+                    (
+                        snap.nodes as i64 - prev.nodes as i64,
+                        snap.edges as i64 - prev.edges as i64,
+                        now.duration_since(*at).as_secs_f64(),
+                    )
+                });
+                let recent_rate =
+                    snap.recent_nodes as f64 / (WATCH_RECENT_WINDOW_SECS as f64 / 60.0);
+
+                if json {
+                    let by_type: serde_json::Map<String, serde_json::Value> = snap
+                        .by_type
+                        .iter()
+                        .map(|(et, n)| (et.name().to_string(), serde_json::json!(n)))
+                        .collect();
+                    println!(
+                        "{}",
+                        serde_json::json!({
+                            "nodes": snap.nodes,
+                            "edges": snap.edges,
+                            "sessions": snap.sessions,
+                            "file_size": snap.file_size,
+                            "event_types": by_type,
+                            "delta_nodes": delta.map(|d| d.0),
+                            "delta_edges": delta.map(|d| d.1),
+                            "recent_nodes": snap.recent_nodes,
+                            "recent_window_secs": WATCH_RECENT_WINDOW_SECS,
+                        })
+                    );
+                } else {
+                    if clear {
+                        print!("\x1b[2J\x1b[H");
+                    } else if previous.is_some() {
+                        println!();
+                    }
+                    println!(
+                        "{} (every {}s, Ctrl-C to exit)",
+                        path.display(),
+                        interval.as_secs()
+                    );
+                    println!("  Nodes: {}", snap.nodes);
+                    println!("  Edges: {}", snap.edges);
+                    println!("  Sessions: {}", snap.sessions);
+                    println!("  File size: {} bytes", snap.file_size);
+                    match delta {
+                        Some((nodes, edges, secs)) => {
+                            let per_min = if secs > 0.0 {
+                                nodes as f64 * 60.0 / secs
+                            } else {
+                                0.0
+                            };
+                            println!(
+                                "  {:+} nodes, {:+} edges since last refresh ({:.1} nodes/min)",
+                                nodes, edges, per_min
+                            );
+                        }
+                        None => println!("  (first refresh)"),
+                    }
+                    println!(
+                        "  Recent activity: {} nodes in the last {} min ({:.1}/min)",
+                        snap.recent_nodes,
+                        WATCH_RECENT_WINDOW_SECS / 60,
+                        recent_rate
+                    );
+                    println!();
+                    println!("  Event types:");
+                    for (et, n) in &snap.by_type {
+                        println!("    {}: {}", et.name(), n);
+                    }
+                }
+                let _ = std::io::stdout().flush();
+                previous = Some((snap, now));
+            }
+            // Keep watching through a bad read once we have a baseline.
+            Err(e) if previous.is_some() => {
+                if json {
+                    println!("{}", serde_json::json!({"error": e.to_string()}));
+                } else {
+                    eprintln!("Refresh failed: {}", e);
+                }
+            }
+            Err(e) => return Err(e),
+        }
+
+        refreshes += 1;
+        if count.is_some_and(|c| refreshes >= c) {
+            return Ok(());
+        }
+        std::thread::sleep(interval);
+    }
+}
+
+/// Characters of content shown per node by `tail-session`.
+const TAIL_PREVIEW_CHARS: usize = 80;
+
+/// One `tail-session` line: time, ID, type, session and a content preview.
+fn tail_line(node: &CognitiveEvent) -> String {
+    let mut preview: String = node
+        .content
+        .chars()
+        .take(TAIL_PREVIEW_CHARS)
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect();
+    if node.content.chars().count() > TAIL_PREVIEW_CHARS {
+        preview.push_str("...");
+    }
+    format!(
+        "{}  [{}] {} (session {}): {}",
+        format_timestamp(node.created_at),
+        node.id,
+        node.event_type.name(),
+        node.session_id,
+        preview
+    )
+}
+
+fn tail_json(node: &CognitiveEvent) -> serde_json::Value {
+    serde_json::json!({
+        "id": node.id,
+        "type": node.event_type.name(),
+        "session_id": node.session_id,
+        "created_at": node.created_at,
+        "content": node.content,
+    })
+}
+
+/// The `limit` most recently created nodes across all sessions, newest
+/// first, read off the temporal index.
+///
+/// With `follow` the file is re-read every `interval_secs` and nodes created
+/// since the last one shown are printed oldest first as they appear, one
+/// JSON object per line in JSON mode. `count` stops after that many polls;
+/// `None` runs until interrupted.
+pub fn cmd_tail_session(
+    path: &Path,
+    limit: usize,
+    follow: bool,
+    interval_secs: u64,
+    count: Option<usize>,
+    json: bool,
+) -> AmemResult<()> {
+    use std::io::Write;
+
+    let graph = read_graph(path)?;
+    let recent: Vec<&CognitiveEvent> = graph
+        .temporal_index()
+        .most_recent(limit)
+        .into_iter()
+        .filter_map(|id| graph.get_node(id))
+        .collect();
+
+    if json && !follow {
+        let entries: Vec<serde_json::Value> = recent.iter().copied().map(tail_json).collect();
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "total_nodes": graph.node_count(),
+                "nodes": entries,
+            }))
+            .unwrap_or_default()
+        );
+        return Ok(());
+    }
+    for node in &recent {
+        if json {
+            println!("{}", tail_json(node));
+        } else {
+            println!("{}", tail_line(node));
+        }
+    }
+    if !follow {
+        if !json && recent.is_empty() {
+            println!("No nodes in {}", path.display());
+        }
+        return Ok(());
+    }
+
+    // Everything at or before this (created_at, id) entry has been seen.
+    let mut seen = graph.temporal_index().entries().last().copied();
+    let interval = std::time::Duration::from_secs(interval_secs.max(1));
+    let mut polls = 0usize;
+    let _ = std::io::stdout().flush();
+    while count.is_none_or(|c| polls < c) {
+        std::thread::sleep(interval);
+        polls += 1;
+        let graph = match read_graph(path) {
+            Ok(graph) => graph,
+            // A writer may be mid-save; try again on the next poll.
+            Err(e) => {
+                eprintln!("Poll failed: {}", e);
+                continue;
+            }
+        };
+        let entries = graph.temporal_index().entries();
+        let from = seen.map_or(0, |last| entries.partition_point(|&entry| entry <= last));
+        for &(_, id) in &entries[from..] {
+            let Some(node) = graph.get_node(id) else {
+                continue;
+            };
+            if json {
+                println!("{}", tail_json(node));
+            } else {
+                println!("{}", tail_line(node));
+            }
+        }
+        if let Some(&last) = entries.last() {
+            seen = seen.max(Some(last));
+        }
+        let _ = std::io::stdout().flush();
+    }
+    Ok(())
+}
+
+/// Graph-wide quality report (confidence, staleness, structural health).
+pub fn cmd_quality(
+    path: &Path,
+    low_confidence: f32,
+    stale_decay: f32,
+    limit: usize,
+    json: bool,
+) -> AmemResult<()> {
+    let graph = read_graph(path)?;
+    let query_engine = QueryEngine::new();
+    let report = query_engine.memory_quality(
+        &graph,
+        MemoryQualityParams {
+            low_confidence_threshold: low_confidence.clamp(0.0, 1.0),
+            stale_decay_threshold: stale_decay.clamp(0.0, 1.0),
+            max_examples: limit.max(1),
+        },
+    )?;
+
+    if json {
+        let out = serde_json::json!({
+            "status": report.status,
+            "summary": {
+                "nodes": report.node_count,
+                "edges": report.edge_count,
+                "low_confidence_count": report.low_confidence_count,
+                "stale_count": report.stale_count,
+                "orphan_count": report.orphan_count,
+                "decisions_without_support_count": report.decisions_without_support_count,
+                "contradiction_edges": report.contradiction_edges,
+                "supersedes_edges": report.supersedes_edges,
+            },
+            "confidence_decay": {
+                "confident_fresh": report.quadrants.confident_fresh,
+                "confident_stale": report.quadrants.confident_stale,
+                "weak_fresh": report.quadrants.weak_fresh,
+                "weak_stale": report.quadrants.weak_stale,
+            },
+            "examples": {
+                "low_confidence": report.low_confidence_examples,
+                "stale": report.stale_examples,
+                "orphan": report.orphan_examples,
+                "unsupported_decisions": report.unsupported_decision_examples,
+                "confident_stale": report.confident_stale_examples,
+            }
+        });
+        println!("{}", serde_json::to_string_pretty(&out).unwrap_or_default());
+    } else {
+        println!("Memory quality report for {}", path.display());
+        println!("  Status: {}", report.status.to_uppercase());
+        println!("  Nodes: {}", report.node_count);
+        println!("  Edges: {}", report.edge_count);
+        println!(
+            "  Weak confidence (<{:.2}): {}",
+            low_confidence, report.low_confidence_count
+        );
+        println!("  Stale (<{:.2}): {}", stale_decay, report.stale_count);
+        println!("  Orphan nodes: {}", report.orphan_count);
+        println!(
+            "  Decisions without support edges: {}",
+            report.decisions_without_support_count
+        );
+        println!("  Contradiction edges: {}", report.contradiction_edges);
+        println!("  Supersedes edges: {}", report.supersedes_edges);
+        println!();
+        println!("  Confidence x freshness:");
+        println!("    {:<12} {:>8} {:>8}", "", "fresh", "stale");
+        println!(
+            "    {:<12} {:>8} {:>8}",
+            "confident", report.quadrants.confident_fresh, report.quadrants.confident_stale
+        );
+        println!(
+            "    {:<12} {:>8} {:>8}",
+            "weak", report.quadrants.weak_fresh, report.quadrants.weak_stale
+        );
+        if !report.confident_stale_examples.is_empty() {
+            println!(
+                "  Confident but stale (re-verify): {:?}",
+                report.confident_stale_examples
+            );
+        }
+        if !report.low_confidence_examples.is_empty() {
+            println!(
+                "  Low-confidence examples: {:?}",
+                report.low_confidence_examples
+            );
+        }
+        if !report.unsupported_decision_examples.is_empty() {
+            println!(
+                "  Unsupported decision examples: {:?}",
+                report.unsupported_decision_examples
+            );
+        }
+        println!(
+            "  Next: amem runtime-sync {} --workspace . --write-episode",
+            path.display()
+        );
+    }
+
+    Ok(())
+}
+
+#[derive(Default)]
+struct ArtifactScanReport {
+    amem_files: Vec<std::path::PathBuf>,
+    acb_files: Vec<std::path::PathBuf>,
+    avis_files: Vec<std::path::PathBuf>,
+    io_errors: usize,
+}
+
+/// Scan a workspace for sister artifacts and optionally write an episode memory.
+pub fn cmd_runtime_sync(
+    path: &Path,
+    workspace: &Path,
+    max_depth: u32,
+    session_id: u32,
+    write_episode: bool,
+    json: bool,
+) -> AmemResult<()> {
+    let mut graph = read_graph(path)?;
+    let report = scan_workspace_artifacts(workspace, max_depth);
+
+    let mut episode_id = None;
+    if write_episode {
+        let sid = if session_id == 0 {
             graph
                 .session_index()
                 .session_ids()
@@ -875,7 +3093,7 @@ pub fn cmd_runtime_sync(
             .build();
         let id = graph.add_node(event)?;
         episode_id = Some(id);
-        let writer = AmemWriter::new(graph.dimension());
+        let writer = graph_writer(graph.dimension());
         writer.write_to_file(&graph, path)?;
     }
 
@@ -920,7 +3138,7 @@ pub fn cmd_runtime_sync(
 
 /// Estimate long-horizon storage growth vs configured budget.
 pub fn cmd_budget(path: &Path, max_bytes: u64, horizon_years: u32, json: bool) -> AmemResult<()> {
-    let graph = AmemReader::read_from_file(path)?;
+    let graph = read_graph(path)?;
     let current_size = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
     let projected = estimate_projected_size(&graph, current_size, horizon_years);
     let over_budget = current_size > max_bytes || projected.map(|v| v > max_bytes).unwrap_or(false);
@@ -1070,6 +3288,65 @@ fn format_size(bytes: u64) -> String {
     }
 }
 
+/// Result entries for expanded episode members, marked with their Episode.
+fn episode_members_json(graph: &MemoryGraph, members: &[EpisodeMember]) -> Vec<serde_json::Value> {
+    members
+        .iter()
+        .filter_map(|member| {
+            let node = graph.get_node(member.node_id)?;
+            Some(serde_json::json!({
+                "node_id": member.node_id,
+                "expanded_from_episode": member.episode_id,
+                "type": node.event_type.name(),
+                "content": node.content,
+            }))
+        })
+        .collect()
+}
+
+/// Print the members expanded from `episode_id`, indented under its hit.
+fn print_episode_members(graph: &MemoryGraph, members: &[EpisodeMember], episode_id: u64) {
+    for member in members.iter().filter(|m| m.episode_id == episode_id) {
+        if let Some(node) = graph.get_node(member.node_id) {
+            let preview = if node.content.len() > 60 {
+                format!("{}...", &node.content[..60])
+            } else {
+                node.content.clone()
+            };
+            println!(
+                "         - Node {} ({})  {:?}",
+                node.id,
+                node.event_type.name(),
+                preview
+            );
+        }
+    }
+}
+
+/// Print a query profile breakdown to stderr, keeping stdout parseable.
+fn print_profile(profile: &QueryProfile, node_count: usize, result_count: usize) {
+    let total = profile.total();
+    eprintln!("Profile ({} nodes, {} results):", node_count, result_count);
+    for phase in profile.phases() {
+        let share = if total.is_zero() {
+            0.0
+        } else {
+            phase.elapsed.as_secs_f64() / total.as_secs_f64() * 100.0
+        };
+        eprintln!(
+            "  {:<14} {:>10.3} ms  {:>5.1}%",
+            phase.name,
+            phase.elapsed.as_secs_f64() * 1000.0,
+            share
+        );
+    }
+    eprintln!(
+        "  {:<14} {:>10.3} ms",
+        "total",
+        total.as_secs_f64() * 1000.0
+    );
+}
+
 fn format_timestamp(micros: u64) -> String {
     let secs = (micros / 1_000_000) as i64;
     let dt = chrono::DateTime::from_timestamp(secs, 0);
@@ -1082,6 +3359,7 @@ fn format_timestamp(micros: u64) -> String {
 // ==================== New Query Expansion Commands ====================
 
 /// BM25 text search.
+#[allow(clippy::too_many_arguments)]
 pub fn cmd_text_search(
     path: &Path,
     query: &str,
@@ -1089,24 +3367,52 @@ pub fn cmd_text_search(
     session_ids: Vec<u32>,
     limit: usize,
     min_score: f32,
+    require_all: bool,
+    confidence_weight: f32,
+    expand_episodes: Option<usize>,
+    explain: bool,
     json: bool,
 ) -> AmemResult<()> {
-    let graph = AmemReader::read_from_file(path)?;
+    let graph = read_graph(path)?;
     let query_engine = QueryEngine::new();
 
+    // Filters are kept for --explain before the params are consumed.
+    let mut filters = Vec::new();
+    if !event_types.is_empty() {
+        let names: Vec<&str> = event_types.iter().map(|t| t.name()).collect();
+        filters.push(format!("type in [{}]", names.join(", ")));
+    }
+    if !session_ids.is_empty() {
+        let ids: Vec<String> = session_ids.iter().map(|s| s.to_string()).collect();
+        filters.push(format!("session in [{}]", ids.join(", ")));
+    }
+    if min_score > 0.0 {
+        filters.push(format!("bm25 >= {:.2}", min_score));
+    }
+    if require_all {
+        filters.push("contains every query term".to_string());
+    }
+
     let start = std::time::Instant::now();
-    let results = query_engine.text_search(
-        &graph,
-        graph.term_index(),
-        graph.doc_lengths(),
-        TextSearchParams {
-            query: query.to_string(),
-            max_results: limit,
-            event_types,
-            session_ids,
-            min_score,
-        },
-    )?;
+    let params = TextSearchParams {
+        query: query.to_string(),
+        max_results: limit,
+        event_types,
+        session_ids,
+        min_score,
+        confidence_weight,
+    };
+    let results = if require_all {
+        query_engine.text_search_all(&graph, graph.term_index(), graph.doc_lengths(), params)?
+    } else {
+        query_engine.text_search(&graph, graph.term_index(), graph.doc_lengths(), params)?
+    };
+    let members = expand_episodes
+        .map(|max_per_episode| {
+            let hit_ids: Vec<u64> = results.iter().map(|m| m.node_id).collect();
+            query_engine.expand_episodes(&graph, &hit_ids, max_per_episode)
+        })
+        .unwrap_or_default();
     let elapsed = start.elapsed();
 
     if json {
@@ -1115,15 +3421,31 @@ pub fn cmd_text_search(
             .enumerate()
             .map(|(i, m)| {
                 let node = graph.get_node(m.node_id);
-                serde_json::json!({
+                let mut entry = serde_json::json!({
                     "rank": i + 1,
                     "node_id": m.node_id,
                     "score": m.score,
                     "matched_terms": m.matched_terms,
                     "type": node.map(|n| n.event_type.name()).unwrap_or("unknown"),
                     "content": node.map(|n| n.content.as_str()).unwrap_or(""),
-                })
+                });
+                if explain {
+                    let terms: Vec<serde_json::Value> = m
+                        .matched_terms
+                        .iter()
+                        .zip(&m.term_scores)
+                        .map(|(term, score)| serde_json::json!({"term": term, "score": score}))
+                        .collect();
+                    entry["explain"] = serde_json::json!({
+                        "bm25": m.term_scores.iter().sum::<f32>(),
+                        "terms": terms,
+                        "confidence_factor": confidence_factor(m.score, &m.term_scores),
+                        "filters": filters,
+                    });
+                }
+                entry
             })
+            .chain(episode_members_json(&graph, &members))
             .collect();
         println!(
             "{}",
@@ -1152,6 +3474,26 @@ pub fn cmd_text_search(
                     m.score,
                     preview
                 );
+                if explain {
+                    let terms: Vec<String> = m
+                        .matched_terms
+                        .iter()
+                        .zip(&m.term_scores)
+                        .map(|(term, score)| format!("{} {:.2}", term, score))
+                        .collect();
+                    let mut reasons = vec![format!(
+                        "bm25 {:.2} = {}",
+                        m.term_scores.iter().sum::<f32>(),
+                        terms.join(" + ")
+                    )];
+                    let factor = confidence_factor(m.score, &m.term_scores);
+                    if (factor - 1.0).abs() > 1e-6 {
+                        reasons.push(format!("confidence x{:.2}", factor));
+                    }
+                    reasons.extend(filters.iter().cloned());
+                    println!("        why: {}", reasons.join("; "));
+                }
+                print_episode_members(&graph, &members, m.node_id);
             }
         }
         println!(
@@ -1163,34 +3505,38 @@ pub fn cmd_text_search(
     Ok(())
 }
 
-/// Hybrid BM25 + vector search.
-#[allow(clippy::too_many_arguments)]
-pub fn cmd_hybrid_search(
+/// Multiplier confidence weighting applied to a match's BM25 score.
+fn confidence_factor(score: f32, term_scores: &[f32]) -> f32 {
+    let bm25: f32 = term_scores.iter().sum();
+    if bm25 > 0.0 {
+        score / bm25
+    } else {
+        1.0
+    }
+}
+
+/// Typo-tolerant search by Jaro-Winkler similarity between query and
+/// content tokens.
+pub fn cmd_fuzzy_search(
     path: &Path,
     query: &str,
-    text_weight: f32,
-    vec_weight: f32,
-    limit: usize,
     event_types: Vec<EventType>,
+    limit: usize,
+    min_similarity: f32,
     json: bool,
 ) -> AmemResult<()> {
-    let graph = AmemReader::read_from_file(path)?;
-    let query_engine = QueryEngine::new();
-
-    let results = query_engine.hybrid_search(
+    let graph = read_graph(path)?;
+    let start = std::time::Instant::now();
+    let results = QueryEngine::new().fuzzy_search(
         &graph,
-        graph.term_index(),
-        graph.doc_lengths(),
-        HybridSearchParams {
-            query_text: query.to_string(),
-            query_vec: None,
+        FuzzySearchParams {
+            query: query.to_string(),
             max_results: limit,
+            min_similarity,
             event_types,
-            text_weight,
-            vector_weight: vec_weight,
-            rrf_k: 60,
         },
     )?;
+    let elapsed = start.elapsed();
 
     if json {
         let matches: Vec<serde_json::Value> = results
@@ -1198,18 +3544,345 @@ pub fn cmd_hybrid_search(
             .enumerate()
             .map(|(i, m)| {
                 let node = graph.get_node(m.node_id);
+                let terms: Vec<serde_json::Value> = m
+                    .matched_terms
+                    .iter()
+                    .zip(&m.term_scores)
+                    .map(|(term, sim)| serde_json::json!({"term": term, "similarity": sim}))
+                    .collect();
                 serde_json::json!({
                     "rank": i + 1,
                     "node_id": m.node_id,
-                    "combined_score": m.combined_score,
-                    "text_rank": m.text_rank,
-                    "vector_rank": m.vector_rank,
-                    "text_score": m.text_score,
+                    "similarity": m.score,
+                    "matched_terms": terms,
+                    "type": node.map(|n| n.event_type.name()).unwrap_or("unknown"),
+                    "content": node.map(|n| n.content.as_str()).unwrap_or(""),
+                })
+            })
+            .collect();
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "query": query,
+                "min_similarity": min_similarity,
+                "results": matches,
+                "total": results.len(),
+                "elapsed_ms": elapsed.as_secs_f64() * 1000.0,
+            }))
+            .unwrap_or_default()
+        );
+    } else {
+        println!("Fuzzy search for {:?} in {}:", query, path.display());
+        for (i, m) in results.iter().enumerate() {
+            if let Some(node) = graph.get_node(m.node_id) {
+                let preview = if node.content.chars().count() > 60 {
+                    format!("{}...", node.content.chars().take(60).collect::<String>())
+                } else {
+                    node.content.clone()
+                };
+                println!(
+                    "  #{:<3} Node {} ({}) [similarity: {:.2}, matched: {}]  {:?}",
+                    i + 1,
+                    m.node_id,
+                    node.event_type.name(),
+                    m.score,
+                    m.matched_terms.join(", "),
+                    preview
+                );
+            }
+        }
+        println!(
+            "  {} results ({:.1}ms)",
+            results.len(),
+            elapsed.as_secs_f64() * 1000.0
+        );
+    }
+    Ok(())
+}
+
+/// `grep`-style search: nodes whose content matches a regular expression.
+pub fn cmd_regex_search(
+    path: &Path,
+    pattern: &str,
+    case_sensitive: bool,
+    event_types: Vec<EventType>,
+    limit: usize,
+    json: bool,
+) -> AmemResult<()> {
+    let graph = read_graph(path)?;
+    let start = std::time::Instant::now();
+    let results = QueryEngine::new().regex_search(
+        &graph,
+        RegexSearchParams {
+            pattern: pattern.to_string(),
+            case_sensitive,
+            max_results: limit,
+            event_types,
+        },
+    )?;
+    let elapsed = start.elapsed();
+
+    if json {
+        let matches: Vec<serde_json::Value> = results
+            .iter()
+            .map(|m| {
+                let node = graph.get_node(m.node_id);
+                serde_json::json!({
+                    "node_id": m.node_id,
+                    "score": m.score,
+                    "matches": m.matched_terms,
+                    "type": node.map(|n| n.event_type.name()).unwrap_or("unknown"),
+                    "content": node.map(|n| n.content.as_str()).unwrap_or(""),
+                })
+            })
+            .collect();
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "pattern": pattern,
+                "case_sensitive": case_sensitive,
+                "results": matches,
+                "total": results.len(),
+                "elapsed_ms": elapsed.as_secs_f64() * 1000.0,
+            }))
+            .unwrap_or_default()
+        );
+    } else {
+        println!("Regex search for /{}/ in {}:", pattern, path.display());
+        for m in &results {
+            if let Some(node) = graph.get_node(m.node_id) {
+                let preview = if node.content.chars().count() > 60 {
+                    format!("{}...", node.content.chars().take(60).collect::<String>())
+                } else {
+                    node.content.clone()
+                };
+                println!(
+                    "  Node {} ({}) [matched: {}]  {:?}",
+                    m.node_id,
+                    node.event_type.name(),
+                    m.matched_terms.join(", "),
+                    preview
+                );
+            }
+        }
+        println!(
+            "  {} results ({:.1}ms)",
+            results.len(),
+            elapsed.as_secs_f64() * 1000.0
+        );
+    }
+    Ok(())
+}
+
+/// Show the BM25 term statistics behind text search: the most frequent
+/// terms, or the nodes containing one term, with the IDF each contributes.
+pub fn cmd_terms(path: &Path, top: usize, term: Option<&str>, json: bool) -> AmemResult<()> {
+    let graph = read_graph(path)?;
+    // Files written before BM25 indexes existed get them built on the fly.
+    let built_index;
+    let term_index = match graph.term_index() {
+        Some(ti) => ti,
+        None => {
+            built_index = TermIndex::build(&graph, &Tokenizer::new());
+            &built_index
+        }
+    };
+    let built_lengths;
+    let doc_lengths = match graph.doc_lengths() {
+        Some(dl) => dl,
+        None => {
+            built_lengths = DocLengths::build(&graph, &Tokenizer::new());
+            &built_lengths
+        }
+    };
+    let doc_count = term_index.doc_count();
+    let avg_doc_length = term_index.avg_doc_length();
+
+    if let Some(raw) = term {
+        // Normalize the way indexing does; stop words and 1-char tokens vanish.
+        let normalized = Tokenizer::new().tokenize(raw).into_iter().next();
+        let postings = normalized
+            .as_deref()
+            .map(|t| term_index.get(t))
+            .unwrap_or(&[]);
+        let idf = bm25_idf(doc_count as f32, postings.len() as f32);
+        let mut postings = postings.to_vec();
+        postings.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+        if json {
+            let nodes: Vec<serde_json::Value> = postings
+                .iter()
+                .map(|&(node_id, tf)| {
+                    let node = graph.get_node(node_id);
+                    serde_json::json!({
+                        "node_id": node_id,
+                        "term_frequency": tf,
+                        "doc_length": doc_lengths.get(node_id),
+                        "type": node.map(|n| n.event_type.name()).unwrap_or("unknown"),
+                        "content": node.map(|n| n.content.as_str()).unwrap_or(""),
+                    })
+                })
+                .collect();
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&serde_json::json!({
+                    "term": raw,
+                    "indexed_as": normalized,
+                    "doc_frequency": postings.len(),
+                    "idf": idf,
+                    "doc_count": doc_count,
+                    "avg_doc_length": avg_doc_length,
+                    "nodes": nodes,
+                }))
+                .unwrap_or_default()
+            );
+        } else {
+            match &normalized {
+                Some(t) => println!(
+                    "Term {:?}: in {} of {} nodes, IDF {:.4}",
+                    t,
+                    postings.len(),
+                    doc_count,
+                    idf
+                ),
+                None => println!("Term {:?} is not indexed (stop word or too short)", raw),
+            }
+            for (node_id, tf) in &postings {
+                let preview = graph
+                    .get_node(*node_id)
+                    .map(|n| {
+                        if n.content.len() > 50 {
+                            format!("{}...", &n.content[..50])
+                        } else {
+                            n.content.clone()
+                        }
+                    })
+                    .unwrap_or_default();
+                println!(
+                    "  Node {} tf={} len={}  {:?}",
+                    node_id,
+                    tf,
+                    doc_lengths.get(*node_id),
+                    preview
+                );
+            }
+        }
+        return Ok(());
+    }
+
+    // (term, doc frequency, total occurrences), most frequent first.
+    let mut stats: Vec<(&str, usize, u64)> = term_index
+        .terms()
+        .map(|(t, posting)| {
+            let total: u64 = posting.iter().map(|(_, tf)| *tf as u64).sum();
+            (t, posting.len(), total)
+        })
+        .collect();
+    stats.sort_by(|a, b| b.2.cmp(&a.2).then(b.1.cmp(&a.1)).then(a.0.cmp(b.0)));
+    stats.truncate(top);
+
+    if json {
+        let terms: Vec<serde_json::Value> = stats
+            .iter()
+            .map(|&(t, df, total)| {
+                serde_json::json!({
+                    "term": t,
+                    "total_frequency": total,
+                    "doc_frequency": df,
+                    "idf": bm25_idf(doc_count as f32, df as f32),
+                })
+            })
+            .collect();
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "doc_count": doc_count,
+                "avg_doc_length": avg_doc_length,
+                "unique_terms": term_index.term_count(),
+                "k1": BM25_K1,
+                "b": BM25_B,
+                "terms": terms,
+            }))
+            .unwrap_or_default()
+        );
+    } else {
+        println!(
+            "{} nodes, {} unique terms, average length {:.2} tokens (k1={}, b={})",
+            doc_count,
+            term_index.term_count(),
+            avg_doc_length,
+            BM25_K1,
+            BM25_B
+        );
+        println!("  {:<24} {:>8} {:>8} {:>8}", "term", "total", "docs", "idf");
+        for (t, df, total) in &stats {
+            println!(
+                "  {:<24} {:>8} {:>8} {:>8.4}",
+                t,
+                total,
+                df,
+                bm25_idf(doc_count as f32, *df as f32)
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Hybrid BM25 + vector search.
+#[allow(clippy::too_many_arguments)]
+pub fn cmd_hybrid_search(
+    path: &Path,
+    query: &str,
+    text_weight: f32,
+    vec_weight: f32,
+    limit: usize,
+    event_types: Vec<EventType>,
+    expand_episodes: Option<usize>,
+    json: bool,
+) -> AmemResult<()> {
+    let graph = read_graph(path)?;
+    let query_engine = QueryEngine::new();
+
+    let results = query_engine.hybrid_search(
+        &graph,
+        graph.term_index(),
+        graph.doc_lengths(),
+        HybridSearchParams {
+            query_text: query.to_string(),
+            query_vec: None,
+            max_results: limit,
+            event_types,
+            text_weight,
+            vector_weight: vec_weight,
+            rrf_k: 60,
+        },
+    )?;
+    let members = expand_episodes
+        .map(|max_per_episode| {
+            let hit_ids: Vec<u64> = results.iter().map(|m| m.node_id).collect();
+            query_engine.expand_episodes(&graph, &hit_ids, max_per_episode)
+        })
+        .unwrap_or_default();
+
+    if json {
+        let matches: Vec<serde_json::Value> = results
+            .iter()
+            .enumerate()
+            .map(|(i, m)| {
+                let node = graph.get_node(m.node_id);
+                serde_json::json!({
+                    "rank": i + 1,
+                    "node_id": m.node_id,
+                    "combined_score": m.combined_score,
+                    "text_rank": m.text_rank,
+                    "vector_rank": m.vector_rank,
+                    "text_score": m.text_score,
                     "vector_similarity": m.vector_similarity,
                     "type": node.map(|n| n.event_type.name()).unwrap_or("unknown"),
                     "content": node.map(|n| n.content.as_str()).unwrap_or(""),
                 })
             })
+            .chain(episode_members_json(&graph, &members))
             .collect();
         println!(
             "{}",
@@ -1237,6 +3910,7 @@ pub fn cmd_hybrid_search(
                     m.combined_score,
                     preview
                 );
+                print_episode_members(&graph, &members, m.node_id);
             }
         }
         println!("  {} results", results.len());
@@ -1244,7 +3918,18 @@ pub fn cmd_hybrid_search(
     Ok(())
 }
 
-/// Centrality analysis.
+/// Most text matches `--seed-query` contributes as PageRank seeds.
+const CENTRALITY_SEED_QUERY_LIMIT: usize = 20;
+
+/// Seed for `--algorithm kmeans`, fixed so repeated runs agree.
+const CENTRALITY_KMEANS_SEED: u64 = 0;
+
+/// Centrality analysis. With seeds (given or found by `seed_query`),
+/// PageRank is personalized to rank importance relative to them. With
+/// `sample`, scores are estimated from that share of the nodes and the
+/// output is marked approximate. The `kmeans` algorithm ranks by PageRank
+/// and labels each node with its cluster from k-means over the feature
+/// vectors, with `kmeans_k` clusters.
 #[allow(clippy::too_many_arguments)]
 pub fn cmd_centrality(
     path: &Path,
@@ -1254,28 +3939,83 @@ pub fn cmd_centrality(
     event_types: Vec<EventType>,
     limit: usize,
     iterations: u32,
+    seeds: Vec<u64>,
+    seed_query: Option<&str>,
+    symmetric: bool,
+    sample: Option<NodeSample>,
+    kmeans_k: usize,
     json: bool,
 ) -> AmemResult<()> {
-    let graph = AmemReader::read_from_file(path)?;
+    let graph = read_graph(path)?;
     let query_engine = QueryEngine::new();
 
+    let seeded = !seeds.is_empty() || seed_query.is_some();
+    let mut seeds = seeds;
+    if let Some(query) = seed_query {
+        let matches = query_engine.text_search(
+            &graph,
+            graph.term_index(),
+            graph.doc_lengths(),
+            TextSearchParams {
+                query: query.to_string(),
+                max_results: CENTRALITY_SEED_QUERY_LIMIT,
+                event_types: Vec::new(),
+                session_ids: Vec::new(),
+                min_score: 0.0,
+                confidence_weight: 0.0,
+            },
+        )?;
+        seeds.extend(matches.iter().map(|m| m.node_id));
+    }
+    seeds.retain(|id| graph.get_node(*id).is_some());
+    seeds.sort_unstable();
+    seeds.dedup();
+    if seeded && seeds.is_empty() {
+        eprintln!("Warning: no seed nodes found; ranking by global centrality");
+    }
+
     let algo = match algorithm {
         "degree" => CentralityAlgorithm::Degree,
         "betweenness" => CentralityAlgorithm::Betweenness,
         _ => CentralityAlgorithm::PageRank { damping },
     };
 
-    let result = query_engine.centrality(
-        &graph,
-        CentralityParams {
-            algorithm: algo,
-            max_iterations: iterations,
-            tolerance: 1e-6,
-            top_k: limit,
-            event_types,
-            edge_types,
-        },
-    )?;
+    let params = CentralityParams {
+        algorithm: algo,
+        max_iterations: iterations,
+        tolerance: 1e-6,
+        top_k: limit,
+        event_types,
+        edge_types,
+        seeds: seeds.clone(),
+        symmetric_undirected: symmetric,
+    };
+    let result = match sample {
+        Some(sample) => query_engine.centrality_sampled(&graph, params, sample)?,
+        None => query_engine.centrality(&graph, params)?,
+    };
+
+    let mut cluster_map = ClusterMap::new(graph.dimension());
+    let clusters: Option<std::collections::HashMap<u64, ClusterAssignment>> =
+        (algorithm == "kmeans").then(|| {
+            let vectors: Vec<(u64, &[f32])> = graph
+                .nodes()
+                .iter()
+                .map(|n| (n.id, n.feature_vec.as_slice()))
+                .collect();
+            cluster_map
+                .cluster(&vectors, kmeans_k, iterations, CENTRALITY_KMEANS_SEED)
+                .into_iter()
+                .map(|a| (a.node_id, a))
+                .collect()
+        });
+    let cluster_sizes: Vec<usize> = (0..cluster_map.cluster_count())
+        .map(|ci| cluster_map.get_cluster(ci).len())
+        .collect();
+    if clusters.is_some() && cluster_sizes.is_empty() {
+        eprintln!("Warning: no nodes have feature vectors; nothing to cluster");
+    }
+    let cluster_of = |id: u64| clusters.as_ref().and_then(|c| c.get(&id));
 
     if json {
         let scores: Vec<serde_json::Value> = result
@@ -1284,24 +4024,37 @@ pub fn cmd_centrality(
             .enumerate()
             .map(|(i, (id, score))| {
                 let node = graph.get_node(*id);
-                serde_json::json!({
+                let mut entry = serde_json::json!({
                     "rank": i + 1,
                     "node_id": id,
                     "score": score,
                     "type": node.map(|n| n.event_type.name()).unwrap_or("unknown"),
                     "content": node.map(|n| n.content.as_str()).unwrap_or(""),
-                })
+                });
+                if clusters.is_some() {
+                    let assignment = cluster_of(*id);
+                    entry["cluster"] = serde_json::json!(assignment.map(|a| a.cluster_id));
+                    entry["distance_to_centroid"] =
+                        serde_json::json!(assignment.map(|a| a.distance_to_centroid));
+                }
+                entry
             })
             .collect();
+        let mut output = serde_json::json!({
+            "algorithm": algorithm,
+            "seeds": seeds,
+            "approximate": sample_json(sample.as_ref()),
+            "converged": result.converged,
+            "iterations": result.iterations,
+            "components": result.components.len(),
+            "scores": scores,
+        });
+        if clusters.is_some() {
+            output["cluster_sizes"] = serde_json::json!(cluster_sizes);
+        }
         println!(
             "{}",
-            serde_json::to_string_pretty(&serde_json::json!({
-                "algorithm": algorithm,
-                "converged": result.converged,
-                "iterations": result.iterations,
-                "scores": scores,
-            }))
-            .unwrap_or_default()
+            serde_json::to_string_pretty(&output).unwrap_or_default()
         );
     } else {
         let algo_name = match algorithm {
@@ -1313,6 +4066,23 @@ pub fn cmd_centrality(
             "{} centrality (converged: {}, iterations: {}):",
             algo_name, result.converged, result.iterations
         );
+        print_sample_notice(sample.as_ref());
+        if !seeds.is_empty() {
+            let ids: Vec<String> = seeds.iter().map(|id| id.to_string()).collect();
+            println!("  Personalized to seeds: {}", ids.join(", "));
+        }
+        if clusters.is_some() {
+            let sizes: Vec<String> = cluster_sizes
+                .iter()
+                .enumerate()
+                .map(|(ci, size)| format!("{}: {}", ci, size))
+                .collect();
+            println!(
+                "  k-means clusters (k = {}): {}",
+                cluster_sizes.len(),
+                sizes.join(", ")
+            );
+        }
         for (i, (id, score)) in result.scores.iter().enumerate() {
             if let Some(node) = graph.get_node(*id) {
                 let preview = if node.content.len() > 50 {
@@ -1320,12 +4090,18 @@ pub fn cmd_centrality(
                 } else {
                     node.content.clone()
                 };
+                let cluster = match (&clusters, cluster_of(*id)) {
+                    (None, _) => String::new(),
+                    (Some(_), Some(a)) => format!(" [cluster {}]", a.cluster_id),
+                    (Some(_), None) => " [no vector]".to_string(),
+                };
                 println!(
-                    "  #{:<3} Node {} ({}) [score: {:.6}]  {:?}",
+                    "  #{:<3} Node {} ({}) [score: {:.6}]{}  {:?}",
                     i + 1,
                     id,
                     node.event_type.name(),
                     score,
+                    cluster,
                     preview
                 );
             }
@@ -1344,9 +4120,10 @@ pub fn cmd_path(
     direction: TraversalDirection,
     max_depth: u32,
     weighted: bool,
+    type_cost: std::collections::HashMap<EdgeType, f32>,
     json: bool,
 ) -> AmemResult<()> {
-    let graph = AmemReader::read_from_file(path)?;
+    let graph = read_graph(path)?;
     let query_engine = QueryEngine::new();
 
     let result = query_engine.shortest_path(
@@ -1358,6 +4135,7 @@ pub fn cmd_path(
             direction,
             max_depth,
             weighted,
+            type_cost,
         },
     )?;
 
@@ -1381,7 +4159,7 @@ pub fn cmd_path(
                 serde_json::json!({
                     "source_id": e.source_id,
                     "target_id": e.target_id,
-                    "edge_type": e.edge_type.name(),
+                    "edge_type": graph.edge_type_name(e.edge_type),
                     "weight": e.weight,
                 })
             })
@@ -1413,7 +4191,7 @@ pub fn cmd_path(
                     parts.push(format!(
                         "{} --[{}]-->",
                         label,
-                        result.edges[i].edge_type.name()
+                        graph.edge_type_name(result.edges[i].edge_type)
                     ));
                 } else {
                     parts.push(label);
@@ -1439,7 +4217,7 @@ pub fn cmd_revise(
     confidence: f32,
     json: bool,
 ) -> AmemResult<()> {
-    let graph = AmemReader::read_from_file(path)?;
+    let graph = read_graph(path)?;
     let query_engine = QueryEngine::new();
 
     let report = query_engine.belief_revision(
@@ -1562,7 +4340,7 @@ pub fn cmd_gaps(
     session_range: Option<(u32, u32)>,
     json: bool,
 ) -> AmemResult<()> {
-    let graph = AmemReader::read_from_file(path)?;
+    let graph = read_graph(path)?;
     let query_engine = QueryEngine::new();
 
     let sort_by = match sort {
@@ -1665,7 +4443,7 @@ pub fn cmd_analogy(
     depth: u32,
     json: bool,
 ) -> AmemResult<()> {
-    let graph = AmemReader::read_from_file(path)?;
+    let graph = read_graph(path)?;
     let query_engine = QueryEngine::new();
 
     // Find the best matching node to use as anchor
@@ -1775,7 +4553,7 @@ pub fn cmd_consolidate(
     backup: Option<std::path::PathBuf>,
     json: bool,
 ) -> AmemResult<()> {
-    let mut graph = AmemReader::read_from_file(path)?;
+    let mut graph = read_graph(path)?;
     let query_engine = QueryEngine::new();
 
     let dry_run = !confirm;
@@ -1838,7 +4616,7 @@ pub fn cmd_consolidate(
 
     // Write back if not dry-run
     if !dry_run {
-        let writer = AmemWriter::new(graph.dimension());
+        let writer = graph_writer(graph.dimension());
         writer.write_to_file(&graph, path)?;
     }
 
@@ -1886,6 +4664,1020 @@ pub fn cmd_consolidate(
     Ok(())
 }
 
+/// Parse an age such as `90d`, `12h`, `30m`, `45s` or `2w` into seconds.
+/// A bare number is taken as days.
+pub fn parse_age_secs(raw: &str) -> Result<u64, String> {
+    let raw = raw.trim();
+    let split = raw.find(|c: char| !c.is_ascii_digit()).unwrap_or(raw.len());
+    let (digits, unit) = raw.split_at(split);
+    let value: u64 = digits
+        .parse()
+        .map_err(|_| format!("invalid age '{raw}' (expected e.g. 90d, 12h, 30m)"))?;
+    let unit_secs = match unit {
+        "" | "d" => 86_400,
+        "w" => 7 * 86_400,
+        "h" => 3_600,
+        "m" => 60,
+        "s" => 1,
+        _ => {
+            return Err(format!(
+                "invalid age unit '{unit}' (expected s, m, h, d or w)"
+            ))
+        }
+    };
+    Ok(value.saturating_mul(unit_secs))
+}
+
+/// Serialize a compacted graph and, when `apply`, back up the original file
+/// and overwrite it. The backup defaults to `<stem>.<backup_tag>.amem` next
+/// to the file. Returns the bytes saved and the backup path.
+fn write_compacted(
+    path: &Path,
+    graph: &MemoryGraph,
+    size_before: u64,
+    apply: bool,
+    backup: Option<std::path::PathBuf>,
+    backup_tag: &str,
+) -> AmemResult<(u64, Option<std::path::PathBuf>)> {
+    let writer = graph_writer(graph.dimension());
+    let mut compacted = Vec::new();
+    writer.write_to(graph, &mut compacted)?;
+    let reclaimed = size_before.saturating_sub(compacted.len() as u64);
+    if !apply {
+        return Ok((reclaimed, None));
+    }
+    let bp = backup.unwrap_or_else(|| {
+        let mut p = path.to_path_buf();
+        let name = p
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        p.set_file_name(format!("{}.{}.amem", name, backup_tag));
+        p
+    });
+    std::fs::copy(path, &bp)?;
+    std::fs::write(path, &compacted)?;
+    Ok((reclaimed, Some(bp)))
+}
+
+fn removed_by_type_json(
+    removed_by_type: &[(EventType, usize)],
+) -> serde_json::Map<String, serde_json::Value> {
+    removed_by_type
+        .iter()
+        .map(|(t, n)| (t.name().to_string(), serde_json::json!(n)))
+        .collect()
+}
+
+/// Time-based compaction: remove nodes neither created nor accessed within
+/// `older_than_secs`, except the `keep_types`. Dry-run unless `confirm`.
+pub fn cmd_compact(
+    path: &Path,
+    older_than_secs: u64,
+    keep_types: &[EventType],
+    confirm: bool,
+    backup: Option<std::path::PathBuf>,
+    json: bool,
+) -> AmemResult<()> {
+    let mut graph = read_graph(path)?;
+    let size_before = std::fs::metadata(path)?.len();
+    let cutoff =
+        crate::types::now_micros().saturating_sub(older_than_secs.saturating_mul(1_000_000));
+
+    let report = QueryEngine::new().compact_older_than(
+        &mut graph,
+        &CompactionParams {
+            cutoff,
+            keep_types: keep_types.to_vec(),
+        },
+    )?;
+
+    let dry_run = !confirm;
+    let (reclaimed, backup_path) = write_compacted(
+        path,
+        &graph,
+        size_before,
+        !dry_run && !report.removed.is_empty(),
+        backup,
+        "pre-compact",
+    )?;
+
+    if json {
+        let by_type = removed_by_type_json(&report.removed_by_type);
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "dry_run": dry_run,
+                "older_than_secs": older_than_secs,
+                "cutoff": cutoff,
+                "keep_types": keep_types.iter().map(|t| t.name()).collect::<Vec<_>>(),
+                "removed": report.removed.len(),
+                "removed_by_type": by_type,
+                "removed_ids": report.removed,
+                "reclaimed_bytes": reclaimed,
+                "backup_path": backup_path.map(|p| p.display().to_string()),
+            }))
+            .unwrap_or_default()
+        );
+    } else {
+        if dry_run {
+            println!("Compaction DRY RUN (use --confirm to apply):\n");
+        } else {
+            println!("Compaction applied:\n");
+            if let Some(bp) = &backup_path {
+                println!("  Backup: {}", bp.display());
+            }
+        }
+        println!(
+            "  Nodes untouched since {}: {}",
+            format_timestamp(cutoff),
+            report.removed.len()
+        );
+        for (event_type, count) in &report.removed_by_type {
+            println!("    {}: {}", event_type.name(), count);
+        }
+        if !keep_types.is_empty() {
+            let kept: Vec<&str> = keep_types.iter().map(|t| t.name()).collect();
+            println!("  Protected types: {}", kept.join(", "));
+        }
+        println!("  Reclaimed: ~{}", format_size(reclaimed));
+    }
+    Ok(())
+}
+
+/// Rank-based compaction: keep the `keep` most important nodes (see
+/// [`importance_score`](crate::engine::decay::importance_score)) plus every
+/// pinned node and protected type. Dry-run unless `confirm`.
+#[allow(clippy::too_many_arguments)]
+pub fn cmd_compact_keep_top(
+    path: &Path,
+    keep: usize,
+    keep_types: &[EventType],
+    pinned: &[u64],
+    rewire_to_episode: bool,
+    confirm: bool,
+    backup: Option<std::path::PathBuf>,
+    json: bool,
+) -> AmemResult<()> {
+    let mut graph = read_graph(path)?;
+    let size_before = std::fs::metadata(path)?.len();
+    let total = graph.node_count();
+
+    let report = QueryEngine::new().compact_keep_top(
+        &mut graph,
+        &KeepTopParams {
+            keep,
+            keep_types: keep_types.to_vec(),
+            pinned: pinned.to_vec(),
+            rewire_to_episode,
+            now: crate::types::now_micros(),
+        },
+    )?;
+
+    let dry_run = !confirm;
+    let (reclaimed, backup_path) = write_compacted(
+        path,
+        &graph,
+        size_before,
+        !dry_run && !report.removed.is_empty(),
+        backup,
+        "pre-compact",
+    )?;
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "dry_run": dry_run,
+                "keep_top": keep,
+                "total_nodes": total,
+                "kept": total - report.removed.len(),
+                "pinned_kept": report.pinned_kept,
+                "cutoff_score": report.cutoff_score,
+                "removed": report.removed.len(),
+                "removed_by_type": removed_by_type_json(&report.removed_by_type),
+                "removed_ids": report.removed,
+                "rewired_edges": report.rewired_edges,
+                "reclaimed_bytes": reclaimed,
+                "backup_path": backup_path.map(|p| p.display().to_string()),
+            }))
+            .unwrap_or_default()
+        );
+    } else {
+        if dry_run {
+            println!("Compaction DRY RUN (use --confirm to apply):\n");
+        } else {
+            println!("Compaction applied:\n");
+            if let Some(bp) = &backup_path {
+                println!("  Backup: {}", bp.display());
+            }
+        }
+        println!(
+            "  Keeping {} of {} nodes ({} pinned or protected)",
+            total - report.removed.len(),
+            total,
+            report.pinned_kept
+        );
+        match report.cutoff_score {
+            Some(score) => println!("  Importance cutoff: {score:.4}"),
+            None if report.removed.is_empty() => {
+                println!("  Importance cutoff: none (nothing to cut)")
+            }
+            None => println!("  Importance cutoff: none (only pinned nodes kept)"),
+        }
+        println!("  Nodes cut: {}", report.removed.len());
+        for (event_type, count) in &report.removed_by_type {
+            println!("    {}: {}", event_type.name(), count);
+        }
+        if dry_run && !report.removed.is_empty() {
+            let ids: Vec<String> = report.removed.iter().map(|id| id.to_string()).collect();
+            println!("  Would remove: {}", ids.join(", "));
+        }
+        if rewire_to_episode {
+            println!("  Edges rewired to episodes: {}", report.rewired_edges);
+        }
+        println!("  Reclaimed: ~{}", format_size(reclaimed));
+    }
+    Ok(())
+}
+
+/// Bulk delete: remove every node matching `filter`, with its edges.
+/// Dry-run unless `confirm`.
+pub fn cmd_delete(
+    path: &Path,
+    filter: &DeleteFilter,
+    confirm: bool,
+    backup: Option<std::path::PathBuf>,
+    json: bool,
+) -> AmemResult<()> {
+    let mut graph = read_graph(path)?;
+    let size_before = std::fs::metadata(path)?.len();
+
+    let report = QueryEngine::new().delete_where(&mut graph, filter)?;
+    let dry_run = !confirm;
+    let (reclaimed, backup_path) = write_compacted(
+        path,
+        &graph,
+        size_before,
+        !dry_run && report.deleted_count > 0,
+        backup,
+        "pre-delete",
+    )?;
+
+    if json {
+        let mut out = serde_json::to_value(&report).unwrap_or_default();
+        out["dry_run"] = serde_json::json!(dry_run);
+        out["reclaimed_bytes"] = serde_json::json!(reclaimed);
+        out["backup_path"] = serde_json::json!(backup_path.map(|p| p.display().to_string()));
+        println!("{}", serde_json::to_string_pretty(&out).unwrap_or_default());
+    } else {
+        if dry_run {
+            println!("Delete DRY RUN (use --yes to apply):\n");
+            println!("  Would delete {} nodes", report.deleted_count);
+        } else {
+            println!(
+                "Deleted {} nodes from {}",
+                report.deleted_count,
+                path.display()
+            );
+            if let Some(bp) = &backup_path {
+                println!("  Backup: {}", bp.display());
+            }
+        }
+        println!("  Edges removed: {}", report.edges_removed);
+        println!("  Reclaimed: ~{}", format_size(reclaimed));
+    }
+    Ok(())
+}
+
+/// Migrate a file to the current storage version, checkpointing the original
+/// first. With `dry_run`, report what would happen without writing anything.
+pub fn cmd_migrate(path: &Path, dry_run: bool, json: bool) -> AmemResult<()> {
+    let plan = if dry_run {
+        crate::format::migration::plan(path)?
+    } else {
+        crate::format::migration::migrate(path)?
+    };
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "dry_run": dry_run,
+                "needed": plan.needed(),
+                "from_version": plan.from_version,
+                "to_version": plan.to_version,
+                "node_count": plan.node_count,
+                "edge_count": plan.edge_count,
+                "checkpoint_path": plan.checkpoint_path.as_ref().map(|p| p.display().to_string()),
+            }))
+            .unwrap_or_default()
+        );
+        return Ok(());
+    }
+
+    if !plan.needed() {
+        println!(
+            "{} is already at storage v{}; nothing to migrate.",
+            path.display(),
+            plan.to_version
+        );
+        return Ok(());
+    }
+    if dry_run {
+        println!("Migration DRY RUN (run without --dry-run to apply):\n");
+    } else {
+        println!("Migration applied:\n");
+    }
+    println!("  Storage: v{} -> v{}", plan.from_version, plan.to_version);
+    println!(
+        "  Rewrites: {} nodes, {} edges",
+        plan.node_count, plan.edge_count
+    );
+    if let Some(checkpoint) = &plan.checkpoint_path {
+        let label = if dry_run {
+            "Checkpoint (would create)"
+        } else {
+            "Checkpoint"
+        };
+        println!("  {}: {}", label, checkpoint.display());
+    }
+    Ok(())
+}
+
+/// Check a file's graph for dangling edges, SUPERSEDES cycles and index
+/// drift, and compare the indexes against a rebuild. With `repair`, cycles
+/// are broken by dropping the edge that closed each one, any index that no
+/// longer matches its rebuild is rebuilt, and the file is rewritten. Fails if
+/// any violation remains.
+pub fn cmd_fsck(path: &Path, repair: bool, json: bool) -> AmemResult<()> {
+    let mut graph = read_graph(path)?;
+    let found = graph.check_integrity();
+    let indexes = graph.verify_indexes();
+
+    let mut removed = Vec::new();
+    let mut rebuilt = Vec::new();
+    if repair {
+        if !graph.supersedes_cycles().is_empty() {
+            removed = graph.break_supersedes_cycles()?;
+        }
+        // Edge removal updates the indexes incrementally; trust but verify.
+        let after = graph.verify_indexes();
+        if !after.is_consistent() {
+            rebuilt = after.divergences;
+            graph.rebuild_indexes();
+        }
+        if !removed.is_empty() || !rebuilt.is_empty() {
+            graph_writer(graph.dimension()).write_to_file(&graph, path)?;
+        }
+    }
+    let remaining = if removed.is_empty() && rebuilt.is_empty() {
+        found.len() + indexes.divergences.len()
+    } else {
+        graph.check_integrity().len() + graph.verify_indexes().divergences.len()
+    };
+
+    if json {
+        let removed_json: Vec<serde_json::Value> = removed
+            .iter()
+            .map(|(source, target)| serde_json::json!({ "source_id": source, "target_id": target }))
+            .collect();
+        let divergence_json = |divergences: &[crate::graph::IndexDivergence]| {
+            divergences
+                .iter()
+                .map(|d| serde_json::json!({ "index": d.index.name(), "node_ids": d.node_ids }))
+                .collect::<Vec<_>>()
+        };
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "nodes": graph.node_count(),
+                "edges": graph.edge_count(),
+                "violations": found.iter().map(|v| v.to_string()).collect::<Vec<_>>(),
+                "index_divergences": divergence_json(&indexes.divergences),
+                "removed_edges": removed_json,
+                "rebuilt_indexes": divergence_json(&rebuilt),
+                "remaining": remaining,
+            }))
+            .unwrap_or_default()
+        );
+    } else {
+        println!(
+            "Checked {} nodes, {} edges in {}",
+            graph.node_count(),
+            graph.edge_count(),
+            path.display()
+        );
+        if found.is_empty() && indexes.is_consistent() {
+            println!("  No problems found.");
+        }
+        for violation in &found {
+            println!("  {violation}");
+        }
+        for divergence in &indexes.divergences {
+            println!("  {divergence}");
+        }
+        for (source, target) in &removed {
+            println!("  Repaired: removed SUPERSEDES edge {source} -> {target}");
+        }
+        for divergence in &rebuilt {
+            println!("  Repaired: rebuilt {} index", divergence.index.name());
+        }
+    }
+
+    if remaining == 0 {
+        Ok(())
+    } else {
+        Err(crate::types::AmemError::IntegrityCheckFailed(remaining))
+    }
+}
+
+/// Content prefix of every node written by MCP auto-capture.
+const AUTO_CAPTURE_PREFIX: &str = "[auto-capture]";
+
+/// What an auto-captured node's content says about how it was captured.
+struct CaptureEntry<'a> {
+    /// Capture path: `prompt`, `feedback` or `tool`.
+    kind: &'a str,
+    /// Prompt template or tool name that triggered the capture.
+    trigger: Option<&'a str>,
+    /// Whether the redactor replaced anything.
+    redacted: bool,
+    /// Whether the text was cut at the capture size limit.
+    truncated: bool,
+}
+
+/// Parse `[auto-capture][kind] tool=name ...` or `... template=name ...`;
+/// `None` for nodes not written by auto-capture.
+fn parse_capture(content: &str) -> Option<CaptureEntry<'_>> {
+    let rest = content.strip_prefix(AUTO_CAPTURE_PREFIX)?;
+    let (kind, rest) = rest.strip_prefix('[')?.split_once(']')?;
+    let trigger = rest
+        .split_whitespace()
+        .next()
+        .and_then(|field| {
+            field
+                .strip_prefix("tool=")
+                .or_else(|| field.strip_prefix("template="))
+        })
+        .filter(|name| !name.is_empty());
+    Some(CaptureEntry {
+        kind,
+        trigger,
+        redacted: RedactionCategory::ALL
+            .iter()
+            .any(|c| content.contains(c.placeholder())),
+        truncated: content.ends_with("…[truncated]"),
+    })
+}
+
+/// List nodes written by MCP auto-capture, newest first, with what
+/// triggered each one and whether it was redacted or truncated.
+///
+/// With `purge`, every auto-captured node is removed instead (edges go with
+/// them and each leaves a tombstone). Purging is a dry run unless `confirm`;
+/// applying copies the original file to a backup first.
+pub fn cmd_capture_log(
+    path: &Path,
+    limit: usize,
+    purge: bool,
+    confirm: bool,
+    backup: Option<std::path::PathBuf>,
+    json: bool,
+) -> AmemResult<()> {
+    let mut graph = read_graph(path)?;
+    let mut captured: Vec<&CognitiveEvent> = graph
+        .nodes()
+        .iter()
+        .filter(|n| n.content.starts_with(AUTO_CAPTURE_PREFIX))
+        .collect();
+    captured.sort_by_key(|n| std::cmp::Reverse((n.created_at, n.id)));
+    let total = captured.len();
+
+    let mut by_kind: std::collections::BTreeMap<&str, usize> = std::collections::BTreeMap::new();
+    let mut redacted = 0usize;
+    for entry in captured.iter().filter_map(|n| parse_capture(&n.content)) {
+        *by_kind.entry(entry.kind).or_insert(0) += 1;
+        redacted += entry.redacted as usize;
+    }
+    let by_kind: Vec<(String, usize)> = by_kind
+        .into_iter()
+        .map(|(k, n)| (k.to_string(), n))
+        .collect();
+    let by_kind_json: serde_json::Map<String, serde_json::Value> = by_kind
+        .iter()
+        .map(|(k, n)| (k.clone(), serde_json::json!(n)))
+        .collect();
+
+    if purge {
+        let ids: Vec<u64> = captured.iter().map(|n| n.id).collect();
+        let size_before = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+        for &id in &ids {
+            graph.remove_node(id)?;
+        }
+        let (reclaimed, backup_path) = write_compacted(
+            path,
+            &graph,
+            size_before,
+            confirm && !ids.is_empty(),
+            backup,
+            "pre-purge",
+        )?;
+        if json {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&serde_json::json!({
+                    "dry_run": !confirm,
+                    "purged": ids.len(),
+                    "purged_ids": ids,
+                    "by_kind": by_kind_json,
+                    "reclaimed_bytes": reclaimed,
+                    "backup_path": backup_path.map(|p| p.display().to_string()),
+                }))
+                .unwrap_or_default()
+            );
+        } else {
+            if confirm {
+                println!(
+                    "Purged {} auto-captured nodes from {}",
+                    ids.len(),
+                    path.display()
+                );
+                if let Some(bp) = &backup_path {
+                    println!("  Backup: {}", bp.display());
+                }
+            } else {
+                println!("Purge DRY RUN (use --confirm to apply):\n");
+                println!("  Would remove {} auto-captured nodes", ids.len());
+            }
+            for (kind, n) in &by_kind {
+                println!("    {}: {}", kind, n);
+            }
+            println!("  Reclaimed: ~{}", format_size(reclaimed));
+        }
+        return Ok(());
+    }
+
+    let shown = &captured[..total.min(limit)];
+    if json {
+        let entries: Vec<serde_json::Value> = shown
+            .iter()
+            .map(|n| {
+                let entry = parse_capture(&n.content);
+                serde_json::json!({
+                    "id": n.id,
+                    "type": n.event_type.name(),
+                    "session_id": n.session_id,
+                    "created_at": n.created_at,
+                    "kind": entry.as_ref().map(|e| e.kind),
+                    "trigger": entry.as_ref().and_then(|e| e.trigger),
+                    "redacted": entry.as_ref().is_some_and(|e| e.redacted),
+                    "truncated": entry.as_ref().is_some_and(|e| e.truncated),
+                    "content": n.content,
+                })
+            })
+            .collect();
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "total_nodes": graph.node_count(),
+                "captured": total,
+                "redacted": redacted,
+                "by_kind": by_kind_json,
+                "entries": entries,
+            }))
+            .unwrap_or_default()
+        );
+        return Ok(());
+    }
+
+    println!(
+        "{} auto-captured of {} nodes in {} ({} redacted)",
+        total,
+        graph.node_count(),
+        path.display(),
+        redacted
+    );
+    for (kind, n) in &by_kind {
+        println!("  {}: {}", kind, n);
+    }
+    if !shown.is_empty() {
+        println!();
+    }
+    for node in shown {
+        let Some(entry) = parse_capture(&node.content) else {
+            continue;
+        };
+        let mut flags = Vec::new();
+        if entry.redacted {
+            flags.push("redacted");
+        }
+        if entry.truncated {
+            flags.push("truncated");
+        }
+        println!(
+            "  [{}] {} {} via {}{}",
+            node.id,
+            format_timestamp(node.created_at),
+            entry.kind,
+            entry.trigger.unwrap_or("unknown"),
+            if flags.is_empty() {
+                String::new()
+            } else {
+                format!(" ({})", flags.join(", "))
+            }
+        );
+    }
+    if total > shown.len() {
+        println!(
+            "  ... {} older (raise --limit to see them)",
+            total - shown.len()
+        );
+    }
+    Ok(())
+}
+
+/// Sign a file in place with the PKCS#8 PEM private key at `key_path`.
+#[cfg(feature = "signing")]
+pub fn cmd_sign(path: &Path, key_path: &Path, json: bool) -> AmemResult<()> {
+    use crate::format::signing;
+
+    let key = signing::load_signing_key(key_path)?;
+    // Refuse to sign something that does not parse as a brain.
+    read_graph(path)?;
+    let replaced = signing::is_signed(&std::fs::read(path)?)?;
+    signing::sign_file(path, &key)?;
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "file": path.display().to_string(),
+                "signed": true,
+                "replaced_signature": replaced,
+            }))
+            .unwrap_or_default()
+        );
+    } else if replaced {
+        println!("Re-signed {} (previous signature replaced)", path.display());
+    } else {
+        println!("Signed {}", path.display());
+    }
+    Ok(())
+}
+
+/// Check that a file parses and report its signature state.
+///
+/// With a public key, anything other than a valid signature is an error.
+#[cfg(feature = "signing")]
+pub fn cmd_verify(path: &Path, pub_key: Option<&Path>, json: bool) -> AmemResult<()> {
+    use crate::format::signing::{self, SignatureStatus};
+
+    let graph = read_graph(path)?;
+    let data = read_plain_bytes(path)?;
+    let signed = signing::is_signed(&data)?;
+    let status = match pub_key {
+        Some(key_path) => Some(signing::verify_bytes(
+            &data,
+            &signing::load_verifying_key(key_path)?,
+        )?),
+        None => None,
+    };
+    let status_name = status.map(|s| match s {
+        SignatureStatus::Valid => "valid".to_string(),
+        SignatureStatus::Invalid => "invalid".to_string(),
+        SignatureStatus::Unsigned => "unsigned".to_string(),
+        SignatureStatus::UnsupportedVersion(v) => format!("unsupported trailer version {v}"),
+    });
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "file": path.display().to_string(),
+                "nodes": graph.node_count(),
+                "edges": graph.edge_count(),
+                "signed": signed,
+                "signature": status_name,
+            }))
+            .unwrap_or_default()
+        );
+    } else {
+        println!(
+            "{}: {} nodes, {} edges, {}",
+            path.display(),
+            graph.node_count(),
+            graph.edge_count(),
+            if signed { "signed" } else { "unsigned" }
+        );
+        if let Some(name) = &status_name {
+            println!("Signature: {name}");
+        }
+    }
+    match (status, status_name) {
+        (Some(s), Some(name)) if s != SignatureStatus::Valid => Err(
+            crate::types::AmemError::SignatureMismatch(format!("{}: {name}", path.display())),
+        ),
+        _ => Ok(()),
+    }
+}
+
+/// Review near-duplicate facts one pair at a time, or replay a saved plan.
+///
+/// Interactive review reads choices from stdin and prompts on stderr, so
+/// stdout carries only the summary. Decisions are applied together once the
+/// review ends; nothing is written if any of them fails.
+pub fn cmd_dedup(
+    path: &Path,
+    floor: f32,
+    apply_plan: Option<&Path>,
+    plan_out: Option<&Path>,
+    dry_run: bool,
+    json: bool,
+) -> AmemResult<()> {
+    let mut graph = read_graph(path)?;
+    let query_engine = QueryEngine::new();
+
+    let (decisions, candidate_count) = match apply_plan {
+        Some(plan_path) => {
+            let decisions = read_dedup_plan(plan_path)?;
+            let count = decisions.len();
+            (decisions, count)
+        }
+        None => {
+            let candidates = query_engine.duplicate_candidates(&graph, floor, None);
+            let stdin = std::io::stdin();
+            let decisions = review_duplicates(
+                &graph,
+                &candidates,
+                &mut stdin.lock(),
+                &mut std::io::stderr(),
+            )?;
+            (decisions, candidates.len())
+        }
+    };
+
+    if let Some(out) = plan_out {
+        write_dedup_plan(out, floor, &decisions)?;
+    }
+
+    let merged = if dry_run {
+        0
+    } else {
+        let merged = query_engine.apply_dedup_decisions(&mut graph, &decisions)?;
+        if merged > 0 {
+            let writer = graph_writer(graph.dimension());
+            writer.write_to_file(&graph, path)?;
+        }
+        merged
+    };
+
+    let count = |d: DedupDecision| decisions.iter().filter(|(_, x)| *x == d).count();
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "candidates": candidate_count,
+                "reviewed": decisions.len(),
+                "keep_both": count(DedupDecision::KeepBoth),
+                "merge": count(DedupDecision::MergeIntoFirst) + count(DedupDecision::MergeIntoSecond),
+                "skipped": count(DedupDecision::Skip),
+                "merged": merged,
+                "dry_run": dry_run,
+                "plan_out": plan_out.map(|p| p.display().to_string()),
+            }))
+            .unwrap_or_default()
+        );
+    } else {
+        println!(
+            "Reviewed {} of {} candidate pairs: {} merge, {} keep both, {} skipped",
+            decisions.len(),
+            candidate_count,
+            count(DedupDecision::MergeIntoFirst) + count(DedupDecision::MergeIntoSecond),
+            count(DedupDecision::KeepBoth),
+            count(DedupDecision::Skip),
+        );
+        if let Some(out) = plan_out {
+            println!("  Plan saved to {}", out.display());
+        }
+        if dry_run {
+            println!("  Dry run: no changes written");
+        } else {
+            println!("  Merged: {merged}");
+        }
+    }
+    Ok(())
+}
+
+/// Prompt for a decision on each candidate until the list or input runs out,
+/// or the reviewer quits.
+fn review_duplicates(
+    graph: &MemoryGraph,
+    candidates: &[DuplicateCandidate],
+    input: &mut impl std::io::BufRead,
+    out: &mut impl std::io::Write,
+) -> AmemResult<Vec<(DuplicateCandidate, DedupDecision)>> {
+    let mut decisions = Vec::new();
+    if candidates.is_empty() {
+        writeln!(out, "No duplicate candidates found.")?;
+        return Ok(decisions);
+    }
+
+    let mut line = String::new();
+    for (i, candidate) in candidates.iter().enumerate() {
+        writeln!(
+            out,
+            "\n[{}/{}] cosine {:.3}, jaccard {:.3}",
+            i + 1,
+            candidates.len(),
+            candidate.cosine,
+            candidate.jaccard
+        )?;
+        for (label, id) in [("1", candidate.first), ("2", candidate.second)] {
+            if let Some(node) = graph.get_node(id) {
+                writeln!(
+                    out,
+                    "  {label}) #{} (confidence {:.2}, session {}): {}",
+                    node.id, node.confidence, node.session_id, node.content
+                )?;
+            }
+        }
+
+        let decision = loop {
+            write!(
+                out,
+                "  [k]eep both, merge into [1], merge into [2], [s]kip, [q]uit > "
+            )?;
+            out.flush()?;
+            line.clear();
+            if input.read_line(&mut line)? == 0 {
+                break None;
+            }
+            match line.trim().to_ascii_lowercase().as_str() {
+                "k" | "keep" => break Some(DedupDecision::KeepBoth),
+                "1" => break Some(DedupDecision::MergeIntoFirst),
+                "2" => break Some(DedupDecision::MergeIntoSecond),
+                "s" | "skip" | "" => break Some(DedupDecision::Skip),
+                "q" | "quit" => break None,
+                other => writeln!(out, "  Unrecognized choice {other:?}")?,
+            }
+        };
+        match decision {
+            Some(decision) => decisions.push((*candidate, decision)),
+            None => break,
+        }
+    }
+    Ok(decisions)
+}
+
+fn write_dedup_plan(
+    path: &Path,
+    floor: f32,
+    decisions: &[(DuplicateCandidate, DedupDecision)],
+) -> AmemResult<()> {
+    let entries: Vec<serde_json::Value> = decisions
+        .iter()
+        .map(|(c, d)| {
+            serde_json::json!({
+                "first": c.first,
+                "second": c.second,
+                "cosine": c.cosine,
+                "jaccard": c.jaccard,
+                "decision": d.name(),
+            })
+        })
+        .collect();
+    let plan = serde_json::json!({
+        "version": 1,
+        "floor": floor,
+        "decisions": entries,
+    });
+    std::fs::write(
+        path,
+        serde_json::to_string_pretty(&plan).unwrap_or_default(),
+    )?;
+    Ok(())
+}
+
+fn read_dedup_plan(path: &Path) -> AmemResult<Vec<(DuplicateCandidate, DedupDecision)>> {
+    let invalid = |msg: String| crate::types::AmemError::Compression(msg);
+    let data = std::fs::read_to_string(path)?;
+    let plan: serde_json::Value =
+        serde_json::from_str(&data).map_err(|e| invalid(e.to_string()))?;
+    let entries = plan
+        .get("decisions")
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| invalid("dedup plan has no decisions array".to_string()))?;
+
+    entries
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            let id = |key: &str| {
+                entry
+                    .get(key)
+                    .and_then(|v| v.as_u64())
+                    .ok_or_else(|| invalid(format!("dedup plan entry {i}: missing {key}")))
+            };
+            let score = |key: &str| entry.get(key).and_then(|v| v.as_f64()).unwrap_or(0.0) as f32;
+            let decision = entry
+                .get("decision")
+                .and_then(|v| v.as_str())
+                .and_then(DedupDecision::from_name)
+                .ok_or_else(|| invalid(format!("dedup plan entry {i}: unknown decision")))?;
+            Ok((
+                DuplicateCandidate {
+                    first: id("first")?,
+                    second: id("second")?,
+                    cosine: score("cosine"),
+                    jaccard: score("jaccard"),
+                },
+                decision,
+            ))
+        })
+        .collect()
+}
+
+/// Propose `Supports` edges from facts to the inferences drawn from them,
+/// listing them with scores. With `apply`, the edges are added and the file
+/// is rewritten; otherwise nothing is written.
+pub fn cmd_link_inferences(
+    path: &Path,
+    min_score: f32,
+    max_per_inference: usize,
+    apply: bool,
+    json: bool,
+) -> AmemResult<()> {
+    let mut graph = read_graph(path)?;
+    let query_engine = QueryEngine::new();
+    let proposals = query_engine.propose_inference_support(&graph, min_score, max_per_inference);
+
+    let linked = if apply && !proposals.is_empty() {
+        let linked = query_engine.apply_support_proposals(&mut graph, &proposals)?;
+        graph_writer(graph.dimension()).write_to_file(&graph, path)?;
+        linked
+    } else {
+        0
+    };
+    let inferences: std::collections::HashSet<u64> =
+        proposals.iter().map(|p| p.inference).collect();
+
+    if json {
+        let entries: Vec<serde_json::Value> = proposals
+            .iter()
+            .map(|p| {
+                serde_json::json!({
+                    "fact": p.fact,
+                    "inference": p.inference,
+                    "score": p.score,
+                })
+            })
+            .collect();
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "dry_run": !apply,
+                "min_score": min_score,
+                "max_per_inference": max_per_inference,
+                "inferences": inferences.len(),
+                "proposed": entries,
+                "linked": linked,
+            }))
+            .unwrap_or_default()
+        );
+        return Ok(());
+    }
+
+    if apply {
+        println!("Inference linking applied:\n");
+    } else {
+        println!("Inference linking DRY RUN (use --apply to add the edges):\n");
+    }
+    let preview = |id: u64| {
+        graph
+            .get_node(id)
+            .map(|n| n.content.chars().take(60).collect::<String>())
+            .unwrap_or_default()
+    };
+    for p in &proposals {
+        println!(
+            "  Fact {} supports Inference {}  (score {:.3})",
+            p.fact, p.inference, p.score
+        );
+        println!("     {:?}", preview(p.fact));
+        println!("     -> {:?}", preview(p.inference));
+    }
+    println!(
+        "\n  Proposed: {} edges for {} inferences",
+        proposals.len(),
+        inferences.len()
+    );
+    if apply {
+        println!("  Linked: {linked}");
+    }
+    Ok(())
+}
+
 /// Drift detection.
 pub fn cmd_drift(
     path: &Path,
@@ -1894,7 +5686,7 @@ pub fn cmd_drift(
     min_relevance: f32,
     json: bool,
 ) -> AmemResult<()> {
-    let graph = AmemReader::read_from_file(path)?;
+    let graph = read_graph(path)?;
     let query_engine = QueryEngine::new();
 
     let report = query_engine.drift_detection(
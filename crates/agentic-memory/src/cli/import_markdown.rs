@@ -0,0 +1,139 @@
+//! Markdown notes parser for `amem import-markdown`.
+//!
+//! Headings become episodes, list items become facts (or the type given
+//! with `--type`), and blockquotes become inferences. Any line starting with
+//! "I decided" or "Decision:" is a decision. Other text, and anything inside
+//! fenced code blocks, is skipped.
+
+use crate::types::EventType;
+
+/// One node parsed from a Markdown file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarkdownItem {
+    pub event_type: EventType,
+    pub content: String,
+    /// Index (into the parsed items) of the heading this item sits under.
+    pub heading: Option<usize>,
+    /// 1-based line the item starts on.
+    pub line: usize,
+}
+
+/// Heading text of an ATX heading (`#` to `######`), without the markers.
+fn heading_text(line: &str) -> Option<&str> {
+    let level = line.bytes().take_while(|&b| b == b'#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    let rest = &line[level..];
+    if !rest.is_empty() && !rest.starts_with([' ', '\t']) {
+        return None;
+    }
+    Some(rest.trim().trim_end_matches('#').trim_end())
+}
+
+/// Text of a bullet (`-`, `*`, `+`) or numbered (`1.`, `1)`) list item.
+fn list_item_text(line: &str) -> Option<&str> {
+    if let Some(rest) = line
+        .strip_prefix("- ")
+        .or_else(|| line.strip_prefix("* "))
+        .or_else(|| line.strip_prefix("+ "))
+    {
+        return Some(rest.trim());
+    }
+    let digits = line.bytes().take_while(u8::is_ascii_digit).count();
+    if digits == 0 || digits > 9 {
+        return None;
+    }
+    line[digits..]
+        .strip_prefix(". ")
+        .or_else(|| line[digits..].strip_prefix(") "))
+        .map(str::trim)
+}
+
+/// Whether `text` records a decision ("I decided ...", "Decision: ...").
+fn is_decision(text: &str) -> bool {
+    let lower = text.to_lowercase();
+    lower.starts_with("i decided") || lower.starts_with("decision:")
+}
+
+/// Parse Markdown `text` into nodes, in document order.
+///
+/// List items get `list_type` unless they read as a decision. Consecutive
+/// blockquote lines form one inference.
+pub fn parse_markdown(text: &str, list_type: EventType) -> Vec<MarkdownItem> {
+    let mut items: Vec<MarkdownItem> = Vec::new();
+    let mut heading = None;
+    let mut fence: Option<&str> = None;
+    let mut in_quote = false;
+
+    for (i, raw) in text.lines().enumerate() {
+        let line = raw.trim();
+        if let Some(marker) = fence {
+            if line.starts_with(marker) {
+                fence = None;
+            }
+            continue;
+        }
+        if line.starts_with("```") || line.starts_with("~~~") {
+            fence = Some(&line[..3]);
+            in_quote = false;
+            continue;
+        }
+
+        if let Some(quoted) = line.strip_prefix('>') {
+            let quoted = quoted.trim();
+            if quoted.is_empty() {
+                in_quote = false;
+            } else if let Some(last) = items.last_mut().filter(|_| in_quote) {
+                last.content.push(' ');
+                last.content.push_str(quoted);
+            } else {
+                let event_type = if is_decision(quoted) {
+                    EventType::Decision
+                } else {
+                    EventType::Inference
+                };
+                items.push(MarkdownItem {
+                    event_type,
+                    content: quoted.to_string(),
+                    heading,
+                    line: i + 1,
+                });
+                in_quote = true;
+            }
+            continue;
+        }
+        in_quote = false;
+
+        let (event_type, content) = if let Some(title) = heading_text(line) {
+            (EventType::Episode, title)
+        } else if let Some(item) = list_item_text(line) {
+            let event_type = if is_decision(item) {
+                EventType::Decision
+            } else {
+                list_type
+            };
+            (event_type, item)
+        } else if is_decision(line) {
+            (EventType::Decision, line)
+        } else {
+            continue;
+        };
+        if content.is_empty() {
+            continue;
+        }
+        let parent = if event_type == EventType::Episode {
+            heading = Some(items.len());
+            None
+        } else {
+            heading
+        };
+        items.push(MarkdownItem {
+            event_type,
+            content: content.to_string(),
+            heading: parent,
+            line: i + 1,
+        });
+    }
+    items
+}
@@ -1,6 +1,7 @@
 //! CLI command implementations for the `amem` binary.
 
 pub mod commands;
+pub mod import_markdown;
 pub mod repl;
 pub mod repl_commands;
 pub mod repl_complete;
@@ -1,7 +1,8 @@
 //! Interactive REPL for amem — slash command interface.
 //!
 //! Launch with `amem` (no subcommand) to enter interactive mode.
-//! Type `/help` for available commands, Tab for completion.
+//! Type `/help` for available commands, Tab for completion. Dot commands
+//! (`.json on`, `.save`, `.load`, `.help`) control the shell itself.
 
 use crate::cli::repl_commands;
 use crate::cli::repl_complete;
@@ -9,12 +10,17 @@ use rustyline::config::CompletionType;
 use rustyline::error::ReadlineError;
 use rustyline::{Config, Editor};
 
-/// History file location.
-fn history_path() -> std::path::PathBuf {
+/// A file in the user's home directory (current directory if unknown).
+pub(crate) fn home_file(name: &str) -> std::path::PathBuf {
     let home = std::env::var("HOME")
         .or_else(|_| std::env::var("USERPROFILE"))
         .unwrap_or_else(|_| ".".to_string());
-    std::path::PathBuf::from(home).join(".amem_history")
+    std::path::PathBuf::from(home).join(name)
+}
+
+/// History file location.
+fn history_path() -> std::path::PathBuf {
+    home_file(".amem_history")
 }
 
 /// Print the welcome banner.
@@ -69,6 +75,7 @@ pub fn run() -> Result<(), Box<dyn std::error::Error>> {
                         break;
                     }
                     Ok(false) => {}
+                    Err(e) if state.json => state.report_error(e),
                     Err(e) => {
                         eprintln!("  Error: {e}");
                     }
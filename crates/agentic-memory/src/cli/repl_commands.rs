@@ -1,10 +1,11 @@
 //! Slash command dispatch for the amem REPL.
 
+use std::fmt::Display;
 use std::path::PathBuf;
 
 use crate::cli::commands;
-use crate::cli::repl_complete::COMMANDS;
-use crate::engine::PatternSort;
+use crate::cli::repl_complete::{COMMANDS, COMMAND_EXAMPLES, META_COMMANDS};
+use crate::engine::{CausalDirection, PatternSort};
 use crate::graph::TraversalDirection;
 use crate::types::EventType;
 
@@ -12,6 +13,10 @@ use crate::types::EventType;
 pub struct ReplState {
     /// Path to the currently loaded .amem file.
     pub file_path: Option<PathBuf>,
+    /// Emit command output and errors as JSON (toggled by `.json on|off`).
+    pub json: bool,
+    /// Where `.save` remembers the loaded file path.
+    pub saved_path_file: PathBuf,
 }
 
 impl Default for ReplState {
@@ -22,14 +27,28 @@ impl Default for ReplState {
 
 impl ReplState {
     pub fn new() -> Self {
-        Self { file_path: None }
+        Self {
+            file_path: None,
+            json: false,
+            saved_path_file: crate::cli::repl::home_file(".amem_repl"),
+        }
+    }
+
+    /// Report a problem: a `{"error": ...}` object on stdout in JSON mode,
+    /// indented text on stderr otherwise.
+    pub fn report_error(&self, message: impl Display) {
+        if self.json {
+            println!("{}", serde_json::json!({ "error": message.to_string() }));
+        } else {
+            eprintln!("  {message}");
+        }
     }
 
     fn require_file(&self) -> Option<&PathBuf> {
         if let Some(ref p) = self.file_path {
             Some(p)
         } else {
-            eprintln!("  No .amem file loaded. Use /load <file.amem> or /create <file.amem>");
+            self.report_error("No .amem file loaded. Use /load <file.amem> or /create <file.amem>");
             None
         }
     }
@@ -42,9 +61,13 @@ pub fn execute(input: &str, state: &mut ReplState) -> Result<bool, Box<dyn std::
         return Ok(false);
     }
 
+    if let Some(meta) = input.strip_prefix('.') {
+        return execute_meta(meta, state);
+    }
+
     let input = input.strip_prefix('/').unwrap_or(input);
     if input.is_empty() {
-        cmd_help();
+        cmd_help(state);
         return Ok(false);
     }
 
@@ -54,7 +77,7 @@ pub fn execute(input: &str, state: &mut ReplState) -> Result<bool, Box<dyn std::
 
     match cmd {
         "exit" | "quit" => return Ok(true),
-        "help" | "h" | "?" => cmd_help(),
+        "help" | "h" | "?" => cmd_help(state),
         "clear" | "cls" => eprint!("\x1b[2J\x1b[H"),
         "create" => cmd_create(args, state)?,
         "load" => cmd_load(args, state)?,
@@ -72,22 +95,129 @@ pub fn execute(input: &str, state: &mut ReplState) -> Result<bool, Box<dyn std::
         "sessions" => cmd_sessions(state)?,
         _ => {
             if let Some(suggestion) = crate::cli::repl_complete::suggest_command(cmd) {
-                eprintln!("  Unknown command '/{cmd}'. Did you mean {suggestion}?");
+                state.report_error(format!(
+                    "Unknown command '/{cmd}'. Did you mean {suggestion}?"
+                ));
+            } else {
+                state.report_error(format!(
+                    "Unknown command '/{cmd}'. Type /help for commands."
+                ));
+            }
+        }
+    }
+
+    Ok(false)
+}
+
+/// Execute a dot command (`.json`, `.save`, `.load`, `.help`).
+fn execute_meta(input: &str, state: &mut ReplState) -> Result<bool, Box<dyn std::error::Error>> {
+    let mut parts = input.splitn(2, ' ');
+    let cmd = parts.next().unwrap_or("");
+    let args = parts.next().unwrap_or("").trim();
+
+    match cmd {
+        "json" => match args {
+            "on" => state.json = true,
+            "off" => state.json = false,
+            "" => {}
+            _ => {
+                state.report_error("Usage: .json on|off");
+                return Ok(false);
+            }
+        },
+        "save" => {
+            let file = match state.require_file() {
+                Some(f) => f.clone(),
+                None => return Ok(false),
+            };
+            let file = std::fs::canonicalize(&file).unwrap_or(file);
+            std::fs::write(&state.saved_path_file, file.to_string_lossy().as_bytes())?;
+            if state.json {
+                println!("{}", serde_json::json!({ "saved": file }));
             } else {
-                eprintln!("  Unknown command '/{cmd}'. Type /help for commands.");
+                eprintln!("  Saved working file: {}", file.display());
             }
+            return Ok(false);
+        }
+        "load" => {
+            let saved = match std::fs::read_to_string(&state.saved_path_file) {
+                Ok(s) if !s.trim().is_empty() => PathBuf::from(s.trim()),
+                _ => {
+                    state.report_error("No saved working file. Use .save after /load.");
+                    return Ok(false);
+                }
+            };
+            if !saved.exists() {
+                state.report_error(format!("Saved file not found: {}", saved.display()));
+                return Ok(false);
+            }
+            if state.json {
+                println!("{}", serde_json::json!({ "loaded": saved }));
+            } else {
+                eprintln!("  Loaded working file: {}", saved.display());
+            }
+            state.file_path = Some(saved);
+            return Ok(false);
+        }
+        "help" => {
+            cmd_help(state);
+            return Ok(false);
+        }
+        _ => {
+            state.report_error(format!(
+                "Unknown command '.{cmd}'. Type .help for commands."
+            ));
+            return Ok(false);
         }
     }
 
+    // Report the resulting mode.
+    if state.json {
+        println!("{}", serde_json::json!({ "json": true }));
+    } else {
+        eprintln!("  JSON output: off");
+    }
     Ok(false)
 }
 
-fn cmd_help() {
+fn cmd_help(state: &ReplState) {
+    let example = |cmd: &str| {
+        COMMAND_EXAMPLES
+            .iter()
+            .find(|(c, _)| *c == cmd)
+            .map(|(_, e)| *e)
+            .unwrap_or("")
+    };
+
+    if state.json {
+        let list = |commands: &[(&str, &str)]| -> Vec<serde_json::Value> {
+            commands
+                .iter()
+                .map(|(cmd, desc)| {
+                    serde_json::json!({
+                        "command": cmd,
+                        "description": desc,
+                        "example": example(cmd),
+                    })
+                })
+                .collect()
+        };
+        println!(
+            "{}",
+            serde_json::json!({
+                "commands": list(COMMANDS),
+                "meta_commands": list(META_COMMANDS),
+            })
+        );
+        return;
+    }
+
     eprintln!();
     eprintln!("  Commands:");
     eprintln!();
-    for (cmd, desc) in COMMANDS {
+    for (cmd, desc) in COMMANDS.iter().chain(META_COMMANDS) {
         eprintln!("    {cmd:<20} {desc}");
+        eprintln!("    {:<20} \x1b[90me.g. {}\x1b[0m", "", example(cmd));
     }
     eprintln!();
     eprintln!("  Tip: Tab completion works for commands, event types, and .amem files.");
@@ -96,7 +226,7 @@ fn cmd_help() {
 
 fn cmd_create(args: &str, state: &mut ReplState) -> Result<(), Box<dyn std::error::Error>> {
     if args.is_empty() {
-        eprintln!("  Usage: /create <file.amem> [--dimension N]");
+        state.report_error("Usage: /create <file.amem> [--dimension N]");
         return Ok(());
     }
     let tokens: Vec<&str> = args.split_whitespace().collect();
@@ -108,24 +238,26 @@ fn cmd_create(args: &str, state: &mut ReplState) -> Result<(), Box<dyn std::erro
         .and_then(|s| s.parse().ok())
         .unwrap_or(128);
 
-    commands::cmd_create(&file, dim)?;
+    commands::cmd_create(&file, dim, state.json)?;
     state.file_path = Some(file.clone());
-    eprintln!("  Created and loaded: {}", file.display());
+    if !state.json {
+        eprintln!("  Created and loaded: {}", file.display());
+    }
     Ok(())
 }
 
 fn cmd_load(args: &str, state: &mut ReplState) -> Result<(), Box<dyn std::error::Error>> {
     if args.is_empty() {
-        eprintln!("  Usage: /load <file.amem>");
+        state.report_error("Usage: /load <file.amem>");
         return Ok(());
     }
     let file = PathBuf::from(args.split_whitespace().next().unwrap_or(args));
     if !file.exists() {
-        eprintln!("  File not found: {}", file.display());
+        state.report_error(format!("File not found: {}", file.display()));
         return Ok(());
     }
     // Verify it's readable
-    commands::cmd_info(&file, false)?;
+    commands::cmd_info(&file, state.json)?;
     state.file_path = Some(file);
     Ok(())
 }
@@ -135,7 +267,7 @@ fn cmd_info(state: &mut ReplState) -> Result<(), Box<dyn std::error::Error>> {
         Some(f) => f.clone(),
         None => return Ok(()),
     };
-    commands::cmd_info(&file, false)?;
+    commands::cmd_info(&file, state.json)?;
     Ok(())
 }
 
@@ -146,18 +278,32 @@ fn cmd_add(args: &str, state: &mut ReplState) -> Result<(), Box<dyn std::error::
     };
     let tokens: Vec<&str> = args.splitn(2, ' ').collect();
     if tokens.len() < 2 {
-        eprintln!("  Usage: /add <type> <content>");
-        eprintln!("  Types: fact, decision, inference, correction, skill, episode");
+        state.report_error(
+            "Usage: /add <type> <content> (types: fact, decision, inference, correction, skill, episode)",
+        );
         return Ok(());
     }
     let et = match EventType::from_name(tokens[0]) {
         Some(et) => et,
         None => {
-            eprintln!("  Invalid event type: {}", tokens[0]);
+            state.report_error(format!("Invalid event type: {}", tokens[0]));
             return Ok(());
         }
     };
-    commands::cmd_add(&file, et, tokens[1], 0, 1.0, None, false)?;
+    commands::cmd_add(
+        &file,
+        et,
+        tokens[1],
+        0,
+        1.0,
+        &[],
+        None,
+        None,
+        None,
+        false,
+        false,
+        state.json,
+    )?;
     Ok(())
 }
 
@@ -169,11 +315,11 @@ fn cmd_get(args: &str, state: &mut ReplState) -> Result<(), Box<dyn std::error::
     let node_id: u64 = match args.split_whitespace().next().and_then(|s| s.parse().ok()) {
         Some(id) => id,
         None => {
-            eprintln!("  Usage: /get <node-id>");
+            state.report_error("Usage: /get <node-id>");
             return Ok(());
         }
     };
-    commands::cmd_get(&file, node_id, false)?;
+    commands::cmd_get(&file, node_id, state.json)?;
     Ok(())
 }
 
@@ -187,6 +333,7 @@ fn cmd_search(args: &str, state: &mut ReplState) -> Result<(), Box<dyn std::erro
     let mut event_types = Vec::new();
     let mut limit: usize = 20;
     let mut sort = PatternSort::MostRecent;
+    let mut explain = false;
     let mut i = 0;
     while i < tokens.len() {
         match tokens[i] {
@@ -211,6 +358,10 @@ fn cmd_search(args: &str, state: &mut ReplState) -> Result<(), Box<dyn std::erro
                 };
                 i += 2;
             }
+            "--explain" => {
+                explain = true;
+                i += 1;
+            }
             _ => {
                 i += 1;
             }
@@ -226,7 +377,11 @@ fn cmd_search(args: &str, state: &mut ReplState) -> Result<(), Box<dyn std::erro
         None,
         sort,
         limit,
+        None,
+        None,
+        explain,
         false,
+        state.json,
     )?;
     Ok(())
 }
@@ -237,11 +392,23 @@ fn cmd_text_search(args: &str, state: &mut ReplState) -> Result<(), Box<dyn std:
         None => return Ok(()),
     };
     if args.is_empty() {
-        eprintln!("  Usage: /text-search <query>");
+        state.report_error("Usage: /text-search <query>");
         return Ok(());
     }
     let query = args.to_string();
-    commands::cmd_text_search(&file, &query, vec![], vec![], 20, 0.0, false)?;
+    commands::cmd_text_search(
+        &file,
+        &query,
+        vec![],
+        vec![],
+        20,
+        0.0,
+        false,
+        0.0,
+        None,
+        false,
+        state.json,
+    )?;
     Ok(())
 }
 
@@ -253,7 +420,7 @@ fn cmd_traverse(args: &str, state: &mut ReplState) -> Result<(), Box<dyn std::er
     let start_id: u64 = match args.split_whitespace().next().and_then(|s| s.parse().ok()) {
         Some(id) => id,
         None => {
-            eprintln!("  Usage: /traverse <start-node-id> [--depth N]");
+            state.report_error("Usage: /traverse <start-node-id> [--depth N]");
             return Ok(());
         }
     };
@@ -274,6 +441,7 @@ fn cmd_traverse(args: &str, state: &mut ReplState) -> Result<(), Box<dyn std::er
         50,
         0.0,
         false,
+        state.json,
     )?;
     Ok(())
 }
@@ -286,11 +454,11 @@ fn cmd_impact(args: &str, state: &mut ReplState) -> Result<(), Box<dyn std::erro
     let node_id: u64 = match args.split_whitespace().next().and_then(|s| s.parse().ok()) {
         Some(id) => id,
         None => {
-            eprintln!("  Usage: /impact <node-id>");
+            state.report_error("Usage: /impact <node-id>");
             return Ok(());
         }
     };
-    commands::cmd_impact(&file, node_id, 10, false)?;
+    commands::cmd_impact(&file, node_id, 10, CausalDirection::Downstream, state.json)?;
     Ok(())
 }
 
@@ -300,7 +468,21 @@ fn cmd_centrality(args: &str, state: &mut ReplState) -> Result<(), Box<dyn std::
         None => return Ok(()),
     };
     let algo = args.split_whitespace().next().unwrap_or("pagerank");
-    commands::cmd_centrality(&file, algo, 0.85, vec![], vec![], 20, 100, false)?;
+    commands::cmd_centrality(
+        &file,
+        algo,
+        0.85,
+        vec![],
+        vec![],
+        20,
+        100,
+        vec![],
+        None,
+        false,
+        None,
+        5,
+        state.json,
+    )?;
     Ok(())
 }
 
@@ -311,20 +493,20 @@ fn cmd_path(args: &str, state: &mut ReplState) -> Result<(), Box<dyn std::error:
     };
     let tokens: Vec<&str> = args.split_whitespace().collect();
     if tokens.len() < 2 {
-        eprintln!("  Usage: /path <source-id> <target-id>");
+        state.report_error("Usage: /path <source-id> <target-id>");
         return Ok(());
     }
     let source: u64 = match tokens[0].parse() {
         Ok(v) => v,
         Err(_) => {
-            eprintln!("  Invalid source ID");
+            state.report_error("Invalid source ID");
             return Ok(());
         }
     };
     let target: u64 = match tokens[1].parse() {
         Ok(v) => v,
         Err(_) => {
-            eprintln!("  Invalid target ID");
+            state.report_error("Invalid target ID");
             return Ok(());
         }
     };
@@ -336,7 +518,8 @@ fn cmd_path(args: &str, state: &mut ReplState) -> Result<(), Box<dyn std::error:
         TraversalDirection::Both,
         20,
         false,
-        false,
+        std::collections::HashMap::new(),
+        state.json,
     )?;
     Ok(())
 }
@@ -351,7 +534,7 @@ fn cmd_gaps(args: &str, state: &mut ReplState) -> Result<(), Box<dyn std::error:
         .next()
         .and_then(|s| s.parse().ok())
         .unwrap_or(20);
-    commands::cmd_gaps(&file, 0.5, 1, limit, "dangerous", None, false)?;
+    commands::cmd_gaps(&file, 0.5, 1, limit, "dangerous", None, state.json)?;
     Ok(())
 }
 
@@ -360,7 +543,7 @@ fn cmd_stats(state: &mut ReplState) -> Result<(), Box<dyn std::error::Error>> {
         Some(f) => f.clone(),
         None => return Ok(()),
     };
-    commands::cmd_stats(&file, false)?;
+    commands::cmd_stats(&file, state.json)?;
     Ok(())
 }
 
@@ -369,6 +552,6 @@ fn cmd_sessions(state: &mut ReplState) -> Result<(), Box<dyn std::error::Error>>
         Some(f) => f.clone(),
         None => return Ok(()),
     };
-    commands::cmd_sessions(&file, 20, false)?;
+    commands::cmd_sessions(&file, 20, None, state.json)?;
     Ok(())
 }
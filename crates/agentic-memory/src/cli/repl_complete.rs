@@ -29,6 +29,42 @@ pub const COMMANDS: &[(&str, &str)] = &[
     ("/exit", "Quit the REPL"),
 ];
 
+/// Dot commands that control the REPL itself.
+pub const META_COMMANDS: &[(&str, &str)] = &[
+    (".json", "Switch JSON output on or off"),
+    (".save", "Remember the loaded file for later sessions"),
+    (".load", "Reload the remembered file"),
+    (".help", "Show commands with examples"),
+];
+
+/// One usage example per command, shown by `.help`.
+pub const COMMAND_EXAMPLES: &[(&str, &str)] = &[
+    ("/create", "/create project.amem --dimension 128"),
+    ("/info", "/info"),
+    ("/load", "/load project.amem"),
+    ("/add", "/add fact The API uses OAuth2"),
+    ("/get", "/get 42"),
+    (
+        "/search",
+        "/search --type fact,decision --sort confidence --limit 10",
+    ),
+    ("/text-search", "/text-search deploy pipeline"),
+    ("/traverse", "/traverse 7 --depth 3"),
+    ("/impact", "/impact 7"),
+    ("/centrality", "/centrality pagerank"),
+    ("/path", "/path 3 12"),
+    ("/gaps", "/gaps 10"),
+    ("/stats", "/stats"),
+    ("/sessions", "/sessions"),
+    ("/clear", "/clear"),
+    ("/help", "/help"),
+    ("/exit", "/exit"),
+    (".json", ".json on"),
+    (".save", ".save"),
+    (".load", ".load"),
+    (".help", ".help"),
+];
+
 /// Event types for completion.
 pub const EVENT_TYPES: &[&str] = &[
     "fact",
@@ -86,6 +122,7 @@ impl Completer for AmemHelper {
         if !input.contains(' ') {
             let matches: Vec<Pair> = COMMANDS
                 .iter()
+                .chain(META_COMMANDS)
                 .filter(|(cmd, _)| cmd.starts_with(input))
                 .map(|(cmd, desc)| Pair {
                     display: format!("{cmd:<18} {desc}"),
@@ -114,6 +151,19 @@ impl Completer for AmemHelper {
                 Ok((prefix_start, matches))
             }
 
+            ".json" => {
+                let prefix_start = input.len() - args.len();
+                let matches: Vec<Pair> = ["on", "off"]
+                    .iter()
+                    .filter(|v| v.starts_with(args.trim()))
+                    .map(|v| Pair {
+                        display: v.to_string(),
+                        replacement: v.to_string(),
+                    })
+                    .collect();
+                Ok((prefix_start, matches))
+            }
+
             "/add" => {
                 if !args.contains(' ') {
                     let prefix_start = input.len() - args.len();
@@ -51,6 +51,9 @@ impl From<AmemError> for SisterError {
             AmemError::Io(io_err) => {
                 SisterError::new(ErrorCode::StorageError, format!("I/O error: {}", io_err))
             }
+            AmemError::LockTimeout { .. } | AmemError::Storage(_) => {
+                SisterError::new(ErrorCode::StorageError, e.to_string())
+            }
             AmemError::Truncated => {
                 SisterError::new(ErrorCode::StorageError, "File is empty or truncated")
             }
@@ -58,6 +61,18 @@ impl From<AmemError> for SisterError {
                 ErrorCode::ChecksumMismatch,
                 format!("Corrupt data at offset {}", offset),
             ),
+            AmemError::InvalidSessionAlias(_) | AmemError::SessionAliasTaken { .. } => {
+                SisterError::new(ErrorCode::InvalidInput, e.to_string())
+            }
+            AmemError::SessionAliasNotFound(alias) => {
+                SisterError::not_found(format!("session alias {}", alias))
+            }
+            AmemError::EmbeddingModelMismatch { .. }
+            | AmemError::InvalidEdgeType(_)
+            | AmemError::PatchConflict(_)
+            | AmemError::InvalidPattern(_) => {
+                SisterError::new(ErrorCode::InvalidInput, e.to_string())
+            }
             _ => SisterError::new(ErrorCode::MemoryError, e.to_string()),
         }
     }
@@ -400,6 +415,7 @@ impl Grounding for MemorySister {
             event_types: vec![],
             session_ids: vec![],
             min_score: 0.3,
+            confidence_weight: 0.0,
         };
 
         let matches = self
@@ -466,6 +482,7 @@ impl Grounding for MemorySister {
             event_types: vec![],
             session_ids: vec![],
             min_score: 0.0,
+            confidence_weight: 0.0,
         };
 
         let matches = self
@@ -592,6 +609,7 @@ impl Queryable for MemorySister {
                     event_types: vec![],
                     session_ids: vec![],
                     min_score: 0.0,
+                    confidence_weight: 0.0,
                 };
 
                 let matches = self
@@ -971,7 +971,7 @@ impl super::query::QueryEngine {
         // Edge type counts.
         let mut edge_type_counts: HashMap<u8, usize> = HashMap::new();
         for edge in edges {
-            *edge_type_counts.entry(edge.edge_type as u8).or_insert(0) += 1;
+            *edge_type_counts.entry(edge.edge_type.to_u8()).or_insert(0) += 1;
         }
 
         // Causal chain depth: longest chain of CausedBy edges.
@@ -1,7 +1,89 @@
 //! Confidence decay and access tracking.
 
+use std::fmt;
+
 use crate::types::{CognitiveEvent, EventType};
 
+const MICROS_PER_DAY: f64 = 86_400_000_000.0;
+
+/// A custom decay curve: `(current_decay, age_micros, access_count) ->
+/// new_decay`, where the age is measured from the node's last access. See
+/// [`WriteEngine::new_with_decay`](super::WriteEngine::new_with_decay).
+pub type DecayFn = dyn Fn(f32, u64, u64) -> f32 + Send + Sync;
+
+/// Built-in forgetting curves for [`WriteEngine`](super::WriteEngine).
+///
+/// Each curve is a function of age alone, starting at 1.0 on access, so
+/// running decay twice at the same time gives the same score.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DecayFunction {
+    /// Halve the score every `half_life_days`.
+    Exponential(f32),
+    /// `1 / (1 + days)`: fast early loss with a long tail.
+    Hyperbolic,
+    /// Full score until `threshold_days`, then `step_factor`.
+    Step(f32, f32),
+}
+
+impl DecayFunction {
+    /// Score of a node last accessed `age_micros` ago.
+    pub fn apply(&self, age_micros: u64) -> f32 {
+        let days = age_micros as f64 / MICROS_PER_DAY;
+        let score = match *self {
+            Self::Exponential(half_life_days) => {
+                0.5f64.powf(days / f64::from(half_life_days.max(f32::MIN_POSITIVE)))
+            }
+            Self::Hyperbolic => 1.0 / (1.0 + days),
+            Self::Step(threshold_days, step_factor) => {
+                if days < f64::from(threshold_days) {
+                    1.0
+                } else {
+                    f64::from(step_factor)
+                }
+            }
+        };
+        (score as f32).clamp(0.0, 1.0)
+    }
+
+    /// Parse `exponential:<half_life_days>`, `hyperbolic` or
+    /// `step:<threshold_days>:<step_factor>`.
+    pub fn parse(raw: &str) -> Option<Self> {
+        let raw = raw.trim().to_ascii_lowercase();
+        let mut parts = raw.split(':').map(str::trim);
+        let number = |part: Option<&str>| {
+            part.and_then(|p| p.parse::<f32>().ok())
+                .filter(|v| v.is_finite() && *v >= 0.0)
+        };
+        let function = match parts.next()? {
+            "exponential" | "exp" => Self::Exponential(number(parts.next()).filter(|v| *v > 0.0)?),
+            "hyperbolic" => Self::Hyperbolic,
+            "step" => Self::Step(
+                number(parts.next())?,
+                number(parts.next()).filter(|v| *v <= 1.0)?,
+            ),
+            _ => return None,
+        };
+        parts.next().is_none().then_some(function)
+    }
+
+    /// This curve as a [`DecayFn`].
+    pub fn boxed(self) -> Box<DecayFn> {
+        Box::new(move |_, age_micros, _| self.apply(age_micros))
+    }
+}
+
+impl fmt::Display for DecayFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Exponential(half_life_days) => write!(f, "exponential:{half_life_days}"),
+            Self::Hyperbolic => f.write_str("hyperbolic"),
+            Self::Step(threshold_days, step_factor) => {
+                write!(f, "step:{threshold_days}:{step_factor}")
+            }
+        }
+    }
+}
+
 /// Calculate the decay score for a node.
 ///
 /// Formula: base_importance * recency_factor * access_factor
@@ -30,3 +112,19 @@ pub fn calculate_decay(event: &CognitiveEvent, current_time: u64) -> f32 {
 
     (base_importance * recency_factor * access_factor).clamp(0.0, 1.0)
 }
+
+/// Importance of a node for top-N retention.
+///
+/// Formula: confidence * recency_factor * connectivity_factor
+///
+/// - recency_factor: exp(-lambda * days_since_last_use) with lambda = 0.01,
+///   where last use is the later of creation and last access
+/// - connectivity_factor: 1 + ln(1 + degree), counting edges in both directions
+pub fn importance_score(event: &CognitiveEvent, degree: usize, current_time: u64) -> f32 {
+    let micros_per_day: f64 = 86_400_000_000.0;
+    let last_use = event.last_accessed.max(event.created_at);
+    let days = current_time.saturating_sub(last_use) as f64 / micros_per_day;
+    let recency_factor = (-0.01f64 * days).exp() as f32;
+    let connectivity_factor = 1.0 + (degree as f32).ln_1p();
+    event.confidence * recency_factor * connectivity_factor
+}
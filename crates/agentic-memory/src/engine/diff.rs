@@ -0,0 +1,346 @@
+//! Node-level comparison of two graphs, and patches that replay it.
+//!
+//! Nodes are matched by ID. A diff lists the nodes only one side has and,
+//! for nodes on both sides, changes to `content`, `confidence` and
+//! `decay_score`. A [`GraphPatch`] carries the same changes in a form that
+//! can be applied to the first graph to bring it in line with the second.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::graph::MemoryGraph;
+use crate::types::{AmemError, AmemResult, CognitiveEvent, Edge, EdgeTypeNames};
+
+/// Current patch format version.
+pub const PATCH_VERSION: u32 = 1;
+
+/// A node field compared by [`diff_graphs`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffField {
+    /// Node text.
+    Content,
+    /// Confidence score.
+    Confidence,
+    /// Decay score.
+    DecayScore,
+}
+
+impl DiffField {
+    /// Field name as it appears in diffs and patches.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Content => "content",
+            Self::Confidence => "confidence",
+            Self::DecayScore => "decay_score",
+        }
+    }
+
+    fn get(&self, node: &CognitiveEvent) -> FieldValue {
+        match self {
+            Self::Content => FieldValue::Text(node.content.clone()),
+            Self::Confidence => FieldValue::Number(node.confidence),
+            Self::DecayScore => FieldValue::Number(node.decay_score),
+        }
+    }
+
+    fn accepts(&self, value: &FieldValue) -> bool {
+        matches!(
+            (self, value),
+            (Self::Content, FieldValue::Text(_))
+                | (Self::Confidence | Self::DecayScore, FieldValue::Number(_))
+        )
+    }
+
+    /// Set the field; values of the wrong type are ignored (see `accepts`).
+    fn set(&self, node: &mut CognitiveEvent, value: &FieldValue) {
+        match (self, value) {
+            (Self::Content, FieldValue::Text(text)) => node.content = text.clone(),
+            (Self::Confidence, FieldValue::Number(v)) => node.confidence = *v,
+            (Self::DecayScore, FieldValue::Number(v)) => node.decay_score = *v,
+            _ => {}
+        }
+    }
+}
+
+/// Value of a compared field.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum FieldValue {
+    /// A score.
+    Number(f32),
+    /// Node text.
+    Text(String),
+}
+
+impl std::fmt::Display for FieldValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Number(v) => write!(f, "{v}"),
+            Self::Text(text) => write!(f, "{text:?}"),
+        }
+    }
+}
+
+/// One field that differs between the two versions of a node.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldChange {
+    /// Node ID.
+    pub id: u64,
+    /// Which field changed.
+    pub field: DiffField,
+    /// Value in the first graph.
+    pub before: FieldValue,
+    /// Value in the second graph.
+    pub after: FieldValue,
+}
+
+/// Differences between two graphs, by node ID.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GraphDiff {
+    /// Nodes only the first graph has, in ID order.
+    pub only_in_a: Vec<CognitiveEvent>,
+    /// Nodes only the second graph has, in ID order.
+    pub only_in_b: Vec<CognitiveEvent>,
+    /// Field changes of nodes both graphs have, by node ID.
+    pub modified: Vec<FieldChange>,
+}
+
+impl GraphDiff {
+    /// Whether the graphs hold the same nodes with the same compared fields.
+    pub fn is_empty(&self) -> bool {
+        self.only_in_a.is_empty() && self.only_in_b.is_empty() && self.modified.is_empty()
+    }
+}
+
+/// Compare `a` with `b`. Edges and fields other than `content`,
+/// `confidence` and `decay_score` are not compared.
+pub fn diff_graphs(a: &MemoryGraph, b: &MemoryGraph) -> GraphDiff {
+    let mut a_nodes: Vec<&CognitiveEvent> = a.nodes().iter().collect();
+    a_nodes.sort_unstable_by_key(|n| n.id);
+    let mut b_nodes: Vec<&CognitiveEvent> = b.nodes().iter().collect();
+    b_nodes.sort_unstable_by_key(|n| n.id);
+
+    let mut diff = GraphDiff::default();
+    for node in &a_nodes {
+        let Some(other) = b.get_node(node.id) else {
+            diff.only_in_a.push((*node).clone());
+            continue;
+        };
+        for field in [
+            DiffField::Content,
+            DiffField::Confidence,
+            DiffField::DecayScore,
+        ] {
+            let (before, after) = (field.get(node), field.get(other));
+            if before != after {
+                diff.modified.push(FieldChange {
+                    id: node.id,
+                    field,
+                    before,
+                    after,
+                });
+            }
+        }
+    }
+    diff.only_in_b = b_nodes
+        .into_iter()
+        .filter(|n| a.get_node(n.id).is_none())
+        .cloned()
+        .collect();
+    diff
+}
+
+/// An edge carried by a patch, with its type stored by name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PatchEdge {
+    /// Source node ID.
+    pub source_id: u64,
+    /// Target node ID.
+    pub target_id: u64,
+    /// Edge type name, built-in or custom.
+    pub edge_type: String,
+    /// Edge weight.
+    pub weight: f32,
+    /// Creation timestamp (Unix epoch microseconds).
+    pub created_at: u64,
+}
+
+impl PatchEdge {
+    fn from_edge(edge: &Edge, graph: &MemoryGraph) -> Self {
+        Self {
+            source_id: edge.source_id,
+            target_id: edge.target_id,
+            edge_type: graph.edge_type_name(edge.edge_type).to_string(),
+            weight: edge.weight,
+            created_at: edge.created_at,
+        }
+    }
+
+    fn to_edge(&self, names: &mut EdgeTypeNames) -> AmemResult<Edge> {
+        let edge_type = names.register(&self.edge_type)?;
+        Ok(Edge::with_timestamp(
+            self.source_id,
+            self.target_id,
+            edge_type,
+            self.weight,
+            self.created_at,
+        ))
+    }
+}
+
+/// Changes that turn the first graph of a diff into the second.
+///
+/// Added nodes keep their IDs and bring their feature vectors and the
+/// edges that touch them. Removed nodes leave tombstones, as any deletion
+/// does. Every change is checked before any is made, so a patch that does
+/// not fit the graph leaves it untouched.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphPatch {
+    /// Patch format version.
+    pub version: u32,
+    /// IDs of nodes to remove.
+    pub remove: Vec<u64>,
+    /// Nodes to add under their own IDs.
+    pub add: Vec<CognitiveEvent>,
+    /// Feature vectors of added nodes, by node ID (zero vectors are left out).
+    #[serde(default)]
+    pub feature_vecs: HashMap<u64, Vec<f32>>,
+    /// Edges touching added nodes.
+    #[serde(default)]
+    pub edges: Vec<PatchEdge>,
+    /// Field changes; each `before` must match the graph the patch is applied to.
+    pub modify: Vec<FieldChange>,
+}
+
+impl GraphPatch {
+    /// Build the patch for `diff`, taking edges and vectors of added nodes
+    /// from `b`, the second graph of the diff.
+    pub fn from_diff(diff: &GraphDiff, b: &MemoryGraph) -> Self {
+        let added: HashSet<u64> = diff.only_in_b.iter().map(|n| n.id).collect();
+        let feature_vecs = diff
+            .only_in_b
+            .iter()
+            .filter(|n| n.feature_vec.iter().any(|&v| v != 0.0))
+            .map(|n| (n.id, n.feature_vec.clone()))
+            .collect();
+        let edges = b
+            .edges()
+            .iter()
+            .filter(|e| added.contains(&e.source_id) || added.contains(&e.target_id))
+            .map(|e| PatchEdge::from_edge(e, b))
+            .collect();
+        Self {
+            version: PATCH_VERSION,
+            remove: diff.only_in_a.iter().map(|n| n.id).collect(),
+            add: diff.only_in_b.clone(),
+            feature_vecs,
+            edges,
+            modify: diff.modified.clone(),
+        }
+    }
+
+    /// Whether applying the patch would change nothing.
+    pub fn is_empty(&self) -> bool {
+        self.remove.is_empty() && self.add.is_empty() && self.modify.is_empty()
+    }
+
+    /// Apply the patch to `graph`.
+    pub fn apply(&self, graph: &mut MemoryGraph) -> AmemResult<()> {
+        if self.version > PATCH_VERSION {
+            return Err(AmemError::PatchConflict(format!(
+                "unsupported patch version {}",
+                self.version
+            )));
+        }
+
+        let removed: HashSet<u64> = self.remove.iter().copied().collect();
+        for &id in &self.remove {
+            if graph.get_node(id).is_none() {
+                return Err(AmemError::PatchConflict(format!(
+                    "node {id} to remove is missing"
+                )));
+            }
+        }
+        for change in &self.modify {
+            let node = graph
+                .get_node(change.id)
+                .filter(|_| !removed.contains(&change.id))
+                .ok_or_else(|| {
+                    AmemError::PatchConflict(format!("node {} to modify is missing", change.id))
+                })?;
+            if !change.field.accepts(&change.after) {
+                return Err(AmemError::PatchConflict(format!(
+                    "wrong value type for {} of node {}",
+                    change.field.name(),
+                    change.id
+                )));
+            }
+            if change.field.get(node) != change.before {
+                return Err(AmemError::PatchConflict(format!(
+                    "{} of node {} is {}, expected {}",
+                    change.field.name(),
+                    change.id,
+                    change.field.get(node),
+                    change.before
+                )));
+            }
+        }
+        let mut added = HashSet::new();
+        for node in &self.add {
+            let taken = graph.get_node(node.id).is_some() && !removed.contains(&node.id);
+            if taken || !added.insert(node.id) {
+                return Err(AmemError::PatchConflict(format!(
+                    "node {} to add already exists",
+                    node.id
+                )));
+            }
+            node.validate(graph.dimension())?;
+            if let Some(vec) = self.feature_vecs.get(&node.id) {
+                if vec.len() != graph.dimension() {
+                    return Err(AmemError::DimensionMismatch {
+                        expected: graph.dimension(),
+                        got: vec.len(),
+                    });
+                }
+            }
+        }
+        let exists_after = |id: u64| {
+            added.contains(&id) || (graph.get_node(id).is_some() && !removed.contains(&id))
+        };
+        let mut edge_type_names = graph.edge_type_names().clone();
+        let edges = self
+            .edges
+            .iter()
+            .map(|e| {
+                for id in [e.source_id, e.target_id] {
+                    if !exists_after(id) {
+                        return Err(AmemError::InvalidEdgeTarget(id));
+                    }
+                }
+                e.to_edge(&mut edge_type_names)
+            })
+            .collect::<AmemResult<Vec<_>>>()?;
+
+        for &id in &self.remove {
+            graph.remove_node(id)?;
+        }
+        for change in &self.modify {
+            let node = graph
+                .get_node_mut(change.id)
+                .ok_or(AmemError::NodeNotFound(change.id))?;
+            change.field.set(node, &change.after);
+        }
+        for node in &self.add {
+            let mut node = node.clone();
+            node.feature_vec = self.feature_vecs.get(&node.id).cloned().unwrap_or_default();
+            graph.insert_node(node)?;
+        }
+        graph.set_edge_type_names(edge_type_names);
+        for edge in edges {
+            graph.add_edge(edge)?;
+        }
+        Ok(())
+    }
+}
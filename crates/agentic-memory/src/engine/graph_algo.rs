@@ -2,9 +2,10 @@
 
 use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
 
-use crate::graph::traversal::TraversalDirection;
+use super::sampling::NodeSample;
+use crate::graph::traversal::{connected_components, TraversalDirection};
 use crate::graph::MemoryGraph;
-use crate::types::{AmemResult, Edge, EdgeType, EventType};
+use crate::types::{AmemResult, CognitiveEvent, Edge, EdgeType, EventType};
 
 /// Which centrality algorithm to use.
 #[derive(Debug, Clone)]
@@ -25,6 +26,14 @@ pub struct CentralityParams {
     pub top_k: usize,
     pub event_types: Vec<EventType>,
     pub edge_types: Vec<EdgeType>,
+    /// Personalize PageRank around these nodes: teleports land on the seeds
+    /// instead of anywhere, so scores reflect proximity to them. Empty (or no
+    /// seed among the candidate nodes) means uniform teleport. Ignored by the
+    /// other algorithms.
+    pub seeds: Vec<u64>,
+    /// Let PageRank flow both ways along symmetric edge types (`RelatedTo`,
+    /// `Contradicts`). Degree and betweenness already ignore direction.
+    pub symmetric_undirected: bool,
 }
 
 /// Result of a centrality computation.
@@ -34,6 +43,9 @@ pub struct CentralityResult {
     pub algorithm: CentralityAlgorithm,
     pub iterations: u32,
     pub converged: bool,
+    /// Connected components of the scored nodes over the followed edges,
+    /// taken as undirected (see [`MemoryGraph::connected_components`]).
+    pub components: Vec<Vec<u64>>,
 }
 
 /// Parameters for shortest path query.
@@ -44,6 +56,10 @@ pub struct ShortestPathParams {
     pub direction: TraversalDirection,
     pub max_depth: u32,
     pub weighted: bool,
+    /// Per-type traversal cost for weighted paths; types not listed cost 1.0.
+    /// When empty, a weighted edge costs `1 - weight`. Otherwise it costs
+    /// `type_cost / weight`, so cheap types and heavy edges are preferred.
+    pub type_cost: HashMap<EdgeType, f32>,
 }
 
 /// Result of a shortest path query.
@@ -57,12 +73,60 @@ pub struct PathResult {
     pub found: bool,
 }
 
+/// Parameters for extracting the high-centrality core of a graph.
+pub struct CentralityCoreParams {
+    /// Keep nodes whose PageRank score is at least this value.
+    pub min_centrality: f32,
+    /// Also keep lower-ranked nodes that connect retained nodes.
+    pub keep_connectors: bool,
+    pub damping: f32,
+    pub max_iterations: u32,
+}
+
+/// The part of a graph that survives a centrality threshold.
+pub struct CentralityCore {
+    /// Retained nodes (core plus connectors), in ID order.
+    pub nodes: Vec<CognitiveEvent>,
+    /// Edges whose endpoints were both retained.
+    pub edges: Vec<Edge>,
+    /// Nodes at or above the threshold.
+    pub core_ids: Vec<u64>,
+    /// Nodes kept only because they lie on a path between core nodes.
+    pub connector_ids: Vec<u64>,
+    /// Node count of the full graph.
+    pub total_nodes: usize,
+}
+
 impl super::query::QueryEngine {
     /// Compute centrality scores for nodes in the graph.
     pub fn centrality(
         &self,
         graph: &MemoryGraph,
         params: CentralityParams,
+    ) -> AmemResult<CentralityResult> {
+        self.centrality_over(graph, params, None)
+    }
+
+    /// Approximate centrality from a random sample of the candidate nodes.
+    ///
+    /// Betweenness runs Brandes' algorithm from the sampled nodes only and
+    /// scales the result up to estimate the full sum. PageRank and degree are
+    /// computed on the subgraph induced by the sample, so only sampled nodes
+    /// are ranked.
+    pub fn centrality_sampled(
+        &self,
+        graph: &MemoryGraph,
+        params: CentralityParams,
+        sample: NodeSample,
+    ) -> AmemResult<CentralityResult> {
+        self.centrality_over(graph, params, Some(sample))
+    }
+
+    fn centrality_over(
+        &self,
+        graph: &MemoryGraph,
+        params: CentralityParams,
+        sample: Option<NodeSample>,
     ) -> AmemResult<CentralityResult> {
         let type_filter: HashSet<EventType> = params.event_types.iter().copied().collect();
         let edge_filter: HashSet<EdgeType> = params.edge_types.iter().copied().collect();
@@ -75,23 +139,60 @@ impl super::query::QueryEngine {
             .map(|n| n.id)
             .collect();
 
+        // Betweenness keeps every node and samples the path sources instead.
+        let mut sources = None;
+        let node_ids = match (sample, &params.algorithm) {
+            (Some(sample), CentralityAlgorithm::Betweenness) => {
+                sources = Some(sample.select(&node_ids));
+                node_ids
+            }
+            (Some(sample), _) => sample.select(&node_ids),
+            (None, _) => node_ids,
+        };
+
         let node_set: HashSet<u64> = node_ids.iter().copied().collect();
 
-        // Collect relevant edges
+        // Collect relevant edges; a self-edge would only feed a node's rank
+        // back into itself, so any left in an older file are skipped.
         let edges: Vec<&Edge> = graph
             .edges()
             .iter()
             .filter(|e| {
-                node_set.contains(&e.source_id)
+                e.source_id != e.target_id
+                    && node_set.contains(&e.source_id)
                     && node_set.contains(&e.target_id)
                     && (edge_filter.is_empty() || edge_filter.contains(&e.edge_type))
             })
             .collect();
 
-        match params.algorithm {
+        // Mirror symmetric edges whose reverse is not stored.
+        let mirrored: Vec<Edge> = if params.symmetric_undirected {
+            let stored: HashSet<(u64, u64, EdgeType)> = edges
+                .iter()
+                .map(|e| (e.source_id, e.target_id, e.edge_type))
+                .collect();
+            edges
+                .iter()
+                .filter(|e| {
+                    e.edge_type.is_symmetric()
+                        && !stored.contains(&(e.target_id, e.source_id, e.edge_type))
+                })
+                .map(|e| Edge {
+                    source_id: e.target_id,
+                    target_id: e.source_id,
+                    ..**e
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let pagerank_edges: Vec<&Edge> = edges.iter().copied().chain(mirrored.iter()).collect();
+
+        let mut result = match params.algorithm {
             CentralityAlgorithm::PageRank { damping } => self.pagerank(
                 &node_ids,
-                &edges,
+                &pagerank_edges,
+                &params.seeds,
                 damping,
                 params.max_iterations,
                 params.tolerance,
@@ -99,15 +200,20 @@ impl super::query::QueryEngine {
             ),
             CentralityAlgorithm::Degree => self.degree_centrality(&node_ids, &edges, params.top_k),
             CentralityAlgorithm::Betweenness => {
-                self.betweenness_centrality(&node_ids, &edges, params.top_k)
+                self.betweenness_centrality(&node_ids, &edges, sources.as_deref(), params.top_k)
             }
-        }
+        }?;
+        result.components =
+            connected_components(&node_ids, edges.iter().map(|e| (e.source_id, e.target_id)));
+        Ok(result)
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn pagerank(
         &self,
         node_ids: &[u64],
         edges: &[&Edge],
+        seeds: &[u64],
         damping: f32,
         max_iterations: u32,
         tolerance: f32,
@@ -120,6 +226,7 @@ impl super::query::QueryEngine {
                 algorithm: CentralityAlgorithm::PageRank { damping },
                 iterations: 0,
                 converged: true,
+                components: Vec::new(),
             });
         }
 
@@ -143,22 +250,36 @@ impl super::query::QueryEngine {
             }
         }
 
-        let mut pr = vec![1.0 / n as f32; n];
+        // Teleport distribution: uniform, or spread evenly over the seeds.
+        let seed_idx: HashSet<usize> = seeds
+            .iter()
+            .filter_map(|id| id_to_idx.get(id).copied())
+            .collect();
+        let teleport: Vec<f32> = if seed_idx.is_empty() {
+            vec![1.0 / n as f32; n]
+        } else {
+            let share = 1.0 / seed_idx.len() as f32;
+            (0..n)
+                .map(|i| if seed_idx.contains(&i) { share } else { 0.0 })
+                .collect()
+        };
+
+        let mut pr = teleport.clone();
         let mut iterations = 0;
         let mut converged = false;
 
         for _ in 0..max_iterations {
             iterations += 1;
-            let mut new_pr = vec![(1.0 - damping) / n as f32; n];
+            let mut new_pr: Vec<f32> = teleport.iter().map(|t| (1.0 - damping) * t).collect();
 
-            // Dangling node rank
+            // Dangling node rank is redistributed like a teleport.
             let dangling_sum: f32 = (0..n)
                 .filter(|&i| outgoing[i].is_empty())
                 .map(|i| pr[i])
                 .sum();
 
             for i in 0..n {
-                new_pr[i] += damping * dangling_sum / n as f32;
+                new_pr[i] += damping * dangling_sum * teleport[i];
                 for &j in &incoming[i] {
                     let out_degree = outgoing[j].len() as f32;
                     if out_degree > 0.0 {
@@ -185,7 +306,11 @@ impl super::query::QueryEngine {
             .zip(pr.iter())
             .map(|(&id, &s)| (id, s))
             .collect();
-        scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        scores.sort_by(|a, b| {
+            b.1.partial_cmp(&a.1)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(a.0.cmp(&b.0))
+        });
         scores.truncate(top_k);
 
         Ok(CentralityResult {
@@ -193,6 +318,7 @@ impl super::query::QueryEngine {
             algorithm: CentralityAlgorithm::PageRank { damping },
             iterations,
             converged,
+            components: Vec::new(),
         })
     }
 
@@ -219,7 +345,11 @@ impl super::query::QueryEngine {
             .into_iter()
             .map(|(id, deg)| (id, deg as f32 / max_possible as f32))
             .collect();
-        scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        scores.sort_by(|a, b| {
+            b.1.partial_cmp(&a.1)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(a.0.cmp(&b.0))
+        });
         scores.truncate(top_k);
 
         Ok(CentralityResult {
@@ -227,6 +357,7 @@ impl super::query::QueryEngine {
             algorithm: CentralityAlgorithm::Degree,
             iterations: 0,
             converged: true,
+            components: Vec::new(),
         })
     }
 
@@ -234,6 +365,7 @@ impl super::query::QueryEngine {
         &self,
         node_ids: &[u64],
         edges: &[&Edge],
+        sampled_sources: Option<&[u64]>,
         top_k: usize,
     ) -> AmemResult<CentralityResult> {
         let n = node_ids.len();
@@ -243,6 +375,7 @@ impl super::query::QueryEngine {
                 algorithm: CentralityAlgorithm::Betweenness,
                 iterations: 0,
                 converged: true,
+                components: Vec::new(),
             });
         }
 
@@ -266,8 +399,12 @@ impl super::query::QueryEngine {
 
         let mut betweenness = vec![0.0f32; n];
 
-        // Sample source nodes if graph is large
-        let sources: Vec<usize> = if n > 10_000 {
+        // Sample source nodes if asked to, or if the graph is large
+        let sources: Vec<usize> = if let Some(ids) = sampled_sources {
+            ids.iter()
+                .filter_map(|id| id_to_idx.get(id).copied())
+                .collect()
+        } else if n > 10_000 {
             (0..1000.min(n)).collect()
         } else {
             (0..n).collect()
@@ -309,19 +446,26 @@ impl super::query::QueryEngine {
             }
         }
 
-        // Normalize
-        let norm = if n > 2 {
+        // Normalize; a sampled sum is scaled up to estimate the full one.
+        let mut norm = if n > 2 {
             ((n - 1) * (n - 2)) as f32
         } else {
             1.0
         };
+        if sampled_sources.is_some() && !sources.is_empty() {
+            norm *= sources.len() as f32 / n as f32;
+        }
 
         let mut scores: Vec<(u64, f32)> = node_ids
             .iter()
             .enumerate()
             .map(|(i, &id)| (id, betweenness[i] / norm))
             .collect();
-        scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        scores.sort_by(|a, b| {
+            b.1.partial_cmp(&a.1)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(a.0.cmp(&b.0))
+        });
         scores.truncate(top_k);
 
         Ok(CentralityResult {
@@ -329,6 +473,74 @@ impl super::query::QueryEngine {
             algorithm: CentralityAlgorithm::Betweenness,
             iterations: 0,
             converged: true,
+            components: Vec::new(),
+        })
+    }
+
+    /// Extract the subgraph of nodes whose PageRank meets a threshold.
+    ///
+    /// Dropping low-ranked nodes can split the graph. With `keep_connectors`,
+    /// core nodes that were connected stay connected: shortest undirected
+    /// paths are grown from every core node at once, and the paths joining
+    /// neighbouring regions are kept cheapest first until each originally
+    /// connected group of core nodes is linked again (an approximate Steiner
+    /// tree). Intermediate nodes on those paths are the connectors.
+    pub fn centrality_core(
+        &self,
+        graph: &MemoryGraph,
+        params: CentralityCoreParams,
+    ) -> AmemResult<CentralityCore> {
+        let ranked = self.centrality(
+            graph,
+            CentralityParams {
+                algorithm: CentralityAlgorithm::PageRank {
+                    damping: params.damping,
+                },
+                max_iterations: params.max_iterations,
+                tolerance: 1e-6,
+                top_k: usize::MAX,
+                event_types: Vec::new(),
+                edge_types: Vec::new(),
+                seeds: Vec::new(),
+                symmetric_undirected: false,
+            },
+        )?;
+
+        let mut core_ids: Vec<u64> = ranked
+            .scores
+            .iter()
+            .filter(|(_, score)| *score >= params.min_centrality)
+            .map(|(id, _)| *id)
+            .collect();
+        core_ids.sort_unstable();
+
+        let mut connector_ids = if params.keep_connectors {
+            connecting_nodes(graph, &core_ids)
+        } else {
+            Vec::new()
+        };
+        connector_ids.sort_unstable();
+
+        let retained: HashSet<u64> = core_ids.iter().chain(&connector_ids).copied().collect();
+        let nodes: Vec<CognitiveEvent> = graph
+            .nodes()
+            .iter()
+            .filter(|n| retained.contains(&n.id))
+            .cloned()
+            .collect();
+        let edges: Vec<Edge> = graph
+            .edges()
+            .iter()
+            .filter(|e| retained.contains(&e.source_id) && retained.contains(&e.target_id))
+            .copied()
+            .collect();
+
+        Ok(CentralityCore {
+            nodes,
+            edges,
+            core_ids,
+            connector_ids,
+            total_nodes: graph.node_count(),
         })
     }
 
@@ -356,6 +568,14 @@ impl super::query::QueryEngine {
             return Err(crate::types::AmemError::NodeNotFound(params.target_id));
         }
 
+        if let Some(&bad) = params
+            .type_cost
+            .values()
+            .find(|c| !c.is_finite() || **c < 0.0)
+        {
+            return Err(crate::types::AmemError::InvalidWeight(bad));
+        }
+
         let edge_filter: HashSet<EdgeType> = params.edge_types.iter().copied().collect();
 
         if params.weighted {
@@ -562,7 +782,8 @@ impl super::query::QueryEngine {
         }
 
         let mut dist: HashMap<u64, f32> = HashMap::new();
-        let mut prev: HashMap<u64, u64> = HashMap::new();
+        // Node → (predecessor, edge traversed to reach it).
+        let mut prev: HashMap<u64, (u64, Edge)> = HashMap::new();
         let mut heap = BinaryHeap::new();
 
         dist.insert(params.source_id, 0.0);
@@ -575,24 +796,17 @@ impl super::query::QueryEngine {
             if node == params.target_id {
                 // Reconstruct path
                 let mut path = Vec::new();
+                let mut edges = Vec::new();
                 let mut current = params.target_id;
                 while current != params.source_id {
                     path.push(current);
-                    current = prev[&current];
+                    let (previous, edge) = prev[&current];
+                    edges.push(edge);
+                    current = previous;
                 }
                 path.push(params.source_id);
                 path.reverse();
-
-                // Collect edges
-                let mut edges = Vec::new();
-                for i in 0..path.len() - 1 {
-                    for edge in graph.edges_from(path[i]) {
-                        if edge.target_id == path[i + 1] {
-                            edges.push(*edge);
-                            break;
-                        }
-                    }
-                }
+                edges.reverse();
 
                 return Ok(PathResult {
                     path,
@@ -611,12 +825,14 @@ impl super::query::QueryEngine {
                 if !edge_filter.is_empty() && !edge_filter.contains(&edge.edge_type) {
                     continue;
                 }
-                let edge_cost = 1.0 - edge.weight; // Higher weight = lower cost
+                let Some(edge_cost) = weighted_edge_cost(params, edge) else {
+                    continue;
+                };
                 let next_cost = cost + edge_cost;
 
                 if next_cost < *dist.get(&edge.target_id).unwrap_or(&f32::INFINITY) {
                     dist.insert(edge.target_id, next_cost);
-                    prev.insert(edge.target_id, node);
+                    prev.insert(edge.target_id, (node, *edge));
                     heap.push(State {
                         cost: next_cost,
                         node: edge.target_id,
@@ -633,12 +849,14 @@ impl super::query::QueryEngine {
                     if !edge_filter.is_empty() && !edge_filter.contains(&edge.edge_type) {
                         continue;
                     }
-                    let edge_cost = 1.0 - edge.weight;
+                    let Some(edge_cost) = weighted_edge_cost(params, edge) else {
+                        continue;
+                    };
                     let next_cost = cost + edge_cost;
 
                     if next_cost < *dist.get(&edge.source_id).unwrap_or(&f32::INFINITY) {
                         dist.insert(edge.source_id, next_cost);
-                        prev.insert(edge.source_id, node);
+                        prev.insert(edge.source_id, (node, *edge));
                         heap.push(State {
                             cost: next_cost,
                             node: edge.source_id,
@@ -656,3 +874,97 @@ impl super::query::QueryEngine {
         })
     }
 }
+
+/// Cost of traversing `edge` in a weighted shortest-path search, or `None`
+/// when the edge cannot be traversed (zero weight under type costs).
+fn weighted_edge_cost(params: &ShortestPathParams, edge: &Edge) -> Option<f32> {
+    if params.type_cost.is_empty() {
+        return Some(1.0 - edge.weight); // Higher weight = lower cost
+    }
+    if edge.weight <= 0.0 {
+        return None;
+    }
+    let type_cost = params
+        .type_cost
+        .get(&edge.edge_type)
+        .copied()
+        .unwrap_or(1.0);
+    Some(type_cost / edge.weight)
+}
+
+/// Non-terminal nodes on the paths that reconnect `terminals`.
+///
+/// A multi-source BFS assigns every reachable node to its nearest terminal.
+/// Each edge between two regions is a candidate terminal-to-terminal path;
+/// candidates are taken shortest first, skipping any whose terminals are
+/// already joined.
+fn connecting_nodes(graph: &MemoryGraph, terminals: &[u64]) -> Vec<u64> {
+    let mut neighbors: HashMap<u64, Vec<u64>> = HashMap::new();
+    for edge in graph.edges() {
+        neighbors
+            .entry(edge.source_id)
+            .or_default()
+            .push(edge.target_id);
+        neighbors
+            .entry(edge.target_id)
+            .or_default()
+            .push(edge.source_id);
+    }
+
+    // node -> (nearest terminal, distance, parent towards that terminal)
+    let mut region: HashMap<u64, (u64, u32, u64)> = HashMap::new();
+    let mut queue = VecDeque::new();
+    for &t in terminals {
+        region.insert(t, (t, 0, t));
+        queue.push_back(t);
+    }
+    while let Some(node) = queue.pop_front() {
+        let (origin, dist, _) = region[&node];
+        for &next in neighbors.get(&node).map(Vec::as_slice).unwrap_or(&[]) {
+            if let std::collections::hash_map::Entry::Vacant(slot) = region.entry(next) {
+                slot.insert((origin, dist + 1, node));
+                queue.push_back(next);
+            }
+        }
+    }
+
+    let mut bridges: Vec<(u32, u64, u64)> = graph
+        .edges()
+        .iter()
+        .filter_map(|e| {
+            let (a, da, _) = region.get(&e.source_id)?;
+            let (b, db, _) = region.get(&e.target_id)?;
+            (a != b).then_some((da + db + 1, e.source_id, e.target_id))
+        })
+        .collect();
+    bridges.sort_unstable();
+
+    let mut joined: HashMap<u64, u64> = terminals.iter().map(|&t| (t, t)).collect();
+    fn root(joined: &mut HashMap<u64, u64>, mut t: u64) -> u64 {
+        while joined[&t] != t {
+            let up = joined[&joined[&t]];
+            joined.insert(t, up);
+            t = up;
+        }
+        t
+    }
+
+    let mut connectors: HashSet<u64> = HashSet::new();
+    for (_, u, v) in bridges {
+        let ru = root(&mut joined, region[&u].0);
+        let rv = root(&mut joined, region[&v].0);
+        if ru == rv {
+            continue;
+        }
+        joined.insert(ru, rv);
+        for end in [u, v] {
+            let mut node = end;
+            while region[&node].1 > 0 {
+                connectors.insert(node);
+                node = region[&node].2;
+            }
+        }
+    }
+
+    connectors.into_iter().collect()
+}
@@ -5,13 +5,18 @@
 //! [`MemoryGraph`].  This is the only query type that mutates the
 //! graph.
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 
+use serde::Serialize;
+
 use crate::graph::MemoryGraph;
-use crate::index::cosine_similarity;
-use crate::types::{AmemResult, Edge, EdgeType, EventType};
+use crate::index::{cosine_similarity, TermIndex};
+use crate::types::{
+    AmemError, AmemResult, CognitiveEvent, Edge, EdgeType, EventType, MAX_EDGES_PER_NODE,
+};
 
+use super::decay::importance_score;
 use super::tokenizer::Tokenizer;
 
 // ---------------------------------------------------------------------------
@@ -103,6 +108,168 @@ pub struct ConsolidationReport {
     pub backup_path: Option<PathBuf>,
 }
 
+/// Parameters for time-based compaction.
+pub struct CompactionParams {
+    /// Nodes neither created nor accessed at or after this time (Unix
+    /// microseconds) are removed, whatever their decay score.
+    pub cutoff: u64,
+    /// Event types that are never removed.
+    pub keep_types: Vec<EventType>,
+}
+
+/// Nodes removed by [`QueryEngine::compact_older_than`](super::query::QueryEngine::compact_older_than).
+pub struct CompactionReport {
+    /// Removed node IDs, ascending.
+    pub removed: Vec<u64>,
+    /// Removed node count per event type, in type order; types with no
+    /// removals are omitted.
+    pub removed_by_type: Vec<(EventType, usize)>,
+}
+
+/// Parameters for rank-based compaction.
+pub struct KeepTopParams {
+    /// Nodes to keep, pinned ones included. Pinned nodes survive even when
+    /// there are more of them than this.
+    pub keep: usize,
+    /// Event types that are never removed.
+    pub keep_types: Vec<EventType>,
+    /// Node IDs that are never removed.
+    pub pinned: Vec<u64>,
+    /// Redirect edges from surviving nodes into removed ones to the newest
+    /// surviving Episode of the removed node's session, instead of dropping
+    /// them.
+    pub rewire_to_episode: bool,
+    /// Reference time for the recency factor (Unix microseconds).
+    pub now: u64,
+}
+
+/// Outcome of [`QueryEngine::compact_keep_top`](super::query::QueryEngine::compact_keep_top).
+pub struct KeepTopReport {
+    /// Removed node IDs, ascending.
+    pub removed: Vec<u64>,
+    /// Removed node count per event type, in type order.
+    pub removed_by_type: Vec<(EventType, usize)>,
+    /// Importance of the lowest-ranked node kept by rank, when any node was
+    /// cut: everything scoring below it (and not pinned) was removed.
+    pub cutoff_score: Option<f32>,
+    /// Nodes kept because they were pinned or of a protected type.
+    pub pinned_kept: usize,
+    /// Edges redirected to a session episode.
+    pub rewired_edges: usize,
+}
+
+/// Criteria for [`QueryEngine::delete_where`](super::query::QueryEngine::delete_where).
+/// A node is deleted when it matches every criterion that is set; a filter
+/// with none set matches nothing.
+#[derive(Debug, Clone, Default)]
+pub struct DeleteFilter {
+    /// Event types to delete (empty = any type).
+    pub event_types: Vec<EventType>,
+    /// Only nodes whose decay score is below this.
+    pub decay_below: Option<f32>,
+    /// Only nodes whose confidence is below this.
+    pub confidence_below: Option<f32>,
+    /// Sessions to delete from (empty = any session).
+    pub session_ids: Vec<u32>,
+    /// Only nodes created before this time (Unix microseconds).
+    pub created_before: Option<u64>,
+}
+
+impl DeleteFilter {
+    /// Whether no criterion is set.
+    pub fn is_empty(&self) -> bool {
+        self.event_types.is_empty()
+            && self.decay_below.is_none()
+            && self.confidence_below.is_none()
+            && self.session_ids.is_empty()
+            && self.created_before.is_none()
+    }
+
+    /// Whether `node` matches every set criterion.
+    pub fn matches(&self, node: &CognitiveEvent) -> bool {
+        !self.is_empty()
+            && (self.event_types.is_empty() || self.event_types.contains(&node.event_type))
+            && self.decay_below.is_none_or(|d| node.decay_score < d)
+            && self.confidence_below.is_none_or(|c| node.confidence < c)
+            && (self.session_ids.is_empty() || self.session_ids.contains(&node.session_id))
+            && self.created_before.is_none_or(|t| node.created_at < t)
+    }
+}
+
+/// Outcome of [`QueryEngine::delete_where`](super::query::QueryEngine::delete_where).
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DeleteReport {
+    /// Number of deleted nodes.
+    pub deleted_count: usize,
+    /// Deleted node IDs, ascending.
+    pub deleted_ids: Vec<u64>,
+    /// Edges that touched a deleted node and went with it.
+    pub edges_removed: usize,
+}
+
+/// A pair of Fact nodes that may duplicate each other, `first < second`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DuplicateCandidate {
+    /// The lower node ID.
+    pub first: u64,
+    /// The higher node ID.
+    pub second: u64,
+    /// Cosine similarity of the feature vectors.
+    pub cosine: f32,
+    /// Jaccard overlap of the content tokens.
+    pub jaccard: f32,
+}
+
+/// A reviewer's resolution of a [`DuplicateCandidate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DedupDecision {
+    /// The nodes are distinct; leave both.
+    KeepBoth,
+    /// `first` survives and supersedes `second`.
+    MergeIntoFirst,
+    /// `second` survives and supersedes `first`.
+    MergeIntoSecond,
+    /// Undecided; leave both.
+    Skip,
+}
+
+impl DedupDecision {
+    /// Stable name used in saved plans.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::KeepBoth => "keep-both",
+            Self::MergeIntoFirst => "merge-into-first",
+            Self::MergeIntoSecond => "merge-into-second",
+            Self::Skip => "skip",
+        }
+    }
+
+    /// Parse a name produced by [`DedupDecision::name`].
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "keep-both" => Some(Self::KeepBoth),
+            "merge-into-first" => Some(Self::MergeIntoFirst),
+            "merge-into-second" => Some(Self::MergeIntoSecond),
+            "skip" => Some(Self::Skip),
+            _ => None,
+        }
+    }
+}
+
+/// Minimum token Jaccard overlap for two facts to count as duplicates.
+const DEDUP_MIN_JACCARD: f32 = 0.5;
+
+/// A proposed `Supports` edge from a Fact to an Inference derived from it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SupportProposal {
+    /// The supporting Fact (edge source).
+    pub fact: u64,
+    /// The unsupported Inference (edge target).
+    pub inference: u64,
+    /// Token Jaccard overlap of the two contents.
+    pub score: f32,
+}
+
 // ---------------------------------------------------------------------------
 // Negation words used by the contradiction detector.
 // ---------------------------------------------------------------------------
@@ -236,47 +403,11 @@ impl super::query::QueryEngine {
     ) {
         let tokenizer = Tokenizer::new();
 
-        // Collect Fact node IDs, respecting the session filter.
-        let fact_ids: Vec<u64> = graph
-            .nodes()
-            .iter()
-            .filter(|n| {
-                n.event_type == EventType::Fact && in_session_range(n.session_id, session_filter)
-            })
-            .map(|n| n.id)
-            .collect();
-
-        // Group facts by cluster so we only compare within-cluster pairs.
-        let cluster_count = graph.cluster_map().cluster_count();
-        let fact_set: HashSet<u64> = fact_ids.iter().copied().collect();
-
-        // Build cluster -> [fact ids in that cluster].
-        let mut cluster_groups: Vec<Vec<u64>> = Vec::new();
-        if cluster_count > 0 {
-            for ci in 0..cluster_count {
-                let members: Vec<u64> = graph
-                    .cluster_map()
-                    .get_cluster(ci)
-                    .iter()
-                    .copied()
-                    .filter(|id| fact_set.contains(id))
-                    .collect();
-                if members.len() >= 2 {
-                    cluster_groups.push(members);
-                }
-            }
-        }
-
-        // Fallback: if no clusters, treat all facts as one group.
-        if cluster_groups.is_empty() && fact_ids.len() >= 2 {
-            cluster_groups.push(fact_ids.clone());
-        }
-
         // Track which nodes have already been marked as duplicates so we
         // don't supersede the same node twice.
         let mut superseded: HashSet<u64> = HashSet::new();
 
-        for group in &cluster_groups {
+        for group in &fact_groups(graph, session_filter) {
             for i in 0..group.len() {
                 if superseded.contains(&group[i]) {
                     continue;
@@ -286,45 +417,15 @@ impl super::query::QueryEngine {
                         continue;
                     }
 
-                    // Borrow two separate snapshots so we don't alias &graph.
-                    let (vec_a, conf_a, content_a) = match graph.get_node(group[i]) {
-                        Some(n) => (n.feature_vec.clone(), n.confidence, n.content.clone()),
-                        None => continue,
-                    };
-                    let (vec_b, conf_b, content_b) = match graph.get_node(group[j]) {
-                        Some(n) => (n.feature_vec.clone(), n.confidence, n.content.clone()),
-                        None => continue,
-                    };
-
-                    let sim = cosine_similarity(&vec_a, &vec_b);
-                    if sim < threshold {
+                    let Some((sim, jaccard)) =
+                        fact_pair_similarity(graph, &tokenizer, group[i], group[j], threshold)
+                    else {
                         continue;
-                    }
-
-                    // Also require high token-level overlap.
-                    let tokens_a: HashSet<String> =
-                        tokenizer.tokenize(&content_a).into_iter().collect();
-                    let tokens_b: HashSet<String> =
-                        tokenizer.tokenize(&content_b).into_iter().collect();
-
-                    if tokens_a.is_empty() && tokens_b.is_empty() {
-                        continue;
-                    }
-
-                    let intersection = tokens_a.intersection(&tokens_b).count();
-                    let union = tokens_a.union(&tokens_b).count();
-                    let jaccard = if union > 0 {
-                        intersection as f32 / union as f32
-                    } else {
-                        0.0
                     };
 
-                    if jaccard < 0.5 {
-                        continue;
-                    }
-
                     // Determine winner (higher confidence survives).
-                    let (winner, loser) = if conf_a >= conf_b {
+                    let conf = |id: u64| graph.get_node(id).map(|n| n.confidence).unwrap_or(0.0);
+                    let (winner, loser) = if conf(group[i]) >= conf(group[j]) {
                         (group[i], group[j])
                     } else {
                         (group[j], group[i])
@@ -359,6 +460,394 @@ impl super::query::QueryEngine {
         }
     }
 
+    /// List every Fact pair whose cosine similarity reaches `floor` and whose
+    /// token overlap passes the same gate as `DeduplicateFacts`, most similar
+    /// first. Unlike consolidation, no pair is resolved, so a node can appear
+    /// in several candidates. The graph is not mutated.
+    pub fn duplicate_candidates(
+        &self,
+        graph: &MemoryGraph,
+        floor: f32,
+        session_range: Option<(u32, u32)>,
+    ) -> Vec<DuplicateCandidate> {
+        let tokenizer = Tokenizer::new();
+        let mut seen: HashSet<(u64, u64)> = HashSet::new();
+        let mut candidates = Vec::new();
+
+        for group in &fact_groups(graph, session_range) {
+            for i in 0..group.len() {
+                for j in (i + 1)..group.len() {
+                    let (first, second) = ordered_pair(group[i], group[j]);
+                    if !seen.insert((first, second)) {
+                        continue;
+                    }
+                    if let Some((cosine, jaccard)) =
+                        fact_pair_similarity(graph, &tokenizer, first, second, floor)
+                    {
+                        candidates.push(DuplicateCandidate {
+                            first,
+                            second,
+                            cosine,
+                            jaccard,
+                        });
+                    }
+                }
+            }
+        }
+
+        candidates.sort_by(|a, b| {
+            b.cosine
+                .total_cmp(&a.cosine)
+                .then(b.jaccard.total_cmp(&a.jaccard))
+                .then((a.first, a.second).cmp(&(b.first, b.second)))
+        });
+        candidates
+    }
+
+    /// Apply reviewed duplicate decisions as one unit.
+    ///
+    /// Each merge adds a `Supersedes` edge from the surviving node to the
+    /// other, weighted by cosine similarity; `KeepBoth` and `Skip` change
+    /// nothing. Every merge is validated before any edge is written, so on
+    /// error the graph is left untouched. Returns the number of merges.
+    pub fn apply_dedup_decisions(
+        &self,
+        graph: &mut MemoryGraph,
+        decisions: &[(DuplicateCandidate, DedupDecision)],
+    ) -> AmemResult<usize> {
+        let merges: Vec<Edge> = decisions
+            .iter()
+            .filter_map(|(candidate, decision)| {
+                let (winner, loser) = match decision {
+                    DedupDecision::MergeIntoFirst => (candidate.first, candidate.second),
+                    DedupDecision::MergeIntoSecond => (candidate.second, candidate.first),
+                    DedupDecision::KeepBoth | DedupDecision::Skip => return None,
+                };
+                Some(Edge {
+                    source_id: winner,
+                    target_id: loser,
+                    edge_type: EdgeType::Supersedes,
+                    weight: candidate.cosine.clamp(0.0, 1.0),
+                    created_at: crate::types::now_micros(),
+                })
+            })
+            .collect();
+
+        let mut added_per_source: HashMap<u64, usize> = HashMap::new();
+        for edge in &merges {
+            if edge.source_id == edge.target_id {
+                return Err(AmemError::SelfEdge(edge.source_id));
+            }
+            if graph.get_node(edge.source_id).is_none() {
+                return Err(AmemError::NodeNotFound(edge.source_id));
+            }
+            if graph.get_node(edge.target_id).is_none() {
+                return Err(AmemError::NodeNotFound(edge.target_id));
+            }
+            let added = added_per_source.entry(edge.source_id).or_insert(0);
+            *added += 1;
+            if graph.edges_from(edge.source_id).len() + *added > MAX_EDGES_PER_NODE as usize {
+                return Err(AmemError::TooManyEdges(MAX_EDGES_PER_NODE));
+            }
+        }
+
+        for edge in &merges {
+            graph.add_edge(*edge)?;
+        }
+        Ok(merges.len())
+    }
+
+    /// Propose `Supports` edges from Fact nodes to the Inference nodes that
+    /// appear to be drawn from them.
+    ///
+    /// For each Inference, candidates are the Facts sharing at least one term
+    /// with it (looked up in the term index, built on the fly if the graph has
+    /// none) that were created earlier in the same session and do not already
+    /// support it. Candidates scoring at least `min_score` by token Jaccard are
+    /// kept, best first, at most `max_per_inference` per Inference. Proposals
+    /// are ordered by Inference ID, then score. The graph is not mutated.
+    pub fn propose_inference_support(
+        &self,
+        graph: &MemoryGraph,
+        min_score: f32,
+        max_per_inference: usize,
+    ) -> Vec<SupportProposal> {
+        let tokenizer = Tokenizer::new();
+        let built;
+        let term_index = match graph.term_index() {
+            Some(index) => index,
+            None => {
+                built = TermIndex::build(graph, &tokenizer);
+                &built
+            }
+        };
+        let tokens = |id: u64, content: &str| -> HashSet<String> {
+            tokenizer
+                .tokenize_as(content, graph.node_language(id))
+                .into_iter()
+                .collect()
+        };
+
+        let mut proposals = Vec::new();
+        for inference in graph.nodes() {
+            if inference.event_type != EventType::Inference {
+                continue;
+            }
+            let supported: HashSet<u64> = graph
+                .edges_to(inference.id)
+                .iter()
+                .filter(|e| e.edge_type == EdgeType::Supports)
+                .map(|e| e.source_id)
+                .collect();
+            let inference_tokens = tokens(inference.id, &inference.content);
+
+            let mut candidates: HashSet<u64> = HashSet::new();
+            for term in &inference_tokens {
+                for &(id, _) in term_index.get(term) {
+                    candidates.insert(id);
+                }
+            }
+
+            let mut scored: Vec<SupportProposal> = candidates
+                .into_iter()
+                .filter(|id| !supported.contains(id))
+                .filter_map(|id| graph.get_node(id))
+                .filter(|fact| {
+                    fact.event_type == EventType::Fact
+                        && fact.session_id == inference.session_id
+                        && fact.created_at < inference.created_at
+                })
+                .map(|fact| SupportProposal {
+                    fact: fact.id,
+                    inference: inference.id,
+                    score: token_jaccard(&inference_tokens, &tokens(fact.id, &fact.content)),
+                })
+                .filter(|p| p.score >= min_score)
+                .collect();
+            scored.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.fact.cmp(&b.fact)));
+            scored.truncate(max_per_inference);
+            proposals.extend(scored);
+        }
+        proposals
+    }
+
+    /// Add a `Supports` edge, weighted by score, for each proposal as one unit.
+    ///
+    /// Every edge is validated before any is written, so on error the graph
+    /// is left untouched. Returns the number of edges added.
+    pub fn apply_support_proposals(
+        &self,
+        graph: &mut MemoryGraph,
+        proposals: &[SupportProposal],
+    ) -> AmemResult<usize> {
+        let edges: Vec<Edge> = proposals
+            .iter()
+            .map(|p| Edge {
+                source_id: p.fact,
+                target_id: p.inference,
+                edge_type: EdgeType::Supports,
+                weight: p.score.clamp(0.0, 1.0),
+                created_at: crate::types::now_micros(),
+            })
+            .collect();
+
+        let mut added_per_source: HashMap<u64, usize> = HashMap::new();
+        for edge in &edges {
+            if edge.source_id == edge.target_id {
+                return Err(AmemError::SelfEdge(edge.source_id));
+            }
+            if graph.get_node(edge.source_id).is_none() {
+                return Err(AmemError::NodeNotFound(edge.source_id));
+            }
+            if graph.get_node(edge.target_id).is_none() {
+                return Err(AmemError::NodeNotFound(edge.target_id));
+            }
+            let added = added_per_source.entry(edge.source_id).or_insert(0);
+            *added += 1;
+            if graph.edges_from(edge.source_id).len() + *added > MAX_EDGES_PER_NODE as usize {
+                return Err(AmemError::TooManyEdges(MAX_EDGES_PER_NODE));
+            }
+        }
+
+        for edge in &edges {
+            graph.add_edge(*edge)?;
+        }
+        Ok(edges.len())
+    }
+
+    /// Remove every node that was neither created nor accessed at or after
+    /// `params.cutoff`, skipping pinned nodes and the protected types. Edges
+    /// touching a removed node go with it, and each removal leaves a
+    /// tombstone.
+    pub fn compact_older_than(
+        &self,
+        graph: &mut MemoryGraph,
+        params: &CompactionParams,
+    ) -> AmemResult<CompactionReport> {
+        let removable: Vec<EventType> = graph
+            .type_index()
+            .iter()
+            .map(|(t, _)| t)
+            .filter(|t| !params.keep_types.contains(t))
+            .collect();
+        // An empty type list would mean every node, so guard it here.
+        let candidates = if removable.is_empty() {
+            Vec::new()
+        } else {
+            graph.nodes_of_types(&removable)
+        };
+        let mut removed: Vec<u64> = candidates
+            .into_iter()
+            .filter(|n| {
+                !n.pinned && n.created_at < params.cutoff && n.last_accessed < params.cutoff
+            })
+            .map(|n| n.id)
+            .collect();
+        removed.sort_unstable();
+
+        let mut counts: HashMap<EventType, usize> = HashMap::new();
+        for &id in &removed {
+            let node = graph.remove_node(id)?;
+            *counts.entry(node.event_type).or_insert(0) += 1;
+        }
+        let mut removed_by_type: Vec<(EventType, usize)> = counts.into_iter().collect();
+        removed_by_type.sort_by_key(|(t, _)| *t as u8);
+
+        Ok(CompactionReport {
+            removed,
+            removed_by_type,
+        })
+    }
+
+    /// Delete every node matching `filter`, with all edges touching it. Each
+    /// removal leaves a tombstone.
+    pub fn delete_where(
+        &self,
+        graph: &mut MemoryGraph,
+        filter: &DeleteFilter,
+    ) -> AmemResult<DeleteReport> {
+        let mut deleted_ids: Vec<u64> = graph
+            .nodes()
+            .iter()
+            .filter(|n| filter.matches(n))
+            .map(|n| n.id)
+            .collect();
+        deleted_ids.sort_unstable();
+        let deleted: HashSet<u64> = deleted_ids.iter().copied().collect();
+        let edges_removed = graph
+            .edges()
+            .iter()
+            .filter(|e| deleted.contains(&e.source_id) || deleted.contains(&e.target_id))
+            .count();
+
+        for &id in &deleted_ids {
+            graph.remove_node(id)?;
+        }
+        Ok(DeleteReport {
+            deleted_count: deleted_ids.len(),
+            deleted_ids,
+            edges_removed,
+        })
+    }
+
+    /// Keep the `params.keep` most important nodes and remove the rest,
+    /// scoring each with [`importance_score`]. Pinned nodes (by flag or in
+    /// `params.pinned`) and protected types always survive and take up slots
+    /// first. Edges touching a
+    /// removed node go with it unless `rewire_to_episode` redirects them, and
+    /// each removal leaves a tombstone.
+    pub fn compact_keep_top(
+        &self,
+        graph: &mut MemoryGraph,
+        params: &KeepTopParams,
+    ) -> AmemResult<KeepTopReport> {
+        let pinned_ids: HashSet<u64> = params.pinned.iter().copied().collect();
+        let mut degree: HashMap<u64, usize> = HashMap::new();
+        for edge in graph.edges() {
+            *degree.entry(edge.source_id).or_insert(0) += 1;
+            *degree.entry(edge.target_id).or_insert(0) += 1;
+        }
+
+        let (pinned, mut ranked): (Vec<_>, Vec<_>) = graph
+            .nodes()
+            .iter()
+            .map(|n| {
+                let score =
+                    importance_score(n, degree.get(&n.id).copied().unwrap_or(0), params.now);
+                (n.id, score, n.event_type, n.pinned)
+            })
+            .partition(|(id, _, event_type, flagged)| {
+                *flagged || pinned_ids.contains(id) || params.keep_types.contains(event_type)
+            });
+        // Highest score first; newer nodes win ties.
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then(b.0.cmp(&a.0)));
+        let slots = params.keep.saturating_sub(pinned.len()).min(ranked.len());
+        let cutoff_score = (slots < ranked.len() && slots > 0).then(|| ranked[slots - 1].1);
+
+        let mut removed: Vec<u64> = ranked[slots..].iter().map(|(id, ..)| *id).collect();
+        removed.sort_unstable();
+        let removed_set: HashSet<u64> = removed.iter().copied().collect();
+
+        // Incoming edges from survivors, to redirect once the targets are gone.
+        let mut rewire: Vec<Edge> = Vec::new();
+        if params.rewire_to_episode {
+            let mut episode_of: HashMap<u32, Option<u64>> = HashMap::new();
+            for edge in graph.edges() {
+                if !removed_set.contains(&edge.target_id) || removed_set.contains(&edge.source_id) {
+                    continue;
+                }
+                let Some(session_id) = graph.get_node(edge.target_id).map(|n| n.session_id) else {
+                    continue;
+                };
+                let episode = *episode_of.entry(session_id).or_insert_with(|| {
+                    graph
+                        .session_index()
+                        .get_session(session_id)
+                        .iter()
+                        .filter(|id| !removed_set.contains(id))
+                        .filter_map(|&id| graph.get_node(id))
+                        .filter(|n| n.event_type == EventType::Episode)
+                        .max_by_key(|n| (n.created_at, n.id))
+                        .map(|n| n.id)
+                });
+                if let Some(episode) = episode.filter(|&e| e != edge.source_id) {
+                    rewire.push(Edge {
+                        target_id: episode,
+                        ..*edge
+                    });
+                }
+            }
+        }
+
+        let mut counts: HashMap<EventType, usize> = HashMap::new();
+        for &id in &removed {
+            let node = graph.remove_node(id)?;
+            *counts.entry(node.event_type).or_insert(0) += 1;
+        }
+        let mut removed_by_type: Vec<(EventType, usize)> = counts.into_iter().collect();
+        removed_by_type.sort_by_key(|(t, _)| *t as u8);
+
+        let mut rewired_edges = 0;
+        for edge in rewire {
+            let exists = graph
+                .edges_from(edge.source_id)
+                .iter()
+                .any(|e| e.target_id == edge.target_id && e.edge_type == edge.edge_type);
+            if !exists && graph.edges_from(edge.source_id).len() < MAX_EDGES_PER_NODE as usize {
+                graph.add_edge(edge)?;
+                rewired_edges += 1;
+            }
+        }
+
+        Ok(KeepTopReport {
+            removed,
+            removed_by_type,
+            cutoff_score,
+            pinned_kept: pinned.len(),
+            rewired_edges,
+        })
+    }
+
     // -----------------------------------------------------------------------
     // PruneOrphans (dry-run only in V1)
     // -----------------------------------------------------------------------
@@ -595,9 +1084,7 @@ impl super::query::QueryEngine {
             report.inferences_promoted += 1;
 
             if !dry_run {
-                if let Some(node) = graph.get_node_mut(id) {
-                    node.event_type = EventType::Fact;
-                }
+                let _ = graph.set_event_type(id, EventType::Fact);
             }
         }
     }
@@ -615,6 +1102,73 @@ fn in_session_range(session_id: u32, range: Option<(u32, u32)>) -> bool {
     }
 }
 
+/// Fact node IDs grouped by cluster, so dedup only compares within-cluster
+/// pairs. Falls back to a single group of all facts when there are no
+/// clusters.
+fn fact_groups(graph: &MemoryGraph, session_filter: Option<(u32, u32)>) -> Vec<Vec<u64>> {
+    // Collect Fact node IDs, respecting the session filter.
+    let fact_ids: Vec<u64> = graph
+        .nodes()
+        .iter()
+        .filter(|n| {
+            n.event_type == EventType::Fact && in_session_range(n.session_id, session_filter)
+        })
+        .map(|n| n.id)
+        .collect();
+    let fact_set: HashSet<u64> = fact_ids.iter().copied().collect();
+
+    // Build cluster -> [fact ids in that cluster].
+    let mut cluster_groups: Vec<Vec<u64>> = Vec::new();
+    for ci in 0..graph.cluster_map().cluster_count() {
+        let members: Vec<u64> = graph
+            .cluster_map()
+            .get_cluster(ci)
+            .iter()
+            .copied()
+            .filter(|id| fact_set.contains(id))
+            .collect();
+        if members.len() >= 2 {
+            cluster_groups.push(members);
+        }
+    }
+
+    if cluster_groups.is_empty() && fact_ids.len() >= 2 {
+        cluster_groups.push(fact_ids);
+    }
+    cluster_groups
+}
+
+/// `(cosine, jaccard)` for two facts when the cosine similarity reaches
+/// `threshold` and the token overlap reaches [`DEDUP_MIN_JACCARD`].
+fn fact_pair_similarity(
+    graph: &MemoryGraph,
+    tokenizer: &Tokenizer,
+    a: u64,
+    b: u64,
+    threshold: f32,
+) -> Option<(f32, f32)> {
+    let node_a = graph.get_node(a)?;
+    let node_b = graph.get_node(b)?;
+
+    let sim = cosine_similarity(&node_a.feature_vec, &node_b.feature_vec);
+    if sim < threshold {
+        return None;
+    }
+
+    // Also require high token-level overlap.
+    let tokens_a: HashSet<String> = tokenizer.tokenize(&node_a.content).into_iter().collect();
+    let tokens_b: HashSet<String> = tokenizer.tokenize(&node_b.content).into_iter().collect();
+    if tokens_a.is_empty() && tokens_b.is_empty() {
+        return None;
+    }
+
+    let jaccard = token_jaccard(&tokens_a, &tokens_b);
+    if jaccard < DEDUP_MIN_JACCARD {
+        return None;
+    }
+    Some((sim, jaccard))
+}
+
 /// Return the pair `(min, max)` so we can use it as a canonical key.
 fn ordered_pair(a: u64, b: u64) -> (u64, u64) {
     if a <= b {
@@ -623,3 +1177,97 @@ fn ordered_pair(a: u64, b: u64) -> (u64, u64) {
         (b, a)
     }
 }
+
+// ---------------------------------------------------------------------------
+// Near-duplicate lookup
+// ---------------------------------------------------------------------------
+
+/// Jaccard overlap of two token sets (0.0 when both are empty).
+pub(crate) fn token_jaccard(a: &HashSet<String>, b: &HashSet<String>) -> f32 {
+    let intersection = a.intersection(b).count();
+    let union = a.len() + b.len() - intersection;
+    if union > 0 {
+        intersection as f32 / union as f32
+    } else {
+        0.0
+    }
+}
+
+/// Finds existing nodes whose content nearly duplicates new content.
+///
+/// Content matches when the event types agree and either the trimmed,
+/// lowercased text is identical or the token Jaccard overlap reaches the
+/// threshold. Candidates are drawn from an inverted token index, so only
+/// nodes sharing at least one token are compared.
+pub struct DuplicateIndex {
+    tokenizer: Tokenizer,
+    threshold: f32,
+    exact: HashMap<(EventType, String), u64>,
+    entries: Vec<(u64, EventType, HashSet<String>)>,
+    postings: HashMap<String, Vec<usize>>,
+}
+
+impl DuplicateIndex {
+    /// Create an empty index with the given Jaccard threshold.
+    pub fn new(threshold: f32) -> Self {
+        Self {
+            tokenizer: Tokenizer::new(),
+            threshold: threshold.clamp(0.0, 1.0),
+            exact: HashMap::new(),
+            entries: Vec::new(),
+            postings: HashMap::new(),
+        }
+    }
+
+    /// Index every node in the graph.
+    pub fn build(graph: &MemoryGraph, threshold: f32) -> Self {
+        let mut index = Self::new(threshold);
+        for node in graph.nodes() {
+            index.insert(node.id, node.event_type, &node.content);
+        }
+        index
+    }
+
+    /// Add a node to the index.
+    pub fn insert(&mut self, id: u64, event_type: EventType, content: &str) {
+        self.exact
+            .entry((event_type, normalize(content)))
+            .or_insert(id);
+
+        let tokens: HashSet<String> = self.tokenizer.tokenize(content).into_iter().collect();
+        let slot = self.entries.len();
+        for token in &tokens {
+            self.postings.entry(token.clone()).or_default().push(slot);
+        }
+        self.entries.push((id, event_type, tokens));
+    }
+
+    /// Best-matching existing node and its similarity, if any reaches the threshold.
+    pub fn find(&self, event_type: EventType, content: &str) -> Option<(u64, f32)> {
+        if let Some(&id) = self.exact.get(&(event_type, normalize(content))) {
+            return Some((id, 1.0));
+        }
+
+        let tokens: HashSet<String> = self.tokenizer.tokenize(content).into_iter().collect();
+        let mut candidates: HashSet<usize> = HashSet::new();
+        for token in &tokens {
+            if let Some(slots) = self.postings.get(token) {
+                candidates.extend(slots.iter().copied());
+            }
+        }
+
+        candidates
+            .into_iter()
+            .filter(|&slot| self.entries[slot].1 == event_type)
+            .map(|slot| {
+                let (id, _, other) = &self.entries[slot];
+                (*id, token_jaccard(&tokens, other))
+            })
+            .filter(|&(_, sim)| sim >= self.threshold)
+            .max_by(|a, b| a.1.total_cmp(&b.1).then(b.0.cmp(&a.0)))
+    }
+}
+
+fn normalize(content: &str) -> String {
+    content.trim().to_lowercase()
+}
@@ -2,19 +2,36 @@
 
 pub mod cognitive;
 pub mod decay;
+pub mod diff;
 pub mod graph_algo;
 pub mod maintenance;
+pub mod normalize;
+pub mod profile;
 pub mod query;
+pub mod redact;
+pub mod sampling;
 pub mod text_search;
 pub mod tokenizer;
 pub mod write;
 
+pub use decay::{DecayFn, DecayFunction};
+pub use diff::{diff_graphs, DiffField, FieldChange, FieldValue, GraphDiff, GraphPatch, PatchEdge};
+pub use normalize::ContentNormalizer;
+pub use profile::{PhaseTimer, ProfilePhase, QueryProfile};
 pub use query::{
-    CausalParams, CausalResult, MemoryQualityParams, MemoryQualityReport, PatternParams,
-    PatternSort, QueryEngine, SimilarityMatchResult, SimilarityParams, SubGraph, TemporalParams,
-    TemporalResult, TimeRange, TraversalParams, TraversalResult,
+    CausalDirection, CausalParams, CausalResult, ConfidenceDecayQuadrants, CrossSessionView,
+    EdgeExpansion, EpisodeMember, ExpansionSource, MemoryQualityParams, MemoryQualityReport,
+    PatternParams, PatternSort, QueryEngine, SessionActivity, SessionGroup, SimilarityMatchResult,
+    SimilarityParams, SubGraph, TemporalCluster, TemporalClusterParams, TemporalParams,
+    TemporalResult, TimeRange, TraversalParams, TraversalResult, DEFAULT_ACTIVE_WINDOW_SECS,
+    DEFAULT_EPISODE_MEMBER_LIMIT,
+};
+pub use redact::{IdAnonymizer, RedactionCategory, RedactionCounts, Redactor};
+pub use sampling::NodeSample;
+pub use write::{
+    BatchIngestResult, DecayReport, EdgeMergePolicy, IngestResult, MergeReport, MergeStrategy,
+    WriteEngine,
 };
-pub use write::{DecayReport, IngestResult, WriteEngine};
 
 // New query expansion types
 pub use cognitive::{
@@ -24,10 +41,17 @@ pub use cognitive::{
     PatternMatch, RevisionReport, WeakenedNode,
 };
 pub use graph_algo::{
-    CentralityAlgorithm, CentralityParams, CentralityResult, PathResult, ShortestPathParams,
+    CentralityAlgorithm, CentralityCore, CentralityCoreParams, CentralityParams, CentralityResult,
+    PathResult, ShortestPathParams,
 };
 pub use maintenance::{
-    ConsolidationAction, ConsolidationOp, ConsolidationParams, ConsolidationReport,
+    CompactionParams, CompactionReport, ConsolidationAction, ConsolidationOp, ConsolidationParams,
+    ConsolidationReport, DedupDecision, DeleteFilter, DeleteReport, DuplicateCandidate,
+    DuplicateIndex, KeepTopParams, KeepTopReport, SupportProposal,
+};
+pub use text_search::{
+    bm25_idf, jaro_winkler, query_language, FuzzySearchParams, HybridMatch, HybridSearchParams,
+    RegexSearchParams, RelevanceFeedbackParams, RelevanceFeedbackResult, TextMatch,
+    TextSearchParams, WeightedTerm, BM25_B, BM25_K1,
 };
-pub use text_search::{HybridMatch, HybridSearchParams, TextMatch, TextSearchParams};
-pub use tokenizer::Tokenizer;
+pub use tokenizer::{detect_language, Tokenizer};
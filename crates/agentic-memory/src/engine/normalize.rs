@@ -0,0 +1,89 @@
+//! Content normalization applied before storage.
+//!
+//! Normalization replaces the content that is stored: the raw text is not
+//! kept alongside it. Every step only rewrites whitespace or the Unicode
+//! encoding of the same characters, so the stored text reads the same as
+//! what was submitted while hashing, deduplication and search see one
+//! canonical form.
+
+#[cfg(feature = "nfc")]
+use unicode_normalization::UnicodeNormalization;
+
+/// Which normalization steps to apply to node content. All steps are off by
+/// default. Applying a normalizer twice gives the same result as applying it
+/// once.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ContentNormalizer {
+    /// Replace each run of whitespace with a single space.
+    pub collapse_whitespace: bool,
+    /// Strip leading and trailing whitespace, as V3's `normalize_content` does.
+    pub trim: bool,
+    /// Convert to Unicode Normalization Form C. Needs the `nfc` feature and
+    /// is ignored without it.
+    pub unicode_nfc: bool,
+}
+
+impl ContentNormalizer {
+    /// A normalizer that changes nothing.
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    /// Collapse whitespace, trim, and NFC-normalize (where supported).
+    pub fn standard() -> Self {
+        Self {
+            collapse_whitespace: true,
+            trim: true,
+            unicode_nfc: true,
+        }
+    }
+
+    /// Whether any step is enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.collapse_whitespace || self.trim || self.unicode_nfc
+    }
+
+    /// Normalize `content`.
+    pub fn normalize(&self, content: &str) -> String {
+        let mut out = self.nfc(content);
+        if self.collapse_whitespace {
+            out = collapse_whitespace(&out);
+        }
+        if self.trim {
+            out = out.trim().to_string();
+        }
+        out
+    }
+
+    #[cfg(feature = "nfc")]
+    fn nfc(&self, content: &str) -> String {
+        if self.unicode_nfc {
+            content.nfc().collect()
+        } else {
+            content.to_string()
+        }
+    }
+
+    #[cfg(not(feature = "nfc"))]
+    fn nfc(&self, content: &str) -> String {
+        content.to_string()
+    }
+}
+
+/// Replace each run of whitespace characters with one ASCII space.
+fn collapse_whitespace(content: &str) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut in_space = false;
+    for c in content.chars() {
+        if c.is_whitespace() {
+            if !in_space {
+                out.push(' ');
+            }
+            in_space = true;
+        } else {
+            out.push(c);
+            in_space = false;
+        }
+    }
+    out
+}
@@ -0,0 +1,77 @@
+//! Per-phase timing for query profiling.
+//!
+//! Engines take an `Option<&mut QueryProfile>`; with `None` the probes never
+//! read the clock, so unprofiled queries pay nothing.
+
+use std::time::{Duration, Instant};
+
+/// Time spent in one phase of a query.
+#[derive(Debug, Clone, Copy)]
+pub struct ProfilePhase {
+    /// Phase name, e.g. `"index lookup"` or `"sort"`.
+    pub name: &'static str,
+    /// Wall-clock time spent in the phase.
+    pub elapsed: Duration,
+}
+
+/// Phase-by-phase timing of a single query, in execution order.
+#[derive(Debug, Clone, Default)]
+pub struct QueryProfile {
+    phases: Vec<ProfilePhase>,
+}
+
+impl QueryProfile {
+    /// Create an empty profile.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add time to `name`, merging with an existing phase of that name.
+    pub fn record(&mut self, name: &'static str, elapsed: Duration) {
+        match self.phases.iter_mut().find(|p| p.name == name) {
+            Some(phase) => phase.elapsed += elapsed,
+            None => self.phases.push(ProfilePhase { name, elapsed }),
+        }
+    }
+
+    /// Recorded phases, in the order they first ran.
+    pub fn phases(&self) -> &[ProfilePhase] {
+        &self.phases
+    }
+
+    /// Time spent in `name`, if recorded.
+    pub fn phase(&self, name: &str) -> Option<Duration> {
+        self.phases
+            .iter()
+            .find(|p| p.name == name)
+            .map(|p| p.elapsed)
+    }
+
+    /// Total time across all phases.
+    pub fn total(&self) -> Duration {
+        self.phases.iter().map(|p| p.elapsed).sum()
+    }
+}
+
+/// Lap timer feeding a [`QueryProfile`]. Inert when created without one.
+pub struct PhaseTimer<'a> {
+    profile: Option<&'a mut QueryProfile>,
+    last: Option<Instant>,
+}
+
+impl<'a> PhaseTimer<'a> {
+    /// Start timing the first phase.
+    pub fn start(profile: Option<&'a mut QueryProfile>) -> Self {
+        let last = profile.as_ref().map(|_| Instant::now());
+        Self { profile, last }
+    }
+
+    /// Close the current phase as `name` and start the next one.
+    pub fn lap(&mut self, name: &'static str) {
+        if let (Some(profile), Some(last)) = (self.profile.as_deref_mut(), self.last.as_mut()) {
+            let now = Instant::now();
+            profile.record(name, now - *last);
+            *last = now;
+        }
+    }
+}
@@ -1,7 +1,10 @@
 //! Query executor — all query types.
 
 use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::Duration;
 
+use super::profile::{PhaseTimer, QueryProfile};
+use super::sampling::NodeSample;
 use crate::graph::traversal::{bfs_traverse, TraversalDirection};
 use crate::graph::MemoryGraph;
 use crate::index::cosine_similarity;
@@ -21,6 +24,9 @@ pub struct TraversalParams {
     pub max_results: usize,
     /// Minimum confidence threshold for visited nodes.
     pub min_confidence: f32,
+    /// Follow symmetric edge types (`RelatedTo`, `Contradicts`) in both
+    /// directions regardless of `direction`.
+    pub symmetric_undirected: bool,
 }
 
 /// Result of a traversal query.
@@ -46,7 +52,30 @@ pub enum PatternSort {
     MostImportant,
 }
 
+impl PatternSort {
+    /// Name of the node field this order sorts by.
+    pub fn key_name(self) -> &'static str {
+        match self {
+            Self::MostRecent => "created_at",
+            Self::HighestConfidence => "confidence",
+            Self::MostAccessed => "access_count",
+            Self::MostImportant => "decay_score",
+        }
+    }
+
+    /// Value of that field for `node`.
+    pub fn key_value(self, node: &CognitiveEvent) -> f64 {
+        match self {
+            Self::MostRecent => node.created_at as f64,
+            Self::HighestConfidence => node.confidence as f64,
+            Self::MostAccessed => node.access_count as f64,
+            Self::MostImportant => node.decay_score as f64,
+        }
+    }
+}
+
 /// Parameters for a pattern query.
+#[derive(Clone)]
 pub struct PatternParams {
     /// Filter by event type(s). Empty = all types.
     pub event_types: Vec<EventType>,
@@ -62,12 +91,82 @@ pub struct PatternParams {
     pub created_before: Option<u64>,
     /// Filter by minimum decay score.
     pub min_decay_score: Option<f32>,
+    /// Keep only nodes carrying every one of these tags with the same
+    /// value. Empty = no tag filter.
+    pub required_tags: HashMap<String, String>,
+    /// Keep only nodes carrying at least one of these `(key, value)` tags.
+    /// Empty = no tag filter. Applies alongside `required_tags`.
+    pub any_tags: Vec<(String, String)>,
     /// Maximum number of results.
     pub max_results: usize,
     /// Sort order.
     pub sort_by: PatternSort,
 }
 
+impl PatternParams {
+    /// One line per filter these params set, describing how `node` meets
+    /// it, e.g. `confidence 0.92 >= 0.80`. Unset filters are left out, so
+    /// an unfiltered query explains nothing beyond its sort key.
+    pub fn matched_predicates(&self, node: &CognitiveEvent) -> Vec<String> {
+        let mut predicates = Vec::new();
+        if !self.event_types.is_empty() {
+            let names: Vec<&str> = self.event_types.iter().map(|t| t.name()).collect();
+            predicates.push(format!(
+                "type {} in [{}]",
+                node.event_type.name(),
+                names.join(", ")
+            ));
+        }
+        if !self.session_ids.is_empty() {
+            let ids: Vec<String> = self.session_ids.iter().map(|s| s.to_string()).collect();
+            predicates.push(format!(
+                "session {} in [{}]",
+                node.session_id,
+                ids.join(", ")
+            ));
+        }
+        if let Some(min) = self.min_confidence {
+            predicates.push(format!("confidence {:.2} >= {:.2}", node.confidence, min));
+        }
+        if let Some(max) = self.max_confidence {
+            predicates.push(format!("confidence {:.2} <= {:.2}", node.confidence, max));
+        }
+        if let Some(after) = self.created_after {
+            predicates.push(format!("created_at {} >= {}", node.created_at, after));
+        }
+        if let Some(before) = self.created_before {
+            predicates.push(format!("created_at {} <= {}", node.created_at, before));
+        }
+        if let Some(min) = self.min_decay_score {
+            predicates.push(format!("decay_score {:.2} >= {:.2}", node.decay_score, min));
+        }
+        let mut tags: Vec<(&String, &String)> = self.required_tags.iter().collect();
+        tags.sort_unstable();
+        for (key, value) in tags {
+            predicates.push(format!("tag {key}={value}"));
+        }
+        if !self.any_tags.is_empty() {
+            let matched: Vec<String> = self
+                .any_tags
+                .iter()
+                .filter(|(k, v)| node.tags.get(k) == Some(v))
+                .map(|(k, v)| format!("{k}={v}"))
+                .collect();
+            let wanted: Vec<String> = self
+                .any_tags
+                .iter()
+                .map(|(k, v)| format!("{k}={v}"))
+                .collect();
+            predicates.push(format!(
+                "tags [{}] match any of [{}]",
+                matched.join(", "),
+                wanted.join(", ")
+            ));
+        }
+        predicates
+    }
+}
+
 /// Time range for temporal queries.
 pub enum TimeRange {
     /// All nodes created in this timestamp range.
@@ -84,6 +183,8 @@ pub struct TemporalParams {
     pub range_a: TimeRange,
     /// Second time range.
     pub range_b: TimeRange,
+    /// Also group the nodes of both ranges by session.
+    pub group_by_session: bool,
 }
 
 /// Result of a temporal query.
@@ -96,6 +197,87 @@ pub struct TemporalResult {
     pub unchanged: Vec<u64>,
     /// Nodes only in range_a that have low decay scores (potentially stale).
     pub potentially_stale: Vec<u64>,
+    /// Sessions touched by either range, in timeline order
+    /// (only when `group_by_session` is set).
+    pub session_groups: Option<Vec<SessionGroup>>,
+}
+
+/// Summary of one session within a temporal query.
+///
+/// A session is included when any of its nodes falls in either range; the
+/// summary then describes the whole session, and `partial` records that some
+/// of its nodes lie outside the ranges.
+pub struct SessionGroup {
+    /// Session ID.
+    pub session_id: u32,
+    /// Session nodes that fall in either range, in creation order.
+    pub in_range: Vec<u64>,
+    /// Total nodes in the session.
+    pub node_count: usize,
+    /// Most frequent event type in the session (ties go to the lower type).
+    pub dominant_type: EventType,
+    /// Creation time of the session's first node.
+    pub started_at: u64,
+    /// Creation time of the session's last node.
+    pub ended_at: u64,
+    /// Whether some of the session's nodes fall outside both ranges.
+    pub partial: bool,
+}
+
+/// Parameters for [`QueryEngine::temporal_clusters`].
+pub struct TemporalClusterParams {
+    /// Width of each cluster's time window. A zero window is treated as one
+    /// microsecond.
+    pub window: Duration,
+    /// Filter by event type(s). Empty = all types.
+    pub event_types: Vec<EventType>,
+    /// Filter by session ID(s). Empty = all sessions.
+    pub session_ids: Vec<u32>,
+}
+
+/// Nodes created within one time window.
+pub struct TemporalCluster {
+    /// Start of the window (Unix epoch microseconds, inclusive).
+    pub start_micros: u64,
+    /// End of the window (Unix epoch microseconds, exclusive).
+    pub end_micros: u64,
+    /// Nodes created in the window, in creation order.
+    pub node_ids: Vec<u64>,
+    /// Most frequent event type in the window (ties go to the lower type).
+    pub dominant_type: EventType,
+    /// Mean confidence of the window's nodes.
+    pub mean_confidence: f32,
+}
+
+/// Default recency window for [`QueryEngine::session_activity`]: a session
+/// whose newest node is younger than this may still be running.
+pub const DEFAULT_ACTIVE_WINDOW_SECS: u64 = 30 * 60;
+
+/// Activity of one session, as classified by [`QueryEngine::session_activity`].
+pub struct SessionActivity {
+    /// Session ID.
+    pub session_id: u32,
+    /// Total nodes in the session.
+    pub node_count: usize,
+    /// Creation time of the session's newest node.
+    pub last_activity: u64,
+    /// Whether the newest node is an Episode, i.e. the session was compressed
+    /// after its last event.
+    pub closed: bool,
+    /// Likely in progress: the newest node falls within the recency window
+    /// and no Episode closes the session.
+    pub active: bool,
+}
+
+/// The edges that cross session boundaries, with their endpoints.
+pub struct CrossSessionView {
+    /// Endpoints of the cross-session edges, in ID order.
+    pub nodes: Vec<CognitiveEvent>,
+    /// Edges whose source and target lie in different sessions.
+    pub edges: Vec<Edge>,
+    /// Edge count per (source session, target session), most connected first
+    /// (ties by session pair).
+    pub session_pairs: Vec<((u32, u32), usize)>,
 }
 
 /// Parameters for a causal (impact) query.
@@ -106,19 +288,58 @@ pub struct CausalParams {
     pub max_depth: u32,
     /// Which dependency edge types to follow.
     pub dependency_types: Vec<EdgeType>,
+    /// Follow dependents (impact) or causes (provenance).
+    pub direction: CausalDirection,
+}
+
+/// Which way a causal query follows dependency edges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CausalDirection {
+    /// Nodes that depend on the root: what is affected if it changes.
+    #[default]
+    Downstream,
+    /// Nodes the root depends on, back to its root causes and evidence:
+    /// what it is based on.
+    Upstream,
+}
+
+impl CausalDirection {
+    /// Lowercase name, e.g. `"upstream"`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Downstream => "downstream",
+            Self::Upstream => "upstream",
+        }
+    }
+
+    /// Parse a direction name (case-insensitive).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "downstream" => Some(Self::Downstream),
+            "upstream" => Some(Self::Upstream),
+            _ => None,
+        }
+    }
 }
 
 /// Result of a causal query.
 pub struct CausalResult {
     /// The root node being analyzed.
     pub root_id: u64,
-    /// All nodes that directly or indirectly depend on the root.
+    /// Direction the query followed.
+    pub direction: CausalDirection,
+    /// All nodes reached from the root: its direct and indirect dependents
+    /// downstream, or its direct and indirect causes upstream.
     pub dependents: Vec<u64>,
-    /// The dependency tree: node_id -> list of (dependent_id, edge_type).
+    /// The dependency tree: node_id -> list of (reached_id, edge_type).
     pub dependency_tree: HashMap<u64, Vec<(u64, EdgeType)>>,
-    /// Total number of decisions that depend on this node.
+    /// Reached nodes with no further dependency edge in the query's
+    /// direction: the root causes upstream, the last dependents downstream.
+    /// Nodes cut off by `max_depth` are not leaves.
+    pub leaves: Vec<u64>,
+    /// Total number of decisions among the reached nodes.
     pub affected_decisions: usize,
-    /// Total number of inferences that depend on this node.
+    /// Total number of inferences among the reached nodes.
     pub affected_inferences: usize,
 }
 
@@ -134,6 +355,51 @@ pub struct SimilarityParams {
     pub event_types: Vec<EventType>,
     /// Exclude nodes with zero vectors.
     pub skip_zero_vectors: bool,
+    /// Spread activation from the top matches along edges. `None` = off.
+    pub expand_via_edges: Option<EdgeExpansion>,
+}
+
+/// Spreading-activation step applied after a similarity search.
+///
+/// Each top match passes `similarity * edge weight * decay^hop` to the
+/// neighbors it reaches over `edge_types`, in either direction. A neighbor
+/// that already matched keeps the higher of its two scores; others are
+/// added to the results.
+pub struct EdgeExpansion {
+    /// Which edge types to follow.
+    pub edge_types: Vec<EdgeType>,
+    /// Maximum number of hops from a seed.
+    pub hops: u32,
+    /// Score multiplier applied per hop (0.0 - 1.0).
+    pub decay: f32,
+    /// Neighbors followed from each node per hop, strongest edges first.
+    /// Keeps a hub from pulling in the whole graph.
+    pub max_neighbors: usize,
+    /// Maximum number of nodes added beyond the `top_k` matches.
+    pub max_added: usize,
+}
+
+/// Where an expanded result came from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExpansionSource {
+    /// The top match whose activation reached this node.
+    pub seed_id: u64,
+    /// Type of the last edge on the path.
+    pub edge_type: EdgeType,
+    /// Number of hops from the seed.
+    pub hops: u32,
+}
+
+/// Default cap on members returned per expanded Episode.
+pub const DEFAULT_EPISODE_MEMBER_LIMIT: usize = 10;
+
+/// A node reached by expanding an Episode hit into the events it summarizes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EpisodeMember {
+    /// The member node.
+    pub node_id: u64,
+    /// The Episode hit it is `PartOf`.
+    pub episode_id: u64,
 }
 
 /// A match from a similarity search.
@@ -142,6 +408,8 @@ pub struct SimilarityMatchResult {
     pub node_id: u64,
     /// The similarity score.
     pub similarity: f32,
+    /// Set when edge expansion added this node or raised its score.
+    pub expanded_from: Option<ExpansionSource>,
 }
 
 /// Parameters for memory quality analysis.
@@ -179,6 +447,26 @@ pub struct MemoryQualityReport {
     pub stale_examples: Vec<u64>,
     pub orphan_examples: Vec<u64>,
     pub unsupported_decision_examples: Vec<u64>,
+    /// Node counts by confidence and freshness, using the same thresholds.
+    pub quadrants: ConfidenceDecayQuadrants,
+    /// Confident but stale nodes, most confident first: beliefs held
+    /// firmly that have not been revisited and may need re-verification.
+    pub confident_stale_examples: Vec<u64>,
+}
+
+/// Cross-tabulation of nodes by confidence (confident = at or above the
+/// low-confidence threshold) and freshness (stale = below the stale-decay
+/// threshold).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConfidenceDecayQuadrants {
+    /// Confident and recently reinforced.
+    pub confident_fresh: usize,
+    /// Confident but stale; the quadrant most likely to hide outdated beliefs.
+    pub confident_stale: usize,
+    /// Weak evidence, still fresh.
+    pub weak_fresh: usize,
+    /// Weak evidence and stale.
+    pub weak_stale: usize,
 }
 
 /// A subgraph extracted around a center node.
@@ -214,6 +502,7 @@ impl QueryEngine {
             params.max_depth,
             params.max_results,
             params.min_confidence,
+            params.symmetric_undirected,
         )?;
 
         Ok(TraversalResult {
@@ -229,22 +518,43 @@ impl QueryEngine {
         graph: &'a MemoryGraph,
         params: PatternParams,
     ) -> AmemResult<Vec<&'a CognitiveEvent>> {
-        // Start with candidate set
+        self.pattern_profiled(graph, params, None)
+    }
+
+    /// [`pattern`](Self::pattern), recording time spent in index lookup,
+    /// filtering and sorting into `profile` when given.
+    pub fn pattern_profiled<'a>(
+        &self,
+        graph: &'a MemoryGraph,
+        params: PatternParams,
+        profile: Option<&mut QueryProfile>,
+    ) -> AmemResult<Vec<&'a CognitiveEvent>> {
+        let mut timer = PhaseTimer::start(profile);
+
+        // Start with candidate set; the type index yields only the requested
+        // types, so no type filter is needed afterwards
         let mut candidates: Vec<&CognitiveEvent> = if !params.event_types.is_empty() {
-            let ids = graph.type_index().get_any(&params.event_types);
-            ids.iter().filter_map(|&id| graph.get_node(id)).collect()
+            graph.nodes_of_types(&params.event_types)
         } else if !params.session_ids.is_empty() {
             let ids = graph.session_index().get_sessions(&params.session_ids);
             ids.iter().filter_map(|&id| graph.get_node(id)).collect()
+        } else if !params.required_tags.is_empty() {
+            let tags: Vec<(String, String)> = params
+                .required_tags
+                .iter()
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect();
+            let ids = graph.tag_index().get_all(&tags);
+            ids.iter().filter_map(|&id| graph.get_node(id)).collect()
+        } else if !params.any_tags.is_empty() {
+            let ids = graph.tag_index().get_any(&params.any_tags);
+            ids.iter().filter_map(|&id| graph.get_node(id)).collect()
         } else {
             graph.nodes().iter().collect()
         };
+        timer.lap("index lookup");
 
         // Apply filters
-        if !params.event_types.is_empty() {
-            let type_set: HashSet<EventType> = params.event_types.iter().copied().collect();
-            candidates.retain(|n| type_set.contains(&n.event_type));
-        }
 
         if !params.session_ids.is_empty() {
             let session_set: HashSet<u32> = params.session_ids.iter().copied().collect();
@@ -266,6 +576,23 @@ impl QueryEngine {
         if let Some(min_decay) = params.min_decay_score {
             candidates.retain(|n| n.decay_score >= min_decay);
         }
+        if !params.required_tags.is_empty() {
+            candidates.retain(|n| {
+                params
+                    .required_tags
+                    .iter()
+                    .all(|(k, v)| n.tags.get(k) == Some(v))
+            });
+        }
+        if !params.any_tags.is_empty() {
+            candidates.retain(|n| {
+                params
+                    .any_tags
+                    .iter()
+                    .any(|(k, v)| n.tags.get(k) == Some(v))
+            });
+        }
+        timer.lap("filter");
 
         // Sort
         match params.sort_by {
@@ -292,9 +619,53 @@ impl QueryEngine {
         }
 
         candidates.truncate(params.max_results);
+        timer.lap("sort");
         Ok(candidates)
     }
 
+    /// Expand the Episode nodes among `hit_ids` into their members, following
+    /// inbound `PartOf` edges. Returns at most `max_per_episode` members per
+    /// Episode, oldest first, in hit order. Nodes that are hits themselves or
+    /// already listed under an earlier Episode are skipped.
+    pub fn expand_episodes(
+        &self,
+        graph: &MemoryGraph,
+        hit_ids: &[u64],
+        max_per_episode: usize,
+    ) -> Vec<EpisodeMember> {
+        let mut seen: HashSet<u64> = hit_ids.iter().copied().collect();
+        let mut members = Vec::new();
+
+        for &episode_id in hit_ids {
+            match graph.get_node(episode_id) {
+                Some(node) if node.event_type == EventType::Episode => {}
+                _ => continue,
+            }
+            let mut parts: Vec<&CognitiveEvent> = graph
+                .edges_to(episode_id)
+                .into_iter()
+                .filter(|e| e.edge_type == EdgeType::PartOf)
+                .filter_map(|e| graph.get_node(e.source_id))
+                .collect();
+            parts.sort_by_key(|n| (n.created_at, n.id));
+
+            let mut added = 0;
+            for part in parts {
+                if added >= max_per_episode {
+                    break;
+                }
+                if seen.insert(part.id) {
+                    members.push(EpisodeMember {
+                        node_id: part.id,
+                        episode_id,
+                    });
+                    added += 1;
+                }
+            }
+        }
+        members
+    }
+
     /// Compare graph state across time ranges or sessions.
     pub fn temporal(
         &self,
@@ -352,14 +723,114 @@ impl QueryEngine {
             .copied()
             .collect();
 
+        let session_groups = if params.group_by_session {
+            let mut in_range: Vec<u64> = nodes_a.iter().chain(&nodes_b).copied().collect();
+            in_range.sort_unstable();
+            in_range.dedup();
+            Some(self.group_by_session(graph, &in_range))
+        } else {
+            None
+        };
+
         Ok(TemporalResult {
             added,
             corrected,
             unchanged,
             potentially_stale,
+            session_groups,
         })
     }
 
+    /// Summarize every session with at least one node in `in_range`.
+    fn group_by_session(&self, graph: &MemoryGraph, in_range: &[u64]) -> Vec<SessionGroup> {
+        let mut by_session: HashMap<u32, Vec<&CognitiveEvent>> = HashMap::new();
+        for &id in in_range {
+            if let Some(node) = graph.get_node(id) {
+                by_session.entry(node.session_id).or_default().push(node);
+            }
+        }
+
+        let mut groups: Vec<SessionGroup> = by_session
+            .into_iter()
+            .map(|(session_id, mut hits)| {
+                hits.sort_by_key(|n| (n.created_at, n.id));
+                let members: Vec<&CognitiveEvent> = graph
+                    .session_index()
+                    .get_session(session_id)
+                    .iter()
+                    .filter_map(|&id| graph.get_node(id))
+                    .collect();
+
+                SessionGroup {
+                    session_id,
+                    in_range: hits.iter().map(|n| n.id).collect(),
+                    node_count: members.len(),
+                    dominant_type: dominant_type(members.iter().copied()),
+                    started_at: members.iter().map(|n| n.created_at).min().unwrap_or(0),
+                    ended_at: members.iter().map(|n| n.created_at).max().unwrap_or(0),
+                    partial: hits.len() < members.len(),
+                }
+            })
+            .collect();
+
+        groups.sort_by_key(|g| (g.started_at, g.session_id));
+        groups
+    }
+
+    /// Group nodes into consecutive time windows of `params.window`.
+    ///
+    /// Windows are aligned to multiples of the window width since the Unix
+    /// epoch, so the same node always lands in the same window. Windows
+    /// without matching nodes are left out; the rest are returned in time
+    /// order.
+    pub fn temporal_clusters(
+        &self,
+        graph: &MemoryGraph,
+        params: TemporalClusterParams,
+    ) -> AmemResult<Vec<TemporalCluster>> {
+        let width = u64::try_from(params.window.as_micros())
+            .unwrap_or(u64::MAX)
+            .max(1);
+
+        let mut clusters: Vec<TemporalCluster> = Vec::new();
+        let mut members: Vec<&CognitiveEvent> = Vec::new();
+        let mut flush = |start: u64, members: &mut Vec<&CognitiveEvent>| {
+            if members.is_empty() {
+                return;
+            }
+            let confidence: f32 = members.iter().map(|n| n.confidence).sum();
+            clusters.push(TemporalCluster {
+                start_micros: start,
+                end_micros: start.saturating_add(width),
+                node_ids: members.iter().map(|n| n.id).collect(),
+                dominant_type: dominant_type(members.iter().copied()),
+                mean_confidence: confidence / members.len() as f32,
+            });
+            members.clear();
+        };
+
+        let mut current = 0;
+        for &(created_at, id) in graph.temporal_index().entries() {
+            let Some(node) = graph.get_node(id) else {
+                continue;
+            };
+            if !params.event_types.is_empty() && !params.event_types.contains(&node.event_type) {
+                continue;
+            }
+            if !params.session_ids.is_empty() && !params.session_ids.contains(&node.session_id) {
+                continue;
+            }
+            let start = created_at - created_at % width;
+            if start != current {
+                flush(current, &mut members);
+                current = start;
+            }
+            members.push(node);
+        }
+        flush(current, &mut members);
+        Ok(clusters)
+    }
+
     fn collect_range_nodes(&self, graph: &MemoryGraph, range: &TimeRange) -> Vec<u64> {
         match range {
             TimeRange::TimeWindow { start, end } => graph.temporal_index().range(*start, *end),
@@ -368,15 +839,37 @@ impl QueryEngine {
         }
     }
 
-    /// Impact analysis: what depends on a given node?
+    /// Causal analysis: what depends on a node (downstream), or what it is
+    /// based on (upstream). Each node is visited once, so cycles terminate.
     pub fn causal(&self, graph: &MemoryGraph, params: CausalParams) -> AmemResult<CausalResult> {
         if graph.get_node(params.node_id).is_none() {
             return Err(AmemError::NodeNotFound(params.node_id));
         }
 
         let dep_set: HashSet<EdgeType> = params.dependency_types.iter().copied().collect();
+        // Downstream: nodes with dependency edges pointing TO the node depend
+        // on it. Upstream: the node's own dependency edges point at what it
+        // depends on.
+        let linked = |id: u64| -> Vec<(u64, EdgeType)> {
+            match params.direction {
+                CausalDirection::Downstream => graph
+                    .edges_to(id)
+                    .into_iter()
+                    .filter(|e| dep_set.contains(&e.edge_type))
+                    .map(|e| (e.source_id, e.edge_type))
+                    .collect(),
+                CausalDirection::Upstream => graph
+                    .edges_from(id)
+                    .iter()
+                    .filter(|e| dep_set.contains(&e.edge_type))
+                    .map(|e| (e.target_id, e.edge_type))
+                    .collect(),
+            }
+        };
+
         let mut dependents: Vec<u64> = Vec::new();
         let mut dependency_tree: HashMap<u64, Vec<(u64, EdgeType)>> = HashMap::new();
+        let mut leaves: Vec<u64> = Vec::new();
         let mut visited: HashSet<u64> = HashSet::new();
         let mut queue: VecDeque<(u64, u32)> = VecDeque::new();
 
@@ -384,21 +877,23 @@ impl QueryEngine {
         queue.push_back((params.node_id, 0));
 
         while let Some((current_id, depth)) = queue.pop_front() {
+            let next = linked(current_id);
+            if next.is_empty() && current_id != params.node_id {
+                leaves.push(current_id);
+            }
             if depth >= params.max_depth {
                 continue;
             }
 
-            // Find all nodes that have dependency edges pointing TO current_id
-            // These are nodes that depend on current_id
-            for edge in graph.edges_to(current_id) {
-                if dep_set.contains(&edge.edge_type) && !visited.contains(&edge.source_id) {
-                    visited.insert(edge.source_id);
-                    dependents.push(edge.source_id);
+            for (next_id, edge_type) in next {
+                if !visited.contains(&next_id) {
+                    visited.insert(next_id);
+                    dependents.push(next_id);
                     dependency_tree
                         .entry(current_id)
                         .or_default()
-                        .push((edge.source_id, edge.edge_type));
-                    queue.push_back((edge.source_id, depth + 1));
+                        .push((next_id, edge_type));
+                    queue.push_back((next_id, depth + 1));
                 }
             }
         }
@@ -417,8 +912,10 @@ impl QueryEngine {
 
         Ok(CausalResult {
             root_id: params.node_id,
+            direction: params.direction,
             dependents,
             dependency_tree,
+            leaves,
             affected_decisions,
             affected_inferences,
         })
@@ -430,16 +927,37 @@ impl QueryEngine {
         graph: &MemoryGraph,
         params: SimilarityParams,
     ) -> AmemResult<Vec<SimilarityMatchResult>> {
-        let type_filter: HashSet<EventType> = params.event_types.iter().copied().collect();
+        self.similarity_over(graph, params, None)
+    }
 
-        let mut matches: Vec<SimilarityMatchResult> = Vec::new();
+    /// Approximate similarity search that scores only a random sample of
+    /// the candidate nodes. Edge expansion still follows the full graph.
+    pub fn similarity_sampled(
+        &self,
+        graph: &MemoryGraph,
+        params: SimilarityParams,
+        sample: NodeSample,
+    ) -> AmemResult<Vec<SimilarityMatchResult>> {
+        self.similarity_over(graph, params, Some(sample))
+    }
 
-        for node in graph.nodes() {
-            // Type filter
-            if !type_filter.is_empty() && !type_filter.contains(&node.event_type) {
-                continue;
-            }
+    fn similarity_over(
+        &self,
+        graph: &MemoryGraph,
+        params: SimilarityParams,
+        sample: Option<NodeSample>,
+    ) -> AmemResult<Vec<SimilarityMatchResult>> {
+        let type_filter: HashSet<EventType> = params.event_types.iter().copied().collect();
+        let mut matches: Vec<SimilarityMatchResult> = Vec::new();
 
+        // Only nodes of the requested types are visited.
+        let mut candidates = graph.nodes_of_types(&params.event_types);
+        if let Some(sample) = sample {
+            let ids: Vec<u64> = candidates.iter().map(|n| n.id).collect();
+            let kept: HashSet<u64> = sample.select(&ids).into_iter().collect();
+            candidates.retain(|n| kept.contains(&n.id));
+        }
+        for node in candidates {
             // Skip zero vectors
             if params.skip_zero_vectors && node.feature_vec.iter().all(|&x| x == 0.0) {
                 continue;
@@ -450,6 +968,7 @@ impl QueryEngine {
                 matches.push(SimilarityMatchResult {
                     node_id: node.id,
                     similarity: sim,
+                    expanded_from: None,
                 });
             }
         }
@@ -461,9 +980,89 @@ impl QueryEngine {
         });
         matches.truncate(params.top_k);
 
+        if let Some(expansion) = &params.expand_via_edges {
+            expand_matches(graph, &mut matches, expansion, &type_filter);
+        }
+
         Ok(matches)
     }
 
+    /// Classify every non-empty session as active or completed, in session ID
+    /// order. A session counts as active when its newest node was created at
+    /// most `window_secs` before `now` and that node is not an Episode.
+    pub fn session_activity(
+        &self,
+        graph: &MemoryGraph,
+        now: u64,
+        window_secs: u64,
+    ) -> Vec<SessionActivity> {
+        let cutoff = now.saturating_sub(window_secs.saturating_mul(1_000_000));
+        let index = graph.session_index();
+        let mut session_ids = index.session_ids();
+        session_ids.sort_unstable();
+
+        session_ids
+            .into_iter()
+            .filter_map(|session_id| {
+                // An Episode wins a timestamp tie: compression stamps it with
+                // the same clock reading as a node written just before.
+                let newest = index
+                    .get_session(session_id)
+                    .iter()
+                    .filter_map(|&id| graph.get_node(id))
+                    .max_by_key(|n| (n.created_at, n.event_type == EventType::Episode, n.id))?;
+                let closed = newest.event_type == EventType::Episode;
+                Some(SessionActivity {
+                    session_id,
+                    node_count: index.node_count(session_id),
+                    last_activity: newest.created_at,
+                    closed,
+                    active: !closed && newest.created_at >= cutoff,
+                })
+            })
+            .collect()
+    }
+
+    /// Extract the edges whose endpoints lie in different sessions, showing
+    /// how sessions build on each other. The graph is not mutated.
+    pub fn cross_session(&self, graph: &MemoryGraph) -> CrossSessionView {
+        let session_of = |id: u64| graph.get_node(id).map(|n| n.session_id);
+        let mut pair_counts: HashMap<(u32, u32), usize> = HashMap::new();
+        let mut endpoint_ids: HashSet<u64> = HashSet::new();
+        let mut edges = Vec::new();
+
+        for edge in graph.edges() {
+            let (Some(from), Some(to)) = (session_of(edge.source_id), session_of(edge.target_id))
+            else {
+                continue;
+            };
+            if from == to {
+                continue;
+            }
+            *pair_counts.entry((from, to)).or_insert(0) += 1;
+            endpoint_ids.insert(edge.source_id);
+            endpoint_ids.insert(edge.target_id);
+            edges.push(*edge);
+        }
+
+        let mut nodes: Vec<CognitiveEvent> = graph
+            .nodes()
+            .iter()
+            .filter(|n| endpoint_ids.contains(&n.id))
+            .cloned()
+            .collect();
+        nodes.sort_by_key(|n| n.id);
+
+        let mut session_pairs: Vec<((u32, u32), usize)> = pair_counts.into_iter().collect();
+        session_pairs.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+        CrossSessionView {
+            nodes,
+            edges,
+            session_pairs,
+        }
+    }
+
     /// Evaluate memory quality across confidence, freshness, and graph structure.
     pub fn memory_quality(
         &self,
@@ -474,14 +1073,27 @@ impl QueryEngine {
         let mut stale = Vec::new();
         let mut orphan = Vec::new();
         let mut unsupported_decisions = Vec::new();
+        let mut quadrants = ConfidenceDecayQuadrants::default();
+        let mut confident_stale: Vec<(u64, f32)> = Vec::new();
 
         for node in graph.nodes() {
-            if node.confidence < params.low_confidence_threshold {
+            let weak = node.confidence < params.low_confidence_threshold;
+            let is_stale = node.decay_score < params.stale_decay_threshold;
+            if weak {
                 low_confidence.push(node.id);
             }
-            if node.decay_score < params.stale_decay_threshold {
+            if is_stale {
                 stale.push(node.id);
             }
+            match (weak, is_stale) {
+                (false, false) => quadrants.confident_fresh += 1,
+                (false, true) => {
+                    quadrants.confident_stale += 1;
+                    confident_stale.push((node.id, node.confidence));
+                }
+                (true, false) => quadrants.weak_fresh += 1,
+                (true, true) => quadrants.weak_stale += 1,
+            }
 
             let has_out = !graph.edges_from(node.id).is_empty();
             let has_in = !graph.edges_to(node.id).is_empty();
@@ -544,6 +1156,12 @@ impl QueryEngine {
         orphan_examples.truncate(params.max_examples);
         let mut unsupported_decision_examples = unsupported_decisions;
         unsupported_decision_examples.truncate(params.max_examples);
+        confident_stale.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        let confident_stale_examples: Vec<u64> = confident_stale
+            .into_iter()
+            .take(params.max_examples)
+            .map(|(id, _)| id)
+            .collect();
 
         Ok(MemoryQualityReport {
             status,
@@ -559,6 +1177,8 @@ impl QueryEngine {
             stale_examples,
             orphan_examples,
             unsupported_decision_examples,
+            quadrants,
+            confident_stale_examples,
         })
     }
 
@@ -569,15 +1189,7 @@ impl QueryEngine {
         }
 
         // BFS in all directions, following all edge types
-        let all_edge_types: Vec<EdgeType> = vec![
-            EdgeType::CausedBy,
-            EdgeType::Supports,
-            EdgeType::Contradicts,
-            EdgeType::Supersedes,
-            EdgeType::RelatedTo,
-            EdgeType::PartOf,
-            EdgeType::TemporalNext,
-        ];
+        let all_edge_types = graph.edge_types();
 
         let (visited, _, _) = bfs_traverse(
             graph,
@@ -587,6 +1199,7 @@ impl QueryEngine {
             depth,
             usize::MAX,
             0.0,
+            false,
         )?;
 
         let visited_set: HashSet<u64> = visited.iter().copied().collect();
@@ -613,6 +1226,10 @@ impl QueryEngine {
     }
 
     /// Get the latest version of a node, following SUPERSEDES chains.
+    ///
+    /// A chain that leads back to a node already on it fails with
+    /// [`AmemError::SupersedesCycle`], listing the nodes of the cycle in
+    /// chain order.
     pub fn resolve<'a>(
         &self,
         graph: &'a MemoryGraph,
@@ -624,7 +1241,9 @@ impl QueryEngine {
             return Err(AmemError::NodeNotFound(node_id));
         }
 
-        for _ in 0..100 {
+        let mut chain = vec![node_id];
+        let mut seen = HashSet::from([node_id]);
+        loop {
             // Find if any node supersedes the current one
             let mut superseded_by = None;
             for edge in graph.edges_to(current_id) {
@@ -635,7 +1254,16 @@ impl QueryEngine {
             }
 
             match superseded_by {
-                Some(new_id) => current_id = new_id,
+                Some(new_id) if !seen.insert(new_id) => {
+                    let start = chain.iter().position(|&id| id == new_id).unwrap_or(0);
+                    return Err(AmemError::SupersedesCycle {
+                        nodes: chain.split_off(start),
+                    });
+                }
+                Some(new_id) => {
+                    chain.push(new_id);
+                    current_id = new_id;
+                }
                 None => break,
             }
         }
@@ -651,3 +1279,101 @@ impl Default for QueryEngine {
         Self::new()
     }
 }
+
+/// Spread activation from `matches` along the expansion's edges, boosting
+/// or adding neighbors. Results are re-sorted by score.
+/// Most frequent event type among `nodes` (ties go to the lower type).
+fn dominant_type<'a>(nodes: impl Iterator<Item = &'a CognitiveEvent>) -> EventType {
+    let mut type_counts = [0usize; 6];
+    for node in nodes {
+        type_counts[node.event_type as usize] += 1;
+    }
+    let dominant = (0..type_counts.len())
+        .max_by(|&a, &b| type_counts[a].cmp(&type_counts[b]).then(b.cmp(&a)))
+        .unwrap_or(0);
+    EventType::from_u8(dominant as u8).unwrap_or(EventType::Fact)
+}
+
+fn expand_matches(
+    graph: &MemoryGraph,
+    matches: &mut Vec<SimilarityMatchResult>,
+    expansion: &EdgeExpansion,
+    type_filter: &HashSet<EventType>,
+) {
+    let edge_set: HashSet<EdgeType> = expansion.edge_types.iter().copied().collect();
+    let decay = expansion.decay.clamp(0.0, 1.0);
+    let mut index: HashMap<u64, usize> = matches
+        .iter()
+        .enumerate()
+        .map(|(i, m)| (m.node_id, i))
+        .collect();
+    let mut added = 0;
+
+    // (node, score reaching it, seed it came from)
+    let mut frontier: Vec<(u64, f32, u64)> = matches
+        .iter()
+        .map(|m| (m.node_id, m.similarity, m.node_id))
+        .collect();
+
+    for hop in 1..=expansion.hops {
+        let mut next = Vec::new();
+        for &(id, score, seed) in &frontier {
+            let mut incident: Vec<(u64, &Edge)> = graph
+                .edges_from(id)
+                .iter()
+                .map(|e| (e.target_id, e))
+                .chain(graph.edges_to(id).into_iter().map(|e| (e.source_id, e)))
+                .filter(|(_, e)| edge_set.contains(&e.edge_type))
+                .collect();
+            incident.sort_by(|a, b| b.1.weight.total_cmp(&a.1.weight).then(a.0.cmp(&b.0)));
+            incident.truncate(expansion.max_neighbors);
+
+            for (neighbor, edge) in incident {
+                if neighbor == seed {
+                    continue;
+                }
+                let Some(node) = graph.get_node(neighbor) else {
+                    continue;
+                };
+                if !type_filter.is_empty() && !type_filter.contains(&node.event_type) {
+                    continue;
+                }
+                let activation = score * edge.weight * decay;
+                let source = ExpansionSource {
+                    seed_id: seed,
+                    edge_type: edge.edge_type,
+                    hops: hop,
+                };
+                match index.get(&neighbor) {
+                    Some(&i) if activation > matches[i].similarity => {
+                        matches[i].similarity = activation;
+                        matches[i].expanded_from = Some(source);
+                        next.push((neighbor, activation, seed));
+                    }
+                    Some(_) => {}
+                    None if added < expansion.max_added => {
+                        index.insert(neighbor, matches.len());
+                        matches.push(SimilarityMatchResult {
+                            node_id: neighbor,
+                            similarity: activation,
+                            expanded_from: Some(source),
+                        });
+                        added += 1;
+                        next.push((neighbor, activation, seed));
+                    }
+                    None => {}
+                }
+            }
+        }
+        if next.is_empty() {
+            break;
+        }
+        frontier = next;
+    }
+
+    matches.sort_by(|a, b| {
+        b.similarity
+            .total_cmp(&a.similarity)
+            .then(a.node_id.cmp(&b.node_id))
+    });
+}
@@ -0,0 +1,255 @@
+//! Redaction of sensitive tokens in node content.
+//!
+//! Content is scanned one whitespace-separated token at a time; a sensitive
+//! token is replaced by a placeholder such as `[REDACTED_SECRET]` and the
+//! surrounding whitespace is kept as is.
+//!
+//! [`IdAnonymizer`] hides node IDs instead: it maps them onto a shuffled
+//! `0..n` range, so an export does not reveal creation order or ID gaps.
+
+use std::collections::HashMap;
+
+use crate::util::splitmix64;
+
+/// Kind of sensitive token a [`Redactor`] can scrub.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RedactionCategory {
+    /// API keys, bearer tokens and long opaque credentials.
+    Secrets,
+    /// Email addresses.
+    Emails,
+    /// Home-directory paths, which reveal user names.
+    Paths,
+}
+
+impl RedactionCategory {
+    /// All categories, in the order they are checked.
+    pub const ALL: [RedactionCategory; 3] = [Self::Paths, Self::Emails, Self::Secrets];
+
+    /// Name used on the command line, e.g. `"secrets"`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Secrets => "secrets",
+            Self::Emails => "emails",
+            Self::Paths => "paths",
+        }
+    }
+
+    /// Parse a category name (case-insensitive).
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim();
+        Self::ALL
+            .into_iter()
+            .find(|c| c.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Text that replaces a redacted token of this category.
+    pub fn placeholder(&self) -> &'static str {
+        match self {
+            Self::Secrets => "[REDACTED_SECRET]",
+            Self::Emails => "[REDACTED_EMAIL]",
+            Self::Paths => "[REDACTED_PATH]",
+        }
+    }
+}
+
+/// Number of tokens redacted, per category.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RedactionCounts {
+    /// Secrets replaced.
+    pub secrets: usize,
+    /// Email addresses replaced.
+    pub emails: usize,
+    /// Paths replaced.
+    pub paths: usize,
+}
+
+impl RedactionCounts {
+    /// Count for `category`.
+    pub fn get(&self, category: RedactionCategory) -> usize {
+        match category {
+            RedactionCategory::Secrets => self.secrets,
+            RedactionCategory::Emails => self.emails,
+            RedactionCategory::Paths => self.paths,
+        }
+    }
+
+    /// Redactions across all categories.
+    pub fn total(&self) -> usize {
+        self.secrets + self.emails + self.paths
+    }
+
+    fn add(&mut self, category: RedactionCategory) {
+        match category {
+            RedactionCategory::Secrets => self.secrets += 1,
+            RedactionCategory::Emails => self.emails += 1,
+            RedactionCategory::Paths => self.paths += 1,
+        }
+    }
+}
+
+/// Replaces sensitive tokens in text with category placeholders.
+#[derive(Debug, Clone)]
+pub struct Redactor {
+    categories: Vec<RedactionCategory>,
+}
+
+impl Redactor {
+    /// Redactor for every category.
+    pub fn new() -> Self {
+        Self::with_categories(RedactionCategory::ALL)
+    }
+
+    /// Redactor for the given categories only.
+    pub fn with_categories(categories: impl IntoIterator<Item = RedactionCategory>) -> Self {
+        let mut enabled: Vec<RedactionCategory> = categories.into_iter().collect();
+        // Keep the check order of ALL regardless of how they were given.
+        enabled.sort_by_key(|c| RedactionCategory::ALL.iter().position(|a| a == c));
+        enabled.dedup();
+        Self {
+            categories: enabled,
+        }
+    }
+
+    /// Enabled categories.
+    pub fn categories(&self) -> &[RedactionCategory] {
+        &self.categories
+    }
+
+    /// Redact `text`.
+    pub fn redact(&self, text: &str) -> String {
+        self.redact_counted(text, &mut RedactionCounts::default())
+    }
+
+    /// Redact `text`, adding each replacement to `counts`.
+    pub fn redact_counted(&self, text: &str, counts: &mut RedactionCounts) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut rest = text;
+        while !rest.is_empty() {
+            let gap = rest
+                .find(|c: char| !c.is_whitespace())
+                .unwrap_or(rest.len());
+            out.push_str(&rest[..gap]);
+            rest = &rest[gap..];
+
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            let token = &rest[..end];
+            match self.classify(token) {
+                Some(category) => {
+                    counts.add(category);
+                    out.push_str(category.placeholder());
+                }
+                None => out.push_str(token),
+            }
+            rest = &rest[end..];
+        }
+        out
+    }
+
+    /// Category of `token` if it is sensitive and that category is enabled.
+    pub fn classify(&self, token: &str) -> Option<RedactionCategory> {
+        let trimmed = token.trim_matches(|c: char| c == '"' || c == '\'' || c == ',' || c == ';');
+        if trimmed.is_empty() {
+            return None;
+        }
+        self.categories
+            .iter()
+            .copied()
+            .find(|&category| matches_category(category, trimmed))
+    }
+}
+
+impl Default for Redactor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn matches_category(category: RedactionCategory, token: &str) -> bool {
+    match category {
+        RedactionCategory::Paths => token.contains("/Users/") || token.contains("C:\\Users\\"),
+        RedactionCategory::Emails => token.contains('@') && token.contains('.'),
+        RedactionCategory::Secrets => {
+            let lower = token.to_ascii_lowercase();
+            lower.starts_with("sk-")
+                || lower.contains("api_key")
+                || lower.contains("access_token")
+                || lower.contains("bearer")
+                || lower.contains("authorization")
+                || looks_like_long_secret(token)
+        }
+    }
+}
+
+/// Consistent remapping of node IDs onto a shuffled `0..n` range.
+///
+/// The permutation depends only on the set of IDs and the seed, so the same
+/// seed reproduces the same mapping.
+#[derive(Debug, Clone)]
+pub struct IdAnonymizer {
+    seed: u64,
+    map: HashMap<u64, u64>,
+}
+
+impl IdAnonymizer {
+    /// Anonymizer for `ids`, shuffled with `seed`.
+    pub fn new(ids: impl IntoIterator<Item = u64>, seed: u64) -> Self {
+        let mut originals: Vec<u64> = ids.into_iter().collect();
+        originals.sort_unstable();
+        originals.dedup();
+
+        // Fisher-Yates shuffle of 0..n driven by splitmix64.
+        let mut targets: Vec<u64> = (0..originals.len() as u64).collect();
+        let mut state = seed;
+        for i in (1..targets.len()).rev() {
+            let j = (splitmix64(&mut state) % (i as u64 + 1)) as usize;
+            targets.swap(i, j);
+        }
+
+        Self {
+            seed,
+            map: originals.into_iter().zip(targets).collect(),
+        }
+    }
+
+    /// Anonymizer for `ids` with a random seed.
+    pub fn random(ids: impl IntoIterator<Item = u64>) -> Self {
+        Self::new(ids, uuid::Uuid::new_v4().as_u64_pair().0)
+    }
+
+    /// Seed the permutation was drawn with.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Anonymized ID for `id`, if it was one of the mapped IDs.
+    pub fn get(&self, id: u64) -> Option<u64> {
+        self.map.get(&id).copied()
+    }
+
+    /// Number of mapped IDs.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Whether no IDs are mapped.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// `(anonymized, original)` pairs, ordered by anonymized ID.
+    pub fn mapping(&self) -> Vec<(u64, u64)> {
+        let mut pairs: Vec<(u64, u64)> = self.map.iter().map(|(&o, &a)| (a, o)).collect();
+        pairs.sort_unstable();
+        pairs
+    }
+}
+
+fn looks_like_long_secret(token: &str) -> bool {
+    if token.len() < 24 {
+        return false;
+    }
+    token
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.')
+}
@@ -0,0 +1,51 @@
+//! Seeded node sampling for approximate queries.
+
+use std::collections::HashSet;
+
+use crate::util::splitmix64;
+
+/// A reproducible random subset of a query's candidate nodes.
+///
+/// The same fraction, seed and candidate list always select the same nodes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NodeSample {
+    /// Share of candidates to keep, clamped to `(0, 1]`.
+    pub fraction: f32,
+    /// Seed for the selection.
+    pub seed: u64,
+}
+
+impl NodeSample {
+    /// Sample `fraction` of the candidates with `seed`.
+    pub fn new(fraction: f32, seed: u64) -> Self {
+        Self { fraction, seed }
+    }
+
+    /// Number of nodes kept out of `total`: at least one unless `total` is 0.
+    pub fn size(&self, total: usize) -> usize {
+        let fraction = self.fraction.clamp(f32::MIN_POSITIVE, 1.0) as f64;
+        ((total as f64 * fraction).ceil() as usize).clamp(total.min(1), total)
+    }
+
+    /// The sampled subset of `ids`, in their original order.
+    pub fn select(&self, ids: &[u64]) -> Vec<u64> {
+        let keep = self.size(ids.len());
+        if keep == ids.len() {
+            return ids.to_vec();
+        }
+
+        // Partial Fisher-Yates over positions: the first `keep` are the sample.
+        let mut positions: Vec<usize> = (0..ids.len()).collect();
+        let mut state = self.seed;
+        for i in 0..keep {
+            let j = i + (splitmix64(&mut state) % (ids.len() - i) as u64) as usize;
+            positions.swap(i, j);
+        }
+        let chosen: HashSet<usize> = positions[..keep].iter().copied().collect();
+        ids.iter()
+            .enumerate()
+            .filter(|(i, _)| chosen.contains(i))
+            .map(|(_, &id)| id)
+            .collect()
+    }
+}
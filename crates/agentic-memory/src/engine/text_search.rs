@@ -2,14 +2,77 @@
 
 use std::collections::HashMap;
 
-use crate::engine::tokenizer::Tokenizer;
+use regex::RegexBuilder;
+
+use crate::engine::tokenizer::{detect_language, Tokenizer};
 use crate::graph::MemoryGraph;
 use crate::index::cosine_similarity;
-use crate::index::{DocLengths, TermIndex};
-use crate::types::{AmemResult, EventType};
+use crate::index::{DocLengths, TermBloom, TermIndex};
+use crate::types::{AmemError, AmemResult, EventType, Language};
+
+/// BM25 term-frequency saturation.
+pub const BM25_K1: f32 = 1.2;
+/// BM25 document-length normalization.
+pub const BM25_B: f32 = 0.75;
+
+/// IDF a term contributes to BM25 scores, given the number of indexed
+/// documents and how many of them contain the term.
+pub fn bm25_idf(doc_count: f32, doc_freq: f32) -> f32 {
+    ((doc_count - doc_freq + 0.5) / (doc_freq + 0.5) + 1.0).ln()
+}
+
+/// Jaro-Winkler similarity of two strings, from 0.0 (nothing in common) to
+/// 1.0 (identical). A shared prefix of up to four characters raises the
+/// score, so abbreviations and typos near the end of a word rank high.
+pub fn jaro_winkler(a: &str, b: &str) -> f32 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    let window = (a.len().max(b.len()) / 2).saturating_sub(1);
+    let mut a_matched = vec![false; a.len()];
+    let mut b_matched = vec![false; b.len()];
+    let mut matches = 0usize;
+    for (i, &ca) in a.iter().enumerate() {
+        let lo = i.saturating_sub(window);
+        let hi = (i + window + 1).min(b.len());
+        for j in lo..hi {
+            if !b_matched[j] && b[j] == ca {
+                a_matched[i] = true;
+                b_matched[j] = true;
+                matches += 1;
+                break;
+            }
+        }
+    }
+    if matches == 0 {
+        return 0.0;
+    }
 
-const BM25_K1: f32 = 1.2;
-const BM25_B: f32 = 0.75;
+    let a_seq = a.iter().zip(&a_matched).filter(|(_, &m)| m).map(|(c, _)| c);
+    let b_seq = b.iter().zip(&b_matched).filter(|(_, &m)| m).map(|(c, _)| c);
+    let transpositions = a_seq.zip(b_seq).filter(|(x, y)| x != y).count() / 2;
+
+    let m = matches as f32;
+    let jaro = (m / a.len() as f32 + m / b.len() as f32 + (m - transpositions as f32) / m) / 3.0;
+    let prefix = a.iter().zip(&b).take(4).take_while(|(x, y)| x == y).count();
+    jaro + prefix as f32 * 0.1 * (1.0 - jaro)
+}
+
+/// Language to tokenize `query` in. Only detected when some nodes carry a
+/// language tag; otherwise queries use the default rules, like the index.
+pub fn query_language(graph: &MemoryGraph, query: &str) -> Option<Language> {
+    if graph.has_node_languages() {
+        detect_language(query)
+    } else {
+        None
+    }
+}
 
 /// Parameters for BM25 text search.
 pub struct TextSearchParams {
@@ -23,14 +86,48 @@ pub struct TextSearchParams {
     pub session_ids: Vec<u32>,
     /// Minimum BM25 score to include (default: 0.0).
     pub min_score: f32,
+    /// How strongly node confidence scales the score, 0.0 to 1.0 (default:
+    /// 0.0, pure BM25). Each score is multiplied by
+    /// `1 - w + w * confidence`, so at 1.0 a node's score is proportional to
+    /// its confidence. `min_score` still applies to the unweighted score.
+    pub confidence_weight: f32,
 }
 
 /// A single BM25 text search match.
+#[derive(Debug)]
 pub struct TextMatch {
     pub node_id: u64,
     pub score: f32,
     /// Which query terms matched in this node's content.
     pub matched_terms: Vec<String>,
+    /// BM25 contribution of each matched term, in `matched_terms` order.
+    /// Sums to the score before confidence weighting.
+    pub term_scores: Vec<f32>,
+}
+
+/// Parameters for fuzzy (Jaro-Winkler) text search.
+pub struct FuzzySearchParams {
+    /// The search query string (will be tokenized).
+    pub query: String,
+    /// Maximum number of results.
+    pub max_results: usize,
+    /// Minimum Jaro-Winkler similarity, 0.0 to 1.0, for a content token to
+    /// count as a match.
+    pub min_similarity: f32,
+    /// Filter by event type(s). Empty = all types.
+    pub event_types: Vec<EventType>,
+}
+
+/// Parameters for regular expression search.
+pub struct RegexSearchParams {
+    /// Pattern in `regex` crate syntax. `^` and `$` match at line breaks.
+    pub pattern: String,
+    /// Match letter case exactly (default: false).
+    pub case_sensitive: bool,
+    /// Maximum number of results.
+    pub max_results: usize,
+    /// Filter by event type(s). Empty = all types.
+    pub event_types: Vec<EventType>,
 }
 
 /// Parameters for hybrid BM25 + vector search.
@@ -66,7 +163,154 @@ pub struct HybridMatch {
     pub vector_similarity: f32,
 }
 
+/// Rocchio weight of the original query terms.
+pub const FEEDBACK_ALPHA: f32 = 1.0;
+/// Rocchio weight of terms from nodes marked useful.
+pub const FEEDBACK_BETA: f32 = 0.75;
+/// Rocchio weight of terms from nodes marked not useful.
+pub const FEEDBACK_GAMMA: f32 = 0.25;
+/// Terms from useful nodes added to the refined query, at most.
+pub const FEEDBACK_MAX_EXPANSION_TERMS: usize = 10;
+
+/// Parameters for a relevance-feedback re-query.
+pub struct RelevanceFeedbackParams {
+    /// The query the judged results came from.
+    pub query: String,
+    /// Nodes of the earlier result that were useful.
+    pub useful_ids: Vec<u64>,
+    /// Nodes of the earlier result that were not useful.
+    pub not_useful_ids: Vec<u64>,
+    /// Maximum number of results.
+    pub max_results: usize,
+}
+
+/// A term of a refined query and its weight.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeightedTerm {
+    /// The term, as the tokenizer produced it.
+    pub term: String,
+    /// Rocchio weight; the term's BM25 contribution is scaled by it.
+    pub weight: f32,
+    /// Whether the term was in the original query.
+    pub from_query: bool,
+}
+
+/// Result of [`QueryEngine::relevance_feedback_search`](super::query::QueryEngine::relevance_feedback_search).
+pub struct RelevanceFeedbackResult {
+    /// Terms of the refined query, highest weight first. Terms whose weight
+    /// dropped to zero or below are left out.
+    pub terms: Vec<WeightedTerm>,
+    /// BM25 matches for the refined query, each term's contribution scaled
+    /// by its weight.
+    pub matches: Vec<TextMatch>,
+}
+
 impl super::query::QueryEngine {
+    /// Refine `params.query` with Rocchio relevance feedback and run it.
+    ///
+    /// Each term's weight is `alpha * q + beta * mean(useful) - gamma *
+    /// mean(not_useful)`, where a node contributes its term frequencies
+    /// divided by its length. Original query terms are kept while their
+    /// weight stays positive; the strongest terms from useful nodes are
+    /// added. Stateless: nothing about the feedback is stored.
+    pub fn relevance_feedback_search(
+        &self,
+        graph: &MemoryGraph,
+        term_index: Option<&TermIndex>,
+        doc_lengths: Option<&DocLengths>,
+        params: RelevanceFeedbackParams,
+    ) -> AmemResult<RelevanceFeedbackResult> {
+        let tokenizer = Tokenizer::new();
+        let mut query_terms =
+            tokenizer.tokenize_as(&params.query, query_language(graph, &params.query));
+        query_terms.sort();
+        query_terms.dedup();
+
+        let mut weights: HashMap<String, f32> = query_terms
+            .iter()
+            .map(|t| (t.clone(), FEEDBACK_ALPHA))
+            .collect();
+        let mut add_centroid = |ids: &[u64], factor: f32| {
+            let nodes: Vec<_> = ids.iter().filter_map(|&id| graph.get_node(id)).collect();
+            if nodes.is_empty() {
+                return;
+            }
+            let share = factor / nodes.len() as f32;
+            for node in nodes {
+                let freqs =
+                    tokenizer.term_frequencies_as(&node.content, graph.node_language(node.id));
+                let len = freqs.values().sum::<u32>().max(1) as f32;
+                for (term, tf) in freqs {
+                    *weights.entry(term).or_insert(0.0) += share * tf as f32 / len;
+                }
+            }
+        };
+        add_centroid(&params.useful_ids, FEEDBACK_BETA);
+        add_centroid(&params.not_useful_ids, -FEEDBACK_GAMMA);
+
+        let mut expansion: Vec<WeightedTerm> = Vec::new();
+        let mut terms: Vec<WeightedTerm> = Vec::new();
+        for (term, weight) in weights {
+            if weight <= 0.0 {
+                continue;
+            }
+            let from_query = query_terms.binary_search(&term).is_ok();
+            let entry = WeightedTerm {
+                term,
+                weight,
+                from_query,
+            };
+            if from_query {
+                terms.push(entry);
+            } else {
+                expansion.push(entry);
+            }
+        }
+        let by_weight = |a: &WeightedTerm, b: &WeightedTerm| {
+            b.weight
+                .partial_cmp(&a.weight)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.term.cmp(&b.term))
+        };
+        expansion.sort_by(by_weight);
+        expansion.truncate(FEEDBACK_MAX_EXPANSION_TERMS);
+        terms.extend(expansion);
+        terms.sort_by(by_weight);
+
+        if terms.is_empty() {
+            return Ok(RelevanceFeedbackResult {
+                terms,
+                matches: Vec::new(),
+            });
+        }
+
+        let refined: Vec<String> = terms.iter().map(|t| t.term.clone()).collect();
+        let no_types = std::collections::HashSet::new();
+        let no_sessions = std::collections::HashSet::new();
+        let mut matches = if let (Some(ti), Some(dl)) = (term_index, doc_lengths) {
+            self.bm25_fast_path(graph, ti, dl, &refined, &no_types, &no_sessions)
+        } else {
+            self.bm25_slow_path(graph, &tokenizer, &refined, &no_types, &no_sessions)
+        };
+
+        let weight_of: HashMap<&str, f32> =
+            terms.iter().map(|t| (t.term.as_str(), t.weight)).collect();
+        for m in &mut matches {
+            for (term, score) in m.matched_terms.iter().zip(m.term_scores.iter_mut()) {
+                *score *= weight_of.get(term.as_str()).copied().unwrap_or(0.0);
+            }
+            m.score = m.term_scores.iter().sum();
+        }
+        matches.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        matches.truncate(params.max_results);
+
+        Ok(RelevanceFeedbackResult { terms, matches })
+    }
+
     /// BM25 text search over node contents.
     /// Uses TermIndex if available, falls back to full scan.
     pub fn text_search(
@@ -77,7 +321,8 @@ impl super::query::QueryEngine {
         params: TextSearchParams,
     ) -> AmemResult<Vec<TextMatch>> {
         let tokenizer = Tokenizer::new();
-        let query_terms = tokenizer.tokenize(&params.query);
+        let query_terms =
+            tokenizer.tokenize_as(&params.query, query_language(graph, &params.query));
 
         if query_terms.is_empty() {
             return Ok(Vec::new());
@@ -107,6 +352,74 @@ impl super::query::QueryEngine {
             .into_iter()
             .filter(|m| m.score >= params.min_score)
             .collect();
+        apply_confidence_weight(graph, &mut results, params.confidence_weight);
+
+        results.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        results.truncate(params.max_results);
+
+        Ok(results)
+    }
+
+    /// BM25 text search that only returns nodes containing every query term.
+    ///
+    /// Candidates come from the rarest term's posting list. When the graph
+    /// carries term bloom filters, candidates that definitely lack another
+    /// term are skipped before any posting lookup. A bloom false positive
+    /// only costs the exact lookup that follows, never a wrong result.
+    pub fn text_search_all(
+        &self,
+        graph: &MemoryGraph,
+        term_index: Option<&TermIndex>,
+        doc_lengths: Option<&DocLengths>,
+        params: TextSearchParams,
+    ) -> AmemResult<Vec<TextMatch>> {
+        let tokenizer = Tokenizer::new();
+        let mut query_terms =
+            tokenizer.tokenize_as(&params.query, query_language(graph, &params.query));
+        query_terms.sort();
+        query_terms.dedup();
+
+        if query_terms.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let type_filter: std::collections::HashSet<EventType> =
+            params.event_types.iter().copied().collect();
+        let session_filter: std::collections::HashSet<u32> =
+            params.session_ids.iter().copied().collect();
+
+        let matches = if let (Some(ti), Some(dl)) = (term_index, doc_lengths) {
+            self.bm25_all_terms_fast_path(
+                graph,
+                ti,
+                dl,
+                graph.term_bloom(),
+                &query_terms,
+                &type_filter,
+                &session_filter,
+            )
+        } else {
+            self.bm25_slow_path(
+                graph,
+                &tokenizer,
+                &query_terms,
+                &type_filter,
+                &session_filter,
+            )
+            .into_iter()
+            .filter(|m| m.matched_terms.len() == query_terms.len())
+            .collect()
+        };
+
+        let mut results: Vec<TextMatch> = matches
+            .into_iter()
+            .filter(|m| m.score >= params.min_score)
+            .collect();
+        apply_confidence_weight(graph, &mut results, params.confidence_weight);
 
         results.sort_by(|a, b| {
             b.score
@@ -118,6 +431,87 @@ impl super::query::QueryEngine {
         Ok(results)
     }
 
+    #[allow(clippy::too_many_arguments)]
+    fn bm25_all_terms_fast_path(
+        &self,
+        graph: &MemoryGraph,
+        term_index: &TermIndex,
+        doc_lengths: &DocLengths,
+        bloom: Option<&TermBloom>,
+        query_terms: &[String],
+        type_filter: &std::collections::HashSet<EventType>,
+        session_filter: &std::collections::HashSet<u32>,
+    ) -> Vec<TextMatch> {
+        let n = term_index.doc_count() as f32;
+        let avgdl = term_index.avg_doc_length();
+
+        // Drive from the rarest term; every other term is probed per candidate.
+        let Some(rarest) = query_terms
+            .iter()
+            .min_by_key(|t| term_index.doc_frequency(t))
+        else {
+            return Vec::new();
+        };
+        let others: Vec<String> = query_terms
+            .iter()
+            .filter(|t| *t != rarest)
+            .cloned()
+            .collect();
+        let idfs: HashMap<&str, f32> = query_terms
+            .iter()
+            .map(|t| {
+                let df = term_index.doc_frequency(t) as f32;
+                (t.as_str(), bm25_idf(n, df))
+            })
+            .collect();
+
+        let mut results = Vec::new();
+        'candidates: for &(node_id, rarest_tf) in term_index.get(rarest) {
+            if let Some(node) = graph.get_node(node_id) {
+                if !type_filter.is_empty() && !type_filter.contains(&node.event_type) {
+                    continue;
+                }
+                if !session_filter.is_empty() && !session_filter.contains(&node.session_id) {
+                    continue;
+                }
+            }
+            if let Some(bloom) = bloom {
+                if !bloom.might_contain_all(node_id, &others) {
+                    continue;
+                }
+            }
+
+            let dl = doc_lengths.get(node_id) as f32;
+            let term_score = |term: &str, tf: u32| {
+                let tf_f = tf as f32;
+                idfs[term] * (tf_f * (BM25_K1 + 1.0))
+                    / (tf_f + BM25_K1 * (1.0 - BM25_B + BM25_B * dl / avgdl.max(1.0)))
+            };
+
+            let mut term_scores = Vec::with_capacity(query_terms.len());
+            for term in query_terms {
+                let tf = if term == rarest {
+                    rarest_tf
+                } else {
+                    match term_index.term_frequency(term, node_id) {
+                        Some(tf) => tf,
+                        None => continue 'candidates,
+                    }
+                };
+                term_scores.push(term_score(term.as_str(), tf));
+            }
+
+            results.push(TextMatch {
+                node_id,
+                score: term_scores.iter().sum(),
+                matched_terms: query_terms.to_vec(),
+                term_scores,
+            });
+        }
+
+        results
+    }
+
     fn bm25_fast_path(
         &self,
         graph: &MemoryGraph,
@@ -131,12 +525,12 @@ impl super::query::QueryEngine {
         let avgdl = term_index.avg_doc_length();
 
         // Collect all candidate node IDs from posting lists
-        let mut scores: HashMap<u64, (f32, Vec<String>)> = HashMap::new();
+        let mut scores: HashMap<u64, (f32, Vec<String>, Vec<f32>)> = HashMap::new();
 
         for term in query_terms {
             let postings = term_index.get(term);
             let df = postings.len() as f32;
-            let idf = ((n - df + 0.5) / (df + 0.5) + 1.0).ln();
+            let idf = bm25_idf(n, df);
 
             for &(node_id, tf) in postings {
                 // Apply filters
@@ -154,20 +548,27 @@ impl super::query::QueryEngine {
                 let bm25_term = idf * (tf_f * (BM25_K1 + 1.0))
                     / (tf_f + BM25_K1 * (1.0 - BM25_B + BM25_B * dl / avgdl.max(1.0)));
 
-                let entry = scores.entry(node_id).or_insert((0.0, Vec::new()));
+                let entry = scores
+                    .entry(node_id)
+                    .or_insert((0.0, Vec::new(), Vec::new()));
                 entry.0 += bm25_term;
-                if !entry.1.contains(term) {
-                    entry.1.push(term.clone());
+                match entry.1.iter().position(|t| t == term) {
+                    Some(i) => entry.2[i] += bm25_term,
+                    None => {
+                        entry.1.push(term.clone());
+                        entry.2.push(bm25_term);
+                    }
                 }
             }
         }
 
         scores
             .into_iter()
-            .map(|(node_id, (score, matched_terms))| TextMatch {
+            .map(|(node_id, (score, matched_terms, term_scores))| TextMatch {
                 node_id,
                 score,
                 matched_terms,
+                term_scores,
             })
             .collect()
     }
@@ -191,15 +592,13 @@ impl super::query::QueryEngine {
         let mut node_data: Vec<(u64, HashMap<String, u32>, u32)> = Vec::new();
         let mut total_tokens: u64 = 0;
 
-        for node in nodes {
-            if !type_filter.is_empty() && !type_filter.contains(&node.event_type) {
-                continue;
-            }
+        let types: Vec<EventType> = type_filter.iter().copied().collect();
+        for node in graph.nodes_of_types(&types) {
             if !session_filter.is_empty() && !session_filter.contains(&node.session_id) {
                 continue;
             }
 
-            let freqs = tokenizer.term_frequencies(&node.content);
+            let freqs = tokenizer.term_frequencies_as(&node.content, graph.node_language(node.id));
             let doc_len: u32 = freqs.values().sum();
             total_tokens += doc_len as u64;
 
@@ -220,19 +619,24 @@ impl super::query::QueryEngine {
 
         for (node_id, freqs, doc_len) in &node_data {
             let mut score = 0.0f32;
-            let mut matched = Vec::new();
+            let mut matched: Vec<String> = Vec::new();
+            let mut term_scores: Vec<f32> = Vec::new();
 
             for term in query_terms {
                 if let Some(&tf) = freqs.get(term) {
                     let df = *doc_freqs.get(term).unwrap_or(&0) as f32;
-                    let idf = ((n - df + 0.5) / (df + 0.5) + 1.0).ln();
+                    let idf = bm25_idf(n, df);
                     let tf_f = tf as f32;
                     let dl = *doc_len as f32;
                     let bm25_term = idf * (tf_f * (BM25_K1 + 1.0))
                         / (tf_f + BM25_K1 * (1.0 - BM25_B + BM25_B * dl / avgdl.max(1.0)));
                     score += bm25_term;
-                    if !matched.contains(term) {
-                        matched.push(term.clone());
+                    match matched.iter().position(|t| t == term) {
+                        Some(i) => term_scores[i] += bm25_term,
+                        None => {
+                            matched.push(term.clone());
+                            term_scores.push(bm25_term);
+                        }
                     }
                 }
             }
@@ -242,6 +646,7 @@ impl super::query::QueryEngine {
                     node_id: *node_id,
                     score,
                     matched_terms: matched,
+                    term_scores,
                 });
             }
         }
@@ -249,6 +654,112 @@ impl super::query::QueryEngine {
         results
     }
 
+    /// Typo-tolerant text search. Each query token is compared with every
+    /// content token by Jaro-Winkler similarity; a node scores the best
+    /// similarity any of its tokens reaches and is returned when that is at
+    /// least `min_similarity`.
+    ///
+    /// `matched_terms` holds, per query token, the closest content token
+    /// that passed the threshold, with its similarity in `term_scores`.
+    /// Results are sorted by score, ties by node ID.
+    pub fn fuzzy_search(
+        &self,
+        graph: &MemoryGraph,
+        params: FuzzySearchParams,
+    ) -> AmemResult<Vec<TextMatch>> {
+        let tokenizer = Tokenizer::new();
+        let mut query_terms =
+            tokenizer.tokenize_as(&params.query, query_language(graph, &params.query));
+        query_terms.sort();
+        query_terms.dedup();
+        if query_terms.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Best (similarity, query term index) per distinct content token,
+        // so a token repeated across nodes is only compared once.
+        let mut cache: HashMap<String, Vec<f32>> = HashMap::new();
+        let mut results = Vec::new();
+        for node in graph.nodes_of_types(&params.event_types) {
+            let mut best: Vec<Option<(f32, String)>> = vec![None; query_terms.len()];
+            for token in tokenizer.tokenize_as(&node.content, graph.node_language(node.id)) {
+                let sims = cache.entry(token.clone()).or_insert_with(|| {
+                    query_terms
+                        .iter()
+                        .map(|q| jaro_winkler(q, &token))
+                        .collect()
+                });
+                for (slot, &sim) in best.iter_mut().zip(sims.iter()) {
+                    if sim >= params.min_similarity && !matches!(slot, Some((s, _)) if *s >= sim) {
+                        *slot = Some((sim, token.clone()));
+                    }
+                }
+            }
+
+            let (term_scores, matched_terms): (Vec<f32>, Vec<String>) =
+                best.into_iter().flatten().unzip();
+            if let Some(score) = term_scores.iter().copied().reduce(f32::max) {
+                results.push(TextMatch {
+                    node_id: node.id,
+                    score,
+                    matched_terms,
+                    term_scores,
+                });
+            }
+        }
+
+        results.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(a.node_id.cmp(&b.node_id))
+        });
+        results.truncate(params.max_results);
+        Ok(results)
+    }
+
+    /// `grep`-style search: every node whose content matches the pattern.
+    ///
+    /// Scores are 1.0, as a match is all or nothing. `matched_terms` holds
+    /// the distinct matched texts in order of first appearance, each with a
+    /// `term_scores` entry of 1.0. Results are sorted by node ID. A
+    /// malformed pattern fails with [`AmemError::InvalidPattern`].
+    pub fn regex_search(
+        &self,
+        graph: &MemoryGraph,
+        params: RegexSearchParams,
+    ) -> AmemResult<Vec<TextMatch>> {
+        let regex = RegexBuilder::new(&params.pattern)
+            .case_insensitive(!params.case_sensitive)
+            .multi_line(true)
+            .build()
+            .map_err(|e| AmemError::InvalidPattern(e.to_string()))?;
+
+        let mut results = Vec::new();
+        for node in graph.nodes_of_types(&params.event_types) {
+            let mut matched_terms: Vec<String> = Vec::new();
+            for m in regex.find_iter(&node.content) {
+                if !matched_terms.iter().any(|t| t == m.as_str()) {
+                    matched_terms.push(m.as_str().to_string());
+                }
+            }
+            // An empty match (`^$` on a blank line) is still a match.
+            if matched_terms.is_empty() {
+                continue;
+            }
+            results.push(TextMatch {
+                node_id: node.id,
+                score: 1.0,
+                term_scores: vec![1.0; matched_terms.len()],
+                matched_terms,
+            });
+        }
+
+        results.sort_by_key(|m| m.node_id);
+        results.truncate(params.max_results);
+        Ok(results)
+    }
+
     /// Hybrid BM25 + vector search with Reciprocal Rank Fusion.
     pub fn hybrid_search(
         &self,
@@ -281,6 +792,7 @@ impl super::query::QueryEngine {
                 event_types: params.event_types.clone(),
                 session_ids: Vec::new(),
                 min_score: 0.0,
+                confidence_weight: 0.0,
             },
         )?;
 
@@ -300,14 +812,9 @@ impl super::query::QueryEngine {
 
         if has_vectors {
             if let Some(ref qvec) = params.query_vec {
-                let type_filter: std::collections::HashSet<EventType> =
-                    params.event_types.iter().copied().collect();
                 let mut sim_results: Vec<(u64, f32)> = Vec::new();
 
-                for node in graph.nodes() {
-                    if !type_filter.is_empty() && !type_filter.contains(&node.event_type) {
-                        continue;
-                    }
+                for node in graph.nodes_of_types(&params.event_types) {
                     if node.feature_vec.iter().all(|&x| x == 0.0) {
                         continue;
                     }
@@ -377,3 +884,18 @@ impl super::query::QueryEngine {
         Ok(hybrid_results)
     }
 }
+
+/// Scale each match's score by `1 - weight + weight * confidence`.
+fn apply_confidence_weight(graph: &MemoryGraph, results: &mut [TextMatch], weight: f32) {
+    let weight = weight.clamp(0.0, 1.0);
+    if weight == 0.0 {
+        return;
+    }
+    for m in results.iter_mut() {
+        let confidence = graph
+            .get_node(m.node_id)
+            .map(|n| n.confidence.clamp(0.0, 1.0))
+            .unwrap_or(1.0);
+        m.score *= 1.0 - weight + weight * confidence;
+    }
+}
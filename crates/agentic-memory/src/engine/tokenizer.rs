@@ -2,6 +2,8 @@
 
 use std::collections::{HashMap, HashSet};
 
+use crate::types::Language;
+
 /// Stop words to exclude from tokenization.
 const STOP_WORDS: &[&str] = &[
     "the", "a", "an", "is", "are", "was", "were", "be", "been", "being", "have", "has", "had",
@@ -12,6 +14,165 @@ const STOP_WORDS: &[&str] = &[
     "no", "than", "too", "very", "just", "also",
 ];
 
+/// Stop words for the non-English languages detected by word voting. Kept
+/// short: the most frequent function words, which also drive detection.
+const GERMAN_STOP_WORDS: &[&str] = &[
+    "der", "die", "das", "und", "ist", "nicht", "ein", "eine", "zu", "den", "von", "mit", "sich",
+    "des", "auf", "für", "im", "dem", "auch", "es", "werden", "aus", "er", "hat", "dass", "sie",
+    "nach", "wird", "bei", "einer", "um", "sind", "noch", "wie", "über", "oder", "aber", "wir",
+];
+const FRENCH_STOP_WORDS: &[&str] = &[
+    "le", "la", "les", "de", "des", "du", "un", "une", "et", "est", "en", "que", "qui", "dans",
+    "pour", "pas", "sur", "au", "aux", "avec", "ce", "ces", "il", "elle", "ne", "se", "sont",
+    "par", "plus", "mais", "ou", "son", "sa", "ses", "nous", "vous", "être", "été",
+];
+const SPANISH_STOP_WORDS: &[&str] = &[
+    "el", "la", "los", "las", "de", "del", "un", "una", "y", "es", "en", "que", "por", "con",
+    "para", "no", "se", "su", "al", "lo", "como", "más", "pero", "sus", "le", "ya", "fue", "este",
+    "ha", "porque", "esta", "son", "entre", "cuando", "muy", "sin", "sobre", "también",
+];
+const PORTUGUESE_STOP_WORDS: &[&str] = &[
+    "o", "a", "os", "as", "de", "do", "da", "dos", "das", "um", "uma", "e", "é", "em", "que",
+    "por", "com", "para", "não", "se", "seu", "sua", "ao", "no", "na", "nos", "nas", "como",
+    "mais", "mas", "foi", "ele", "ela", "são", "também", "já", "pelo", "pela",
+];
+const ITALIAN_STOP_WORDS: &[&str] = &[
+    "il", "lo", "la", "gli", "le", "di", "del", "della", "un", "una", "e", "è", "in", "che", "per",
+    "con", "non", "si", "su", "al", "alla", "come", "più", "ma", "sono", "anche", "dei", "nel",
+    "nella", "questo", "questa",
+];
+const DUTCH_STOP_WORDS: &[&str] = &[
+    "de", "het", "een", "en", "van", "is", "dat", "die", "in", "te", "op", "niet", "zijn", "met",
+    "voor", "er", "aan", "als", "ook", "maar", "bij", "om", "door", "wordt", "naar", "dan", "nog",
+    "werd", "uit",
+];
+
+/// Latin-script languages told apart by counting their stop words.
+const LATIN_CANDIDATES: &[(Language, &[&str])] = &[
+    (Language::English, STOP_WORDS),
+    (Language::German, GERMAN_STOP_WORDS),
+    (Language::French, FRENCH_STOP_WORDS),
+    (Language::Spanish, SPANISH_STOP_WORDS),
+    (Language::Portuguese, PORTUGUESE_STOP_WORDS),
+    (Language::Italian, ITALIAN_STOP_WORDS),
+    (Language::Dutch, DUTCH_STOP_WORDS),
+];
+
+/// Writing systems recognized by [`detect_language`], in tie-break order.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Script {
+    Latin,
+    Cyrillic,
+    Greek,
+    Arabic,
+    Hebrew,
+    Devanagari,
+    Thai,
+    Hangul,
+    Kana,
+    Han,
+}
+
+impl Script {
+    fn of(c: char) -> Option<Self> {
+        let script = match c as u32 {
+            0x0370..=0x03FF => Self::Greek,
+            0x0400..=0x052F => Self::Cyrillic,
+            0x0590..=0x05FF => Self::Hebrew,
+            0x0600..=0x06FF | 0x0750..=0x077F => Self::Arabic,
+            0x0900..=0x097F => Self::Devanagari,
+            0x0E00..=0x0E7F => Self::Thai,
+            0x1100..=0x11FF | 0x3130..=0x318F | 0xAC00..=0xD7AF => Self::Hangul,
+            0x3040..=0x30FF => Self::Kana,
+            0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xF900..=0xFAFF => Self::Han,
+            _ if c.is_alphabetic() && (c as u32) < 0x0250 => Self::Latin,
+            _ => return None,
+        };
+        Some(script)
+    }
+}
+
+const SCRIPTS: [Script; 10] = [
+    Script::Latin,
+    Script::Cyrillic,
+    Script::Greek,
+    Script::Arabic,
+    Script::Hebrew,
+    Script::Devanagari,
+    Script::Thai,
+    Script::Hangul,
+    Script::Kana,
+    Script::Han,
+];
+
+/// Whether `c` belongs to a script written without spaces between words.
+fn is_unsegmented_char(c: char) -> bool {
+    matches!(Script::of(c), Some(Script::Han | Script::Kana))
+}
+
+/// Guess the language of `text` with a lightweight heuristic.
+///
+/// The dominant script decides for non-Latin text (Han with any kana is
+/// Japanese). Latin-script text is assigned the language whose stop words it
+/// contains most often; `None` when no stop word matches or the vote ties
+/// without English, so short or ambiguous text keeps the default rules.
+pub fn detect_language(text: &str) -> Option<Language> {
+    let mut counts = [0usize; 10];
+    for c in text.chars() {
+        if let Some(script) = Script::of(c) {
+            counts[script as usize] += 1;
+        }
+    }
+    // First script with the highest count wins ties.
+    let (dominant, &top) = counts.iter().enumerate().rev().max_by_key(|(_, &n)| n)?;
+    if top == 0 {
+        return None;
+    }
+    let has_kana = counts[Script::Kana as usize] > 0;
+    let dominant = SCRIPTS[dominant];
+
+    let lang = match dominant {
+        Script::Han | Script::Kana if has_kana => Language::Japanese,
+        Script::Han | Script::Kana => Language::Chinese,
+        Script::Hangul => Language::Korean,
+        Script::Cyrillic => Language::Russian,
+        Script::Greek => Language::Greek,
+        Script::Arabic => Language::Arabic,
+        Script::Hebrew => Language::Hebrew,
+        Script::Devanagari => Language::Hindi,
+        Script::Thai => Language::Thai,
+        Script::Latin => return detect_latin(text),
+    };
+    Some(lang)
+}
+
+fn detect_latin(text: &str) -> Option<Language> {
+    let lower = text.to_lowercase();
+    let words: Vec<&str> = lower.split(|c: char| !c.is_alphanumeric()).collect();
+    let scores: Vec<(Language, usize)> = LATIN_CANDIDATES
+        .iter()
+        .map(|(lang, stops)| {
+            let hits = words.iter().filter(|w| stops.contains(w)).count();
+            (*lang, hits)
+        })
+        .collect();
+
+    let best = scores.iter().map(|(_, n)| *n).max().unwrap_or(0);
+    if best == 0 {
+        return None;
+    }
+    let leaders: Vec<Language> = scores
+        .iter()
+        .filter(|(_, n)| *n == best)
+        .map(|(lang, _)| *lang)
+        .collect();
+    match leaders.as_slice() {
+        [lang] => Some(*lang),
+        _ if leaders.contains(&Language::English) => Some(Language::English),
+        _ => None,
+    }
+}
+
 /// Deterministic tokenizer for BM25 text search.
 pub struct Tokenizer {
     stop_words: HashSet<&'static str>,
@@ -35,10 +196,49 @@ impl Tokenizer {
             .collect()
     }
 
+    /// Tokenize text written in `language`. `None` and English use the
+    /// default rules of [`tokenize`](Self::tokenize).
+    ///
+    /// Other languages drop their own stop words (if known) instead of the
+    /// English ones, and keep words of at least two characters. Chinese and
+    /// Japanese runs become overlapping character bigrams, since those
+    /// scripts do not separate words with spaces.
+    pub fn tokenize_as(&self, text: &str, language: Option<Language>) -> Vec<String> {
+        let language = match language {
+            None | Some(Language::English) => return self.tokenize(text),
+            Some(language) => language,
+        };
+        let stop_words = LATIN_CANDIDATES
+            .iter()
+            .find(|(lang, _)| *lang == language)
+            .map(|(_, stops)| *stops)
+            .unwrap_or(&[]);
+
+        let mut tokens = Vec::new();
+        for word in text.to_lowercase().split(|c: char| !c.is_alphanumeric()) {
+            if language.is_unsegmented() && word.chars().any(is_unsegmented_char) {
+                push_segmented(word, &mut tokens);
+            } else if word.chars().count() >= 2 && !stop_words.contains(&word) {
+                tokens.push(word.to_string());
+            }
+        }
+        tokens
+    }
+
     /// Tokenize and return term frequencies.
     pub fn term_frequencies(&self, text: &str) -> HashMap<String, u32> {
+        self.term_frequencies_as(text, None)
+    }
+
+    /// Term frequencies of text written in `language`, see
+    /// [`tokenize_as`](Self::tokenize_as).
+    pub fn term_frequencies_as(
+        &self,
+        text: &str,
+        language: Option<Language>,
+    ) -> HashMap<String, u32> {
         let mut freqs = HashMap::new();
-        for token in self.tokenize(text) {
+        for token in self.tokenize_as(text, language) {
             *freqs.entry(token).or_insert(0) += 1;
         }
         freqs
@@ -50,3 +250,29 @@ impl Default for Tokenizer {
         Self::new()
     }
 }
+
+/// Split a word mixing spaceless-script runs and other characters: the
+/// spaceless runs become character bigrams (a lone character stands alone),
+/// the rest are kept whole if at least two characters long.
+fn push_segmented(word: &str, tokens: &mut Vec<String>) {
+    let chars: Vec<char> = word.chars().collect();
+    let mut start = 0;
+    while start < chars.len() {
+        let unsegmented = is_unsegmented_char(chars[start]);
+        let mut end = start + 1;
+        while end < chars.len() && is_unsegmented_char(chars[end]) == unsegmented {
+            end += 1;
+        }
+        let run = &chars[start..end];
+        if unsegmented {
+            if run.len() == 1 {
+                tokens.push(run[0].to_string());
+            } else {
+                tokens.extend(run.windows(2).map(|pair| pair.iter().collect::<String>()));
+            }
+        } else if run.len() >= 2 {
+            tokens.push(run.iter().collect());
+        }
+        start = end;
+    }
+}
@@ -1,12 +1,17 @@
 //! Memory formation pipeline — the write engine.
 
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
 use crate::graph::MemoryGraph;
 use crate::types::{
-    now_micros, AmemError, AmemResult, CognitiveEvent, CognitiveEventBuilder, Edge, EdgeType,
-    EventType,
+    content_hash, system_clock, AmemError, AmemResult, Clock, CognitiveEvent,
+    CognitiveEventBuilder, Edge, EdgeType, EventType, MAX_EDGES_PER_NODE,
 };
 
-use super::decay::calculate_decay;
+use super::decay::{calculate_decay, DecayFn, DecayFunction};
+use super::normalize::ContentNormalizer;
+use super::tokenizer::detect_language;
 
 /// Result of an ingest operation.
 #[derive(Debug)]
@@ -15,10 +20,22 @@ pub struct IngestResult {
     pub new_node_ids: Vec<u64>,
     /// Number of new edges created.
     pub new_edge_count: usize,
+    /// Edges that duplicated an existing edge and were merged into it.
+    pub merged_edge_count: usize,
     /// IDs of nodes that were updated (touch count, last_accessed).
     pub touched_node_ids: Vec<u64>,
 }
 
+/// Result of [`WriteEngine::batch_ingest`].
+#[derive(Debug)]
+pub struct BatchIngestResult {
+    /// IDs assigned to the events, in input order.
+    pub node_ids: Vec<u64>,
+    /// Number of edges added. Edges merged into an earlier edge of the
+    /// batch are not counted.
+    pub edge_count: usize,
+}
+
 /// Report from running decay calculations.
 #[derive(Debug)]
 pub struct DecayReport {
@@ -26,17 +43,296 @@ pub struct DecayReport {
     pub nodes_decayed: usize,
     /// Nodes whose decay_score dropped below 0.1 (candidates for archival).
     pub low_importance_nodes: Vec<u64>,
+    /// Number of nodes visited by this run.
+    pub nodes_visited: usize,
+    /// Number of visited nodes skipped because they are pinned.
+    pub pinned_count: usize,
+    /// Whether the sweep has visited every node. A chunked sweep that
+    /// stopped early sets this to false.
+    pub complete: bool,
+    /// ID of the last node visited when the sweep is partial; pass it to
+    /// the next [`WriteEngine::run_decay_chunk`] call to resume.
+    pub cursor: Option<u64>,
+}
+
+/// How [`WriteEngine::merge_graphs`] combines the nodes of two graphs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MergeStrategy {
+    /// Keep one node per distinct content; later copies fold into the first.
+    #[default]
+    Deduplicate,
+    /// Keep every node of both graphs.
+    Union,
+    /// Keep only content present in both graphs, one node per content.
+    Intersect,
+}
+
+impl MergeStrategy {
+    /// Parse a strategy name (`deduplicate`, `union`, `intersect`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "deduplicate" | "dedup" => Some(Self::Deduplicate),
+            "union" => Some(Self::Union),
+            "intersect" => Some(Self::Intersect),
+            _ => None,
+        }
+    }
+
+    /// Canonical name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Deduplicate => "deduplicate",
+            Self::Union => "union",
+            Self::Intersect => "intersect",
+        }
+    }
+}
+
+/// Report from [`WriteEngine::merge_graphs_with_report`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MergeReport {
+    /// Nodes in the first graph.
+    pub nodes_a: usize,
+    /// Nodes in the second graph.
+    pub nodes_b: usize,
+    /// Nodes in the merged graph.
+    pub merged_nodes: usize,
+    /// Nodes folded into an earlier node with the same content.
+    pub duplicates_removed: usize,
+    /// Nodes dropped by `intersect` because their content is in one graph only.
+    pub unshared_dropped: usize,
+    /// Edges in the merged graph.
+    pub edges_merged: usize,
+    /// Offset added to the second graph's session IDs.
+    pub session_offset: u32,
+}
+
+/// Split `(created_at, id)` pairs, sorted by time, into contiguous chunks of
+/// at most `max`. Each cut goes at the widest time gap among the positions
+/// that keep the chunk at least half full, so chunks end where activity
+/// paused when it did. Always returns at least one (possibly empty) chunk.
+fn episode_chunks(members: &[(u64, u64)], max: usize) -> Vec<&[(u64, u64)]> {
+    let max = max.max(1);
+    let mut chunks = Vec::new();
+    let mut rest = members;
+    while rest.len() > max {
+        let min = max.div_ceil(2);
+        // Cutting after position `len` leaves a chunk of `len` nodes; the
+        // gap is the time to the next node. Ties keep the longer chunk.
+        let len = (min..=max)
+            .max_by_key(|&len| (rest[len].0.saturating_sub(rest[len - 1].0), len))
+            .unwrap_or(max);
+        let (chunk, tail) = rest.split_at(len);
+        chunks.push(chunk);
+        rest = tail;
+    }
+    chunks.push(rest);
+    chunks
+}
+
+/// What [`WriteEngine::ingest`] does with an edge whose source, target and
+/// type match an edge already in the graph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EdgeMergePolicy {
+    /// Keep the existing edge, raising its weight if the new one is heavier.
+    #[default]
+    KeepStronger,
+    /// Keep the existing edge unchanged and drop the new one.
+    KeepExisting,
+    /// Fail with [`AmemError::DuplicateEdge`].
+    Reject,
 }
 
 /// The write engine orchestrates memory formation.
+#[derive(Clone)]
 pub struct WriteEngine {
     dimension: usize,
+    clock: Arc<dyn Clock>,
+    detect_language: bool,
+    reassert_corrections: bool,
+    normalizer: ContentNormalizer,
+    edge_merge: EdgeMergePolicy,
+    allow_self_edges: bool,
+    max_events_per_episode: Option<usize>,
+    decay_fn: Option<Arc<DecayFn>>,
 }
 
 impl WriteEngine {
-    /// Create a new write engine.
+    /// Create a new write engine using wall-clock time.
     pub fn new(dimension: usize) -> Self {
-        Self { dimension }
+        Self {
+            dimension,
+            clock: system_clock(),
+            detect_language: false,
+            reassert_corrections: false,
+            normalizer: ContentNormalizer::none(),
+            edge_merge: EdgeMergePolicy::default(),
+            allow_self_edges: false,
+            max_events_per_episode: None,
+            decay_fn: None,
+        }
+    }
+
+    /// Create a write engine whose decay runs score nodes with `decay_fn`
+    /// instead of the built-in formula. See
+    /// [`with_decay_fn`](Self::with_decay_fn).
+    pub fn new_with_decay(dimension: usize, decay_fn: Box<DecayFn>) -> Self {
+        Self::new(dimension).with_decay_fn(decay_fn)
+    }
+
+    /// Use the given clock for access, correction and decay timestamps.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Detect the content language of new nodes and tag them with it, so
+    /// the text indexes tokenize them with per-language rules.
+    pub fn with_language_detection(mut self, on: bool) -> Self {
+        self.detect_language = on;
+        self
+    }
+
+    /// Whether new nodes are tagged with their detected language.
+    pub fn detects_language(&self) -> bool {
+        self.detect_language
+    }
+
+    /// Record a correction even when its content matches the node being
+    /// corrected. By default such a no-op correction adds nothing and
+    /// returns the existing node's ID.
+    pub fn with_reassert_corrections(mut self, on: bool) -> Self {
+        self.reassert_corrections = on;
+        self
+    }
+
+    /// Whether no-op corrections still create a new node.
+    pub fn reasserts_corrections(&self) -> bool {
+        self.reassert_corrections
+    }
+
+    /// Normalize the content of new and correcting nodes before they are
+    /// stored. Only the normalized text is kept; see [`ContentNormalizer`].
+    pub fn with_normalizer(mut self, normalizer: ContentNormalizer) -> Self {
+        self.normalizer = normalizer;
+        self
+    }
+
+    /// The normalizer applied to new content.
+    pub fn normalizer(&self) -> ContentNormalizer {
+        self.normalizer
+    }
+
+    /// How ingest treats an edge that duplicates an existing one.
+    pub fn with_edge_merge_policy(mut self, policy: EdgeMergePolicy) -> Self {
+        self.edge_merge = policy;
+        self
+    }
+
+    /// The policy applied to duplicate edges on ingest.
+    pub fn edge_merge_policy(&self) -> EdgeMergePolicy {
+        self.edge_merge
+    }
+
+    /// Accept edges from a node to itself on ingest. They are rejected with
+    /// [`AmemError::SelfEdge`] by default because they add nothing to
+    /// traversals and skew PageRank.
+    pub fn with_self_edges(mut self, on: bool) -> Self {
+        self.allow_self_edges = on;
+        self
+    }
+
+    /// Whether ingest accepts self-edges.
+    pub fn allows_self_edges(&self) -> bool {
+        self.allow_self_edges
+    }
+
+    /// Cap the nodes summarized by one episode. A larger session compresses
+    /// into several episodes, one per contiguous chunk. `None` (the default)
+    /// keeps one episode per session.
+    pub fn with_max_events_per_episode(mut self, max: Option<usize>) -> Self {
+        self.max_events_per_episode = max.map(|m| m.max(1));
+        self
+    }
+
+    /// The per-episode node cap, if any.
+    pub fn max_events_per_episode(&self) -> Option<usize> {
+        self.max_events_per_episode
+    }
+
+    /// Score nodes in decay runs with `decay_fn`, called as
+    /// `(current_decay, age_micros, access_count)` with the age measured
+    /// from the node's last access. Results are clamped to [0.0, 1.0].
+    pub fn with_decay_fn(mut self, decay_fn: Box<DecayFn>) -> Self {
+        self.decay_fn = Some(Arc::from(decay_fn));
+        self
+    }
+
+    /// Score nodes in decay runs with a built-in curve.
+    pub fn with_decay_function(self, function: DecayFunction) -> Self {
+        self.with_decay_fn(function.boxed())
+    }
+
+    /// Add one edge, applying the self-edge and duplicate-edge rules.
+    /// Returns whether it was merged into an existing edge.
+    fn add_checked_edge(&self, graph: &mut MemoryGraph, edge: Edge) -> AmemResult<bool> {
+        let existing = graph
+            .edges_from(edge.source_id)
+            .iter()
+            .find(|e| e.target_id == edge.target_id && e.edge_type == edge.edge_type)
+            .map(|e| e.weight);
+        let Some(existing_weight) = existing else {
+            if edge.source_id == edge.target_id && self.allow_self_edges {
+                graph.add_self_edge(edge)?;
+            } else {
+                graph.add_edge(edge)?;
+            }
+            return Ok(false);
+        };
+        match self.edge_merge {
+            EdgeMergePolicy::Reject => Err(AmemError::DuplicateEdge {
+                source_id: edge.source_id,
+                target_id: edge.target_id,
+                edge_type: graph.edge_type_name(edge.edge_type).to_string(),
+            }),
+            EdgeMergePolicy::KeepExisting => Ok(true),
+            EdgeMergePolicy::KeepStronger => {
+                if edge.weight > existing_weight {
+                    graph.set_edge_weight(
+                        edge.source_id,
+                        edge.target_id,
+                        edge.edge_type,
+                        edge.weight,
+                    )?;
+                }
+                Ok(true)
+            }
+        }
+    }
+
+    /// Whether correcting `node_id` to `new_content` would change nothing:
+    /// the content hashes match the node's current content once
+    /// `new_content` is normalized.
+    pub fn is_noop_correction(&self, graph: &MemoryGraph, node_id: u64, new_content: &str) -> bool {
+        let new_hash = content_hash(&self.normalizer.normalize(new_content));
+        graph
+            .get_node(node_id)
+            .is_some_and(|node| content_hash(&node.content) == new_hash)
+    }
+
+    /// Tag a freshly added node with its detected language, if enabled.
+    fn tag_language(&self, graph: &mut MemoryGraph, id: u64, content: &str) -> AmemResult<()> {
+        if self.detect_language {
+            if let Some(language) = detect_language(content) {
+                graph.set_node_language(id, Some(language))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Current time according to this engine's clock.
+    pub fn now_micros(&self) -> u64 {
+        self.clock.now_micros()
     }
 
     /// Process a batch of new cognitive events and integrate them into the graph.
@@ -50,16 +346,28 @@ impl WriteEngine {
         let mut touched_node_ids = Vec::new();
 
         // Step 1-3: Validate and add all events
-        for event in events {
+        for mut event in events {
+            if self.normalizer.is_enabled() {
+                event.content = self.normalizer.normalize(&event.content);
+            }
+            let content = self.detect_language.then(|| event.content.clone());
             let id = graph.add_node(event)?;
+            if let Some(content) = content {
+                self.tag_language(graph, id, &content)?;
+            }
             new_node_ids.push(id);
         }
 
-        // Step 4-5: Validate and add all edges
+        // Step 4-5: Validate and add all edges. Self-edges are rejected and
+        // duplicates merged per the engine's settings.
         let mut new_edge_count = 0;
+        let mut merged_edge_count = 0;
         for edge in edges {
-            graph.add_edge(edge)?;
-            new_edge_count += 1;
+            if self.add_checked_edge(graph, edge)? {
+                merged_edge_count += 1;
+            } else {
+                new_edge_count += 1;
+            }
         }
 
         // Ensure adjacency is rebuilt after bulk edge insertion
@@ -77,44 +385,156 @@ impl WriteEngine {
             }
         }
 
+        let now = self.now_micros();
         for &id in &touched_node_ids {
-            if let Some(node) = graph.get_node_mut(id) {
-                node.access_count += 1;
-                node.last_accessed = now_micros();
-            }
+            let _ = graph.record_access(id, now);
         }
 
         Ok(IngestResult {
             new_node_ids,
             new_edge_count,
+            merged_edge_count,
             touched_node_ids,
         })
     }
 
+    /// Insert a batch of events and the edges between them atomically.
+    ///
+    /// Edges name their endpoints by index into `events`. Every event and
+    /// edge is checked before the graph is touched, so a batch either goes
+    /// in whole or not at all. Errors report the ID an event would have
+    /// been given. Unlike [`ingest`](Self::ingest), edges to nodes outside
+    /// the batch cannot be expressed, and no existing node is touched.
+    pub fn batch_ingest(
+        &self,
+        graph: &mut MemoryGraph,
+        mut events: Vec<CognitiveEvent>,
+        edges: Vec<(usize, usize, EdgeType, f32)>,
+    ) -> AmemResult<BatchIngestResult> {
+        let first_id = graph.next_id();
+        let id_of = |index: usize| first_id + index as u64;
+
+        for event in &mut events {
+            if self.normalizer.is_enabled() {
+                event.content = self.normalizer.normalize(&event.content);
+            }
+            event.validate(graph.dimension())?;
+        }
+        let mut out_degree: HashMap<usize, usize> = HashMap::new();
+        let mut seen: HashSet<(usize, usize, EdgeType)> = HashSet::new();
+        for &(source, target, edge_type, _) in &edges {
+            if source >= events.len() {
+                return Err(AmemError::NodeNotFound(id_of(source)));
+            }
+            if target >= events.len() {
+                return Err(AmemError::InvalidEdgeTarget(id_of(target)));
+            }
+            if source == target && !self.allow_self_edges {
+                return Err(AmemError::SelfEdge(id_of(source)));
+            }
+            if !seen.insert((source, target, edge_type)) {
+                if self.edge_merge == EdgeMergePolicy::Reject {
+                    return Err(AmemError::DuplicateEdge {
+                        source_id: id_of(source),
+                        target_id: id_of(target),
+                        edge_type: graph.edge_type_name(edge_type).to_string(),
+                    });
+                }
+                continue;
+            }
+            let degree = out_degree.entry(source).or_insert(0);
+            *degree += 1;
+            if *degree > MAX_EDGES_PER_NODE as usize {
+                return Err(AmemError::TooManyEdges(MAX_EDGES_PER_NODE));
+            }
+        }
+
+        let commit = |graph: &mut MemoryGraph| -> AmemResult<BatchIngestResult> {
+            let mut node_ids = Vec::with_capacity(events.len());
+            for event in events {
+                let content = self.detect_language.then(|| event.content.clone());
+                let id = graph.add_node(event)?;
+                if let Some(content) = content {
+                    self.tag_language(graph, id, &content)?;
+                }
+                node_ids.push(id);
+            }
+            let mut edge_count = 0;
+            for (source, target, edge_type, weight) in edges {
+                let edge = Edge::new(node_ids[source], node_ids[target], edge_type, weight);
+                if !self.add_checked_edge(graph, edge)? {
+                    edge_count += 1;
+                }
+            }
+            graph.ensure_adjacency();
+            Ok(BatchIngestResult {
+                node_ids,
+                edge_count,
+            })
+        };
+        // Validation above covers every error the graph can raise; undo
+        // anyway should that ever change.
+        commit(graph).inspect_err(|_| graph.discard_since(first_id))
+    }
+
     /// Record a correction: marks old node as superseded, adds new node.
+    ///
+    /// The new node gets confidence 1.0 and a zero feature vector; use
+    /// [`WriteEngine::correct_with`] to set either at correction time. A
+    /// correction to identical content returns `old_node_id` and changes
+    /// nothing, unless [`with_reassert_corrections`](Self::with_reassert_corrections)
+    /// is on.
     pub fn correct(
         &self,
         graph: &mut MemoryGraph,
         old_node_id: u64,
         new_content: &str,
         session_id: u32,
+    ) -> AmemResult<u64> {
+        self.correct_with(graph, old_node_id, new_content, session_id, 1.0, None)
+    }
+
+    /// Record a correction with an explicit confidence and optional feature
+    /// vector for the new node. An out-of-range confidence or a vector of the
+    /// wrong dimension is rejected and leaves the graph untouched, and so is
+    /// a no-op correction (see [`WriteEngine::correct`]), which returns
+    /// `old_node_id`.
+    pub fn correct_with(
+        &self,
+        graph: &mut MemoryGraph,
+        old_node_id: u64,
+        new_content: &str,
+        session_id: u32,
+        confidence: f32,
+        feature_vec: Option<Vec<f32>>,
     ) -> AmemResult<u64> {
         // Verify old node exists
         if graph.get_node(old_node_id).is_none() {
             return Err(AmemError::NodeNotFound(old_node_id));
         }
+        if !(0.0..=1.0).contains(&confidence) {
+            return Err(AmemError::InvalidConfidence(confidence));
+        }
+        if !self.reassert_corrections && self.is_noop_correction(graph, old_node_id, new_content) {
+            return Ok(old_node_id);
+        }
 
-        // Create new correction node
-        let event = CognitiveEventBuilder::new(EventType::Correction, new_content)
+        // Create new correction node (add_node rejects a mismatched vector
+        // before the SUPERSEDES edge or the old node are touched)
+        let new_content = self.normalizer.normalize(new_content);
+        let now = self.now_micros();
+        let event = CognitiveEventBuilder::new(EventType::Correction, new_content.as_str())
             .session_id(session_id)
-            .confidence(1.0)
-            .feature_vec(vec![0.0; self.dimension])
+            .confidence(confidence)
+            .feature_vec(feature_vec.unwrap_or_else(|| vec![0.0; self.dimension]))
+            .created_at(now)
             .build();
 
         let new_id = graph.add_node(event)?;
+        self.tag_language(graph, new_id, &new_content)?;
 
         // Create SUPERSEDES edge from new to old
-        let edge = Edge::new(new_id, old_node_id, EdgeType::Supersedes, 1.0);
+        let edge = Edge::with_timestamp(new_id, old_node_id, EdgeType::Supersedes, 1.0, now);
         graph.add_edge(edge)?;
 
         // Ensure adjacency is rebuilt
@@ -128,58 +548,141 @@ impl WriteEngine {
         Ok(new_id)
     }
 
-    /// Compress a session into an episode node.
+    /// Compress a session into an episode node. With a
+    /// [`max_events_per_episode`](Self::with_max_events_per_episode) cap a
+    /// large session yields several episodes; this returns the first. See
+    /// [`compress_session_episodes`](Self::compress_session_episodes).
     pub fn compress_session(
         &self,
         graph: &mut MemoryGraph,
         session_id: u32,
         summary: &str,
     ) -> AmemResult<u64> {
-        // Find all nodes in this session
-        let session_node_ids: Vec<u64> = graph.session_index().get_session(session_id).to_vec();
+        let episodes = self.compress_session_episodes(graph, session_id, summary)?;
+        Ok(episodes[0])
+    }
 
-        // Create episode node
-        let event = CognitiveEventBuilder::new(EventType::Episode, summary)
-            .session_id(session_id)
-            .confidence(1.0)
-            .feature_vec(vec![0.0; self.dimension])
-            .build();
+    /// Compress a session into one episode per chunk of at most
+    /// `max_events_per_episode` nodes, in creation order. Each node gets a
+    /// PART_OF edge to its chunk's episode, and consecutive episodes are
+    /// chained with TEMPORAL_NEXT. When there is more than one chunk, each
+    /// summary is suffixed with `(part i of n)`. Returns the episode IDs in
+    /// chunk order.
+    pub fn compress_session_episodes(
+        &self,
+        graph: &mut MemoryGraph,
+        session_id: u32,
+        summary: &str,
+    ) -> AmemResult<Vec<u64>> {
+        // Find all nodes in this session, oldest first
+        let mut members: Vec<(u64, u64)> = graph
+            .session_index()
+            .get_session(session_id)
+            .iter()
+            .filter_map(|&id| graph.get_node(id).map(|n| (n.created_at, id)))
+            .collect();
+        members.sort_unstable();
+        let chunks = match self.max_events_per_episode {
+            Some(max) => episode_chunks(&members, max),
+            None => vec![members.as_slice()],
+        };
 
-        let episode_id = graph.add_node(event)?;
+        let now = self.now_micros();
+        let mut episode_ids = Vec::with_capacity(chunks.len());
+        for (i, chunk) in chunks.iter().enumerate() {
+            let content = if chunks.len() > 1 {
+                format!("{summary} (part {} of {})", i + 1, chunks.len())
+            } else {
+                summary.to_string()
+            };
+            let event = CognitiveEventBuilder::new(EventType::Episode, content)
+                .session_id(session_id)
+                .confidence(1.0)
+                .feature_vec(vec![0.0; self.dimension])
+                .created_at(now)
+                .build();
+            let episode_id = graph.add_node(event)?;
 
-        // Create PART_OF edges from each session node to the episode
-        for &node_id in &session_node_ids {
-            let edge = Edge::new(node_id, episode_id, EdgeType::PartOf, 1.0);
-            graph.add_edge(edge)?;
+            // Create PART_OF edges from each chunk node to the episode
+            for &(_, node_id) in chunk.iter() {
+                let edge = Edge::with_timestamp(node_id, episode_id, EdgeType::PartOf, 1.0, now);
+                graph.add_edge(edge)?;
+            }
+            if let Some(&prev) = episode_ids.last() {
+                let edge = Edge::with_timestamp(prev, episode_id, EdgeType::TemporalNext, 1.0, now);
+                graph.add_edge(edge)?;
+            }
+            episode_ids.push(episode_id);
         }
 
         // Ensure adjacency is rebuilt
         graph.ensure_adjacency();
 
-        Ok(episode_id)
+        Ok(episode_ids)
     }
 
-    /// Touch a node (update access_count and last_accessed).
+    /// Touch a node (update access_count, last_accessed and its access log).
     pub fn touch(&self, graph: &mut MemoryGraph, node_id: u64) -> AmemResult<()> {
-        let node = graph
-            .get_node_mut(node_id)
-            .ok_or(AmemError::NodeNotFound(node_id))?;
-        node.access_count += 1;
-        node.last_accessed = now_micros();
-        Ok(())
+        graph.record_access(node_id, self.now_micros())
+    }
+
+    /// Run decay calculations across all nodes as of this engine's clock.
+    pub fn run_decay_now(&self, graph: &mut MemoryGraph) -> AmemResult<DecayReport> {
+        self.run_decay(graph, self.now_micros())
     }
 
     /// Run decay calculations across all nodes.
     pub fn run_decay(&self, graph: &mut MemoryGraph, current_time: u64) -> AmemResult<DecayReport> {
+        self.run_decay_chunk(graph, current_time, None, usize::MAX)
+    }
+
+    /// Run decay calculations over at most `max_nodes` nodes, in ascending
+    /// ID order, starting after `cursor` (`None` starts a new sweep).
+    ///
+    /// Feed the report's `cursor` back in until `complete` is set. Node IDs
+    /// are never reused, so nodes added between chunks are visited later in
+    /// the same sweep and removed nodes are skipped.
+    pub fn run_decay_chunk(
+        &self,
+        graph: &mut MemoryGraph,
+        current_time: u64,
+        cursor: Option<u64>,
+        max_nodes: usize,
+    ) -> AmemResult<DecayReport> {
+        let max_nodes = max_nodes.max(1);
         let mut nodes_decayed = 0;
+        let mut pinned_count = 0;
         let mut low_importance_nodes = Vec::new();
 
         // Collect node IDs first to avoid borrow issues
-        let node_ids: Vec<u64> = graph.nodes().iter().map(|n| n.id).collect();
+        let mut node_ids: Vec<u64> = graph
+            .nodes()
+            .iter()
+            .map(|n| n.id)
+            .filter(|&id| Some(id) > cursor)
+            .collect();
+        let complete = node_ids.len() <= max_nodes;
+        if !complete {
+            node_ids.select_nth_unstable(max_nodes - 1);
+            node_ids.truncate(max_nodes);
+        }
+        node_ids.sort_unstable();
 
-        for id in node_ids {
+        for &id in &node_ids {
             if let Some(node) = graph.get_node_mut(id) {
-                let new_score = calculate_decay(node, current_time);
+                if node.pinned {
+                    pinned_count += 1;
+                    continue;
+                }
+                let new_score = match &self.decay_fn {
+                    Some(decay_fn) => decay_fn(
+                        node.decay_score,
+                        current_time.saturating_sub(node.last_accessed),
+                        u64::from(node.access_count),
+                    )
+                    .clamp(0.0, 1.0),
+                    None => calculate_decay(node, current_time),
+                };
                 if (new_score - node.decay_score).abs() > f32::EPSILON {
                     node.decay_score = new_score;
                     nodes_decayed += 1;
@@ -193,6 +696,179 @@ impl WriteEngine {
         Ok(DecayReport {
             nodes_decayed,
             low_importance_nodes,
+            nodes_visited: node_ids.len(),
+            pinned_count,
+            complete,
+            cursor: if complete {
+                None
+            } else {
+                node_ids.last().copied()
+            },
         })
     }
+
+    /// Merge two graphs into a new one. See
+    /// [`merge_graphs_with_report`](Self::merge_graphs_with_report).
+    pub fn merge_graphs(
+        &self,
+        a: &MemoryGraph,
+        b: &MemoryGraph,
+        strategy: MergeStrategy,
+    ) -> AmemResult<MemoryGraph> {
+        Ok(self.merge_graphs_with_report(a, b, strategy)?.0)
+    }
+
+    /// Merge two graphs into a new one with fresh node IDs: `a`'s nodes
+    /// first, then `b`'s. Content is compared by [`content_hash`].
+    ///
+    /// `b`'s session IDs are shifted past the highest session in `a`, and its
+    /// session aliases carried over unless `a` already uses the name. Edges
+    /// are remapped to the merged IDs; an edge whose endpoint was dropped, or
+    /// that folding turned into a self-edge or a repeat, is left out. Both
+    /// graphs must share a dimension and, when both record one, an
+    /// embedding model.
+    pub fn merge_graphs_with_report(
+        &self,
+        a: &MemoryGraph,
+        b: &MemoryGraph,
+        strategy: MergeStrategy,
+    ) -> AmemResult<(MemoryGraph, MergeReport)> {
+        if a.dimension() != b.dimension() {
+            return Err(AmemError::DimensionMismatch {
+                expected: a.dimension(),
+                got: b.dimension(),
+            });
+        }
+        if let (Some(model_a), Some(model_b)) = (a.embedding_model(), b.embedding_model()) {
+            if model_a != model_b {
+                return Err(AmemError::EmbeddingModelMismatch {
+                    file_model: model_a.to_string(),
+                    declared: model_b.to_string(),
+                });
+            }
+        }
+
+        let session_offset = a
+            .session_index()
+            .session_ids()
+            .into_iter()
+            .max()
+            .map_or(0, |max| max.saturating_add(1));
+        let shared: HashSet<u64> = match strategy {
+            MergeStrategy::Intersect => {
+                let in_b: HashSet<u64> =
+                    b.nodes().iter().map(|n| content_hash(&n.content)).collect();
+                a.nodes()
+                    .iter()
+                    .map(|n| content_hash(&n.content))
+                    .filter(|h| in_b.contains(h))
+                    .collect()
+            }
+            _ => HashSet::new(),
+        };
+
+        let mut nodes: Vec<CognitiveEvent> = Vec::with_capacity(a.node_count() + b.node_count());
+        let mut languages = Vec::new();
+        let mut by_content: HashMap<u64, u64> = HashMap::new();
+        // Per side: source ID -> merged ID.
+        let mut id_maps: [HashMap<u64, u64>; 2] = [HashMap::new(), HashMap::new()];
+        let mut duplicates_removed = 0;
+        let mut unshared_dropped = 0;
+
+        for (side, graph) in [a, b].into_iter().enumerate() {
+            for node in graph.nodes() {
+                let hash = content_hash(&node.content);
+                if strategy == MergeStrategy::Intersect && !shared.contains(&hash) {
+                    unshared_dropped += 1;
+                    continue;
+                }
+                if strategy != MergeStrategy::Union {
+                    if let Some(&existing) = by_content.get(&hash) {
+                        id_maps[side].insert(node.id, existing);
+                        duplicates_removed += 1;
+                        continue;
+                    }
+                }
+                let id = nodes.len() as u64;
+                let mut event = node.clone();
+                event.id = id;
+                if side == 1 {
+                    event.session_id = event.session_id.saturating_add(session_offset);
+                }
+                event.validate(a.dimension())?;
+                if event.feature_vec.is_empty() {
+                    event.feature_vec = vec![0.0; a.dimension()];
+                }
+                if let Some(language) = graph.node_language(node.id) {
+                    languages.push((id, language));
+                }
+                by_content.entry(hash).or_insert(id);
+                id_maps[side].insert(node.id, id);
+                nodes.push(event);
+            }
+        }
+
+        // Custom edge type ids are per graph; `b`'s are remapped by name.
+        let mut edge_type_names = a.edge_type_names().clone();
+        let mut edges = Vec::with_capacity(a.edge_count() + b.edge_count());
+        let mut seen: HashSet<(u64, u64, EdgeType)> = HashSet::new();
+        let mut out_degree: HashMap<u64, usize> = HashMap::new();
+        for (side, graph) in [a, b].into_iter().enumerate() {
+            for edge in graph.edges() {
+                let (Some(&source), Some(&target)) = (
+                    id_maps[side].get(&edge.source_id),
+                    id_maps[side].get(&edge.target_id),
+                ) else {
+                    continue;
+                };
+                if source == target && edge.source_id != edge.target_id {
+                    continue;
+                }
+                let edge_type = edge_type_names.import(edge.edge_type, graph.edge_type_names())?;
+                let degree = out_degree.entry(source).or_default();
+                if *degree >= MAX_EDGES_PER_NODE as usize
+                    || !seen.insert((source, target, edge_type))
+                {
+                    continue;
+                }
+                *degree += 1;
+                edges.push(Edge {
+                    source_id: source,
+                    target_id: target,
+                    edge_type,
+                    ..*edge
+                });
+            }
+        }
+
+        let mut merged = MemoryGraph::from_parts(nodes, edges, a.dimension())?;
+        merged.set_edge_type_names(edge_type_names);
+        merged.set_embedding_model(
+            a.embedding_model()
+                .or(b.embedding_model())
+                .map(str::to_string),
+        );
+        for (id, language) in languages {
+            merged.set_node_language(id, Some(language))?;
+        }
+        for (alias, session_id) in a.session_index().aliases() {
+            let _ = merged.rename_session(session_id, alias);
+        }
+        for (alias, session_id) in b.session_index().aliases() {
+            if merged.resolve_session(alias).is_err() {
+                let _ = merged.rename_session(session_id.saturating_add(session_offset), alias);
+            }
+        }
+
+        let report = MergeReport {
+            nodes_a: a.node_count(),
+            nodes_b: b.node_count(),
+            merged_nodes: merged.node_count(),
+            duplicates_removed,
+            unshared_dropped,
+            edges_merged: merged.edge_count(),
+            session_offset,
+        };
+        Ok((merged, report))
+    }
 }
@@ -343,7 +343,7 @@ pub unsafe extern "C" fn amem_graph_get_edges(
         for (i, edge) in edges.iter().take(count).enumerate() {
             unsafe {
                 *target_ids.add(i) = edge.target_id;
-                *edge_types.add(i) = edge.edge_type as u8;
+                *edge_types.add(i) = edge.edge_type.to_u8();
                 *weights.add(i) = edge.weight;
             }
         }
@@ -397,6 +397,7 @@ pub unsafe extern "C" fn amem_graph_traverse(
             max_depth,
             max_results: max_results as usize,
             min_confidence: 0.0,
+            symmetric_undirected: false,
         };
 
         match query_engine.traverse(graph_ref, params) {
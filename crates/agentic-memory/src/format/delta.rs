@@ -0,0 +1,412 @@
+//! Incremental backup deltas between two graph snapshots.
+//!
+//! A delta records what changed between a previous snapshot and the current
+//! one: removed node IDs, removed edges, every added or modified node and
+//! edge, and the tombstones recorded in between. Deltas form a chain on top of a full base backup; replaying them in
+//! sequence order reconstructs the snapshot each one was taken from.
+
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Write};
+use std::path::Path;
+
+use crate::graph::MemoryGraph;
+use crate::types::edge::{
+    custom_edge_types_from_bytes, custom_edge_types_to_bytes, decode_edge_type, EdgeTypeNames,
+};
+use crate::types::error::{AmemError, AmemResult};
+use crate::types::tombstone::Tombstone;
+use crate::types::{CognitiveEvent, Edge, EdgeType};
+
+use super::reader::AmemReader;
+use super::writer::AmemWriter;
+
+/// Magic bytes at the start of every delta file.
+pub const DELTA_MAGIC: [u8; 4] = *b"AMDL";
+
+/// Current delta format version. Version 2 added the custom edge type
+/// table after the removed edges; version 1 deltas are still read.
+pub const DELTA_VERSION: u16 = 2;
+
+/// Identity of an edge: (source, target, type).
+pub type EdgeKey = (u64, u64, EdgeType);
+
+/// Changes between two snapshots of a memory graph.
+pub struct GraphDelta {
+    /// File name of the full backup this chain starts from.
+    pub base: String,
+    /// 1-based position of this delta in its chain.
+    pub sequence: u32,
+    /// IDs of nodes present before and absent now.
+    pub removed_nodes: Vec<u64>,
+    /// Edges present before and absent now.
+    pub removed_edges: Vec<EdgeKey>,
+    /// Added or modified nodes, with their full current state.
+    pub nodes: Vec<CognitiveEvent>,
+    /// Added or modified edges.
+    pub edges: Vec<Edge>,
+    /// Feature vector dimension of the graph.
+    pub dimension: usize,
+    /// Names of the custom edge types in `removed_edges` and `edges`.
+    pub edge_type_names: EdgeTypeNames,
+    /// Tombstones recorded since the previous snapshot.
+    pub tombstones: Vec<Tombstone>,
+}
+
+impl GraphDelta {
+    /// Compute the changes that turn `previous` into `current`.
+    ///
+    /// Fails only when the two graphs together use more custom edge types
+    /// than one table can hold.
+    pub fn diff(
+        previous: &MemoryGraph,
+        current: &MemoryGraph,
+        base: impl Into<String>,
+        sequence: u32,
+    ) -> AmemResult<Self> {
+        let prev_nodes: HashMap<u64, &CognitiveEvent> =
+            previous.nodes().iter().map(|n| (n.id, n)).collect();
+        let curr_ids: HashSet<u64> = current.nodes().iter().map(|n| n.id).collect();
+
+        let mut removed_nodes: Vec<u64> = prev_nodes
+            .keys()
+            .copied()
+            .filter(|id| !curr_ids.contains(id))
+            .collect();
+        removed_nodes.sort_unstable();
+
+        let nodes: Vec<CognitiveEvent> = current
+            .nodes()
+            .iter()
+            .filter(|n| match prev_nodes.get(&n.id) {
+                Some(old) => !same_node(old, n),
+                None => true,
+            })
+            .cloned()
+            .collect();
+
+        // Custom edge types are compared by name, in `current`'s table.
+        let mut edge_type_names = current.edge_type_names().clone();
+        let previous_edges = previous
+            .edges()
+            .iter()
+            .map(|e| {
+                Ok(Edge {
+                    edge_type: edge_type_names.import(e.edge_type, previous.edge_type_names())?,
+                    ..*e
+                })
+            })
+            .collect::<AmemResult<Vec<Edge>>>()?;
+
+        // Edges are compared per key as a group, since a graph may hold
+        // several edges with the same source, target and type.
+        let prev_edges = group_edges(&previous_edges);
+        let curr_edges = group_edges(current.edges());
+
+        let mut removed_edges: Vec<EdgeKey> = prev_edges
+            .keys()
+            .copied()
+            .filter(|k| !curr_edges.contains_key(k))
+            .collect();
+        removed_edges.sort_unstable_by_key(|&(s, t, ty)| (s, t, ty.to_u8()));
+
+        let mut edges: Vec<Edge> = Vec::new();
+        for edge in current.edges() {
+            let key = edge_key(edge);
+            let group = &curr_edges[&key];
+            if !std::ptr::eq(group[0], edge) {
+                continue;
+            }
+            let unchanged = prev_edges.get(&key).is_some_and(|old| {
+                old.len() == group.len() && old.iter().zip(group).all(|(a, b)| same_edge(a, b))
+            });
+            if !unchanged {
+                edges.extend(group.iter().map(|e| **e));
+            }
+        }
+
+        let tombstones: Vec<Tombstone> = current
+            .tombstones()
+            .iter()
+            .filter(|t| !previous.tombstones().iter().any(|old| old.same_node(t)))
+            .copied()
+            .collect();
+
+        Ok(Self {
+            base: base.into(),
+            sequence,
+            removed_nodes,
+            removed_edges,
+            nodes,
+            edges,
+            dimension: current.dimension(),
+            edge_type_names,
+            tombstones,
+        })
+    }
+
+    /// Whether the delta carries no changes.
+    pub fn is_empty(&self) -> bool {
+        self.removed_nodes.is_empty()
+            && self.removed_edges.is_empty()
+            && self.nodes.is_empty()
+            && self.edges.is_empty()
+            && self.tombstones.is_empty()
+    }
+
+    /// Apply the delta on top of `graph`, returning the resulting graph.
+    pub fn apply(&self, graph: &MemoryGraph) -> AmemResult<MemoryGraph> {
+        let removed: HashSet<u64> = self.removed_nodes.iter().copied().collect();
+        let replaced: HashMap<u64, &CognitiveEvent> =
+            self.nodes.iter().map(|n| (n.id, n)).collect();
+
+        let mut nodes: Vec<CognitiveEvent> = graph
+            .nodes()
+            .iter()
+            .filter(|n| !removed.contains(&n.id) && !replaced.contains_key(&n.id))
+            .cloned()
+            .collect();
+        nodes.extend(self.nodes.iter().cloned());
+        nodes.sort_by_key(|n| n.id);
+
+        // Bring the delta's custom edge types into the base graph's table.
+        let mut edge_type_names = graph.edge_type_names().clone();
+        let delta_edges = self
+            .edges
+            .iter()
+            .map(|e| {
+                Ok(Edge {
+                    edge_type: edge_type_names.import(e.edge_type, &self.edge_type_names)?,
+                    ..*e
+                })
+            })
+            .collect::<AmemResult<Vec<Edge>>>()?;
+        // A removed edge of a type the base does not know is not there.
+        let removed_edges = self.removed_edges.iter().filter_map(|&(s, t, ty)| {
+            let name = self.edge_type_names.name(ty);
+            edge_type_names.get(name).map(|ty| (s, t, ty))
+        });
+        let dropped: HashSet<EdgeKey> = removed_edges
+            .chain(delta_edges.iter().map(edge_key))
+            .collect();
+        let mut edges: Vec<Edge> = graph
+            .edges()
+            .iter()
+            .filter(|e| !dropped.contains(&edge_key(e)))
+            .copied()
+            .collect();
+        edges.extend(delta_edges);
+
+        // Aliases, the embedding model, access logs and node languages are
+        // not part of the delta; keep the base's. Tombstones accumulate.
+        let mut applied = MemoryGraph::from_parts(nodes, edges, self.dimension)?;
+        applied.set_edge_type_names(edge_type_names);
+        for (alias, session_id) in graph.session_index().aliases() {
+            applied.rename_session(session_id, alias)?;
+        }
+        applied.set_embedding_model(graph.embedding_model().map(str::to_string));
+        for tombstone in graph.tombstones() {
+            applied.add_tombstone(*tombstone);
+        }
+        for tombstone in &self.tombstones {
+            applied.add_tombstone(*tombstone);
+        }
+        for (id, timestamps) in graph.access_logs().iter() {
+            if applied.get_node(id).is_some() {
+                for at in timestamps {
+                    applied.access_log.record(id, at);
+                }
+            }
+        }
+        for (&id, &language) in graph.node_languages() {
+            if applied.get_node(id).is_some() {
+                applied.node_languages.insert(id, language);
+            }
+        }
+        Ok(applied)
+    }
+
+    /// Write the delta to a file.
+    pub fn write_to_file(&self, path: &Path) -> AmemResult<()> {
+        let file = std::fs::File::create(path)?;
+        let mut writer = std::io::BufWriter::new(file);
+        self.write_to(&mut writer)
+    }
+
+    /// Serialize the delta.
+    ///
+    /// Layout: `[magic: 4][version: u16][sequence: u32][base_len: u16][base]`
+    /// `[removed_node_count: u64][ids: u64...]`
+    /// `[removed_edge_count: u64][(source: u64, target: u64, type: u8)...]`
+    /// `[custom_types_len: u32][custom edge type table]`
+    /// followed by the changed nodes and edges encoded as a regular `.amem` body,
+    /// whose tombstone section holds the delta's new tombstones.
+    pub fn write_to(&self, writer: &mut impl Write) -> AmemResult<()> {
+        writer.write_all(&DELTA_MAGIC)?;
+        writer.write_all(&DELTA_VERSION.to_le_bytes())?;
+        writer.write_all(&self.sequence.to_le_bytes())?;
+        let base = self.base.as_bytes();
+        writer.write_all(&(base.len() as u16).to_le_bytes())?;
+        writer.write_all(base)?;
+
+        writer.write_all(&(self.removed_nodes.len() as u64).to_le_bytes())?;
+        for id in &self.removed_nodes {
+            writer.write_all(&id.to_le_bytes())?;
+        }
+        writer.write_all(&(self.removed_edges.len() as u64).to_le_bytes())?;
+        for &(source, target, edge_type) in &self.removed_edges {
+            writer.write_all(&source.to_le_bytes())?;
+            writer.write_all(&target.to_le_bytes())?;
+            writer.write_all(&[edge_type.to_u8()])?;
+        }
+        let custom_types = custom_edge_types_to_bytes(
+            self.removed_edges.iter().map(|&(_, _, et)| et),
+            &self.edge_type_names,
+        );
+        writer.write_all(&(custom_types.len() as u32).to_le_bytes())?;
+        writer.write_all(&custom_types)?;
+
+        let mut changed =
+            MemoryGraph::from_parts(self.nodes.clone(), self.edges.clone(), self.dimension)?;
+        changed.set_edge_type_names(self.edge_type_names.clone());
+        for tombstone in &self.tombstones {
+            changed.add_tombstone(*tombstone);
+        }
+        AmemWriter::new(self.dimension).write_to(&changed, writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Read a delta from a file.
+    pub fn read_from_file(path: &Path) -> AmemResult<Self> {
+        let data = std::fs::read(path)?;
+        Self::read_from(&mut std::io::Cursor::new(data))
+    }
+
+    /// Deserialize a delta.
+    pub fn read_from(reader: &mut impl Read) -> AmemResult<Self> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        let mut pos = 0usize;
+
+        let magic = take(&data, &mut pos, 4)?;
+        if magic != DELTA_MAGIC {
+            return Err(AmemError::InvalidMagic);
+        }
+        let version = u16::from_le_bytes(take(&data, &mut pos, 2)?.try_into().unwrap());
+        if version == 0 || version > DELTA_VERSION {
+            return Err(AmemError::UnsupportedVersion(version as u32));
+        }
+        let sequence = u32::from_le_bytes(take(&data, &mut pos, 4)?.try_into().unwrap());
+        let base_len = u16::from_le_bytes(take(&data, &mut pos, 2)?.try_into().unwrap()) as usize;
+        let base = String::from_utf8(take(&data, &mut pos, base_len)?.to_vec())
+            .map_err(|_| AmemError::Corrupt(pos as u64))?;
+
+        let removed_node_count = read_u64(&data, &mut pos)? as usize;
+        let mut removed_nodes = Vec::with_capacity(removed_node_count.min(data.len() / 8));
+        for _ in 0..removed_node_count {
+            removed_nodes.push(read_u64(&data, &mut pos)?);
+        }
+
+        let removed_edge_count = read_u64(&data, &mut pos)? as usize;
+        let mut removed_edge_bytes = Vec::with_capacity(removed_edge_count.min(data.len() / 17));
+        for _ in 0..removed_edge_count {
+            let source = read_u64(&data, &mut pos)?;
+            let target = read_u64(&data, &mut pos)?;
+            let type_byte = take(&data, &mut pos, 1)?[0];
+            removed_edge_bytes.push((source, target, type_byte, pos - 1));
+        }
+        let mut edge_type_names = EdgeTypeNames::new();
+        let custom_types = if version >= 2 {
+            let len = u32::from_le_bytes(take(&data, &mut pos, 4)?.try_into().unwrap()) as usize;
+            custom_edge_types_from_bytes(take(&data, &mut pos, len)?, &mut edge_type_names)?
+        } else {
+            HashMap::new()
+        };
+        let mut removed_edges = Vec::with_capacity(removed_edge_bytes.len());
+        for (source, target, type_byte, at) in removed_edge_bytes {
+            let edge_type =
+                decode_edge_type(type_byte, &custom_types).ok_or(AmemError::Corrupt(at as u64))?;
+            removed_edges.push((source, target, edge_type));
+        }
+
+        let changed = AmemReader::read_from(&mut std::io::Cursor::new(&data[pos..]))?;
+        let edges = changed
+            .edges()
+            .iter()
+            .map(|e| {
+                Ok(Edge {
+                    edge_type: edge_type_names.import(e.edge_type, changed.edge_type_names())?,
+                    ..*e
+                })
+            })
+            .collect::<AmemResult<Vec<Edge>>>()?;
+
+        Ok(Self {
+            base,
+            sequence,
+            removed_nodes,
+            removed_edges,
+            nodes: changed.nodes().to_vec(),
+            edges,
+            dimension: changed.dimension(),
+            edge_type_names,
+            tombstones: changed.tombstones().to_vec(),
+        })
+    }
+}
+
+/// Replay a chain of deltas, in the order given, on top of a base graph.
+pub fn apply_chain(base: MemoryGraph, deltas: &[GraphDelta]) -> AmemResult<MemoryGraph> {
+    let mut graph = base;
+    for delta in deltas {
+        graph = delta.apply(&graph)?;
+    }
+    Ok(graph)
+}
+
+fn edge_key(edge: &Edge) -> EdgeKey {
+    (edge.source_id, edge.target_id, edge.edge_type)
+}
+
+fn group_edges(edges: &[Edge]) -> HashMap<EdgeKey, Vec<&Edge>> {
+    let mut groups: HashMap<EdgeKey, Vec<&Edge>> = HashMap::new();
+    for edge in edges {
+        groups.entry(edge_key(edge)).or_default().push(edge);
+    }
+    groups
+}
+
+fn same_node(a: &CognitiveEvent, b: &CognitiveEvent) -> bool {
+    a.event_type == b.event_type
+        && a.created_at == b.created_at
+        && a.session_id == b.session_id
+        && a.confidence.to_bits() == b.confidence.to_bits()
+        && a.access_count == b.access_count
+        && a.last_accessed == b.last_accessed
+        && a.decay_score.to_bits() == b.decay_score.to_bits()
+        && a.pinned == b.pinned
+        && a.content == b.content
+        && a.tags == b.tags
+        && a.feature_vec.len() == b.feature_vec.len()
+        && a.feature_vec
+            .iter()
+            .zip(&b.feature_vec)
+            .all(|(x, y)| x.to_bits() == y.to_bits())
+}
+
+fn same_edge(a: &Edge, b: &Edge) -> bool {
+    a.weight.to_bits() == b.weight.to_bits() && a.created_at == b.created_at
+}
+
+fn take<'a>(data: &'a [u8], pos: &mut usize, len: usize) -> AmemResult<&'a [u8]> {
+    if *pos + len > data.len() {
+        return Err(AmemError::Truncated);
+    }
+    let slice = &data[*pos..*pos + len];
+    *pos += len;
+    Ok(slice)
+}
+
+fn read_u64(data: &[u8], pos: &mut usize) -> AmemResult<u64> {
+    Ok(u64::from_le_bytes(take(data, pos, 8)?.try_into().unwrap()))
+}
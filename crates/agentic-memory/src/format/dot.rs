@@ -0,0 +1,80 @@
+//! Graphviz DOT export for visualizing a graph with `dot` or `neato`.
+
+use std::io::Write;
+
+use crate::graph::MemoryGraph;
+use crate::types::{AmemResult, EventType};
+
+/// Characters of content shown in a node label.
+pub const DOT_PREVIEW_CHARS: usize = 40;
+
+/// Node color for each event type.
+pub fn dot_color(event_type: EventType) -> &'static str {
+    match event_type {
+        EventType::Fact => "blue",
+        EventType::Decision => "orange",
+        EventType::Inference => "green",
+        EventType::Correction => "red",
+        EventType::Skill => "purple",
+        EventType::Episode => "gray",
+    }
+}
+
+/// Escape text for a double-quoted DOT string. Line breaks become spaces so
+/// a label stays on the lines the exporter chooses.
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' | '\r' | '\t' => out.push(' '),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// First [`DOT_PREVIEW_CHARS`] characters of `content`, with an ellipsis if
+/// it was cut.
+fn preview(content: &str) -> String {
+    let mut chars = content.chars();
+    let head: String = chars.by_ref().take(DOT_PREVIEW_CHARS).collect();
+    if chars.next().is_some() {
+        format!("{head}…")
+    } else {
+        head
+    }
+}
+
+/// Write `graph` as a DOT digraph.
+///
+/// Each node is `ID [label="TYPE\nPREVIEW" color=...]`, colored by event
+/// type; each edge is labeled with its edge type and carries its weight as
+/// a tooltip.
+pub fn write_dot(graph: &MemoryGraph, w: &mut dyn Write) -> AmemResult<()> {
+    writeln!(w, "digraph amem {{")?;
+    writeln!(w, "  node [shape=box];")?;
+    for node in graph.nodes() {
+        writeln!(
+            w,
+            "  {} [label=\"{}\\n{}\" color={}];",
+            node.id,
+            node.event_type.name().to_ascii_uppercase(),
+            escape(&preview(&node.content)),
+            dot_color(node.event_type)
+        )?;
+    }
+    for edge in graph.edges() {
+        writeln!(
+            w,
+            "  {} -> {} [label=\"{}\" tooltip=\"{:.2}\"];",
+            edge.source_id,
+            edge.target_id,
+            escape(graph.edge_type_name(edge.edge_type)),
+            edge.weight
+        )?;
+    }
+    writeln!(w, "}}")?;
+    Ok(())
+}
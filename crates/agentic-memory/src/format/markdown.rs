@@ -0,0 +1,121 @@
+//! Markdown export: one note per session, for Obsidian or Logseq vaults.
+//!
+//! The layout mirrors what `amem import-markdown` reads, so a fact whose
+//! content is a single trimmed line survives a round trip unchanged. Other
+//! facts do not: line breaks become spaces, surrounding whitespace is
+//! dropped, and one starting with "Decision:" or "I decided" is read back
+//! as a decision.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use crate::graph::MemoryGraph;
+use crate::types::{AmemResult, CognitiveEvent, EdgeType, EventType};
+
+/// File name of the note for `session_id`.
+pub fn markdown_file_name(session_id: u32) -> String {
+    format!("session-{session_id}.md")
+}
+
+/// Content on one line: line breaks become single spaces and each line is
+/// trimmed, so the text stays inside its bullet, heading or quote.
+fn one_line(content: &str) -> String {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Whether `text` already reads as a decision to `import-markdown`.
+fn reads_as_decision(text: &str) -> bool {
+    let lower = text.to_lowercase();
+    lower.starts_with("i decided") || lower.starts_with("decision:")
+}
+
+/// Write a blank line unless the last line written was blank.
+fn gap(w: &mut dyn Write, at_gap: &mut bool) -> std::io::Result<()> {
+    if !*at_gap {
+        writeln!(w)?;
+        *at_gap = true;
+    }
+    Ok(())
+}
+
+/// Write the note for one session.
+///
+/// Nodes appear in creation order: episodes as `##` headings, corrections
+/// as blockquotes, decisions as `- Decision: ...` bullets and everything
+/// else as plain bullets. A node's outgoing edges follow it on an indented
+/// line as wikilinks, e.g. `caused_by [[Node 42]]`; `temporal_next` edges
+/// are left out since the order already shows them.
+pub fn write_session_markdown(
+    graph: &MemoryGraph,
+    session_id: u32,
+    w: &mut dyn Write,
+) -> AmemResult<()> {
+    let mut nodes: Vec<&CognitiveEvent> = graph
+        .session_index()
+        .get_session(session_id)
+        .iter()
+        .filter_map(|&id| graph.get_node(id))
+        .collect();
+    nodes.sort_by_key(|n| (n.created_at, n.id));
+
+    let mut at_gap = true;
+    for node in nodes {
+        let text = one_line(&node.content);
+        match node.event_type {
+            EventType::Episode => {
+                gap(w, &mut at_gap)?;
+                writeln!(w, "## {text}")?;
+            }
+            EventType::Correction => {
+                gap(w, &mut at_gap)?;
+                writeln!(w, "> {text}")?;
+            }
+            EventType::Decision if !reads_as_decision(&text) => {
+                writeln!(w, "- Decision: {text}")?;
+            }
+            _ => writeln!(w, "- {text}")?,
+        }
+        at_gap = false;
+
+        let links: Vec<String> = graph
+            .edges_from(node.id)
+            .iter()
+            .filter(|e| e.edge_type != EdgeType::TemporalNext)
+            .map(|e| {
+                let name = graph.edge_type_name(e.edge_type);
+                format!("{name} [[Node {}]]", e.target_id)
+            })
+            .collect();
+        if !links.is_empty() {
+            writeln!(w, "  {}", links.join(", "))?;
+        }
+        if matches!(node.event_type, EventType::Episode | EventType::Correction) {
+            gap(w, &mut at_gap)?;
+        }
+    }
+    Ok(())
+}
+
+/// Write one Markdown note per session into `dir`, named by
+/// [`markdown_file_name`], or only the note for `session` if given.
+///
+/// The directory is created if needed and existing notes are overwritten.
+pub fn write_markdown(graph: &MemoryGraph, dir: &Path, session: Option<u32>) -> AmemResult<()> {
+    std::fs::create_dir_all(dir)?;
+    let sessions = match session {
+        Some(session_id) => vec![session_id],
+        None => graph.session_index().session_ids(),
+    };
+    for session_id in sessions {
+        let mut file = BufWriter::new(File::create(dir.join(markdown_file_name(session_id)))?);
+        write_session_markdown(graph, session_id, &mut file)?;
+        file.flush()?;
+    }
+    Ok(())
+}
@@ -0,0 +1,105 @@
+//! Storage-version migration for .amem files.
+//!
+//! A file whose header version predates [`FORMAT_VERSION`] is migrated by
+//! rewriting it with the current writer. Before anything is written, the
+//! original is copied to a checkpoint in `.amem-migrations/` next to the
+//! file, so a migration can always be undone by hand.
+
+use std::ffi::OsStr;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use crate::format::{AmemReader, AmemWriter};
+use crate::types::{AmemResult, FileHeader, FORMAT_VERSION};
+
+/// Directory, next to the brain file, that holds migration checkpoints.
+pub const MIGRATION_DIR: &str = ".amem-migrations";
+
+/// What migrating one file involves (or involved).
+#[derive(Debug, Clone)]
+pub struct MigrationPlan {
+    /// Storage version found in the file header.
+    pub from_version: u32,
+    /// Version the file is (or would be) rewritten at.
+    pub to_version: u32,
+    /// Nodes carried over by the rewrite.
+    pub node_count: usize,
+    /// Edges carried over by the rewrite.
+    pub edge_count: usize,
+    /// Checkpoint of the original file; `None` when no migration is needed.
+    pub checkpoint_path: Option<PathBuf>,
+}
+
+impl MigrationPlan {
+    /// Whether the file is older than the current storage version.
+    pub fn needed(&self) -> bool {
+        self.from_version < self.to_version
+    }
+}
+
+/// Storage version recorded in the header of `path`.
+///
+/// Fails for files that are not .amem files or that were written by a newer
+/// version of the format.
+pub fn storage_version(path: &Path) -> AmemResult<u32> {
+    let mut file = File::open(path)?;
+    Ok(FileHeader::read_from(&mut file)?.version)
+}
+
+/// Where the checkpoint for migrating `path` away from `from_version` goes,
+/// stamped with the current UTC time.
+pub fn checkpoint_path(path: &Path, from_version: u32) -> PathBuf {
+    let dir = path
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join(MIGRATION_DIR);
+    let stem = path.file_stem().and_then(OsStr::to_str).unwrap_or("brain");
+    let ts = chrono::Utc::now().format("%Y%m%d%H%M%S");
+    dir.join(format!("{stem}.v{from_version}.{ts}.amem.checkpoint"))
+}
+
+/// Copy `path` to a fresh migration checkpoint and return where it went.
+pub fn create_checkpoint(path: &Path, from_version: u32) -> AmemResult<PathBuf> {
+    let checkpoint = checkpoint_path(path, from_version);
+    if let Some(dir) = checkpoint.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::copy(path, &checkpoint)?;
+    Ok(checkpoint)
+}
+
+/// Describe what migrating `path` would do without writing anything: no
+/// checkpoint is created and the file is left untouched.
+pub fn plan(path: &Path) -> AmemResult<MigrationPlan> {
+    let from_version = storage_version(path)?;
+    let graph = AmemReader::read_from_file(path)?;
+    let needed = from_version < FORMAT_VERSION;
+    Ok(MigrationPlan {
+        from_version,
+        to_version: FORMAT_VERSION,
+        node_count: graph.node_count(),
+        edge_count: graph.edge_count(),
+        checkpoint_path: needed.then(|| checkpoint_path(path, from_version)),
+    })
+}
+
+/// Migrate `path` to the current storage version: checkpoint the original,
+/// then rewrite it in place. Files already at the current version are left
+/// alone.
+pub fn migrate(path: &Path) -> AmemResult<MigrationPlan> {
+    let from_version = storage_version(path)?;
+    let graph = AmemReader::read_from_file(path)?;
+    let mut plan = MigrationPlan {
+        from_version,
+        to_version: FORMAT_VERSION,
+        node_count: graph.node_count(),
+        edge_count: graph.edge_count(),
+        checkpoint_path: None,
+    };
+    if !plan.needed() {
+        return Ok(plan);
+    }
+    plan.checkpoint_path = Some(create_checkpoint(path, from_version)?);
+    AmemWriter::new(graph.dimension()).write_to_file(&graph, path)?;
+    Ok(plan)
+}
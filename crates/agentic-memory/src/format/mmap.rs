@@ -1,16 +1,20 @@
 //! Memory-mapped file access for .amem files.
 
+use std::collections::HashMap;
 use std::path::Path;
 
 use memmap2::Mmap;
 
 use crate::graph::MemoryGraph;
 use crate::index::cosine_similarity;
+use crate::types::edge::{decode_edge_type, EdgeTypeNames};
 use crate::types::error::{AmemError, AmemResult};
 use crate::types::header::FileHeader;
 use crate::types::{CognitiveEvent, Edge, EdgeType, EventType};
 
 use super::compression::decompress_content;
+use super::reader::{custom_edge_type_table, stored_dimension};
+use super::writer::NODE_FLAG_PINNED;
 
 /// A match result from a similarity search.
 #[derive(Debug, Clone)]
@@ -25,6 +29,10 @@ pub struct SimilarityMatch {
 pub struct MmapReader {
     mmap: Mmap,
     header: FileHeader,
+    /// Stored bytes of the file's custom edge types.
+    custom_types: HashMap<u8, EdgeType>,
+    /// Names of the custom types in `custom_types`.
+    edge_type_names: EdgeTypeNames,
 }
 
 impl MmapReader {
@@ -38,8 +46,23 @@ impl MmapReader {
         }
 
         let header = FileHeader::read_from(&mut std::io::Cursor::new(&mmap[..64]))?;
+        let stored = stored_dimension(&mmap, &header);
+        if stored != header.dimension as usize {
+            return Err(AmemError::DimensionMismatch {
+                expected: header.dimension as usize,
+                got: stored,
+            });
+        }
+
+        let mut edge_type_names = EdgeTypeNames::new();
+        let custom_types = custom_edge_type_table(&mmap, &header, stored, &mut edge_type_names)?;
 
-        Ok(Self { mmap, header })
+        Ok(Self {
+            mmap,
+            header,
+            custom_types,
+            edge_type_names,
+        })
     }
 
     /// Get the file header.
@@ -47,6 +70,11 @@ impl MmapReader {
         &self.header
     }
 
+    /// Names of the file's custom edge types, as used by the edges read.
+    pub fn edge_type_names(&self) -> &EdgeTypeNames {
+        &self.edge_type_names
+    }
+
     /// Read a single node record by ID (O(1) access).
     pub fn read_node(&self, id: u64) -> AmemResult<CognitiveEvent> {
         if id >= self.header.node_count {
@@ -153,7 +181,8 @@ impl MmapReader {
             let data = &self.mmap[offset..offset + 32];
             let source_id = u64::from_le_bytes(data[0..8].try_into().unwrap());
             let target_id = u64::from_le_bytes(data[8..16].try_into().unwrap());
-            let edge_type = EdgeType::from_u8(data[16]).ok_or(AmemError::Corrupt(offset as u64))?;
+            let edge_type = decode_edge_type(data[16], &self.custom_types)
+                .ok_or(AmemError::Corrupt(offset as u64))?;
             let weight = f32::from_le_bytes(data[20..24].try_into().unwrap());
             let created_at = u64::from_le_bytes(data[24..32].try_into().unwrap());
             edges.push(Edge {
@@ -189,7 +218,8 @@ impl MmapReader {
             let data = &self.mmap[offset..offset + 32];
             let source_id = u64::from_le_bytes(data[0..8].try_into().unwrap());
             let target_id = u64::from_le_bytes(data[8..16].try_into().unwrap());
-            let edge_type = EdgeType::from_u8(data[16]).ok_or(AmemError::Corrupt(offset as u64))?;
+            let edge_type = decode_edge_type(data[16], &self.custom_types)
+                .ok_or(AmemError::Corrupt(offset as u64))?;
             let weight = f32::from_le_bytes(data[20..24].try_into().unwrap());
             let created_at = u64::from_le_bytes(data[24..32].try_into().unwrap());
             edges.push(Edge {
@@ -201,7 +231,9 @@ impl MmapReader {
             });
         }
 
-        MemoryGraph::from_parts(nodes, edges, dimension)
+        let mut graph = MemoryGraph::from_parts(nodes, edges, dimension)?;
+        graph.set_edge_type_names(self.edge_type_names.clone());
+        Ok(graph)
     }
 
     /// Compute cosine similarity between a query and a node's feature vector.
@@ -286,5 +318,7 @@ fn parse_node_record_mmap(data: &[u8]) -> AmemResult<CognitiveEvent> {
         decay_score,
         content: String::new(),
         feature_vec: Vec::new(),
+        tags: HashMap::new(),
+        pinned: data[9] & NODE_FLAG_PINNED != 0,
     })
 }
@@ -1,10 +1,26 @@
 //! Binary file I/O for .amem files.
 
 pub mod compression;
+pub mod delta;
+pub mod dot;
+pub mod markdown;
+pub mod migration;
 pub mod mmap;
+#[cfg(feature = "parquet")]
+pub mod parquet;
 pub mod reader;
+#[cfg(feature = "signing")]
+pub mod signing;
 pub mod writer;
 
+#[cfg(feature = "parquet")]
+pub use self::parquet::write_parquet;
+pub use delta::GraphDelta;
+pub use dot::write_dot;
+pub use markdown::write_markdown;
+pub use migration::MigrationPlan;
 pub use mmap::{MmapReader, SimilarityMatch};
 pub use reader::AmemReader;
+#[cfg(feature = "signing")]
+pub use signing::SignatureStatus;
 pub use writer::AmemWriter;
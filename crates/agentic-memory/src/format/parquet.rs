@@ -0,0 +1,149 @@
+//! Parquet export for loading a graph into pandas, Polars or DuckDB.
+
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+
+use parquet::data_type::{ByteArray, ByteArrayType, FloatType, Int32Type, Int64Type};
+use parquet::errors::ParquetError;
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::schema::parser::parse_message_type;
+
+use crate::graph::MemoryGraph;
+use crate::types::{AmemError, AmemResult};
+
+/// Schema of the node file. IDs, sessions and access counts are unsigned;
+/// `created_at` is Unix microseconds.
+const NODE_SCHEMA: &str = "
+message amem_node {
+    REQUIRED INT64 id (INTEGER(64,false));
+    REQUIRED BINARY event_type (STRING);
+    REQUIRED INT64 created_at;
+    REQUIRED INT32 session_id (INTEGER(32,false));
+    REQUIRED FLOAT confidence;
+    REQUIRED FLOAT decay_score;
+    REQUIRED INT32 access_count (INTEGER(32,false));
+    REQUIRED BINARY content (STRING);
+}
+";
+
+/// Schema of the companion edge file.
+const EDGE_SCHEMA: &str = "
+message amem_edge {
+    REQUIRED INT64 source_id (INTEGER(64,false));
+    REQUIRED INT64 target_id (INTEGER(64,false));
+    REQUIRED BINARY edge_type (STRING);
+    REQUIRED FLOAT weight;
+    REQUIRED INT64 created_at;
+}
+";
+
+/// Values of one column, in schema order.
+enum Column {
+    Int64(Vec<i64>),
+    Int32(Vec<i32>),
+    Float(Vec<f32>),
+    Text(Vec<ByteArray>),
+}
+
+fn parquet_error(e: ParquetError) -> AmemError {
+    AmemError::Export(e.to_string())
+}
+
+/// Write one row group holding `columns` to a new file at `path`.
+fn write_columns(path: &Path, schema: &str, columns: Vec<Column>) -> AmemResult<()> {
+    let schema = Arc::new(parse_message_type(schema).map_err(parquet_error)?);
+    let props = Arc::new(WriterProperties::builder().build());
+    let mut writer =
+        SerializedFileWriter::new(File::create(path)?, schema, props).map_err(parquet_error)?;
+    let mut row_group = writer.next_row_group().map_err(parquet_error)?;
+    let mut columns = columns.into_iter();
+    while let Some(mut column_writer) = row_group.next_column().map_err(parquet_error)? {
+        let Some(column) = columns.next() else {
+            return Err(AmemError::Export(
+                "schema has more columns than data".into(),
+            ));
+        };
+        match column {
+            Column::Int64(values) => column_writer
+                .typed::<Int64Type>()
+                .write_batch(&values, None, None),
+            Column::Int32(values) => column_writer
+                .typed::<Int32Type>()
+                .write_batch(&values, None, None),
+            Column::Float(values) => column_writer
+                .typed::<FloatType>()
+                .write_batch(&values, None, None),
+            Column::Text(values) => column_writer
+                .typed::<ByteArrayType>()
+                .write_batch(&values, None, None),
+        }
+        .map_err(parquet_error)?;
+        column_writer.close().map_err(parquet_error)?;
+    }
+    row_group.close().map_err(parquet_error)?;
+    writer.close().map_err(parquet_error)?;
+    Ok(())
+}
+
+/// Write the nodes of `graph` to a Parquet file at `node_path`, and its
+/// edges to `edge_path` if given.
+///
+/// Node columns are `id, event_type, created_at, session_id, confidence,
+/// decay_score, access_count, content`; edge columns are `source_id,
+/// target_id, edge_type, weight, created_at`. Unsigned integers are stored
+/// with the Parquet unsigned annotation, so readers see them as `u64` and
+/// `u32`. Existing files are overwritten.
+pub fn write_parquet(
+    graph: &MemoryGraph,
+    node_path: &Path,
+    edge_path: Option<&Path>,
+) -> AmemResult<()> {
+    let nodes = graph.nodes();
+    write_columns(
+        node_path,
+        NODE_SCHEMA,
+        vec![
+            Column::Int64(nodes.iter().map(|n| n.id as i64).collect()),
+            Column::Text(
+                nodes
+                    .iter()
+                    .map(|n| ByteArray::from(n.event_type.name()))
+                    .collect(),
+            ),
+            Column::Int64(nodes.iter().map(|n| n.created_at as i64).collect()),
+            Column::Int32(nodes.iter().map(|n| n.session_id as i32).collect()),
+            Column::Float(nodes.iter().map(|n| n.confidence).collect()),
+            Column::Float(nodes.iter().map(|n| n.decay_score).collect()),
+            Column::Int32(nodes.iter().map(|n| n.access_count as i32).collect()),
+            Column::Text(
+                nodes
+                    .iter()
+                    .map(|n| ByteArray::from(n.content.as_str()))
+                    .collect(),
+            ),
+        ],
+    )?;
+
+    if let Some(edge_path) = edge_path {
+        let edges = graph.edges();
+        write_columns(
+            edge_path,
+            EDGE_SCHEMA,
+            vec![
+                Column::Int64(edges.iter().map(|e| e.source_id as i64).collect()),
+                Column::Int64(edges.iter().map(|e| e.target_id as i64).collect()),
+                Column::Text(
+                    edges
+                        .iter()
+                        .map(|e| ByteArray::from(graph.edge_type_name(e.edge_type)))
+                        .collect(),
+                ),
+                Column::Float(edges.iter().map(|e| e.weight).collect()),
+                Column::Int64(edges.iter().map(|e| e.created_at as i64).collect()),
+            ],
+        )?;
+    }
+    Ok(())
+}
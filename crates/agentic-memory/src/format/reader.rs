@@ -1,33 +1,99 @@
 //! Reads .amem files into in-memory graph.
 
+use std::collections::HashMap;
 use std::io::Read;
 use std::path::Path;
 
 use crate::graph::MemoryGraph;
-use crate::index::{DocLengths, TermIndex};
+use crate::index::{DocLengths, TermBloom, TermIndex};
+use crate::types::edge::{custom_edge_types_from_bytes, decode_edge_type, EdgeTypeNames};
 use crate::types::error::{AmemError, AmemResult};
-use crate::types::header::FileHeader;
-use crate::types::{CognitiveEvent, Edge, EdgeType, EventType};
+use crate::types::header::{feature_flags, FileHeader};
+use crate::types::language::languages_from_bytes;
+use crate::types::tags::node_tags_from_bytes;
+use crate::types::tombstone::tombstones_from_bytes;
+use crate::types::{AccessLog, CognitiveEvent, Edge, EdgeType, EventType, ENCRYPTED_MAGIC};
 
 use super::compression::decompress_content;
+use super::writer::NODE_FLAG_PINNED;
 
 /// Reader for .amem binary files.
 pub struct AmemReader;
 
 impl AmemReader {
     /// Read an .amem file into a MemoryGraph.
+    ///
+    /// An encrypted file fails with [`AmemError::InvalidKey`]; read it
+    /// with `read_encrypted` instead.
     pub fn read_from_file(path: &Path) -> AmemResult<MemoryGraph> {
         let data = std::fs::read(path)?;
+        if data.starts_with(&ENCRYPTED_MAGIC) {
+            return Err(AmemError::InvalidKey(format!(
+                "{} is encrypted; a key is required to read it",
+                path.display()
+            )));
+        }
         let mut cursor = std::io::Cursor::new(data);
         Self::read_from(&mut cursor)
     }
 
+    /// Read an AES-256-GCM encrypted .amem file with `key`.
+    ///
+    /// A wrong key fails with [`AmemError::Corrupt`], since AES-GCM cannot
+    /// tell it apart from a tampered file.
+    #[cfg(feature = "encryption")]
+    pub fn read_encrypted(
+        path: &Path,
+        key: &crate::types::encryption::EncryptionKey,
+    ) -> AmemResult<MemoryGraph> {
+        let data = crate::types::encryption::decrypt(&std::fs::read(path)?, key)?;
+        Self::parse(&data, None).map(|(graph, _)| graph)
+    }
+
+    /// Whether the file at `path` is an encrypted .amem file.
+    pub fn is_encrypted_file(path: &Path) -> AmemResult<bool> {
+        let mut magic = [0u8; 4];
+        let mut file = std::fs::File::open(path)?;
+        match file.read_exact(&mut magic) {
+            Ok(()) => Ok(magic == ENCRYPTED_MAGIC),
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Open a SQLite graph database, creating it if it does not exist.
+    /// See [`SqliteGraph`](crate::graph::SqliteGraph).
+    #[cfg(feature = "sqlite-backend")]
+    pub fn open_sqlite(path: &Path) -> AmemResult<Box<dyn crate::graph::GraphBackend>> {
+        Ok(Box::new(crate::graph::SqliteGraph::open(path)?))
+    }
+
     /// Read from any reader into a MemoryGraph.
+    ///
+    /// Fails with [`AmemError::DimensionMismatch`] when the header dimension
+    /// does not match the width the feature vectors were stored with.
     pub fn read_from(reader: &mut impl Read) -> AmemResult<MemoryGraph> {
         // Read all data into a buffer
         let mut data = Vec::new();
         reader.read_to_end(&mut data)?;
+        Self::parse(&data, None).map(|(graph, _)| graph)
+    }
+
+    /// Read a file whose header dimension may be wrong, padding or
+    /// truncating every feature vector to `dimension`.
+    ///
+    /// Returns the graph together with the dimension the vectors were
+    /// actually stored with.
+    pub fn read_resized(
+        reader: &mut impl Read,
+        dimension: usize,
+    ) -> AmemResult<(MemoryGraph, usize)> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        Self::parse(&data, Some(dimension))
+    }
 
+    fn parse(data: &[u8], resize: Option<usize>) -> AmemResult<(MemoryGraph, usize)> {
         if data.len() < 64 {
             return Err(AmemError::Truncated);
         }
@@ -35,7 +101,14 @@ impl AmemReader {
         // Parse header
         let header = FileHeader::read_from(&mut std::io::Cursor::new(&data[..64]))?;
 
-        let dimension = header.dimension as usize;
+        let stored = stored_dimension(data, &header);
+        if resize.is_none() && stored != header.dimension as usize {
+            return Err(AmemError::DimensionMismatch {
+                expected: header.dimension as usize,
+                got: stored,
+            });
+        }
+        let dimension = resize.unwrap_or(stored);
         let node_count = header.node_count as usize;
         let edge_count = header.edge_count as usize;
 
@@ -56,6 +129,8 @@ impl AmemReader {
         }
 
         // Read edge table
+        let mut edge_type_names = EdgeTypeNames::new();
+        let custom_types = custom_edge_type_table(data, &header, stored, &mut edge_type_names)?;
         let edge_table_start = header.edge_table_offset as usize;
         let mut edges: Vec<Edge> = Vec::with_capacity(edge_count);
 
@@ -65,7 +140,7 @@ impl AmemReader {
                 return Err(AmemError::Truncated);
             }
             let record = &data[offset..offset + 32];
-            edges.push(parse_edge_record(record)?);
+            edges.push(parse_edge_record(record, &custom_types)?);
         }
 
         // Read content block
@@ -85,25 +160,27 @@ impl AmemReader {
         // Read feature vectors
         let fv_start = header.feature_vec_offset as usize;
         for (i, node) in nodes.iter_mut().enumerate() {
-            let offset = fv_start + i * dimension * 4;
-            if offset + dimension * 4 > data.len() {
+            let offset = fv_start + i * stored * 4;
+            if offset + stored * 4 > data.len() {
                 return Err(AmemError::Truncated);
             }
             let mut vec = Vec::with_capacity(dimension);
-            for j in 0..dimension {
+            for j in 0..stored.min(dimension) {
                 let byte_offset = offset + j * 4;
                 let bytes: [u8; 4] = data[byte_offset..byte_offset + 4].try_into().unwrap();
                 vec.push(f32::from_le_bytes(bytes));
             }
+            vec.resize(dimension, 0.0);
             node.feature_vec = vec;
         }
 
         // Build graph from parts (this rebuilds type/temporal/session indexes)
         let mut graph = MemoryGraph::from_parts(nodes, edges, dimension)?;
+        graph.set_edge_type_names(edge_type_names);
 
         // Parse index block for new BM25 indexes (tags 0x05, 0x06)
         // The index block starts after the feature vector block
-        let index_block_start = fv_start + node_count * dimension * 4;
+        let index_block_start = fv_start + node_count * stored * 4;
         let mut pos = index_block_start;
 
         while pos + 9 <= data.len() {
@@ -136,6 +213,59 @@ impl AmemReader {
                     }
                     pos += length;
                 }
+                0x07 if header.has_flag(feature_flags::HAS_TERM_BLOOM) => {
+                    // Term Bloom Filters
+                    if let Some(tb) = TermBloom::from_bytes(&data[pos..pos + length]) {
+                        graph.set_term_bloom(tb);
+                    }
+                    pos += length;
+                }
+                0x08 if header.has_flag(feature_flags::HAS_SESSION_ALIASES) => {
+                    // Session Aliases
+                    graph.session_index.load_aliases(&data[pos..pos + length]);
+                    pos += length;
+                }
+                0x09 if header.has_flag(feature_flags::HAS_EMBEDDING_MODEL) => {
+                    // Embedding Model
+                    if let Ok(model) = std::str::from_utf8(&data[pos..pos + length]) {
+                        graph.set_embedding_model(Some(model.to_string()));
+                    }
+                    pos += length;
+                }
+                0x0A if header.has_flag(feature_flags::HAS_TOMBSTONES) => {
+                    // Tombstones
+                    if let Some(tombstones) = tombstones_from_bytes(&data[pos..pos + length]) {
+                        graph.tombstones = tombstones;
+                    }
+                    pos += length;
+                }
+                0x0B if header.has_flag(feature_flags::HAS_ACCESS_LOG) => {
+                    // Access Log
+                    if let Some(log) = AccessLog::from_bytes(&data[pos..pos + length]) {
+                        graph.access_log = log;
+                    }
+                    pos += length;
+                }
+                0x0C if header.has_flag(feature_flags::HAS_NODE_LANGUAGES) => {
+                    // Node Languages
+                    if let Some(languages) = languages_from_bytes(&data[pos..pos + length]) {
+                        graph.node_languages = languages;
+                    }
+                    pos += length;
+                }
+                0x0D => {
+                    // Custom Edge Types — already applied to the edge table
+                    pos += length;
+                }
+                0x0F if header.has_flag(feature_flags::HAS_NODE_TAGS) => {
+                    // Node Tags
+                    if let Some(tags) = node_tags_from_bytes(&data[pos..pos + length]) {
+                        for (id, node_tags) in tags {
+                            let _ = graph.set_node_tags(id, node_tags);
+                        }
+                    }
+                    pos += length;
+                }
                 _ => {
                     // Unknown tag — skip gracefully (forward compatibility)
                     pos += length;
@@ -143,8 +273,87 @@ impl AmemReader {
             }
         }
 
-        Ok(graph)
+        Ok((graph, stored))
+    }
+}
+
+/// Stored bytes of the file's custom edge types mapped to their types in
+/// `names`, read from the index block (tag 0x0D) and registered there.
+/// Empty when the file has none.
+pub(super) fn custom_edge_type_table(
+    data: &[u8],
+    header: &FileHeader,
+    stored: usize,
+    names: &mut EdgeTypeNames,
+) -> AmemResult<HashMap<u8, EdgeType>> {
+    if !header.has_flag(feature_flags::HAS_CUSTOM_EDGE_TYPES) {
+        return Ok(HashMap::new());
+    }
+    let mut pos = header.feature_vec_offset as usize + header.node_count as usize * stored * 4;
+    while pos + 9 <= data.len() {
+        let tag = data[pos];
+        let length = u64::from_le_bytes(data[pos + 1..pos + 9].try_into().unwrap()) as usize;
+        pos += 9;
+        if pos + length > data.len() {
+            break;
+        }
+        if tag == 0x0D {
+            return custom_edge_types_from_bytes(&data[pos..pos + length], names);
+        }
+        pos += length;
+    }
+    Ok(HashMap::new())
+}
+
+/// Width the feature vectors were actually written with.
+///
+/// The block has no length of its own, so the declared dimension is trusted
+/// when the index block chain starting right after it runs exactly to the
+/// end of the file. Otherwise every other width that fits is tried, looking
+/// for the type index (tag 0x01) that writers always put first. When nothing
+/// fits cleanly the declared dimension is kept and damage is left to the
+/// usual truncation handling.
+pub(super) fn stored_dimension(data: &[u8], header: &FileHeader) -> usize {
+    let declared = header.dimension as usize;
+    let node_count = header.node_count as usize;
+    let fv_start = header.feature_vec_offset as usize;
+    if node_count == 0 || fv_start > data.len() {
+        return declared;
+    }
+    let block_at = |dimension: usize| {
+        node_count
+            .checked_mul(dimension)
+            .and_then(|n| n.checked_mul(4))
+            .and_then(|n| n.checked_add(fv_start))
+    };
+    if block_at(declared).is_some_and(|start| index_chain_fits(data, start, false)) {
+        return declared;
+    }
+    let max = (data.len() - fv_start) / (node_count * 4);
+    (0..=max)
+        .filter(|&d| d != declared)
+        .find(|&d| block_at(d).is_some_and(|start| index_chain_fits(data, start, true)))
+        .unwrap_or(declared)
+}
+
+/// Whether `[tag][len][data]` sections starting at `pos` end exactly at EOF.
+///
+/// Tag 0 is never written, so a chain opening with it is zeroed vector data.
+fn index_chain_fits(data: &[u8], mut pos: usize, require_type_index: bool) -> bool {
+    match data.get(pos) {
+        Some(0) => return false,
+        Some(&tag) if require_type_index && tag != 0x01 => return false,
+        None if require_type_index => return false,
+        _ => {}
+    }
+    while pos + 9 <= data.len() {
+        let length = u64::from_le_bytes(data[pos + 1..pos + 9].try_into().unwrap());
+        match (pos as u64 + 9).checked_add(length) {
+            Some(end) if end <= data.len() as u64 => pos = end as usize,
+            _ => return false,
+        }
     }
+    pos == data.len()
 }
 
 /// Parse a 72-byte node record.
@@ -152,7 +361,8 @@ fn parse_node_record(data: &[u8]) -> AmemResult<(CognitiveEvent, u64, u32)> {
     let id = u64::from_le_bytes(data[0..8].try_into().unwrap());
     let event_type_byte = data[8];
     let event_type = EventType::from_u8(event_type_byte).ok_or(AmemError::Corrupt(0))?;
-    // bytes 9..12: padding
+    let flags = data[9];
+    // bytes 10..12: padding
     let created_at = u64::from_le_bytes(data[12..20].try_into().unwrap());
     let session_id = u32::from_le_bytes(data[20..24].try_into().unwrap());
     let confidence = f32::from_le_bytes(data[24..28].try_into().unwrap());
@@ -176,17 +386,19 @@ fn parse_node_record(data: &[u8]) -> AmemResult<(CognitiveEvent, u64, u32)> {
         decay_score,
         content: String::new(),  // Will be filled from content block
         feature_vec: Vec::new(), // Will be filled from feature vec block
+        tags: HashMap::new(),    // Will be filled from the node tags index
+        pinned: flags & NODE_FLAG_PINNED != 0,
     };
 
     Ok((event, content_offset, content_length))
 }
 
 /// Parse a 32-byte edge record.
-fn parse_edge_record(data: &[u8]) -> AmemResult<Edge> {
+fn parse_edge_record(data: &[u8], custom_types: &HashMap<u8, EdgeType>) -> AmemResult<Edge> {
     let source_id = u64::from_le_bytes(data[0..8].try_into().unwrap());
     let target_id = u64::from_le_bytes(data[8..16].try_into().unwrap());
     let edge_type_byte = data[16];
-    let edge_type = EdgeType::from_u8(edge_type_byte).ok_or(AmemError::Corrupt(0))?;
+    let edge_type = decode_edge_type(edge_type_byte, custom_types).ok_or(AmemError::Corrupt(0))?;
     // bytes 17..20: padding
     let weight = f32::from_le_bytes(data[20..24].try_into().unwrap());
     let created_at = u64::from_le_bytes(data[24..32].try_into().unwrap());
@@ -0,0 +1,165 @@
+//! Ed25519 signatures over .amem files (`signing` feature).
+//!
+//! A signature is stored as the last section of the index block:
+//! `[tag 0x0E][length: u64 = 68][trailer version: u32][signature: 64 bytes]`.
+//! It covers every byte of the file before that section. Readers skip the
+//! tag like any other unknown index, so signed files stay readable by builds
+//! without this feature.
+//!
+//! Any write replaces the whole file and drops the signature, so a signed
+//! brain must be re-signed after every save, either with [`sign_file`] or
+//! by giving the writer a key ([`AmemWriter::with_signing_key`]).
+//!
+//! [`AmemWriter::with_signing_key`]: super::AmemWriter::with_signing_key
+
+use std::path::Path;
+
+use ed25519_dalek::pkcs8::{DecodePrivateKey, DecodePublicKey};
+use ed25519_dalek::{Signature, Signer, Verifier, SIGNATURE_LENGTH};
+
+pub use ed25519_dalek::{SigningKey, VerifyingKey};
+
+use crate::types::error::{AmemError, AmemResult};
+use crate::types::header::FileHeader;
+
+use super::reader::stored_dimension;
+
+/// Index tag of the signature section.
+pub const SIGNATURE_TAG: u8 = 0x0E;
+/// Layout version written in the signature section.
+pub const SIGNATURE_TRAILER_VERSION: u32 = 1;
+/// Payload length of the signature section: version plus signature.
+const TRAILER_PAYLOAD_LEN: usize = 4 + SIGNATURE_LENGTH;
+
+/// Outcome of checking a file's signature against a public key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureStatus {
+    /// The signature matches the file body and the key.
+    Valid,
+    /// The file carries a signature, but not one made by this key over
+    /// these bytes.
+    Invalid,
+    /// The file has no signature section.
+    Unsigned,
+    /// The signature section has a layout version this build cannot read.
+    UnsupportedVersion(u32),
+}
+
+/// A signature section found at the end of a file.
+struct Trailer {
+    /// Offset of the section's tag byte; the signed body is `data[..start]`.
+    start: usize,
+    version: u32,
+    signature: [u8; SIGNATURE_LENGTH],
+}
+
+/// Locate the signature section, if the file ends with one.
+///
+/// The index block is walked section by section so that vector or index
+/// bytes that happen to look like a trailer are never mistaken for one.
+fn find_trailer(data: &[u8]) -> AmemResult<Option<Trailer>> {
+    if data.len() < 64 {
+        return Err(AmemError::Truncated);
+    }
+    let header = FileHeader::read_from(&mut std::io::Cursor::new(&data[..64]))?;
+    let stored = stored_dimension(data, &header);
+    let mut pos = header.feature_vec_offset as usize + header.node_count as usize * stored * 4;
+    let mut last = None;
+    while pos + 9 <= data.len() {
+        let length = u64::from_le_bytes(data[pos + 1..pos + 9].try_into().unwrap()) as usize;
+        let end = match (pos + 9).checked_add(length) {
+            Some(end) if end <= data.len() => end,
+            _ => return Err(AmemError::Corrupt(pos as u64)),
+        };
+        last = Some((pos, data[pos], length));
+        pos = end;
+    }
+    match last {
+        Some((start, SIGNATURE_TAG, length)) if length >= 4 => {
+            let payload = &data[start + 9..start + 9 + length];
+            let version = u32::from_le_bytes(payload[..4].try_into().unwrap());
+            let mut signature = [0u8; SIGNATURE_LENGTH];
+            if version == SIGNATURE_TRAILER_VERSION {
+                if length != TRAILER_PAYLOAD_LEN {
+                    return Err(AmemError::Corrupt(start as u64));
+                }
+                signature.copy_from_slice(&payload[4..]);
+            }
+            Ok(Some(Trailer {
+                start,
+                version,
+                signature,
+            }))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// The bytes a signature covers: the file without its signature section.
+pub fn signed_body(data: &[u8]) -> AmemResult<&[u8]> {
+    Ok(match find_trailer(data)? {
+        Some(trailer) => &data[..trailer.start],
+        None => data,
+    })
+}
+
+/// Whether the file ends with a signature section.
+pub fn is_signed(data: &[u8]) -> AmemResult<bool> {
+    Ok(find_trailer(data)?.is_some())
+}
+
+/// Sign an encoded file, replacing any signature it already has.
+pub fn sign_bytes(data: &[u8], key: &SigningKey) -> AmemResult<Vec<u8>> {
+    let body = signed_body(data)?;
+    let signature = key.sign(body);
+    let mut out = Vec::with_capacity(body.len() + 9 + TRAILER_PAYLOAD_LEN);
+    out.extend_from_slice(body);
+    out.push(SIGNATURE_TAG);
+    out.extend_from_slice(&(TRAILER_PAYLOAD_LEN as u64).to_le_bytes());
+    out.extend_from_slice(&SIGNATURE_TRAILER_VERSION.to_le_bytes());
+    out.extend_from_slice(&signature.to_bytes());
+    Ok(out)
+}
+
+/// Check an encoded file's signature against `key`.
+pub fn verify_bytes(data: &[u8], key: &VerifyingKey) -> AmemResult<SignatureStatus> {
+    let Some(trailer) = find_trailer(data)? else {
+        return Ok(SignatureStatus::Unsigned);
+    };
+    if trailer.version != SIGNATURE_TRAILER_VERSION {
+        return Ok(SignatureStatus::UnsupportedVersion(trailer.version));
+    }
+    let signature = Signature::from_bytes(&trailer.signature);
+    Ok(match key.verify(&data[..trailer.start], &signature) {
+        Ok(()) => SignatureStatus::Valid,
+        Err(_) => SignatureStatus::Invalid,
+    })
+}
+
+/// Sign the file at `path` in place.
+pub fn sign_file(path: &Path, key: &SigningKey) -> AmemResult<()> {
+    let signed = sign_bytes(&std::fs::read(path)?, key)?;
+    std::fs::write(path, signed)?;
+    Ok(())
+}
+
+/// Check the signature of the file at `path`.
+pub fn verify_file(path: &Path, key: &VerifyingKey) -> AmemResult<SignatureStatus> {
+    verify_bytes(&std::fs::read(path)?, key)
+}
+
+/// Load an Ed25519 private key from a PKCS#8 PEM file, as written by
+/// `openssl genpkey -algorithm ed25519`.
+pub fn load_signing_key(path: &Path) -> AmemResult<SigningKey> {
+    let pem = std::fs::read_to_string(path)?;
+    SigningKey::from_pkcs8_pem(&pem)
+        .map_err(|e| AmemError::InvalidKey(format!("{}: {e}", path.display())))
+}
+
+/// Load an Ed25519 public key from an SPKI PEM file, as written by
+/// `openssl pkey -pubout`.
+pub fn load_verifying_key(path: &Path) -> AmemResult<VerifyingKey> {
+    let pem = std::fs::read_to_string(path)?;
+    VerifyingKey::from_public_key_pem(&pem)
+        .map_err(|e| AmemError::InvalidKey(format!("{}: {e}", path.display())))
+}
@@ -5,9 +5,13 @@ use std::path::Path;
 
 use crate::engine::tokenizer::Tokenizer;
 use crate::graph::MemoryGraph;
-use crate::index::{DocLengths, TermIndex};
+use crate::index::{DocLengths, TermBloom, TermIndex};
+use crate::types::edge::custom_edge_types_to_bytes;
 use crate::types::error::AmemResult;
 use crate::types::header::{feature_flags, FileHeader, HEADER_SIZE};
+use crate::types::language::languages_to_bytes;
+use crate::types::tags::node_tags_to_bytes;
+use crate::types::tombstone::tombstones_to_bytes;
 use crate::types::{Edge, EventType, AMEM_MAGIC, FORMAT_VERSION};
 
 use super::compression::compress_content;
@@ -15,29 +19,148 @@ use super::compression::compress_content;
 /// Size of a single node record on disk: 72 bytes.
 const NODE_RECORD_SIZE: u64 = 72;
 
+/// Node record flag bit: the node is pinned against decay.
+pub(crate) const NODE_FLAG_PINNED: u8 = 0x01;
+
 /// Size of a single edge record on disk: 32 bytes.
 const EDGE_RECORD_SIZE: u64 = 32;
 
 /// Writer for .amem binary files.
 pub struct AmemWriter {
     dimension: usize,
+    /// Target false-positive rate for per-document term bloom filters.
+    /// `None` skips writing the filters.
+    term_bloom_fp_rate: Option<f32>,
+    /// Key every written file is signed with.
+    #[cfg(feature = "signing")]
+    signing_key: Option<ed25519_dalek::SigningKey>,
+    /// Key every file written by [`write_to_file`](Self::write_to_file)
+    /// is encrypted with.
+    #[cfg(feature = "encryption")]
+    encryption_key: Option<crate::types::encryption::EncryptionKey>,
 }
 
 impl AmemWriter {
     /// Create a new writer with the given feature vector dimension.
     pub fn new(dimension: usize) -> Self {
-        Self { dimension }
+        Self {
+            dimension,
+            term_bloom_fp_rate: None,
+            #[cfg(feature = "signing")]
+            signing_key: None,
+            #[cfg(feature = "encryption")]
+            encryption_key: None,
+        }
+    }
+
+    /// Also write per-document term bloom filters sized for `fp_rate`.
+    ///
+    /// Lower rates give larger files and fewer wasted posting lookups for
+    /// all-terms queries.
+    pub fn with_term_bloom(mut self, fp_rate: f32) -> Self {
+        self.term_bloom_fp_rate = Some(fp_rate);
+        self
+    }
+
+    /// Sign every written file with `key`, so a signed brain stays signed
+    /// across saves. See [`signing`](super::signing).
+    #[cfg(feature = "signing")]
+    pub fn with_signing_key(mut self, key: ed25519_dalek::SigningKey) -> Self {
+        self.signing_key = Some(key);
+        self
+    }
+
+    /// Encrypt every file written by [`write_to_file`](Self::write_to_file)
+    /// with `key`, so an encrypted brain stays encrypted across saves.
+    #[cfg(feature = "encryption")]
+    pub fn with_encryption_key(mut self, key: crate::types::encryption::EncryptionKey) -> Self {
+        self.encryption_key = Some(key);
+        self
     }
 
-    /// Write a complete MemoryGraph to an .amem file.
+    /// Write a complete MemoryGraph to an .amem file, streaming it through
+    /// a [`BufWriter`](std::io::BufWriter).
+    ///
+    /// A writer with an encryption key writes an encrypted file instead.
     pub fn write_to_file(&self, graph: &MemoryGraph, path: &Path) -> AmemResult<()> {
+        #[cfg(feature = "encryption")]
+        if let Some(key) = &self.encryption_key {
+            return self.write_encrypted(graph, path, key);
+        }
         let file = std::fs::File::create(path)?;
         let mut writer = std::io::BufWriter::new(file);
-        self.write_to(graph, &mut writer)
+        self.write_streaming(graph, &mut writer)
+    }
+
+    /// Write a MemoryGraph to an AES-256-GCM encrypted file under `key`.
+    ///
+    /// The whole file is built in memory and encrypted with a fresh random
+    /// nonce. See [`encryption`](crate::types::encryption) for the layout.
+    #[cfg(feature = "encryption")]
+    pub fn write_encrypted(
+        &self,
+        graph: &MemoryGraph,
+        path: &Path,
+        key: &crate::types::encryption::EncryptionKey,
+    ) -> AmemResult<()> {
+        let mut plain = Vec::new();
+        self.write_to(graph, &mut plain)?;
+        std::fs::write(path, crate::types::encryption::encrypt(&plain, key)?)?;
+        Ok(())
     }
 
     /// Write a complete MemoryGraph to any writer.
+    ///
+    /// The compressed content block is built in memory before the node
+    /// table is written.
     pub fn write_to(&self, graph: &MemoryGraph, writer: &mut impl Write) -> AmemResult<()> {
+        self.write_signed(graph, writer, false)
+    }
+
+    /// Write a complete MemoryGraph to any writer without buffering the
+    /// content block.
+    ///
+    /// Each node's content is compressed once to lay out the file and
+    /// again as the content block is written, trading CPU for a peak
+    /// memory that no longer grows with total content size. The output is
+    /// byte-for-byte what [`write_to`](Self::write_to) produces. A writer
+    /// with a signing key still buffers the body, since the signature
+    /// covers all of it.
+    pub fn write_streaming(
+        &self,
+        graph: &MemoryGraph,
+        mut writer: &mut dyn Write,
+    ) -> AmemResult<()> {
+        self.write_signed(graph, &mut writer, true)
+    }
+
+    fn write_signed(
+        &self,
+        graph: &MemoryGraph,
+        writer: &mut impl Write,
+        stream_content: bool,
+    ) -> AmemResult<()> {
+        #[cfg(feature = "signing")]
+        if let Some(key) = &self.signing_key {
+            let mut body = Vec::new();
+            self.write_body(graph, &mut body, stream_content)?;
+            writer.write_all(&super::signing::sign_bytes(&body, key)?)?;
+            return Ok(());
+        }
+        self.write_body(graph, writer, stream_content)
+    }
+
+    fn write_body(
+        &self,
+        graph: &MemoryGraph,
+        writer: &mut impl Write,
+        stream_content: bool,
+    ) -> AmemResult<()> {
+        debug_assert!(
+            graph.verify_indexes().is_consistent(),
+            "writing a graph whose indexes diverge: {}",
+            graph.verify_indexes()
+        );
         let nodes = graph.nodes();
         // Sort edges by source_id for correct edge offset computation
         let mut edges_sorted: Vec<Edge> = graph.edges().to_vec();
@@ -50,16 +173,21 @@ impl AmemWriter {
         let node_count = nodes.len() as u64;
         let edge_count = edges.len() as u64;
 
-        // Step 1: Compress all node contents and record offsets
-        let mut compressed_contents: Vec<Vec<u8>> = Vec::with_capacity(nodes.len());
+        // Step 1: Compress all node contents and record offsets. When
+        // streaming, only the sizes are kept and step 7 compresses again.
+        let mut compressed_contents: Vec<Vec<u8>> = Vec::new();
         let mut content_offsets: Vec<u64> = Vec::with_capacity(nodes.len());
+        let mut content_lengths: Vec<u32> = Vec::with_capacity(nodes.len());
         let mut content_total_size: u64 = 0;
 
         for node in nodes {
             let compressed = compress_content(&node.content)?;
             content_offsets.push(content_total_size);
+            content_lengths.push(compressed.len() as u32);
             content_total_size += compressed.len() as u64;
-            compressed_contents.push(compressed);
+            if !stream_content {
+                compressed_contents.push(compressed);
+            }
         }
 
         // Step 2: Calculate edge offsets per node
@@ -90,6 +218,30 @@ impl AmemWriter {
         let mut flags: u32 = 0;
         if graph.node_count() > 0 {
             flags |= feature_flags::HAS_TERM_INDEX | feature_flags::HAS_DOC_LENGTHS;
+            if self.term_bloom_fp_rate.is_some() {
+                flags |= feature_flags::HAS_TERM_BLOOM;
+            }
+        }
+        if !graph.session_index().aliases().is_empty() {
+            flags |= feature_flags::HAS_SESSION_ALIASES;
+        }
+        if graph.embedding_model().is_some() {
+            flags |= feature_flags::HAS_EMBEDDING_MODEL;
+        }
+        if !graph.tombstones().is_empty() {
+            flags |= feature_flags::HAS_TOMBSTONES;
+        }
+        if !graph.access_logs().is_empty() {
+            flags |= feature_flags::HAS_ACCESS_LOG;
+        }
+        if graph.has_node_languages() {
+            flags |= feature_flags::HAS_NODE_LANGUAGES;
+        }
+        if edges.iter().any(|e| e.edge_type.is_custom()) {
+            flags |= feature_flags::HAS_CUSTOM_EDGE_TYPES;
+        }
+        if graph.nodes().iter().any(|n| !n.tags.is_empty()) {
+            flags |= feature_flags::HAS_NODE_TAGS;
         }
 
         // Step 4: Write header
@@ -113,7 +265,7 @@ impl AmemWriter {
                 writer,
                 node,
                 content_offsets[i],
-                compressed_contents[i].len() as u32,
+                content_lengths[i],
                 edge_offsets[i].0,
                 edge_offsets[i].1,
             )?;
@@ -125,8 +277,14 @@ impl AmemWriter {
         }
 
         // Step 7: Write content block
-        for compressed in &compressed_contents {
-            writer.write_all(compressed)?;
+        if stream_content {
+            for node in nodes {
+                writer.write_all(&compress_content(&node.content)?)?;
+            }
+        } else {
+            for compressed in &compressed_contents {
+                writer.write_all(compressed)?;
+            }
         }
 
         // Step 8: Write feature vector block
@@ -244,6 +402,74 @@ impl AmemWriter {
             writer.write_all(&[0x06u8])?;
             writer.write_all(&(buf.len() as u64).to_le_bytes())?;
             writer.write_all(&buf)?;
+
+            // Term Bloom Filters (tag 0x07) — optional, gated by HAS_TERM_BLOOM
+            if let Some(fp_rate) = self.term_bloom_fp_rate {
+                let bloom = TermBloom::build(graph, &tokenizer, fp_rate);
+                let buf = bloom.to_bytes();
+                writer.write_all(&[0x07u8])?;
+                writer.write_all(&(buf.len() as u64).to_le_bytes())?;
+                writer.write_all(&buf)?;
+            }
+        }
+
+        // Session Aliases (tag 0x08) — optional, gated by HAS_SESSION_ALIASES
+        if !graph.session_index().aliases().is_empty() {
+            let buf = graph.session_index().aliases_to_bytes();
+            writer.write_all(&[0x08u8])?;
+            writer.write_all(&(buf.len() as u64).to_le_bytes())?;
+            writer.write_all(&buf)?;
+        }
+
+        // Embedding Model (tag 0x09) — optional, gated by HAS_EMBEDDING_MODEL
+        if let Some(model) = graph.embedding_model() {
+            writer.write_all(&[0x09u8])?;
+            writer.write_all(&(model.len() as u64).to_le_bytes())?;
+            writer.write_all(model.as_bytes())?;
+        }
+
+        // Tombstones (tag 0x0A) — optional, gated by HAS_TOMBSTONES
+        if !graph.tombstones().is_empty() {
+            let buf = tombstones_to_bytes(graph.tombstones());
+            writer.write_all(&[0x0Au8])?;
+            writer.write_all(&(buf.len() as u64).to_le_bytes())?;
+            writer.write_all(&buf)?;
+        }
+
+        // Access Log (tag 0x0B) — optional, gated by HAS_ACCESS_LOG
+        if !graph.access_logs().is_empty() {
+            let buf = graph.access_logs().to_bytes();
+            writer.write_all(&[0x0Bu8])?;
+            writer.write_all(&(buf.len() as u64).to_le_bytes())?;
+            writer.write_all(&buf)?;
+        }
+
+        // Node Languages (tag 0x0C) — optional, gated by HAS_NODE_LANGUAGES
+        if graph.has_node_languages() {
+            let buf = languages_to_bytes(graph.node_languages());
+            writer.write_all(&[0x0Cu8])?;
+            writer.write_all(&(buf.len() as u64).to_le_bytes())?;
+            writer.write_all(&buf)?;
+        }
+
+        // Custom Edge Types (tag 0x0D) — optional, gated by HAS_CUSTOM_EDGE_TYPES
+        if graph.edges().iter().any(|e| e.edge_type.is_custom()) {
+            let buf = custom_edge_types_to_bytes(
+                graph.edges().iter().map(|e| e.edge_type),
+                graph.edge_type_names(),
+            );
+            writer.write_all(&[0x0Du8])?;
+            writer.write_all(&(buf.len() as u64).to_le_bytes())?;
+            writer.write_all(&buf)?;
+        }
+
+        // Node Tags (tag 0x0F) — optional, gated by HAS_NODE_TAGS. Tag 0x0E
+        // is reserved for the signature section, which must come last.
+        if graph.nodes().iter().any(|n| !n.tags.is_empty()) {
+            let buf = node_tags_to_bytes(graph.nodes());
+            writer.write_all(&[0x0Fu8])?;
+            writer.write_all(&(buf.len() as u64).to_le_bytes())?;
+            writer.write_all(&buf)?;
         }
 
         Ok(())
@@ -261,7 +487,8 @@ fn write_node_record(
 ) -> AmemResult<()> {
     writer.write_all(&node.id.to_le_bytes())?; // 8 bytes
     writer.write_all(&[node.event_type as u8])?; // 1 byte
-    writer.write_all(&[0u8; 3])?; // 3 bytes padding
+    let flags = if node.pinned { NODE_FLAG_PINNED } else { 0 };
+    writer.write_all(&[flags, 0, 0])?; // 1 byte flags + 2 bytes padding
     writer.write_all(&node.created_at.to_le_bytes())?; // 8 bytes
     writer.write_all(&node.session_id.to_le_bytes())?; // 4 bytes
     writer.write_all(&node.confidence.to_le_bytes())?; // 4 bytes
@@ -273,7 +500,7 @@ fn write_node_record(
     writer.write_all(&edge_offset.to_le_bytes())?; // 8 bytes
     writer.write_all(&edge_count.to_le_bytes())?; // 2 bytes
     writer.write_all(&[0u8; 6])?; // 6 bytes padding
-                                  // Total: 8+1+3+8+4+4+4+8+4+8+4+8+2+6 = 72
+                                  // Total: 8+1+1+2+8+4+4+4+8+4+8+4+8+2+6 = 72
     Ok(())
 }
 
@@ -281,7 +508,7 @@ fn write_node_record(
 fn write_edge_record(writer: &mut impl Write, edge: &crate::types::Edge) -> AmemResult<()> {
     writer.write_all(&edge.source_id.to_le_bytes())?; // 8 bytes
     writer.write_all(&edge.target_id.to_le_bytes())?; // 8 bytes
-    writer.write_all(&[edge.edge_type as u8])?; // 1 byte
+    writer.write_all(&[edge.edge_type.to_u8()])?; // 1 byte
     writer.write_all(&[0u8; 3])?; // 3 bytes padding
     writer.write_all(&edge.weight.to_le_bytes())?; // 4 bytes
     writer.write_all(&edge.created_at.to_le_bytes())?; // 8 bytes
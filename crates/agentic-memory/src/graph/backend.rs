@@ -0,0 +1,179 @@
+//! Storage backends behind a common graph interface.
+
+use crate::types::{AmemError, AmemResult, CognitiveEvent, Edge, EdgeType, EdgeTypeNames};
+
+use super::{GraphChanges, MemoryGraph};
+
+/// A graph store: the node and edge operations of [`MemoryGraph`], for
+/// stores that may live on disk.
+///
+/// Reads return owned values and every call may fail, since a backend can
+/// hit storage on each of them. Only nodes and edges are kept; graph-level
+/// data such as session aliases, tombstones and access logs belongs to the
+/// in-memory graph.
+pub trait GraphBackend: Send {
+    /// Feature vector dimension.
+    fn dimension(&self) -> usize;
+
+    /// Number of nodes.
+    fn node_count(&self) -> AmemResult<usize>;
+
+    /// Number of edges.
+    fn edge_count(&self) -> AmemResult<usize>;
+
+    /// Add a node, returns the assigned ID.
+    fn add_node(&mut self, event: CognitiveEvent) -> AmemResult<u64>;
+
+    /// Add an edge between two existing nodes.
+    fn add_edge(&mut self, edge: Edge) -> AmemResult<()>;
+
+    /// Store a node under the ID it carries, replacing the node with that
+    /// ID if there is one. Its edges are kept.
+    fn put_node(&mut self, event: CognitiveEvent) -> AmemResult<()>;
+
+    /// Remove a node and every edge touching it. A missing node is not an
+    /// error.
+    fn remove_node(&mut self, id: u64) -> AmemResult<()>;
+
+    /// Replace the edges leaving `source_id` with `edges`, whose custom
+    /// types are named in `names`.
+    fn set_edges_from(
+        &mut self,
+        source_id: u64,
+        edges: &[Edge],
+        names: &EdgeTypeNames,
+    ) -> AmemResult<()>;
+
+    /// Get a node by ID.
+    fn get_node(&self, id: u64) -> AmemResult<Option<CognitiveEvent>>;
+
+    /// All nodes, in ID order.
+    fn nodes(&self) -> AmemResult<Vec<CognitiveEvent>>;
+
+    /// All edges, ordered by source and then target.
+    fn edges(&self) -> AmemResult<Vec<Edge>>;
+
+    /// Edges leaving `source_id`.
+    fn edges_from(&self, source_id: u64) -> AmemResult<Vec<Edge>>;
+
+    /// Edges pointing at `target_id`.
+    fn edges_to(&self, target_id: u64) -> AmemResult<Vec<Edge>>;
+
+    /// Names of the custom edge types the store's edges may use.
+    fn edge_type_names(&self) -> &EdgeTypeNames;
+
+    /// The edge type called `name`, registering a new custom type in the
+    /// store if needed (see [`EdgeTypeNames::register`]).
+    fn register_edge_type(&mut self, name: &str) -> AmemResult<EdgeType>;
+
+    /// Load the whole store into a [`MemoryGraph`].
+    fn to_memory_graph(&self) -> AmemResult<MemoryGraph> {
+        let mut graph = MemoryGraph::from_parts(self.nodes()?, self.edges()?, self.dimension())?;
+        graph.set_edge_type_names(self.edge_type_names().clone());
+        Ok(graph)
+    }
+
+    /// Replace the store's contents with the nodes and edges of `graph`.
+    fn store(&mut self, graph: &MemoryGraph) -> AmemResult<()>;
+
+    /// Write to the store only what `changes` records as changed in
+    /// `graph`, which the store held before those changes.
+    fn apply_changes(&mut self, graph: &MemoryGraph, changes: &GraphChanges) -> AmemResult<()> {
+        for &id in &changes.removed_nodes {
+            self.remove_node(id)?;
+        }
+        for &id in &changes.nodes {
+            if let Some(node) = graph.get_node(id) {
+                self.put_node(node.clone())?;
+            }
+        }
+        for &id in &changes.edge_sources {
+            if graph.get_node(id).is_some() {
+                self.set_edges_from(id, graph.edges_from(id), graph.edge_type_names())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl GraphBackend for MemoryGraph {
+    fn dimension(&self) -> usize {
+        MemoryGraph::dimension(self)
+    }
+
+    fn node_count(&self) -> AmemResult<usize> {
+        Ok(MemoryGraph::node_count(self))
+    }
+
+    fn edge_count(&self) -> AmemResult<usize> {
+        Ok(MemoryGraph::edge_count(self))
+    }
+
+    fn add_node(&mut self, event: CognitiveEvent) -> AmemResult<u64> {
+        MemoryGraph::add_node(self, event)
+    }
+
+    fn add_edge(&mut self, edge: Edge) -> AmemResult<()> {
+        MemoryGraph::add_edge(self, edge)
+    }
+
+    fn put_node(&mut self, event: CognitiveEvent) -> AmemResult<()> {
+        MemoryGraph::put_node(self, event)
+    }
+
+    fn remove_node(&mut self, id: u64) -> AmemResult<()> {
+        match self.remove_node_untracked(id) {
+            Ok(_) | Err(AmemError::NodeNotFound(_)) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn set_edges_from(
+        &mut self,
+        source_id: u64,
+        edges: &[Edge],
+        names: &EdgeTypeNames,
+    ) -> AmemResult<()> {
+        MemoryGraph::set_edges_from(self, source_id, edges, names)
+    }
+
+    fn get_node(&self, id: u64) -> AmemResult<Option<CognitiveEvent>> {
+        Ok(MemoryGraph::get_node(self, id).cloned())
+    }
+
+    fn nodes(&self) -> AmemResult<Vec<CognitiveEvent>> {
+        Ok(MemoryGraph::nodes(self).to_vec())
+    }
+
+    fn edges(&self) -> AmemResult<Vec<Edge>> {
+        Ok(MemoryGraph::edges(self).to_vec())
+    }
+
+    fn edges_from(&self, source_id: u64) -> AmemResult<Vec<Edge>> {
+        Ok(MemoryGraph::edges_from(self, source_id).to_vec())
+    }
+
+    fn edges_to(&self, target_id: u64) -> AmemResult<Vec<Edge>> {
+        Ok(MemoryGraph::edges_to(self, target_id)
+            .into_iter()
+            .copied()
+            .collect())
+    }
+
+    fn edge_type_names(&self) -> &EdgeTypeNames {
+        MemoryGraph::edge_type_names(self)
+    }
+
+    fn register_edge_type(&mut self, name: &str) -> AmemResult<EdgeType> {
+        MemoryGraph::register_edge_type(self, name)
+    }
+
+    fn to_memory_graph(&self) -> AmemResult<MemoryGraph> {
+        Ok(self.clone())
+    }
+
+    fn store(&mut self, graph: &MemoryGraph) -> AmemResult<()> {
+        *self = graph.clone();
+        Ok(())
+    }
+}
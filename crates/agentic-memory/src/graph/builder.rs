@@ -1,28 +1,36 @@
 //! Fluent API for building MemoryGraph instances.
 
 use crate::types::{
-    AmemResult, CognitiveEvent, CognitiveEventBuilder, Edge, EdgeType, EventType, DEFAULT_DIMENSION,
+    AmemError, AmemResult, CognitiveEvent, CognitiveEventBuilder, Edge, EdgeType, EventType,
+    DEFAULT_DIMENSION,
 };
 
 use super::MemoryGraph;
 
 /// Fluent builder for constructing a MemoryGraph.
+///
+/// The short adders (`fact`, `decision`, ...) return the new node's ID and
+/// use the session and confidence set with [`session`](Self::session) and
+/// [`confidence`](Self::confidence), e.g.
+/// `let id = builder.session(1).confidence(0.9).fact("Team uses Postgres");`.
+///
+/// Edge endpoints are checked as edges are added. The first invalid edge or
+/// node reference is remembered and returned by [`build`](Self::build), so a
+/// chain of calls never drops a mistake silently.
 pub struct GraphBuilder {
     dimension: usize,
     nodes: Vec<CognitiveEvent>,
     edges: Vec<Edge>,
     next_id: u64,
+    session_id: u32,
+    confidence: f32,
+    error: Option<AmemError>,
 }
 
 impl GraphBuilder {
     /// Create a new builder with the default dimension.
     pub fn new() -> Self {
-        Self {
-            dimension: DEFAULT_DIMENSION,
-            nodes: Vec::new(),
-            edges: Vec::new(),
-            next_id: 0,
-        }
+        Self::with_dimension(DEFAULT_DIMENSION)
     }
 
     /// Create a new builder with a specific dimension.
@@ -32,9 +40,65 @@ impl GraphBuilder {
             nodes: Vec::new(),
             edges: Vec::new(),
             next_id: 0,
+            session_id: 0,
+            confidence: 1.0,
+            error: None,
         }
     }
 
+    /// Session for nodes added with the short adders from now on (default 0).
+    pub fn session(&mut self, session_id: u32) -> &mut Self {
+        self.session_id = session_id;
+        self
+    }
+
+    /// Confidence for nodes added with the short adders from now on
+    /// (default 1.0).
+    pub fn confidence(&mut self, confidence: f32) -> &mut Self {
+        self.confidence = confidence;
+        self
+    }
+
+    /// Add a fact in the current session, at the current confidence.
+    pub fn fact(&mut self, content: &str) -> u64 {
+        self.add_event(EventType::Fact, content, self.session_id, self.confidence)
+    }
+
+    /// Add a decision in the current session, at the current confidence.
+    pub fn decision(&mut self, content: &str) -> u64 {
+        self.add_event(
+            EventType::Decision,
+            content,
+            self.session_id,
+            self.confidence,
+        )
+    }
+
+    /// Add an inference in the current session, at the current confidence.
+    pub fn inference(&mut self, content: &str) -> u64 {
+        self.add_event(
+            EventType::Inference,
+            content,
+            self.session_id,
+            self.confidence,
+        )
+    }
+
+    /// Add a skill in the current session, at the current confidence.
+    pub fn skill(&mut self, content: &str) -> u64 {
+        self.add_event(EventType::Skill, content, self.session_id, self.confidence)
+    }
+
+    /// Add a correction of `old_node_id` in the current session.
+    pub fn correction(&mut self, content: &str, old_node_id: u64) -> u64 {
+        self.add_correction(content, self.session_id, old_node_id)
+    }
+
+    /// Add an episode summarizing `member_node_ids` in the current session.
+    pub fn episode(&mut self, content: &str, member_node_ids: &[u64]) -> u64 {
+        self.add_episode(content, self.session_id, member_node_ids)
+    }
+
     fn add_event(
         &mut self,
         event_type: EventType,
@@ -73,8 +137,7 @@ impl GraphBuilder {
     /// Add a correction (automatically creates SUPERSEDES edge to old_node_id).
     pub fn add_correction(&mut self, content: &str, session_id: u32, old_node_id: u64) -> u64 {
         let id = self.add_event(EventType::Correction, content, session_id, 1.0);
-        self.edges
-            .push(Edge::new(id, old_node_id, EdgeType::Supersedes, 1.0));
+        self.push_edge(Edge::new(id, old_node_id, EdgeType::Supersedes, 1.0));
         // Reduce old node's confidence
         if let Some(old_node) = self.nodes.iter_mut().find(|n| n.id == old_node_id) {
             old_node.confidence = 0.0;
@@ -91,13 +154,13 @@ impl GraphBuilder {
     pub fn add_episode(&mut self, content: &str, session_id: u32, member_node_ids: &[u64]) -> u64 {
         let id = self.add_event(EventType::Episode, content, session_id, 1.0);
         for &member_id in member_node_ids {
-            self.edges
-                .push(Edge::new(member_id, id, EdgeType::PartOf, 1.0));
+            self.push_edge(Edge::new(member_id, id, EdgeType::PartOf, 1.0));
         }
         id
     }
 
-    /// Add an edge between two nodes.
+    /// Add an edge between two nodes. Both must already exist and differ;
+    /// otherwise the edge is left out and [`build`](Self::build) fails.
     pub fn link(
         &mut self,
         source_id: u64,
@@ -105,23 +168,63 @@ impl GraphBuilder {
         edge_type: EdgeType,
         weight: f32,
     ) -> &mut Self {
-        self.edges
-            .push(Edge::new(source_id, target_id, edge_type, weight));
+        self.push_edge(Edge::new(source_id, target_id, edge_type, weight));
         self
     }
 
-    /// Set feature vector for a node.
+    /// Set feature vector for a node. An unknown node or a vector of the
+    /// wrong dimension makes [`build`](Self::build) fail.
     pub fn set_feature_vec(&mut self, node_id: u64, vec: Vec<f32>) -> &mut Self {
-        if let Some(node) = self.nodes.iter_mut().find(|n| n.id == node_id) {
-            node.feature_vec = vec;
+        if vec.len() != self.dimension {
+            self.fail(AmemError::DimensionMismatch {
+                expected: self.dimension,
+                got: vec.len(),
+            });
+            return self;
+        }
+        match self.nodes.iter_mut().find(|n| n.id == node_id) {
+            Some(node) => node.feature_vec = vec,
+            None => self.fail(AmemError::NodeNotFound(node_id)),
         }
         self
     }
 
-    /// Build the final MemoryGraph.
+    /// The first invalid edge or node reference seen so far, if any.
+    pub fn error(&self) -> Option<&AmemError> {
+        self.error.as_ref()
+    }
+
+    /// Build the final MemoryGraph, or return the first error recorded while
+    /// building.
     pub fn build(self) -> AmemResult<MemoryGraph> {
+        if let Some(e) = self.error {
+            return Err(e);
+        }
         MemoryGraph::from_parts(self.nodes, self.edges, self.dimension)
     }
+
+    /// Validate an edge the way [`MemoryGraph::add_edge`] does and keep it,
+    /// or record why it was rejected.
+    fn push_edge(&mut self, edge: Edge) {
+        let exists = |id: u64| self.nodes.iter().any(|n| n.id == id);
+        let rejected = if edge.source_id == edge.target_id {
+            Some(AmemError::SelfEdge(edge.source_id))
+        } else if !exists(edge.source_id) {
+            Some(AmemError::NodeNotFound(edge.source_id))
+        } else if !exists(edge.target_id) {
+            Some(AmemError::InvalidEdgeTarget(edge.target_id))
+        } else {
+            None
+        };
+        match rejected {
+            Some(e) => self.fail(e),
+            None => self.edges.push(edge),
+        }
+    }
+
+    fn fail(&mut self, error: AmemError) {
+        self.error.get_or_insert(error);
+    }
 }
 
 impl Default for GraphBuilder {
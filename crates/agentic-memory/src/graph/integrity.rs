@@ -0,0 +1,437 @@
+//! Structural integrity checks over an in-memory graph.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::hash::Hash;
+
+use super::MemoryGraph;
+use crate::index::{SessionIndex, TemporalIndex, TypeIndex};
+use crate::types::{AmemResult, EdgeType};
+
+/// One inconsistency found by [`MemoryGraph::check_integrity`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IntegrityViolation {
+    /// Two nodes share an ID.
+    DuplicateNodeId(u64),
+    /// An edge names a node that does not exist.
+    DanglingEdge {
+        /// Edge source.
+        source_id: u64,
+        /// Edge target.
+        target_id: u64,
+        /// The endpoint that is missing.
+        missing: u64,
+    },
+    /// An edge points from a node to itself.
+    SelfEdge(u64),
+    /// The adjacency index does not return an edge that is stored.
+    AdjacencyMismatch {
+        /// Edge source.
+        source_id: u64,
+        /// Edge target.
+        target_id: u64,
+    },
+    /// The type index disagrees with a node's event type.
+    TypeIndexMismatch(u64),
+    /// The session index disagrees with a node's session.
+    SessionIndexMismatch(u64),
+    /// The temporal index disagrees with a node's creation time.
+    TemporalIndexMismatch(u64),
+    /// SUPERSEDES edges form a loop; the nodes are listed in edge order.
+    SupersedesCycle(Vec<u64>),
+}
+
+impl fmt::Display for IntegrityViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DuplicateNodeId(id) => write!(f, "duplicate node ID {id}"),
+            Self::DanglingEdge {
+                source_id,
+                target_id,
+                missing,
+            } => write!(
+                f,
+                "edge {source_id} -> {target_id} references missing node {missing}"
+            ),
+            Self::SelfEdge(id) => write!(f, "self-edge on node {id}"),
+            Self::AdjacencyMismatch {
+                source_id,
+                target_id,
+            } => write!(
+                f,
+                "edge {source_id} -> {target_id} is missing from the adjacency index"
+            ),
+            Self::TypeIndexMismatch(id) => write!(f, "type index out of step for node {id}"),
+            Self::SessionIndexMismatch(id) => {
+                write!(f, "session index out of step for node {id}")
+            }
+            Self::TemporalIndexMismatch(id) => {
+                write!(f, "temporal index out of step for node {id}")
+            }
+            Self::SupersedesCycle(nodes) => write!(f, "supersedes cycle through nodes {nodes:?}"),
+        }
+    }
+}
+
+/// An index compared by [`MemoryGraph::verify_indexes`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IndexKind {
+    /// Event type to node IDs.
+    Type,
+    /// Session to node IDs.
+    Session,
+    /// Creation time to node IDs.
+    Temporal,
+    /// Forward and reverse edge lookup.
+    Adjacency,
+}
+
+impl IndexKind {
+    /// Lowercase name used in reports.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Type => "type",
+            Self::Session => "session",
+            Self::Temporal => "temporal",
+            Self::Adjacency => "adjacency",
+        }
+    }
+}
+
+/// One index that differs from a fresh rebuild.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexDivergence {
+    /// The index that diverges.
+    pub index: IndexKind,
+    /// Nodes whose entries differ (for adjacency, the edge endpoints),
+    /// ascending.
+    pub node_ids: Vec<u64>,
+}
+
+impl fmt::Display for IndexDivergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} index diverges for {} node(s): {:?}",
+            self.index.name(),
+            self.node_ids.len(),
+            self.node_ids
+        )
+    }
+}
+
+/// Outcome of [`MemoryGraph::verify_indexes`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IndexConsistencyReport {
+    /// Nodes in the graph when it was checked.
+    pub node_count: usize,
+    /// Edges in the graph when it was checked.
+    pub edge_count: usize,
+    /// Indexes that differ from a rebuild, in [`IndexKind`] order.
+    pub divergences: Vec<IndexDivergence>,
+}
+
+impl IndexConsistencyReport {
+    /// Whether every index matches its rebuild.
+    pub fn is_consistent(&self) -> bool {
+        self.divergences.is_empty()
+    }
+}
+
+impl fmt::Display for IndexConsistencyReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_consistent() {
+            return write!(
+                f,
+                "indexes consistent ({} nodes, {} edges)",
+                self.node_count, self.edge_count
+            );
+        }
+        let parts: Vec<String> = self.divergences.iter().map(|d| d.to_string()).collect();
+        write!(f, "{}", parts.join("; "))
+    }
+}
+
+/// IDs whose `(key, id)` entries occur a different number of times in the
+/// two listings.
+fn diverging_ids<K: Hash + Eq>(
+    current: impl IntoIterator<Item = (K, u64)>,
+    rebuilt: impl IntoIterator<Item = (K, u64)>,
+) -> Vec<u64> {
+    let mut balance: HashMap<(K, u64), i64> = HashMap::new();
+    for entry in current {
+        *balance.entry(entry).or_insert(0) += 1;
+    }
+    for entry in rebuilt {
+        *balance.entry(entry).or_insert(0) -= 1;
+    }
+    let mut ids: Vec<u64> = balance
+        .into_iter()
+        .filter(|(_, count)| *count != 0)
+        .map(|((_, id), _)| id)
+        .collect();
+    ids.sort_unstable();
+    ids.dedup();
+    ids
+}
+
+impl MemoryGraph {
+    /// Compare the type, session, temporal and adjacency indexes against
+    /// ones rebuilt from the nodes and edges, naming the nodes whose entries
+    /// differ. Incremental index updates are correct exactly when this
+    /// reports nothing.
+    pub fn verify_indexes(&self) -> IndexConsistencyReport {
+        let mut divergences = Vec::new();
+        let mut push = |index, node_ids: Vec<u64>| {
+            if !node_ids.is_empty() {
+                divergences.push(IndexDivergence { index, node_ids });
+            }
+        };
+
+        let mut types = TypeIndex::new();
+        types.rebuild(self.nodes());
+        let type_entries = |index: &TypeIndex| {
+            index
+                .iter()
+                .flat_map(|(t, ids)| ids.iter().map(move |&id| (t, id)))
+                .collect::<Vec<_>>()
+        };
+        push(
+            IndexKind::Type,
+            diverging_ids(type_entries(self.type_index()), type_entries(&types)),
+        );
+
+        let mut sessions = SessionIndex::new();
+        sessions.rebuild(self.nodes());
+        let session_entries = |index: &SessionIndex| {
+            index
+                .inner()
+                .iter()
+                .flat_map(|(&s, ids)| ids.iter().map(move |&id| (s, id)))
+                .collect::<Vec<_>>()
+        };
+        push(
+            IndexKind::Session,
+            diverging_ids(
+                session_entries(self.session_index()),
+                session_entries(&sessions),
+            ),
+        );
+
+        let mut times = TemporalIndex::new();
+        times.rebuild(self.nodes());
+        push(
+            IndexKind::Temporal,
+            diverging_ids(
+                self.temporal_index().entries().iter().copied(),
+                times.entries().iter().copied(),
+            ),
+        );
+
+        // Every edge must come back once from its source and once from its
+        // target; the edge list itself is the rebuilt truth.
+        let mut endpoints: Vec<u64> = self
+            .nodes()
+            .iter()
+            .map(|n| n.id)
+            .chain(self.edges().iter().flat_map(|e| [e.source_id, e.target_id]))
+            .collect();
+        endpoints.sort_unstable();
+        endpoints.dedup();
+        let indexed = endpoints.iter().flat_map(|&id| {
+            let outgoing = self
+                .edges_from(id)
+                .iter()
+                .map(move |e| ((true, e.target_id, e.edge_type.to_u8()), id));
+            let incoming = self
+                .edges_to(id)
+                .into_iter()
+                .map(move |e| ((false, e.source_id, e.edge_type.to_u8()), id));
+            outgoing.chain(incoming)
+        });
+        let stored = self.edges().iter().flat_map(|e| {
+            [
+                ((true, e.target_id, e.edge_type.to_u8()), e.source_id),
+                ((false, e.source_id, e.edge_type.to_u8()), e.target_id),
+            ]
+        });
+        push(IndexKind::Adjacency, diverging_ids(indexed, stored));
+
+        IndexConsistencyReport {
+            node_count: self.node_count(),
+            edge_count: self.edge_count(),
+            divergences,
+        }
+    }
+
+    /// Check that every edge resolves, SUPERSEDES edges form no cycle, and the
+    /// type, session and temporal indexes match the nodes. Returns every
+    /// violation found; an empty list means the graph is consistent.
+    pub fn check_integrity(&self) -> Vec<IntegrityViolation> {
+        let mut violations = Vec::new();
+
+        let mut ids = HashSet::with_capacity(self.node_count());
+        for node in self.nodes() {
+            if !ids.insert(node.id) {
+                violations.push(IntegrityViolation::DuplicateNodeId(node.id));
+            }
+        }
+
+        for edge in self.edges() {
+            let (source_id, target_id) = (edge.source_id, edge.target_id);
+            if source_id == target_id {
+                violations.push(IntegrityViolation::SelfEdge(source_id));
+            }
+            for endpoint in [source_id, target_id] {
+                if !ids.contains(&endpoint) {
+                    violations.push(IntegrityViolation::DanglingEdge {
+                        source_id,
+                        target_id,
+                        missing: endpoint,
+                    });
+                }
+            }
+            if !self.edges_from(source_id).contains(edge) {
+                violations.push(IntegrityViolation::AdjacencyMismatch {
+                    source_id,
+                    target_id,
+                });
+            }
+        }
+        violations.extend(
+            self.supersedes_cycles()
+                .into_iter()
+                .map(IntegrityViolation::SupersedesCycle),
+        );
+
+        // Each index must hold exactly one entry per node, with the node's
+        // current key. Entries are keyed by node ID so both stale and missing
+        // entries show up.
+        let mut by_type = HashMap::with_capacity(self.node_count());
+        for (event_type, type_ids) in self.type_index().iter() {
+            for &id in type_ids {
+                if by_type.insert(id, event_type).is_some() {
+                    violations.push(IntegrityViolation::TypeIndexMismatch(id));
+                }
+            }
+        }
+        let mut by_session = HashMap::with_capacity(self.node_count());
+        for (&session_id, session_ids) in self.session_index().inner() {
+            for &id in session_ids {
+                if by_session.insert(id, session_id).is_some() {
+                    violations.push(IntegrityViolation::SessionIndexMismatch(id));
+                }
+            }
+        }
+        let mut by_time = HashMap::with_capacity(self.node_count());
+        for &(created_at, id) in self.temporal_index().entries() {
+            if by_time.insert(id, created_at).is_some() {
+                violations.push(IntegrityViolation::TemporalIndexMismatch(id));
+            }
+        }
+
+        for node in self.nodes() {
+            if by_type.remove(&node.id) != Some(node.event_type) {
+                violations.push(IntegrityViolation::TypeIndexMismatch(node.id));
+            }
+            if by_session.remove(&node.id) != Some(node.session_id) {
+                violations.push(IntegrityViolation::SessionIndexMismatch(node.id));
+            }
+            if by_time.remove(&node.id) != Some(node.created_at) {
+                violations.push(IntegrityViolation::TemporalIndexMismatch(node.id));
+            }
+        }
+        // Whatever is left indexes a node that no longer exists.
+        let mut stale: Vec<IntegrityViolation> = by_type
+            .into_keys()
+            .map(IntegrityViolation::TypeIndexMismatch)
+            .chain(
+                by_session
+                    .into_keys()
+                    .map(IntegrityViolation::SessionIndexMismatch),
+            )
+            .chain(
+                by_time
+                    .into_keys()
+                    .map(IntegrityViolation::TemporalIndexMismatch),
+            )
+            .collect();
+        stale.sort_by_key(|v| v.to_string());
+        violations.extend(stale);
+
+        violations
+    }
+
+    /// Cycles among SUPERSEDES edges, found by a depth-first walk from each
+    /// node in ID order. Each cycle lists its nodes in edge order; the edge
+    /// from the last node back to the first is the one that closed it.
+    /// Removing those closing edges leaves no cycle.
+    pub fn supersedes_cycles(&self) -> Vec<Vec<u64>> {
+        #[derive(Clone, Copy, PartialEq)]
+        enum Mark {
+            OnPath,
+            Done,
+        }
+
+        // Successors in reverse, so popping visits them in edge order.
+        let pending_of = |id: u64| {
+            let mut pending: Vec<u64> = self
+                .edges_from(id)
+                .iter()
+                .filter(|e| e.edge_type == EdgeType::Supersedes)
+                .map(|e| e.target_id)
+                .collect();
+            pending.reverse();
+            pending
+        };
+        let mut marks: HashMap<u64, Mark> = HashMap::new();
+        let mut cycles = Vec::new();
+        for start in self.nodes().iter().map(|n| n.id) {
+            if marks.contains_key(&start) {
+                continue;
+            }
+            // Path of (node, its remaining successors) being explored.
+            let mut path: Vec<(u64, Vec<u64>)> = vec![(start, pending_of(start))];
+            marks.insert(start, Mark::OnPath);
+            while let Some((node, pending)) = path.last_mut() {
+                let node = *node;
+                match pending.pop() {
+                    Some(next) => match marks.get(&next) {
+                        Some(Mark::OnPath) => {
+                            let from = path.iter().position(|(id, _)| *id == next).unwrap_or(0);
+                            cycles.push(path[from..].iter().map(|(id, _)| *id).collect());
+                        }
+                        Some(Mark::Done) => {}
+                        None => {
+                            marks.insert(next, Mark::OnPath);
+                            path.push((next, pending_of(next)));
+                        }
+                    },
+                    None => {
+                        marks.insert(node, Mark::Done);
+                        path.pop();
+                    }
+                }
+            }
+        }
+        cycles
+    }
+
+    /// Break every SUPERSEDES cycle by removing the edge that closed it (see
+    /// [`supersedes_cycles`](Self::supersedes_cycles)). Returns the removed
+    /// edges as `(source_id, target_id)` pairs.
+    pub fn break_supersedes_cycles(&mut self) -> AmemResult<Vec<(u64, u64)>> {
+        let mut closing: Vec<(u64, u64)> = self
+            .supersedes_cycles()
+            .into_iter()
+            .filter_map(|cycle| Some((*cycle.last()?, *cycle.first()?)))
+            .collect();
+        // Parallel edges between one pair close a cycle each but go together.
+        closing.sort_unstable();
+        closing.dedup();
+        for &(source_id, target_id) in &closing {
+            self.remove_edge(source_id, target_id, EdgeType::Supersedes)?;
+        }
+        Ok(closing)
+    }
+}
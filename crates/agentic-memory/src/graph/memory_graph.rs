@@ -1,11 +1,18 @@
 //! Core graph structure — nodes + edges with adjacency indexes.
 
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap, HashSet};
 
-use crate::index::{ClusterMap, DocLengths, SessionIndex, TemporalIndex, TermIndex, TypeIndex};
-use crate::types::{AmemError, AmemResult, CognitiveEvent, Edge, EdgeType, MAX_EDGES_PER_NODE};
+use crate::engine::tokenizer::Tokenizer;
+use crate::index::{
+    ClusterMap, DocLengths, SessionIndex, TagIndex, TemporalIndex, TermBloom, TermIndex, TypeIndex,
+};
+use crate::types::{
+    now_micros, AccessLog, AmemError, AmemResult, CognitiveEvent, Edge, EdgeType, EdgeTypeNames,
+    EventType, Language, Tombstone, MAX_EDGES_PER_NODE,
+};
 
 /// The core in-memory graph structure holding cognitive events and their relationships.
+#[derive(Clone)]
 pub struct MemoryGraph {
     /// All nodes, indexed by ID.
     nodes: Vec<CognitiveEvent>,
@@ -21,6 +28,8 @@ pub struct MemoryGraph {
     dimension: usize,
     /// Type index.
     pub(crate) type_index: TypeIndex,
+    /// Tag index.
+    pub(crate) tag_index: TagIndex,
     /// Temporal index.
     pub(crate) temporal_index: TemporalIndex,
     /// Session index.
@@ -31,6 +40,53 @@ pub struct MemoryGraph {
     pub term_index: Option<TermIndex>,
     /// Document lengths for BM25 normalization (optional).
     pub doc_lengths: Option<DocLengths>,
+    /// Per-document term bloom filters (optional, only in files written with them).
+    pub term_bloom: Option<TermBloom>,
+    /// Model that produced the feature vectors (`None` = unknown).
+    embedding_model: Option<String>,
+    /// Records of deleted nodes, so merges do not re-add them.
+    pub(crate) tombstones: Vec<Tombstone>,
+    /// Recent access timestamps per node.
+    pub(crate) access_log: AccessLog,
+    /// Detected content language per node (untagged nodes use the defaults).
+    pub(crate) node_languages: HashMap<u64, Language>,
+    /// Names of the custom edge types this graph's edges may use.
+    edge_type_names: EdgeTypeNames,
+    /// Nodes and edges changed since tracking began (`None` = not tracking).
+    changes: Option<GraphChanges>,
+}
+
+/// Nodes and edges changed since a graph began tracking changes, so a
+/// store can save just those (see [`MemoryGraph::track_changes`]).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GraphChanges {
+    /// Nodes added or possibly modified.
+    pub nodes: BTreeSet<u64>,
+    /// Nodes removed.
+    pub removed_nodes: BTreeSet<u64>,
+    /// Nodes whose outgoing edges may have changed.
+    pub edge_sources: BTreeSet<u64>,
+}
+
+impl GraphChanges {
+    /// Whether nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty() && self.removed_nodes.is_empty() && self.edge_sources.is_empty()
+    }
+}
+
+/// Outcome of [`MemoryGraph::declare_embedding_model`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EmbeddingModelCheck {
+    /// The graph had no model; the declared one is now recorded.
+    Recorded,
+    /// The declared model matches the graph's.
+    Matches,
+    /// The graph already records a different model; nothing was changed.
+    Mismatch {
+        /// The model the graph records.
+        file_model: String,
+    },
 }
 
 impl MemoryGraph {
@@ -44,11 +100,19 @@ impl MemoryGraph {
             next_id: 0,
             dimension,
             type_index: TypeIndex::new(),
+            tag_index: TagIndex::new(),
             temporal_index: TemporalIndex::new(),
             session_index: SessionIndex::new(),
             cluster_map: ClusterMap::new(dimension),
             term_index: None,
             doc_lengths: None,
+            term_bloom: None,
+            embedding_model: None,
+            tombstones: Vec::new(),
+            access_log: AccessLog::new(),
+            node_languages: HashMap::new(),
+            edge_type_names: EdgeTypeNames::new(),
+            changes: None,
         }
     }
 
@@ -68,11 +132,19 @@ impl MemoryGraph {
             next_id,
             dimension,
             type_index: TypeIndex::new(),
+            tag_index: TagIndex::new(),
             temporal_index: TemporalIndex::new(),
             session_index: SessionIndex::new(),
             cluster_map: ClusterMap::new(dimension),
             term_index: None,
             doc_lengths: None,
+            term_bloom: None,
+            embedding_model: None,
+            tombstones: Vec::new(),
+            access_log: AccessLog::new(),
+            node_languages: HashMap::new(),
+            edge_type_names: EdgeTypeNames::new(),
+            changes: None,
         };
 
         // Insert nodes directly (they already have IDs assigned)
@@ -80,6 +152,7 @@ impl MemoryGraph {
 
         // Rebuild indexes from nodes
         graph.type_index.rebuild(&graph.nodes);
+        graph.tag_index.rebuild(&graph.nodes);
         graph.temporal_index.rebuild(&graph.nodes);
         graph.session_index.rebuild(&graph.nodes);
 
@@ -115,19 +188,66 @@ impl MemoryGraph {
         if idx < self.nodes.len() && self.nodes[idx].id == id {
             return Some(&self.nodes[idx]);
         }
-        // Fallback: linear scan (needed after remove_node)
+        // After remove_node the nodes are still in ID order: binary search,
+        // then fall back to a linear scan for graphs loaded out of order
+        if let Ok(pos) = self.nodes.binary_search_by_key(&id, |n| n.id) {
+            return Some(&self.nodes[pos]);
+        }
         self.nodes.iter().find(|n| n.id == id)
     }
 
     /// Get a node by ID (mutable).
     pub fn get_node_mut(&mut self, id: u64) -> Option<&mut CognitiveEvent> {
+        let pos = self.node_position(id)?;
+        self.node_changed(id);
+        Some(&mut self.nodes[pos])
+    }
+
+    fn node_position(&self, id: u64) -> Option<usize> {
         // Fast path: if IDs are sequential, nodes[id] has id == id
         let idx = id as usize;
         if idx < self.nodes.len() && self.nodes[idx].id == id {
-            return Some(&mut self.nodes[idx]);
+            return Some(idx);
         }
-        // Fallback: linear scan (needed after remove_node)
-        self.nodes.iter_mut().find(|n| n.id == id)
+        // See get_node
+        if let Ok(pos) = self.nodes.binary_search_by_key(&id, |n| n.id) {
+            return Some(pos);
+        }
+        self.nodes.iter().position(|n| n.id == id)
+    }
+
+    /// Nodes of any of `event_types` in ID order, looked up through the type
+    /// index so only matching nodes are visited. An empty list means every
+    /// node.
+    pub fn nodes_of_types(&self, event_types: &[EventType]) -> Vec<&CognitiveEvent> {
+        if event_types.is_empty() {
+            return self.nodes.iter().collect();
+        }
+        self.type_index
+            .get_any(event_types)
+            .into_iter()
+            .filter_map(|id| self.get_node(id))
+            .collect()
+    }
+
+    /// Change the event type of node `id`, keeping the type index in step.
+    pub fn set_event_type(&mut self, id: u64, event_type: EventType) -> AmemResult<()> {
+        let node = self.get_node_mut(id).ok_or(AmemError::NodeNotFound(id))?;
+        let previous = std::mem::replace(&mut node.event_type, event_type);
+        self.type_index.retype_node(id, previous, event_type);
+        Ok(())
+    }
+
+    /// Replace the tags of node `id`, keeping the tag index in step.
+    pub fn set_node_tags(&mut self, id: u64, tags: HashMap<String, String>) -> AmemResult<()> {
+        let node = self.get_node_mut(id).ok_or(AmemError::NodeNotFound(id))?;
+        let previous = std::mem::take(&mut node.tags);
+        self.tag_index.remove_node(id, &previous);
+        self.tag_index.add_tags(id, &tags);
+        if let Some(node) = self.get_node_mut(id) {
+            node.tags = tags;
+        }
+        Ok(())
     }
 
     /// Ensure adjacency indexes are up to date.
@@ -167,11 +287,69 @@ impl MemoryGraph {
         &self.nodes
     }
 
+    /// Edges touching this node, reading symmetric edge types without direction.
+    ///
+    /// Returns every outgoing edge plus the incoming edges whose type is
+    /// symmetric (see [`EdgeType::is_symmetric`]). Edges come back as stored,
+    /// so an incoming one has `node_id` as its target.
+    pub fn edges_undirected(&self, node_id: u64) -> Vec<&Edge> {
+        let mut result: Vec<&Edge> = self.edges_from(node_id).iter().collect();
+        result.extend(
+            self.edges_to(node_id)
+                .into_iter()
+                .filter(|e| e.edge_type.is_symmetric()),
+        );
+        result
+    }
+
     /// Get all edges (immutable slice).
     pub fn edges(&self) -> &[Edge] {
         &self.edges
     }
 
+    /// Every built-in edge type plus the custom types used by this graph's
+    /// edges, in [`EdgeType`] order.
+    pub fn edge_types(&self) -> Vec<EdgeType> {
+        let mut types: Vec<EdgeType> = EdgeType::BUILTIN.to_vec();
+        let mut custom: Vec<EdgeType> = self
+            .edges
+            .iter()
+            .map(|e| e.edge_type)
+            .filter(EdgeType::is_custom)
+            .collect();
+        custom.sort_unstable();
+        custom.dedup();
+        types.extend(custom);
+        types
+    }
+
+    /// The names of this graph's custom edge types.
+    pub fn edge_type_names(&self) -> &EdgeTypeNames {
+        &self.edge_type_names
+    }
+
+    /// Replace the custom edge type names, e.g. with those of the graph a
+    /// derived graph's edges were taken from.
+    pub fn set_edge_type_names(&mut self, names: EdgeTypeNames) {
+        self.edge_type_names = names;
+    }
+
+    /// The edge type called `name` in this graph, without registering it.
+    pub fn edge_type(&self, name: &str) -> Option<EdgeType> {
+        self.edge_type_names.get(name)
+    }
+
+    /// The edge type called `name`, registering a new custom type in this
+    /// graph if needed (see [`EdgeTypeNames::register`]).
+    pub fn register_edge_type(&mut self, name: &str) -> AmemResult<EdgeType> {
+        self.edge_type_names.register(name)
+    }
+
+    /// The name of `edge_type` in this graph.
+    pub fn edge_type_name(&self, edge_type: EdgeType) -> &str {
+        self.edge_type_names.name(edge_type)
+    }
+
     /// The feature vector dimension for this graph.
     pub fn dimension(&self) -> usize {
         self.dimension
@@ -199,18 +377,79 @@ impl MemoryGraph {
 
         // Update indexes
         self.type_index.add_node(&event);
+        self.tag_index.add_node(&event);
         self.temporal_index.add_node(&event);
         self.session_index.add_node(&event);
 
         self.nodes.push(event);
+        self.node_changed(id);
 
         Ok(id)
     }
 
+    /// Insert a node under the ID it already carries, keeping nodes in ID
+    /// order. The caller makes sure no node has that ID yet.
+    pub(crate) fn insert_node(&mut self, mut event: CognitiveEvent) -> AmemResult<()> {
+        event.validate(self.dimension)?;
+        if event.feature_vec.is_empty() {
+            event.feature_vec = vec![0.0; self.dimension];
+        }
+        self.next_id = self.next_id.max(event.id + 1);
+
+        self.type_index.add_node(&event);
+        self.tag_index.add_node(&event);
+        self.temporal_index.add_node(&event);
+        self.session_index.add_node(&event);
+
+        self.node_changed(event.id);
+        let pos = self.nodes.partition_point(|n| n.id < event.id);
+        self.nodes.insert(pos, event);
+        Ok(())
+    }
+
+    /// Store a node under the ID it carries, replacing the node with that
+    /// ID if there is one. Its edges are kept.
+    pub(crate) fn put_node(&mut self, mut event: CognitiveEvent) -> AmemResult<()> {
+        let Some(pos) = self.node_position(event.id) else {
+            return self.insert_node(event);
+        };
+        event.validate(self.dimension)?;
+        if event.feature_vec.is_empty() {
+            event.feature_vec = vec![0.0; self.dimension];
+        }
+
+        let old = &self.nodes[pos];
+        self.type_index.remove_node(old.id, old.event_type);
+        self.tag_index.remove_node(old.id, &old.tags);
+        self.temporal_index.remove_node(old.id, old.created_at);
+        self.session_index.remove_node(old.id, old.session_id);
+
+        self.type_index.add_node(&event);
+        self.tag_index.add_node(&event);
+        self.temporal_index.add_node(&event);
+        self.session_index.add_node(&event);
+
+        self.node_changed(event.id);
+        self.nodes[pos] = event;
+        Ok(())
+    }
+
     /// Add an edge between two existing nodes.
     pub fn add_edge(&mut self, edge: Edge) -> AmemResult<()> {
+        self.insert_edge(edge, false)
+    }
+
+    /// Add an edge that may point from a node to itself. Self-edges are
+    /// rejected by [`add_edge`](Self::add_edge); use this only where a loop
+    /// is meaningful. Centrality ignores self-edges either way, and
+    /// [`check_integrity`](Self::check_integrity) still reports them.
+    pub fn add_self_edge(&mut self, edge: Edge) -> AmemResult<()> {
+        self.insert_edge(edge, true)
+    }
+
+    fn insert_edge(&mut self, edge: Edge, allow_self_edge: bool) -> AmemResult<()> {
         // Validate: no self-edges
-        if edge.source_id == edge.target_id {
+        if edge.source_id == edge.target_id && !allow_self_edge {
             return Err(AmemError::SelfEdge(edge.source_id));
         }
 
@@ -224,6 +463,12 @@ impl MemoryGraph {
             return Err(AmemError::InvalidEdgeTarget(edge.target_id));
         }
 
+        if !self.edge_type_names.contains(edge.edge_type) {
+            return Err(AmemError::InvalidEdgeType(
+                self.edge_type_names.name(edge.edge_type).to_string(),
+            ));
+        }
+
         // Check max edges per node
         let current_count = self
             .adjacency
@@ -236,12 +481,70 @@ impl MemoryGraph {
 
         self.edges.push(edge);
         self.rebuild_adjacency();
+        self.edges_changed(edge.source_id);
+
+        Ok(())
+    }
 
+    /// Replace the edges leaving `source_id` with `edges`, whose custom
+    /// types are named in `names`.
+    pub(crate) fn set_edges_from(
+        &mut self,
+        source_id: u64,
+        edges: &[Edge],
+        names: &EdgeTypeNames,
+    ) -> AmemResult<()> {
+        if self.get_node(source_id).is_none() {
+            return Err(AmemError::NodeNotFound(source_id));
+        }
+        let mut replacement = Vec::with_capacity(edges.len());
+        for edge in edges {
+            if self.get_node(edge.target_id).is_none() {
+                return Err(AmemError::InvalidEdgeTarget(edge.target_id));
+            }
+            replacement.push(Edge {
+                source_id,
+                edge_type: self.edge_type_names.import(edge.edge_type, names)?,
+                ..*edge
+            });
+        }
+        self.edges.retain(|e| e.source_id != source_id);
+        self.edges.extend(replacement);
+        self.edges.sort_by(|a, b| {
+            a.source_id
+                .cmp(&b.source_id)
+                .then(a.target_id.cmp(&b.target_id))
+        });
+        self.rebuild_adjacency();
+        self.edges_changed(source_id);
         Ok(())
     }
 
-    /// Remove a node and all its edges.
+    /// Remove a node and all its edges, leaving a tombstone for it.
     pub fn remove_node(&mut self, id: u64) -> AmemResult<CognitiveEvent> {
+        let removed = self.remove_node_untracked(id)?;
+        self.add_tombstone(Tombstone::for_node(&removed, now_micros()));
+        Ok(removed)
+    }
+
+    /// Drop every node with an ID of at least `first_id`, with its edges and
+    /// without tombstones, and hand those IDs out again. Undoes a partly
+    /// applied batch whose first node got `first_id`.
+    pub(crate) fn discard_since(&mut self, first_id: u64) {
+        let ids: Vec<u64> = self
+            .nodes
+            .iter()
+            .map(|n| n.id)
+            .filter(|&id| id >= first_id)
+            .collect();
+        for id in ids {
+            let _ = self.remove_node_untracked(id);
+        }
+        self.next_id = self.next_id.min(first_id);
+    }
+
+    /// Remove a node and all its edges without leaving a tombstone.
+    pub(crate) fn remove_node_untracked(&mut self, id: u64) -> AmemResult<CognitiveEvent> {
         let pos = self
             .nodes
             .iter()
@@ -249,12 +552,27 @@ impl MemoryGraph {
             .ok_or(AmemError::NodeNotFound(id))?;
 
         let removed = self.nodes.remove(pos);
+        self.access_log.remove(id);
+        self.node_languages.remove(&id);
 
         // Remove from indexes
         self.type_index.remove_node(id, removed.event_type);
+        self.tag_index.remove_node(id, &removed.tags);
         self.temporal_index.remove_node(id, removed.created_at);
         self.session_index.remove_node(id, removed.session_id);
 
+        if let Some(changes) = &mut self.changes {
+            changes.nodes.remove(&id);
+            changes.edge_sources.remove(&id);
+            changes.removed_nodes.insert(id);
+            changes.edge_sources.extend(
+                self.edges
+                    .iter()
+                    .filter(|e| e.target_id == id && e.source_id != id)
+                    .map(|e| e.source_id),
+            );
+        }
+
         // Remove all edges involving this node
         self.edges
             .retain(|e| e.source_id != id && e.target_id != id);
@@ -265,6 +583,31 @@ impl MemoryGraph {
         Ok(removed)
     }
 
+    /// Erase a node's content but keep the node and its edges.
+    ///
+    /// The content becomes `marker`, the feature vector is zeroed, and the
+    /// node is dropped from the term index, doc lengths, term bloom filters
+    /// and language map, so nothing derived from the old text remains.
+    /// Unlike [`remove_node`](Self::remove_node) no tombstone is left: the
+    /// node is still there.
+    pub fn forget_node(&mut self, id: u64, marker: impl Into<String>) -> AmemResult<()> {
+        let node = self.get_node_mut(id).ok_or(AmemError::NodeNotFound(id))?;
+        node.content = marker.into();
+        node.feature_vec.iter_mut().for_each(|v| *v = 0.0);
+
+        if let Some(index) = self.term_index.as_mut() {
+            index.remove_node(id);
+        }
+        if let Some(lengths) = self.doc_lengths.as_mut() {
+            lengths.remove_node(id);
+        }
+        if let Some(bloom) = self.term_bloom.as_mut() {
+            bloom.remove_node(id);
+        }
+        self.node_languages.remove(&id);
+        Ok(())
+    }
+
     /// Remove a specific edge.
     pub fn remove_edge(
         &mut self,
@@ -280,10 +623,141 @@ impl MemoryGraph {
             return Err(AmemError::NodeNotFound(source_id));
         }
         self.rebuild_adjacency();
+        self.edges_changed(source_id);
         Ok(())
     }
 
+    /// Set the weight of the edge `source_id -> target_id` of `edge_type`
+    /// (every such edge, if there are duplicates).
+    pub fn set_edge_weight(
+        &mut self,
+        source_id: u64,
+        target_id: u64,
+        edge_type: EdgeType,
+        weight: f32,
+    ) -> AmemResult<()> {
+        if !(0.0..=1.0).contains(&weight) {
+            return Err(AmemError::InvalidWeight(weight));
+        }
+        let mut found = false;
+        for edge in self.edges.iter_mut().filter(|e| {
+            e.source_id == source_id && e.target_id == target_id && e.edge_type == edge_type
+        }) {
+            edge.weight = weight;
+            found = true;
+        }
+        if found {
+            self.edges_changed(source_id);
+            Ok(())
+        } else {
+            Err(AmemError::NodeNotFound(source_id))
+        }
+    }
+
+    /// The subgraph induced by `node_ids`: copies of those nodes and of every
+    /// edge whose endpoints are both in the set. IDs not in the graph are
+    /// ignored.
+    ///
+    /// Node IDs are kept, and so is the next free ID, so nodes added to the
+    /// subgraph never collide with the source. The type, temporal, session,
+    /// term and doc-length indexes are built from the copied nodes alone.
+    /// The embedding model, node languages and aliases of the sessions that
+    /// remain carry over; tombstones and access logs do not.
+    pub fn subgraph(&self, node_ids: &HashSet<u64>) -> MemoryGraph {
+        let nodes: Vec<CognitiveEvent> = self
+            .nodes
+            .iter()
+            .filter(|n| node_ids.contains(&n.id))
+            .cloned()
+            .collect();
+        let edges: Vec<Edge> = self
+            .edges
+            .iter()
+            .filter(|e| node_ids.contains(&e.source_id) && node_ids.contains(&e.target_id))
+            .copied()
+            .collect();
+
+        let mut graph = Self::new(self.dimension);
+        graph.nodes = nodes;
+        graph.edges = edges;
+        graph.next_id = self.next_id;
+        graph.rebuild_indexes();
+        graph.embedding_model = self.embedding_model.clone();
+        graph.edge_type_names = self.edge_type_names.clone();
+        graph.node_languages = self
+            .node_languages
+            .iter()
+            .filter(|(id, _)| node_ids.contains(id))
+            .map(|(&id, &language)| (id, language))
+            .collect();
+        for (alias, session_id) in self.session_index.aliases() {
+            if !graph.session_index.get_session(session_id).is_empty() {
+                let _ = graph.session_index.rename(session_id, alias);
+            }
+        }
+
+        let tokenizer = Tokenizer::new();
+        graph.term_index = Some(TermIndex::build(&graph, &tokenizer));
+        graph.doc_lengths = Some(DocLengths::build(&graph, &tokenizer));
+        graph
+    }
+
+    /// Connected components, treating every edge as undirected. Each
+    /// component is a sorted list of node IDs, and components are ordered
+    /// by their smallest ID; a node without edges is a component of its own.
+    pub fn connected_components(&self) -> Vec<Vec<u64>> {
+        let ids: Vec<u64> = self.nodes.iter().map(|n| n.id).collect();
+        super::traversal::connected_components(
+            &ids,
+            self.edges.iter().map(|e| (e.source_id, e.target_id)),
+        )
+    }
+
+    /// Rebuild the type, tag, temporal, session and adjacency indexes from
+    /// the nodes and edges, discarding whatever incremental updates left
+    /// behind. Session aliases are kept.
+    pub fn rebuild_indexes(&mut self) {
+        self.type_index.rebuild(&self.nodes);
+        self.tag_index.rebuild(&self.nodes);
+        self.temporal_index.rebuild(&self.nodes);
+        self.session_index.rebuild(&self.nodes);
+        self.rebuild_adjacency();
+    }
+
     /// Rebuild adjacency indexes from the current edge list.
+    /// Start recording which nodes and edges change, for stores that save
+    /// only those (see [`GraphBackend::apply_changes`](super::GraphBackend::apply_changes)).
+    pub fn track_changes(&mut self) {
+        self.changes.get_or_insert_with(GraphChanges::default);
+    }
+
+    /// Changes recorded since tracking began or the last
+    /// [`take_changes`](Self::take_changes); `None` when not tracking.
+    pub fn changes(&self) -> Option<&GraphChanges> {
+        self.changes.as_ref()
+    }
+
+    /// Take the recorded changes, leaving tracking on if it was.
+    pub fn take_changes(&mut self) -> GraphChanges {
+        self.changes
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    fn node_changed(&mut self, id: u64) {
+        if let Some(changes) = &mut self.changes {
+            changes.removed_nodes.remove(&id);
+            changes.nodes.insert(id);
+        }
+    }
+
+    fn edges_changed(&mut self, source_id: u64) {
+        if let Some(changes) = &mut self.changes {
+            changes.edge_sources.insert(source_id);
+        }
+    }
+
     fn rebuild_adjacency(&mut self) {
         self.adjacency.clear();
         self.reverse_adjacency.clear();
@@ -327,6 +801,11 @@ impl MemoryGraph {
         &self.type_index
     }
 
+    /// Get the tag index.
+    pub fn tag_index(&self) -> &TagIndex {
+        &self.tag_index
+    }
+
     /// Get the temporal index.
     pub fn temporal_index(&self) -> &TemporalIndex {
         &self.temporal_index
@@ -337,6 +816,164 @@ impl MemoryGraph {
         &self.session_index
     }
 
+    /// Give a session a stable alias. See [`SessionIndex::rename`].
+    pub fn rename_session(&mut self, session_id: u32, alias: &str) -> AmemResult<()> {
+        self.session_index.rename(session_id, alias)
+    }
+
+    /// Remove a session alias, returning the session it pointed to.
+    pub fn remove_session_alias(&mut self, alias: &str) -> Option<u32> {
+        self.session_index.remove_alias(alias)
+    }
+
+    /// Resolve a session reference: a numeric ID or an alias.
+    pub fn resolve_session(&self, reference: &str) -> AmemResult<u32> {
+        let reference = reference.trim();
+        if let Ok(id) = reference.parse::<u32>() {
+            return Ok(id);
+        }
+        self.session_index
+            .resolve_alias(reference)
+            .ok_or_else(|| AmemError::SessionAliasNotFound(reference.to_string()))
+    }
+
+    /// Model that produced the feature vectors, if recorded.
+    pub fn embedding_model(&self) -> Option<&str> {
+        self.embedding_model.as_deref()
+    }
+
+    /// Tombstones of deleted nodes, oldest first.
+    pub fn tombstones(&self) -> &[Tombstone] {
+        &self.tombstones
+    }
+
+    /// Record a tombstone unless one for the same node already exists.
+    pub fn add_tombstone(&mut self, tombstone: Tombstone) {
+        if !self.tombstones.iter().any(|t| t.same_node(&tombstone)) {
+            self.tombstones.push(tombstone);
+        }
+    }
+
+    /// Whether `node` has been deleted according to a tombstone.
+    pub fn is_tombstoned(&self, node: &CognitiveEvent) -> bool {
+        self.tombstones.iter().any(|t| t.matches(node))
+    }
+
+    /// Remove every node a tombstone in `tombstones` records, and keep those
+    /// tombstones. Returns the number of nodes removed.
+    pub fn apply_tombstones(&mut self, tombstones: &[Tombstone]) -> usize {
+        let doomed: Vec<u64> = self
+            .nodes
+            .iter()
+            .filter(|n| tombstones.iter().any(|t| t.matches(n)))
+            .map(|n| n.id)
+            .collect();
+        for id in &doomed {
+            let _ = self.remove_node_untracked(*id);
+        }
+        for t in tombstones {
+            self.add_tombstone(*t);
+        }
+        doomed.len()
+    }
+
+    /// Drop tombstones deleted before `cutoff` (Unix epoch microseconds).
+    /// Returns the number dropped.
+    pub fn prune_tombstones(&mut self, cutoff: u64) -> usize {
+        let before = self.tombstones.len();
+        self.tombstones.retain(|t| t.deleted_at >= cutoff);
+        before - self.tombstones.len()
+    }
+
+    /// Record a read of node `id` at `at`: bumps `access_count`, sets
+    /// `last_accessed`, and appends `at` to the node's access log.
+    pub fn record_access(&mut self, id: u64, at: u64) -> AmemResult<()> {
+        let node = self.get_node_mut(id).ok_or(AmemError::NodeNotFound(id))?;
+        node.access_count = node.access_count.saturating_add(1);
+        node.last_accessed = at;
+        self.access_log.record(id, at);
+        Ok(())
+    }
+
+    /// Recent access timestamps of node `id`, oldest first (at most
+    /// [`ACCESS_LOG_CAPACITY`](crate::ACCESS_LOG_CAPACITY)).
+    pub fn access_log(&self, id: u64) -> Vec<u64> {
+        self.access_log.get(id)
+    }
+
+    /// Access logs of all nodes.
+    pub fn access_logs(&self) -> &AccessLog {
+        &self.access_log
+    }
+
+    /// Detected content language of node `id`, if tagged.
+    pub fn node_language(&self, id: u64) -> Option<Language> {
+        self.node_languages.get(&id).copied()
+    }
+
+    /// Tag node `id` with a content language (`None` clears the tag).
+    pub fn set_node_language(&mut self, id: u64, language: Option<Language>) -> AmemResult<()> {
+        if self.get_node(id).is_none() {
+            return Err(AmemError::NodeNotFound(id));
+        }
+        match language {
+            Some(language) => self.node_languages.insert(id, language),
+            None => self.node_languages.remove(&id),
+        };
+        Ok(())
+    }
+
+    /// Whether any node carries a language tag.
+    pub fn has_node_languages(&self) -> bool {
+        !self.node_languages.is_empty()
+    }
+
+    /// Language tags of all tagged nodes.
+    pub fn node_languages(&self) -> &HashMap<u64, Language> {
+        &self.node_languages
+    }
+
+    /// Record (or clear) the embedding model unconditionally.
+    pub fn set_embedding_model(&mut self, model: Option<String>) {
+        self.embedding_model = model.filter(|m| !m.trim().is_empty());
+    }
+
+    /// Declare the model behind vectors about to be attached. Records it if
+    /// the graph has none; never overwrites a different recorded model.
+    pub fn declare_embedding_model(&mut self, model: &str) -> EmbeddingModelCheck {
+        let model = model.trim();
+        match self.embedding_model.as_deref() {
+            None => {
+                self.set_embedding_model(Some(model.to_string()));
+                EmbeddingModelCheck::Recorded
+            }
+            Some(existing) if existing == model => EmbeddingModelCheck::Matches,
+            Some(existing) => EmbeddingModelCheck::Mismatch {
+                file_model: existing.to_string(),
+            },
+        }
+    }
+
+    /// Like [`declare_embedding_model`](Self::declare_embedding_model), but a
+    /// mismatch is an error when `strict`. Returns the file's model on a
+    /// tolerated mismatch so the caller can warn.
+    pub fn check_embedding_model(
+        &mut self,
+        model: &str,
+        strict: bool,
+    ) -> AmemResult<Option<String>> {
+        match self.declare_embedding_model(model) {
+            EmbeddingModelCheck::Mismatch { file_model } if strict => {
+                Err(AmemError::EmbeddingModelMismatch {
+                    file_model,
+                    declared: model.trim().to_string(),
+                })
+            }
+            EmbeddingModelCheck::Mismatch { file_model } => Ok(Some(file_model)),
+            _ => Ok(None),
+        }
+    }
+
     /// Get the cluster map.
     pub fn cluster_map(&self) -> &ClusterMap {
         &self.cluster_map
@@ -366,4 +1003,14 @@ impl MemoryGraph {
     pub fn set_doc_lengths(&mut self, lengths: DocLengths) {
         self.doc_lengths = Some(lengths);
     }
+
+    /// Get the term bloom filters. None if the file was written without them.
+    pub fn term_bloom(&self) -> Option<&TermBloom> {
+        self.term_bloom.as_ref()
+    }
+
+    /// Set the term bloom filters.
+    pub fn set_term_bloom(&mut self, bloom: TermBloom) {
+        self.term_bloom = Some(bloom);
+    }
 }
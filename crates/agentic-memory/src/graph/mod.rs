@@ -1,9 +1,17 @@
 //! In-memory graph operations — the core data structure.
 
+pub mod backend;
 pub mod builder;
+pub mod integrity;
 pub mod memory_graph;
+#[cfg(feature = "sqlite-backend")]
+pub mod sqlite;
 pub mod traversal;
 
+pub use backend::GraphBackend;
 pub use builder::GraphBuilder;
-pub use memory_graph::MemoryGraph;
-pub use traversal::{bfs_traverse, TraversalDirection};
+pub use integrity::{IndexConsistencyReport, IndexDivergence, IndexKind, IntegrityViolation};
+pub use memory_graph::{EmbeddingModelCheck, GraphChanges, MemoryGraph};
+#[cfg(feature = "sqlite-backend")]
+pub use sqlite::SqliteGraph;
+pub use traversal::{bfs_traverse, connected_components, TraversalDirection};
@@ -0,0 +1,526 @@
+//! SQLite-backed graph store for graphs too large to hold in memory.
+//!
+//! Nodes live in a `nodes` table with their content, tags and feature
+//! vector; edges live in an `edges` table indexed by `(source_id,
+//! edge_type)` and by `target_id`. Requires the `sqlite-backend` feature.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use rusqlite::{params, Connection, OptionalExtension, Row};
+
+use crate::types::{
+    AmemError, AmemResult, CognitiveEvent, Edge, EdgeType, EdgeTypeNames, EventType,
+    DEFAULT_DIMENSION, MAX_EDGES_PER_NODE,
+};
+
+use super::backend::GraphBackend;
+use super::{GraphChanges, MemoryGraph};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS meta (
+    key   TEXT PRIMARY KEY,
+    value INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS nodes (
+    id            INTEGER PRIMARY KEY,
+    event_type    INTEGER NOT NULL,
+    created_at    INTEGER NOT NULL,
+    session_id    INTEGER NOT NULL,
+    confidence    REAL NOT NULL,
+    access_count  INTEGER NOT NULL,
+    last_accessed INTEGER NOT NULL,
+    decay_score   REAL NOT NULL,
+    content       TEXT NOT NULL,
+    tags          TEXT,
+    feature_vec   BLOB NOT NULL,
+    pinned        INTEGER NOT NULL DEFAULT 0
+);
+CREATE TABLE IF NOT EXISTS edges (
+    source_id  INTEGER NOT NULL,
+    target_id  INTEGER NOT NULL,
+    edge_type  TEXT NOT NULL,
+    weight     REAL NOT NULL,
+    created_at INTEGER NOT NULL,
+    PRIMARY KEY (source_id, target_id, edge_type)
+);
+CREATE INDEX IF NOT EXISTS edges_by_source ON edges (source_id, edge_type);
+CREATE INDEX IF NOT EXISTS edges_by_target ON edges (target_id);
+";
+
+const NODE_COLUMNS: &str = "id, event_type, created_at, session_id, confidence, access_count, \
+     last_accessed, decay_score, content, tags, feature_vec, pinned";
+
+const EDGE_COLUMNS: &str = "source_id, target_id, edge_type, weight, created_at";
+
+fn db_error(e: rusqlite::Error) -> AmemError {
+    AmemError::Storage(e.to_string())
+}
+
+/// A graph stored in a SQLite database.
+///
+/// Offers the node and edge operations of [`MemoryGraph`] through
+/// [`GraphBackend`] without loading the graph into memory. IDs are never
+/// reused, as in `MemoryGraph`.
+pub struct SqliteGraph {
+    conn: Connection,
+    dimension: usize,
+    next_id: u64,
+    /// Custom edge types, loaded from the names the `edges` table holds.
+    edge_type_names: EdgeTypeNames,
+}
+
+impl SqliteGraph {
+    /// Open a database, creating it with [`DEFAULT_DIMENSION`] if it is new.
+    pub fn open(path: &Path) -> AmemResult<Self> {
+        Self::init(Connection::open(path).map_err(db_error)?, None)
+    }
+
+    /// Open or create a database whose vectors have `dimension` entries.
+    ///
+    /// Fails with [`AmemError::DimensionMismatch`] if the database already
+    /// holds vectors of another width.
+    pub fn open_with_dimension(path: &Path, dimension: usize) -> AmemResult<Self> {
+        Self::init(Connection::open(path).map_err(db_error)?, Some(dimension))
+    }
+
+    /// A database held in memory (for tests).
+    pub fn open_in_memory(dimension: usize) -> AmemResult<Self> {
+        Self::init(
+            Connection::open_in_memory().map_err(db_error)?,
+            Some(dimension),
+        )
+    }
+
+    fn init(conn: Connection, dimension: Option<usize>) -> AmemResult<Self> {
+        conn.execute_batch("PRAGMA journal_mode=WAL; PRAGMA synchronous=NORMAL;")
+            .map_err(db_error)?;
+        conn.execute_batch(SCHEMA).map_err(db_error)?;
+        let mut graph = Self {
+            conn,
+            dimension: dimension.unwrap_or(DEFAULT_DIMENSION),
+            next_id: 0,
+            edge_type_names: EdgeTypeNames::new(),
+        };
+        match graph.meta("dimension")? {
+            Some(stored) if dimension.is_some_and(|d| d as u64 != stored) => {
+                return Err(AmemError::DimensionMismatch {
+                    expected: graph.dimension,
+                    got: stored as usize,
+                });
+            }
+            Some(stored) => graph.dimension = stored as usize,
+            None => graph.set_meta("dimension", graph.dimension as u64)?,
+        }
+        graph.next_id = graph.meta("next_id")?.unwrap_or(0);
+        let names: Vec<String> = {
+            let mut stmt = graph
+                .conn
+                .prepare("SELECT DISTINCT edge_type FROM edges ORDER BY edge_type")
+                .map_err(db_error)?;
+            let rows = stmt.query_map([], |row| row.get(0)).map_err(db_error)?;
+            rows.collect::<rusqlite::Result<_>>().map_err(db_error)?
+        };
+        for name in names {
+            graph.edge_type_names.register(&name)?;
+        }
+        Ok(graph)
+    }
+
+    /// Write `graph` into a new or existing database at `path`, replacing
+    /// its contents.
+    pub fn from_graph(graph: &MemoryGraph, path: &Path) -> AmemResult<Self> {
+        let mut db = Self::open_with_dimension(path, graph.dimension())?;
+        db.store(graph)?;
+        Ok(db)
+    }
+
+    fn meta(&self, key: &str) -> AmemResult<Option<u64>> {
+        self.conn
+            .query_row("SELECT value FROM meta WHERE key = ?1", [key], |row| {
+                row.get::<_, i64>(0)
+            })
+            .optional()
+            .map(|v| v.map(|v| v as u64))
+            .map_err(db_error)
+    }
+
+    fn set_meta(&self, key: &str, value: u64) -> AmemResult<()> {
+        self.conn
+            .execute(
+                "INSERT OR REPLACE INTO meta (key, value) VALUES (?1, ?2)",
+                params![key, value as i64],
+            )
+            .map(|_| ())
+            .map_err(db_error)
+    }
+
+    fn count(&self, sql: &str, id: Option<u64>) -> AmemResult<usize> {
+        let n: i64 = match id {
+            Some(id) => self.conn.query_row(sql, [id as i64], |row| row.get(0)),
+            None => self.conn.query_row(sql, [], |row| row.get(0)),
+        }
+        .map_err(db_error)?;
+        Ok(n as usize)
+    }
+
+    fn check_node(&self, event: &mut CognitiveEvent) -> AmemResult<()> {
+        event.validate(self.dimension)?;
+        if event.feature_vec.is_empty() {
+            event.feature_vec = vec![0.0; self.dimension];
+        }
+        Ok(())
+    }
+
+    /// Replace or insert `node` under its ID, raising the stored next ID
+    /// past it.
+    fn put_node_in(conn: &Connection, node: &CognitiveEvent, next_id: u64) -> AmemResult<()> {
+        conn.execute("DELETE FROM nodes WHERE id = ?1", [node.id as i64])
+            .map_err(db_error)?;
+        Self::insert_node(conn, node)?;
+        conn.execute(
+            "INSERT OR REPLACE INTO meta (key, value) VALUES ('next_id', ?1)",
+            [next_id.max(node.id + 1) as i64],
+        )
+        .map(|_| ())
+        .map_err(db_error)
+    }
+
+    fn remove_node_in(conn: &Connection, id: u64) -> AmemResult<()> {
+        conn.execute(
+            "DELETE FROM edges WHERE source_id = ?1 OR target_id = ?1",
+            [id as i64],
+        )
+        .map_err(db_error)?;
+        conn.execute("DELETE FROM nodes WHERE id = ?1", [id as i64])
+            .map(|_| ())
+            .map_err(db_error)
+    }
+
+    fn set_edges_from_in(
+        conn: &Connection,
+        source_id: u64,
+        edges: &[Edge],
+        names: &EdgeTypeNames,
+    ) -> AmemResult<()> {
+        conn.execute("DELETE FROM edges WHERE source_id = ?1", [source_id as i64])
+            .map_err(db_error)?;
+        for edge in edges {
+            Self::insert_edge(conn, &Edge { source_id, ..*edge }, names)?;
+        }
+        Ok(())
+    }
+
+    /// Register the names of the custom types `edges` use in `names`.
+    fn import_edge_types(&mut self, edges: &[Edge], names: &EdgeTypeNames) -> AmemResult<()> {
+        for edge in edges {
+            self.edge_type_names.import(edge.edge_type, names)?;
+        }
+        Ok(())
+    }
+
+    fn insert_node(conn: &Connection, node: &CognitiveEvent) -> AmemResult<()> {
+        let tags = if node.tags.is_empty() {
+            None
+        } else {
+            Some(serde_json::to_string(&node.tags).map_err(|e| AmemError::Storage(e.to_string()))?)
+        };
+        let vector: Vec<u8> = node
+            .feature_vec
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        conn.execute(
+            &format!(
+                "INSERT INTO nodes ({NODE_COLUMNS}) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)"
+            ),
+            params![
+                node.id as i64,
+                node.event_type as u8,
+                node.created_at as i64,
+                node.session_id,
+                node.confidence,
+                node.access_count,
+                node.last_accessed as i64,
+                node.decay_score,
+                node.content,
+                tags,
+                vector,
+                node.pinned,
+            ],
+        )
+        .map(|_| ())
+        .map_err(db_error)
+    }
+
+    fn insert_edge(conn: &Connection, edge: &Edge, names: &EdgeTypeNames) -> AmemResult<()> {
+        conn.execute(
+            &format!("INSERT OR REPLACE INTO edges ({EDGE_COLUMNS}) VALUES (?1, ?2, ?3, ?4, ?5)"),
+            params![
+                edge.source_id as i64,
+                edge.target_id as i64,
+                names.name(edge.edge_type),
+                edge.weight,
+                edge.created_at as i64,
+            ],
+        )
+        .map(|_| ())
+        .map_err(db_error)
+    }
+
+    fn row_to_node(row: &Row) -> rusqlite::Result<(CognitiveEvent, Option<String>)> {
+        let event_type: u8 = row.get(1)?;
+        let vector: Vec<u8> = row.get(10)?;
+        let node = CognitiveEvent {
+            id: row.get::<_, i64>(0)? as u64,
+            event_type: EventType::from_u8(event_type).unwrap_or(EventType::Fact),
+            created_at: row.get::<_, i64>(2)? as u64,
+            session_id: row.get(3)?,
+            confidence: row.get(4)?,
+            access_count: row.get(5)?,
+            last_accessed: row.get::<_, i64>(6)? as u64,
+            decay_score: row.get(7)?,
+            content: row.get(8)?,
+            feature_vec: vector
+                .chunks_exact(4)
+                .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .collect(),
+            tags: HashMap::new(),
+            pinned: row.get(11)?,
+        };
+        Ok((node, row.get(9)?))
+    }
+
+    fn query_nodes(&self, sql: &str, id: Option<u64>) -> AmemResult<Vec<CognitiveEvent>> {
+        let mut stmt = self.conn.prepare(sql).map_err(db_error)?;
+        let rows = match id {
+            Some(id) => stmt.query_map([id as i64], Self::row_to_node),
+            None => stmt.query_map([], Self::row_to_node),
+        }
+        .map_err(db_error)?;
+        rows.map(|row| {
+            let (mut node, tags) = row.map_err(db_error)?;
+            if let Some(tags) = tags {
+                node.tags =
+                    serde_json::from_str(&tags).map_err(|e| AmemError::Storage(e.to_string()))?;
+            }
+            Ok(node)
+        })
+        .collect()
+    }
+
+    fn query_edges(&self, sql: &str, id: Option<u64>) -> AmemResult<Vec<Edge>> {
+        let mut stmt = self.conn.prepare(sql).map_err(db_error)?;
+        let row_to_edge = |row: &Row| {
+            Ok((
+                row.get::<_, i64>(0)? as u64,
+                row.get::<_, i64>(1)? as u64,
+                row.get::<_, String>(2)?,
+                row.get::<_, f32>(3)?,
+                row.get::<_, i64>(4)? as u64,
+            ))
+        };
+        let rows = match id {
+            Some(id) => stmt.query_map([id as i64], row_to_edge),
+            None => stmt.query_map([], row_to_edge),
+        }
+        .map_err(db_error)?;
+        rows.map(|row| {
+            let (source_id, target_id, edge_type, weight, created_at) = row.map_err(db_error)?;
+            Ok(Edge {
+                source_id,
+                target_id,
+                edge_type: self
+                    .edge_type_names
+                    .get(&edge_type)
+                    .ok_or(AmemError::InvalidEdgeType(edge_type))?,
+                weight,
+                created_at,
+            })
+        })
+        .collect()
+    }
+}
+
+impl GraphBackend for SqliteGraph {
+    fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    fn node_count(&self) -> AmemResult<usize> {
+        self.count("SELECT COUNT(*) FROM nodes", None)
+    }
+
+    fn edge_count(&self) -> AmemResult<usize> {
+        self.count("SELECT COUNT(*) FROM edges", None)
+    }
+
+    fn add_node(&mut self, mut event: CognitiveEvent) -> AmemResult<u64> {
+        self.check_node(&mut event)?;
+        event.id = self.next_id;
+        let tx = self.conn.transaction().map_err(db_error)?;
+        Self::insert_node(&tx, &event)?;
+        tx.execute(
+            "INSERT OR REPLACE INTO meta (key, value) VALUES ('next_id', ?1)",
+            [(event.id + 1) as i64],
+        )
+        .map_err(db_error)?;
+        tx.commit().map_err(db_error)?;
+        self.next_id = event.id + 1;
+        Ok(event.id)
+    }
+
+    fn add_edge(&mut self, edge: Edge) -> AmemResult<()> {
+        if edge.source_id == edge.target_id {
+            return Err(AmemError::SelfEdge(edge.source_id));
+        }
+        if !self.edge_type_names.contains(edge.edge_type) {
+            return Err(AmemError::InvalidEdgeType(
+                self.edge_type_names.name(edge.edge_type).to_string(),
+            ));
+        }
+        let exists = "SELECT COUNT(*) FROM nodes WHERE id = ?1";
+        if self.count(exists, Some(edge.source_id))? == 0 {
+            return Err(AmemError::NodeNotFound(edge.source_id));
+        }
+        if self.count(exists, Some(edge.target_id))? == 0 {
+            return Err(AmemError::InvalidEdgeTarget(edge.target_id));
+        }
+        let out_degree = self.count(
+            "SELECT COUNT(*) FROM edges WHERE source_id = ?1",
+            Some(edge.source_id),
+        )?;
+        if out_degree >= MAX_EDGES_PER_NODE as usize {
+            return Err(AmemError::TooManyEdges(MAX_EDGES_PER_NODE));
+        }
+        Self::insert_edge(&self.conn, &edge, &self.edge_type_names)
+    }
+
+    fn put_node(&mut self, mut event: CognitiveEvent) -> AmemResult<()> {
+        self.check_node(&mut event)?;
+        let tx = self.conn.transaction().map_err(db_error)?;
+        Self::put_node_in(&tx, &event, self.next_id)?;
+        tx.commit().map_err(db_error)?;
+        self.next_id = self.next_id.max(event.id + 1);
+        Ok(())
+    }
+
+    fn remove_node(&mut self, id: u64) -> AmemResult<()> {
+        let tx = self.conn.transaction().map_err(db_error)?;
+        Self::remove_node_in(&tx, id)?;
+        tx.commit().map_err(db_error)
+    }
+
+    fn set_edges_from(
+        &mut self,
+        source_id: u64,
+        edges: &[Edge],
+        names: &EdgeTypeNames,
+    ) -> AmemResult<()> {
+        self.import_edge_types(edges, names)?;
+        let tx = self.conn.transaction().map_err(db_error)?;
+        Self::set_edges_from_in(&tx, source_id, edges, names)?;
+        tx.commit().map_err(db_error)
+    }
+
+    fn get_node(&self, id: u64) -> AmemResult<Option<CognitiveEvent>> {
+        let sql = format!("SELECT {NODE_COLUMNS} FROM nodes WHERE id = ?1");
+        Ok(self.query_nodes(&sql, Some(id))?.pop())
+    }
+
+    fn nodes(&self) -> AmemResult<Vec<CognitiveEvent>> {
+        self.query_nodes(
+            &format!("SELECT {NODE_COLUMNS} FROM nodes ORDER BY id"),
+            None,
+        )
+    }
+
+    fn edges(&self) -> AmemResult<Vec<Edge>> {
+        self.query_edges(
+            &format!("SELECT {EDGE_COLUMNS} FROM edges ORDER BY source_id, target_id"),
+            None,
+        )
+    }
+
+    fn edges_from(&self, source_id: u64) -> AmemResult<Vec<Edge>> {
+        self.query_edges(
+            &format!("SELECT {EDGE_COLUMNS} FROM edges WHERE source_id = ?1 ORDER BY target_id"),
+            Some(source_id),
+        )
+    }
+
+    fn edges_to(&self, target_id: u64) -> AmemResult<Vec<Edge>> {
+        self.query_edges(
+            &format!("SELECT {EDGE_COLUMNS} FROM edges WHERE target_id = ?1 ORDER BY source_id"),
+            Some(target_id),
+        )
+    }
+
+    fn edge_type_names(&self) -> &EdgeTypeNames {
+        &self.edge_type_names
+    }
+
+    fn register_edge_type(&mut self, name: &str) -> AmemResult<EdgeType> {
+        self.edge_type_names.register(name)
+    }
+
+    fn store(&mut self, graph: &MemoryGraph) -> AmemResult<()> {
+        if graph.dimension() != self.dimension {
+            return Err(AmemError::DimensionMismatch {
+                expected: self.dimension,
+                got: graph.dimension(),
+            });
+        }
+        let next_id = graph.next_id().max(self.next_id);
+        let tx = self.conn.transaction().map_err(db_error)?;
+        tx.execute_batch("DELETE FROM edges; DELETE FROM nodes;")
+            .map_err(db_error)?;
+        for node in graph.nodes() {
+            Self::insert_node(&tx, node)?;
+        }
+        for edge in graph.edges() {
+            Self::insert_edge(&tx, edge, graph.edge_type_names())?;
+        }
+        tx.execute(
+            "INSERT OR REPLACE INTO meta (key, value) VALUES ('next_id', ?1)",
+            [next_id as i64],
+        )
+        .map_err(db_error)?;
+        tx.commit().map_err(db_error)?;
+        self.next_id = next_id;
+        self.edge_type_names = graph.edge_type_names().clone();
+        Ok(())
+    }
+    /// Writes every change in one transaction.
+    fn apply_changes(&mut self, graph: &MemoryGraph, changes: &GraphChanges) -> AmemResult<()> {
+        if graph.dimension() != self.dimension {
+            return Err(AmemError::DimensionMismatch {
+                expected: self.dimension,
+                got: graph.dimension(),
+            });
+        }
+        for &id in &changes.edge_sources {
+            self.import_edge_types(graph.edges_from(id), graph.edge_type_names())?;
+        }
+        let mut next_id = self.next_id;
+        let tx = self.conn.transaction().map_err(db_error)?;
+        for &id in &changes.removed_nodes {
+            Self::remove_node_in(&tx, id)?;
+        }
+        for &id in &changes.nodes {
+            if let Some(node) = graph.get_node(id) {
+                Self::put_node_in(&tx, node, next_id)?;
+                next_id = next_id.max(id + 1);
+            }
+        }
+        for &id in &changes.edge_sources {
+            if graph.get_node(id).is_some() {
+                Self::set_edges_from_in(&tx, id, graph.edges_from(id), graph.edge_type_names())?;
+            }
+        }
+        tx.commit().map_err(db_error)?;
+        self.next_id = next_id;
+        Ok(())
+    }
+}
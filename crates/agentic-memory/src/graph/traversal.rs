@@ -18,7 +18,11 @@ pub enum TraversalDirection {
 }
 
 /// BFS traversal from a starting node, following specific edge types.
-#[allow(clippy::type_complexity)]
+///
+/// With `symmetric_undirected`, edges of symmetric types (see
+/// [`EdgeType::is_symmetric`]) are followed both ways whatever `direction`
+/// says, so a `RelatedTo` stored only as B -> A still leads from A to B.
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
 pub fn bfs_traverse(
     graph: &MemoryGraph,
     start_id: u64,
@@ -27,6 +31,7 @@ pub fn bfs_traverse(
     max_depth: u32,
     max_results: usize,
     min_confidence: f32,
+    symmetric_undirected: bool,
 ) -> AmemResult<(Vec<u64>, Vec<Edge>, HashMap<u64, u32>)> {
     if graph.get_node(start_id).is_none() {
         return Err(AmemError::NodeNotFound(start_id));
@@ -54,21 +59,26 @@ pub fn bfs_traverse(
 
         let mut neighbors: Vec<(u64, Edge)> = Vec::new();
 
+        let forward =
+            direction == TraversalDirection::Forward || direction == TraversalDirection::Both;
+        let backward =
+            direction == TraversalDirection::Backward || direction == TraversalDirection::Both;
+        let follows = |edge: &Edge, wanted: bool| {
+            edge_set.contains(&edge.edge_type)
+                && (wanted || (symmetric_undirected && edge.edge_type.is_symmetric()))
+        };
+
         // Forward: follow outgoing edges
-        if direction == TraversalDirection::Forward || direction == TraversalDirection::Both {
-            for edge in graph.edges_from(current_id) {
-                if edge_set.contains(&edge.edge_type) {
-                    neighbors.push((edge.target_id, *edge));
-                }
+        for edge in graph.edges_from(current_id) {
+            if follows(edge, forward) {
+                neighbors.push((edge.target_id, *edge));
             }
         }
 
         // Backward: follow incoming edges
-        if direction == TraversalDirection::Backward || direction == TraversalDirection::Both {
-            for edge in graph.edges_to(current_id) {
-                if edge_set.contains(&edge.edge_type) {
-                    neighbors.push((edge.source_id, *edge));
-                }
+        for edge in graph.edges_to(current_id) {
+            if follows(edge, backward) {
+                neighbors.push((edge.source_id, *edge));
             }
         }
 
@@ -99,3 +109,58 @@ pub fn bfs_traverse(
 
     Ok((visited_order, edges_traversed, depths))
 }
+
+/// Connected components of the graph on `node_ids`, treating `edges` as
+/// undirected. Edges to IDs outside `node_ids` are ignored.
+///
+/// Union-find with path halving and union by size, so the cost is
+/// O(V + E) up to the inverse Ackermann factor. Each component is sorted
+/// ascending, and components are ordered by their smallest ID.
+pub fn connected_components(
+    node_ids: &[u64],
+    edges: impl IntoIterator<Item = (u64, u64)>,
+) -> Vec<Vec<u64>> {
+    let index: HashMap<u64, usize> = node_ids
+        .iter()
+        .enumerate()
+        .map(|(i, &id)| (id, i))
+        .collect();
+    let mut parent: Vec<usize> = (0..node_ids.len()).collect();
+    let mut size = vec![1usize; node_ids.len()];
+
+    for (a, b) in edges {
+        let (Some(&a), Some(&b)) = (index.get(&a), index.get(&b)) else {
+            continue;
+        };
+        let (mut a, mut b) = (find_root(&mut parent, a), find_root(&mut parent, b));
+        if a == b {
+            continue;
+        }
+        if size[a] < size[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        parent[b] = a;
+        size[a] += size[b];
+    }
+
+    let mut by_root: HashMap<usize, Vec<u64>> = HashMap::new();
+    for (i, &id) in node_ids.iter().enumerate() {
+        let root = find_root(&mut parent, i);
+        by_root.entry(root).or_default().push(id);
+    }
+    let mut components: Vec<Vec<u64>> = by_root.into_values().collect();
+    for component in &mut components {
+        component.sort_unstable();
+    }
+    components.sort_unstable_by_key(|c| c[0]);
+    components
+}
+
+/// Union-find root of `i`, halving the path on the way.
+fn find_root(parent: &mut [usize], mut i: usize) -> usize {
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+    i
+}
@@ -1,6 +1,61 @@
 //! Cluster map — pre-computed k-means clustering of feature vectors.
 
+use crate::util::splitmix64;
+
+/// Vectors drawn per round of mini-batch k-means in [`ClusterMap::cluster`].
+pub const KMEANS_BATCH_SIZE: usize = 256;
+
+/// Where one node landed in a k-means clustering.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClusterAssignment {
+    pub node_id: u64,
+    pub cluster_id: usize,
+    /// Cosine distance (1 - cosine similarity) to the cluster's centroid.
+    pub distance_to_centroid: f32,
+}
+
+/// Uniform draw from `[0, 1)`.
+fn unit_f64(state: &mut u64) -> f64 {
+    (splitmix64(state) >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// k-means++ seeding: the first centroid is a random vector, each further
+/// one is drawn with probability proportional to its squared cosine
+/// distance from the nearest centroid chosen so far.
+fn kmeans_plus_plus(points: &[(u64, &[f32])], k: usize, state: &mut u64) -> Vec<Vec<f32>> {
+    let first = (splitmix64(state) % points.len() as u64) as usize;
+    let mut centroids = vec![points[first].1.to_vec()];
+    let mut nearest: Vec<f64> = points
+        .iter()
+        .map(|(_, v)| (1.0 - cosine_similarity(v, &centroids[0])) as f64)
+        .collect();
+
+    while centroids.len() < k {
+        let total: f64 = nearest.iter().map(|d| d * d).sum();
+        let pick = if total > 0.0 {
+            let mut target = unit_f64(state) * total;
+            nearest
+                .iter()
+                .position(|d| {
+                    target -= d * d;
+                    target < 0.0
+                })
+                .unwrap_or(points.len() - 1)
+        } else {
+            // Every vector sits on a centroid already; any pick will do.
+            (splitmix64(state) % points.len() as u64) as usize
+        };
+        let centroid = points[pick].1.to_vec();
+        for (d, (_, v)) in nearest.iter_mut().zip(points) {
+            *d = d.min((1.0 - cosine_similarity(v, &centroid)) as f64);
+        }
+        centroids.push(centroid);
+    }
+    centroids
+}
+
 /// Pre-computed clustering of feature vectors for fast semantic grouping.
+#[derive(Clone)]
 pub struct ClusterMap {
     /// Cluster centroids.
     centroids: Vec<Vec<f32>>,
@@ -109,6 +164,84 @@ impl ClusterMap {
         best
     }
 
+    /// Mini-batch k-means over `nodes` into `k` clusters, replacing the
+    /// map's clusters with the result.
+    ///
+    /// Centroids start from a k-means++ pick. Each of the `max_iters` rounds
+    /// then draws [`KMEANS_BATCH_SIZE`] vectors (all of them when there are
+    /// fewer) and moves each one's nearest centroid toward it, at a rate of
+    /// one over the number of vectors that centroid has absorbed. Nearness is
+    /// cosine similarity, as in [`build`](Self::build). Zero vectors are
+    /// skipped and `k` is capped at the number left. `seed` drives every
+    /// random choice, so the same input and seed give the same clusters.
+    ///
+    /// Returns one assignment per clustered node, in input order.
+    pub fn cluster(
+        &mut self,
+        nodes: &[(u64, &[f32])],
+        k: usize,
+        max_iters: u32,
+        seed: u64,
+    ) -> Vec<ClusterAssignment> {
+        let points: Vec<(u64, &[f32])> = nodes
+            .iter()
+            .filter(|(_, v)| v.iter().any(|&x| x != 0.0))
+            .copied()
+            .collect();
+        self.clear();
+        let k = k.min(points.len());
+        if k == 0 {
+            return Vec::new();
+        }
+
+        let mut state = seed;
+        self.centroids = kmeans_plus_plus(&points, k, &mut state);
+        let mut absorbed = vec![0u32; k];
+        let batch_size = KMEANS_BATCH_SIZE.min(points.len());
+        for _ in 0..max_iters {
+            let batch: Vec<usize> = if batch_size == points.len() {
+                (0..batch_size).collect()
+            } else {
+                (0..batch_size)
+                    .map(|_| (splitmix64(&mut state) % points.len() as u64) as usize)
+                    .collect()
+            };
+            // Assign the whole batch before moving any centroid.
+            let nearest: Vec<usize> = batch
+                .iter()
+                .map(|&p| self.find_nearest_centroid(points[p].1))
+                .collect();
+            for (&p, &ci) in batch.iter().zip(&nearest) {
+                absorbed[ci] += 1;
+                let rate = 1.0 / absorbed[ci] as f32;
+                for (c, &x) in self.centroids[ci].iter_mut().zip(points[p].1) {
+                    *c += rate * (x - *c);
+                }
+            }
+        }
+
+        self.assignments = vec![Vec::new(); k];
+        let mut result = Vec::with_capacity(points.len());
+        for &(node_id, vec) in &points {
+            let cluster_id = self.find_nearest_centroid(vec);
+            self.assignments[cluster_id].push(node_id);
+            result.push(ClusterAssignment {
+                node_id,
+                cluster_id,
+                distance_to_centroid: 1.0 - cosine_similarity(vec, &self.centroids[cluster_id]),
+            });
+        }
+        for a in &mut self.assignments {
+            a.sort_unstable();
+        }
+        result
+    }
+
+    /// Each cluster's index with a copy of its centroid vector.
+    pub fn cluster_centroids(&self) -> Vec<(usize, Vec<f32>)> {
+        self.centroids.iter().cloned().enumerate().collect()
+    }
+
     /// Find the nearest cluster for a query vector.
     pub fn nearest_cluster(&self, query: &[f32]) -> Option<usize> {
         if self.centroids.is_empty() {
@@ -177,7 +310,26 @@ impl ClusterMap {
 }
 
 /// Compute cosine similarity between two vectors.
+///
+/// Uses the SIMD kernels when built with the `simd` feature, otherwise the
+/// same loop as [`cosine_similarity_scalar`].
 pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    #[cfg(feature = "simd")]
+    let (dot, norm_a, norm_b) = super::simd::dot_and_norms(a, b);
+    #[cfg(not(feature = "simd"))]
+    let (dot, norm_a, norm_b) = scalar_dot_and_norms(a, b);
+    finish_cosine(dot, norm_a, norm_b)
+}
+
+/// Cosine similarity computed with the plain scalar loop, whatever features
+/// are enabled. Reference for the SIMD path.
+pub fn cosine_similarity_scalar(a: &[f32], b: &[f32]) -> f32 {
+    let (dot, norm_a, norm_b) = scalar_dot_and_norms(a, b);
+    finish_cosine(dot, norm_a, norm_b)
+}
+
+/// Dot product and squared norms over the shorter of the two lengths.
+pub(crate) fn scalar_dot_and_norms(a: &[f32], b: &[f32]) -> (f32, f32, f32) {
     let mut dot = 0.0f32;
     let mut norm_a = 0.0f32;
     let mut norm_b = 0.0f32;
@@ -186,6 +338,10 @@ pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
         norm_a += a[i] * a[i];
         norm_b += b[i] * b[i];
     }
+    (dot, norm_a, norm_b)
+}
+
+fn finish_cosine(dot: f32, norm_a: f32, norm_b: f32) -> f32 {
     let denom = norm_a.sqrt() * norm_b.sqrt();
     if denom == 0.0 {
         0.0
@@ -6,6 +6,7 @@ use crate::types::CognitiveEvent;
 
 /// Document length table for BM25 normalization.
 /// Stores the token count for each node's content indexed by node ID (dense array).
+#[derive(Clone)]
 pub struct DocLengths {
     /// node_id → token count (document length). Indexed by node ID.
     lengths: Vec<u32>,
@@ -28,7 +29,9 @@ impl DocLengths {
             if id >= lengths.len() {
                 lengths.resize(id + 1, 0);
             }
-            lengths[id] = tokenizer.tokenize(&node.content).len() as u32;
+            lengths[id] = tokenizer
+                .tokenize_as(&node.content, graph.node_language(node.id))
+                .len() as u32;
         }
 
         Self { lengths }
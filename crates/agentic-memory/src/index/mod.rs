@@ -3,13 +3,21 @@
 pub mod cluster_map;
 pub mod doc_lengths;
 pub mod session_index;
+#[cfg(feature = "simd")]
+mod simd;
+pub mod tag_index;
 pub mod temporal_index;
+pub mod term_bloom;
 pub mod term_index;
 pub mod type_index;
 
-pub use cluster_map::{cosine_similarity, ClusterMap};
+pub use cluster_map::{
+    cosine_similarity, cosine_similarity_scalar, ClusterAssignment, ClusterMap, KMEANS_BATCH_SIZE,
+};
 pub use doc_lengths::DocLengths;
 pub use session_index::SessionIndex;
+pub use tag_index::TagIndex;
 pub use temporal_index::TemporalIndex;
+pub use term_bloom::{TermBloom, DEFAULT_BLOOM_FP_RATE};
 pub use term_index::TermIndex;
 pub use type_index::TypeIndex;
@@ -2,11 +2,20 @@
 
 use std::collections::HashMap;
 
-use crate::types::CognitiveEvent;
+use crate::types::{AmemError, AmemResult, CognitiveEvent};
+
+/// Longest accepted session alias, in bytes.
+pub const MAX_SESSION_ALIAS_LEN: usize = 128;
 
 /// Maps each session_id to a sorted list of node IDs in that session.
+///
+/// Sessions can also carry a stable alias ("project-alpha"). Aliases are
+/// unique, at most one per session, and are not tied to node membership, so
+/// rebuilding the node lists keeps them.
+#[derive(Clone)]
 pub struct SessionIndex {
     index: HashMap<u32, Vec<u64>>,
+    aliases: HashMap<String, u32>,
 }
 
 impl SessionIndex {
@@ -14,6 +23,7 @@ impl SessionIndex {
     pub fn new() -> Self {
         Self {
             index: HashMap::new(),
+            aliases: HashMap::new(),
         }
     }
 
@@ -104,6 +114,105 @@ impl SessionIndex {
     pub fn inner(&self) -> &HashMap<u32, Vec<u64>> {
         &self.index
     }
+
+    /// Give `session_id` the alias `alias`, replacing any alias it had.
+    ///
+    /// Fails if the alias is empty, longer than [`MAX_SESSION_ALIAS_LEN`],
+    /// purely numeric (it would be ambiguous with a session ID), or already
+    /// assigned to a different session.
+    pub fn rename(&mut self, session_id: u32, alias: &str) -> AmemResult<()> {
+        let alias = alias.trim();
+        if alias.is_empty() || alias.len() > MAX_SESSION_ALIAS_LEN || alias.parse::<u32>().is_ok() {
+            return Err(AmemError::InvalidSessionAlias(alias.to_string()));
+        }
+        match self.aliases.get(alias) {
+            Some(&owner) if owner == session_id => return Ok(()),
+            Some(&owner) => {
+                return Err(AmemError::SessionAliasTaken {
+                    alias: alias.to_string(),
+                    session_id: owner,
+                })
+            }
+            None => {}
+        }
+        self.aliases.retain(|_, sid| *sid != session_id);
+        self.aliases.insert(alias.to_string(), session_id);
+        Ok(())
+    }
+
+    /// Remove an alias. Returns the session it pointed to, if any.
+    pub fn remove_alias(&mut self, alias: &str) -> Option<u32> {
+        self.aliases.remove(alias.trim())
+    }
+
+    /// Look up the session carrying `alias`.
+    pub fn resolve_alias(&self, alias: &str) -> Option<u32> {
+        self.aliases.get(alias.trim()).copied()
+    }
+
+    /// The alias of `session_id`, if it has one.
+    pub fn alias_of(&self, session_id: u32) -> Option<&str> {
+        self.aliases
+            .iter()
+            .find(|(_, sid)| **sid == session_id)
+            .map(|(alias, _)| alias.as_str())
+    }
+
+    /// All aliases, sorted by alias.
+    pub fn aliases(&self) -> Vec<(&str, u32)> {
+        let mut all: Vec<(&str, u32)> = self
+            .aliases
+            .iter()
+            .map(|(alias, &sid)| (alias.as_str(), sid))
+            .collect();
+        all.sort_unstable();
+        all
+    }
+
+    /// Serialize the alias map: `[count: u32]` then per alias
+    /// `[session_id: u32][len: u16][utf8 bytes]`, sorted by alias.
+    pub fn aliases_to_bytes(&self) -> Vec<u8> {
+        let all = self.aliases();
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&(all.len() as u32).to_le_bytes());
+        for (alias, sid) in all {
+            buf.extend_from_slice(&sid.to_le_bytes());
+            buf.extend_from_slice(&(alias.len() as u16).to_le_bytes());
+            buf.extend_from_slice(alias.as_bytes());
+        }
+        buf
+    }
+
+    /// Restore aliases written by [`SessionIndex::aliases_to_bytes`].
+    /// Malformed input leaves the aliases unchanged and returns `false`.
+    pub fn load_aliases(&mut self, data: &[u8]) -> bool {
+        fn parse(data: &[u8]) -> Option<Vec<(String, u32)>> {
+            let count = u32::from_le_bytes(data.get(0..4)?.try_into().ok()?) as usize;
+            let mut pos = 4;
+            let mut out = Vec::with_capacity(count.min(1024));
+            for _ in 0..count {
+                let sid = u32::from_le_bytes(data.get(pos..pos + 4)?.try_into().ok()?);
+                let len = u16::from_le_bytes(data.get(pos + 4..pos + 6)?.try_into().ok()?) as usize;
+                pos += 6;
+                let alias = std::str::from_utf8(data.get(pos..pos + len)?).ok()?;
+                pos += len;
+                out.push((alias.to_string(), sid));
+            }
+            Some(out)
+        }
+
+        let Some(entries) = parse(data) else {
+            return false;
+        };
+        let mut loaded = SessionIndex::new();
+        for (alias, sid) in entries {
+            if loaded.rename(sid, &alias).is_err() {
+                return false;
+            }
+        }
+        self.aliases = loaded.aliases;
+        true
+    }
 }
 
 impl Default for SessionIndex {
@@ -0,0 +1,104 @@
+//! SIMD kernels for [`cosine_similarity`](super::cosine_similarity).
+//!
+//! Compiled with the `simd` feature. On x86_64 the AVX+FMA kernel is used
+//! when the CPU supports it (checked at runtime); on aarch64 NEON is always
+//! available. Every other target, and x86_64 CPUs without AVX, use the scalar
+//! loop. Lanes are summed in a different order from the scalar loop, so
+//! results agree to within float rounding rather than bit for bit.
+
+#[cfg(not(target_arch = "aarch64"))]
+use super::cluster_map::scalar_dot_and_norms;
+
+/// Dot product of `a` and `b` and the squared norm of each, over the
+/// shorter of the two lengths.
+#[cfg(not(target_arch = "aarch64"))]
+pub(crate) fn dot_and_norms(a: &[f32], b: &[f32]) -> (f32, f32, f32) {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if is_x86_feature_detected!("avx") && is_x86_feature_detected!("fma") {
+            // SAFETY: the required CPU features were detected above.
+            return unsafe { avx::dot_and_norms(a, b) };
+        }
+    }
+    scalar_dot_and_norms(a, b)
+}
+
+/// Dot product of `a` and `b` and the squared norm of each, over the
+/// shorter of the two lengths.
+#[cfg(target_arch = "aarch64")]
+pub(crate) fn dot_and_norms(a: &[f32], b: &[f32]) -> (f32, f32, f32) {
+    // SAFETY: NEON is part of the aarch64 baseline.
+    unsafe { neon::dot_and_norms(a, b) }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod avx {
+    use std::arch::x86_64::*;
+
+    const LANES: usize = 8;
+
+    #[target_feature(enable = "avx,fma")]
+    pub(super) unsafe fn dot_and_norms(a: &[f32], b: &[f32]) -> (f32, f32, f32) {
+        let n = a.len().min(b.len());
+        let body = n - n % LANES;
+        let mut dot = _mm256_setzero_ps();
+        let mut norm_a = _mm256_setzero_ps();
+        let mut norm_b = _mm256_setzero_ps();
+        let mut i = 0;
+        while i < body {
+            let va = _mm256_loadu_ps(a.as_ptr().add(i));
+            let vb = _mm256_loadu_ps(b.as_ptr().add(i));
+            dot = _mm256_fmadd_ps(va, vb, dot);
+            norm_a = _mm256_fmadd_ps(va, va, norm_a);
+            norm_b = _mm256_fmadd_ps(vb, vb, norm_b);
+            i += LANES;
+        }
+        let (mut dot, mut norm_a, mut norm_b) = (sum(dot), sum(norm_a), sum(norm_b));
+        for (x, y) in a[body..n].iter().zip(&b[body..n]) {
+            dot += x * y;
+            norm_a += x * x;
+            norm_b += y * y;
+        }
+        (dot, norm_a, norm_b)
+    }
+
+    #[target_feature(enable = "avx")]
+    unsafe fn sum(v: __m256) -> f32 {
+        let mut lanes = [0.0f32; LANES];
+        _mm256_storeu_ps(lanes.as_mut_ptr(), v);
+        lanes.iter().sum()
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+mod neon {
+    use std::arch::aarch64::*;
+
+    const LANES: usize = 4;
+
+    #[target_feature(enable = "neon")]
+    pub(super) unsafe fn dot_and_norms(a: &[f32], b: &[f32]) -> (f32, f32, f32) {
+        let n = a.len().min(b.len());
+        let body = n - n % LANES;
+        let mut dot = vdupq_n_f32(0.0);
+        let mut norm_a = vdupq_n_f32(0.0);
+        let mut norm_b = vdupq_n_f32(0.0);
+        let mut i = 0;
+        while i < body {
+            let va = vld1q_f32(a.as_ptr().add(i));
+            let vb = vld1q_f32(b.as_ptr().add(i));
+            dot = vfmaq_f32(dot, va, vb);
+            norm_a = vfmaq_f32(norm_a, va, va);
+            norm_b = vfmaq_f32(norm_b, vb, vb);
+            i += LANES;
+        }
+        let (mut dot, mut norm_a, mut norm_b) =
+            (vaddvq_f32(dot), vaddvq_f32(norm_a), vaddvq_f32(norm_b));
+        for (x, y) in a[body..n].iter().zip(&b[body..n]) {
+            dot += x * y;
+            norm_a += x * x;
+            norm_b += y * y;
+        }
+        (dot, norm_a, norm_b)
+    }
+}
@@ -0,0 +1,143 @@
+//! Index by node tag — maps each `key=value` tag to sorted node IDs.
+
+use std::collections::HashMap;
+
+use crate::types::CognitiveEvent;
+
+/// Maps each tag key, then value, to a sorted list of node IDs.
+#[derive(Clone)]
+pub struct TagIndex {
+    index: HashMap<String, HashMap<String, Vec<u64>>>,
+}
+
+impl TagIndex {
+    /// Create a new, empty tag index.
+    pub fn new() -> Self {
+        Self {
+            index: HashMap::new(),
+        }
+    }
+
+    /// Node IDs tagged `key=value`, ascending.
+    pub fn get(&self, key: &str, value: &str) -> &[u64] {
+        self.index
+            .get(key)
+            .and_then(|values| values.get(value))
+            .map(|v| v.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Node IDs carrying any of `tags`, merged and sorted.
+    pub fn get_any(&self, tags: &[(String, String)]) -> Vec<u64> {
+        let mut result: Vec<u64> = Vec::new();
+        for (key, value) in tags {
+            result.extend_from_slice(self.get(key, value));
+        }
+        result.sort_unstable();
+        result.dedup();
+        result
+    }
+
+    /// Node IDs carrying every one of `tags`, sorted. Empty when `tags` is.
+    pub fn get_all(&self, tags: &[(String, String)]) -> Vec<u64> {
+        let mut lists: Vec<&[u64]> = tags.iter().map(|(k, v)| self.get(k, v)).collect();
+        // Intersect starting from the rarest tag.
+        lists.sort_by_key(|list| list.len());
+        let Some((first, rest)) = lists.split_first() else {
+            return Vec::new();
+        };
+        first
+            .iter()
+            .copied()
+            .filter(|id| rest.iter().all(|list| list.binary_search(id).is_ok()))
+            .collect()
+    }
+
+    /// Count nodes tagged `key=value`.
+    pub fn count(&self, key: &str, value: &str) -> usize {
+        self.get(key, value).len()
+    }
+
+    /// Rebuild the entire index from a slice of nodes.
+    pub fn rebuild(&mut self, nodes: &[CognitiveEvent]) {
+        self.index.clear();
+        for node in nodes {
+            for (key, value) in &node.tags {
+                self.index
+                    .entry(key.clone())
+                    .or_default()
+                    .entry(value.clone())
+                    .or_default()
+                    .push(node.id);
+            }
+        }
+        // Ensure each list is sorted
+        for list in self
+            .index
+            .values_mut()
+            .flat_map(|values| values.values_mut())
+        {
+            list.sort_unstable();
+        }
+    }
+
+    /// Incrementally add a new node.
+    pub fn add_node(&mut self, event: &CognitiveEvent) {
+        self.add_tags(event.id, &event.tags);
+    }
+
+    /// Record that node `id` carries `tags`.
+    pub fn add_tags(&mut self, id: u64, tags: &HashMap<String, String>) {
+        for (key, value) in tags {
+            let list = self
+                .index
+                .entry(key.clone())
+                .or_default()
+                .entry(value.clone())
+                .or_default();
+            let pos = list.binary_search(&id).unwrap_or_else(|p| p);
+            list.insert(pos, id);
+        }
+    }
+
+    /// Remove a node carrying `tags` from the index.
+    pub fn remove_node(&mut self, id: u64, tags: &HashMap<String, String>) {
+        for (key, value) in tags {
+            let Some(values) = self.index.get_mut(key) else {
+                continue;
+            };
+            if let Some(list) = values.get_mut(value) {
+                if let Ok(pos) = list.binary_search(&id) {
+                    list.remove(pos);
+                }
+                if list.is_empty() {
+                    values.remove(value);
+                }
+            }
+            if values.is_empty() {
+                self.index.remove(key);
+            }
+        }
+    }
+
+    /// Clear the index.
+    pub fn clear(&mut self) {
+        self.index.clear();
+    }
+
+    /// Number of distinct `key=value` tags.
+    pub fn len(&self) -> usize {
+        self.index.values().map(|values| values.len()).sum()
+    }
+
+    /// Whether no node carries a tag.
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+}
+
+impl Default for TagIndex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -3,6 +3,7 @@
 use crate::types::CognitiveEvent;
 
 /// Sorted list of (created_at, node_id) pairs for efficient time range queries.
+#[derive(Clone)]
 pub struct TemporalIndex {
     /// Sorted by timestamp ascending.
     entries: Vec<(u64, u64)>,
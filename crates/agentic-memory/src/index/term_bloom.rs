@@ -0,0 +1,209 @@
+//! Per-document bloom filters over tokenized terms.
+//!
+//! Used to prefilter candidate documents for queries that require every term
+//! to be present. A filter can answer "definitely absent" or "maybe present";
+//! a false positive only costs a posting lookup, never a wrong result.
+
+use std::collections::HashMap;
+
+use crate::engine::tokenizer::Tokenizer;
+use crate::graph::MemoryGraph;
+
+/// Default target false-positive rate for newly built filters.
+pub const DEFAULT_BLOOM_FP_RATE: f32 = 0.01;
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+const SECOND_SEED: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// A single document's bloom filter.
+#[derive(Clone)]
+struct DocBloom {
+    /// Number of hash probes per term.
+    hashes: u8,
+    /// Bit array, length is a multiple of 8 bits.
+    bits: Vec<u8>,
+}
+
+impl DocBloom {
+    fn with_capacity(term_count: usize, fp_rate: f32) -> Self {
+        let n = term_count.max(1) as f64;
+        let p = (fp_rate as f64).clamp(1e-6, 0.5);
+        let ln2 = std::f64::consts::LN_2;
+        let m_bits = (-(n * p.ln()) / (ln2 * ln2)).ceil().max(8.0) as usize;
+        let bytes = m_bits.div_ceil(8);
+        let k = ((bytes * 8) as f64 / n * ln2).round().clamp(1.0, 16.0) as u8;
+        Self {
+            hashes: k,
+            bits: vec![0u8; bytes],
+        }
+    }
+
+    fn bit_len(&self) -> u64 {
+        (self.bits.len() * 8) as u64
+    }
+
+    fn insert(&mut self, term: &str) {
+        let (h1, h2) = term_hashes(term);
+        let m = self.bit_len();
+        for i in 0..self.hashes as u64 {
+            let bit = h1.wrapping_add(i.wrapping_mul(h2)) % m;
+            self.bits[(bit / 8) as usize] |= 1 << (bit % 8);
+        }
+    }
+
+    fn might_contain(&self, term: &str) -> bool {
+        let m = self.bit_len();
+        if m == 0 {
+            return true;
+        }
+        let (h1, h2) = term_hashes(term);
+        (0..self.hashes as u64).all(|i| {
+            let bit = h1.wrapping_add(i.wrapping_mul(h2)) % m;
+            self.bits[(bit / 8) as usize] & (1 << (bit % 8)) != 0
+        })
+    }
+}
+
+/// Bloom filters of each node's content terms, keyed by node ID.
+#[derive(Clone)]
+pub struct TermBloom {
+    /// Target false-positive rate the filters were sized for.
+    fp_rate: f32,
+    filters: HashMap<u64, DocBloom>,
+}
+
+impl TermBloom {
+    /// Create an empty filter set sized for the given false-positive rate.
+    pub fn new(fp_rate: f32) -> Self {
+        Self {
+            fp_rate: fp_rate.clamp(1e-6, 0.5),
+            filters: HashMap::new(),
+        }
+    }
+
+    /// Build filters for every node in the graph.
+    pub fn build(graph: &MemoryGraph, tokenizer: &Tokenizer, fp_rate: f32) -> Self {
+        let mut bloom = Self::new(fp_rate);
+        for node in graph.nodes() {
+            let freqs = tokenizer.term_frequencies_as(&node.content, graph.node_language(node.id));
+            let mut filter = DocBloom::with_capacity(freqs.len(), bloom.fp_rate);
+            for term in freqs.keys() {
+                filter.insert(term);
+            }
+            bloom.filters.insert(node.id, filter);
+        }
+        bloom
+    }
+
+    /// Whether the node may contain the term.
+    ///
+    /// Returns `true` for nodes without a filter (e.g. added after the file
+    /// was written), so callers always fall back to an exact check.
+    pub fn might_contain(&self, node_id: u64, term: &str) -> bool {
+        self.filters
+            .get(&node_id)
+            .map(|f| f.might_contain(term))
+            .unwrap_or(true)
+    }
+
+    /// Drop the node's filter; it then matches every term again.
+    pub fn remove_node(&mut self, node_id: u64) {
+        self.filters.remove(&node_id);
+    }
+
+    /// Whether the node may contain every one of the terms.
+    pub fn might_contain_all(&self, node_id: u64, terms: &[String]) -> bool {
+        match self.filters.get(&node_id) {
+            Some(f) => terms.iter().all(|t| f.might_contain(t)),
+            None => true,
+        }
+    }
+
+    /// Target false-positive rate.
+    pub fn fp_rate(&self) -> f32 {
+        self.fp_rate
+    }
+
+    /// Number of documents with a filter.
+    pub fn len(&self) -> usize {
+        self.filters.len()
+    }
+
+    /// Whether no filters are stored.
+    pub fn is_empty(&self) -> bool {
+        self.filters.is_empty()
+    }
+
+    /// Total bytes used by filter bit arrays.
+    pub fn size_bytes(&self) -> usize {
+        self.filters.values().map(|f| f.bits.len()).sum()
+    }
+
+    /// Serialize for the index block.
+    ///
+    /// Layout: `[fp_rate: f32][doc_count: u64]` then per document
+    /// `[node_id: u64][hashes: u8][byte_len: u32][bits]`, sorted by node ID.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf: Vec<u8> = Vec::new();
+        buf.extend_from_slice(&self.fp_rate.to_le_bytes());
+        buf.extend_from_slice(&(self.filters.len() as u64).to_le_bytes());
+
+        let mut ids: Vec<u64> = self.filters.keys().copied().collect();
+        ids.sort_unstable();
+        for id in ids {
+            let filter = &self.filters[&id];
+            buf.extend_from_slice(&id.to_le_bytes());
+            buf.push(filter.hashes);
+            buf.extend_from_slice(&(filter.bits.len() as u32).to_le_bytes());
+            buf.extend_from_slice(&filter.bits);
+        }
+        buf
+    }
+
+    /// Deserialize from the index block.
+    pub fn from_bytes(data: &[u8]) -> Option<Self> {
+        if data.len() < 12 {
+            return None;
+        }
+        let fp_rate = f32::from_le_bytes(data[0..4].try_into().ok()?);
+        let count = u64::from_le_bytes(data[4..12].try_into().ok()?) as usize;
+        let mut pos = 12;
+
+        let mut filters = HashMap::with_capacity(count.min(data.len() / 13));
+        for _ in 0..count {
+            if pos + 13 > data.len() {
+                return None;
+            }
+            let id = u64::from_le_bytes(data[pos..pos + 8].try_into().ok()?);
+            let hashes = data[pos + 8];
+            let len = u32::from_le_bytes(data[pos + 9..pos + 13].try_into().ok()?) as usize;
+            pos += 13;
+            if pos + len > data.len() {
+                return None;
+            }
+            let bits = data[pos..pos + len].to_vec();
+            pos += len;
+            filters.insert(id, DocBloom { hashes, bits });
+        }
+
+        Some(Self { fp_rate, filters })
+    }
+}
+
+fn fnv1a(bytes: &[u8], seed: u64) -> u64 {
+    let mut hash = seed;
+    for &b in bytes {
+        hash ^= b as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    hash
+}
+
+/// Two independent hashes for double hashing (`h1 + i * h2`).
+fn term_hashes(term: &str) -> (u64, u64) {
+    let bytes = term.as_bytes();
+    let h1 = fnv1a(bytes, FNV_OFFSET);
+    let h2 = fnv1a(bytes, FNV_OFFSET ^ SECOND_SEED) | 1;
+    (h1, h2)
+}
@@ -7,6 +7,7 @@ use crate::graph::MemoryGraph;
 use crate::types::CognitiveEvent;
 
 /// An inverted index mapping tokenized terms to posting lists (sorted node ID arrays).
+#[derive(Clone)]
 pub struct TermIndex {
     /// term → sorted Vec of (node_id, term_frequency_in_node)
     postings: HashMap<String, Vec<(u64, u32)>>,
@@ -32,7 +33,7 @@ impl TermIndex {
         let mut total_tokens: u64 = 0;
 
         for node in graph.nodes() {
-            let freqs = tokenizer.term_frequencies(&node.content);
+            let freqs = tokenizer.term_frequencies_as(&node.content, graph.node_language(node.id));
             let doc_len: u32 = freqs.values().sum();
             total_tokens += doc_len as u64;
 
@@ -60,6 +61,15 @@ impl TermIndex {
         self.postings.get(term).map(|v| v.as_slice()).unwrap_or(&[])
     }
 
+    /// Term frequency of `term` in one node, via binary search of its posting list.
+    pub fn term_frequency(&self, term: &str, node_id: u64) -> Option<u32> {
+        let posting = self.postings.get(term)?;
+        posting
+            .binary_search_by_key(&node_id, |(id, _)| *id)
+            .ok()
+            .map(|pos| posting[pos].1)
+    }
+
     /// Number of nodes containing a term (document frequency).
     pub fn doc_frequency(&self, term: &str) -> usize {
         self.postings.get(term).map(|v| v.len()).unwrap_or(0)
@@ -80,6 +90,13 @@ impl TermIndex {
         self.postings.len()
     }
 
+    /// Iterate over every term and its posting list, in no particular order.
+    pub fn terms(&self) -> impl Iterator<Item = (&str, &[(u64, u32)])> + '_ {
+        self.postings
+            .iter()
+            .map(|(term, posting)| (term.as_str(), posting.as_slice()))
+    }
+
     /// Add a single node to the index incrementally.
     pub fn add_node(&mut self, event: &CognitiveEvent) {
         let tokenizer = Tokenizer::new();
@@ -5,6 +5,7 @@ use std::collections::HashMap;
 use crate::types::{CognitiveEvent, EventType};
 
 /// Maps each EventType to a sorted list of node IDs.
+#[derive(Clone)]
 pub struct TypeIndex {
     index: HashMap<EventType, Vec<u64>>,
 }
@@ -17,6 +18,24 @@ impl TypeIndex {
         }
     }
 
+    /// Node IDs of one type, ascending. Type-scoped operations iterate these
+    /// instead of scanning every node.
+    pub fn ids(&self, event_type: EventType) -> &[u64] {
+        self.get(event_type)
+    }
+
+    /// Each type with at least one node, and its node IDs, in type order.
+    pub fn iter(&self) -> impl Iterator<Item = (EventType, &[u64])> + '_ {
+        let mut types: Vec<EventType> = self
+            .index
+            .iter()
+            .filter(|(_, ids)| !ids.is_empty())
+            .map(|(t, _)| *t)
+            .collect();
+        types.sort_by_key(|t| *t as u8);
+        types.into_iter().map(move |t| (t, self.get(t)))
+    }
+
     /// Get all node IDs of a given type.
     pub fn get(&self, event_type: EventType) -> &[u64] {
         self.index
@@ -71,6 +90,17 @@ impl TypeIndex {
         }
     }
 
+    /// Move a node whose event type changed from `from` to `to`.
+    pub fn retype_node(&mut self, id: u64, from: EventType, to: EventType) {
+        if from == to {
+            return;
+        }
+        self.remove_node(id, from);
+        let list = self.index.entry(to).or_default();
+        let pos = list.binary_search(&id).unwrap_or_else(|p| p);
+        list.insert(pos, id);
+    }
+
     /// Clear the index.
     pub fn clear(&mut self) {
         self.index.clear();
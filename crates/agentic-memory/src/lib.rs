@@ -18,38 +18,60 @@ pub mod index;
 pub mod metrics;
 pub mod query;
 pub mod types;
+pub(crate) mod util;
 
 // V3: Immortal Architecture — Memory That Never Dies
 #[cfg(feature = "v3")]
 pub mod v3;
 
 // Re-export commonly used types at the crate root
+pub use engine::{diff_graphs, GraphDiff, GraphPatch};
 pub use engine::{
-    CausalParams, CausalResult, DecayReport, IngestResult, MemoryQualityParams,
-    MemoryQualityReport, PatternParams, PatternSort, QueryEngine, SimilarityMatchResult,
-    SimilarityParams, SubGraph, TemporalParams, TemporalResult, TimeRange, TraversalParams,
-    TraversalResult, WriteEngine,
+    BatchIngestResult, CausalDirection, CausalParams, CausalResult, ConfidenceDecayQuadrants,
+    ContentNormalizer, CrossSessionView, DecayFn, DecayFunction, DecayReport, EdgeExpansion,
+    EdgeMergePolicy, EpisodeMember, ExpansionSource, IdAnonymizer, IngestResult,
+    MemoryQualityParams, MemoryQualityReport, MergeReport, MergeStrategy, NodeSample,
+    PatternParams, PatternSort, QueryEngine, QueryProfile, RedactionCategory, RedactionCounts,
+    Redactor, SessionActivity, SessionGroup, SimilarityMatchResult, SimilarityParams, SubGraph,
+    TemporalCluster, TemporalClusterParams, TemporalParams, TemporalResult, TimeRange,
+    TraversalParams, TraversalResult, WriteEngine, DEFAULT_ACTIVE_WINDOW_SECS,
+    DEFAULT_EPISODE_MEMBER_LIMIT,
 };
+#[cfg(feature = "parquet")]
+pub use format::write_parquet;
+#[cfg(feature = "signing")]
+pub use format::SignatureStatus;
 #[cfg(feature = "format")]
-pub use format::{AmemReader, AmemWriter, MmapReader, SimilarityMatch};
-pub use graph::{GraphBuilder, MemoryGraph, TraversalDirection};
+pub use format::{AmemReader, AmemWriter, GraphDelta, MigrationPlan, MmapReader, SimilarityMatch};
+#[cfg(feature = "sqlite-backend")]
+pub use graph::SqliteGraph;
+pub use graph::{
+    EmbeddingModelCheck, GraphBackend, GraphBuilder, GraphChanges, IndexConsistencyReport,
+    IndexDivergence, IndexKind, IntegrityViolation, MemoryGraph, TraversalDirection,
+};
 pub use index::{
-    cosine_similarity, ClusterMap, DocLengths, SessionIndex, TemporalIndex, TermIndex, TypeIndex,
+    cosine_similarity, cosine_similarity_scalar, ClusterAssignment, ClusterMap, DocLengths,
+    SessionIndex, TagIndex, TemporalIndex, TermBloom, TermIndex, TypeIndex,
 };
 pub use types::{
-    now_micros, AmemError, AmemResult, CognitiveEvent, CognitiveEventBuilder, Edge, EdgeType,
-    EventType, FileHeader, DEFAULT_DIMENSION, MAX_CONTENT_SIZE, MAX_EDGES_PER_NODE,
+    now_micros, system_clock, AccessLog, AmemError, AmemResult, Clock, CognitiveEvent,
+    CognitiveEventBuilder, Edge, EdgeType, EdgeTypeNames, EventType, FileHeader, Language,
+    MockClock, SystemClock, Tombstone, ACCESS_LOG_CAPACITY, DEFAULT_DIMENSION,
+    DEFAULT_TOMBSTONE_RETENTION_MICROS, MAX_CONTENT_SIZE, MAX_EDGES_PER_NODE,
 };
 
 // New query expansion re-exports
 pub use engine::{
     AnalogicalAnchor, AnalogicalParams, Analogy, BeliefRevisionParams, BeliefSnapshot,
-    BeliefTimeline, CascadeEffect, CascadeStep, CentralityAlgorithm, CentralityParams,
-    CentralityResult, ChangeType, ConsolidationAction, ConsolidationOp, ConsolidationParams,
-    ConsolidationReport, ContradictedNode, DriftParams, DriftReport, Gap, GapDetectionParams,
-    GapReport, GapSeverity, GapSummary, GapType, HybridMatch, HybridSearchParams, PathResult,
-    PatternMatch, RevisionReport, ShortestPathParams, TextMatch, TextSearchParams, Tokenizer,
-    WeakenedNode,
+    BeliefTimeline, CascadeEffect, CascadeStep, CentralityAlgorithm, CentralityCore,
+    CentralityCoreParams, CentralityParams, CentralityResult, ChangeType, CompactionParams,
+    CompactionReport, ConsolidationAction, ConsolidationOp, ConsolidationParams,
+    ConsolidationReport, ContradictedNode, DedupDecision, DeleteFilter, DeleteReport, DriftParams,
+    DriftReport, DuplicateCandidate, FuzzySearchParams, Gap, GapDetectionParams, GapReport,
+    GapSeverity, GapSummary, GapType, HybridMatch, HybridSearchParams, KeepTopParams,
+    KeepTopReport, PathResult, PatternMatch, RegexSearchParams, RelevanceFeedbackParams,
+    RelevanceFeedbackResult, RevisionReport, ShortestPathParams, SupportProposal, TextMatch,
+    TextSearchParams, Tokenizer, WeakenedNode, WeightedTerm,
 };
 pub use types::header::feature_flags;
 
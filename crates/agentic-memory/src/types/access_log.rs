@@ -0,0 +1,101 @@
+//! Per-node access logs.
+//!
+//! `access_count` only says how often a node was read. The access log keeps
+//! the timestamps of the most recent reads, bounded per node, so retrieval
+//! patterns and the decay trajectory can be inspected later.
+
+use std::collections::{HashMap, VecDeque};
+
+/// Maximum access timestamps kept per node; older ones are dropped first.
+pub const ACCESS_LOG_CAPACITY: usize = 32;
+
+/// Bounded access timestamps (Unix epoch microseconds) per node ID, oldest
+/// first.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccessLog {
+    entries: HashMap<u64, VecDeque<u64>>,
+}
+
+impl AccessLog {
+    /// Create an empty access log.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record an access to `node_id` at `at`, evicting the oldest timestamp
+    /// once the node holds [`ACCESS_LOG_CAPACITY`].
+    pub fn record(&mut self, node_id: u64, at: u64) {
+        let log = self.entries.entry(node_id).or_default();
+        if log.len() == ACCESS_LOG_CAPACITY {
+            log.pop_front();
+        }
+        log.push_back(at);
+    }
+
+    /// Access timestamps for `node_id`, oldest first. Empty if none recorded.
+    pub fn get(&self, node_id: u64) -> Vec<u64> {
+        self.entries
+            .get(&node_id)
+            .map(|log| log.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// Forget the log of `node_id`.
+    pub fn remove(&mut self, node_id: u64) {
+        self.entries.remove(&node_id);
+    }
+
+    /// Number of nodes with a recorded access.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether no access has been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterate `(node_id, timestamps)` pairs in ascending node ID order.
+    pub fn iter(&self) -> impl Iterator<Item = (u64, Vec<u64>)> + '_ {
+        let mut ids: Vec<u64> = self.entries.keys().copied().collect();
+        ids.sort_unstable();
+        ids.into_iter().map(move |id| (id, self.get(id)))
+    }
+
+    /// Encode as `[count: u32][(node_id: u64, n: u8, timestamps: u64 * n)] * count`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&(self.entries.len() as u32).to_le_bytes());
+        for (node_id, timestamps) in self.iter() {
+            buf.extend_from_slice(&node_id.to_le_bytes());
+            buf.push(timestamps.len() as u8);
+            for ts in timestamps {
+                buf.extend_from_slice(&ts.to_le_bytes());
+            }
+        }
+        buf
+    }
+
+    /// Decode a log written by [`to_bytes`](Self::to_bytes). Returns `None`
+    /// on malformed input. Logs longer than [`ACCESS_LOG_CAPACITY`] keep
+    /// their newest entries.
+    pub fn from_bytes(data: &[u8]) -> Option<Self> {
+        fn u64_at(data: &[u8], pos: usize) -> Option<u64> {
+            Some(u64::from_le_bytes(data.get(pos..pos + 8)?.try_into().ok()?))
+        }
+
+        let count = u32::from_le_bytes(data.get(0..4)?.try_into().ok()?) as usize;
+        let mut log = Self::new();
+        let mut pos = 4;
+        for _ in 0..count {
+            let node_id = u64_at(data, pos)?;
+            let n = *data.get(pos + 8)? as usize;
+            pos += 9;
+            for _ in 0..n {
+                log.record(node_id, u64_at(data, pos)?);
+                pos += 8;
+            }
+        }
+        Some(log)
+    }
+}
@@ -0,0 +1,70 @@
+//! Injectable time source.
+//!
+//! Engines that stamp or age nodes read the time through a [`Clock`] so tests
+//! can control it. Production code uses [`SystemClock`]; tests use
+//! [`MockClock`] and advance it explicitly instead of sleeping.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// A source of the current time in Unix epoch microseconds.
+pub trait Clock: Send + Sync {
+    /// Current time as Unix epoch microseconds.
+    fn now_micros(&self) -> u64;
+}
+
+/// Wall-clock time.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_micros(&self) -> u64 {
+        super::now_micros()
+    }
+}
+
+/// Manually controlled time for deterministic tests.
+#[derive(Debug, Default)]
+pub struct MockClock {
+    now: AtomicU64,
+}
+
+impl MockClock {
+    /// Create a clock frozen at the given time.
+    pub fn new(start_micros: u64) -> Self {
+        Self {
+            now: AtomicU64::new(start_micros),
+        }
+    }
+
+    /// Create a shared clock frozen at the given time.
+    pub fn shared(start_micros: u64) -> Arc<Self> {
+        Arc::new(Self::new(start_micros))
+    }
+
+    /// Jump to an absolute time.
+    pub fn set(&self, micros: u64) {
+        self.now.store(micros, Ordering::SeqCst);
+    }
+
+    /// Move time forward by the given number of microseconds.
+    pub fn advance(&self, micros: u64) {
+        self.now.fetch_add(micros, Ordering::SeqCst);
+    }
+
+    /// Move time forward by the given number of seconds.
+    pub fn advance_secs(&self, secs: u64) {
+        self.advance(secs.saturating_mul(1_000_000));
+    }
+}
+
+impl Clock for MockClock {
+    fn now_micros(&self) -> u64 {
+        self.now.load(Ordering::SeqCst)
+    }
+}
+
+/// The default shared clock (wall-clock time).
+pub fn system_clock() -> Arc<dyn Clock> {
+    Arc::new(SystemClock)
+}
@@ -1,9 +1,21 @@
 //! Edge types and the core edge struct.
 
+use std::collections::{BTreeMap, HashMap};
+
 use serde::Serialize;
 
+use super::error::{AmemError, AmemResult};
 use super::now_micros;
 
+/// Maximum number of distinct custom edge types in one graph.
+pub const MAX_CUSTOM_EDGE_TYPES: usize = 128;
+
+/// Maximum length of a custom edge type name, in bytes.
+pub const MAX_CUSTOM_EDGE_TYPE_LEN: usize = 64;
+
+/// Stored byte of a custom edge type is this bit plus its ID.
+const CUSTOM_EDGE_TYPE_BIT: u8 = 0x80;
+
 /// The type of relationship between two cognitive events.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
 #[repr(u8)]
@@ -22,10 +34,27 @@ pub enum EdgeType {
     PartOf = 5,
     /// Chronological ordering within a session.
     TemporalNext = 6,
+    /// A user-defined relationship such as `implements`, identified by its
+    /// index in the owning graph's [`EdgeTypeNames`].
+    Custom(u8),
 }
 
 impl EdgeType {
+    /// The built-in edge types, in stored-byte order.
+    pub const BUILTIN: [EdgeType; 7] = [
+        Self::CausedBy,
+        Self::Supports,
+        Self::Contradicts,
+        Self::Supersedes,
+        Self::RelatedTo,
+        Self::PartOf,
+        Self::TemporalNext,
+    ];
+
     /// Convert a u8 value to an EdgeType, returning None for invalid values.
+    ///
+    /// A custom byte gives the type's index in its graph's [`EdgeTypeNames`];
+    /// files map their own bytes through the name table they store.
     pub fn from_u8(value: u8) -> Option<Self> {
         match value {
             0 => Some(Self::CausedBy),
@@ -35,11 +64,29 @@ impl EdgeType {
             4 => Some(Self::RelatedTo),
             5 => Some(Self::PartOf),
             6 => Some(Self::TemporalNext),
+            v if v & CUSTOM_EDGE_TYPE_BIT != 0 => Some(Self::Custom(v & !CUSTOM_EDGE_TYPE_BIT)),
             _ => None,
         }
     }
 
+    /// The byte this edge type is stored as.
+    pub fn to_u8(&self) -> u8 {
+        match self {
+            Self::CausedBy => 0,
+            Self::Supports => 1,
+            Self::Contradicts => 2,
+            Self::Supersedes => 3,
+            Self::RelatedTo => 4,
+            Self::PartOf => 5,
+            Self::TemporalNext => 6,
+            Self::Custom(id) => CUSTOM_EDGE_TYPE_BIT | id,
+        }
+    }
+
     /// Return a human-readable name for this edge type.
+    ///
+    /// Custom types are named by their graph, so this returns `"custom"` for
+    /// them; use [`EdgeTypeNames::name`] to get the real name.
     pub fn name(&self) -> &'static str {
         match self {
             Self::CausedBy => "caused_by",
@@ -49,10 +96,27 @@ impl EdgeType {
             Self::RelatedTo => "related_to",
             Self::PartOf => "part_of",
             Self::TemporalNext => "temporal_next",
+            Self::Custom(_) => "custom",
         }
     }
 
-    /// Parse an edge type from a string name.
+    /// Whether this is a user-defined type rather than a built-in one.
+    pub fn is_custom(&self) -> bool {
+        matches!(self, Self::Custom(_))
+    }
+
+    /// Whether the relationship reads the same in both directions.
+    ///
+    /// `RelatedTo` and `Contradicts` are symmetric: an edge A -> B says as
+    /// much about B as about A, whichever way it was stored. Custom types
+    /// are directed.
+    pub fn is_symmetric(&self) -> bool {
+        matches!(self, Self::RelatedTo | Self::Contradicts)
+    }
+
+    /// Parse a built-in edge type from a string name.
+    ///
+    /// Custom types are looked up with [`EdgeTypeNames::get`].
     pub fn from_name(name: &str) -> Option<Self> {
         match name.to_lowercase().as_str() {
             "caused_by" | "causedby" | "derived_from" | "derivedfrom" => Some(Self::CausedBy),
@@ -67,6 +131,178 @@ impl EdgeType {
     }
 }
 
+/// The custom edge type names of one graph; `Custom(id)` names entry `id`.
+///
+/// Each graph keeps its own table, so the types one graph defines are
+/// neither visible to nor counted against any other graph.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EdgeTypeNames {
+    names: Vec<String>,
+}
+
+impl EdgeTypeNames {
+    /// Create an empty table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The edge type called `name`: a built-in type, or a custom type
+    /// already in this table. Never registers anything.
+    pub fn get(&self, name: &str) -> Option<EdgeType> {
+        EdgeType::from_name(name).or_else(|| {
+            let name = name.to_lowercase();
+            self.names
+                .iter()
+                .position(|n| *n == name)
+                .map(|id| EdgeType::Custom(id as u8))
+        })
+    }
+
+    /// The edge type called `name`, registering it as a custom type when it
+    /// is neither built in nor known yet.
+    ///
+    /// Custom names are lowercased and may hold ASCII letters, digits, `_`
+    /// and `-`, up to [`MAX_CUSTOM_EDGE_TYPE_LEN`] bytes.
+    pub fn register(&mut self, name: &str) -> AmemResult<EdgeType> {
+        if let Some(et) = self.get(name) {
+            return Ok(et);
+        }
+        let name = name.to_lowercase();
+        if name.is_empty()
+            || name.len() > MAX_CUSTOM_EDGE_TYPE_LEN
+            || !name
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'-')
+        {
+            return Err(AmemError::InvalidEdgeType(name));
+        }
+        if self.names.len() >= MAX_CUSTOM_EDGE_TYPES {
+            return Err(AmemError::TooManyCustomEdgeTypes(MAX_CUSTOM_EDGE_TYPES));
+        }
+        self.names.push(name);
+        Ok(EdgeType::Custom((self.names.len() - 1) as u8))
+    }
+
+    /// The name of `edge_type`. A custom type missing from this table (one
+    /// taken from another graph) is called `"custom"`.
+    pub fn name(&self, edge_type: EdgeType) -> &str {
+        match edge_type {
+            EdgeType::Custom(id) => self.names.get(id as usize).map_or("custom", String::as_str),
+            builtin => builtin.name(),
+        }
+    }
+
+    /// Whether `edge_type` is built in or registered in this table.
+    pub fn contains(&self, edge_type: EdgeType) -> bool {
+        match edge_type {
+            EdgeType::Custom(id) => (id as usize) < self.names.len(),
+            _ => true,
+        }
+    }
+
+    /// The type `edge_type` of the graph with `names` has in this table,
+    /// registering its name if needed.
+    pub fn import(&mut self, edge_type: EdgeType, names: &EdgeTypeNames) -> AmemResult<EdgeType> {
+        match edge_type {
+            EdgeType::Custom(_) => self.register(names.name(edge_type)),
+            builtin => Ok(builtin),
+        }
+    }
+
+    /// Every registered custom type, in registration order.
+    pub fn custom_types(&self) -> impl Iterator<Item = EdgeType> + '_ {
+        (0..self.names.len()).map(|id| EdgeType::Custom(id as u8))
+    }
+
+    /// Number of registered custom types.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Whether no custom type is registered.
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+/// Encode the names of the custom types among `types` for the index block.
+///
+/// Layout: `[count: u16]` then, per type, `[byte: u8][len: u8][name]`,
+/// where `byte` is the value edge records store for that type and `name`
+/// comes from `names`.
+pub fn custom_edge_types_to_bytes(
+    types: impl IntoIterator<Item = EdgeType>,
+    names: &EdgeTypeNames,
+) -> Vec<u8> {
+    let custom: BTreeMap<u8, &str> = types
+        .into_iter()
+        .filter(EdgeType::is_custom)
+        .map(|et| (et.to_u8(), names.name(et)))
+        .collect();
+
+    let mut buf = Vec::with_capacity(2 + custom.len() * 16);
+    buf.extend_from_slice(&(custom.len() as u16).to_le_bytes());
+    for (byte, name) in custom {
+        buf.push(byte);
+        buf.push(name.len() as u8);
+        buf.extend_from_slice(name.as_bytes());
+    }
+    buf
+}
+
+/// Decode a table written by [`custom_edge_types_to_bytes`], registering
+/// every name in `names`. Returns the stored byte of each custom type mapped
+/// to its type in `names`.
+pub fn custom_edge_types_from_bytes(
+    data: &[u8],
+    names: &mut EdgeTypeNames,
+) -> AmemResult<HashMap<u8, EdgeType>> {
+    let corrupt = || AmemError::Corrupt(0);
+    let count = u16::from_le_bytes(
+        data.get(0..2)
+            .ok_or_else(corrupt)?
+            .try_into()
+            .map_err(|_| corrupt())?,
+    ) as usize;
+    let mut out = HashMap::with_capacity(count);
+    let mut pos = 2;
+    for _ in 0..count {
+        let byte = *data.get(pos).ok_or_else(corrupt)?;
+        let len = *data.get(pos + 1).ok_or_else(corrupt)? as usize;
+        let name = data.get(pos + 2..pos + 2 + len).ok_or_else(corrupt)?;
+        let name = std::str::from_utf8(name).map_err(|_| corrupt())?;
+        out.insert(byte, names.register(name)?);
+        pos += 2 + len;
+    }
+    Ok(out)
+}
+
+/// Decode a stored edge type byte, looking custom types up in `custom`
+/// (as returned by [`custom_edge_types_from_bytes`]).
+pub fn decode_edge_type(byte: u8, custom: &HashMap<u8, EdgeType>) -> Option<EdgeType> {
+    if byte & CUSTOM_EDGE_TYPE_BIT != 0 {
+        custom.get(&byte).copied()
+    } else {
+        EdgeType::from_u8(byte)
+    }
+}
+
+/// Built-in types first, in stored-byte order, then custom types in the
+/// order their graph registered them.
+impl Ord for EdgeType {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.is_custom()
+            .cmp(&other.is_custom())
+            .then_with(|| self.to_u8().cmp(&other.to_u8()))
+    }
+}
+
+impl PartialOrd for EdgeType {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl std::fmt::Display for EdgeType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.name())
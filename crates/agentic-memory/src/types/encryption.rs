@@ -0,0 +1,154 @@
+//! AES-256-GCM encryption of whole .amem files (`encryption` feature).
+//!
+//! An encrypted file is a 20-byte header, `[magic "AMEC"][version: u32]
+//! [nonce: 12 bytes]`, followed by the AES-256-GCM ciphertext of the plain
+//! .amem bytes. The header is authenticated along with the ciphertext, and
+//! every write draws a fresh random nonce.
+
+use std::fmt;
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use hkdf::Hkdf;
+use sha2::Sha256;
+
+use super::error::{AmemError, AmemResult};
+use super::ENCRYPTED_MAGIC;
+
+/// Current encrypted file layout version.
+pub const ENCRYPTED_VERSION: u32 = 1;
+
+/// Length of the AES-GCM nonce.
+pub const NONCE_LEN: usize = 12;
+
+/// Size of the encrypted file header: magic, version and nonce.
+pub const ENCRYPTED_HEADER_SIZE: usize = 4 + 4 + NONCE_LEN;
+
+/// HKDF salt for passphrase-derived keys.
+const KDF_SALT: &[u8] = b"agentic-memory";
+
+/// HKDF info string, naming what the derived key is for.
+const KDF_INFO: &[u8] = b"amem file encryption v1";
+
+/// A 256-bit file encryption key.
+#[derive(Clone, PartialEq, Eq)]
+pub struct EncryptionKey([u8; 32]);
+
+impl fmt::Debug for EncryptionKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "EncryptionKey(<redacted>)")
+    }
+}
+
+impl EncryptionKey {
+    /// Use 32 raw bytes as the key.
+    pub fn from_bytes(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    /// Parse a key written as 64 hex digits.
+    pub fn from_hex(hex: &str) -> AmemResult<Self> {
+        let bytes = hex::decode(hex.trim())
+            .map_err(|e| AmemError::InvalidKey(format!("invalid hex key: {e}")))?;
+        let bytes: [u8; 32] = bytes.try_into().map_err(|b: Vec<u8>| {
+            AmemError::InvalidKey(format!("key must be 32 bytes, got {}", b.len()))
+        })?;
+        Ok(Self(bytes))
+    }
+
+    /// Derive a key from a passphrase with HKDF-SHA256.
+    ///
+    /// HKDF does no key stretching, so a short passphrase is only as strong
+    /// as its own entropy; prefer a random hex key where one can be stored.
+    pub fn from_passphrase(passphrase: &str) -> Self {
+        let mut key = [0u8; 32];
+        Hkdf::<Sha256>::new(Some(KDF_SALT), passphrase.as_bytes())
+            .expand(KDF_INFO, &mut key)
+            .expect("32 bytes is a valid HKDF-SHA256 output length");
+        Self(key)
+    }
+
+    /// Parse a key argument: 64 hex digits, or the name of an environment
+    /// variable holding either a hex key or a passphrase.
+    pub fn from_arg(arg: &str) -> AmemResult<Self> {
+        let arg = arg.trim();
+        if arg.len() == 64 && arg.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Self::from_hex(arg);
+        }
+        let value = std::env::var(arg).map_err(|_| {
+            AmemError::InvalidKey(format!(
+                "{arg:?} is neither a 64-digit hex key nor a set environment variable"
+            ))
+        })?;
+        Ok(Self::from_secret(&value))
+    }
+
+    /// A hex key if `secret` is one, otherwise a passphrase-derived key.
+    pub fn from_secret(secret: &str) -> Self {
+        Self::from_hex(secret).unwrap_or_else(|_| Self::from_passphrase(secret))
+    }
+
+    /// The raw key bytes.
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    fn cipher(&self) -> Aes256Gcm {
+        Aes256Gcm::new(&self.0.into())
+    }
+}
+
+/// Whether `data` starts with the encrypted file magic.
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(&ENCRYPTED_MAGIC)
+}
+
+/// Encrypt plain .amem bytes into an encrypted file image.
+pub fn encrypt(plaintext: &[u8], key: &EncryptionKey) -> AmemResult<Vec<u8>> {
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let mut out = Vec::with_capacity(ENCRYPTED_HEADER_SIZE + plaintext.len() + 16);
+    out.extend_from_slice(&ENCRYPTED_MAGIC);
+    out.extend_from_slice(&ENCRYPTED_VERSION.to_le_bytes());
+    out.extend_from_slice(&nonce);
+
+    let ciphertext = key
+        .cipher()
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: plaintext,
+                aad: &out,
+            },
+        )
+        .map_err(|_| AmemError::InvalidKey("encryption failed".to_string()))?;
+    out.extend_from_slice(&ciphertext);
+    Ok(out)
+}
+
+/// Decrypt an encrypted file image back to plain .amem bytes.
+///
+/// A wrong key and a tampered file look the same to AES-GCM; both fail
+/// with [`AmemError::Corrupt`] at the start of the ciphertext.
+pub fn decrypt(data: &[u8], key: &EncryptionKey) -> AmemResult<Vec<u8>> {
+    if data.len() < ENCRYPTED_HEADER_SIZE {
+        return Err(AmemError::Truncated);
+    }
+    if !is_encrypted(data) {
+        return Err(AmemError::InvalidMagic);
+    }
+    let version = u32::from_le_bytes(data[4..8].try_into().unwrap());
+    if version != ENCRYPTED_VERSION {
+        return Err(AmemError::UnsupportedVersion(version));
+    }
+
+    let (header, ciphertext) = data.split_at(ENCRYPTED_HEADER_SIZE);
+    key.cipher()
+        .decrypt(
+            Nonce::from_slice(&header[8..]),
+            Payload {
+                msg: ciphertext,
+                aad: header,
+            },
+        )
+        .map_err(|_| AmemError::Corrupt(ENCRYPTED_HEADER_SIZE as u64))
+}
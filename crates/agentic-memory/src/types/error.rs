@@ -1,5 +1,8 @@
 //! Error types for the AgenticMemory library.
 
+use std::path::PathBuf;
+use std::time::Duration;
+
 use thiserror::Error;
 
 /// All errors that can occur in the AgenticMemory library.
@@ -25,6 +28,14 @@ pub enum AmemError {
     #[error("Self-edge not allowed on node {0}")]
     SelfEdge(u64),
 
+    /// An edge with the same source, target and type already exists.
+    #[error("Duplicate {edge_type} edge {source_id} -> {target_id}")]
+    DuplicateEdge {
+        source_id: u64,
+        target_id: u64,
+        edge_type: String,
+    },
+
     /// Content exceeds maximum size.
     #[error("Content exceeds maximum size: {size} > {max}")]
     ContentTooLarge { size: usize, max: usize },
@@ -49,6 +60,11 @@ pub enum AmemError {
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
+    /// Another process held the file lock for longer than we were willing to
+    /// wait. Unlike [`AmemError::Io`] this is transient; retrying may succeed.
+    #[error("Timed out after {} ms waiting for file lock: {}", waited.as_millis(), path.display())]
+    LockTimeout { path: PathBuf, waited: Duration },
+
     /// Compression error.
     #[error("Compression error: {0}")]
     Compression(String),
@@ -60,6 +76,66 @@ pub enum AmemError {
     /// Corrupt data at a given offset.
     #[error("Corrupt data at offset {0}")]
     Corrupt(u64),
+
+    /// Session alias is empty, too long, or purely numeric.
+    #[error("Invalid session alias: {0:?}")]
+    InvalidSessionAlias(String),
+
+    /// Session alias is already assigned to another session.
+    #[error("Session alias {alias:?} already belongs to session {session_id}")]
+    SessionAliasTaken { alias: String, session_id: u32 },
+
+    /// No session carries the given alias.
+    #[error("Unknown session alias: {0:?}")]
+    SessionAliasNotFound(String),
+
+    /// Custom edge type name is empty, too long, or has disallowed characters,
+    /// or an edge uses a custom type its graph has not registered.
+    #[error("Invalid edge type name: {0:?}")]
+    InvalidEdgeType(String),
+
+    /// No room to register another custom edge type.
+    #[error("Too many custom edge types (max {0})")]
+    TooManyCustomEdgeTypes(usize),
+
+    /// Following SUPERSEDES edges led back to a node already on the chain.
+    #[error("Supersedes cycle through nodes {nodes:?}")]
+    SupersedesCycle { nodes: Vec<u64> },
+
+    /// A consistency check found problems it could not repair.
+    #[error("Integrity check failed: {0} violation(s)")]
+    IntegrityCheckFailed(usize),
+
+    /// A signing or verifying key could not be loaded.
+    #[error("Invalid key: {0}")]
+    InvalidKey(String),
+
+    /// A file's signature is missing or does not match its contents.
+    #[error("Signature check failed: {0}")]
+    SignatureMismatch(String),
+
+    /// Vectors declared as coming from a different embedding model than the file's.
+    #[error("Embedding model mismatch: file uses {file_model:?}, vectors are from {declared:?}")]
+    EmbeddingModelMismatch {
+        file_model: String,
+        declared: String,
+    },
+
+    /// A storage backend failed to read or write.
+    #[error("Storage backend error: {0}")]
+    Storage(String),
+
+    /// A graph patch does not fit the graph it is applied to.
+    #[error("Patch does not apply: {0}")]
+    PatchConflict(String),
+
+    /// A regular expression failed to compile.
+    #[error("Invalid pattern: {0}")]
+    InvalidPattern(String),
+
+    /// Writing an export file failed.
+    #[error("Export failed: {0}")]
+    Export(String),
 }
 
 /// Convenience result type for AgenticMemory operations.
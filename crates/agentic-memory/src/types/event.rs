@@ -1,5 +1,7 @@
 //! Cognitive event types and the core event struct.
 
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
 use super::{now_micros, DEFAULT_DIMENSION, MAX_CONTENT_SIZE};
@@ -93,6 +95,12 @@ pub struct CognitiveEvent {
     /// Feature vector for similarity operations (dimension = DEFAULT_DIMENSION).
     #[serde(skip_serializing, default)]
     pub feature_vec: Vec<f32>,
+    /// Key-value metadata such as `project` or `source` (empty = untagged).
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub tags: HashMap<String, String>,
+    /// Pinned nodes are never decayed.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
 }
 
 impl CognitiveEvent {
@@ -122,6 +130,8 @@ pub struct CognitiveEventBuilder {
     confidence: f32,
     feature_vec: Vec<f32>,
     created_at: Option<u64>,
+    tags: HashMap<String, String>,
+    pinned: bool,
 }
 
 impl CognitiveEventBuilder {
@@ -134,6 +144,8 @@ impl CognitiveEventBuilder {
             confidence: 1.0,
             feature_vec: Vec::new(),
             created_at: None,
+            tags: HashMap::new(),
+            pinned: false,
         }
     }
 
@@ -161,6 +173,18 @@ impl CognitiveEventBuilder {
         self
     }
 
+    /// Add a tag, replacing any earlier value for the same key.
+    pub fn tag(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.tags.insert(key.into(), value.into());
+        self
+    }
+
+    /// Pin the node so decay never lowers its score.
+    pub fn pinned(mut self, pinned: bool) -> Self {
+        self.pinned = pinned;
+        self
+    }
+
     /// Build the CognitiveEvent. The id will be 0 (assigned by graph on insertion).
     pub fn build(self) -> CognitiveEvent {
         let now = self.created_at.unwrap_or_else(now_micros);
@@ -179,6 +203,8 @@ impl CognitiveEventBuilder {
             decay_score: 1.0,
             content: self.content,
             feature_vec,
+            tags: self.tags,
+            pinned: self.pinned,
         }
     }
 }
@@ -11,6 +11,24 @@ pub mod feature_flags {
     pub const HAS_TERM_INDEX: u32 = 1 << 0;
     /// Document lengths table is present in the index block (tag 0x06).
     pub const HAS_DOC_LENGTHS: u32 = 1 << 1;
+    /// Per-document term bloom filters are present in the index block (tag 0x07).
+    pub const HAS_TERM_BLOOM: u32 = 1 << 2;
+    /// Session alias map is present in the index block (tag 0x08).
+    pub const HAS_SESSION_ALIASES: u32 = 1 << 3;
+    /// Embedding model identifier is present in the index block (tag 0x09).
+    /// The 64-byte header has no room for a string; the vector size is the
+    /// header's `dimension`.
+    pub const HAS_EMBEDDING_MODEL: u32 = 1 << 4;
+    /// Deletion tombstones are present in the index block (tag 0x0A).
+    pub const HAS_TOMBSTONES: u32 = 1 << 5;
+    /// Per-node access logs are present in the index block (tag 0x0B).
+    pub const HAS_ACCESS_LOG: u32 = 1 << 6;
+    /// Detected node languages are present in the index block (tag 0x0C).
+    pub const HAS_NODE_LANGUAGES: u32 = 1 << 7;
+    /// Custom edge type names are present in the index block (tag 0x0D).
+    pub const HAS_CUSTOM_EDGE_TYPES: u32 = 1 << 8;
+    /// Key-value node tags are present in the index block (tag 0x0F).
+    pub const HAS_NODE_TAGS: u32 = 1 << 9;
 }
 
 /// Header of an .amem file. Fixed size: 64 bytes.
@@ -18,7 +36,7 @@ pub mod feature_flags {
 pub struct FileHeader {
     /// Magic bytes: [0x41, 0x4D, 0x45, 0x4D] ("AMEM").
     pub magic: [u8; 4],
-    /// Format version (currently 1).
+    /// Format version (currently 2).
     pub version: u32,
     /// Feature vector dimensionality.
     pub dimension: u32,
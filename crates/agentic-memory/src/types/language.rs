@@ -0,0 +1,138 @@
+//! Content language tags.
+//!
+//! A node's language is detected once at ingest (when enabled) and stored
+//! beside the graph, so the tokenizer can pick per-language rules without
+//! re-detecting on every query. Nodes without a tag use the default
+//! (English) rules.
+
+use std::collections::HashMap;
+
+/// Languages the tokenizer has rules for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Language {
+    /// English.
+    English,
+    /// German.
+    German,
+    /// French.
+    French,
+    /// Spanish.
+    Spanish,
+    /// Portuguese.
+    Portuguese,
+    /// Italian.
+    Italian,
+    /// Dutch.
+    Dutch,
+    /// Russian (any Cyrillic-script text).
+    Russian,
+    /// Greek.
+    Greek,
+    /// Arabic.
+    Arabic,
+    /// Hebrew.
+    Hebrew,
+    /// Hindi (any Devanagari-script text).
+    Hindi,
+    /// Thai.
+    Thai,
+    /// Chinese (Han text without kana).
+    Chinese,
+    /// Japanese.
+    Japanese,
+    /// Korean.
+    Korean,
+}
+
+impl Language {
+    /// All supported languages.
+    pub const ALL: [Language; 16] = [
+        Language::English,
+        Language::German,
+        Language::French,
+        Language::Spanish,
+        Language::Portuguese,
+        Language::Italian,
+        Language::Dutch,
+        Language::Russian,
+        Language::Greek,
+        Language::Arabic,
+        Language::Hebrew,
+        Language::Hindi,
+        Language::Thai,
+        Language::Chinese,
+        Language::Japanese,
+        Language::Korean,
+    ];
+
+    /// ISO 639-1 tag, e.g. `"en"`.
+    pub fn tag(&self) -> &'static str {
+        match self {
+            Self::English => "en",
+            Self::German => "de",
+            Self::French => "fr",
+            Self::Spanish => "es",
+            Self::Portuguese => "pt",
+            Self::Italian => "it",
+            Self::Dutch => "nl",
+            Self::Russian => "ru",
+            Self::Greek => "el",
+            Self::Arabic => "ar",
+            Self::Hebrew => "he",
+            Self::Hindi => "hi",
+            Self::Thai => "th",
+            Self::Chinese => "zh",
+            Self::Japanese => "ja",
+            Self::Korean => "ko",
+        }
+    }
+
+    /// Parse an ISO 639-1 tag (case-insensitive).
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let tag = tag.trim();
+        Self::ALL
+            .into_iter()
+            .find(|lang| lang.tag().eq_ignore_ascii_case(tag))
+    }
+
+    /// Whether words are written without spaces between them, so the
+    /// tokenizer splits runs into character bigrams.
+    pub fn is_unsegmented(&self) -> bool {
+        matches!(self, Self::Chinese | Self::Japanese)
+    }
+}
+
+/// Encode node languages as `[count: u32][(node_id: u64, len: u8,
+/// tag: [u8; len])] * count`, sorted by node ID.
+pub fn languages_to_bytes(languages: &HashMap<u64, Language>) -> Vec<u8> {
+    let mut ids: Vec<u64> = languages.keys().copied().collect();
+    ids.sort_unstable();
+
+    let mut buf = Vec::with_capacity(4 + ids.len() * 11);
+    buf.extend_from_slice(&(ids.len() as u32).to_le_bytes());
+    for id in ids {
+        let tag = languages[&id].tag();
+        buf.extend_from_slice(&id.to_le_bytes());
+        buf.push(tag.len() as u8);
+        buf.extend_from_slice(tag.as_bytes());
+    }
+    buf
+}
+
+/// Decode node languages written by [`languages_to_bytes`]. Returns `None`
+/// on malformed input; entries with unknown tags are dropped.
+pub fn languages_from_bytes(data: &[u8]) -> Option<HashMap<u64, Language>> {
+    let count = u32::from_le_bytes(data.get(0..4)?.try_into().ok()?) as usize;
+    let mut out = HashMap::with_capacity(count.min(4096));
+    let mut pos = 4;
+    for _ in 0..count {
+        let id = u64::from_le_bytes(data.get(pos..pos + 8)?.try_into().ok()?);
+        let len = *data.get(pos + 8)? as usize;
+        let tag = std::str::from_utf8(data.get(pos + 9..pos + 9 + len)?).ok()?;
+        if let Some(lang) = Language::from_tag(tag) {
+            out.insert(id, lang);
+        }
+        pos += 9 + len;
+    }
+    Some(out)
+}
@@ -1,20 +1,35 @@
 //! All data types for the AgenticMemory library.
 
+pub mod access_log;
+pub mod clock;
 pub mod edge;
+#[cfg(feature = "encryption")]
+pub mod encryption;
 pub mod error;
 pub mod event;
 pub mod header;
+pub mod language;
+pub mod tags;
+pub mod tombstone;
 
-pub use edge::{Edge, EdgeType};
+pub use access_log::{AccessLog, ACCESS_LOG_CAPACITY};
+pub use clock::{system_clock, Clock, MockClock, SystemClock};
+pub use edge::{Edge, EdgeType, EdgeTypeNames, MAX_CUSTOM_EDGE_TYPES};
 pub use error::{AmemError, AmemResult};
 pub use event::{CognitiveEvent, CognitiveEventBuilder, EventType};
 pub use header::{FileHeader, HEADER_SIZE};
+pub use language::Language;
+pub use tombstone::{content_hash, Tombstone, DEFAULT_TOMBSTONE_RETENTION_MICROS};
 
 /// Magic bytes at the start of every .amem file.
 pub const AMEM_MAGIC: [u8; 4] = [0x41, 0x4D, 0x45, 0x4D]; // "AMEM"
 
-/// Current format version.
-pub const FORMAT_VERSION: u32 = 1;
+/// Magic bytes at the start of an encrypted .amem file.
+pub const ENCRYPTED_MAGIC: [u8; 4] = [0x41, 0x4D, 0x45, 0x43]; // "AMEC"
+
+/// Current format version. Version 2 added node tags; version 1 files read
+/// as untagged.
+pub const FORMAT_VERSION: u32 = 2;
 
 /// Default feature vector dimensionality.
 pub const DEFAULT_DIMENSION: usize = 128;
@@ -26,6 +41,8 @@ pub const MAX_CONTENT_SIZE: usize = 65_536;
 pub const MAX_EDGES_PER_NODE: u16 = 4096;
 
 /// Returns the current time as Unix epoch microseconds.
+///
+/// Engines that need controllable time read it through a [`Clock`] instead.
 pub fn now_micros() -> u64 {
     chrono::Utc::now().timestamp_micros() as u64
 }
@@ -0,0 +1,76 @@
+//! Key-value node tags.
+//!
+//! Tags are lightweight metadata (project, source URL, agent role) kept out
+//! of a node's content. They live on [`CognitiveEvent::tags`] in memory and
+//! are stored beside the graph in the index block, so files without them
+//! read as untagged.
+
+use std::collections::HashMap;
+
+use super::CognitiveEvent;
+
+/// Parse a `key=value` tag argument. The key must be non-empty; the value
+/// may be empty and may itself contain `=`.
+pub fn parse_tag(arg: &str) -> Option<(String, String)> {
+    let (key, value) = arg.split_once('=')?;
+    let key = key.trim();
+    if key.is_empty() {
+        return None;
+    }
+    Some((key.to_string(), value.trim().to_string()))
+}
+
+fn push_str(buf: &mut Vec<u8>, s: &str) {
+    buf.extend_from_slice(&(s.len() as u32).to_le_bytes());
+    buf.extend_from_slice(s.as_bytes());
+}
+
+/// Encode the tags of every tagged node as `[count: u32][(node_id: u64,
+/// pairs: u32, [(key_len: u32, key, value_len: u32, value)] * pairs)] *
+/// count`, sorted by node ID and then by key.
+pub fn node_tags_to_bytes(nodes: &[CognitiveEvent]) -> Vec<u8> {
+    let mut tagged: Vec<&CognitiveEvent> = nodes.iter().filter(|n| !n.tags.is_empty()).collect();
+    tagged.sort_unstable_by_key(|n| n.id);
+
+    let mut buf = Vec::new();
+    buf.extend_from_slice(&(tagged.len() as u32).to_le_bytes());
+    for node in tagged {
+        let mut pairs: Vec<(&String, &String)> = node.tags.iter().collect();
+        pairs.sort_unstable();
+        buf.extend_from_slice(&node.id.to_le_bytes());
+        buf.extend_from_slice(&(pairs.len() as u32).to_le_bytes());
+        for (key, value) in pairs {
+            push_str(&mut buf, key);
+            push_str(&mut buf, value);
+        }
+    }
+    buf
+}
+
+fn read_str<'a>(data: &'a [u8], pos: &mut usize) -> Option<&'a str> {
+    let len = u32::from_le_bytes(data.get(*pos..*pos + 4)?.try_into().ok()?) as usize;
+    let s = std::str::from_utf8(data.get(*pos + 4..(*pos + 4).checked_add(len)?)?).ok()?;
+    *pos += 4 + len;
+    Some(s)
+}
+
+/// Decode node tags written by [`node_tags_to_bytes`]. Returns `None` on
+/// malformed input.
+pub fn node_tags_from_bytes(data: &[u8]) -> Option<HashMap<u64, HashMap<String, String>>> {
+    let count = u32::from_le_bytes(data.get(0..4)?.try_into().ok()?) as usize;
+    let mut out = HashMap::with_capacity(count.min(4096));
+    let mut pos = 4;
+    for _ in 0..count {
+        let id = u64::from_le_bytes(data.get(pos..pos + 8)?.try_into().ok()?);
+        let pairs = u32::from_le_bytes(data.get(pos + 8..pos + 12)?.try_into().ok()?) as usize;
+        pos += 12;
+        let mut tags = HashMap::with_capacity(pairs.min(256));
+        for _ in 0..pairs {
+            let key = read_str(data, &mut pos)?;
+            let value = read_str(data, &mut pos)?;
+            tags.insert(key.to_string(), value.to_string());
+        }
+        out.insert(id, tags);
+    }
+    Some(out)
+}
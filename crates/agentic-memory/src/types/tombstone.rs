@@ -0,0 +1,103 @@
+//! Deletion tombstones.
+//!
+//! Node IDs are reassigned when one instance merges its session into the
+//! disk state written by another, so a tombstone identifies the deleted node
+//! by session, creation time, and a hash of its content instead.
+
+use super::event::CognitiveEvent;
+
+/// Bytes per encoded tombstone.
+const TOMBSTONE_BYTES: usize = 8 + 4 + 8 + 8 + 8;
+
+/// Default time a tombstone is kept before pruning: 30 days.
+pub const DEFAULT_TOMBSTONE_RETENTION_MICROS: u64 = 30 * 24 * 60 * 60 * 1_000_000;
+
+/// Record of a deleted node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tombstone {
+    /// ID the node had when it was deleted.
+    pub node_id: u64,
+    /// Session that created the node.
+    pub session_id: u32,
+    /// Creation timestamp of the node (Unix epoch microseconds).
+    pub created_at: u64,
+    /// Hash of the node's content, see [`content_hash`].
+    pub content_hash: u64,
+    /// When the node was deleted (Unix epoch microseconds).
+    pub deleted_at: u64,
+}
+
+impl Tombstone {
+    /// Tombstone for `node`, deleted at `deleted_at`.
+    pub fn for_node(node: &CognitiveEvent, deleted_at: u64) -> Self {
+        Self {
+            node_id: node.id,
+            session_id: node.session_id,
+            created_at: node.created_at,
+            content_hash: content_hash(&node.content),
+            deleted_at,
+        }
+    }
+
+    /// Whether `node` is the node this tombstone records, regardless of the
+    /// ID it currently has.
+    pub fn matches(&self, node: &CognitiveEvent) -> bool {
+        self.session_id == node.session_id
+            && self.created_at == node.created_at
+            && self.content_hash == content_hash(&node.content)
+    }
+
+    /// Whether two tombstones record the same node.
+    pub fn same_node(&self, other: &Tombstone) -> bool {
+        self.session_id == other.session_id
+            && self.created_at == other.created_at
+            && self.content_hash == other.content_hash
+    }
+}
+
+/// Stable 64-bit hash of node content (first 8 bytes of its BLAKE3 hash).
+pub fn content_hash(content: &str) -> u64 {
+    let hash = blake3::hash(content.as_bytes());
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&hash.as_bytes()[..8]);
+    u64::from_le_bytes(bytes)
+}
+
+/// Encode tombstones as `[count: u32][(node_id: u64, session_id: u32,
+/// created_at: u64, content_hash: u64, deleted_at: u64)] * count`.
+pub fn tombstones_to_bytes(tombstones: &[Tombstone]) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(4 + tombstones.len() * TOMBSTONE_BYTES);
+    buf.extend_from_slice(&(tombstones.len() as u32).to_le_bytes());
+    for t in tombstones {
+        buf.extend_from_slice(&t.node_id.to_le_bytes());
+        buf.extend_from_slice(&t.session_id.to_le_bytes());
+        buf.extend_from_slice(&t.created_at.to_le_bytes());
+        buf.extend_from_slice(&t.content_hash.to_le_bytes());
+        buf.extend_from_slice(&t.deleted_at.to_le_bytes());
+    }
+    buf
+}
+
+/// Decode tombstones written by [`tombstones_to_bytes`]. Returns `None` on
+/// malformed input.
+pub fn tombstones_from_bytes(data: &[u8]) -> Option<Vec<Tombstone>> {
+    fn u64_at(data: &[u8], pos: usize) -> Option<u64> {
+        Some(u64::from_le_bytes(data.get(pos..pos + 8)?.try_into().ok()?))
+    }
+
+    let count = u32::from_le_bytes(data.get(0..4)?.try_into().ok()?) as usize;
+    let mut out = Vec::with_capacity(count.min(4096));
+    let mut pos = 4;
+    for _ in 0..count {
+        let session_id = u32::from_le_bytes(data.get(pos + 8..pos + 12)?.try_into().ok()?);
+        out.push(Tombstone {
+            node_id: u64_at(data, pos)?,
+            session_id,
+            created_at: u64_at(data, pos + 12)?,
+            content_hash: u64_at(data, pos + 20)?,
+            deleted_at: u64_at(data, pos + 28)?,
+        });
+        pos += TOMBSTONE_BYTES;
+    }
+    Some(out)
+}
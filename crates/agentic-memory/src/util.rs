@@ -0,0 +1,13 @@
+//! Small helpers shared across modules.
+
+/// Next value of a splitmix64 sequence, advancing `state`.
+///
+/// A fast, seedable generator for reproducible shuffles and sampling; not
+/// for anything that needs unpredictability.
+pub(crate) fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
@@ -76,6 +76,33 @@ pub struct DetectedClient {
     pub memory_dir: PathBuf,
 }
 
+/// What a read-only detection pass found for one client.
+#[derive(Debug, Clone)]
+pub struct ClientProbe {
+    pub client_type: ClientType,
+    pub memory_dir: PathBuf,
+    /// File the ghost writer would write for this client.
+    pub target_file: PathBuf,
+    /// Whether the memory directory exists yet. An installed client without
+    /// one is "detected, not initialized"; sync would create it.
+    pub initialized: bool,
+    /// Whether the target file (or the directory that would hold it) is writable.
+    pub writable: bool,
+    /// Whether an earlier ghost-write is present.
+    pub prior_write: bool,
+}
+
+impl ClientProbe {
+    /// Short status label for display.
+    pub fn status(&self) -> &'static str {
+        if self.initialized {
+            "detected"
+        } else {
+            "detected, not initialized"
+        }
+    }
+}
+
 /// The Ghost Writer daemon.
 /// Runs in background, syncs context to ALL detected AI coding assistants.
 pub struct GhostWriter {
@@ -190,66 +217,120 @@ impl GhostWriter {
     // Multi-client detection
     // ═══════════════════════════════════════════════════════════════
 
-    /// Detect ALL AI coding assistant memory directories.
-    /// Returns every client whose config directory exists or can be created.
-    pub fn detect_all_memory_dirs() -> Vec<DetectedClient> {
+    /// Memory directories of every known client, with whether each one is an
+    /// explicit override (created unconditionally) rather than a home-dir default.
+    fn candidate_memory_dirs() -> Vec<(ClientType, PathBuf, bool)> {
         let mut dirs = Vec::new();
 
         if let Some(home) = dirs::home_dir() {
             // Claude Code: ~/.claude/memory
-            let claude = home.join(".claude").join("memory");
-            if Self::create_if_parent_exists(&claude) {
-                dirs.push(DetectedClient {
-                    client_type: ClientType::Claude,
-                    memory_dir: claude,
-                });
-            }
-
+            dirs.push((
+                ClientType::Claude,
+                home.join(".claude").join("memory"),
+                false,
+            ));
             // Cursor: ~/.cursor/memory
-            let cursor = home.join(".cursor").join("memory");
-            if Self::create_if_parent_exists(&cursor) {
-                dirs.push(DetectedClient {
-                    client_type: ClientType::Cursor,
-                    memory_dir: cursor,
-                });
-            }
-
+            dirs.push((
+                ClientType::Cursor,
+                home.join(".cursor").join("memory"),
+                false,
+            ));
             // Windsurf: ~/.windsurf/memory
-            let windsurf = home.join(".windsurf").join("memory");
-            if Self::create_if_parent_exists(&windsurf) {
-                dirs.push(DetectedClient {
-                    client_type: ClientType::Windsurf,
-                    memory_dir: windsurf,
-                });
-            }
-
+            dirs.push((
+                ClientType::Windsurf,
+                home.join(".windsurf").join("memory"),
+                false,
+            ));
             // Cody: ~/.sourcegraph/cody/memory
-            let cody = home.join(".sourcegraph").join("cody").join("memory");
-            if Self::create_if_parent_exists(&cody) {
-                dirs.push(DetectedClient {
-                    client_type: ClientType::Cody,
-                    memory_dir: cody,
-                });
-            }
+            dirs.push((
+                ClientType::Cody,
+                home.join(".sourcegraph").join("cody").join("memory"),
+                false,
+            ));
         }
 
         // Also check env overrides
         if let Ok(dir) = std::env::var("CLAUDE_MEMORY_DIR") {
             let path = PathBuf::from(dir);
-            if std::fs::create_dir_all(&path).is_ok() {
-                // Avoid duplicate if already detected
-                if !dirs.iter().any(|d| d.memory_dir == path) {
-                    dirs.push(DetectedClient {
-                        client_type: ClientType::Claude,
-                        memory_dir: path,
-                    });
-                }
+            // Avoid duplicate if already listed
+            match dirs.iter_mut().find(|(_, d, _)| *d == path) {
+                Some(existing) => existing.2 = true,
+                None => dirs.push((ClientType::Claude, path, true)),
             }
         }
 
         dirs
     }
 
+    /// Detect ALL AI coding assistant memory directories.
+    /// Returns every client whose config directory exists or can be created.
+    pub fn detect_all_memory_dirs() -> Vec<DetectedClient> {
+        Self::candidate_memory_dirs()
+            .into_iter()
+            .filter(|(_, dir, explicit)| {
+                if *explicit {
+                    std::fs::create_dir_all(dir).is_ok()
+                } else {
+                    Self::create_if_parent_exists(dir)
+                }
+            })
+            .map(|(client_type, memory_dir, _)| DetectedClient {
+                client_type,
+                memory_dir,
+            })
+            .collect()
+    }
+
+    /// Run client detection without touching the filesystem.
+    ///
+    /// Reports the same clients as [`detect_all_memory_dirs`](Self::detect_all_memory_dirs)
+    /// but never creates directories, so clients whose memory directory does
+    /// not exist yet come back with `initialized == false`.
+    pub fn probe_clients() -> Vec<ClientProbe> {
+        Self::candidate_memory_dirs()
+            .into_iter()
+            .filter_map(|(client_type, dir, explicit)| Self::probe(client_type, dir, explicit))
+            .collect()
+    }
+
+    /// Probe one client's memory directory. Returns `None` when the client is
+    /// not installed (neither the directory nor its parent exists).
+    fn probe(client_type: ClientType, memory_dir: PathBuf, explicit: bool) -> Option<ClientProbe> {
+        let initialized = memory_dir.is_dir();
+        let parent_exists = memory_dir.parent().is_some_and(Path::is_dir);
+        if !initialized && !parent_exists && !explicit {
+            return None;
+        }
+
+        let target_file = memory_dir.join(client_type.memory_filename());
+        let writable = if target_file.exists() {
+            is_writable(&target_file)
+        } else if initialized {
+            is_writable(&memory_dir)
+        } else {
+            memory_dir
+                .ancestors()
+                .skip(1)
+                .find(|p| p.exists())
+                .is_some_and(is_writable)
+        };
+
+        let mut prior_write = target_file.is_file();
+        if client_type == ClientType::Claude {
+            prior_write |= std::fs::read_to_string(memory_dir.join("MEMORY.md"))
+                .is_ok_and(|content| content.contains(START_MARKER));
+        }
+
+        Some(ClientProbe {
+            client_type,
+            memory_dir,
+            target_file,
+            initialized,
+            writable,
+            prior_write,
+        })
+    }
+
     /// Create the memory directory if its parent directory already exists
     /// (i.e., the client is installed). Returns true if the dir now exists.
     fn create_if_parent_exists(memory_dir: &Path) -> bool {
@@ -639,6 +720,10 @@ impl GhostWriter {
     }
 }
 
+fn is_writable(path: &Path) -> bool {
+    std::fs::metadata(path).is_ok_and(|m| !m.permissions().readonly())
+}
+
 impl Drop for GhostWriter {
     fn drop(&mut self) {
         self.running.store(false, Ordering::SeqCst);
@@ -748,6 +833,46 @@ mod tests {
         assert!(memory_dir.exists());
     }
 
+    #[test]
+    fn test_probe_reports_uninitialized_client() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let memory_dir = dir.path().join("memory");
+
+        let probe = GhostWriter::probe(ClientType::Cursor, memory_dir.clone(), false).unwrap();
+        assert!(!probe.initialized);
+        assert!(!probe.prior_write);
+        assert!(probe.writable);
+        assert_eq!(probe.status(), "detected, not initialized");
+        // Probing is read-only.
+        assert!(!memory_dir.exists());
+    }
+
+    #[test]
+    fn test_probe_detects_prior_write() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let memory_dir = dir.path().join("memory");
+        std::fs::create_dir_all(&memory_dir).unwrap();
+
+        let probe = GhostWriter::probe(ClientType::Claude, memory_dir.clone(), false).unwrap();
+        assert!(probe.initialized);
+        assert!(!probe.prior_write);
+
+        std::fs::write(
+            memory_dir.join("MEMORY.md"),
+            format!("# Notes\n\n{}\n{}\n", START_MARKER, END_MARKER),
+        )
+        .unwrap();
+        let probe = GhostWriter::probe(ClientType::Claude, memory_dir, false).unwrap();
+        assert!(probe.prior_write);
+        assert_eq!(probe.status(), "detected");
+    }
+
+    #[test]
+    fn test_probe_skips_missing_client() {
+        let memory_dir = PathBuf::from("/tmp/nonexistent_ghost_probe_dir/also_missing/memory");
+        assert!(GhostWriter::probe(ClientType::Windsurf, memory_dir, false).is_none());
+    }
+
     #[test]
     fn test_create_if_parent_missing() {
         let memory_dir = PathBuf::from("/tmp/nonexistent_ghost_test_dir/also_missing/memory");
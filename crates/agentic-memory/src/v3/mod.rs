@@ -54,13 +54,14 @@ pub use embeddings::EmbeddingManager;
 pub use engine::{
     EngineConfig, EngineStats, MemoryEngineV3, ResurrectionResult, SessionResumeResult,
 };
-pub use ghost_writer::{ClientType, DetectedClient, GhostWriter};
+pub use ghost_writer::{ClientProbe, ClientType, DetectedClient, GhostWriter};
 pub use immortal_log::{ImmortalLog, IntegrityReport};
 pub use indexes::{Index, IndexResult};
 pub use migration::V2ToV3Migration;
 pub use recovery::{RecoveryManager, WriteAheadLog};
 pub use retrieval::{
-    RetrievalCoverage, RetrievalRequest, RetrievalResult, RetrievalStrategy, SmartRetrievalEngine,
+    IndexContribution, RetrievalCoverage, RetrievalRequest, RetrievalResult, RetrievalStrategy,
+    SmartRetrievalEngine,
 };
 pub use tiered::{TierConfig, TierStats, TieredStorage};
 
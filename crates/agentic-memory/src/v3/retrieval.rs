@@ -5,7 +5,7 @@ use super::immortal_log::*;
 use super::indexes::*;
 use super::tiered::*;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 /// Request for smart context retrieval
 #[derive(Debug, Clone)]
@@ -21,7 +21,7 @@ pub struct RetrievalRequest {
 }
 
 /// Retrieval strategy
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Default)]
 pub enum RetrievalStrategy {
     /// Prioritize recent blocks
     Recency,
@@ -30,6 +30,7 @@ pub enum RetrievalStrategy {
     /// Prioritize causal chains
     Causal,
     /// Balanced mix
+    #[default]
     Balanced,
     /// Custom weights
     Custom {
@@ -39,6 +40,31 @@ pub enum RetrievalStrategy {
     },
 }
 
+impl RetrievalStrategy {
+    /// Lowercase name, e.g. `"recency"`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Recency => "recency",
+            Self::Relevance => "relevance",
+            Self::Causal => "causal",
+            Self::Balanced => "balanced",
+            Self::Custom { .. } => "custom",
+        }
+    }
+
+    /// Parse a named strategy (case-insensitive). `Custom` has no name
+    /// form since it needs explicit weights.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "recency" => Some(Self::Recency),
+            "relevance" => Some(Self::Relevance),
+            "causal" => Some(Self::Causal),
+            "balanced" => Some(Self::Balanced),
+            _ => None,
+        }
+    }
+}
+
 /// Result of smart retrieval
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RetrievalResult {
@@ -48,6 +74,11 @@ pub struct RetrievalResult {
     pub tokens_used: u32,
     /// Coverage metrics
     pub coverage: RetrievalCoverage,
+    /// Selected block sequences in the order the strategy ranked them
+    /// (causal expansions last)
+    pub ranking: Vec<u64>,
+    /// Per-index contribution, keyed by index name
+    pub contributions: BTreeMap<String, IndexContribution>,
     /// Blocks that didn't fit
     pub omitted: Vec<BlockHash>,
     /// Retrieval duration in ms
@@ -62,6 +93,17 @@ pub struct RetrievalCoverage {
     pub causal: f32,
 }
 
+/// What one index contributed to a retrieval
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IndexContribution {
+    /// Blocks this index proposed
+    pub candidates: usize,
+    /// Proposed blocks that made it into the context
+    pub selected: usize,
+    /// Weighted score this index added to the selected blocks
+    pub score: f32,
+}
+
 /// Smart retrieval engine
 pub struct SmartRetrievalEngine {}
 
@@ -87,20 +129,25 @@ impl SmartRetrievalEngine {
 
         // Step 1: Gather candidates from all indexes
         let mut candidates: HashMap<u64, f32> = HashMap::new();
+        let mut by_index: BTreeMap<&'static str, HashMap<u64, f32>> = BTreeMap::new();
+        let mut propose = |index: &'static str, seq: u64, score: f32| {
+            *candidates.entry(seq).or_insert(0.0) += score;
+            *by_index.entry(index).or_default().entry(seq).or_insert(0.0) += score;
+        };
 
         // Semantic search
         let semantic_results = semantic.search_by_text(&request.query, 100);
         for result in &semantic_results {
             let score = result.score * self.get_weight(&request.strategy, "semantic");
-            *candidates.entry(result.block_sequence).or_insert(0.0) += score;
+            propose("semantic", result.block_sequence, score);
         }
 
-        // Temporal search (recent)
+        // Temporal search (recent, newest first)
         let recent_results = temporal.query_recent(3600); // Last hour
-        for (i, result) in recent_results.iter().enumerate() {
+        for (i, result) in recent_results.iter().rev().enumerate() {
             let recency_score = 1.0 - (i as f32 / recent_results.len().max(1) as f32);
             let score = recency_score * self.get_weight(&request.strategy, "temporal");
-            *candidates.entry(result.block_sequence).or_insert(0.0) += score;
+            propose("temporal", result.block_sequence, score);
         }
 
         // Entity search (extract entities from query)
@@ -109,7 +156,7 @@ impl SmartRetrievalEngine {
                 let entity_results = entity.query_entity(word);
                 for result in entity_results {
                     let score = 0.8 * self.get_weight(&request.strategy, "entity");
-                    *candidates.entry(result.block_sequence).or_insert(0.0) += score;
+                    propose("entity", result.block_sequence, score);
                 }
             }
         }
@@ -125,12 +172,14 @@ impl SmartRetrievalEngine {
         let mut selected_blocks = Vec::new();
         let mut tokens_used = 0u32;
         let mut omitted = Vec::new();
+        let mut ranking = Vec::new();
 
         for (seq, _score) in &sorted {
             if let Some(block) = storage.get(*seq) {
                 let block_tokens = self.estimate_tokens(&block);
                 if tokens_used + block_tokens <= request.token_budget {
                     tokens_used += block_tokens;
+                    ranking.push(block.sequence);
                     selected_blocks.push(block);
                 } else {
                     omitted.push(block.hash);
@@ -145,9 +194,11 @@ impl SmartRetrievalEngine {
             .map(|b| b.sequence)
             .collect();
 
+        let mut causal_contribution = IndexContribution::default();
         for decision_seq in &decision_blocks {
             let ancestors = causal.get_ancestors(*decision_seq, 3);
             for result in ancestors {
+                causal_contribution.candidates += 1;
                 if !selected_blocks
                     .iter()
                     .any(|b| b.sequence == result.block_sequence)
@@ -156,6 +207,8 @@ impl SmartRetrievalEngine {
                         let block_tokens = self.estimate_tokens(&block);
                         if tokens_used + block_tokens <= request.token_budget {
                             tokens_used += block_tokens;
+                            causal_contribution.selected += 1;
+                            ranking.push(block.sequence);
                             selected_blocks.push(block);
                         }
                     }
@@ -163,10 +216,29 @@ impl SmartRetrievalEngine {
             }
         }
 
-        // Step 6: Sort by sequence (chronological order)
+        // Step 6: Attribute the ranked blocks back to the indexes that proposed them
+        let mut contributions: BTreeMap<String, IndexContribution> = by_index
+            .into_iter()
+            .map(|(index, proposed)| {
+                let mut contribution = IndexContribution {
+                    candidates: proposed.len(),
+                    ..Default::default()
+                };
+                for seq in &ranking {
+                    if let Some(score) = proposed.get(seq) {
+                        contribution.selected += 1;
+                        contribution.score += score;
+                    }
+                }
+                (index.to_string(), contribution)
+            })
+            .collect();
+        contributions.insert("causal".to_string(), causal_contribution);
+
+        // Step 7: Sort by sequence (chronological order)
         selected_blocks.sort_by_key(|b| b.sequence);
 
-        // Step 7: Calculate coverage
+        // Step 8: Calculate coverage
         let coverage = RetrievalCoverage {
             semantic: (selected_blocks.len() as f32 / 100.0).min(1.0),
             temporal: (selected_blocks
@@ -187,6 +259,8 @@ impl SmartRetrievalEngine {
             blocks: selected_blocks,
             tokens_used,
             coverage,
+            ranking,
+            contributions,
             omitted,
             retrieval_ms: start.elapsed().as_millis() as u64,
         }
@@ -862,6 +862,58 @@ mod v3_tests {
         );
     }
 
+    #[test]
+    fn test_retrieval_strategy_changes_ranking() {
+        let (_dir, engine) = test_engine();
+
+        let relevant = engine
+            .capture_user_message("tokio runtime panic on shutdown", None)
+            .unwrap();
+        engine
+            .capture_user_message("lunch plans for friday", None)
+            .unwrap();
+        let newest = engine
+            .capture_user_message("the weather is nice today", None)
+            .unwrap();
+
+        let retrieve = |strategy| {
+            engine.retrieve(RetrievalRequest {
+                query: "tokio runtime panic".to_string(),
+                token_budget: 10000,
+                strategy,
+                min_relevance: 0.0,
+            })
+        };
+        let seq_of = |result: &RetrievalResult, hash: BlockHash| {
+            result
+                .blocks
+                .iter()
+                .find(|b| b.hash == hash)
+                .map(|b| b.sequence)
+                .unwrap()
+        };
+
+        let by_relevance = retrieve(RetrievalStrategy::Relevance);
+        let by_recency = retrieve(RetrievalStrategy::Recency);
+
+        assert_eq!(by_relevance.ranking[0], seq_of(&by_relevance, relevant));
+        assert_eq!(by_recency.ranking[0], seq_of(&by_recency, newest));
+        assert_ne!(by_relevance.ranking, by_recency.ranking);
+
+        // Same candidates either way; only the ordering differs.
+        let mut a = by_relevance.ranking.clone();
+        let mut b = by_recency.ranking.clone();
+        a.sort_unstable();
+        b.sort_unstable();
+        assert_eq!(a, b);
+
+        let semantic = &by_relevance.contributions["semantic"];
+        assert_eq!(semantic.candidates, 1);
+        assert_eq!(semantic.selected, 1);
+        assert!(semantic.score > by_recency.contributions["semantic"].score);
+        assert!(by_relevance.contributions.contains_key("causal"));
+    }
+
     // ═══════════════════════════════════════════════════════════════════
     // EDGE CASE: EMPTY LOG (boundary case)
     // ═══════════════════════════════════════════════════════════════════
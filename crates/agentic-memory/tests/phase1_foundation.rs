@@ -1,13 +1,15 @@
 //! Phase 1 tests: Data structures + file format.
 
 use agentic_memory::format::{AmemReader, AmemWriter};
-use agentic_memory::graph::MemoryGraph;
-use agentic_memory::types::edge::{Edge, EdgeType};
+use agentic_memory::graph::{GraphBuilder, IndexKind, IntegrityViolation, MemoryGraph};
+use agentic_memory::types::edge::{Edge, EdgeType, EdgeTypeNames};
 use agentic_memory::types::error::AmemError;
 use agentic_memory::types::event::{CognitiveEventBuilder, EventType};
 use agentic_memory::types::header::FileHeader;
 use agentic_memory::types::{AMEM_MAGIC, DEFAULT_DIMENSION, FORMAT_VERSION};
 
+use proptest::prelude::*;
+use std::collections::HashSet;
 use std::io::Cursor;
 use tempfile::NamedTempFile;
 
@@ -32,9 +34,55 @@ fn test_event_type_invalid() {
 fn test_edge_type_roundtrip() {
     for val in 0u8..=6 {
         let et = EdgeType::from_u8(val).unwrap();
-        assert_eq!(et as u8, val);
-        assert_eq!(EdgeType::from_u8(et as u8), Some(et));
+        assert_eq!(et.to_u8(), val);
+        assert_eq!(EdgeType::from_u8(et.to_u8()), Some(et));
+    }
+}
+
+#[test]
+fn test_custom_edge_type_names() {
+    let mut names = EdgeTypeNames::new();
+    let implements = names.register("Implements").unwrap();
+    assert!(implements.is_custom());
+    assert_eq!(names.name(implements), "implements");
+    assert_eq!(names.register("implements").unwrap(), implements);
+    assert_eq!(names.get("IMPLEMENTS"), Some(implements));
+    assert_eq!(EdgeType::from_u8(implements.to_u8()), Some(implements));
+
+    // Built-in names never become custom types, and lookups never register.
+    assert_eq!(names.register("supports").unwrap(), EdgeType::Supports);
+    assert_eq!(names.get("not-registered-yet"), None);
+    assert_eq!(EdgeType::from_name("implements"), None);
+    assert_eq!(names.len(), 1);
+
+    assert!(matches!(
+        names.register("has space"),
+        Err(AmemError::InvalidEdgeType(_))
+    ));
+    assert!(names.register("").is_err());
+
+    // Each table is independent: the same id names different types.
+    let mut other = EdgeTypeNames::new();
+    let owns = other.register("owns").unwrap();
+    assert_eq!(owns, implements);
+    assert_eq!(other.name(owns), "owns");
+    assert_eq!(other.get("implements"), None);
+}
+
+#[test]
+fn test_unregistered_custom_edge_type_rejected() {
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    for i in 0..2 {
+        let event = CognitiveEventBuilder::new(EventType::Fact, format!("node_{}", i)).build();
+        graph.add_node(event).unwrap();
     }
+    let custom = EdgeType::from_u8(0x80).unwrap();
+    assert!(matches!(
+        graph.add_edge(Edge::new(0, 1, custom, 0.5)),
+        Err(AmemError::InvalidEdgeType(_))
+    ));
+    assert_eq!(graph.register_edge_type("owns").unwrap(), custom);
+    graph.add_edge(Edge::new(0, 1, custom, 0.5)).unwrap();
 }
 
 #[test]
@@ -331,6 +379,187 @@ fn test_graph_from_parts() {
     assert_eq!(graph.edge_count(), 1);
 }
 
+#[test]
+fn test_graph_check_integrity() {
+    let event = |id: u64| {
+        let mut e = CognitiveEventBuilder::new(EventType::Fact, "a").build();
+        e.id = id;
+        e.feature_vec = vec![0.0; DEFAULT_DIMENSION];
+        e
+    };
+    let graph = MemoryGraph::from_parts(
+        vec![event(0), event(1)],
+        vec![Edge::new(1, 0, EdgeType::CausedBy, 1.0)],
+        DEFAULT_DIMENSION,
+    )
+    .unwrap();
+    assert!(graph.check_integrity().is_empty());
+
+    // The reader does not reject an edge to a node that is gone.
+    let graph = MemoryGraph::from_parts(
+        vec![event(0)],
+        vec![Edge::new(0, 7, EdgeType::Supports, 1.0)],
+        DEFAULT_DIMENSION,
+    )
+    .unwrap();
+    assert_eq!(
+        graph.check_integrity(),
+        vec![IntegrityViolation::DanglingEdge {
+            source_id: 0,
+            target_id: 7,
+            missing: 7,
+        }]
+    );
+
+    let mut graph = MemoryGraph::from_parts(vec![event(0)], vec![], DEFAULT_DIMENSION).unwrap();
+    graph.get_node_mut(0).unwrap().session_id = 9;
+    assert_eq!(
+        graph.check_integrity(),
+        vec![IntegrityViolation::SessionIndexMismatch(0)]
+    );
+}
+
+#[test]
+fn test_graph_verify_indexes_after_incremental_updates() {
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    let mut ids = Vec::new();
+    for (i, content) in ["a", "b", "c", "d"].into_iter().enumerate() {
+        let event = CognitiveEventBuilder::new(EventType::Fact, content)
+            .session_id(i as u32 % 2)
+            .created_at(100 * i as u64)
+            .build();
+        ids.push(graph.add_node(event).unwrap());
+    }
+    graph
+        .add_edge(Edge::new(ids[3], ids[0], EdgeType::Supports, 1.0))
+        .unwrap();
+    graph
+        .add_edge(Edge::new(ids[2], ids[1], EdgeType::CausedBy, 1.0))
+        .unwrap();
+    graph.set_event_type(ids[2], EventType::Decision).unwrap();
+    graph.remove_node(ids[1]).unwrap();
+    let report = graph.verify_indexes();
+    assert!(report.is_consistent(), "{report}");
+    assert_eq!((report.node_count, report.edge_count), (3, 1));
+
+    // Move a node to another session behind the index's back.
+    graph.get_node_mut(ids[0]).unwrap().session_id = 7;
+    let report = graph.verify_indexes();
+    assert_eq!(report.divergences.len(), 1);
+    assert_eq!(report.divergences[0].index, IndexKind::Session);
+    assert_eq!(report.divergences[0].node_ids, vec![ids[0]]);
+    assert!(report.to_string().contains("session index"), "{report}");
+
+    graph.rebuild_indexes();
+    assert!(graph.verify_indexes().is_consistent());
+    assert_eq!(graph.session_index().get_session(7), &[ids[0]]);
+}
+
+#[test]
+fn test_graph_subgraph_is_induced() {
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    for i in 0..6u64 {
+        let event = CognitiveEventBuilder::new(EventType::Fact, format!("node {i} rust memory"))
+            .session_id(i as u32 % 2)
+            .created_at(10 * i)
+            .build();
+        graph.add_node(event).unwrap();
+    }
+    for (source, target) in [(1, 0), (2, 1), (3, 2), (4, 0), (5, 3)] {
+        graph
+            .add_edge(Edge::new(source, target, EdgeType::CausedBy, 1.0))
+            .unwrap();
+    }
+    graph.rename_session(0, "even").unwrap();
+    graph.rename_session(1, "odd").unwrap();
+
+    let input: HashSet<u64> = [0, 2, 4].into_iter().collect();
+    let sub = graph.subgraph(&input);
+    assert_eq!(sub.node_count(), input.len());
+    assert_eq!(sub.dimension(), graph.dimension());
+    assert_eq!(sub.edge_count(), 1);
+    for edge in sub.edges() {
+        assert!(input.contains(&edge.source_id) && input.contains(&edge.target_id));
+    }
+    assert!(sub.verify_indexes().is_consistent());
+    assert_eq!(sub.session_index().get_session(0), &[0, 2, 4]);
+    assert!(sub.session_index().get_session(1).is_empty());
+    assert_eq!(sub.resolve_session("even").unwrap(), 0);
+    assert!(sub.resolve_session("odd").is_err());
+    let terms = sub.term_index().unwrap();
+    assert_eq!(terms.doc_count(), 3);
+    assert_eq!(terms.doc_frequency("rust"), 3);
+
+    // Unknown IDs are ignored and new nodes never reuse a source ID.
+    let mut sub = graph.subgraph(&[1, 99].into_iter().collect());
+    assert_eq!(sub.node_count(), 1);
+    let id = sub
+        .add_node(CognitiveEventBuilder::new(EventType::Fact, "new").build())
+        .unwrap();
+    assert_eq!(id, graph.next_id());
+}
+
+#[test]
+fn test_graph_builder_fluent_defaults() {
+    let mut b = GraphBuilder::new();
+    let fact = b.session(3).confidence(0.8).fact("Team uses Postgres");
+    let decision = b.decision("Keep the ORM");
+    let skill = b
+        .session(4)
+        .confidence(1.0)
+        .skill("Run migrations with sqlx");
+    let episode = b.episode("Database review", &[fact, decision]);
+    b.link(decision, fact, EdgeType::CausedBy, 1.0)
+        .link(skill, decision, EdgeType::Supports, 0.5);
+    assert!(b.error().is_none());
+
+    let graph = b.build().unwrap();
+    assert_eq!(graph.node_count(), 4);
+    assert_eq!(graph.edge_count(), 4);
+    let node = |id| graph.get_node(id).unwrap();
+    assert_eq!((node(fact).session_id, node(fact).confidence), (3, 0.8));
+    assert_eq!(node(decision).event_type, EventType::Decision);
+    assert_eq!(
+        (node(decision).session_id, node(decision).confidence),
+        (3, 0.8)
+    );
+    assert_eq!((node(skill).session_id, node(skill).confidence), (4, 1.0));
+    assert_eq!(node(episode).session_id, 4);
+}
+
+#[test]
+fn test_graph_builder_rejects_invalid_references() {
+    let mut b = GraphBuilder::new();
+    let a = b.fact("a");
+    b.link(a, 99, EdgeType::RelatedTo, 1.0)
+        .link(a, a, EdgeType::RelatedTo, 1.0);
+    // The first mistake is the one reported.
+    assert!(matches!(b.error(), Some(AmemError::InvalidEdgeTarget(99))));
+    assert!(matches!(b.build(), Err(AmemError::InvalidEdgeTarget(99))));
+
+    let mut b = GraphBuilder::new();
+    let a = b.fact("a");
+    b.link(a, a, EdgeType::RelatedTo, 1.0);
+    assert!(matches!(b.build(), Err(AmemError::SelfEdge(id)) if id == a));
+
+    let mut b = GraphBuilder::new();
+    b.fact("a");
+    b.correction("a, corrected", 7);
+    assert!(matches!(b.build(), Err(AmemError::InvalidEdgeTarget(7))));
+
+    let mut b = GraphBuilder::new();
+    let a = b.fact("a");
+    b.set_feature_vec(a, vec![1.0; 3]);
+    assert!(matches!(
+        b.build(),
+        Err(AmemError::DimensionMismatch { got: 3, .. })
+    ));
+
+    let mut b = GraphBuilder::new();
+    b.set_feature_vec(5, vec![0.0; DEFAULT_DIMENSION]);
+    assert!(matches!(b.build(), Err(AmemError::NodeNotFound(5))));
+}
+
 // ==================== File Format Tests ====================
 
 #[test]
@@ -434,6 +663,124 @@ fn test_write_read_with_edges() {
     }
 }
 
+#[test]
+fn test_write_read_custom_edge_types() {
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    for i in 0..3 {
+        let event = CognitiveEventBuilder::new(EventType::Fact, format!("node_{}", i)).build();
+        graph.add_node(event).unwrap();
+    }
+    let depends_on = graph.register_edge_type("depends-on").unwrap();
+    graph.add_edge(Edge::new(0, 1, depends_on, 0.9)).unwrap();
+    graph
+        .add_edge(Edge::new(1, 2, EdgeType::Supports, 0.5))
+        .unwrap();
+
+    let mut buf = Vec::new();
+    AmemWriter::new(DEFAULT_DIMENSION)
+        .write_to(&graph, &mut buf)
+        .unwrap();
+    let loaded = AmemReader::read_from(&mut Cursor::new(buf)).unwrap();
+
+    let edge = loaded.edges_from(0)[0];
+    assert_eq!(edge.edge_type, depends_on);
+    assert_eq!(loaded.edge_type_name(edge.edge_type), "depends-on");
+    assert_eq!(loaded.edges_from(1)[0].edge_type, EdgeType::Supports);
+    assert_eq!(
+        loaded.edge_types().last().copied(),
+        Some(depends_on),
+        "custom types follow the built-ins"
+    );
+}
+
+#[test]
+fn test_write_read_node_tags() {
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    let event = CognitiveEventBuilder::new(EventType::Fact, "tagged")
+        .tag("project", "foo")
+        .tag("source", "https://example.com/a=b")
+        .build();
+    graph.add_node(event).unwrap();
+    graph
+        .add_node(CognitiveEventBuilder::new(EventType::Fact, "untagged").build())
+        .unwrap();
+
+    let mut buf = Vec::new();
+    AmemWriter::new(DEFAULT_DIMENSION)
+        .write_to(&graph, &mut buf)
+        .unwrap();
+    let header = FileHeader::read_from(&mut Cursor::new(&buf[..64])).unwrap();
+    assert_eq!(header.version, FORMAT_VERSION);
+    let loaded = AmemReader::read_from(&mut Cursor::new(&buf)).unwrap();
+
+    let tags = &loaded.get_node(0).unwrap().tags;
+    assert_eq!(tags.len(), 2);
+    assert_eq!(tags["project"], "foo");
+    assert_eq!(tags["source"], "https://example.com/a=b");
+    assert!(loaded.get_node(1).unwrap().tags.is_empty());
+}
+
+proptest! {
+    #[test]
+    fn prop_streaming_write_matches_buffered(
+        nodes in prop::collection::vec(
+            (0u8..6, ".{0,200}", 0u32..4, prop::option::of(("[a-z]{1,8}", "[ -~]{0,16}"))),
+            0..24,
+        ),
+        edges in prop::collection::vec((0usize..24, 0usize..24, 0usize..3, 0.0f32..=1.0), 0..48),
+    ) {
+        let mut graph = MemoryGraph::new(8);
+        for (i, (event_type, content, session, tag)) in nodes.iter().enumerate() {
+            let event_type = EventType::from_u8(*event_type).unwrap();
+            // Every node needs a vector of the graph's dimension; odd ones
+            // stay zero.
+            let value = if i % 2 == 0 { i as f32 } else { 0.0 };
+            let mut builder = CognitiveEventBuilder::new(event_type, content.as_str())
+                .session_id(*session)
+                .feature_vec(vec![value; 8]);
+            if let Some((key, value)) = tag {
+                builder = builder.tag(key.as_str(), value.as_str());
+            }
+            graph.add_node(builder.build()).unwrap();
+        }
+        let types = [EdgeType::CausedBy, EdgeType::Supports, EdgeType::RelatedTo];
+        for &(source, target, edge_type, weight) in &edges {
+            if source < nodes.len() && target < nodes.len() && source != target {
+                let _ = graph.add_edge(Edge::new(
+                    source as u64,
+                    target as u64,
+                    types[edge_type],
+                    weight,
+                ));
+            }
+        }
+
+        let writer = AmemWriter::new(8);
+        let mut buffered = Vec::new();
+        writer.write_to(&graph, &mut buffered).unwrap();
+        let mut streamed = Vec::new();
+        writer.write_streaming(&graph, &mut streamed).unwrap();
+        prop_assert_eq!(streamed, buffered);
+    }
+}
+
+#[test]
+fn test_version_1_file_reads_untagged() {
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    graph
+        .add_node(CognitiveEventBuilder::new(EventType::Fact, "old").build())
+        .unwrap();
+    let mut buf = Vec::new();
+    AmemWriter::new(DEFAULT_DIMENSION)
+        .write_to(&graph, &mut buf)
+        .unwrap();
+    buf[4..8].copy_from_slice(&1u32.to_le_bytes());
+
+    let loaded = AmemReader::read_from(&mut Cursor::new(&buf)).unwrap();
+    assert_eq!(loaded.get_node(0).unwrap().content, "old");
+    assert!(loaded.get_node(0).unwrap().tags.is_empty());
+}
+
 #[test]
 fn test_write_read_feature_vectors() {
     let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
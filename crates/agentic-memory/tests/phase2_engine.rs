@@ -1,16 +1,21 @@
 //! Phase 2 tests: Write Engine + Query Engine.
 
+use agentic_memory::engine::decay::DecayFunction;
+use agentic_memory::engine::diff::{diff_graphs, DiffField, FieldValue, GraphPatch};
+use agentic_memory::engine::normalize::ContentNormalizer;
+use agentic_memory::engine::profile::QueryProfile;
 use agentic_memory::engine::query::{
-    CausalParams, PatternParams, PatternSort, QueryEngine, SimilarityParams, TemporalParams,
-    TimeRange, TraversalParams,
+    CausalDirection, CausalParams, EdgeExpansion, PatternParams, PatternSort, QueryEngine,
+    SimilarityParams, TemporalClusterParams, TemporalParams, TimeRange, TraversalParams,
 };
-use agentic_memory::engine::write::WriteEngine;
+use agentic_memory::engine::write::{EdgeMergePolicy, MergeStrategy, WriteEngine};
+use agentic_memory::format::{AmemReader, AmemWriter};
 use agentic_memory::graph::traversal::TraversalDirection;
 use agentic_memory::graph::MemoryGraph;
 use agentic_memory::types::edge::{Edge, EdgeType};
 use agentic_memory::types::error::AmemError;
 use agentic_memory::types::event::{CognitiveEventBuilder, EventType};
-use agentic_memory::types::{DEFAULT_DIMENSION, MAX_CONTENT_SIZE};
+use agentic_memory::types::{content_hash, Clock, MockClock, DEFAULT_DIMENSION, MAX_CONTENT_SIZE};
 
 // ==================== Helper ====================
 
@@ -83,6 +88,167 @@ fn test_ingest_batch() {
     assert_eq!(graph.edge_count(), 49);
 }
 
+#[test]
+fn test_ingest_rejects_self_edges_unless_enabled() {
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    let engine = WriteEngine::new(DEFAULT_DIMENSION);
+    let event = CognitiveEventBuilder::new(EventType::Fact, "loops back")
+        .feature_vec(zero_vec())
+        .build();
+    let id = engine
+        .ingest(&mut graph, vec![event], vec![])
+        .unwrap()
+        .new_node_ids[0];
+
+    let self_edge = Edge::new(id, id, EdgeType::RelatedTo, 1.0);
+    let err = engine
+        .ingest(&mut graph, vec![], vec![self_edge])
+        .unwrap_err();
+    assert!(matches!(err, AmemError::SelfEdge(n) if n == id));
+    assert_eq!(graph.edge_count(), 0);
+
+    let permissive = WriteEngine::new(DEFAULT_DIMENSION).with_self_edges(true);
+    let result = permissive
+        .ingest(&mut graph, vec![], vec![self_edge])
+        .unwrap();
+    assert_eq!(result.new_edge_count, 1);
+    assert_eq!(graph.edge_count(), 1);
+}
+
+#[test]
+fn test_ingest_merges_duplicate_edges() {
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    let engine = WriteEngine::new(DEFAULT_DIMENSION);
+    let events = ["cause", "effect"]
+        .into_iter()
+        .map(|c| {
+            CognitiveEventBuilder::new(EventType::Fact, c)
+                .feature_vec(zero_vec())
+                .build()
+        })
+        .collect();
+    let ids = engine
+        .ingest(&mut graph, events, vec![])
+        .unwrap()
+        .new_node_ids;
+    let (a, b) = (ids[0], ids[1]);
+
+    // Default: one edge survives, with the stronger weight.
+    let result = engine
+        .ingest(
+            &mut graph,
+            vec![],
+            vec![
+                Edge::new(a, b, EdgeType::Supports, 0.3),
+                Edge::new(a, b, EdgeType::Supports, 0.8),
+                Edge::new(a, b, EdgeType::RelatedTo, 0.5),
+            ],
+        )
+        .unwrap();
+    assert_eq!(result.new_edge_count, 2);
+    assert_eq!(result.merged_edge_count, 1);
+    assert_eq!(graph.edge_count(), 2);
+    let weight = |graph: &MemoryGraph| {
+        graph
+            .edges_from(a)
+            .iter()
+            .find(|e| e.edge_type == EdgeType::Supports)
+            .unwrap()
+            .weight
+    };
+    assert!((weight(&graph) - 0.8).abs() < f32::EPSILON);
+
+    let keep =
+        WriteEngine::new(DEFAULT_DIMENSION).with_edge_merge_policy(EdgeMergePolicy::KeepExisting);
+    let result = keep
+        .ingest(
+            &mut graph,
+            vec![],
+            vec![Edge::new(a, b, EdgeType::Supports, 1.0)],
+        )
+        .unwrap();
+    assert_eq!(result.merged_edge_count, 1);
+    assert!((weight(&graph) - 0.8).abs() < f32::EPSILON);
+
+    let strict =
+        WriteEngine::new(DEFAULT_DIMENSION).with_edge_merge_policy(EdgeMergePolicy::Reject);
+    let err = strict
+        .ingest(
+            &mut graph,
+            vec![],
+            vec![Edge::new(a, b, EdgeType::Supports, 1.0)],
+        )
+        .unwrap_err();
+    assert!(
+        matches!(err, AmemError::DuplicateEdge { source_id, target_id, .. } if source_id == a && target_id == b)
+    );
+    assert_eq!(graph.edge_count(), 2);
+}
+
+#[test]
+fn test_batch_ingest_is_atomic() {
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    let engine = WriteEngine::new(DEFAULT_DIMENSION);
+    let batch = |contents: &[&str]| {
+        contents
+            .iter()
+            .map(|c| {
+                CognitiveEventBuilder::new(EventType::Fact, *c)
+                    .feature_vec(zero_vec())
+                    .build()
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let result = engine
+        .batch_ingest(
+            &mut graph,
+            batch(&["premise", "conclusion", "aside"]),
+            vec![
+                (1, 0, EdgeType::CausedBy, 0.9),
+                (2, 0, EdgeType::RelatedTo, 0.4),
+                (2, 0, EdgeType::RelatedTo, 0.6),
+            ],
+        )
+        .unwrap();
+    assert_eq!(result.node_ids, vec![0, 1, 2]);
+    assert_eq!(result.edge_count, 2);
+    assert_eq!(graph.node_count(), 3);
+    assert_eq!(graph.edge_count(), 2);
+
+    // An out-of-range index, a self-edge or a bad vector fails the whole batch.
+    let err = engine
+        .batch_ingest(
+            &mut graph,
+            batch(&["x", "y"]),
+            vec![
+                (0, 1, EdgeType::Supports, 1.0),
+                (0, 5, EdgeType::Supports, 1.0),
+            ],
+        )
+        .unwrap_err();
+    assert!(matches!(err, AmemError::InvalidEdgeTarget(8)));
+    let err = engine
+        .batch_ingest(
+            &mut graph,
+            batch(&["x"]),
+            vec![(0, 0, EdgeType::RelatedTo, 1.0)],
+        )
+        .unwrap_err();
+    assert!(matches!(err, AmemError::SelfEdge(3)));
+    let mut events = batch(&["x", "y"]);
+    events[1].feature_vec = vec![1.0; 3];
+    assert!(engine.batch_ingest(&mut graph, events, vec![]).is_err());
+    assert_eq!(graph.node_count(), 3);
+    assert_eq!(graph.edge_count(), 2);
+
+    // Failed batches do not use up IDs.
+    let result = engine
+        .batch_ingest(&mut graph, batch(&["next"]), vec![])
+        .unwrap();
+    assert_eq!(result.node_ids, vec![3]);
+}
+
 #[test]
 fn test_correct_node() {
     let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
@@ -119,6 +285,150 @@ fn test_correct_node() {
     assert_eq!(edges_from_new[0].target_id, old_id);
 }
 
+#[test]
+fn test_correct_to_same_content_is_noop() {
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    let engine = WriteEngine::new(DEFAULT_DIMENSION);
+    let event = CognitiveEventBuilder::new(EventType::Fact, "Earth has one moon")
+        .session_id(1)
+        .confidence(0.9)
+        .feature_vec(zero_vec())
+        .build();
+    let old_id = engine
+        .ingest(&mut graph, vec![event], vec![])
+        .unwrap()
+        .new_node_ids[0];
+
+    assert!(engine.is_noop_correction(&graph, old_id, "Earth has one moon"));
+    let id = engine
+        .correct(&mut graph, old_id, "Earth has one moon", 2)
+        .unwrap();
+    assert_eq!(id, old_id);
+    assert_eq!(graph.node_count(), 1);
+    assert_eq!(graph.edge_count(), 0);
+    assert!((graph.get_node(old_id).unwrap().confidence - 0.9).abs() < f32::EPSILON);
+
+    let reasserting = WriteEngine::new(DEFAULT_DIMENSION).with_reassert_corrections(true);
+    let new_id = reasserting
+        .correct(&mut graph, old_id, "Earth has one moon", 2)
+        .unwrap();
+    assert_ne!(new_id, old_id);
+    assert_eq!(graph.node_count(), 2);
+}
+
+#[test]
+fn test_normalizer_dedups_whitespace_variants() {
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    let engine = WriteEngine::new(DEFAULT_DIMENSION).with_normalizer(ContentNormalizer::standard());
+    let events = vec![
+        CognitiveEventBuilder::new(EventType::Fact, "  User prefers\tdark   mode\n")
+            .session_id(1)
+            .feature_vec(zero_vec())
+            .build(),
+        CognitiveEventBuilder::new(EventType::Fact, "User prefers dark mode")
+            .session_id(1)
+            .feature_vec(zero_vec())
+            .build(),
+    ];
+    let ids = engine
+        .ingest(&mut graph, events, vec![])
+        .unwrap()
+        .new_node_ids;
+
+    let first = &graph.get_node(ids[0]).unwrap().content;
+    let second = &graph.get_node(ids[1]).unwrap().content;
+    assert_eq!(first, "User prefers dark mode");
+    assert_eq!(content_hash(first), content_hash(second));
+
+    // A correction differing only in whitespace changes nothing.
+    assert!(engine.is_noop_correction(&graph, ids[0], "User  prefers dark mode "));
+    let id = engine
+        .correct(&mut graph, ids[0], "User  prefers dark mode ", 2)
+        .unwrap();
+    assert_eq!(id, ids[0]);
+    assert_eq!(graph.node_count(), 2);
+}
+
+#[test]
+fn test_normalizer_is_idempotent_and_off_by_default() {
+    let normalizer = ContentNormalizer::standard();
+    for raw in [
+        "  a \t b\n\n c  ",
+        "plain",
+        "",
+        "   ",
+        "caf\u{65}\u{301}  au lait",
+    ] {
+        let once = normalizer.normalize(raw);
+        assert_eq!(normalizer.normalize(&once), once);
+    }
+
+    let engine = WriteEngine::new(DEFAULT_DIMENSION);
+    assert!(!engine.normalizer().is_enabled());
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    let event = CognitiveEventBuilder::new(EventType::Fact, " spaced  out ")
+        .session_id(1)
+        .feature_vec(zero_vec())
+        .build();
+    let id = engine
+        .ingest(&mut graph, vec![event], vec![])
+        .unwrap()
+        .new_node_ids[0];
+    assert_eq!(graph.get_node(id).unwrap().content, " spaced  out ");
+}
+
+#[test]
+fn test_correct_with_confidence_and_vector() {
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    let engine = WriteEngine::new(DEFAULT_DIMENSION);
+
+    let event = CognitiveEventBuilder::new(EventType::Fact, "Build takes 10 minutes")
+        .confidence(0.6)
+        .feature_vec(zero_vec())
+        .build();
+    let old_id = engine
+        .ingest(&mut graph, vec![event], vec![])
+        .unwrap()
+        .new_node_ids[0];
+
+    // Wrong dimension and out-of-range confidence are rejected without writes.
+    let err = engine
+        .correct_with(
+            &mut graph,
+            old_id,
+            "Build takes 4 minutes",
+            1,
+            0.9,
+            Some(vec![1.0; 3]),
+        )
+        .unwrap_err();
+    assert!(matches!(err, AmemError::DimensionMismatch { .. }));
+    let err = engine
+        .correct_with(&mut graph, old_id, "Build takes 4 minutes", 1, 1.5, None)
+        .unwrap_err();
+    assert!(matches!(err, AmemError::InvalidConfidence(_)));
+    assert_eq!(graph.node_count(), 1);
+    assert_eq!(graph.edge_count(), 0);
+    assert!((graph.get_node(old_id).unwrap().confidence - 0.6).abs() < f32::EPSILON);
+
+    let mut vec = zero_vec();
+    vec[0] = 1.0;
+    let new_id = engine
+        .correct_with(
+            &mut graph,
+            old_id,
+            "Build takes 4 minutes",
+            1,
+            0.8,
+            Some(vec.clone()),
+        )
+        .unwrap();
+    let new_node = graph.get_node(new_id).unwrap();
+    assert!((new_node.confidence - 0.8).abs() < f32::EPSILON);
+    assert_eq!(new_node.feature_vec, vec);
+    assert!((graph.get_node(old_id).unwrap().confidence - 0.0).abs() < f32::EPSILON);
+}
+
 #[test]
 fn test_compress_session() {
     let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
@@ -162,6 +472,73 @@ fn test_compress_session() {
     }
 }
 
+#[test]
+fn test_compress_large_session_into_chunked_episodes() {
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    let engine = WriteEngine::new(DEFAULT_DIMENSION).with_max_events_per_episode(Some(10));
+
+    // Three bursts of activity (8, 9 and 8 nodes) separated by long pauses.
+    let mut members = Vec::new();
+    for (burst, size) in [(0u64, 8u64), (1, 9), (2, 8)] {
+        for i in 0..size {
+            let event = CognitiveEventBuilder::new(EventType::Fact, format!("burst {burst} #{i}"))
+                .session_id(7)
+                .created_at(burst * 1_000_000_000 + i * 1_000)
+                .feature_vec(zero_vec())
+                .build();
+            members.push(graph.add_node(event).unwrap());
+        }
+    }
+
+    let episodes = engine
+        .compress_session_episodes(&mut graph, 7, "Long session")
+        .unwrap();
+
+    // Chunks break at the pauses rather than every 10 nodes.
+    assert_eq!(episodes.len(), 3);
+    let mut covered = Vec::new();
+    for (i, (&episode, expected)) in episodes.iter().zip([8, 9, 8]).enumerate() {
+        let node = graph.get_node(episode).unwrap();
+        assert_eq!(node.event_type, EventType::Episode);
+        assert_eq!(node.content, format!("Long session (part {} of 3)", i + 1));
+        let parts: Vec<u64> = graph
+            .edges_to(episode)
+            .iter()
+            .filter(|e| e.edge_type == EdgeType::PartOf)
+            .map(|e| e.source_id)
+            .collect();
+        assert_eq!(parts.len(), expected);
+        covered.extend(parts);
+    }
+    covered.sort_unstable();
+    assert_eq!(covered, members);
+    assert_eq!(
+        graph.get_node(members[8]).map(|n| n.content.as_str()),
+        Some("burst 1 #0")
+    );
+    assert!(graph
+        .edges_from(members[8])
+        .iter()
+        .any(|e| e.edge_type == EdgeType::PartOf && e.target_id == episodes[1]));
+
+    // Episodes form a TEMPORAL_NEXT chain in order.
+    for pair in episodes.windows(2) {
+        assert!(graph
+            .edges_from(pair[0])
+            .iter()
+            .any(|e| e.edge_type == EdgeType::TemporalNext && e.target_id == pair[1]));
+    }
+    assert!(graph.edges_from(episodes[2]).is_empty());
+
+    // Without a cap the session still compresses into a single episode.
+    let unlimited = WriteEngine::new(DEFAULT_DIMENSION);
+    assert_eq!(unlimited.max_events_per_episode(), None);
+    let single = unlimited
+        .compress_session_episodes(&mut graph, 7, "Again")
+        .unwrap();
+    assert_eq!(single.len(), 1);
+}
+
 #[test]
 fn test_touch_updates_access() {
     let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
@@ -247,6 +624,214 @@ fn test_decay_calculation() {
     );
 }
 
+#[test]
+fn test_decay_with_mock_clock() {
+    let micros_per_day: u64 = 86_400_000_000;
+    let clock = MockClock::shared(10 * micros_per_day);
+    let engine = WriteEngine::new(DEFAULT_DIMENSION).with_clock(clock.clone());
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+
+    let event = CognitiveEventBuilder::new(EventType::Fact, "clocked fact")
+        .session_id(1)
+        .created_at(engine.now_micros())
+        .feature_vec(zero_vec())
+        .build();
+    let id = engine
+        .ingest(&mut graph, vec![event], vec![])
+        .unwrap()
+        .new_node_ids[0];
+
+    // Touch stamps the mocked time, not the wall clock.
+    clock.advance_secs(3600);
+    engine.touch(&mut graph, id).unwrap();
+    assert_eq!(
+        graph.get_node(id).unwrap().last_accessed,
+        10 * micros_per_day + 3_600_000_000
+    );
+
+    engine.run_decay_now(&mut graph).unwrap();
+    let fresh = graph.get_node(id).unwrap().decay_score;
+    assert!(fresh > 0.0);
+
+    // Jump ahead without sleeping; the same node decays.
+    clock.advance(90 * micros_per_day);
+    engine.run_decay_now(&mut graph).unwrap();
+    let aged = graph.get_node(id).unwrap().decay_score;
+    assert!(
+        aged < fresh,
+        "decay should drop after 90 mocked days ({} -> {})",
+        fresh,
+        aged
+    );
+
+    // Corrections are stamped with the mocked time too.
+    let new_id = engine.correct(&mut graph, id, "corrected", 1).unwrap();
+    assert_eq!(
+        graph.get_node(new_id).unwrap().created_at,
+        clock.now_micros()
+    );
+}
+
+#[test]
+fn test_custom_decay_functions() {
+    let micros_per_day: u64 = 86_400_000_000;
+    let decay_after = |engine: &WriteEngine, days: f64| {
+        let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+        let event = CognitiveEventBuilder::new(EventType::Fact, "fading")
+            .created_at(micros_per_day)
+            .feature_vec(zero_vec())
+            .build();
+        let id = graph.add_node(event).unwrap();
+        let now = micros_per_day + (days * micros_per_day as f64) as u64;
+        engine.run_decay(&mut graph, now).unwrap();
+        graph.get_node(id).unwrap().decay_score
+    };
+
+    let exponential =
+        WriteEngine::new(DEFAULT_DIMENSION).with_decay_function(DecayFunction::Exponential(1.0));
+    assert!((decay_after(&exponential, 1.0) - 0.5).abs() < 1e-3);
+    assert!((decay_after(&exponential, 2.0) - 0.25).abs() < 1e-3);
+
+    let hyperbolic =
+        WriteEngine::new(DEFAULT_DIMENSION).with_decay_function(DecayFunction::Hyperbolic);
+    assert!((decay_after(&hyperbolic, 3.0) - 0.25).abs() < 1e-3);
+
+    let step =
+        WriteEngine::new(DEFAULT_DIMENSION).with_decay_function(DecayFunction::Step(7.0, 0.3));
+    assert!((decay_after(&step, 6.0) - 1.0).abs() < f32::EPSILON);
+    assert!((decay_after(&step, 8.0) - 0.3).abs() < f32::EPSILON);
+
+    // A closure sees the current score, the age and the access count, and
+    // its result is clamped.
+    let custom = WriteEngine::new_with_decay(
+        DEFAULT_DIMENSION,
+        Box::new(|current, age, accesses| current - age as f32 / 1e9 + accesses as f32),
+    );
+    assert_eq!(decay_after(&custom, 0.0), 1.0);
+    assert_eq!(decay_after(&custom, 30.0), 0.0);
+
+    assert_eq!(
+        DecayFunction::parse("exponential:14"),
+        Some(DecayFunction::Exponential(14.0))
+    );
+    assert_eq!(
+        DecayFunction::parse(" Hyperbolic "),
+        Some(DecayFunction::Hyperbolic)
+    );
+    assert_eq!(
+        DecayFunction::parse("step:30:0.5"),
+        Some(DecayFunction::Step(30.0, 0.5))
+    );
+    for bad in [
+        "exponential",
+        "exponential:0",
+        "step:30",
+        "step:30:2",
+        "linear",
+    ] {
+        assert_eq!(DecayFunction::parse(bad), None, "{bad}");
+    }
+    assert_eq!(DecayFunction::Step(30.0, 0.5).to_string(), "step:30:0.5");
+}
+
+#[test]
+fn test_decay_chunk_resumes_across_changes() {
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    let engine = WriteEngine::new(DEFAULT_DIMENSION);
+    let micros_per_day: u64 = 86_400_000_000;
+
+    let events: Vec<_> = (0..10)
+        .map(|i| {
+            CognitiveEventBuilder::new(EventType::Fact, format!("chunk_fact_{}", i))
+                .session_id(1)
+                .created_at(micros_per_day)
+                .feature_vec(zero_vec())
+                .build()
+        })
+        .collect();
+    let ids = engine
+        .ingest(&mut graph, events, vec![])
+        .unwrap()
+        .new_node_ids;
+    let now = 1000 * micros_per_day;
+
+    let first = engine.run_decay_chunk(&mut graph, now, None, 4).unwrap();
+    assert_eq!(first.nodes_visited, 4);
+    assert!(!first.complete);
+    assert_eq!(first.cursor, Some(ids[3]));
+
+    // Between chunks: drop a node not yet visited and add a new one.
+    graph.remove_node(ids[6]).unwrap();
+    let late = CognitiveEventBuilder::new(EventType::Fact, "late fact")
+        .session_id(1)
+        .created_at(micros_per_day)
+        .feature_vec(zero_vec())
+        .build();
+    let late_id = engine
+        .ingest(&mut graph, vec![late], vec![])
+        .unwrap()
+        .new_node_ids[0];
+
+    let mut cursor = first.cursor;
+    let mut visited = first.nodes_visited;
+    loop {
+        let report = engine.run_decay_chunk(&mut graph, now, cursor, 4).unwrap();
+        visited += report.nodes_visited;
+        cursor = report.cursor;
+        if report.complete {
+            assert!(cursor.is_none());
+            break;
+        }
+    }
+
+    // Nine original survivors plus the late node, each visited once.
+    assert_eq!(visited, 10);
+    assert!(graph.get_node(late_id).unwrap().decay_score < 0.2);
+    for node in graph.nodes() {
+        assert!(node.decay_score < 0.2, "node {} was not decayed", node.id);
+    }
+}
+
+#[test]
+fn test_pinned_nodes_skip_decay() {
+    let micros_per_day: u64 = 86_400_000_000;
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    let engine = WriteEngine::new(DEFAULT_DIMENSION);
+
+    let old_event = |content: &str, pinned: bool| {
+        CognitiveEventBuilder::new(EventType::Decision, content)
+            .created_at(micros_per_day)
+            .feature_vec(zero_vec())
+            .pinned(pinned)
+            .build()
+    };
+    let ids = engine
+        .ingest(
+            &mut graph,
+            vec![old_event("pinned", true), old_event("unpinned", false)],
+            vec![],
+        )
+        .unwrap()
+        .new_node_ids;
+    let original = graph.get_node(ids[0]).unwrap().decay_score;
+
+    let report = engine.run_decay(&mut graph, 101 * micros_per_day).unwrap();
+    assert_eq!(report.pinned_count, 1);
+    assert_eq!(report.nodes_decayed, 1);
+    assert_eq!(graph.get_node(ids[0]).unwrap().decay_score, original);
+    assert!(graph.get_node(ids[1]).unwrap().decay_score < original);
+    assert!(!report.low_importance_nodes.contains(&ids[0]));
+
+    // The pin survives a save and reload.
+    let mut buf = Vec::new();
+    AmemWriter::new(DEFAULT_DIMENSION)
+        .write_to(&graph, &mut buf)
+        .unwrap();
+    let loaded = AmemReader::read_from(&mut buf.as_slice()).unwrap();
+    assert!(loaded.get_node(ids[0]).unwrap().pinned);
+    assert!(!loaded.get_node(ids[1]).unwrap().pinned);
+}
+
 #[test]
 fn test_decay_never_deletes() {
     let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
@@ -384,6 +969,7 @@ fn test_traverse_forward() {
                 max_depth: 10,
                 max_results: 100,
                 min_confidence: 0.0,
+                symmetric_undirected: false,
             },
         )
         .unwrap();
@@ -395,6 +981,31 @@ fn test_traverse_forward() {
     assert_eq!(result.visited.len(), 3);
 }
 
+#[test]
+fn test_traverse_with_self_edge_visits_each_node_once() {
+    let (mut graph, [id_a, id_b, _]) = build_chain_abc();
+    graph
+        .add_self_edge(Edge::new(id_b, id_b, EdgeType::CausedBy, 1.0))
+        .unwrap();
+
+    let result = QueryEngine::new()
+        .traverse(
+            &graph,
+            TraversalParams {
+                start_id: id_a,
+                edge_types: vec![EdgeType::CausedBy],
+                direction: TraversalDirection::Forward,
+                max_depth: 10,
+                max_results: 100,
+                min_confidence: 0.0,
+                symmetric_undirected: false,
+            },
+        )
+        .unwrap();
+    assert_eq!(result.visited.len(), 3);
+    assert_eq!(result.depths[&id_b], 1);
+}
+
 #[test]
 fn test_traverse_backward() {
     let (graph, [id_a, id_b, id_c]) = build_chain_abc();
@@ -410,6 +1021,7 @@ fn test_traverse_backward() {
                 max_depth: 10,
                 max_results: 100,
                 min_confidence: 0.0,
+                symmetric_undirected: false,
             },
         )
         .unwrap();
@@ -452,6 +1064,7 @@ fn test_traverse_max_depth() {
                 max_depth: 3,
                 max_results: 100,
                 min_confidence: 0.0,
+                symmetric_undirected: false,
             },
         )
         .unwrap();
@@ -509,6 +1122,7 @@ fn test_traverse_respects_edge_type_filter() {
                 max_depth: 10,
                 max_results: 100,
                 min_confidence: 0.0,
+                symmetric_undirected: false,
             },
         )
         .unwrap();
@@ -567,6 +1181,7 @@ fn test_traverse_handles_cycles() {
                 max_depth: 100,
                 max_results: 100,
                 min_confidence: 0.0,
+                symmetric_undirected: false,
             },
         )
         .unwrap();
@@ -621,6 +1236,7 @@ fn test_traverse_min_confidence() {
                 max_depth: 10,
                 max_results: 100,
                 min_confidence: 0.5,
+                symmetric_undirected: false,
             },
         )
         .unwrap();
@@ -633,6 +1249,62 @@ fn test_traverse_min_confidence() {
     );
 }
 
+#[test]
+fn test_traverse_symmetric_edges_undirected() {
+    // A -> B (CausedBy), C -> B (RelatedTo, stored only in that direction).
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    let mut add = |content: &str| {
+        graph
+            .add_node(
+                CognitiveEventBuilder::new(EventType::Fact, content)
+                    .feature_vec(zero_vec())
+                    .build(),
+            )
+            .unwrap()
+    };
+    let (a, b, c) = (add("A"), add("B"), add("C"));
+    graph
+        .add_edge(Edge::new(a, b, EdgeType::CausedBy, 1.0))
+        .unwrap();
+    graph
+        .add_edge(Edge::new(c, b, EdgeType::RelatedTo, 1.0))
+        .unwrap();
+
+    assert!(EdgeType::RelatedTo.is_symmetric());
+    assert!(EdgeType::Contradicts.is_symmetric());
+    assert!(!EdgeType::CausedBy.is_symmetric());
+
+    // B has no outgoing edges, but the incoming RelatedTo counts; the
+    // incoming CausedBy does not.
+    let undirected: Vec<(u64, u64)> = graph
+        .edges_undirected(b)
+        .iter()
+        .map(|e| (e.source_id, e.target_id))
+        .collect();
+    assert_eq!(undirected, vec![(c, b)]);
+
+    let engine = QueryEngine::new();
+    let traverse = |symmetric_undirected| {
+        engine
+            .traverse(
+                &graph,
+                TraversalParams {
+                    start_id: a,
+                    edge_types: vec![EdgeType::CausedBy, EdgeType::RelatedTo],
+                    direction: TraversalDirection::Forward,
+                    max_depth: 5,
+                    max_results: 10,
+                    min_confidence: 0.0,
+                    symmetric_undirected,
+                },
+            )
+            .unwrap()
+            .visited
+    };
+    assert_eq!(traverse(false), vec![a, b]);
+    assert_eq!(traverse(true), vec![a, b, c]);
+}
+
 // ==================== Query Engine: Pattern Tests ====================
 
 #[test]
@@ -667,6 +1339,8 @@ fn test_pattern_by_type() {
                 created_after: None,
                 created_before: None,
                 min_decay_score: None,
+                required_tags: Default::default(),
+                any_tags: Default::default(),
                 max_results: 100,
                 sort_by: PatternSort::MostRecent,
             },
@@ -707,6 +1381,8 @@ fn test_pattern_by_session() {
                 created_after: None,
                 created_before: None,
                 min_decay_score: None,
+                required_tags: Default::default(),
+                any_tags: Default::default(),
                 max_results: 100,
                 sort_by: PatternSort::MostRecent,
             },
@@ -745,6 +1421,8 @@ fn test_pattern_by_time_range() {
                 created_after: Some(2_000_000),
                 created_before: Some(4_000_000),
                 min_decay_score: None,
+                required_tags: Default::default(),
+                any_tags: Default::default(),
                 max_results: 100,
                 sort_by: PatternSort::MostRecent,
             },
@@ -785,6 +1463,8 @@ fn test_pattern_by_confidence_range() {
                 created_after: None,
                 created_before: None,
                 min_decay_score: None,
+                required_tags: Default::default(),
+                any_tags: Default::default(),
                 max_results: 100,
                 sort_by: PatternSort::MostRecent,
             },
@@ -824,6 +1504,8 @@ fn test_pattern_sort_recent() {
                 created_after: None,
                 created_before: None,
                 min_decay_score: None,
+                required_tags: Default::default(),
+                any_tags: Default::default(),
                 max_results: 100,
                 sort_by: PatternSort::MostRecent,
             },
@@ -864,6 +1546,8 @@ fn test_pattern_sort_confidence() {
                 created_after: None,
                 created_before: None,
                 min_decay_score: None,
+                required_tags: Default::default(),
+                any_tags: Default::default(),
                 max_results: 100,
                 sort_by: PatternSort::HighestConfidence,
             },
@@ -905,6 +1589,8 @@ fn test_pattern_max_results() {
                 created_after: None,
                 created_before: None,
                 min_decay_score: None,
+                required_tags: Default::default(),
+                any_tags: Default::default(),
                 max_results: 10,
                 sort_by: PatternSort::MostRecent,
             },
@@ -914,6 +1600,186 @@ fn test_pattern_max_results() {
     assert_eq!(results.len(), 10);
 }
 
+#[test]
+fn test_pattern_profiled_records_phases() {
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    for i in 0..50 {
+        let event_type = if i % 2 == 0 {
+            EventType::Fact
+        } else {
+            EventType::Decision
+        };
+        let event = CognitiveEventBuilder::new(event_type, format!("node_{}", i))
+            .session_id(1)
+            .build();
+        graph.add_node(event).unwrap();
+    }
+
+    let params = || PatternParams {
+        event_types: vec![EventType::Decision],
+        min_confidence: None,
+        max_confidence: None,
+        session_ids: vec![],
+        created_after: None,
+        created_before: None,
+        min_decay_score: None,
+        required_tags: Default::default(),
+        any_tags: Default::default(),
+        max_results: 5,
+        sort_by: PatternSort::MostRecent,
+    };
+    let qe = QueryEngine::new();
+    let mut profile = QueryProfile::new();
+    let profiled: Vec<u64> = qe
+        .pattern_profiled(&graph, params(), Some(&mut profile))
+        .unwrap()
+        .iter()
+        .map(|n| n.id)
+        .collect();
+    let plain: Vec<u64> = qe
+        .pattern(&graph, params())
+        .unwrap()
+        .iter()
+        .map(|n| n.id)
+        .collect();
+    assert_eq!(profiled, plain);
+
+    let names: Vec<&str> = profile.phases().iter().map(|p| p.name).collect();
+    assert_eq!(names, ["index lookup", "filter", "sort"]);
+    assert_eq!(
+        profile.total(),
+        profile
+            .phases()
+            .iter()
+            .map(|p| p.elapsed)
+            .sum::<std::time::Duration>()
+    );
+}
+
+#[test]
+fn test_pattern_required_tags() {
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    for (event_type, project) in [
+        (EventType::Fact, Some("foo")),
+        (EventType::Fact, Some("bar")),
+        (EventType::Decision, Some("foo")),
+        (EventType::Fact, None),
+    ] {
+        let mut builder = CognitiveEventBuilder::new(event_type, "node");
+        if let Some(project) = project {
+            builder = builder.tag("project", project).tag("role", "planner");
+        }
+        graph.add_node(builder.build()).unwrap();
+    }
+
+    let params = PatternParams {
+        event_types: vec![EventType::Fact],
+        min_confidence: None,
+        max_confidence: None,
+        session_ids: vec![],
+        created_after: None,
+        created_before: None,
+        min_decay_score: None,
+        required_tags: [("project".to_string(), "foo".to_string())].into(),
+        any_tags: Default::default(),
+        max_results: 10,
+        sort_by: PatternSort::MostRecent,
+    };
+    let results = QueryEngine::new().pattern(&graph, params.clone()).unwrap();
+    let ids: Vec<u64> = results.iter().map(|n| n.id).collect();
+    assert_eq!(ids, vec![0]);
+    assert!(params
+        .matched_predicates(results[0])
+        .contains(&"tag project=foo".to_string()));
+
+    let mut both = params;
+    both.required_tags
+        .insert("role".to_string(), "reviewer".to_string());
+    assert!(QueryEngine::new().pattern(&graph, both).unwrap().is_empty());
+}
+
+#[test]
+fn test_pattern_any_tags() {
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    for project in ["foo", "bar", "baz"] {
+        let event = CognitiveEventBuilder::new(EventType::Fact, "node")
+            .tag("project", project)
+            .build();
+        graph.add_node(event).unwrap();
+    }
+    graph
+        .add_node(CognitiveEventBuilder::new(EventType::Fact, "untagged").build())
+        .unwrap();
+
+    let params = PatternParams {
+        event_types: vec![],
+        min_confidence: None,
+        max_confidence: None,
+        session_ids: vec![],
+        created_after: None,
+        created_before: None,
+        min_decay_score: None,
+        required_tags: Default::default(),
+        any_tags: vec![
+            ("project".to_string(), "foo".to_string()),
+            ("project".to_string(), "baz".to_string()),
+        ],
+        max_results: 10,
+        sort_by: PatternSort::MostRecent,
+    };
+    let results = QueryEngine::new().pattern(&graph, params.clone()).unwrap();
+    let mut ids: Vec<u64> = results.iter().map(|n| n.id).collect();
+    ids.sort_unstable();
+    assert_eq!(ids, vec![0, 2]);
+    let node = graph.get_node(2).unwrap();
+    assert_eq!(
+        params.matched_predicates(node),
+        vec!["tags [project=baz] match any of [project=foo, project=baz]".to_string()]
+    );
+
+    // Retagging a node moves it in the tag index.
+    graph
+        .set_node_tags(1, [("project".to_string(), "foo".to_string())].into())
+        .unwrap();
+    let results = QueryEngine::new().pattern(&graph, params).unwrap();
+    assert_eq!(results.len(), 3);
+}
+
+#[test]
+fn test_expand_episodes_follows_part_of() {
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    let engine = WriteEngine::new(DEFAULT_DIMENSION);
+    let mut facts = Vec::new();
+    for i in 0..3 {
+        let event = CognitiveEventBuilder::new(EventType::Fact, format!("step_{}", i))
+            .session_id(1)
+            .created_at(1_000 + i)
+            .feature_vec(zero_vec())
+            .build();
+        facts.push(graph.add_node(event).unwrap());
+    }
+    let unrelated = CognitiveEventBuilder::new(EventType::Fact, "other session")
+        .session_id(2)
+        .feature_vec(zero_vec())
+        .build();
+    let unrelated = graph.add_node(unrelated).unwrap();
+    let episode = engine
+        .compress_session(&mut graph, 1, "session 1 summary")
+        .unwrap();
+
+    let qe = QueryEngine::new();
+    // Non-episode hits are not expanded; hits are not repeated as members.
+    let members = qe.expand_episodes(&graph, &[unrelated, episode, facts[0]], 10);
+    let ids: Vec<u64> = members.iter().map(|m| m.node_id).collect();
+    assert_eq!(ids, [facts[1], facts[2]]);
+    assert!(members.iter().all(|m| m.episode_id == episode));
+
+    // Oldest members first, bounded per episode.
+    let members = qe.expand_episodes(&graph, &[episode], 2);
+    let ids: Vec<u64> = members.iter().map(|m| m.node_id).collect();
+    assert_eq!(ids, [facts[0], facts[1]]);
+}
+
 // ==================== Query Engine: Temporal Tests ====================
 
 #[test]
@@ -946,6 +1812,7 @@ fn test_temporal_added_nodes() {
             TemporalParams {
                 range_a: TimeRange::Session(1),
                 range_b: TimeRange::Session(2),
+                group_by_session: false,
             },
         )
         .unwrap();
@@ -986,6 +1853,7 @@ fn test_temporal_corrected_nodes() {
             TemporalParams {
                 range_a: TimeRange::Session(1),
                 range_b: TimeRange::Session(2),
+                group_by_session: false,
             },
         )
         .unwrap();
@@ -1000,6 +1868,234 @@ fn test_temporal_corrected_nodes() {
     );
 }
 
+#[test]
+fn test_temporal_group_by_session() {
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    let nodes = [
+        (1, EventType::Fact, 1_000),
+        (1, EventType::Fact, 2_000),
+        (1, EventType::Decision, 3_000),
+        (2, EventType::Decision, 4_000),
+        (2, EventType::Decision, 5_000),
+        (2, EventType::Fact, 6_000),
+        (3, EventType::Skill, 9_000),
+    ];
+    for (i, (session, event_type, ts)) in nodes.iter().enumerate() {
+        let event = CognitiveEventBuilder::new(*event_type, format!("grouped_{}", i))
+            .session_id(*session)
+            .created_at(*ts)
+            .feature_vec(zero_vec())
+            .build();
+        graph.add_node(event).unwrap();
+    }
+
+    let qe = QueryEngine::new();
+    let result = qe
+        .temporal(
+            &graph,
+            TemporalParams {
+                range_a: TimeRange::TimeWindow {
+                    start: 2_500,
+                    end: 4_500,
+                },
+                range_b: TimeRange::TimeWindow {
+                    start: 4_500,
+                    end: 6_000,
+                },
+                group_by_session: true,
+            },
+        )
+        .unwrap();
+
+    let groups = result.session_groups.expect("groups requested");
+    assert_eq!(groups.len(), 2, "session 3 has no node in range");
+
+    // Session 1 straddles the range start: included, but marked partial.
+    assert_eq!(groups[0].session_id, 1);
+    assert_eq!(groups[0].in_range, vec![2]);
+    assert_eq!(groups[0].node_count, 3);
+    assert!(groups[0].partial);
+    assert_eq!(groups[0].dominant_type, EventType::Fact);
+    assert_eq!((groups[0].started_at, groups[0].ended_at), (1_000, 3_000));
+
+    assert_eq!(groups[1].session_id, 2);
+    assert_eq!(groups[1].in_range, vec![3, 4, 5]);
+    assert!(!groups[1].partial);
+    assert_eq!(groups[1].dominant_type, EventType::Decision);
+
+    let plain = qe
+        .temporal(
+            &graph,
+            TemporalParams {
+                range_a: TimeRange::Session(1),
+                range_b: TimeRange::Session(2),
+                group_by_session: false,
+            },
+        )
+        .unwrap();
+    assert!(plain.session_groups.is_none());
+}
+
+#[test]
+fn test_temporal_clusters_by_window() {
+    const MINUTE: u64 = 60_000_000;
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    let nodes = [
+        (1, EventType::Fact, 10 * MINUTE, 1.0),
+        (1, EventType::Decision, 50 * MINUTE, 0.5),
+        (1, EventType::Fact, 55 * MINUTE, 0.6),
+        (2, EventType::Skill, 150 * MINUTE, 0.4),
+        (2, EventType::Decision, 125 * MINUTE, 0.8),
+    ];
+    for (i, (session, event_type, ts, confidence)) in nodes.iter().enumerate() {
+        let event = CognitiveEventBuilder::new(*event_type, format!("clustered_{}", i))
+            .session_id(*session)
+            .created_at(*ts)
+            .confidence(*confidence)
+            .feature_vec(zero_vec())
+            .build();
+        graph.add_node(event).unwrap();
+    }
+
+    let qe = QueryEngine::new();
+    let params = |minutes: u64| TemporalClusterParams {
+        window: std::time::Duration::from_secs(minutes * 60),
+        event_types: vec![],
+        session_ids: vec![],
+    };
+
+    let hourly = qe.temporal_clusters(&graph, params(60)).unwrap();
+    assert_eq!(hourly.len(), 2, "the empty second hour is skipped");
+    assert_eq!(
+        (hourly[0].start_micros, hourly[0].end_micros),
+        (0, 60 * MINUTE)
+    );
+    assert_eq!(hourly[0].node_ids, vec![0, 1, 2]);
+    assert_eq!(hourly[0].dominant_type, EventType::Fact);
+    assert!((hourly[0].mean_confidence - 0.7).abs() < 1e-6);
+    // Members are in creation order; the type tie goes to Decision.
+    assert_eq!(hourly[1].start_micros, 120 * MINUTE);
+    assert_eq!(hourly[1].node_ids, vec![4, 3]);
+    assert_eq!(hourly[1].dominant_type, EventType::Decision);
+    assert!((hourly[1].mean_confidence - 0.6).abs() < 1e-6);
+
+    let facts = qe
+        .temporal_clusters(
+            &graph,
+            TemporalClusterParams {
+                event_types: vec![EventType::Fact],
+                ..params(60)
+            },
+        )
+        .unwrap();
+    assert_eq!(facts.len(), 1);
+    assert_eq!(facts[0].node_ids, vec![0, 2]);
+
+    let session_2 = qe
+        .temporal_clusters(
+            &graph,
+            TemporalClusterParams {
+                session_ids: vec![2],
+                ..params(60)
+            },
+        )
+        .unwrap();
+    assert_eq!(session_2.len(), 1);
+    assert_eq!(session_2[0].node_ids, vec![4, 3]);
+
+    let wide = qe.temporal_clusters(&graph, params(180)).unwrap();
+    assert_eq!(wide.len(), 1);
+    assert_eq!(wide[0].node_ids.len(), 5);
+
+    let zero = qe.temporal_clusters(&graph, params(0)).unwrap();
+    assert_eq!(zero.len(), 5, "a zero window puts each timestamp alone");
+}
+
+#[test]
+fn test_session_activity_classification() {
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    let now = 10_000_000_000;
+    let nodes = [
+        // Session 1: last node an hour ago, never closed.
+        (1, EventType::Fact, now - 3_600_000_000),
+        // Session 2: still writing.
+        (2, EventType::Fact, now - 120_000_000),
+        (2, EventType::Decision, now - 60_000_000),
+        // Session 3: recent, but compressed into an episode (same timestamp).
+        (3, EventType::Fact, now - 30_000_000),
+        (3, EventType::Episode, now - 30_000_000),
+    ];
+    for (i, (session, event_type, ts)) in nodes.iter().enumerate() {
+        let event = CognitiveEventBuilder::new(*event_type, format!("activity_{}", i))
+            .session_id(*session)
+            .created_at(*ts)
+            .feature_vec(zero_vec())
+            .build();
+        graph.add_node(event).unwrap();
+    }
+
+    let activity = QueryEngine::new().session_activity(&graph, now, 600);
+    let summary: Vec<(u32, u64, bool, bool)> = activity
+        .iter()
+        .map(|a| (a.session_id, a.last_activity, a.closed, a.active))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (1, now - 3_600_000_000, false, false),
+            (2, now - 60_000_000, false, true),
+            (3, now - 30_000_000, true, false),
+        ]
+    );
+    assert_eq!(activity[1].node_count, 2);
+
+    // A window wide enough to reach session 1 marks it active too.
+    let wide = QueryEngine::new().session_activity(&graph, now, 7_200);
+    assert!(wide[0].active);
+    assert!(!wide[2].active, "a closing episode always means completed");
+}
+
+#[test]
+fn test_cross_session_view() {
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    let mut ids = Vec::new();
+    for (i, session) in [1, 1, 2, 3].iter().enumerate() {
+        let event = CognitiveEventBuilder::new(EventType::Fact, format!("cross_{}", i))
+            .session_id(*session)
+            .feature_vec(zero_vec())
+            .build();
+        ids.push(graph.add_node(event).unwrap());
+    }
+    // Same-session edge: dropped.
+    graph
+        .add_edge(Edge::new(ids[1], ids[0], EdgeType::Supports, 1.0))
+        .unwrap();
+    // Two edges from session 2 into session 1, one from session 3 into 2.
+    graph
+        .add_edge(Edge::new(ids[2], ids[0], EdgeType::CausedBy, 1.0))
+        .unwrap();
+    graph
+        .add_edge(Edge::new(ids[2], ids[1], EdgeType::Supports, 1.0))
+        .unwrap();
+    graph
+        .add_edge(Edge::new(ids[3], ids[2], EdgeType::CausedBy, 1.0))
+        .unwrap();
+
+    let view = QueryEngine::new().cross_session(&graph);
+    assert_eq!(view.edges.len(), 3);
+    assert!(view
+        .edges
+        .iter()
+        .all(|e| !(e.source_id == ids[1] && e.target_id == ids[0])));
+    let node_ids: Vec<u64> = view.nodes.iter().map(|n| n.id).collect();
+    assert_eq!(node_ids, ids);
+    assert_eq!(view.session_pairs, vec![((2, 1), 2), ((3, 2), 1)]);
+
+    let single = MemoryGraph::new(DEFAULT_DIMENSION);
+    let empty = QueryEngine::new().cross_session(&single);
+    assert!(empty.edges.is_empty() && empty.nodes.is_empty());
+}
+
 // ==================== Query Engine: Causal Tests ====================
 
 #[test]
@@ -1035,6 +2131,7 @@ fn test_causal_direct_dependents() {
                 node_id: id_a,
                 max_depth: 10,
                 dependency_types: vec![EdgeType::CausedBy],
+                direction: CausalDirection::Downstream,
             },
         )
         .unwrap();
@@ -1087,6 +2184,7 @@ fn test_causal_transitive_dependents() {
                 node_id: id_a,
                 max_depth: 10,
                 dependency_types: vec![EdgeType::CausedBy],
+                direction: CausalDirection::Downstream,
             },
         )
         .unwrap();
@@ -1166,6 +2264,7 @@ fn test_causal_counts_decisions_and_inferences() {
                 node_id: id_root,
                 max_depth: 10,
                 dependency_types: vec![EdgeType::CausedBy],
+                direction: CausalDirection::Downstream,
             },
         )
         .unwrap();
@@ -1175,6 +2274,83 @@ fn test_causal_counts_decisions_and_inferences() {
     assert_eq!(result.dependents.len(), 6);
 }
 
+#[test]
+fn test_causal_upstream_root_causes() {
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    let mut add = |event_type, content: &str| {
+        graph
+            .add_node(
+                CognitiveEventBuilder::new(event_type, content)
+                    .feature_vec(zero_vec())
+                    .build(),
+            )
+            .unwrap()
+    };
+    let evidence = add(EventType::Fact, "benchmarks show p99 of 40ms");
+    let observation = add(EventType::Fact, "users report slow pages");
+    let inference = add(EventType::Inference, "the cache is undersized");
+    let decision = add(EventType::Decision, "double the cache");
+
+    // decision <- inference <- {evidence, observation}, with a cycle
+    // between observation and inference.
+    for (source, target, edge_type) in [
+        (decision, inference, EdgeType::CausedBy),
+        (inference, evidence, EdgeType::Supports),
+        (inference, observation, EdgeType::CausedBy),
+        (observation, inference, EdgeType::CausedBy),
+    ] {
+        graph
+            .add_edge(Edge::new(source, target, edge_type, 1.0))
+            .unwrap();
+    }
+
+    let qe = QueryEngine::new();
+    let upstream = |max_depth| {
+        qe.causal(
+            &graph,
+            CausalParams {
+                node_id: decision,
+                max_depth,
+                dependency_types: vec![EdgeType::CausedBy, EdgeType::Supports],
+                direction: CausalDirection::Upstream,
+            },
+        )
+        .unwrap()
+    };
+
+    let result = upstream(10);
+    assert_eq!(result.direction, CausalDirection::Upstream);
+    assert_eq!(result.dependents, vec![inference, evidence, observation]);
+    assert_eq!(
+        result.dependency_tree[&decision],
+        vec![(inference, EdgeType::CausedBy)]
+    );
+    // The observation still points back into the cycle, so only the
+    // benchmark evidence is a root cause.
+    assert_eq!(result.leaves, vec![evidence]);
+    assert_eq!(result.affected_inferences, 1);
+    assert_eq!(result.affected_decisions, 0);
+
+    // Depth-capped nodes are not reported as root causes.
+    let result = upstream(1);
+    assert_eq!(result.dependents, vec![inference]);
+    assert!(result.leaves.is_empty());
+
+    // Downstream from the evidence reaches the decision.
+    let result = qe
+        .causal(
+            &graph,
+            CausalParams {
+                node_id: evidence,
+                max_depth: 10,
+                dependency_types: vec![EdgeType::CausedBy, EdgeType::Supports],
+                direction: CausalDirection::Downstream,
+            },
+        )
+        .unwrap();
+    assert!(result.dependents.contains(&decision));
+}
+
 // ==================== Query Engine: Similarity Tests ====================
 
 #[test]
@@ -1206,6 +2382,7 @@ fn test_similarity_basic() {
                 min_similarity: 0.0,
                 event_types: vec![],
                 skip_zero_vectors: false,
+                expand_via_edges: None,
             },
         )
         .unwrap();
@@ -1247,6 +2424,7 @@ fn test_similarity_respects_threshold() {
                 min_similarity: 0.9, // High threshold
                 event_types: vec![],
                 skip_zero_vectors: false,
+                expand_via_edges: None,
             },
         )
         .unwrap();
@@ -1284,6 +2462,7 @@ fn test_similarity_skips_zero_vectors() {
                 min_similarity: 0.0,
                 event_types: vec![],
                 skip_zero_vectors: true,
+                expand_via_edges: None,
             },
         )
         .unwrap();
@@ -1297,6 +2476,140 @@ fn test_similarity_skips_zero_vectors() {
     );
 }
 
+#[test]
+fn test_similarity_expands_via_edges() {
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    let add = |graph: &mut MemoryGraph, content: &str, fv: Vec<f32>| {
+        let event = CognitiveEventBuilder::new(EventType::Fact, content)
+            .session_id(1)
+            .feature_vec(fv)
+            .build();
+        graph.add_node(event).unwrap()
+    };
+    let seed = add(&mut graph, "seed", basis_vec(0, 1.0));
+    let support = add(&mut graph, "support", basis_vec(1, 1.0));
+    let far = add(&mut graph, "two hops out", basis_vec(2, 1.0));
+    let related = add(&mut graph, "related only", basis_vec(3, 1.0));
+    // A hub hanging off the seed: only the strongest edges are followed.
+    let hub: Vec<u64> = (0..5)
+        .map(|i| add(&mut graph, &format!("hub {i}"), basis_vec(10 + i, 1.0)))
+        .collect();
+
+    graph
+        .add_edge(Edge::new(support, seed, EdgeType::Supports, 0.8))
+        .unwrap();
+    graph
+        .add_edge(Edge::new(far, support, EdgeType::Supports, 1.0))
+        .unwrap();
+    graph
+        .add_edge(Edge::new(seed, related, EdgeType::RelatedTo, 1.0))
+        .unwrap();
+    for (i, &id) in hub.iter().enumerate() {
+        graph
+            .add_edge(Edge::new(
+                id,
+                seed,
+                EdgeType::Supports,
+                0.1 + 0.01 * i as f32,
+            ))
+            .unwrap();
+    }
+
+    let qe = QueryEngine::new();
+    let search = |hops: u32| {
+        qe.similarity(
+            &graph,
+            SimilarityParams {
+                query_vec: basis_vec(0, 1.0),
+                top_k: 1,
+                min_similarity: 0.5,
+                event_types: vec![],
+                skip_zero_vectors: true,
+                expand_via_edges: Some(EdgeExpansion {
+                    edge_types: vec![EdgeType::Supports],
+                    hops,
+                    decay: 0.5,
+                    max_neighbors: 2,
+                    max_added: 10,
+                }),
+            },
+        )
+        .unwrap()
+    };
+
+    let results = search(1);
+    let ids: Vec<u64> = results.iter().map(|m| m.node_id).collect();
+    assert_eq!(ids[0], seed);
+    assert!(results[0].expanded_from.is_none());
+    // Strongest two Supports neighbors only; RelatedTo is not followed.
+    assert_eq!(ids.len(), 3);
+    assert_eq!(ids[1], support);
+    assert!(ids.contains(&hub[4]));
+    assert!(!ids.contains(&related));
+    assert!(!ids.contains(&far));
+
+    let via = results[1].expanded_from.unwrap();
+    assert_eq!(via.seed_id, seed);
+    assert_eq!(via.edge_type, EdgeType::Supports);
+    assert_eq!(via.hops, 1);
+    assert!((results[1].similarity - 0.4).abs() < 1e-5); // 1.0 * 0.8 * 0.5
+
+    // A second hop reaches `far` through `support`, credited to the same seed.
+    let results = search(2);
+    let far_match = results.iter().find(|m| m.node_id == far).unwrap();
+    let via = far_match.expanded_from.unwrap();
+    assert_eq!((via.seed_id, via.hops), (seed, 2));
+    assert!((far_match.similarity - 0.2).abs() < 1e-5); // 0.4 * 1.0 * 0.5
+}
+
+#[test]
+fn test_similarity_expansion_respects_event_types() {
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    let mut add = |event_type: EventType, content: &str, fv: Vec<f32>| {
+        let event = CognitiveEventBuilder::new(event_type, content)
+            .session_id(1)
+            .feature_vec(fv)
+            .build();
+        graph.add_node(event).unwrap()
+    };
+    let seed = add(EventType::Fact, "seed", basis_vec(0, 1.0));
+    let fact = add(EventType::Fact, "supporting fact", basis_vec(1, 1.0));
+    let decision = add(
+        EventType::Decision,
+        "supporting decision",
+        basis_vec(2, 1.0),
+    );
+    for id in [fact, decision] {
+        graph
+            .add_edge(Edge::new(id, seed, EdgeType::Supports, 0.9))
+            .unwrap();
+    }
+
+    let results = QueryEngine::new()
+        .similarity(
+            &graph,
+            SimilarityParams {
+                query_vec: basis_vec(0, 1.0),
+                top_k: 1,
+                min_similarity: 0.5,
+                event_types: vec![EventType::Fact],
+                skip_zero_vectors: true,
+                expand_via_edges: Some(EdgeExpansion {
+                    edge_types: vec![EdgeType::Supports],
+                    hops: 1,
+                    decay: 0.5,
+                    max_neighbors: 4,
+                    max_added: 10,
+                }),
+            },
+        )
+        .unwrap();
+    let ids: Vec<u64> = results.iter().map(|m| m.node_id).collect();
+    // Expanded neighbors obey the same event type filter as the seeds.
+    assert_eq!(ids, vec![seed, fact]);
+    assert!(!ids.contains(&decision));
+}
+
 // ==================== Query Engine: Context Tests ====================
 
 #[test]
@@ -1428,3 +2741,158 @@ fn test_resolve_chain() {
     let resolved_b = qe.resolve(&graph, id_b).unwrap();
     assert_eq!(resolved_b.id, id_c);
 }
+
+#[test]
+fn test_resolve_reports_supersedes_cycle() {
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    let engine = WriteEngine::new(DEFAULT_DIMENSION);
+
+    let a = CognitiveEventBuilder::new(EventType::Fact, "version 1")
+        .session_id(1)
+        .feature_vec(zero_vec())
+        .build();
+    let id_a = engine
+        .ingest(&mut graph, vec![a], vec![])
+        .unwrap()
+        .new_node_ids[0];
+    let id_b = engine.correct(&mut graph, id_a, "version 2", 2).unwrap();
+    let id_c = engine.correct(&mut graph, id_b, "version 3", 3).unwrap();
+    // A buggy correction flow makes A supersede C: A -> C -> B -> A.
+    graph
+        .add_edge(Edge::new(id_a, id_c, EdgeType::Supersedes, 1.0))
+        .unwrap();
+
+    let qe = QueryEngine::new();
+    match qe.resolve(&graph, id_a) {
+        Err(AmemError::SupersedesCycle { nodes }) => assert_eq!(nodes, vec![id_a, id_b, id_c]),
+        Err(e) => panic!("Expected SupersedesCycle, got {:?}", e),
+        Ok(node) => panic!("Cycle resolved silently to node {}", node.id),
+    }
+    assert_eq!(graph.supersedes_cycles(), vec![vec![id_a, id_c, id_b]]);
+
+    // Repair drops the edge that closed the cycle, B -> A.
+    assert_eq!(graph.break_supersedes_cycles().unwrap(), vec![(id_b, id_a)]);
+    assert!(graph.supersedes_cycles().is_empty());
+    assert!(graph.check_integrity().is_empty());
+    assert_eq!(qe.resolve(&graph, id_b).unwrap().id, id_a);
+}
+
+#[test]
+fn test_merge_graphs_strategies_remap_edges_and_sessions() {
+    let build = |session: u32, contents: &[&str]| {
+        let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+        for content in contents {
+            let event = CognitiveEventBuilder::new(EventType::Fact, *content)
+                .session_id(session)
+                .build();
+            graph.add_node(event).unwrap();
+        }
+        graph
+            .add_edge(Edge::new(1, 0, EdgeType::CausedBy, 0.8))
+            .unwrap();
+        graph
+    };
+    let a = build(3, &["shared", "only in a"]);
+    let b = build(1, &["only in b", "shared"]);
+    let engine = WriteEngine::new(DEFAULT_DIMENSION);
+
+    let (union, report) = engine
+        .merge_graphs_with_report(&a, &b, MergeStrategy::Union)
+        .unwrap();
+    assert_eq!(union.node_count(), 4);
+    assert_eq!(report.duplicates_removed, 0);
+    assert_eq!(report.session_offset, 4);
+    assert_eq!(union.get_node(2).unwrap().session_id, 5);
+    // b's edge 1 -> 0 now runs between b's nodes at their merged IDs.
+    assert!(union
+        .edges_from(3)
+        .iter()
+        .any(|e| e.target_id == 2 && e.edge_type == EdgeType::CausedBy));
+
+    let (dedup, report) = engine
+        .merge_graphs_with_report(&a, &b, MergeStrategy::Deduplicate)
+        .unwrap();
+    assert_eq!(dedup.node_count(), 3);
+    assert_eq!(report.duplicates_removed, 1);
+    // b's "shared" folded into a's node 0, so its edge points there.
+    assert!(dedup.edges_from(0).iter().any(|e| e.target_id == 2));
+    assert_eq!(report.edges_merged, 2);
+    for edge in dedup.edges() {
+        assert!(dedup.get_node(edge.source_id).is_some());
+        assert!(dedup.get_node(edge.target_id).is_some());
+    }
+
+    let intersect = engine
+        .merge_graphs(&a, &b, MergeStrategy::Intersect)
+        .unwrap();
+    assert_eq!(intersect.node_count(), 1);
+    assert_eq!(intersect.get_node(0).unwrap().content, "shared");
+    assert_eq!(intersect.edge_count(), 0);
+
+    let other = MemoryGraph::new(64);
+    assert!(matches!(
+        engine.merge_graphs(&a, &other, MergeStrategy::Union),
+        Err(AmemError::DimensionMismatch { .. })
+    ));
+}
+
+#[test]
+fn test_diff_graphs_and_patch_round_trip() {
+    let mut a = MemoryGraph::new(DEFAULT_DIMENSION);
+    for content in ["kept", "dropped", "untouched"] {
+        let event = CognitiveEventBuilder::new(EventType::Fact, content)
+            .created_at(1_000)
+            .build();
+        a.add_node(event).unwrap();
+    }
+
+    let mut b = a.clone();
+    b.remove_node(1).unwrap();
+    let node = b.get_node_mut(0).unwrap();
+    node.content = "kept, revised".to_string();
+    node.confidence = 0.5;
+    let added = CognitiveEventBuilder::new(EventType::Decision, "new")
+        .feature_vec(basis_vec(3, 1.0))
+        .build();
+    let new_id = b.add_node(added).unwrap();
+    b.add_edge(Edge::new(new_id, 0, EdgeType::Supports, 0.7))
+        .unwrap();
+
+    let diff = diff_graphs(&a, &b);
+    assert_eq!(
+        diff.only_in_a.iter().map(|n| n.id).collect::<Vec<_>>(),
+        vec![1]
+    );
+    assert_eq!(
+        diff.only_in_b.iter().map(|n| n.id).collect::<Vec<_>>(),
+        vec![new_id]
+    );
+    let fields: Vec<(u64, DiffField)> = diff.modified.iter().map(|c| (c.id, c.field)).collect();
+    assert_eq!(
+        fields,
+        vec![(0, DiffField::Content), (0, DiffField::Confidence)]
+    );
+    assert_eq!(
+        diff.modified[0].after,
+        FieldValue::Text("kept, revised".to_string())
+    );
+
+    // A patch survives JSON and brings a copy of `a` in line with `b`.
+    let json = serde_json::to_string(&GraphPatch::from_diff(&diff, &b)).unwrap();
+    let patch: GraphPatch = serde_json::from_str(&json).unwrap();
+    let mut patched = a.clone();
+    patch.apply(&mut patched).unwrap();
+    assert!(diff_graphs(&patched, &b).is_empty());
+    assert_eq!(patched.get_node(new_id).unwrap().feature_vec[3], 1.0);
+    assert!(patched
+        .edges_from(new_id)
+        .iter()
+        .any(|e| e.target_id == 0 && e.edge_type == EdgeType::Supports));
+
+    // Applying it again conflicts and leaves the graph as it was.
+    assert!(matches!(
+        patch.apply(&mut patched),
+        Err(AmemError::PatchConflict(_))
+    ));
+    assert!(diff_graphs(&patched, &b).is_empty());
+}
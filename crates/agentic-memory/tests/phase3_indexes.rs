@@ -5,14 +5,20 @@ use std::time::Instant;
 use rand::Rng;
 use tempfile::NamedTempFile;
 
-use agentic_memory::format::{AmemWriter, MmapReader};
-use agentic_memory::graph::MemoryGraph;
-use agentic_memory::index::{ClusterMap, TemporalIndex, TypeIndex};
+use agentic_memory::engine::{detect_language, TextSearchParams, Tokenizer};
+use agentic_memory::format::{migration, AmemReader, AmemWriter, MmapReader};
+use agentic_memory::graph::{EmbeddingModelCheck, MemoryGraph};
+use agentic_memory::index::{
+    cosine_similarity, cosine_similarity_scalar, ClusterMap, DocLengths, TagIndex, TemporalIndex,
+    TermIndex, TypeIndex,
+};
 use agentic_memory::types::{
-    CognitiveEvent, CognitiveEventBuilder, Edge, EdgeType, EventType, DEFAULT_DIMENSION,
+    AmemError, CognitiveEvent, CognitiveEventBuilder, Edge, EdgeType, EventType, Language,
+    ACCESS_LOG_CAPACITY, DEFAULT_DIMENSION, FORMAT_VERSION,
 };
 use agentic_memory::{
     PatternParams, PatternSort, QueryEngine, SimilarityParams, TraversalDirection, TraversalParams,
+    WriteEngine,
 };
 
 // ==================== Helpers ====================
@@ -179,6 +185,89 @@ fn test_type_index_incremental() {
     assert_eq!(decision_ids[0], 10);
 }
 
+#[test]
+fn test_tag_index_any_all_and_removal() {
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    for (project, role) in [("foo", "planner"), ("foo", "reviewer"), ("bar", "planner")] {
+        let event = CognitiveEventBuilder::new(EventType::Fact, "node")
+            .tag("project", project)
+            .tag("role", role)
+            .build();
+        graph.add_node(event).unwrap();
+    }
+    let tag = |k: &str, v: &str| (k.to_string(), v.to_string());
+
+    let index = graph.tag_index();
+    assert_eq!(index.get("project", "foo"), &[0, 1]);
+    assert_eq!(index.count("role", "planner"), 2);
+    assert_eq!(index.len(), 4);
+    assert_eq!(
+        index.get_all(&[tag("project", "foo"), tag("role", "planner")]),
+        vec![0]
+    );
+    assert_eq!(
+        index.get_any(&[tag("project", "bar"), tag("role", "reviewer")]),
+        vec![1, 2]
+    );
+    assert!(index.get_all(&[]).is_empty());
+
+    graph.remove_node(0).unwrap();
+    assert_eq!(graph.tag_index().get("project", "foo"), &[1]);
+
+    // A rebuilt index matches the incrementally maintained one.
+    let mut rebuilt = TagIndex::new();
+    rebuilt.rebuild(graph.nodes());
+    assert_eq!(rebuilt.get("role", "planner"), &[2]);
+    assert_eq!(rebuilt.len(), graph.tag_index().len());
+}
+
+#[test]
+fn test_type_index_tracks_removal_and_retyping() {
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    for (i, event_type) in [
+        EventType::Fact,
+        EventType::Inference,
+        EventType::Fact,
+        EventType::Inference,
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        graph
+            .add_node(CognitiveEventBuilder::new(event_type, format!("node {i}")).build())
+            .unwrap();
+    }
+
+    graph.remove_node(0).unwrap();
+    graph.set_event_type(1, EventType::Fact).unwrap();
+
+    let index = graph.type_index();
+    assert_eq!(index.ids(EventType::Fact), &[1, 2]);
+    assert_eq!(index.ids(EventType::Inference), &[3]);
+    let listed: Vec<(EventType, Vec<u64>)> =
+        index.iter().map(|(t, ids)| (t, ids.to_vec())).collect();
+    assert_eq!(
+        listed,
+        vec![
+            (EventType::Fact, vec![1, 2]),
+            (EventType::Inference, vec![3])
+        ]
+    );
+
+    let facts: Vec<u64> = graph
+        .nodes_of_types(&[EventType::Fact])
+        .iter()
+        .map(|n| n.id)
+        .collect();
+    assert_eq!(facts, vec![1, 2]);
+    assert!(graph
+        .nodes_of_types(&[EventType::Fact])
+        .iter()
+        .all(|n| n.event_type == EventType::Fact));
+    assert_eq!(graph.nodes_of_types(&[]).len(), 3);
+    assert!(graph.set_event_type(0, EventType::Fact).is_err());
+}
+
 #[test]
 fn test_temporal_index_range() {
     let mut index = TemporalIndex::new();
@@ -307,6 +396,309 @@ fn test_session_index_session_count() {
     assert_eq!(si.node_count(99), 0); // Non-existent session
 }
 
+#[test]
+fn test_embedding_model_recorded_and_checked() {
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    assert_eq!(graph.embedding_model(), None);
+
+    // The first declaration is recorded; later ones are compared against it.
+    assert_eq!(
+        graph.declare_embedding_model("text-embed-a"),
+        EmbeddingModelCheck::Recorded
+    );
+    assert_eq!(
+        graph.check_embedding_model("text-embed-a", true).unwrap(),
+        None
+    );
+    assert_eq!(
+        graph.check_embedding_model("text-embed-b", false).unwrap(),
+        Some("text-embed-a".to_string())
+    );
+    assert!(matches!(
+        graph.check_embedding_model("text-embed-b", true),
+        Err(AmemError::EmbeddingModelMismatch { .. })
+    ));
+    assert_eq!(graph.embedding_model(), Some("text-embed-a"));
+
+    let mut buf = Vec::new();
+    AmemWriter::new(DEFAULT_DIMENSION)
+        .write_to(&graph, &mut buf)
+        .unwrap();
+    let loaded = AmemReader::read_from(&mut std::io::Cursor::new(buf)).unwrap();
+    assert_eq!(loaded.embedding_model(), Some("text-embed-a"));
+
+    // Files written without a model read back as None.
+    let mut buf = Vec::new();
+    AmemWriter::new(DEFAULT_DIMENSION)
+        .write_to(&MemoryGraph::new(DEFAULT_DIMENSION), &mut buf)
+        .unwrap();
+    let loaded = AmemReader::read_from(&mut std::io::Cursor::new(buf)).unwrap();
+    assert_eq!(loaded.embedding_model(), None);
+}
+
+#[test]
+fn test_tombstones_round_trip_apply_and_prune() {
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    for content in ["stays", "goes"] {
+        let event = CognitiveEventBuilder::new(EventType::Fact, content)
+            .session_id(3)
+            .build();
+        graph.add_node(event).unwrap();
+    }
+    let removed = graph.remove_node(1).unwrap();
+    assert_eq!(graph.tombstones().len(), 1);
+    assert!(graph.is_tombstoned(&removed));
+
+    let mut buf = Vec::new();
+    AmemWriter::new(DEFAULT_DIMENSION)
+        .write_to(&graph, &mut buf)
+        .unwrap();
+    let loaded = AmemReader::read_from(&mut std::io::Cursor::new(buf)).unwrap();
+    assert_eq!(loaded.tombstones(), graph.tombstones());
+
+    // Applying the tombstone to a graph that still holds the node removes it,
+    // whatever ID it has there.
+    let mut stale = MemoryGraph::new(DEFAULT_DIMENSION);
+    let mut copy = removed.clone();
+    copy.id = 0;
+    stale.add_node(copy).unwrap();
+    assert_eq!(stale.apply_tombstones(graph.tombstones()), 1);
+    assert_eq!(stale.node_count(), 0);
+    assert_eq!(stale.tombstones().len(), 1);
+
+    let deleted_at = graph.tombstones()[0].deleted_at;
+    assert_eq!(graph.prune_tombstones(deleted_at), 0);
+    assert_eq!(graph.prune_tombstones(deleted_at + 1), 1);
+    assert!(graph.tombstones().is_empty());
+}
+
+#[test]
+fn test_access_log_bounded_and_round_trips() {
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    for content in ["busy", "idle"] {
+        graph
+            .add_node(CognitiveEventBuilder::new(EventType::Fact, content).build())
+            .unwrap();
+    }
+    for at in 1..=40u64 {
+        graph.record_access(0, at).unwrap();
+    }
+    assert!(graph.record_access(9, 1).is_err());
+
+    let log = graph.access_log(0);
+    assert_eq!(log.len(), ACCESS_LOG_CAPACITY);
+    assert_eq!(log.first(), Some(&9));
+    assert_eq!(log.last(), Some(&40));
+    assert_eq!(graph.get_node(0).unwrap().access_count, 40);
+    assert_eq!(graph.get_node(0).unwrap().last_accessed, 40);
+    assert!(graph.access_log(1).is_empty());
+
+    let mut buf = Vec::new();
+    AmemWriter::new(DEFAULT_DIMENSION)
+        .write_to(&graph, &mut buf)
+        .unwrap();
+    let loaded = AmemReader::read_from(&mut std::io::Cursor::new(buf)).unwrap();
+    assert_eq!(loaded.access_log(0), log);
+    assert!(loaded.access_log(1).is_empty());
+
+    // Files without the section load with empty logs.
+    let mut buf = Vec::new();
+    AmemWriter::new(DEFAULT_DIMENSION)
+        .write_to(&MemoryGraph::new(DEFAULT_DIMENSION), &mut buf)
+        .unwrap();
+    let loaded = AmemReader::read_from(&mut std::io::Cursor::new(buf)).unwrap();
+    assert!(loaded.access_logs().is_empty());
+
+    graph.remove_node(0).unwrap();
+    assert!(graph.access_log(0).is_empty());
+}
+
+#[test]
+fn test_detect_language_and_tokenize_as() {
+    assert_eq!(
+        detect_language("Die Datenbank ist nicht erreichbar und wird neu gestartet"),
+        Some(Language::German)
+    );
+    assert_eq!(
+        detect_language("The database is down and the server is restarting"),
+        Some(Language::English)
+    );
+    assert_eq!(detect_language("数据库迁移失败"), Some(Language::Chinese));
+    assert_eq!(
+        detect_language("データベースの移行"),
+        Some(Language::Japanese)
+    );
+    assert_eq!(
+        detect_language("База данных недоступна"),
+        Some(Language::Russian)
+    );
+    assert_eq!(detect_language("kubectl rollout"), None);
+    assert_eq!(detect_language("12345"), None);
+
+    let tokenizer = Tokenizer::new();
+    assert_eq!(
+        tokenizer.tokenize_as("Die Datenbank ist nicht erreichbar", Some(Language::German)),
+        vec!["datenbank", "erreichbar"]
+    );
+    assert_eq!(
+        tokenizer.tokenize_as("数据库迁移", Some(Language::Chinese)),
+        vec!["数据", "据库", "库迁", "迁移"]
+    );
+    // Untagged text keeps the default rules.
+    assert_eq!(
+        tokenizer.tokenize_as("The database migration", None),
+        tokenizer.tokenize("The database migration")
+    );
+}
+
+#[test]
+fn test_node_languages_round_trip_and_search() {
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    let engine = WriteEngine::new(DEFAULT_DIMENSION).with_language_detection(true);
+    let events = [
+        "数据库迁移失败",
+        "Die Migration ist fehlgeschlagen",
+        "kubectl rollout",
+    ]
+    .into_iter()
+    .map(|content| CognitiveEventBuilder::new(EventType::Fact, content).build())
+    .collect();
+    engine.ingest(&mut graph, events, vec![]).unwrap();
+
+    assert_eq!(graph.node_language(0), Some(Language::Chinese));
+    assert_eq!(graph.node_language(1), Some(Language::German));
+    assert_eq!(graph.node_language(2), None);
+
+    let mut buf = Vec::new();
+    AmemWriter::new(DEFAULT_DIMENSION)
+        .write_to(&graph, &mut buf)
+        .unwrap();
+    let loaded = AmemReader::read_from(&mut std::io::Cursor::new(buf)).unwrap();
+    assert_eq!(loaded.node_languages(), graph.node_languages());
+    let tokenizer = Tokenizer::new();
+
+    // A Chinese query matches the segmented node on both search paths.
+    let term_index = TermIndex::build(&loaded, &tokenizer);
+    let doc_lengths = DocLengths::build(&loaded, &tokenizer);
+    let params = || TextSearchParams {
+        query: "数据库迁移".to_string(),
+        max_results: 10,
+        event_types: vec![],
+        session_ids: vec![],
+        min_score: 0.0,
+        confidence_weight: 0.0,
+    };
+    let query = QueryEngine::new();
+    let fast = query
+        .text_search(&loaded, Some(&term_index), Some(&doc_lengths), params())
+        .unwrap();
+    let slow = query.text_search(&loaded, None, None, params()).unwrap();
+    assert_eq!(fast.first().map(|m| m.node_id), Some(0));
+    assert_eq!(slow.first().map(|m| m.node_id), Some(0));
+
+    // Files without the section load untagged.
+    let mut buf = Vec::new();
+    AmemWriter::new(DEFAULT_DIMENSION)
+        .write_to(&MemoryGraph::new(DEFAULT_DIMENSION), &mut buf)
+        .unwrap();
+    let loaded = AmemReader::read_from(&mut std::io::Cursor::new(buf)).unwrap();
+    assert!(!loaded.has_node_languages());
+
+    graph.remove_node(0).unwrap();
+    assert_eq!(graph.node_language(0), None);
+}
+
+#[test]
+fn test_reader_rejects_inconsistent_dimension() {
+    let mut graph = MemoryGraph::new(4);
+    for (i, content) in ["alpha", "beta", "gamma"].into_iter().enumerate() {
+        let event = CognitiveEventBuilder::new(EventType::Fact, content)
+            .feature_vec(vec![1.0, i as f32, 0.5, -1.0])
+            .build();
+        graph.add_node(event).unwrap();
+    }
+    let mut buf = Vec::new();
+    AmemWriter::new(4).write_to(&graph, &mut buf).unwrap();
+
+    // Header dimension lives at offset 8; vectors stay 4 wide.
+    for declared in [0u32, 2, 8] {
+        let mut bad = buf.clone();
+        bad[8..12].copy_from_slice(&declared.to_le_bytes());
+        match AmemReader::read_from(&mut std::io::Cursor::new(&bad)) {
+            Err(AmemError::DimensionMismatch { expected, got }) => {
+                assert_eq!(expected, declared as usize);
+                assert_eq!(got, 4);
+            }
+            other => panic!("expected dimension mismatch, got {:?}", other.map(|_| ())),
+        }
+
+        let tmp = NamedTempFile::new().unwrap();
+        std::fs::write(tmp.path(), &bad).unwrap();
+        assert!(matches!(
+            MmapReader::open(tmp.path()),
+            Err(AmemError::DimensionMismatch { .. })
+        ));
+
+        // A resized read recovers the stored vectors.
+        let (repaired, stored) =
+            AmemReader::read_resized(&mut std::io::Cursor::new(&bad), 6).unwrap();
+        assert_eq!(stored, 4);
+        assert_eq!(repaired.dimension(), 6);
+        assert_eq!(
+            repaired.get_node(1).unwrap().feature_vec,
+            vec![1.0, 1.0, 0.5, -1.0, 0.0, 0.0]
+        );
+        assert_eq!(repaired.get_node(2).unwrap().content, "gamma");
+    }
+
+    // A consistent file reads as before.
+    let loaded = AmemReader::read_from(&mut std::io::Cursor::new(&buf)).unwrap();
+    assert_eq!(loaded.dimension(), 4);
+}
+
+#[test]
+fn test_session_alias_unique_and_round_trips() {
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    for session in [7u32, 1042] {
+        let event = CognitiveEventBuilder::new(EventType::Fact, format!("in {}", session))
+            .session_id(session)
+            .build();
+        graph.add_node(event).unwrap();
+    }
+
+    graph.rename_session(7, "project-alpha").unwrap();
+    assert!(matches!(
+        graph.rename_session(1042, "project-alpha"),
+        Err(AmemError::SessionAliasTaken { session_id: 7, .. })
+    ));
+    assert!(matches!(
+        graph.rename_session(1042, "12"),
+        Err(AmemError::InvalidSessionAlias(_))
+    ));
+    graph.rename_session(1042, "project-beta").unwrap();
+    // Renaming replaces the session's previous alias.
+    graph.rename_session(1042, "project-gamma").unwrap();
+    assert_eq!(graph.session_index().resolve_alias("project-beta"), None);
+
+    assert_eq!(graph.resolve_session("project-alpha").unwrap(), 7);
+    assert_eq!(graph.resolve_session("1042").unwrap(), 1042);
+    assert!(matches!(
+        graph.resolve_session("nope"),
+        Err(AmemError::SessionAliasNotFound(_))
+    ));
+
+    let mut buf = Vec::new();
+    AmemWriter::new(DEFAULT_DIMENSION)
+        .write_to(&graph, &mut buf)
+        .unwrap();
+    let loaded = AmemReader::read_from(&mut std::io::Cursor::new(buf)).unwrap();
+    assert_eq!(
+        loaded.session_index().aliases(),
+        vec![("project-alpha", 7), ("project-gamma", 1042)]
+    );
+    assert_eq!(loaded.session_index().alias_of(1042), Some("project-gamma"));
+}
+
 #[test]
 fn test_cluster_map_build() {
     let mut rng = rand::thread_rng();
@@ -476,6 +868,135 @@ fn test_cluster_map_nearest() {
     );
 }
 
+/// Three groups of `per_group` vectors, each group pointing mostly along
+/// its own block of four axes, with a fixed small offset per vector.
+fn kmeans_groups(per_group: u64) -> Vec<(u64, Vec<f32>)> {
+    (0..3 * per_group)
+        .map(|id| {
+            let (group, i) = (id / per_group, id % per_group);
+            let vec = (0..12u64)
+                .map(|j| {
+                    let base = if j / 4 == group { 1.0 } else { 0.0 };
+                    base + 0.05 * ((i * 7 + j * 3) % 5) as f32
+                })
+                .collect();
+            (id, vec)
+        })
+        .collect()
+}
+
+#[test]
+fn test_cluster_map_kmeans_is_stable() {
+    // 30 vectors fit in one batch; 300 exercise the sampled mini-batches.
+    for per_group in [10, 100] {
+        let nodes = kmeans_groups(per_group);
+        let mut node_refs: Vec<(u64, &[f32])> =
+            nodes.iter().map(|(id, v)| (*id, v.as_slice())).collect();
+        node_refs.push((999, &[0.0; 12]));
+
+        let mut first = ClusterMap::new(12);
+        let a = first.cluster(&node_refs, 3, 10, 42);
+        let mut second = ClusterMap::new(12);
+        let b = second.cluster(&node_refs, 3, 10, 42);
+        assert_eq!(a, b, "same seed must give the same assignments");
+        assert_eq!(first.cluster_centroids(), second.cluster_centroids());
+
+        // The zero vector is skipped; everything else is assigned in order.
+        assert_eq!(a.len(), nodes.len());
+        assert!(a.iter().map(|c| c.node_id).eq(0..3 * per_group));
+        assert_eq!(first.cluster_count(), 3);
+        assert_eq!(first.cluster_centroids().len(), 3);
+        assert!(a
+            .iter()
+            .all(|c| c.cluster_id < 3 && (0.0..=2.0).contains(&c.distance_to_centroid)));
+
+        // Each group lands in a cluster of its own.
+        for group in 0..3 {
+            let members = &a[(group * per_group) as usize..((group + 1) * per_group) as usize];
+            let cluster_id = members[0].cluster_id;
+            assert!(members.iter().all(|c| c.cluster_id == cluster_id));
+            assert_eq!(first.get_cluster(cluster_id).len() as u64, per_group);
+        }
+    }
+
+    // k is capped by the number of vectors, and nothing to cluster is empty.
+    let nodes = kmeans_groups(1);
+    let node_refs: Vec<(u64, &[f32])> = nodes.iter().map(|(id, v)| (*id, v.as_slice())).collect();
+    let mut map = ClusterMap::new(12);
+    assert_eq!(map.cluster(&node_refs, 10, 5, 1).len(), 3);
+    assert_eq!(map.cluster_count(), 3);
+    assert!(map.cluster(&[], 3, 5, 1).is_empty());
+    assert!(map.is_empty());
+}
+
+#[test]
+fn test_cosine_similarity_matches_scalar_across_dimensions() {
+    let mut rng = rand::thread_rng();
+    // Multiples of the 4- and 8-lane widths, and lengths with every tail size.
+    for dim in [
+        1, 2, 3, 4, 5, 7, 8, 9, 15, 16, 17, 31, 33, 63, 128, 129, 383, 384, 385,
+    ] {
+        for _ in 0..8 {
+            let a: Vec<f32> = (0..dim).map(|_| rng.gen_range(-1.0..1.0)).collect();
+            let b: Vec<f32> = (0..dim).map(|_| rng.gen_range(-1.0..1.0)).collect();
+            let fast = cosine_similarity(&a, &b);
+            let exact = cosine_similarity_scalar(&a, &b);
+            assert!(
+                (fast - exact).abs() < 1e-5,
+                "dim {dim}: {fast} vs scalar {exact}"
+            );
+        }
+        // The tail must not read past the shorter vector.
+        let long: Vec<f32> = (0..dim + 5).map(|i| i as f32 + 1.0).collect();
+        let short = &long[..dim];
+        assert!((cosine_similarity(short, &long) - 1.0).abs() < 1e-5);
+    }
+    assert_eq!(cosine_similarity(&[0.0; 9], &[1.0; 9]), 0.0);
+    assert_eq!(cosine_similarity(&[], &[]), 0.0);
+}
+
+#[test]
+fn test_migration_dry_run_then_migrate_legacy_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("legacy.amem");
+    let graph = make_test_graph(5, 1);
+    AmemWriter::new(DEFAULT_DIMENSION)
+        .write_to_file(&graph, &path)
+        .unwrap();
+    // Stamp the header as a pre-v1 file.
+    let mut bytes = std::fs::read(&path).unwrap();
+    bytes[4..8].copy_from_slice(&0u32.to_le_bytes());
+    std::fs::write(&path, &bytes).unwrap();
+    let checkpoints = dir.path().join(migration::MIGRATION_DIR);
+
+    let plan = migration::plan(&path).unwrap();
+    assert!(plan.needed());
+    assert_eq!((plan.from_version, plan.to_version), (0, FORMAT_VERSION));
+    assert_eq!(plan.node_count, graph.node_count());
+    assert!(plan.checkpoint_path.is_some());
+    assert!(!checkpoints.exists(), "dry run must not checkpoint");
+    assert_eq!(
+        std::fs::read(&path).unwrap(),
+        bytes,
+        "dry run must not write"
+    );
+
+    let done = migration::migrate(&path).unwrap();
+    let checkpoint = done.checkpoint_path.unwrap();
+    assert_eq!(std::fs::read(&checkpoint).unwrap(), bytes);
+    assert_eq!(migration::storage_version(&path).unwrap(), FORMAT_VERSION);
+    assert_eq!(
+        AmemReader::read_from_file(&path).unwrap().node_count(),
+        graph.node_count()
+    );
+
+    // Already current: nothing to do, no second checkpoint.
+    let again = migration::migrate(&path).unwrap();
+    assert!(!again.needed());
+    assert!(again.checkpoint_path.is_none());
+    assert_eq!(std::fs::read_dir(&checkpoints).unwrap().count(), 1);
+}
+
 // ==================== Mmap Tests ====================
 
 /// Helper: build a small graph with known data for mmap tests.
@@ -540,7 +1061,7 @@ fn test_mmap_reader_open() {
     assert_eq!(header.node_count, 10);
     assert_eq!(header.edge_count, 8);
     assert_eq!(header.dimension, DEFAULT_DIMENSION as u32);
-    assert_eq!(header.version, 1);
+    assert_eq!(header.version, FORMAT_VERSION);
     assert_eq!(header.magic, [0x41, 0x4D, 0x45, 0x4D]); // "AMEM"
 }
 
@@ -797,6 +1318,7 @@ fn test_large_graph_traversal_speed() {
         max_depth: 3,
         max_results: 1000,
         min_confidence: 0.0,
+        symmetric_undirected: false,
     };
 
     let engine = QueryEngine::new();
@@ -831,6 +1353,8 @@ fn test_large_graph_pattern_query_speed() {
         created_after: None,
         created_before: None,
         min_decay_score: None,
+        required_tags: Default::default(),
+        any_tags: Default::default(),
         max_results: 100,
         sort_by: PatternSort::HighestConfidence,
     };
@@ -874,6 +1398,7 @@ fn test_large_graph_similarity_speed() {
         min_similarity: 0.0,
         event_types: vec![],
         skip_zero_vectors: true,
+        expand_via_edges: None,
     };
 
     let engine = QueryEngine::new();
@@ -1,18 +1,20 @@
 //! Phase 4 tests: CLI integration and end-to-end flows.
 
+use std::io::Write;
 use std::path::PathBuf;
-use std::process::{Command, Output};
+use std::process::{Command, Output, Stdio};
 
 use tempfile::NamedTempFile;
 
 use agentic_memory::engine::{
-    CausalParams, PatternParams, PatternSort, QueryEngine, SimilarityParams, TraversalParams,
-    WriteEngine,
+    CausalDirection, CausalParams, PatternParams, PatternSort, QueryEngine, SimilarityParams,
+    TraversalParams, WriteEngine,
 };
-use agentic_memory::format::{AmemReader, AmemWriter};
+use agentic_memory::format::{AmemReader, AmemWriter, GraphDelta};
 use agentic_memory::graph::{MemoryGraph, TraversalDirection};
 use agentic_memory::types::{
-    now_micros, CognitiveEventBuilder, Edge, EdgeType, EventType, DEFAULT_DIMENSION,
+    now_micros, Clock, CognitiveEventBuilder, Edge, EdgeType, EventType, MockClock,
+    DEFAULT_DIMENSION, FORMAT_VERSION,
 };
 
 // ==================== CLI Helpers ====================
@@ -331,33 +333,1955 @@ fn test_cli_export_import() {
     // We verify that at least the 2 nodes were imported.
 }
 
+#[test]
+fn test_cli_add_tags() {
+    let dir = tempfile::tempdir().unwrap();
+    let brain = dir.path().join("tags.amem");
+    let path = brain.to_str().unwrap();
+    assert_success(&run_amem(&["create", path]));
+
+    let output = run_amem(&[
+        "--format",
+        "json",
+        "add",
+        path,
+        "fact",
+        "tagged fact",
+        "--tag",
+        "project=foo",
+        "--tag",
+        "source=https://example.com/?a=b",
+    ]);
+    assert_success(&output);
+    let json: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    assert_eq!(json["tags"]["project"], "foo");
+    assert_eq!(json["tags"]["source"], "https://example.com/?a=b");
+    assert_success(&run_amem(&["add", path, "fact", "plain fact"]));
+
+    let output = run_amem(&["export", path]);
+    assert_success(&output);
+    let export = stdout_str(&output);
+    let json: serde_json::Value = serde_json::from_str(&export).unwrap();
+    assert_eq!(json["nodes"][0]["tags"]["project"], "foo");
+    assert_eq!(json["nodes"][1]["tags"], serde_json::json!({}));
+
+    // Tags survive an export/import round trip.
+    let export_path = dir.path().join("export.json");
+    std::fs::write(&export_path, export).unwrap();
+    let copy = dir.path().join("copy.amem");
+    assert_success(&run_amem(&["create", copy.to_str().unwrap()]));
+    assert_success(&run_amem(&[
+        "import",
+        copy.to_str().unwrap(),
+        export_path.to_str().unwrap(),
+    ]));
+    let output = run_amem(&["export", copy.to_str().unwrap()]);
+    let json: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    assert_eq!(
+        json["nodes"][0]["tags"]["source"],
+        "https://example.com/?a=b"
+    );
+
+    let output = run_amem(&["add", path, "fact", "bad", "--tag", "novalue"]);
+    assert_eq!(output.status.code(), Some(3));
+}
+
+#[test]
+fn test_cli_import_merge_strategies() {
+    let src_file = NamedTempFile::new().unwrap();
+    let src_path = src_file.path().to_str().unwrap();
+    assert_success(&run_amem(&["create", src_path]));
+    assert_success(&run_amem(&[
+        "add",
+        src_path,
+        "fact",
+        "Rust is fast",
+        "--confidence",
+        "0.95",
+    ]));
+    assert_success(&run_amem(&["add", src_path, "fact", "Brand new fact"]));
+
+    let json_file = NamedTempFile::new().unwrap();
+    let output = run_amem(&["export", src_path]);
+    assert_success(&output);
+    std::fs::write(json_file.path(), stdout_str(&output)).unwrap();
+    let json_path = json_file.path().to_str().unwrap();
+
+    let fresh_target = || {
+        let file = NamedTempFile::new().unwrap();
+        let path = file.path().to_str().unwrap().to_string();
+        assert_success(&run_amem(&["create", &path]));
+        assert_success(&run_amem(&[
+            "add",
+            &path,
+            "fact",
+            "rust is fast",
+            "--confidence",
+            "0.5",
+        ]));
+        file
+    };
+    let import = |path: &str, strategy: &str| -> serde_json::Value {
+        let output = run_amem(&[
+            "--format",
+            "json",
+            "import",
+            path,
+            json_path,
+            "--merge-strategy",
+            strategy,
+        ]);
+        assert_success(&output);
+        serde_json::from_str(&stdout_str(&output)).unwrap()
+    };
+
+    // skip-duplicate: the matching fact is left alone.
+    let dst = fresh_target();
+    let report = import(dst.path().to_str().unwrap(), "skip-duplicate");
+    assert_eq!(report["nodes_added"], 1);
+    assert_eq!(report["nodes_skipped"], 1);
+    let graph = AmemReader::read_from_file(dst.path()).unwrap();
+    assert_eq!(graph.node_count(), 2);
+    assert!((graph.get_node(0).unwrap().confidence - 0.5).abs() < 1e-6);
+
+    // update-confidence: the existing node takes the higher confidence.
+    let dst = fresh_target();
+    let report = import(dst.path().to_str().unwrap(), "update-confidence");
+    assert_eq!(report["nodes_updated"], 1);
+    let graph = AmemReader::read_from_file(dst.path()).unwrap();
+    assert_eq!(graph.node_count(), 2);
+    assert!((graph.get_node(0).unwrap().confidence - 0.95).abs() < 1e-6);
+
+    // supersede: a Correction replaces the old node, which is preserved.
+    let dst = fresh_target();
+    let report = import(dst.path().to_str().unwrap(), "supersede");
+    assert_eq!(report["nodes_superseded"], 1);
+    let graph = AmemReader::read_from_file(dst.path()).unwrap();
+    assert_eq!(graph.node_count(), 3);
+    assert_eq!(graph.get_node(0).unwrap().event_type, EventType::Fact);
+    let correction = graph
+        .nodes()
+        .iter()
+        .find(|n| n.event_type == EventType::Correction)
+        .expect("correction node");
+    assert!(graph
+        .edges()
+        .iter()
+        .any(|e| e.edge_type == EdgeType::Supersedes
+            && e.source_id == correction.id
+            && e.target_id == 0));
+
+    // append: the old behavior, duplicates included.
+    let dst = fresh_target();
+    let report = import(dst.path().to_str().unwrap(), "append");
+    assert_eq!(report["nodes_added"], 2);
+    let graph = AmemReader::read_from_file(dst.path()).unwrap();
+    assert_eq!(graph.node_count(), 3);
+
+    let output = run_amem(&["import", src_path, json_path, "--merge-strategy", "bogus"]);
+    assert_eq!(output.status.code(), Some(3));
+}
+
+#[test]
+fn test_repl_json_mode_and_saved_file() {
+    use agentic_memory::cli::repl_commands::{execute, ReplState};
+
+    let dir = tempfile::tempdir().unwrap();
+    let brain = dir.path().join("brain.amem");
+    let saved = dir.path().join("repl_state");
+
+    let mut state = ReplState::new();
+    state.saved_path_file = saved.clone();
+
+    assert!(!execute(".json on", &mut state).unwrap());
+    assert!(state.json);
+    execute(&format!("/create {}", brain.display()), &mut state).unwrap();
+    execute("/add fact JSON mode works", &mut state).unwrap();
+    execute(".save", &mut state).unwrap();
+    assert!(saved.exists());
+
+    // Errors are reported, not raised, in JSON mode too.
+    assert!(!execute("/get not-a-number", &mut state).unwrap());
+    assert!(!execute(".bogus", &mut state).unwrap());
+
+    execute(".json off", &mut state).unwrap();
+    assert!(!state.json);
+
+    // A fresh session picks the working file back up.
+    let mut fresh = ReplState::new();
+    fresh.saved_path_file = saved;
+    assert!(fresh.file_path.is_none());
+    execute(".load", &mut fresh).unwrap();
+    assert_eq!(
+        fresh.file_path.unwrap(),
+        std::fs::canonicalize(&brain).unwrap()
+    );
+
+    let graph = AmemReader::read_from_file(&brain).unwrap();
+    assert_eq!(graph.node_count(), 1);
+}
+
 #[test]
 fn test_cli_json_format() {
     let tmp = NamedTempFile::new().unwrap();
     let path = tmp.path().to_str().unwrap();
 
-    // Create and add a node
-    let output = run_amem(&["create", path]);
+    // Create and add a node
+    let output = run_amem(&["create", path]);
+    assert_success(&output);
+
+    let output = run_amem(&["add", path, "fact", "json test fact", "--session", "1"]);
+    assert_success(&output);
+
+    // Run info with --format json
+    let output = run_amem(&["--format", "json", "info", path]);
+    assert_success(&output);
+    let json_out = stdout_str(&output);
+
+    // Parse as JSON to validate
+    let parsed: serde_json::Value = serde_json::from_str(&json_out).unwrap_or_else(|e| {
+        panic!(
+            "Failed to parse info --format json output as JSON: {}\nOutput was: {}",
+            e, json_out
+        )
+    });
+
+    assert_eq!(parsed["nodes"], 1);
+    assert!(parsed["dimension"].is_number());
+}
+
+#[test]
+fn test_cli_stats_watch() {
+    let tmp = NamedTempFile::new().unwrap();
+    let path = tmp.path().to_str().unwrap();
+
+    assert_success(&run_amem(&["create", path]));
+    assert_success(&run_amem(&["add", path, "fact", "watched fact"]));
+
+    let output = run_amem(&[
+        "--format",
+        "json",
+        "stats",
+        path,
+        "--watch",
+        "--interval",
+        "1",
+        "--count",
+        "2",
+    ]);
+    assert_success(&output);
+
+    // One JSON object per refresh; the delta is only known from the second.
+    let frames: Vec<serde_json::Value> = stdout_str(&output)
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    assert_eq!(frames.len(), 2);
+    assert_eq!(frames[0]["nodes"], 1);
+    assert_eq!(frames[0]["event_types"]["fact"], 1);
+    assert_eq!(frames[0]["recent_nodes"], 1);
+    assert!(frames[0]["delta_nodes"].is_null());
+    assert_eq!(frames[1]["delta_nodes"], 0);
+
+    // --interval without --watch is rejected.
+    let output = run_amem(&["stats", path, "--interval", "1"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_cli_dedup_interactive_plan_round_trip() {
+    let tmp = NamedTempFile::new().unwrap();
+    let path = tmp.path().to_str().unwrap();
+    let plan = NamedTempFile::new().unwrap();
+    let plan_path = plan.path().to_str().unwrap();
+
+    let mut vector = vec!["0"; DEFAULT_DIMENSION];
+    vector[0] = "1";
+    let vector = vector.join(",");
+    assert_success(&run_amem(&["create", path]));
+    for content in [
+        "Rust compiler guarantees memory safety",
+        "Rust compiler guarantees memory safety always",
+    ] {
+        assert_success(&run_amem(&[
+            "add", path, "fact", content, "--vector", &vector,
+        ]));
+    }
+
+    // Review once without touching the file, choosing "merge into 1".
+    let mut child = Command::new(amem_bin())
+        .args([
+            "--format",
+            "json",
+            "dedup",
+            path,
+            "--interactive",
+            "--plan-out",
+            plan_path,
+            "--dry-run",
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(b"1\n").unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert_success(&output);
+    let summary: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    assert_eq!(summary["candidates"], 1);
+    assert_eq!(summary["merge"], 1);
+    assert_eq!(summary["merged"], 0);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("merge into [1]"));
+
+    let saved: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(plan.path()).unwrap()).unwrap();
+    assert_eq!(saved["decisions"][0]["decision"], "merge-into-first");
+    assert_eq!(
+        AmemReader::read_from_file(tmp.path()).unwrap().edge_count(),
+        0
+    );
+
+    // Replay the saved plan non-interactively.
+    let output = run_amem(&["--format", "json", "dedup", path, "--apply-plan", plan_path]);
+    assert_success(&output);
+    let summary: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    assert_eq!(summary["merged"], 1);
+
+    let graph = AmemReader::read_from_file(tmp.path()).unwrap();
+    let first = saved["decisions"][0]["first"].as_u64().unwrap();
+    let edges = graph.edges_from(first);
+    assert_eq!(edges.len(), 1);
+    assert_eq!(edges[0].edge_type, EdgeType::Supersedes);
+
+    // One of --interactive / --apply-plan is required.
+    assert!(!run_amem(&["dedup", path]).status.success());
+}
+
+#[test]
+fn test_cli_similar_expand() {
+    let tmp = NamedTempFile::new().unwrap();
+    let path = tmp.path().to_str().unwrap();
+    let vector = |hot: &[(usize, &'static str)]| {
+        let mut v = vec!["0"; DEFAULT_DIMENSION];
+        for &(i, value) in hot {
+            v[i] = value;
+        }
+        v.join(",")
+    };
+
+    assert_success(&run_amem(&["create", path]));
+    for (content, v) in [
+        ("query node", vector(&[(0, "1")])),
+        ("close match", vector(&[(0, "1"), (1, "0.1")])),
+        ("supporting evidence", vector(&[(5, "1")])),
+    ] {
+        assert_success(&run_amem(&["add", path, "fact", content, "--vector", &v]));
+    }
+    assert_success(&run_amem(&["link", path, "2", "1", "supports"]));
+
+    let output = run_amem(&["--format", "json", "similar", path, "0"]);
+    assert_success(&output);
+    let plain: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    assert_eq!(plain["count"], 1);
+    assert_eq!(plain["matches"][0]["node_id"], 1);
+
+    let output = run_amem(&[
+        "--format", "json", "similar", path, "0", "--expand", "supports", "--hops", "1",
+    ]);
+    assert_success(&output);
+    let expanded: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    assert_eq!(expanded["count"], 2);
+    let pulled = &expanded["matches"][1];
+    assert_eq!(pulled["node_id"], 2);
+    assert_eq!(pulled["expanded_from"]["seed_id"], 1);
+    assert_eq!(pulled["expanded_from"]["edge_type"], "supports");
+
+    let output = run_amem(&["similar", path, "0", "--expand", "bogus"]);
+    assert_eq!(output.status.code(), Some(3));
+}
+
+#[test]
+fn test_cli_add_embedding_model() {
+    let tmp = NamedTempFile::new().unwrap();
+    let path = tmp.path().to_str().unwrap();
+    let vector = vec!["0.5"; DEFAULT_DIMENSION].join(",");
+
+    assert_success(&run_amem(&["create", path]));
+    assert_success(&run_amem(&[
+        "add",
+        path,
+        "fact",
+        "first",
+        "--vector",
+        &vector,
+        "--embedding-model",
+        "text-embed-a",
+    ]));
+    let output = run_amem(&["--format", "json", "info", path]);
+    assert_success(&output);
+    let info: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    assert_eq!(info["embedding_model"], "text-embed-a");
+
+    // A different model warns by default and fails with --strict.
+    let output = run_amem(&[
+        "add",
+        path,
+        "fact",
+        "second",
+        "--vector",
+        &vector,
+        "--embedding-model",
+        "text-embed-b",
+    ]);
+    assert_success(&output);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Warning"));
+    let output = run_amem(&[
+        "add",
+        path,
+        "fact",
+        "third",
+        "--vector",
+        &vector,
+        "--embedding-model",
+        "text-embed-b",
+        "--strict",
+    ]);
+    assert_eq!(output.status.code(), Some(3));
+
+    let output = run_amem(&["--format", "json", "info", path]);
+    let info: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    assert_eq!(info["embedding_model"], "text-embed-a");
+    assert_eq!(info["nodes"], 2);
+}
+
+#[test]
+fn test_cli_terms() {
+    let tmp = NamedTempFile::new().unwrap();
+    let path = tmp.path().to_str().unwrap();
+
+    assert_success(&run_amem(&["create", path]));
+    for content in [
+        "redis caches sessions, redis is fast",
+        "postgres stores sessions",
+        "sessions expire hourly",
+    ] {
+        assert_success(&run_amem(&["add", path, "fact", content]));
+    }
+
+    let output = run_amem(&["--format", "json", "terms", path, "--top", "2"]);
+    assert_success(&output);
+    let parsed: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    assert_eq!(parsed["doc_count"], 3);
+    let terms = parsed["terms"].as_array().unwrap();
+    assert_eq!(terms.len(), 2);
+    assert_eq!(terms[0]["term"], "sessions");
+    assert_eq!(terms[0]["total_frequency"], 3);
+    assert_eq!(terms[1]["term"], "redis");
+    // Rarer terms carry more IDF.
+    assert!(terms[1]["idf"].as_f64().unwrap() > terms[0]["idf"].as_f64().unwrap());
+
+    let output = run_amem(&["--format", "json", "terms", path, "--term", "Redis"]);
+    assert_success(&output);
+    let parsed: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    assert_eq!(parsed["indexed_as"], "redis");
+    assert_eq!(parsed["doc_frequency"], 1);
+    assert_eq!(parsed["nodes"][0]["node_id"], 0);
+    assert_eq!(parsed["nodes"][0]["term_frequency"], 2);
+}
+
+#[test]
+fn test_cli_centrality_seeds() {
+    let tmp = NamedTempFile::new().unwrap();
+    let path = tmp.path().to_str().unwrap();
+
+    assert_success(&run_amem(&["create", path]));
+    for content in [
+        "deployment uses blue green rollout",
+        "rollout needs a health check",
+        "database schema is normalized",
+        "schema migrations run nightly",
+    ] {
+        assert_success(&run_amem(&["add", path, "fact", content]));
+    }
+    assert_success(&run_amem(&["link", path, "0", "1", "related_to"]));
+    assert_success(&run_amem(&["link", path, "2", "3", "related_to"]));
+
+    let output = run_amem(&[
+        "--format",
+        "json",
+        "centrality",
+        path,
+        "--seed-query",
+        "deployment",
+    ]);
+    assert_success(&output);
+    let parsed: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    assert_eq!(parsed["seeds"], serde_json::json!([0]));
+    let top: Vec<u64> = parsed["scores"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .take(2)
+        .map(|s| s["node_id"].as_u64().unwrap())
+        .collect();
+    assert!(top.contains(&0) && top.contains(&1), "top: {:?}", top);
+
+    let output = run_amem(&["--format", "json", "centrality", path, "--seeds", "2,3"]);
+    assert_success(&output);
+    let parsed: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    assert_eq!(parsed["seeds"], serde_json::json!([2, 3]));
+
+    let output = run_amem(&["centrality", path, "--seeds", "x"]);
+    assert_eq!(output.status.code(), Some(3));
+}
+
+#[test]
+fn test_cli_centrality_and_similar_sample() {
+    let tmp = NamedTempFile::new().unwrap();
+    let path = tmp.path().to_str().unwrap();
+
+    assert_success(&run_amem(&["create", path]));
+    for i in 0..10 {
+        assert_success(&run_amem(&["add", path, "fact", &format!("note {i}")]));
+    }
+
+    let args = [
+        "--format",
+        "json",
+        "centrality",
+        path,
+        "--sample",
+        "0.5",
+        "--sample-seed",
+        "3",
+    ];
+    let output = run_amem(&args);
+    assert_success(&output);
+    let parsed: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    assert_eq!(
+        parsed["approximate"],
+        serde_json::json!({"sampled_percent": 50.0, "seed": 3})
+    );
+    assert_eq!(parsed["scores"].as_array().unwrap().len(), 5);
+    assert_eq!(stdout_str(&run_amem(&args)), stdout_str(&output));
+
+    let output = run_amem(&["--format", "json", "centrality", path]);
+    assert!(
+        serde_json::from_str::<serde_json::Value>(&stdout_str(&output)).unwrap()["approximate"]
+            .is_null()
+    );
+
+    let output = run_amem(&[
+        "similar",
+        path,
+        "0",
+        "--min-similarity",
+        "0",
+        "--sample",
+        "0.25",
+    ]);
+    assert_success(&output);
+    assert!(stdout_str(&output).contains("approximate (sampled 25%, seed "));
+
+    let output = run_amem(&["centrality", path, "--sample", "1.5"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_cli_history() {
+    let tmp = NamedTempFile::new().unwrap();
+    let path = tmp.path().to_str().unwrap();
+
+    let clock = MockClock::shared(1_700_000_000_000_000);
+    let engine = WriteEngine::new(DEFAULT_DIMENSION).with_clock(clock.clone());
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    let event = CognitiveEventBuilder::new(EventType::Fact, "the cache is warmed at boot")
+        .created_at(clock.now_micros())
+        .build();
+    let id = graph.add_node(event).unwrap();
+    for _ in 0..3 {
+        clock.advance_secs(86_400);
+        engine.touch(&mut graph, id).unwrap();
+    }
+    AmemWriter::new(DEFAULT_DIMENSION)
+        .write_to_file(&graph, tmp.path())
+        .unwrap();
+
+    let output = run_amem(&["--format", "json", "history", path, "0"]);
+    assert_success(&output);
+    let parsed: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    assert_eq!(parsed["access_count"], 3);
+    assert_eq!(parsed["unlogged_accesses"], 0);
+    let accesses = parsed["accesses"].as_array().unwrap();
+    assert_eq!(accesses.len(), 3);
+    assert_eq!(accesses[2], clock.now_micros());
+
+    let trajectory = parsed["trajectory"].as_array().unwrap();
+    let events: Vec<&str> = trajectory
+        .iter()
+        .map(|p| p["event"].as_str().unwrap())
+        .collect();
+    assert_eq!(events, ["created", "access", "access", "access", "now"]);
+    // Each access raises the access factor, so decay scores climb.
+    let scores: Vec<f64> = trajectory[..4]
+        .iter()
+        .map(|p| p["decay_score"].as_f64().unwrap())
+        .collect();
+    assert!(scores.windows(2).all(|w| w[0] < w[1]));
+
+    let output = run_amem(&["history", path, "0"]);
+    assert_success(&output);
+    assert!(stdout_str(&output).contains("Access count: 3"));
+}
+
+#[test]
+fn test_cli_query_profile() {
+    let tmp = NamedTempFile::new().unwrap();
+    let path = tmp.path().to_str().unwrap();
+
+    assert_success(&run_amem(&["create", path]));
+    assert_success(&run_amem(&["add", path, "fact", "profiled fact"]));
+
+    let output = run_amem(&["--format", "json", "query", path, "--profile"]);
+    assert_success(&output);
+    // The breakdown goes to stderr; stdout stays valid JSON.
+    let parsed: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    assert_eq!(parsed.as_array().unwrap().len(), 1);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    for phase in [
+        "load",
+        "index lookup",
+        "filter",
+        "sort",
+        "serialize",
+        "total",
+    ] {
+        assert!(stderr.contains(phase), "missing {phase} in {stderr}");
+    }
+
+    let output = run_amem(&["search", path]);
+    assert_success(&output);
+    assert!(!String::from_utf8_lossy(&output.stderr).contains("Profile"));
+}
+
+#[test]
+fn test_cli_query_fields() {
+    let tmp = NamedTempFile::new().unwrap();
+    let path = tmp.path().to_str().unwrap();
+
+    assert_success(&run_amem(&["create", path]));
+    assert_success(&run_amem(&["add", path, "fact", "a long fact body"]));
+
+    let output = run_amem(&[
+        "--format",
+        "json",
+        "query",
+        path,
+        "--fields",
+        "id,type,confidence",
+    ]);
+    assert_success(&output);
+    let json: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    let node = json[0].as_object().unwrap();
+    let mut keys: Vec<&str> = node.keys().map(String::as_str).collect();
+    keys.sort_unstable();
+    assert_eq!(keys, ["confidence", "id", "type"]);
+
+    let output = run_amem(&["query", path, "--fields", "type,id"]);
+    assert_success(&output);
+    assert!(stdout_str(&output).starts_with("type=\"fact\" id=0\n"));
+
+    let output = run_amem(&["query", path, "--fields", "id,body"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("unknown field 'body'"));
+}
+
+#[test]
+fn test_cli_export_redact() {
+    let file = NamedTempFile::new().unwrap();
+    let path = file.path().to_str().unwrap();
+    assert_success(&run_amem(&["create", path]));
+    assert_success(&run_amem(&[
+        "add",
+        path,
+        "fact",
+        "Deploy key sk-THISISALONGSECRET123456 mailed to ops@example.com",
+    ]));
+    assert_success(&run_amem(&[
+        "add",
+        path,
+        "fact",
+        "Config lives in /Users/alice/.config/app.toml",
+    ]));
+
+    let output = run_amem(&["export", path, "--nodes-only", "--redact"]);
+    assert_success(&output);
+    let json: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    assert_eq!(
+        json["nodes"][0]["content"],
+        "Deploy key [REDACTED_SECRET] mailed to [REDACTED_EMAIL]"
+    );
+    assert_eq!(
+        json["nodes"][1]["content"],
+        "Config lives in [REDACTED_PATH]"
+    );
+    assert_eq!(json["redaction"]["total"], 3);
+    assert_eq!(json["redaction"]["by_category"]["secrets"], 1);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Redacted 3 tokens"));
+
+    // Only the chosen categories are scrubbed.
+    let output = run_amem(&[
+        "export",
+        path,
+        "--nodes-only",
+        "--redact",
+        "--redact-categories",
+        "emails",
+    ]);
+    assert_success(&output);
+    let json: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    assert_eq!(
+        json["nodes"][0]["content"],
+        "Deploy key sk-THISISALONGSECRET123456 mailed to [REDACTED_EMAIL]"
+    );
+    assert_eq!(json["redaction"]["total"], 1);
+    assert_eq!(
+        json["redaction"]["categories"],
+        serde_json::json!(["emails"])
+    );
+
+    let output = run_amem(&["export", path, "--redact", "--redact-categories", "names"]);
+    assert_eq!(output.status.code(), Some(3));
+
+    // The stored file is untouched.
+    let output = run_amem(&["export", path, "--nodes-only"]);
+    let json: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    assert!(json["nodes"][0]["content"]
+        .as_str()
+        .unwrap()
+        .contains("sk-THISISALONGSECRET123456"));
+    assert!(json.get("redaction").is_none());
+}
+
+#[test]
+fn test_cli_impact_upstream() {
+    let file = NamedTempFile::new().unwrap();
+    let path = file.path().to_str().unwrap();
+    assert_success(&run_amem(&["create", path]));
+    assert_success(&run_amem(&["add", path, "fact", "load tests hit 2k rps"]));
+    assert_success(&run_amem(&["add", path, "inference", "one node is enough"]));
+    assert_success(&run_amem(&["add", path, "decision", "skip the cluster"]));
+    assert_success(&run_amem(&["link", path, "1", "0", "supports"]));
+    assert_success(&run_amem(&["link", path, "2", "1", "caused_by"]));
+
+    let output = run_amem(&["--format", "json", "impact", path, "2", "--upstream"]);
+    assert_success(&output);
+    let json: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    assert_eq!(json["direction"], "upstream");
+    assert_eq!(json["direct_causes"], 1);
+    assert_eq!(json["causes"], serde_json::json!([1, 0]));
+    assert_eq!(json["root_causes"], serde_json::json!([0]));
+
+    let output = run_amem(&["impact", path, "2", "--upstream"]);
+    assert_success(&output);
+    let text = stdout_str(&output);
+    assert!(text.contains("Provenance of node 2"));
+    assert!(text.contains("-> Node 0 (fact, supports)"));
+
+    // Downstream from the decision finds nothing that depends on it.
+    let output = run_amem(&["--format", "json", "impact", path, "2"]);
+    assert_success(&output);
+    let json: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    assert_eq!(json["total_dependents"], 0);
+}
+
+#[test]
+fn test_cli_fix_dimension() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("brain.amem");
+    let path = file.to_str().unwrap();
+    assert_success(&run_amem(&["create", path, "--dimension", "4"]));
+    assert_success(&run_amem(&["add", path, "fact", "first"]));
+    assert_success(&run_amem(&["add", path, "fact", "second"]));
+
+    // Simulate an external writer that left the header dimension at 0.
+    let mut data = std::fs::read(&file).unwrap();
+    data[8..12].copy_from_slice(&0u32.to_le_bytes());
+    std::fs::write(&file, &data).unwrap();
+
+    let output = run_amem(&["info", path]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("expected 0, got 4"));
+
+    let output = run_amem(&[
+        "--format",
+        "json",
+        "fix-dimension",
+        path,
+        "--dimension",
+        "4",
+    ]);
+    assert_success(&output);
+    let json: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    assert_eq!(json["declared_dimension"], 0);
+    assert_eq!(json["stored_dimension"], 4);
+    assert_eq!(json["changed"], true);
+    let backup = dir.path().join("brain.pre-fix-dimension.amem");
+    assert_eq!(json["backup_path"], backup.display().to_string());
+    assert_eq!(std::fs::read(&backup).unwrap(), data);
+
+    let graph = AmemReader::read_from_file(&file).unwrap();
+    assert_eq!(graph.dimension(), 4);
+    assert_eq!(graph.node_count(), 2);
+
+    assert!(!run_amem(&["fix-dimension", path, "--dimension", "0"])
+        .status
+        .success());
+}
+
+#[test]
+fn test_cli_link_bidirectional_and_symmetric_traverse() {
+    let file = NamedTempFile::new().unwrap();
+    let path = file.path().to_str().unwrap();
+    assert_success(&run_amem(&["create", path]));
+    for content in ["tabs", "spaces", "editorconfig"] {
+        assert_success(&run_amem(&["add", path, "fact", content]));
+    }
+
+    let output = run_amem(&[
+        "--format",
+        "json",
+        "link",
+        path,
+        "0",
+        "1",
+        "contradicts",
+        "--bidirectional",
+    ]);
+    assert_success(&output);
+    let json: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    assert_eq!(json["bidirectional"], true);
+    let graph = AmemReader::read_from_file(file.path()).unwrap();
+    assert_eq!(graph.edges_from(0).len(), 1);
+    assert_eq!(graph.edges_from(1).len(), 1);
+
+    // Stored one way only: 2 -> 1.
+    assert_success(&run_amem(&["link", path, "2", "1", "related_to"]));
+    let visited = |extra: &[&str]| {
+        let mut args = vec![
+            "--format",
+            "json",
+            "traverse",
+            path,
+            "0",
+            "--direction",
+            "forward",
+        ];
+        args.extend_from_slice(extra);
+        let output = run_amem(&args);
+        assert_success(&output);
+        let json: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+        json.as_array().unwrap().len()
+    };
+    assert_eq!(visited(&[]), 2);
+    assert_eq!(visited(&["--symmetric"]), 3);
+}
+
+#[test]
+fn test_cli_sessions_active() {
+    let file = NamedTempFile::new().unwrap();
+    let path = file.path().to_str().unwrap();
+    assert_success(&run_amem(&["create", path]));
+    assert_success(&run_amem(&[
+        "add",
+        path,
+        "fact",
+        "in flight",
+        "--session",
+        "1",
+    ]));
+    assert_success(&run_amem(&[
+        "add",
+        path,
+        "fact",
+        "wrapped up",
+        "--session",
+        "2",
+    ]));
+    assert_success(&run_amem(&[
+        "add",
+        path,
+        "episode",
+        "summary",
+        "--session",
+        "2",
+    ]));
+
+    let output = run_amem(&["--format", "json", "sessions", path, "--active"]);
+    assert_success(&output);
+    let json: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    assert_eq!(json["window_secs"], 1800);
+    assert_eq!(json["active"], 1);
+    let sessions = json["sessions"].as_array().unwrap();
+    let status = |sid: u64| {
+        let s = sessions.iter().find(|s| s["session_id"] == sid).unwrap();
+        assert!(s["last_activity"].as_u64().unwrap() > 0);
+        s["status"].as_str().unwrap().to_string()
+    };
+    assert_eq!(status(1), "active");
+    assert_eq!(status(2), "completed");
+
+    let output = run_amem(&["sessions", path, "--active", "--active-window", "5"]);
+    assert_success(&output);
+    let text = stdout_str(&output);
+    assert!(text.contains("window 5m"), "{text}");
+    assert!(text.contains("1 active of 2 sessions"), "{text}");
+
+    assert!(!run_amem(&["sessions", path, "--active-window", "5"])
+        .status
+        .success());
+}
+
+#[test]
+fn test_cli_compact_older_than() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("brain.amem");
+    let path = file.to_str().unwrap();
+    assert_success(&run_amem(&["create", path]));
+    assert_success(&run_amem(&["add", path, "fact", "stale fact"]));
+    assert_success(&run_amem(&["add", path, "skill", "keep this skill"]));
+    let original = std::fs::read(&file).unwrap();
+
+    let compact = |extra: &[&str]| {
+        let mut args = vec![
+            "--format",
+            "json",
+            "compact",
+            path,
+            "--older-than",
+            "0s",
+            "--keep-types",
+            "skill",
+        ];
+        args.extend_from_slice(extra);
+        let output = run_amem(&args);
+        assert_success(&output);
+        serde_json::from_str::<serde_json::Value>(&stdout_str(&output)).unwrap()
+    };
+
+    // Dry run by default: report only.
+    let json = compact(&[]);
+    assert_eq!(json["dry_run"], true);
+    assert_eq!(json["removed"], 1);
+    assert_eq!(json["removed_by_type"]["fact"], 1);
+    assert!(json["reclaimed_bytes"].as_u64().unwrap() > 0);
+    assert_eq!(std::fs::read(&file).unwrap(), original);
+
+    let json = compact(&["--confirm"]);
+    assert_eq!(json["dry_run"], false);
+    let backup = dir.path().join("brain.pre-compact.amem");
+    assert_eq!(json["backup_path"], backup.display().to_string());
+    assert_eq!(std::fs::read(&backup).unwrap(), original);
+    let graph = AmemReader::read_from_file(&file).unwrap();
+    assert_eq!(graph.node_count(), 1);
+    assert_eq!(graph.nodes()[0].event_type, EventType::Skill);
+
+    assert!(!run_amem(&["compact", path, "--older-than", "ninety"])
+        .status
+        .success());
+    assert!(!run_amem(&[
+        "compact",
+        path,
+        "--older-than",
+        "90d",
+        "--keep-types",
+        "bogus"
+    ])
+    .status
+    .success());
+}
+
+#[test]
+fn test_cli_delete_by_filter() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("brain.amem");
+    let path = file.to_str().unwrap();
+    assert_success(&run_amem(&["create", path]));
+    assert_success(&run_amem(&[
+        "add",
+        path,
+        "inference",
+        "shaky guess",
+        "--confidence",
+        "0.2",
+    ]));
+    assert_success(&run_amem(&["add", path, "inference", "solid inference"]));
+    assert_success(&run_amem(&[
+        "add",
+        path,
+        "fact",
+        "shaky fact",
+        "--confidence",
+        "0.2",
+    ]));
+    assert_success(&run_amem(&["link", path, "0", "2", "caused_by"]));
+    let original = std::fs::read(&file).unwrap();
+
+    let delete = |extra: &[&str]| {
+        let mut args = vec![
+            "--format",
+            "json",
+            "delete",
+            path,
+            "--type",
+            "inference",
+            "--confidence-below",
+            "0.5",
+        ];
+        args.extend_from_slice(extra);
+        let output = run_amem(&args);
+        assert_success(&output);
+        serde_json::from_str::<serde_json::Value>(&stdout_str(&output)).unwrap()
+    };
+
+    // Dry run by default: report only.
+    let json = delete(&[]);
+    assert_eq!(json["dry_run"], true);
+    assert_eq!(json["deleted_count"], 1);
+    assert_eq!(json["deleted_ids"], serde_json::json!([0]));
+    assert_eq!(json["edges_removed"], 1);
+    assert_eq!(std::fs::read(&file).unwrap(), original);
+
+    let json = delete(&["--yes"]);
+    assert_eq!(json["dry_run"], false);
+    let backup = dir.path().join("brain.pre-delete.amem");
+    assert_eq!(std::fs::read(&backup).unwrap(), original);
+    let graph = AmemReader::read_from_file(&file).unwrap();
+    assert_eq!(graph.node_count(), 2);
+    assert!(graph.get_node(0).is_none());
+    assert_eq!(graph.edge_count(), 0);
+
+    // No filter at all, or an unknown type, is refused.
+    assert_eq!(run_amem(&["delete", path, "--yes"]).status.code(), Some(3));
+    assert_eq!(
+        run_amem(&["delete", path, "--type", "bogus"]).status.code(),
+        Some(3)
+    );
+}
+
+#[test]
+fn test_cli_import_markdown() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("brain.amem");
+    let path = file.to_str().unwrap();
+    let notes = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/notes.md");
+    let notes = notes.to_str().unwrap();
+    assert_success(&run_amem(&["create", path]));
+    assert_success(&run_amem(&["add", path, "fact", "existing memory"]));
+
+    let output = run_amem(&["--format", "json", "import-markdown", notes, path]);
+    assert_success(&output);
+    let json: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    assert_eq!(json["session_id"], 1, "a fresh session after session 0");
+    assert_eq!(json["nodes_added"], 12);
+    assert_eq!(
+        json["by_type"],
+        serde_json::json!({"episode": 3, "fact": 5, "inference": 2, "decision": 2})
+    );
+
+    let graph = AmemReader::read_from_file(&file).unwrap();
+    let imported: Vec<_> = graph.nodes().iter().filter(|n| n.session_id == 1).collect();
+    assert_eq!(imported.len(), 12);
+    let content_of = |event_type: EventType| -> Vec<&str> {
+        imported
+            .iter()
+            .filter(|n| n.event_type == event_type)
+            .map(|n| n.content.as_str())
+            .collect()
+    };
+    assert_eq!(
+        content_of(EventType::Episode),
+        ["Project notes", "Build system", "Storage"]
+    );
+    assert_eq!(
+        content_of(EventType::Inference),
+        [
+            "Slow CI is probably caused by the cold cargo cache.",
+            "Mmap access should make big files cheap to open."
+        ]
+    );
+    assert_eq!(
+        content_of(EventType::Decision),
+        [
+            "Decision: cache the target directory in CI.",
+            "I decided to keep the v1 reader for old files"
+        ]
+    );
+    assert!(imported.iter().all(|n| !n.content.contains("code block")));
+
+    // Items are part of the episode of the heading they sit under.
+    let storage = imported.iter().find(|n| n.content == "Storage").unwrap().id;
+    let lz4 = imported
+        .iter()
+        .find(|n| n.content == "Files are LZ4-compressed")
+        .unwrap()
+        .id;
+    assert!(graph
+        .edges_from(lz4)
+        .iter()
+        .any(|e| e.target_id == storage && e.edge_type == EdgeType::PartOf));
+    assert_eq!(graph.edge_count(), 9);
+
+    // A second run into a named session, with list items as decisions.
+    assert_success(&run_amem(&[
+        "import-markdown",
+        notes,
+        path,
+        "--session",
+        "5",
+        "--type",
+        "decision",
+    ]));
+    let graph = AmemReader::read_from_file(&file).unwrap();
+    let second: Vec<_> = graph.nodes().iter().filter(|n| n.session_id == 5).collect();
+    assert_eq!(second.len(), 12);
+    assert_eq!(
+        second
+            .iter()
+            .filter(|n| n.event_type == EventType::Decision)
+            .count(),
+        7
+    );
+    assert_eq!(graph.session_index().node_count(1), 12);
+
+    assert_eq!(
+        run_amem(&["import-markdown", notes, path, "--type", "episode"])
+            .status
+            .code(),
+        Some(3)
+    );
+}
+
+#[test]
+fn test_cli_compact_keep_top() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("brain.amem");
+    let path = file.to_str().unwrap();
+    assert_success(&run_amem(&["create", path]));
+    for (content, confidence) in [("low", "0.2"), ("mid", "0.5"), ("high", "0.9")] {
+        assert_success(&run_amem(&[
+            "add",
+            path,
+            "fact",
+            content,
+            "--confidence",
+            confidence,
+        ]));
+    }
+    let original = std::fs::read(&file).unwrap();
+
+    let output = run_amem(&[
+        "--format",
+        "json",
+        "compact",
+        path,
+        "--keep-top",
+        "1",
+        "--pin",
+        "0",
+    ]);
+    assert_success(&output);
+    let json: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    assert_eq!(json["dry_run"], true);
+    assert_eq!(json["pinned_kept"], 1);
+    assert_eq!(json["removed_ids"], serde_json::json!([1, 2]));
+    assert!(json["cutoff_score"].is_null());
+    assert_eq!(std::fs::read(&file).unwrap(), original);
+
+    let output = run_amem(&["compact", path, "--keep-top", "2", "--confirm"]);
+    assert_success(&output);
+    assert!(stdout_str(&output).contains("Importance cutoff"));
+    let graph = AmemReader::read_from_file(&file).unwrap();
+    let mut kept: Vec<u64> = graph.nodes().iter().map(|n| n.id).collect();
+    kept.sort_unstable();
+    assert_eq!(kept, vec![1, 2]);
+
+    assert!(
+        !run_amem(&["compact", path, "--keep-top", "1", "--pin", "x"])
+            .status
+            .success()
+    );
+    assert!(
+        !run_amem(&["compact", path, "--rewire-to-episode", "--older-than", "1d"])
+            .status
+            .success()
+    );
+}
+
+#[test]
+fn test_cli_tail_session() {
+    let file = NamedTempFile::new().unwrap();
+    let path = file.path().to_str().unwrap();
+    assert_success(&run_amem(&["create", path]));
+    assert_success(&run_amem(&["add", path, "fact", "first", "--session", "1"]));
+    assert_success(&run_amem(&[
+        "add",
+        path,
+        "decision",
+        "second",
+        "--session",
+        "2",
+    ]));
+    assert_success(&run_amem(&["add", path, "fact", "third", "--session", "1"]));
+
+    let output = run_amem(&["--format", "json", "tail-session", path, "-n", "2"]);
+    assert_success(&output);
+    let json: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    assert_eq!(json["total_nodes"], 3);
+    let ids: Vec<u64> = json["nodes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|n| n["id"].as_u64().unwrap())
+        .collect();
+    assert_eq!(ids, vec![2, 1]);
+    assert_eq!(json["nodes"][1]["type"], "decision");
+
+    let output = run_amem(&[
+        "tail-session",
+        path,
+        "--follow",
+        "--interval",
+        "1",
+        "--count",
+        "1",
+    ]);
+    assert_success(&output);
+    let text = stdout_str(&output);
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines.len(), 3, "{text}");
+    assert!(lines[0].contains("[2] fact (session 1): third"), "{text}");
+
+    assert!(!run_amem(&["tail-session", path, "--interval", "1"])
+        .status
+        .success());
+}
+
+#[test]
+fn test_cli_capture_log_and_purge() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("brain.amem");
+    let path = file.to_str().unwrap();
+    assert_success(&run_amem(&["create", path]));
+    assert_success(&run_amem(&["add", path, "fact", "user prefers tabs"]));
+    assert_success(&run_amem(&[
+        "add",
+        path,
+        "fact",
+        "[auto-capture][tool] tool=memory_query input=mail [REDACTED_EMAIL]",
+    ]));
+    assert_success(&run_amem(&[
+        "add",
+        path,
+        "fact",
+        "[auto-capture][prompt] template=remember input=deploy on fridays",
+    ]));
+
+    let output = run_amem(&["--format", "json", "capture-log", path]);
     assert_success(&output);
+    let json: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    assert_eq!(json["captured"], 2);
+    assert_eq!(json["redacted"], 1);
+    assert_eq!(json["by_kind"]["tool"], 1);
+    assert_eq!(json["entries"][0]["id"], 2);
+    assert_eq!(json["entries"][0]["trigger"], "remember");
+    assert_eq!(json["entries"][1]["trigger"], "memory_query");
+    assert_eq!(json["entries"][1]["redacted"], true);
+
+    let output = run_amem(&["capture-log", path, "--purge"]);
+    assert_success(&output);
+    assert!(stdout_str(&output).contains("DRY RUN"));
+    assert_eq!(AmemReader::read_from_file(&file).unwrap().node_count(), 3);
+
+    assert_success(&run_amem(&["capture-log", path, "--purge", "--confirm"]));
+    let graph = AmemReader::read_from_file(&file).unwrap();
+    assert_eq!(graph.node_count(), 1);
+    assert_eq!(graph.nodes()[0].content, "user prefers tabs");
+    assert!(dir.path().join("brain.pre-purge.amem").exists());
+}
 
-    let output = run_amem(&["add", path, "fact", "json test fact", "--session", "1"]);
+#[test]
+fn test_cli_export_anonymize_ids() {
+    let file = NamedTempFile::new().unwrap();
+    let path = file.path().to_str().unwrap();
+    assert_success(&run_amem(&["create", path]));
+    for content in ["alpha", "beta", "gamma", "delta mailed ops@example.com"] {
+        assert_success(&run_amem(&["add", path, "fact", content]));
+    }
+    assert_success(&run_amem(&["link", path, "1", "0", "supports"]));
+    assert_success(&run_amem(&["link", path, "3", "2", "caused_by"]));
+
+    let map_dir = tempfile::tempdir().unwrap();
+    let map_path = map_dir.path().join("ids.json");
+    let output = run_amem(&[
+        "export",
+        path,
+        "--anonymize-ids",
+        "--anonymize-seed",
+        "42",
+        "--emit-id-map",
+        map_path.to_str().unwrap(),
+        "--redact",
+    ]);
     assert_success(&output);
+    let json: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    assert_eq!(json["anonymized_ids"], true);
+    assert_eq!(json["redaction"]["total"], 1);
+
+    // IDs are a permutation of 0..n, listed in anonymized order.
+    let nodes = json["nodes"].as_array().unwrap();
+    let ids: Vec<u64> = nodes.iter().map(|n| n["id"].as_u64().unwrap()).collect();
+    assert_eq!(ids, vec![0, 1, 2, 3]);
+
+    // The map file de-anonymizes every node and edge consistently.
+    let map: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&map_path).unwrap()).unwrap();
+    assert_eq!(map["seed"], 42);
+    let original: std::collections::HashMap<u64, u64> = map["ids"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| {
+            (
+                p["anonymized"].as_u64().unwrap(),
+                p["original"].as_u64().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(original.len(), 4);
+    let content_of = |anon: u64| {
+        nodes
+            .iter()
+            .find(|n| n["id"].as_u64() == Some(anon))
+            .unwrap()["content"]
+            .as_str()
+            .unwrap()
+            .to_string()
+    };
+    for (&anon, &orig) in &original {
+        let expected = ["alpha", "beta", "gamma", "delta mailed [REDACTED_EMAIL]"][orig as usize];
+        assert_eq!(content_of(anon), expected);
+    }
+    let mut edges: Vec<(u64, u64)> = json["edges"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| {
+            (
+                original[&e["source_id"].as_u64().unwrap()],
+                original[&e["target_id"].as_u64().unwrap()],
+            )
+        })
+        .collect();
+    edges.sort_unstable();
+    assert_eq!(edges, vec![(1, 0), (3, 2)]);
 
-    // Run info with --format json
-    let output = run_amem(&["--format", "json", "info", path]);
+    // The same seed reproduces the same export.
+    let again = run_amem(&[
+        "export",
+        path,
+        "--anonymize-ids",
+        "--anonymize-seed",
+        "42",
+        "--redact",
+    ]);
+    assert_success(&again);
+    let again: serde_json::Value = serde_json::from_str(&stdout_str(&again)).unwrap();
+    assert_eq!(again["nodes"], json["nodes"]);
+    assert_eq!(again["edges"], json["edges"]);
+
+    assert!(!run_amem(&["export", path, "--anonymize-seed", "1"])
+        .status
+        .success());
+}
+
+#[test]
+fn test_cli_export_cross_session() {
+    let file = NamedTempFile::new().unwrap();
+    let path = file.path().to_str().unwrap();
+    assert_success(&run_amem(&["create", path]));
+    for (content, session) in [("base", "1"), ("detail", "1"), ("follow-up", "2")] {
+        assert_success(&run_amem(&[
+            "add",
+            path,
+            "fact",
+            content,
+            "--session",
+            session,
+        ]));
+    }
+    assert_success(&run_amem(&["link", path, "1", "0", "supports"]));
+    assert_success(&run_amem(&["link", path, "2", "0", "caused_by"]));
+
+    let output = run_amem(&["export", path, "--cross-session"]);
     assert_success(&output);
-    let json_out = stdout_str(&output);
+    let json: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    let edges = json["edges"].as_array().unwrap();
+    assert_eq!(edges.len(), 1);
+    assert_eq!(edges[0]["source_id"], 2);
+    assert_eq!(edges[0]["target_id"], 0);
+    let ids: Vec<u64> = json["nodes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|n| n["id"].as_u64().unwrap())
+        .collect();
+    assert_eq!(ids, vec![0, 2]);
+    assert_eq!(json["cross_session"]["edges"], 1);
+    assert_eq!(json["cross_session"]["total_edges"], 2);
+    assert_eq!(
+        json["cross_session"]["session_pairs"][0]["source_session"],
+        2
+    );
+    assert_eq!(
+        json["cross_session"]["session_pairs"][0]["target_session"],
+        1
+    );
 
-    // Parse as JSON to validate
-    let parsed: serde_json::Value = serde_json::from_str(&json_out).unwrap_or_else(|e| {
-        panic!(
-            "Failed to parse info --format json output as JSON: {}\nOutput was: {}",
-            e, json_out
-        )
-    });
+    assert!(
+        !run_amem(&["export", path, "--cross-session", "--session", "1"])
+            .status
+            .success()
+    );
+}
 
-    assert_eq!(parsed["nodes"], 1);
-    assert!(parsed["dimension"].is_number());
+#[test]
+fn test_cli_export_dot() {
+    let file = NamedTempFile::new().unwrap();
+    let path = file.path().to_str().unwrap();
+    assert_success(&run_amem(&["create", path]));
+    assert_success(&run_amem(&[
+        "add",
+        path,
+        "fact",
+        "user says \"hi\"",
+        "--session",
+        "1",
+    ]));
+    assert_success(&run_amem(&[
+        "add",
+        path,
+        "decision",
+        "reply",
+        "--session",
+        "1",
+    ]));
+    assert_success(&run_amem(&[
+        "add",
+        path,
+        "inference",
+        "later thought",
+        "--session",
+        "2",
+    ]));
+    assert_success(&run_amem(&["link", path, "1", "0", "caused_by"]));
+    assert_success(&run_amem(&["link", path, "2", "1", "supports"]));
+
+    let output = run_amem(&["export", path, "--format", "dot"]);
+    assert_success(&output);
+    let dot = stdout_str(&output);
+    let lines: Vec<&str> = dot.lines().collect();
+    assert_eq!(lines.first(), Some(&"digraph amem {"));
+    assert_eq!(lines.last(), Some(&"}"));
+
+    let node_lines: Vec<&str> = lines
+        .iter()
+        .copied()
+        .filter(|l| l.contains("color="))
+        .collect();
+    assert_eq!(node_lines.len(), 3);
+    assert!(dot.contains(r#"  0 [label="FACT\nuser says \"hi\"" color=blue];"#));
+    assert!(dot.contains("color=orange"));
+    assert!(dot.contains("color=green"));
+    let declared: Vec<&str> = node_lines
+        .iter()
+        .map(|l| l.split_whitespace().next().unwrap())
+        .collect();
+
+    let edge_lines: Vec<&str> = lines.iter().copied().filter(|l| l.contains("->")).collect();
+    assert_eq!(edge_lines.len(), 2);
+    for line in &edge_lines {
+        let mut parts = line.split_whitespace();
+        let source = parts.next().unwrap();
+        assert_eq!(parts.next(), Some("->"));
+        let target = parts.next().unwrap();
+        assert!(declared.contains(&source) && declared.contains(&target));
+        assert!(line.contains("label=") && line.contains("tooltip=\"1.00\""));
+    }
+    assert!(dot.contains(r#"  1 -> 0 [label="caused_by""#));
+
+    let output = run_amem(&["export", path, "--format", "dot", "--type", "fact,decision"]);
+    assert_success(&output);
+    let dot = stdout_str(&output);
+    assert!(!dot.contains("INFERENCE"));
+    assert_eq!(dot.matches("->").count(), 1);
+
+    let output = run_amem(&["export", path, "--format", "dot", "--session", "2"]);
+    assert_success(&output);
+    let dot = stdout_str(&output);
+    assert_eq!(dot.matches("color=").count(), 1);
+    assert!(!dot.contains("->"));
+
+    assert!(!run_amem(&["export", path, "--format", "svg"])
+        .status
+        .success());
+    assert!(
+        !run_amem(&["export", path, "--format", "dot", "--nodes-only"])
+            .status
+            .success()
+    );
+}
+
+#[test]
+fn test_cli_export_markdown_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("brain.amem");
+    let path = file.to_str().unwrap();
+    assert_success(&run_amem(&["create", path]));
+    for (event_type, content, session) in [
+        ("episode", "Deploy work", "1"),
+        ("fact", "The API listens on port 8080", "1"),
+        ("fact", "Staging mirrors prod (mostly)", "1"),
+        ("decision", "ship on Friday", "1"),
+        ("correction", "the API moved to port 8081", "1"),
+        ("inference", "Fridays are quiet", "1"),
+        ("fact", "Backups run nightly", "2"),
+    ] {
+        assert_success(&run_amem(&[
+            "add",
+            path,
+            event_type,
+            content,
+            "--session",
+            session,
+        ]));
+    }
+    assert_success(&run_amem(&["link", path, "3", "1", "caused_by"]));
+    assert_success(&run_amem(&["link", path, "3", "6", "related_to"]));
+
+    let notes = dir.path().join("notes");
+    let notes_arg = notes.to_str().unwrap();
+    assert_success(&run_amem(&[
+        "export", path, "--format", "markdown", "--output", notes_arg,
+    ]));
+    let session1 = std::fs::read_to_string(notes.join("session-1.md")).unwrap();
+    assert!(session1.contains("## Deploy work\n"));
+    assert!(session1.contains("- The API listens on port 8080\n"));
+    assert!(session1
+        .contains("- Decision: ship on Friday\n  caused_by [[Node 1]], related_to [[Node 6]]\n"));
+    assert!(session1.contains("> the API moved to port 8081\n"));
+    assert!(session1.contains("- Fridays are quiet\n"));
+    assert!(!session1.contains("Backups"));
+    let session2 = std::fs::read_to_string(notes.join("session-2.md")).unwrap();
+    assert_eq!(session2, "- Backups run nightly\n");
+
+    // Facts come back unchanged through import-markdown.
+    let copy = dir.path().join("copy.amem");
+    let copy_path = copy.to_str().unwrap();
+    assert_success(&run_amem(&["create", copy_path]));
+    let session1_path = notes.join("session-1.md");
+    assert_success(&run_amem(&[
+        "import-markdown",
+        session1_path.to_str().unwrap(),
+        copy_path,
+    ]));
+    let facts = |path: &std::path::Path| -> Vec<String> {
+        let graph = AmemReader::read_from_file(path).unwrap();
+        graph
+            .nodes()
+            .iter()
+            .filter(|n| n.event_type == EventType::Fact && n.content != "Fridays are quiet")
+            .filter(|n| !n.content.starts_with("Backups"))
+            .map(|n| n.content.clone())
+            .collect()
+    };
+    assert_eq!(facts(&copy), facts(&file));
+    let copy_graph = AmemReader::read_from_file(&copy).unwrap();
+    assert!(copy_graph
+        .nodes()
+        .iter()
+        .any(|n| n.event_type == EventType::Decision && n.content == "Decision: ship on Friday"));
+
+    // --session writes only that note; Markdown needs --output.
+    let only = dir.path().join("only");
+    assert_success(&run_amem(&[
+        "export",
+        path,
+        "--format",
+        "markdown",
+        "--session",
+        "2",
+        "--output",
+        only.to_str().unwrap(),
+    ]));
+    assert!(only.join("session-2.md").exists());
+    assert!(!only.join("session-1.md").exists());
+    assert_eq!(
+        run_amem(&["export", path, "--format", "markdown"])
+            .status
+            .code(),
+        Some(3)
+    );
+}
+
+#[test]
+fn test_cli_export_markdown_round_trip_limits() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("brain.amem");
+    let path = file.to_str().unwrap();
+    assert_success(&run_amem(&["create", path]));
+    for content in [
+        "Single line fact",
+        "  First line\n  second line  ",
+        "Decision: records are kept for a year",
+    ] {
+        assert_success(&run_amem(&["add", path, "fact", content, "--session", "1"]));
+    }
+
+    let notes = dir.path().join("notes");
+    assert_success(&run_amem(&[
+        "export",
+        path,
+        "--format",
+        "markdown",
+        "--output",
+        notes.to_str().unwrap(),
+    ]));
+    let copy = dir.path().join("copy.amem");
+    let copy_path = copy.to_str().unwrap();
+    assert_success(&run_amem(&["create", copy_path]));
+    assert_success(&run_amem(&[
+        "import-markdown",
+        notes.join("session-1.md").to_str().unwrap(),
+        copy_path,
+    ]));
+
+    // Only the single trimmed line comes back as it was written.
+    let graph = AmemReader::read_from_file(&copy).unwrap();
+    let imported: Vec<(EventType, &str)> = graph
+        .nodes()
+        .iter()
+        .map(|n| (n.event_type, n.content.as_str()))
+        .collect();
+    assert_eq!(
+        imported,
+        vec![
+            (EventType::Fact, "Single line fact"),
+            (EventType::Fact, "First line second line"),
+            (EventType::Decision, "Decision: records are kept for a year"),
+        ]
+    );
+}
+
+#[test]
+fn test_cli_fuzzy_search() {
+    let file = NamedTempFile::new().unwrap();
+    let path = file.path().to_str().unwrap();
+    assert_success(&run_amem(&["create", path]));
+    assert_success(&run_amem(&[
+        "add",
+        path,
+        "decision",
+        "Use a queue for retries",
+    ]));
+    assert_success(&run_amem(&[
+        "add",
+        path,
+        "fact",
+        "Deploys happen on Fridays",
+    ]));
+
+    let output = run_amem(&["--format", "json", "fuzzy-search", path, "retires quee"]);
+    assert_success(&output);
+    let json: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    assert_eq!(json["total"], 1);
+    assert_eq!(json["results"][0]["node_id"], 0);
+    assert!(json["results"][0]["similarity"].as_f64().unwrap() > 0.8);
+    let terms: Vec<&str> = json["results"][0]["matched_terms"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|t| t["term"].as_str().unwrap())
+        .collect();
+    assert!(terms.contains(&"retries"));
+
+    let output = run_amem(&["fuzzy-search", path, "depolys", "--limit", "5"]);
+    assert_success(&output);
+    assert!(stdout_str(&output).contains("Node 1 (fact)"));
+
+    let output = run_amem(&["fuzzy-search", path, "x", "--min-similarity", "1.5"]);
+    assert_eq!(output.status.code(), Some(3));
+}
+
+#[test]
+fn test_cli_temporal_clusters() {
+    let file = NamedTempFile::new().unwrap();
+    let path = file.path().to_str().unwrap();
+    assert_success(&run_amem(&["create", path]));
+    assert_success(&run_amem(&["add", path, "fact", "Schema drafted"]));
+    assert_success(&run_amem(&["add", path, "decision", "Use Postgres"]));
+
+    let output = run_amem(&[
+        "--format",
+        "json",
+        "temporal-clusters",
+        path,
+        "--window",
+        "1d",
+    ]);
+    assert_success(&output);
+    let json: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    let clusters = json.as_array().unwrap();
+    let ids: Vec<u64> = clusters
+        .iter()
+        .flat_map(|c| c["node_ids"].as_array().unwrap().clone())
+        .map(|id| id.as_u64().unwrap())
+        .collect();
+    assert_eq!(ids, vec![0, 1]);
+    let cluster = &clusters[0];
+    assert_eq!(
+        cluster["end_micros"].as_u64().unwrap() - cluster["start_micros"].as_u64().unwrap(),
+        86_400_000_000
+    );
+
+    let output = run_amem(&[
+        "--format",
+        "json",
+        "temporal-clusters",
+        path,
+        "--window",
+        "1h",
+        "--type",
+        "decision",
+    ]);
+    assert_success(&output);
+    let json: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    assert_eq!(json[0]["node_ids"], serde_json::json!([1]));
+    assert_eq!(json[0]["dominant_type"], "decision");
+
+    let output = run_amem(&["temporal-clusters", path, "--window", "0h"]);
+    assert_eq!(output.status.code(), Some(3));
+}
+
+#[test]
+fn test_cli_regex_search() {
+    let file = NamedTempFile::new().unwrap();
+    let path = file.path().to_str().unwrap();
+    assert_success(&run_amem(&["create", path]));
+    assert_success(&run_amem(&["add", path, "fact", "Ticket ABC-123 is open"]));
+    assert_success(&run_amem(&["add", path, "decision", "Close abc-9 first"]));
+
+    let output = run_amem(&["--format", "json", "regex-search", path, r"abc-\d+"]);
+    assert_success(&output);
+    let json: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    assert_eq!(json["total"], 2);
+    assert_eq!(json["results"][0]["matches"][0], "ABC-123");
+    assert_eq!(json["results"][1]["score"], 1.0);
+
+    let output = run_amem(&[
+        "regex-search",
+        path,
+        r"abc-\d+",
+        "--case-sensitive",
+        "--type",
+        "fact,decision",
+    ]);
+    assert_success(&output);
+    let text = stdout_str(&output);
+    assert!(text.contains("Node 1 (decision)"));
+    assert!(!text.contains("Node 0"));
+
+    let output = run_amem(&["regex-search", path, "(unclosed"]);
+    assert_eq!(output.status.code(), Some(3));
+}
+
+#[test]
+fn test_cli_migrate_dry_run() {
+    let dir = tempfile::tempdir().unwrap();
+    let brain = dir.path().join("legacy.amem");
+    let path = brain.to_str().unwrap();
+    assert_success(&run_amem(&["create", path]));
+    assert_success(&run_amem(&["add", path, "fact", "old layout"]));
+
+    let output = run_amem(&["migrate", path, "--dry-run"]);
+    assert_success(&output);
+    assert!(stdout_str(&output).contains("nothing to migrate"));
+
+    let mut bytes = std::fs::read(&brain).unwrap();
+    bytes[4..8].copy_from_slice(&0u32.to_le_bytes());
+    std::fs::write(&brain, &bytes).unwrap();
+
+    let output = run_amem(&["--format", "json", "migrate", path, "--dry-run"]);
+    assert_success(&output);
+    let json: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    assert_eq!(json["needed"], true);
+    assert_eq!(json["from_version"], 0);
+    assert_eq!(json["node_count"], 1);
+    assert!(json["checkpoint_path"].is_string());
+    assert_eq!(std::fs::read(&brain).unwrap(), bytes);
+    assert!(!dir.path().join(".amem-migrations").exists());
+
+    let output = run_amem(&["--format", "json", "migrate", path]);
+    assert_success(&output);
+    let json: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    let checkpoint = json["checkpoint_path"].as_str().unwrap();
+    assert_eq!(std::fs::read(checkpoint).unwrap(), bytes);
+    assert_eq!(
+        &std::fs::read(&brain).unwrap()[4..8],
+        &FORMAT_VERSION.to_le_bytes()
+    );
+}
+
+#[test]
+fn test_cli_search_explain() {
+    let file = NamedTempFile::new().unwrap();
+    let path = file.path().to_str().unwrap();
+    assert_success(&run_amem(&["create", path]));
+    assert_success(&run_amem(&[
+        "add",
+        path,
+        "fact",
+        "rust borrow checker",
+        "--confidence",
+        "0.9",
+    ]));
+    assert_success(&run_amem(&["add", path, "decision", "adopt rust"]));
+
+    let output = run_amem(&[
+        "--format",
+        "json",
+        "query",
+        path,
+        "--event-types",
+        "fact",
+        "--min-confidence",
+        "0.5",
+        "--sort",
+        "confidence",
+        "--explain",
+    ]);
+    assert_success(&output);
+    let json: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    let explain = &json[0]["explain"];
+    assert_eq!(explain["predicates"][0], "type fact in [fact]");
+    assert_eq!(explain["predicates"][1], "confidence 0.90 >= 0.50");
+    assert_eq!(explain["sort_key"]["name"], "confidence");
+
+    let output = run_amem(&["--format", "json", "search", path]);
+    let json: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    assert!(json[0].get("explain").is_none(), "explain is opt-in");
+
+    let output = run_amem(&[
+        "--format",
+        "json",
+        "text-search",
+        path,
+        "rust borrow",
+        "--explain",
+    ]);
+    assert_success(&output);
+    let json: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    let top = &json["results"][0];
+    assert_eq!(top["node_id"], 0);
+    let terms = top["explain"]["terms"].as_array().unwrap();
+    assert_eq!(terms.len(), 2);
+    let sum: f64 = terms.iter().map(|t| t["score"].as_f64().unwrap()).sum();
+    assert!((sum - top["score"].as_f64().unwrap()).abs() < 1e-4);
+    assert_eq!(top["explain"]["confidence_factor"], 1.0);
+
+    let output = run_amem(&["text-search", path, "rust", "--explain"]);
+    assert_success(&output);
+    assert!(stdout_str(&output).contains("why: bm25"));
+}
+
+#[test]
+fn test_cli_link_inferences_dry_run_then_apply() {
+    let file = NamedTempFile::new().unwrap();
+    let path = file.path().to_str().unwrap();
+    assert_success(&run_amem(&["create", path]));
+    assert_success(&run_amem(&[
+        "add",
+        path,
+        "fact",
+        "redis caches session tokens",
+    ]));
+    assert_success(&run_amem(&[
+        "add",
+        path,
+        "inference",
+        "redis caches session tokens for speed",
+    ]));
+
+    let output = run_amem(&["--format", "json", "link-inferences", path]);
+    assert_success(&output);
+    let json: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    assert_eq!(json["dry_run"], true);
+    assert_eq!(json["proposed"].as_array().unwrap().len(), 1);
+    assert_eq!(json["linked"], 0);
+    assert_eq!(
+        AmemReader::read_from_file(file.path())
+            .unwrap()
+            .edge_count(),
+        0
+    );
+
+    let output = run_amem(&["--format", "json", "link-inferences", path, "--apply"]);
+    assert_success(&output);
+    let json: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    assert_eq!(json["linked"], 1);
+    let graph = AmemReader::read_from_file(file.path()).unwrap();
+    assert_eq!(graph.edges()[0].edge_type, EdgeType::Supports);
+}
+
+#[test]
+fn test_cli_session_alias() {
+    let tmp = NamedTempFile::new().unwrap();
+    let path = tmp.path().to_str().unwrap();
+
+    assert_success(&run_amem(&["create", path]));
+    assert_success(&run_amem(&[
+        "add",
+        path,
+        "fact",
+        "alpha fact",
+        "--session",
+        "517",
+    ]));
+    assert_success(&run_amem(&["session-alias", path, "517", "project-alpha"]));
+
+    // --session accepts the alias wherever it accepts an ID.
+    assert_success(&run_amem(&[
+        "add",
+        path,
+        "fact",
+        "second alpha fact",
+        "--session",
+        "project-alpha",
+    ]));
+    let output = run_amem(&["--format", "json", "sessions", path]);
+    assert_success(&output);
+    let sessions: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    assert_eq!(sessions[0]["session_id"], 517);
+    assert_eq!(sessions[0]["alias"], "project-alpha");
+    assert_eq!(sessions[0]["node_count"], 2);
+
+    // Aliases are unique and unknown aliases are usage errors.
+    assert_success(&run_amem(&["add", path, "fact", "other", "--session", "9"]));
+    let output = run_amem(&["session-alias", path, "9", "project-alpha"]);
+    assert!(!output.status.success());
+    let output = run_amem(&["add", path, "fact", "x", "--session", "missing"]);
+    assert_eq!(output.status.code(), Some(3));
 }
 
 #[test]
@@ -530,6 +2454,7 @@ fn test_full_lifecycle() {
                 max_depth: 5,
                 max_results: 50,
                 min_confidence: 0.0,
+                symmetric_undirected: false,
             },
         )
         .unwrap();
@@ -547,6 +2472,7 @@ fn test_full_lifecycle() {
                 node_id: id_fact1,
                 max_depth: 10,
                 dependency_types: vec![EdgeType::CausedBy, EdgeType::Supports],
+                direction: CausalDirection::Downstream,
             },
         )
         .unwrap();
@@ -567,6 +2493,7 @@ fn test_full_lifecycle() {
                 min_similarity: -1.0, // Accept anything
                 event_types: vec![],
                 skip_zero_vectors: false,
+                expand_via_edges: None,
             },
         )
         .unwrap();
@@ -691,6 +2618,7 @@ fn test_portable_file() {
                 max_depth: 3,
                 max_results: 10,
                 min_confidence: 0.0,
+                symmetric_undirected: false,
             },
         )
         .unwrap();
@@ -707,6 +2635,7 @@ fn test_portable_file() {
                 node_id: id1,
                 max_depth: 5,
                 dependency_types: vec![EdgeType::CausedBy, EdgeType::Supports],
+                direction: CausalDirection::Downstream,
             },
         )
         .unwrap();
@@ -724,6 +2653,8 @@ fn test_portable_file() {
                 created_after: None,
                 created_before: None,
                 min_decay_score: None,
+                required_tags: Default::default(),
+                any_tags: Default::default(),
                 max_results: 100,
                 sort_by: PatternSort::MostRecent,
             },
@@ -788,6 +2719,117 @@ fn test_incremental_build() {
     assert_eq!(session2_ids.len(), 50);
 }
 
+#[test]
+fn test_graph_delta_chain_reconstructs_snapshots() {
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    for i in 0..20 {
+        let event = CognitiveEventBuilder::new(EventType::Fact, format!("delta_node_{}", i))
+            .session_id(1)
+            .build();
+        graph.add_node(event).unwrap();
+    }
+    graph
+        .add_edge(Edge::new(1, 0, EdgeType::CausedBy, 0.9))
+        .unwrap();
+    graph
+        .add_edge(Edge::new(2, 1, EdgeType::Supports, 0.5))
+        .unwrap();
+    let base = graph_snapshot(&graph);
+
+    // Delta 1: one new node, one modified node, one new edge.
+    let new_id = graph
+        .add_node(CognitiveEventBuilder::new(EventType::Decision, "delta_added").build())
+        .unwrap();
+    graph.get_node_mut(3).unwrap().confidence = 0.25;
+    graph
+        .add_edge(Edge::new(new_id, 3, EdgeType::CausedBy, 0.8))
+        .unwrap();
+    let snapshot1 = graph_snapshot(&graph);
+
+    // Delta 2: one removed node, one removed edge.
+    graph.remove_node(5).unwrap();
+    graph.remove_edge(2, 1, EdgeType::Supports).unwrap();
+    let snapshot2 = graph_snapshot(&graph);
+
+    let d1 = GraphDelta::diff(&base, &snapshot1, "brain.base.amem.bak", 1).unwrap();
+    assert_eq!(d1.nodes.len(), 2, "only the added and modified nodes");
+    assert_eq!(d1.edges.len(), 1);
+    assert!(d1.removed_nodes.is_empty());
+    let d2 = GraphDelta::diff(&snapshot1, &snapshot2, "brain.base.amem.bak", 2).unwrap();
+    assert_eq!(d2.removed_nodes, vec![5]);
+    assert_eq!(d2.removed_edges, vec![(2, 1, EdgeType::Supports)]);
+    assert_eq!(d2.tombstones.len(), 1);
+    assert!(
+        GraphDelta::diff(&snapshot2, &snapshot2, "brain.base.amem.bak", 3)
+            .unwrap()
+            .is_empty()
+    );
+
+    // Round-trip both deltas through files, then replay on the base.
+    let f1 = NamedTempFile::new().unwrap();
+    let f2 = NamedTempFile::new().unwrap();
+    d1.write_to_file(f1.path()).unwrap();
+    d2.write_to_file(f2.path()).unwrap();
+    let d1 = GraphDelta::read_from_file(f1.path()).unwrap();
+    let d2 = GraphDelta::read_from_file(f2.path()).unwrap();
+    assert_eq!(d2.base, "brain.base.amem.bak");
+    assert_eq!(d2.sequence, 2);
+
+    let restored1 = d1.apply(&base).unwrap();
+    assert_eq!(restored1.node_count(), 21);
+    assert!((restored1.get_node(3).unwrap().confidence - 0.25).abs() < f32::EPSILON);
+    assert_eq!(restored1.get_node(new_id).unwrap().content, "delta_added");
+    assert_eq!(restored1.edge_count(), 3);
+
+    let restored2 = agentic_memory::format::delta::apply_chain(base, &[d1, d2]).unwrap();
+    assert_eq!(restored2.node_count(), snapshot2.node_count());
+    assert!(restored2.get_node(5).is_none());
+    assert_eq!(restored2.tombstones().len(), 1);
+    assert_eq!(restored2.edge_count(), snapshot2.edge_count());
+    assert!(restored2.edges_from(2).is_empty());
+    for node in snapshot2.nodes() {
+        assert_eq!(restored2.get_node(node.id).unwrap().content, node.content);
+    }
+}
+
+#[test]
+fn test_graph_delta_keeps_custom_edge_types() {
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    for i in 0..3 {
+        let event = CognitiveEventBuilder::new(EventType::Fact, format!("custom_{}", i)).build();
+        graph.add_node(event).unwrap();
+    }
+    let implements = graph.register_edge_type("delta-implements").unwrap();
+    graph.add_edge(Edge::new(0, 1, implements, 0.9)).unwrap();
+    let base = graph_snapshot(&graph);
+
+    graph.remove_edge(0, 1, implements).unwrap();
+    graph.add_edge(Edge::new(1, 2, implements, 0.7)).unwrap();
+    let next = graph_snapshot(&graph);
+
+    let mut buf = Vec::new();
+    GraphDelta::diff(&base, &next, "brain.base.amem.bak", 1)
+        .unwrap()
+        .write_to(&mut buf)
+        .unwrap();
+    let delta = GraphDelta::read_from(&mut std::io::Cursor::new(buf)).unwrap();
+    assert_eq!(delta.removed_edges, vec![(0, 1, implements)]);
+
+    let restored = delta.apply(&base).unwrap();
+    assert!(restored.edges_from(0).is_empty());
+    assert_eq!(restored.edges_from(1)[0].edge_type, implements);
+    assert_eq!(restored.edge_type_name(implements), "delta-implements");
+}
+
+/// Persist and reload a graph, as a backup file would hold it.
+fn graph_snapshot(graph: &MemoryGraph) -> MemoryGraph {
+    let mut buf = Vec::new();
+    AmemWriter::new(DEFAULT_DIMENSION)
+        .write_to(graph, &mut buf)
+        .unwrap();
+    AmemReader::read_from(&mut std::io::Cursor::new(buf)).unwrap()
+}
+
 #[test]
 fn test_empty_file_operations() {
     // Create an empty graph
@@ -809,6 +2851,8 @@ fn test_empty_file_operations() {
                 created_after: None,
                 created_before: None,
                 min_decay_score: None,
+                required_tags: Default::default(),
+                any_tags: Default::default(),
                 max_results: 100,
                 sort_by: PatternSort::MostRecent,
             },
@@ -826,6 +2870,7 @@ fn test_empty_file_operations() {
                 min_similarity: 0.0,
                 event_types: vec![],
                 skip_zero_vectors: false,
+                expand_via_edges: None,
             },
         )
         .unwrap();
@@ -841,6 +2886,7 @@ fn test_empty_file_operations() {
             max_depth: 5,
             max_results: 50,
             min_confidence: 0.0,
+            symmetric_undirected: false,
         },
     );
     match result {
@@ -856,6 +2902,7 @@ fn test_empty_file_operations() {
             node_id: 42,
             max_depth: 5,
             dependency_types: vec![EdgeType::CausedBy],
+            direction: CausalDirection::Downstream,
         },
     );
     match result {
@@ -881,3 +2928,96 @@ fn test_empty_file_operations() {
     assert_eq!(reloaded.node_count(), 0);
     assert_eq!(reloaded.edge_count(), 0);
 }
+
+#[test]
+fn test_cli_merge() {
+    let dir = tempfile::tempdir().unwrap();
+    let a = dir.path().join("a.amem");
+    let b = dir.path().join("b.amem");
+    let out = dir.path().join("merged.amem");
+    let (a, b, out) = (
+        a.to_str().unwrap(),
+        b.to_str().unwrap(),
+        out.to_str().unwrap(),
+    );
+    assert_success(&run_amem(&["create", a]));
+    assert_success(&run_amem(&["add", a, "fact", "shared fact"]));
+    assert_success(&run_amem(&["add", a, "decision", "use it"]));
+    assert_success(&run_amem(&["link", a, "1", "0", "caused_by"]));
+    assert_success(&run_amem(&["create", b]));
+    assert_success(&run_amem(&["add", b, "fact", "shared fact"]));
+    assert_success(&run_amem(&["add", b, "fact", "b only"]));
+
+    let output = run_amem(&["--format", "json", "merge", a, b, "--output", out]);
+    assert_success(&output);
+    let json: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    assert_eq!(json["nodes_a"], 2);
+    assert_eq!(json["nodes_b"], 2);
+    assert_eq!(json["merged_nodes"], 3);
+    assert_eq!(json["duplicates_removed"], 1);
+    assert_eq!(json["edges_merged"], 1);
+
+    let output = run_amem(&["--format", "json", "info", out]);
+    assert_success(&output);
+    let info: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    assert_eq!(info["nodes"], 3);
+
+    let output = run_amem(&[
+        "--format",
+        "json",
+        "merge",
+        a,
+        b,
+        "-o",
+        out,
+        "--strategy",
+        "intersect",
+    ]);
+    assert_success(&output);
+    let json: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    assert_eq!(json["merged_nodes"], 1);
+
+    let output = run_amem(&["merge", a, b, "-o", out, "--strategy", "zip"]);
+    assert_eq!(output.status.code(), Some(3));
+}
+
+#[test]
+fn test_cli_temporal_group_by_session() {
+    let file = NamedTempFile::new().unwrap();
+    let path = file.path().to_str().unwrap();
+    assert_success(&run_amem(&["create", path]));
+    for (content, session) in [("first idea", "1"), ("second idea", "2")] {
+        assert_success(&run_amem(&[
+            "add",
+            path,
+            "fact",
+            content,
+            "--session",
+            session,
+        ]));
+    }
+
+    let output = run_amem(&[
+        "--format",
+        "json",
+        "temporal",
+        path,
+        "--range-a",
+        "session:1",
+        "--range-b",
+        "session:2",
+        "--group-by-session",
+    ]);
+    assert_success(&output);
+    let json: serde_json::Value = serde_json::from_str(&stdout_str(&output)).unwrap();
+    let sessions: Vec<u64> = json["session_groups"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|g| g["session_id"].as_u64().unwrap())
+        .collect();
+    assert_eq!(sessions, vec![1, 2]);
+
+    let output = run_amem(&["temporal", path, "--range-a", "yesterday"]);
+    assert_eq!(output.status.code(), Some(3));
+}
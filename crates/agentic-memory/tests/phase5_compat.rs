@@ -3,6 +3,7 @@
 //! Verify that files written by different code versions can be read correctly,
 //! and that the format is forward- and backward-compatible.
 
+use std::collections::HashMap;
 use std::path::Path;
 
 use agentic_memory::format::{AmemReader, AmemWriter};
@@ -77,6 +78,7 @@ fn test_v1_file_opens_in_v2_reader() {
                 event_types: vec![],
                 session_ids: vec![],
                 min_score: 0.0,
+                confidence_weight: 0.0,
             },
         )
         .unwrap();
@@ -95,6 +97,8 @@ fn test_v1_file_opens_in_v2_reader() {
                 top_k: 10,
                 event_types: vec![],
                 edge_types: vec![],
+                seeds: vec![],
+                symmetric_undirected: false,
             },
         )
         .unwrap();
@@ -116,6 +120,7 @@ fn test_v1_file_opens_in_v2_reader() {
                     direction: agentic_memory::TraversalDirection::Both,
                     max_depth: 20,
                     weighted: false,
+                    type_cost: HashMap::new(),
                 },
             )
             .unwrap();
@@ -509,6 +514,7 @@ fn test_bm25_index_rebuilt_on_demand() {
                 event_types: vec![],
                 session_ids: vec![],
                 min_score: 0.0,
+                confidence_weight: 0.0,
             },
         )
         .unwrap();
@@ -563,6 +569,7 @@ fn test_bm25_index_rebuilt_on_demand() {
                 event_types: vec![],
                 session_ids: vec![],
                 min_score: 0.0,
+                confidence_weight: 0.0,
             },
         )
         .unwrap();
@@ -579,6 +586,7 @@ fn test_bm25_index_rebuilt_on_demand() {
                 event_types: vec![],
                 session_ids: vec![],
                 min_score: 0.0,
+                confidence_weight: 0.0,
             },
         )
         .unwrap();
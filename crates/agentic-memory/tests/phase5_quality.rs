@@ -58,3 +58,42 @@ fn test_memory_quality_detects_structural_and_confidence_issues() {
     // Decision has no CausedBy/Supports outgoing edges.
     assert_eq!(report.decisions_without_support_count, 1);
 }
+
+#[test]
+fn test_memory_quality_cross_tabulates_confidence_and_decay() {
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    let mut add = |confidence: f32, decay_score: f32| {
+        let mut event = CognitiveEventBuilder::new(EventType::Fact, "quadrant fact")
+            .session_id(1)
+            .confidence(confidence)
+            .feature_vec(zero_vec())
+            .build();
+        event.decay_score = decay_score;
+        graph.add_node(event).unwrap()
+    };
+    let _fresh = add(0.9, 0.8);
+    let sure_stale = add(0.7, 0.05);
+    let surest_stale = add(0.95, 0.1);
+    let _weak_fresh = add(0.2, 0.9);
+    let _weak_stale = add(0.3, 0.05);
+
+    let report = QueryEngine::new()
+        .memory_quality(
+            &graph,
+            MemoryQualityParams {
+                low_confidence_threshold: 0.45,
+                stale_decay_threshold: 0.2,
+                max_examples: 10,
+            },
+        )
+        .unwrap();
+
+    assert_eq!(report.quadrants.confident_fresh, 1);
+    assert_eq!(report.quadrants.confident_stale, 2);
+    assert_eq!(report.quadrants.weak_fresh, 1);
+    assert_eq!(report.quadrants.weak_stale, 1);
+    assert_eq!(
+        report.confident_stale_examples,
+        vec![surest_stale, sure_stale]
+    );
+}
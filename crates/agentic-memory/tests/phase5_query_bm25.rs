@@ -8,11 +8,14 @@
 //!
 //! Uses only `agentic_memory::` imports and follows existing test conventions.
 
+use agentic_memory::engine::jaro_winkler;
+use agentic_memory::format::{AmemReader, AmemWriter};
 use agentic_memory::graph::MemoryGraph;
 use agentic_memory::types::event::{CognitiveEventBuilder, EventType};
 use agentic_memory::types::DEFAULT_DIMENSION;
 use agentic_memory::{
-    DocLengths, HybridSearchParams, QueryEngine, TermIndex, TextSearchParams, Tokenizer,
+    AmemError, DocLengths, FuzzySearchParams, HybridSearchParams, QueryEngine, RegexSearchParams,
+    TermIndex, TextSearchParams, Tokenizer,
 };
 
 // ==================== Helpers ====================
@@ -324,6 +327,31 @@ fn test_term_index_write_read_roundtrip() {
     }
 }
 
+#[test]
+fn test_reader_loads_persisted_term_index() {
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    for content in ["Rust programming language", "Python data science"] {
+        graph
+            .add_node(CognitiveEventBuilder::new(EventType::Fact, content).build())
+            .unwrap();
+    }
+    let mut bytes = Vec::new();
+    AmemWriter::new(DEFAULT_DIMENSION)
+        .write_to(&graph, &mut bytes)
+        .unwrap();
+
+    // The index block carries the term index and doc lengths, so reading
+    // restores them without re-tokenizing any content.
+    let restored = AmemReader::read_from(&mut std::io::Cursor::new(&bytes)).unwrap();
+    let terms = restored.term_index().expect("term index loaded from file");
+    assert_eq!(terms.doc_count(), 2);
+    assert_eq!(terms.get("python"), &[(1, 1)]);
+    assert_eq!(
+        restored.doc_lengths().expect("doc lengths loaded").get(0),
+        3
+    );
+}
+
 // ==================== DocLengths Tests (2) ====================
 
 #[test]
@@ -462,6 +490,7 @@ fn test_bm25_basic_search() {
         event_types: vec![],
         session_ids: vec![],
         min_score: 0.0,
+        confidence_weight: 0.0,
     };
 
     let results = engine
@@ -523,6 +552,7 @@ fn test_bm25_idf_weighting() {
         event_types: vec![],
         session_ids: vec![],
         min_score: 0.0,
+        confidence_weight: 0.0,
     };
 
     let results = engine
@@ -577,6 +607,7 @@ fn test_bm25_length_normalization() {
         event_types: vec![],
         session_ids: vec![],
         min_score: 0.0,
+        confidence_weight: 0.0,
     };
 
     let results = engine
@@ -620,6 +651,7 @@ fn test_bm25_no_match_returns_empty() {
         event_types: vec![],
         session_ids: vec![],
         min_score: 0.0,
+        confidence_weight: 0.0,
     };
 
     let results = engine
@@ -670,6 +702,7 @@ fn test_bm25_filter_by_type() {
         event_types: vec![EventType::Decision],
         session_ids: vec![],
         min_score: 0.0,
+        confidence_weight: 0.0,
     };
 
     let results = engine
@@ -720,6 +753,7 @@ fn test_bm25_filter_by_session() {
         event_types: vec![],
         session_ids: vec![1, 2],
         min_score: 0.0,
+        confidence_weight: 0.0,
     };
 
     let results = engine
@@ -758,6 +792,7 @@ fn test_bm25_slow_path_matches_fast_path() {
         event_types: vec![],
         session_ids: vec![],
         min_score: 0.0,
+        confidence_weight: 0.0,
     };
     let results_fast = engine
         .text_search(&graph, Some(&term_index), Some(&doc_lengths), fast_params)
@@ -770,6 +805,7 @@ fn test_bm25_slow_path_matches_fast_path() {
         event_types: vec![],
         session_ids: vec![],
         min_score: 0.0,
+        confidence_weight: 0.0,
     };
     let results_slow = engine.text_search(&graph, None, None, slow_params).unwrap();
 
@@ -845,6 +881,7 @@ fn test_bm25_max_results_limit() {
         event_types: vec![],
         session_ids: vec![],
         min_score: 0.0,
+        confidence_weight: 0.0,
     };
 
     let results = engine
@@ -877,6 +914,7 @@ fn test_bm25_scores_are_positive() {
             event_types: vec![],
             session_ids: vec![],
             min_score: 0.0,
+            confidence_weight: 0.0,
         };
 
         let results = engine
@@ -895,6 +933,55 @@ fn test_bm25_scores_are_positive() {
     }
 }
 
+#[test]
+fn test_bm25_confidence_weight_reorders_equal_text() {
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    // Identical text, so pure BM25 ties; the guess is inserted first.
+    for confidence in [0.2, 0.95] {
+        let event = CognitiveEventBuilder::new(EventType::Fact, "deploys run on friday")
+            .session_id(1)
+            .confidence(confidence)
+            .build();
+        graph.add_node(event).unwrap();
+    }
+
+    let tokenizer = Tokenizer::new();
+    let term_index = TermIndex::build(&graph, &tokenizer);
+    let doc_lengths = DocLengths::build(&graph, &tokenizer);
+    let engine = QueryEngine::new();
+    let search = |confidence_weight: f32| {
+        engine
+            .text_search(
+                &graph,
+                Some(&term_index),
+                Some(&doc_lengths),
+                TextSearchParams {
+                    query: "friday deploys".to_string(),
+                    max_results: 10,
+                    event_types: vec![],
+                    session_ids: vec![],
+                    min_score: 0.0,
+                    confidence_weight,
+                },
+            )
+            .unwrap()
+    };
+
+    let pure = search(0.0);
+    assert_eq!(pure.len(), 2);
+    assert!((pure[0].score - pure[1].score).abs() < 1e-6);
+
+    let weighted = search(0.3);
+    assert_eq!(weighted.len(), 2);
+    assert_eq!(
+        weighted[0].node_id, 1,
+        "high-confidence node should rank first"
+    );
+    assert!(weighted[0].score > weighted[1].score);
+    // Weighting only scales scores down from pure BM25.
+    assert!(weighted[0].score <= pure[0].score);
+}
+
 // ==================== Hybrid Search Tests (5) ====================
 
 #[test]
@@ -1175,6 +1262,7 @@ fn test_hybrid_weights() {
         event_types: vec![],
         session_ids: vec![],
         min_score: 0.0,
+        confidence_weight: 0.0,
     };
 
     let hybrid_results = engine
@@ -1257,3 +1345,258 @@ fn test_hybrid_empty_query() {
     // The key assertion is that we get here without panicking.
     let _ = results;
 }
+
+// ==================== All-terms Search + Bloom Prefilter ====================
+
+#[test]
+fn test_text_search_all_with_bloom_roundtrip() {
+    use agentic_memory::{AmemReader, AmemWriter};
+
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    for content in [
+        "rust compiler borrow checker",
+        "rust async runtime tokio",
+        "python async runtime asyncio",
+        "borrow checker rules for rust lifetimes",
+    ] {
+        let event = CognitiveEventBuilder::new(EventType::Fact, content)
+            .feature_vec(zero_vec())
+            .build();
+        graph.add_node(event).unwrap();
+    }
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("bloom.amem");
+    AmemWriter::new(DEFAULT_DIMENSION)
+        .with_term_bloom(0.01)
+        .write_to_file(&graph, &path)
+        .unwrap();
+    let loaded = AmemReader::read_from_file(&path).unwrap();
+    let bloom = loaded.term_bloom().expect("bloom filters persisted");
+    assert_eq!(bloom.len(), 4);
+    assert!(bloom.might_contain(0, "borrow"));
+
+    let engine = QueryEngine::new();
+    let params = || TextSearchParams {
+        query: "rust borrow".to_string(),
+        max_results: 10,
+        event_types: vec![],
+        session_ids: vec![],
+        min_score: 0.0,
+        confidence_weight: 0.0,
+    };
+    let all = engine
+        .text_search_all(&loaded, loaded.term_index(), loaded.doc_lengths(), params())
+        .unwrap();
+    let mut ids: Vec<u64> = all.iter().map(|m| m.node_id).collect();
+    ids.sort_unstable();
+    assert_eq!(ids, vec![0, 3]);
+
+    // Same answer without the bloom filters and without the index.
+    let any = engine
+        .text_search(&graph, None, None, params())
+        .unwrap()
+        .into_iter()
+        .filter(|m| m.matched_terms.len() == 2)
+        .count();
+    assert_eq!(any, all.len());
+}
+
+// ==================== Score Explanation ====================
+
+#[test]
+fn test_text_search_term_scores_sum_to_score() {
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    for content in [
+        "rust borrow checker catches rust bugs",
+        "borrow money from the bank",
+        "rust async runtime",
+    ] {
+        let event = CognitiveEventBuilder::new(EventType::Fact, content)
+            .feature_vec(zero_vec())
+            .build();
+        graph.add_node(event).unwrap();
+    }
+    let term_index = TermIndex::build(&graph, &Tokenizer::new());
+    let doc_lengths = DocLengths::build(&graph, &Tokenizer::new());
+    let engine = QueryEngine::new();
+    let params = || TextSearchParams {
+        query: "rust borrow".to_string(),
+        max_results: 10,
+        event_types: vec![],
+        session_ids: vec![],
+        min_score: 0.0,
+        confidence_weight: 0.0,
+    };
+
+    let fast = engine
+        .text_search(&graph, Some(&term_index), Some(&doc_lengths), params())
+        .unwrap();
+    let slow = engine.text_search(&graph, None, None, params()).unwrap();
+    let all = engine
+        .text_search_all(&graph, Some(&term_index), Some(&doc_lengths), params())
+        .unwrap();
+    for m in fast.iter().chain(&slow).chain(&all) {
+        assert_eq!(m.term_scores.len(), m.matched_terms.len());
+        let sum: f32 = m.term_scores.iter().sum();
+        assert!((sum - m.score).abs() < 1e-5, "{} vs {}", sum, m.score);
+        assert!(m.term_scores.iter().all(|s| *s > 0.0));
+    }
+
+    // Node 0 repeats "rust", so that term outweighs "borrow" there.
+    let top = &fast[0];
+    assert_eq!(top.node_id, 0);
+    let score_of = |term: &str| {
+        let i = top.matched_terms.iter().position(|t| t == term).unwrap();
+        top.term_scores[i]
+    };
+    assert!(score_of("rust") > score_of("borrow"));
+}
+
+// ==================== Fuzzy Search Tests ====================
+
+#[test]
+fn test_jaro_winkler_values() {
+    assert_eq!(jaro_winkler("decision", "decision"), 1.0);
+    assert_eq!(jaro_winkler("abc", "xyz"), 0.0);
+    assert_eq!(jaro_winkler("", ""), 1.0);
+    // Textbook value: MARTHA / MARHTA = 0.961.
+    assert!((jaro_winkler("martha", "marhta") - 0.961).abs() < 0.001);
+    assert!(jaro_winkler("decisoin", "decision") > 0.9);
+    assert!(jaro_winkler("deploy", "database") < 0.7);
+}
+
+#[test]
+fn test_fuzzy_search_finds_typos() {
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    for (event_type, content) in [
+        (EventType::Decision, "Architecture decision: use PostgreSQL"),
+        (EventType::Fact, "The deploy pipeline runs nightly"),
+        (EventType::Fact, "Team prefers decisive code reviews"),
+    ] {
+        graph
+            .add_node(CognitiveEventBuilder::new(event_type, content).build())
+            .unwrap();
+    }
+    let engine = QueryEngine::new();
+    let params = |event_types: Vec<EventType>| FuzzySearchParams {
+        query: "decisoin".to_string(),
+        max_results: 10,
+        min_similarity: 0.9,
+        event_types,
+    };
+
+    let results = engine.fuzzy_search(&graph, params(vec![])).unwrap();
+    assert_eq!(results[0].node_id, 0);
+    assert_eq!(results[0].matched_terms, vec!["decision"]);
+    assert!(results[0].score > 0.9);
+    assert_eq!(results[0].term_scores, vec![results[0].score]);
+    assert!(results.iter().all(|m| m.node_id != 1));
+    assert!(results.windows(2).all(|w| w[0].score >= w[1].score));
+
+    let facts = engine
+        .fuzzy_search(&graph, params(vec![EventType::Fact]))
+        .unwrap();
+    assert!(facts.iter().all(|m| m.node_id != 0));
+
+    let limited = engine
+        .fuzzy_search(
+            &graph,
+            FuzzySearchParams {
+                max_results: 1,
+                min_similarity: 0.0,
+                ..params(vec![])
+            },
+        )
+        .unwrap();
+    assert_eq!(limited.len(), 1);
+}
+
+fn regex_graph() -> MemoryGraph {
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    for (event_type, content) in [
+        (EventType::Fact, "Error 404 on /api/users"),
+        (EventType::Decision, "Retry failed jobs\nerror budget: 3"),
+        (EventType::Fact, "No errors since deploy v2.1"),
+    ] {
+        graph
+            .add_node(CognitiveEventBuilder::new(event_type, content).build())
+            .unwrap();
+    }
+    graph
+}
+
+fn regex_params(pattern: &str) -> RegexSearchParams {
+    RegexSearchParams {
+        pattern: pattern.to_string(),
+        case_sensitive: false,
+        max_results: 10,
+        event_types: vec![],
+    }
+}
+
+fn regex_ids(graph: &MemoryGraph, params: RegexSearchParams) -> Vec<u64> {
+    QueryEngine::new()
+        .regex_search(graph, params)
+        .unwrap()
+        .iter()
+        .map(|m| m.node_id)
+        .collect()
+}
+
+#[test]
+fn test_regex_search_anchors_and_multiline() {
+    let graph = regex_graph();
+    // `^` matches at the start of every line, not only of the content.
+    assert_eq!(regex_ids(&graph, regex_params("^error")), vec![0, 1]);
+    assert_eq!(regex_ids(&graph, regex_params("jobs$")), vec![1]);
+    assert!(regex_ids(&graph, regex_params("^retry.*error")).is_empty());
+    assert_eq!(
+        regex_ids(&graph, regex_params("(?s)^retry.*error")),
+        vec![1]
+    );
+
+    let case_sensitive = RegexSearchParams {
+        case_sensitive: true,
+        ..regex_params("^error")
+    };
+    assert_eq!(regex_ids(&graph, case_sensitive), vec![1]);
+}
+
+#[test]
+fn test_regex_search_character_classes() {
+    let graph = regex_graph();
+    let results = QueryEngine::new()
+        .regex_search(&graph, regex_params(r"\d+"))
+        .unwrap();
+    assert_eq!(
+        results.iter().map(|m| m.node_id).collect::<Vec<_>>(),
+        vec![0, 1, 2]
+    );
+    assert!(results.iter().all(|m| m.score == 1.0));
+    assert_eq!(results[0].matched_terms, vec!["404"]);
+    assert_eq!(results[2].matched_terms, vec!["2", "1"]);
+    assert_eq!(results[2].term_scores, vec![1.0, 1.0]);
+
+    assert_eq!(regex_ids(&graph, regex_params(r"v\d\.\d")), vec![2]);
+    assert_eq!(regex_ids(&graph, regex_params("/[a-z]+/")), vec![0]);
+
+    let decisions = RegexSearchParams {
+        event_types: vec![EventType::Decision],
+        ..regex_params(r"\d")
+    };
+    assert_eq!(regex_ids(&graph, decisions), vec![1]);
+    let limited = RegexSearchParams {
+        max_results: 2,
+        ..regex_params(r"\d")
+    };
+    assert_eq!(regex_ids(&graph, limited), vec![0, 1]);
+}
+
+#[test]
+fn test_regex_search_invalid_pattern() {
+    let err = QueryEngine::new()
+        .regex_search(&regex_graph(), regex_params("(unclosed"))
+        .unwrap_err();
+    assert!(matches!(err, AmemError::InvalidPattern(_)), "got {err:?}");
+}
@@ -1,14 +1,17 @@
 //! Phase 5 tests: Centrality (PageRank, Degree, Betweenness) + Shortest Path.
 
+use std::collections::HashMap;
+
 use agentic_memory::graph::MemoryGraph;
 use agentic_memory::types::edge::{Edge, EdgeType};
 use agentic_memory::types::event::{CognitiveEventBuilder, EventType};
 use agentic_memory::types::DEFAULT_DIMENSION;
 
 use agentic_memory::engine::graph_algo::{
-    CentralityAlgorithm, CentralityParams, ShortestPathParams,
+    CentralityAlgorithm, CentralityCoreParams, CentralityParams, ShortestPathParams,
 };
 use agentic_memory::engine::query::QueryEngine;
+use agentic_memory::engine::sampling::NodeSample;
 use agentic_memory::graph::traversal::TraversalDirection;
 
 // ==================== Helpers ====================
@@ -57,6 +60,8 @@ fn test_pagerank_simple_chain() {
                 top_k: 10,
                 event_types: vec![],
                 edge_types: vec![],
+                seeds: vec![],
+                symmetric_undirected: false,
             },
         )
         .unwrap();
@@ -100,6 +105,8 @@ fn test_pagerank_star_topology() {
                 top_k: 10,
                 event_types: vec![],
                 edge_types: vec![],
+                seeds: vec![],
+                symmetric_undirected: false,
             },
         )
         .unwrap();
@@ -169,6 +176,8 @@ fn test_pagerank_convergence() {
                 top_k: 100,
                 event_types: vec![],
                 edge_types: vec![],
+                seeds: vec![],
+                symmetric_undirected: false,
             },
         )
         .unwrap();
@@ -221,6 +230,8 @@ fn test_pagerank_scores_sum_to_one() {
                 top_k: 100,
                 event_types: vec![],
                 edge_types: vec![],
+                seeds: vec![],
+                symmetric_undirected: false,
             },
         )
         .unwrap();
@@ -257,6 +268,8 @@ fn test_pagerank_isolated_node() {
                 top_k: 10,
                 event_types: vec![],
                 edge_types: vec![],
+                seeds: vec![],
+                symmetric_undirected: false,
             },
         )
         .unwrap();
@@ -278,6 +291,102 @@ fn test_pagerank_isolated_node() {
     );
 }
 
+#[test]
+fn test_pagerank_personalized_to_seeds() {
+    // Two identical chains: A -> B and C -> D. Seeding A ranks its chain
+    // above the other; the unseeded chain gets nothing.
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    let a = add_fact(&mut graph, "A");
+    let b = add_fact(&mut graph, "B");
+    let c = add_fact(&mut graph, "C");
+    let d = add_fact(&mut graph, "D");
+    graph
+        .add_edge(Edge::new(a, b, EdgeType::CausedBy, 1.0))
+        .unwrap();
+    graph
+        .add_edge(Edge::new(c, d, EdgeType::CausedBy, 1.0))
+        .unwrap();
+
+    let qe = QueryEngine::new();
+    let rank = |seeds: Vec<u64>| {
+        let result = qe
+            .centrality(
+                &graph,
+                CentralityParams {
+                    algorithm: CentralityAlgorithm::PageRank { damping: 0.85 },
+                    max_iterations: 200,
+                    tolerance: 1e-7,
+                    top_k: 10,
+                    event_types: vec![],
+                    edge_types: vec![],
+                    seeds,
+                    symmetric_undirected: false,
+                },
+            )
+            .unwrap();
+        let score_of = |id: u64| {
+            result
+                .scores
+                .iter()
+                .find(|(n, _)| *n == id)
+                .map(|(_, s)| *s)
+                .unwrap()
+        };
+        let total: f32 = result.scores.iter().map(|(_, s)| s).sum();
+        (score_of(a), score_of(b), score_of(c), score_of(d), total)
+    };
+
+    let (sa, sb, sc, sd, total) = rank(vec![a]);
+    assert!((total - 1.0).abs() < 1e-3, "scores sum to {}", total);
+    assert!(sb > sd && sa > sc, "seeded chain should outrank the other");
+    assert!(sc.abs() < 1e-6 && sd.abs() < 1e-6);
+
+    // Seeds not in the graph fall back to uniform teleport.
+    let uniform = rank(vec![]);
+    let unknown = rank(vec![999]);
+    assert!((uniform.1 - uniform.3).abs() < 1e-6);
+    assert!((uniform.1 - unknown.1).abs() < 1e-6);
+    assert!((uniform.3 - unknown.3).abs() < 1e-6);
+}
+
+#[test]
+fn test_pagerank_symmetric_undirected() {
+    // A -> B RelatedTo. Directed, B collects A's rank; undirected, the two
+    // are interchangeable and score the same.
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    let a = add_fact(&mut graph, "A");
+    let b = add_fact(&mut graph, "B");
+    graph
+        .add_edge(Edge::new(a, b, EdgeType::RelatedTo, 1.0))
+        .unwrap();
+
+    let qe = QueryEngine::new();
+    let scores = |symmetric_undirected| {
+        let result = qe
+            .centrality(
+                &graph,
+                CentralityParams {
+                    algorithm: CentralityAlgorithm::PageRank { damping: 0.85 },
+                    max_iterations: 200,
+                    tolerance: 1e-7,
+                    top_k: 10,
+                    event_types: vec![],
+                    edge_types: vec![],
+                    seeds: vec![],
+                    symmetric_undirected,
+                },
+            )
+            .unwrap();
+        let score_of = |id: u64| result.scores.iter().find(|(n, _)| *n == id).unwrap().1;
+        (score_of(a), score_of(b))
+    };
+
+    let (sa, sb) = scores(false);
+    assert!(sb > sa);
+    let (sa, sb) = scores(true);
+    assert!((sa - sb).abs() < 1e-5, "{} vs {}", sa, sb);
+}
+
 // ==================== Degree Centrality Test ====================
 
 #[test]
@@ -313,6 +422,8 @@ fn test_degree_centrality() {
                 top_k: 10,
                 event_types: vec![],
                 edge_types: vec![],
+                seeds: vec![],
+                symmetric_undirected: false,
             },
         )
         .unwrap();
@@ -366,6 +477,8 @@ fn test_betweenness_simple() {
                 top_k: 10,
                 event_types: vec![],
                 edge_types: vec![],
+                seeds: vec![],
+                symmetric_undirected: false,
             },
         )
         .unwrap();
@@ -401,6 +514,210 @@ fn test_betweenness_simple() {
     );
 }
 
+#[test]
+fn test_sampled_centrality() {
+    // Hub with twelve spokes pointing at it.
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    let hub = add_fact(&mut graph, "hub");
+    for i in 0..12 {
+        let spoke = add_fact(&mut graph, &format!("spoke {i}"));
+        graph
+            .add_edge(Edge::new(spoke, hub, EdgeType::Supports, 1.0))
+            .unwrap();
+    }
+    let params = |algorithm| CentralityParams {
+        algorithm,
+        max_iterations: 100,
+        tolerance: 1e-6,
+        top_k: 50,
+        event_types: vec![],
+        edge_types: vec![],
+        seeds: vec![],
+        symmetric_undirected: false,
+    };
+    let qe = QueryEngine::new();
+    let sample = NodeSample::new(0.5, 7);
+
+    // PageRank ranks only the sampled nodes, the same ones for the same seed.
+    let pagerank = CentralityAlgorithm::PageRank { damping: 0.85 };
+    let first = qe
+        .centrality_sampled(&graph, params(pagerank.clone()), sample)
+        .unwrap();
+    let second = qe
+        .centrality_sampled(&graph, params(pagerank), sample)
+        .unwrap();
+    assert_eq!(first.scores.len(), 7);
+    assert_eq!(first.scores, second.scores);
+
+    // Sampling every source reproduces exact betweenness.
+    let exact = qe
+        .centrality(&graph, params(CentralityAlgorithm::Betweenness))
+        .unwrap();
+    let full = qe
+        .centrality_sampled(
+            &graph,
+            params(CentralityAlgorithm::Betweenness),
+            NodeSample::new(1.0, 7),
+        )
+        .unwrap();
+    assert_eq!(exact.scores, full.scores);
+    assert_eq!(full.scores[0].0, hub);
+
+    // A partial source sample still ranks every node.
+    let approx = qe
+        .centrality_sampled(&graph, params(CentralityAlgorithm::Betweenness), sample)
+        .unwrap();
+    assert_eq!(approx.scores.len(), 13);
+}
+
+#[test]
+fn test_centrality_ignores_self_edges() {
+    // A -> B -> C, built once plain and once with a loop on B.
+    let build = |with_loop: bool| {
+        let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+        let a = add_fact(&mut graph, "A");
+        let b = add_fact(&mut graph, "B");
+        let c = add_fact(&mut graph, "C");
+        graph
+            .add_edge(Edge::new(a, b, EdgeType::CausedBy, 1.0))
+            .unwrap();
+        graph
+            .add_edge(Edge::new(b, c, EdgeType::CausedBy, 1.0))
+            .unwrap();
+        if with_loop {
+            graph
+                .add_self_edge(Edge::new(b, b, EdgeType::CausedBy, 1.0))
+                .unwrap();
+        }
+        graph
+    };
+    let qe = QueryEngine::new();
+    let rank = |graph: &MemoryGraph, algorithm| {
+        qe.centrality(
+            graph,
+            CentralityParams {
+                algorithm,
+                max_iterations: 100,
+                tolerance: 1e-6,
+                top_k: 10,
+                event_types: vec![],
+                edge_types: vec![],
+                seeds: vec![],
+                symmetric_undirected: false,
+            },
+        )
+        .unwrap()
+        .scores
+    };
+    let (plain, looped) = (build(false), build(true));
+    assert_eq!(looped.edge_count(), 3);
+    for algorithm in [
+        CentralityAlgorithm::PageRank { damping: 0.85 },
+        CentralityAlgorithm::Degree,
+        CentralityAlgorithm::Betweenness,
+    ] {
+        assert_eq!(
+            rank(&plain, algorithm.clone()),
+            rank(&looped, algorithm.clone()),
+            "{algorithm:?}"
+        );
+    }
+}
+
+// ==================== Centrality Core Test ====================
+
+#[test]
+fn test_centrality_core_keeps_connectors() {
+    // Two hubs, each pointed to by five spokes, joined by a chain of two
+    // low-ranked nodes: spokes -> hub_a <- c1 -> c2 -> hub_b <- spokes.
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    let hub_a = add_fact(&mut graph, "hub A");
+    let hub_b = add_fact(&mut graph, "hub B");
+    for hub in [hub_a, hub_b] {
+        for i in 0..5 {
+            let spoke = add_fact(&mut graph, &format!("spoke {i} of {hub}"));
+            graph
+                .add_edge(Edge::new(spoke, hub, EdgeType::Supports, 1.0))
+                .unwrap();
+        }
+    }
+    let c1 = add_fact(&mut graph, "connector 1");
+    let c2 = add_fact(&mut graph, "connector 2");
+    for (from, to) in [(c1, hub_a), (c1, c2), (c2, hub_b)] {
+        graph
+            .add_edge(Edge::new(from, to, EdgeType::RelatedTo, 1.0))
+            .unwrap();
+    }
+
+    let engine = QueryEngine::new();
+    let params = |keep_connectors| CentralityCoreParams {
+        min_centrality: 0.1,
+        keep_connectors,
+        damping: 0.85,
+        max_iterations: 100,
+    };
+
+    // Without connectors the two hubs survive but are disconnected.
+    let core = engine.centrality_core(&graph, params(false)).unwrap();
+    assert_eq!(core.total_nodes, 14);
+    assert_eq!(core.core_ids, vec![hub_a, hub_b]);
+    assert!(core.connector_ids.is_empty());
+    assert!(core.edges.is_empty());
+
+    // With connectors the chain between them is kept, and nothing else.
+    let core = engine.centrality_core(&graph, params(true)).unwrap();
+    assert_eq!(core.core_ids, vec![hub_a, hub_b]);
+    assert_eq!(core.connector_ids, vec![c1, c2]);
+    assert_eq!(core.nodes.len(), 4);
+    assert_eq!(core.edges.len(), 3);
+}
+
+// ==================== Connected Components Test ====================
+
+#[test]
+fn test_connected_components_two_cliques() {
+    // Two 4-node cliques with no edge between them, plus one lone node.
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    let mut cliques = Vec::new();
+    for name in ["left", "right"] {
+        let ids: Vec<u64> = (0..4)
+            .map(|i| add_fact(&mut graph, &format!("{name} {i}")))
+            .collect();
+        for (i, &a) in ids.iter().enumerate() {
+            for &b in &ids[i + 1..] {
+                graph
+                    .add_edge(Edge::new(a, b, EdgeType::RelatedTo, 1.0))
+                    .unwrap();
+            }
+        }
+        cliques.push(ids);
+    }
+
+    assert_eq!(graph.connected_components(), cliques);
+
+    let lone = add_fact(&mut graph, "island");
+    let components = graph.connected_components();
+    assert_eq!(components.len(), 3);
+    assert_eq!(components[2], vec![lone]);
+
+    let result = QueryEngine::new()
+        .centrality(
+            &graph,
+            CentralityParams {
+                algorithm: CentralityAlgorithm::Degree,
+                max_iterations: 0,
+                tolerance: 0.0,
+                top_k: 10,
+                event_types: vec![],
+                edge_types: vec![],
+                seeds: vec![],
+                symmetric_undirected: false,
+            },
+        )
+        .unwrap();
+    assert_eq!(result.components, components);
+}
+
 // ==================== Shortest Path Tests ====================
 
 #[test]
@@ -425,6 +742,7 @@ fn test_shortest_path_direct_edge() {
                 direction: TraversalDirection::Forward,
                 max_depth: 10,
                 weighted: false,
+                type_cost: HashMap::new(),
             },
         )
         .unwrap();
@@ -464,6 +782,7 @@ fn test_shortest_path_two_hops() {
                 direction: TraversalDirection::Forward,
                 max_depth: 10,
                 weighted: false,
+                type_cost: HashMap::new(),
             },
         )
         .unwrap();
@@ -496,6 +815,7 @@ fn test_shortest_path_no_path() {
                 direction: TraversalDirection::Forward,
                 max_depth: 100,
                 weighted: false,
+                type_cost: HashMap::new(),
             },
         )
         .unwrap();
@@ -546,6 +866,7 @@ fn test_shortest_path_bidirectional_faster() {
                 direction: TraversalDirection::Forward,
                 max_depth: 20,
                 weighted: false,
+                type_cost: HashMap::new(),
             },
         )
         .unwrap();
@@ -596,6 +917,7 @@ fn test_shortest_path_respects_edge_types() {
                 direction: TraversalDirection::Forward,
                 max_depth: 10,
                 weighted: false,
+                type_cost: HashMap::new(),
             },
         )
         .unwrap();
@@ -616,6 +938,7 @@ fn test_shortest_path_respects_edge_types() {
                 direction: TraversalDirection::Forward,
                 max_depth: 10,
                 weighted: false,
+                type_cost: HashMap::new(),
             },
         )
         .unwrap();
@@ -660,6 +983,7 @@ fn test_shortest_path_weighted() {
                 direction: TraversalDirection::Forward,
                 max_depth: 10,
                 weighted: true,
+                type_cost: HashMap::new(),
             },
         )
         .unwrap();
@@ -678,6 +1002,74 @@ fn test_shortest_path_weighted() {
     );
 }
 
+#[test]
+fn test_shortest_path_type_cost_changes_route() {
+    // A -> B  (RelatedTo, weight 1.0)
+    // A -> C -> B  (Supports, weight 1.0 each)
+    // With supports=0.2: direct costs 1.0, via C costs 0.4, so the path goes
+    // through C. With supports=1.0 the direct edge is cheaper again.
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    let a = add_fact(&mut graph, "A");
+    let b = add_fact(&mut graph, "B");
+    let c = add_fact(&mut graph, "C");
+
+    graph
+        .add_edge(Edge::new(a, b, EdgeType::RelatedTo, 1.0))
+        .unwrap();
+    graph
+        .add_edge(Edge::new(a, c, EdgeType::Supports, 1.0))
+        .unwrap();
+    graph
+        .add_edge(Edge::new(c, b, EdgeType::Supports, 1.0))
+        .unwrap();
+
+    let qe = QueryEngine::new();
+    let path_with = |supports: f32| {
+        qe.shortest_path(
+            &graph,
+            ShortestPathParams {
+                source_id: a,
+                target_id: b,
+                edge_types: vec![],
+                direction: TraversalDirection::Forward,
+                max_depth: 10,
+                weighted: true,
+                type_cost: HashMap::from([(EdgeType::Supports, supports)]),
+            },
+        )
+        .unwrap()
+    };
+
+    let cheap = path_with(0.2);
+    assert_eq!(cheap.path, vec![a, c, b]);
+    assert!((cheap.cost - 0.4).abs() < 0.01, "got {}", cheap.cost);
+    assert!(cheap
+        .edges
+        .iter()
+        .all(|e| e.edge_type == EdgeType::Supports));
+
+    let even = path_with(1.0);
+    assert_eq!(even.path, vec![a, b]);
+    assert!((even.cost - 1.0).abs() < 0.01, "got {}", even.cost);
+    assert_eq!(even.edges[0].edge_type, EdgeType::RelatedTo);
+
+    // Negative costs would break Dijkstra and are rejected.
+    assert!(qe
+        .shortest_path(
+            &graph,
+            ShortestPathParams {
+                source_id: a,
+                target_id: b,
+                edge_types: vec![],
+                direction: TraversalDirection::Forward,
+                max_depth: 10,
+                weighted: true,
+                type_cost: HashMap::from([(EdgeType::Supports, -1.0)]),
+            },
+        )
+        .is_err());
+}
+
 #[test]
 fn test_shortest_path_max_depth() {
     // Build a chain of 11 nodes (depth 10 from first to last).
@@ -707,6 +1099,7 @@ fn test_shortest_path_max_depth() {
                 direction: TraversalDirection::Forward,
                 max_depth: 5,
                 weighted: false,
+                type_cost: HashMap::new(),
             },
         )
         .unwrap();
@@ -727,6 +1120,7 @@ fn test_shortest_path_max_depth() {
                 direction: TraversalDirection::Forward,
                 max_depth: 15,
                 weighted: false,
+                type_cost: HashMap::new(),
             },
         )
         .unwrap();
@@ -759,6 +1153,7 @@ fn test_shortest_path_same_node() {
                 direction: TraversalDirection::Forward,
                 max_depth: 10,
                 weighted: false,
+                type_cost: HashMap::new(),
             },
         )
         .unwrap();
@@ -5,9 +5,9 @@ use std::path::PathBuf;
 
 use agentic_memory::types::{CognitiveEventBuilder, DEFAULT_DIMENSION};
 use agentic_memory::{
-    AnalogicalAnchor, AnalogicalParams, BeliefRevisionParams, ChangeType, ConsolidationOp,
-    ConsolidationParams, DriftParams, Edge, EdgeType, EventType, GapDetectionParams, GapSeverity,
-    GapType, MemoryGraph, QueryEngine,
+    AnalogicalAnchor, AnalogicalParams, BeliefRevisionParams, ChangeType, CompactionParams,
+    ConsolidationOp, ConsolidationParams, DedupDecision, DeleteFilter, DriftParams, Edge, EdgeType,
+    EventType, GapDetectionParams, GapSeverity, GapType, KeepTopParams, MemoryGraph, QueryEngine,
 };
 
 // ==================== Helpers ====================
@@ -1107,6 +1107,78 @@ fn test_consolidate_dedup_detects_duplicates() {
     );
 }
 
+#[test]
+fn test_duplicate_candidates_review_and_apply() {
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    let mut near = basis_vec(0, 1.0);
+    near[1] = 0.6; // cosine ~0.86 with basis 0: below consolidate's usual 0.95
+    let facts = [
+        (
+            "Rust compiler guarantees memory safety at compile time",
+            basis_vec(0, 1.0),
+        ),
+        (
+            "Rust compiler guarantees memory safety during compilation",
+            near,
+        ),
+        ("The deploy pipeline runs nightly", basis_vec(5, 1.0)),
+    ];
+    let ids: Vec<u64> = facts
+        .iter()
+        .map(|(content, fv)| {
+            let event = CognitiveEventBuilder::new(EventType::Fact, *content)
+                .session_id(1)
+                .feature_vec(fv.clone())
+                .build();
+            graph.add_node(event).unwrap()
+        })
+        .collect();
+
+    let qe = QueryEngine::new();
+    assert!(qe.duplicate_candidates(&graph, 0.95, None).is_empty());
+
+    let candidates = qe.duplicate_candidates(&graph, 0.8, None);
+    assert_eq!(candidates.len(), 1);
+    assert_eq!(
+        (candidates[0].first, candidates[0].second),
+        (ids[0], ids[1])
+    );
+    assert_eq!(graph.edge_count(), 0, "listing candidates must not mutate");
+
+    // A decision naming a missing node aborts the whole batch.
+    let mut bogus = candidates[0];
+    bogus.second = 999;
+    let err = qe.apply_dedup_decisions(
+        &mut graph,
+        &[
+            (candidates[0], DedupDecision::MergeIntoSecond),
+            (bogus, DedupDecision::MergeIntoFirst),
+        ],
+    );
+    assert!(err.is_err());
+    assert_eq!(graph.edge_count(), 0);
+
+    let merged = qe
+        .apply_dedup_decisions(
+            &mut graph,
+            &[(candidates[0], DedupDecision::MergeIntoSecond)],
+        )
+        .unwrap();
+    assert_eq!(merged, 1);
+    let edge = graph.edges_from(ids[1])[0];
+    assert_eq!(edge.edge_type, EdgeType::Supersedes);
+    assert_eq!(edge.target_id, ids[0]);
+
+    for decision in [
+        DedupDecision::KeepBoth,
+        DedupDecision::MergeIntoFirst,
+        DedupDecision::MergeIntoSecond,
+        DedupDecision::Skip,
+    ] {
+        assert_eq!(DedupDecision::from_name(decision.name()), Some(decision));
+    }
+}
+
 #[test]
 fn test_consolidate_dedup_keeps_higher_confidence() {
     // Fact A (confidence 0.7) and near-duplicate B (confidence 0.9).
@@ -1299,6 +1371,261 @@ fn test_consolidate_backup_is_original() {
     );
 }
 
+#[test]
+fn test_compact_older_than_respects_access_and_kept_types() {
+    let day = 86_400_000_000u64;
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    let mut add = |event_type, content: &str, created_day: u64| {
+        let event = CognitiveEventBuilder::new(event_type, content)
+            .session_id(1)
+            .created_at(created_day * day)
+            .feature_vec(zero_vec())
+            .build();
+        graph.add_node(event).unwrap()
+    };
+    let stale_fact = add(EventType::Fact, "Old fact nobody reads", 1);
+    let stale_skill = add(EventType::Skill, "Old but protected skill", 2);
+    let touched = add(EventType::Decision, "Old decision still consulted", 3);
+    let stale_inference = add(EventType::Inference, "Old inference", 4);
+    let fresh = add(EventType::Fact, "Recent fact", 80);
+    graph
+        .add_edge(Edge::new(fresh, stale_fact, EdgeType::RelatedTo, 1.0))
+        .unwrap();
+    graph.record_access(touched, 75 * day).unwrap();
+
+    let report = QueryEngine::new()
+        .compact_older_than(
+            &mut graph,
+            &CompactionParams {
+                cutoff: 50 * day,
+                keep_types: vec![EventType::Skill],
+            },
+        )
+        .unwrap();
+
+    assert_eq!(report.removed, vec![stale_fact, stale_inference]);
+    assert_eq!(
+        report.removed_by_type,
+        vec![(EventType::Fact, 1), (EventType::Inference, 1)]
+    );
+    assert!(graph.get_node(stale_fact).is_none());
+    assert!(graph.get_node(stale_skill).is_some());
+    assert!(graph.get_node(touched).is_some());
+    assert!(graph.get_node(fresh).is_some());
+    assert!(graph.edges_from(fresh).is_empty());
+    assert_eq!(graph.tombstones().len(), 2);
+}
+
+#[test]
+fn test_delete_where_matches_all_criteria_and_drops_edges() {
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    let mut add = |event_type, content: &str, session_id, decay_score| {
+        let event = CognitiveEventBuilder::new(event_type, content)
+            .session_id(session_id)
+            .created_at(1_000)
+            .feature_vec(zero_vec())
+            .build();
+        let id = graph.add_node(event).unwrap();
+        graph.get_node_mut(id).unwrap().decay_score = decay_score;
+        id
+    };
+    let stale = add(EventType::Inference, "Stale inference", 1, 0.05);
+    let fresh = add(EventType::Inference, "Fresh inference", 1, 0.9);
+    let stale_fact = add(EventType::Fact, "Stale fact", 1, 0.05);
+    let other_session = add(EventType::Inference, "Stale elsewhere", 2, 0.05);
+    graph
+        .add_edge(Edge::new(stale, stale_fact, EdgeType::CausedBy, 1.0))
+        .unwrap();
+    graph
+        .add_edge(Edge::new(fresh, stale, EdgeType::Supports, 1.0))
+        .unwrap();
+    graph
+        .add_edge(Edge::new(fresh, stale_fact, EdgeType::RelatedTo, 1.0))
+        .unwrap();
+
+    let qe = QueryEngine::new();
+    let empty = qe
+        .delete_where(&mut graph, &DeleteFilter::default())
+        .unwrap();
+    assert_eq!(empty.deleted_count, 0);
+    assert_eq!(graph.node_count(), 4);
+
+    let report = qe
+        .delete_where(
+            &mut graph,
+            &DeleteFilter {
+                event_types: vec![EventType::Inference],
+                decay_below: Some(0.1),
+                session_ids: vec![1],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    assert_eq!(report.deleted_count, 1);
+    assert_eq!(report.deleted_ids, vec![stale]);
+    assert_eq!(report.edges_removed, 2);
+    assert!(graph.get_node(stale).is_none());
+    assert!(graph.get_node(other_session).is_some());
+    assert_eq!(graph.edge_count(), 1);
+    assert_eq!(graph.tombstones().len(), 1);
+
+    let report = qe
+        .delete_where(
+            &mut graph,
+            &DeleteFilter {
+                decay_below: Some(0.1),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    assert_eq!(report.deleted_ids, vec![stale_fact, other_session]);
+    assert_eq!(report.edges_removed, 1);
+    assert_eq!(graph.node_count(), 1);
+}
+
+#[test]
+fn test_compact_keep_top_ranks_pins_and_rewires() {
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    let mut add = |event_type, content: &str, confidence| {
+        let event = CognitiveEventBuilder::new(event_type, content)
+            .session_id(1)
+            .created_at(1_000)
+            .confidence(confidence)
+            .feature_vec(zero_vec())
+            .build();
+        graph.add_node(event).unwrap()
+    };
+    let episode = add(EventType::Episode, "Session summary", 0.1);
+    let strong = add(EventType::Fact, "Well supported fact", 0.9);
+    let pinned = add(EventType::Fact, "Pinned but shaky fact", 0.3);
+    let weak = add(EventType::Fact, "Weak fact", 0.2);
+    let decision = add(
+        EventType::Decision,
+        "Decision resting on the weak fact",
+        0.8,
+    );
+    graph
+        .add_edge(Edge::new(decision, weak, EdgeType::CausedBy, 1.0))
+        .unwrap();
+
+    let report = QueryEngine::new()
+        .compact_keep_top(
+            &mut graph,
+            &KeepTopParams {
+                keep: 3,
+                keep_types: vec![EventType::Episode],
+                pinned: vec![pinned],
+                rewire_to_episode: true,
+                now: 1_000,
+            },
+        )
+        .unwrap();
+
+    // Episode and pin take two slots; the linked decision outranks the rest.
+    assert_eq!(report.pinned_kept, 2);
+    assert_eq!(report.removed, vec![strong, weak]);
+    let cutoff = report.cutoff_score.unwrap();
+    assert!((cutoff - 0.8 * (1.0 + 2f32.ln())).abs() < 1e-4, "{cutoff}");
+    assert!(graph.get_node(pinned).is_some());
+    assert!(graph.get_node(episode).is_some());
+
+    assert_eq!(report.rewired_edges, 1);
+    let edges = graph.edges_from(decision);
+    assert_eq!(edges.len(), 1);
+    assert_eq!(edges[0].target_id, episode);
+    assert_eq!(edges[0].edge_type, EdgeType::CausedBy);
+    assert_eq!(graph.tombstones().len(), 2);
+}
+
+#[test]
+fn test_compaction_never_removes_pinned_nodes() {
+    let day = 86_400_000_000u64;
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    for (i, confidence) in [0.1f32, 0.9, 0.2].into_iter().enumerate() {
+        let event = CognitiveEventBuilder::new(EventType::Fact, format!("old fact {i}"))
+            .session_id(1)
+            .created_at(day)
+            .confidence(confidence)
+            .feature_vec(zero_vec())
+            .build();
+        graph.add_node(event).unwrap();
+    }
+    graph.get_node_mut(0).unwrap().pinned = true;
+    let qe = QueryEngine::new();
+
+    let mut aged = graph.clone();
+    let report = qe
+        .compact_older_than(
+            &mut aged,
+            &CompactionParams {
+                cutoff: 50 * day,
+                keep_types: vec![],
+            },
+        )
+        .unwrap();
+    assert_eq!(report.removed, vec![1, 2]);
+    assert!(aged.get_node(0).is_some());
+
+    let report = qe
+        .compact_keep_top(
+            &mut graph,
+            &KeepTopParams {
+                keep: 1,
+                keep_types: vec![],
+                pinned: vec![],
+                rewire_to_episode: false,
+                now: day,
+            },
+        )
+        .unwrap();
+    assert_eq!(report.pinned_kept, 1);
+    assert_eq!(report.removed, vec![1, 2]);
+    assert!(graph.get_node(0).is_some());
+}
+
+#[test]
+fn test_link_inferences_proposes_earlier_same_session_facts() {
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    let mut add = |event_type, content: &str, session, created_at| {
+        let event = CognitiveEventBuilder::new(event_type, content)
+            .session_id(session)
+            .created_at(created_at)
+            .feature_vec(zero_vec())
+            .build();
+        graph.add_node(event).unwrap()
+    };
+    let fact = add(EventType::Fact, "postgres handles the write load", 1, 100);
+    let unrelated = add(EventType::Fact, "the office plants need water", 1, 110);
+    let other_session = add(EventType::Fact, "postgres handles the write load", 2, 120);
+    let inference = add(
+        EventType::Inference,
+        "postgres handles the write load well enough",
+        1,
+        200,
+    );
+    let later = add(EventType::Fact, "postgres handles the write load", 1, 300);
+
+    let qe = QueryEngine::new();
+    let proposals = qe.propose_inference_support(&graph, 0.3, 3);
+    assert_eq!(proposals.len(), 1);
+    assert_eq!(proposals[0].fact, fact);
+    assert_eq!(proposals[0].inference, inference);
+    assert!(proposals[0].score > 0.5);
+    for excluded in [unrelated, other_session, later] {
+        assert!(proposals.iter().all(|p| p.fact != excluded));
+    }
+
+    assert_eq!(
+        qe.apply_support_proposals(&mut graph, &proposals).unwrap(),
+        1
+    );
+    assert!(graph
+        .edges_to(inference)
+        .iter()
+        .any(|e| e.source_id == fact && e.edge_type == EdgeType::Supports));
+    assert!(qe.propose_inference_support(&graph, 0.3, 3).is_empty());
+}
+
 // ==================== Drift Detection Tests ====================
 
 #[test]
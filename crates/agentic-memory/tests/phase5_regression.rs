@@ -1,5 +1,6 @@
 //! Phase 5 regression tests — verify new code does not break existing behavior.
 
+use std::collections::HashMap;
 use std::path::Path;
 
 use agentic_memory::format::{AmemReader, AmemWriter};
@@ -79,6 +80,7 @@ fn test_old_file_readable_by_new_code() {
             max_depth: 3,
             max_results: 100,
             min_confidence: 0.0,
+            symmetric_undirected: false,
         },
     );
     assert!(
@@ -97,6 +99,8 @@ fn test_old_file_readable_by_new_code() {
             created_after: None,
             created_before: None,
             min_decay_score: None,
+            required_tags: HashMap::new(),
+            any_tags: Vec::new(),
             max_results: 100,
             sort_by: agentic_memory::PatternSort::MostRecent,
         },
@@ -113,6 +117,7 @@ fn test_old_file_readable_by_new_code() {
             min_similarity: -1.0,
             event_types: vec![],
             skip_zero_vectors: false,
+            expand_via_edges: None,
         },
     );
     assert!(
@@ -308,6 +313,7 @@ fn test_existing_query_engine_methods_unchanged() {
                 max_depth: 5,
                 max_results: 50,
                 min_confidence: 0.0,
+                symmetric_undirected: false,
             },
         )
         .unwrap();
@@ -325,6 +331,8 @@ fn test_existing_query_engine_methods_unchanged() {
                 created_after: None,
                 created_before: None,
                 min_decay_score: None,
+                required_tags: HashMap::new(),
+                any_tags: Vec::new(),
                 max_results: 100,
                 sort_by: agentic_memory::PatternSort::HighestConfidence,
             },
@@ -340,6 +348,7 @@ fn test_existing_query_engine_methods_unchanged() {
             agentic_memory::TemporalParams {
                 range_a: agentic_memory::TimeRange::Session(0),
                 range_b: agentic_memory::TimeRange::Session(1),
+                group_by_session: false,
             },
         )
         .unwrap();
@@ -354,6 +363,7 @@ fn test_existing_query_engine_methods_unchanged() {
                 node_id: 0,
                 max_depth: 5,
                 dependency_types: vec![EdgeType::CausedBy, EdgeType::Supports],
+                direction: agentic_memory::CausalDirection::Downstream,
             },
         )
         .unwrap();
@@ -369,6 +379,7 @@ fn test_existing_query_engine_methods_unchanged() {
                 min_similarity: -1.0,
                 event_types: vec![],
                 skip_zero_vectors: false,
+                expand_via_edges: None,
             },
         )
         .unwrap();
@@ -410,6 +421,7 @@ fn test_empty_graph_all_new_queries_return_empty() {
                 event_types: vec![],
                 session_ids: vec![],
                 min_score: 0.0,
+                confidence_weight: 0.0,
             },
         )
         .unwrap();
@@ -451,6 +463,8 @@ fn test_empty_graph_all_new_queries_return_empty() {
                 top_k: 10,
                 event_types: vec![],
                 edge_types: vec![],
+                seeds: vec![],
+                symmetric_undirected: false,
             },
         )
         .unwrap();
@@ -592,6 +606,7 @@ fn test_single_node_graph_all_new_queries_safe() {
                 event_types: vec![],
                 session_ids: vec![],
                 min_score: 0.0,
+                confidence_weight: 0.0,
             },
         )
         .unwrap();
@@ -629,6 +644,8 @@ fn test_single_node_graph_all_new_queries_safe() {
                 top_k: 10,
                 event_types: vec![],
                 edge_types: vec![],
+                seeds: vec![],
+                symmetric_undirected: false,
             },
         )
         .unwrap();
@@ -653,6 +670,7 @@ fn test_single_node_graph_all_new_queries_safe() {
                 direction: TraversalDirection::Both,
                 max_depth: 10,
                 weighted: false,
+                type_cost: HashMap::new(),
             },
         )
         .unwrap();
@@ -885,6 +903,7 @@ fn test_write_read_roundtrip_with_new_indexes() {
                 event_types: vec![],
                 session_ids: vec![],
                 min_score: 0.0,
+                confidence_weight: 0.0,
             },
         )
         .unwrap();
@@ -0,0 +1,113 @@
+//! Phase 6: AES-256-GCM encrypted .amem files (`encryption` feature).
+#![cfg(feature = "encryption")]
+
+use agentic_memory::format::{AmemReader, AmemWriter};
+use agentic_memory::graph::MemoryGraph;
+use agentic_memory::types::edge::{Edge, EdgeType};
+use agentic_memory::types::encryption::{EncryptionKey, ENCRYPTED_HEADER_SIZE};
+use agentic_memory::types::error::AmemError;
+use agentic_memory::types::event::{CognitiveEventBuilder, EventType};
+use agentic_memory::types::DEFAULT_DIMENSION;
+
+fn key(seed: u8) -> EncryptionKey {
+    EncryptionKey::from_bytes([seed; 32])
+}
+
+fn sample_graph() -> MemoryGraph {
+    let mut graph = MemoryGraph::new(DEFAULT_DIMENSION);
+    let a = graph
+        .add_node(CognitiveEventBuilder::new(EventType::Fact, "user prefers Rust").build())
+        .unwrap();
+    let b = graph
+        .add_node(CognitiveEventBuilder::new(EventType::Decision, "use Rust for the CLI").build())
+        .unwrap();
+    graph
+        .add_edge(Edge::new(b, a, EdgeType::CausedBy, 1.0))
+        .unwrap();
+    graph
+}
+
+#[test]
+fn test_encrypted_roundtrip_uses_fresh_nonce() {
+    let dir = tempfile::tempdir().unwrap();
+    let first = dir.path().join("first.amem");
+    let second = dir.path().join("second.amem");
+    let graph = sample_graph();
+    let writer = AmemWriter::new(DEFAULT_DIMENSION);
+    writer.write_encrypted(&graph, &first, &key(7)).unwrap();
+    writer.write_encrypted(&graph, &second, &key(7)).unwrap();
+
+    let a = std::fs::read(&first).unwrap();
+    let b = std::fs::read(&second).unwrap();
+    assert_eq!(&a[..4], b"AMEC");
+    assert_ne!(a, b, "each write must draw a new nonce");
+    assert_ne!(a[8..ENCRYPTED_HEADER_SIZE], b[8..ENCRYPTED_HEADER_SIZE]);
+
+    for path in [&first, &second] {
+        assert!(AmemReader::is_encrypted_file(path).unwrap());
+        let read = AmemReader::read_encrypted(path, &key(7)).unwrap();
+        assert_eq!(read.node_count(), 2);
+        assert_eq!(read.edge_count(), 1);
+        assert_eq!(read.get_node(0).unwrap().content, "user prefers Rust");
+    }
+}
+
+#[test]
+fn test_wrong_key_is_corrupt() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("brain.amem");
+    AmemWriter::new(DEFAULT_DIMENSION)
+        .write_encrypted(&sample_graph(), &path, &key(1))
+        .unwrap();
+
+    let err = AmemReader::read_encrypted(&path, &key(2)).err().unwrap();
+    assert!(matches!(err, AmemError::Corrupt(_)), "got {err:?}");
+}
+
+#[test]
+fn test_plain_reader_refuses_encrypted_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("brain.amem");
+    AmemWriter::new(DEFAULT_DIMENSION)
+        .with_encryption_key(key(3))
+        .write_to_file(&sample_graph(), &path)
+        .unwrap();
+
+    let err = AmemReader::read_from_file(&path).err().unwrap();
+    assert!(matches!(err, AmemError::InvalidKey(_)), "got {err:?}");
+    assert_eq!(
+        AmemReader::read_encrypted(&path, &key(3))
+            .unwrap()
+            .node_count(),
+        2
+    );
+}
+
+#[test]
+fn test_key_parsing() {
+    let hex = "2a".repeat(32);
+    assert_eq!(EncryptionKey::from_hex(&hex).unwrap(), key(0x2a));
+    assert!(EncryptionKey::from_hex("2a2a").is_err());
+    assert_eq!(EncryptionKey::from_arg(&hex).unwrap(), key(0x2a));
+
+    let derived = EncryptionKey::from_passphrase("correct horse battery staple");
+    assert_eq!(
+        derived,
+        EncryptionKey::from_passphrase("correct horse battery staple")
+    );
+    assert_ne!(
+        derived,
+        EncryptionKey::from_passphrase("another passphrase")
+    );
+    assert_eq!(format!("{:?}", key(0x2a)), "EncryptionKey(<redacted>)");
+}
+
+#[test]
+fn test_cli_key_cannot_be_replaced() {
+    use agentic_memory::cli::commands::set_encryption_key;
+
+    set_encryption_key(key(4)).unwrap();
+    set_encryption_key(key(4)).unwrap();
+    let err = set_encryption_key(key(5)).err().unwrap();
+    assert!(matches!(err, AmemError::InvalidKey(_)), "got {err:?}");
+}
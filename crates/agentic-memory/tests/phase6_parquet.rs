@@ -0,0 +1,99 @@
+//! Phase 6: Parquet export (`parquet` feature).
+#![cfg(feature = "parquet")]
+
+use std::fs::File;
+
+use agentic_memory::format::write_parquet;
+use agentic_memory::graph::MemoryGraph;
+use agentic_memory::types::edge::{Edge, EdgeType};
+use agentic_memory::types::event::{CognitiveEventBuilder, EventType};
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::record::RowAccessor;
+
+fn sample_graph() -> MemoryGraph {
+    let mut graph = MemoryGraph::new(4);
+    let mut ids = Vec::new();
+    for (i, event_type) in [EventType::Fact, EventType::Decision, EventType::Inference]
+        .into_iter()
+        .cycle()
+        .take(10)
+        .enumerate()
+    {
+        let node = CognitiveEventBuilder::new(event_type, format!("memory number {i}"))
+            .session_id(i as u32 / 4)
+            .confidence(0.5 + i as f32 / 20.0)
+            .feature_vec(vec![i as f32; 4])
+            .build();
+        ids.push(graph.add_node(node).unwrap());
+    }
+    for pair in ids.windows(2) {
+        graph
+            .add_edge(Edge::new(pair[1], pair[0], EdgeType::RelatedTo, 0.8))
+            .unwrap();
+    }
+    graph
+}
+
+#[test]
+fn test_write_parquet_round_trips_rows() {
+    let graph = sample_graph();
+    let dir = tempfile::tempdir().unwrap();
+    let nodes_path = dir.path().join("nodes.parquet");
+    let edges_path = dir.path().join("edges.parquet");
+
+    write_parquet(&graph, &nodes_path, Some(&edges_path)).unwrap();
+
+    let reader = SerializedFileReader::new(File::open(&nodes_path).unwrap()).unwrap();
+    let metadata = reader.metadata().file_metadata();
+    assert_eq!(metadata.num_rows() as usize, graph.node_count());
+    let columns: Vec<&str> = metadata
+        .schema_descr()
+        .columns()
+        .iter()
+        .map(|c| c.name())
+        .collect();
+    assert_eq!(
+        columns,
+        [
+            "id",
+            "event_type",
+            "created_at",
+            "session_id",
+            "confidence",
+            "decay_score",
+            "access_count",
+            "content"
+        ]
+    );
+
+    let rows: Vec<_> = reader
+        .get_row_iter(None)
+        .unwrap()
+        .map(|row| row.unwrap())
+        .collect();
+    for (row, node) in rows.iter().zip(graph.nodes()) {
+        assert_eq!(row.get_ulong(0).unwrap(), node.id);
+        assert_eq!(row.get_string(1).unwrap(), node.event_type.name());
+        assert_eq!(row.get_uint(3).unwrap(), node.session_id);
+        assert_eq!(row.get_float(4).unwrap(), node.confidence);
+        assert_eq!(row.get_string(7).unwrap(), &node.content);
+    }
+
+    let reader = SerializedFileReader::new(File::open(&edges_path).unwrap()).unwrap();
+    assert_eq!(
+        reader.metadata().file_metadata().num_rows() as usize,
+        graph.edge_count()
+    );
+}
+
+#[test]
+fn test_write_parquet_without_edges_and_empty_graph() {
+    let dir = tempfile::tempdir().unwrap();
+    let nodes_path = dir.path().join("nodes.parquet");
+
+    write_parquet(&MemoryGraph::new(4), &nodes_path, None).unwrap();
+
+    let reader = SerializedFileReader::new(File::open(&nodes_path).unwrap()).unwrap();
+    assert_eq!(reader.metadata().file_metadata().num_rows(), 0);
+    assert!(!dir.path().join("edges.parquet").exists());
+}
@@ -0,0 +1,150 @@
+//! Phase 6: Ed25519 file signatures (`signing` feature).
+#![cfg(feature = "signing")]
+
+use agentic_memory::format::signing::{
+    is_signed, sign_bytes, sign_file, signed_body, verify_bytes, verify_file, SigningKey,
+};
+use agentic_memory::format::{AmemReader, AmemWriter, SignatureStatus};
+use agentic_memory::graph::MemoryGraph;
+use agentic_memory::types::edge::{Edge, EdgeType};
+use agentic_memory::types::event::{CognitiveEventBuilder, EventType};
+
+use std::io::Cursor;
+
+fn key(seed: u8) -> SigningKey {
+    SigningKey::from_bytes(&[seed; 32])
+}
+
+fn encoded_graph(dimension: usize) -> Vec<u8> {
+    let mut graph = MemoryGraph::new(dimension);
+    let a = graph
+        .add_node(
+            CognitiveEventBuilder::new(EventType::Fact, "user prefers Rust")
+                .feature_vec(vec![0.0; dimension])
+                .build(),
+        )
+        .unwrap();
+    let b = graph
+        .add_node(
+            CognitiveEventBuilder::new(EventType::Decision, "use Rust for the CLI")
+                .feature_vec(vec![0.0; dimension])
+                .build(),
+        )
+        .unwrap();
+    graph
+        .add_edge(Edge::new(b, a, EdgeType::CausedBy, 1.0))
+        .unwrap();
+    let mut out = Vec::new();
+    AmemWriter::new(dimension)
+        .write_to(&graph, &mut out)
+        .unwrap();
+    out
+}
+
+#[test]
+fn test_sign_and_verify_roundtrip() {
+    let data = encoded_graph(16);
+    let signer = key(1);
+    let signed = sign_bytes(&data, &signer).unwrap();
+
+    assert!(is_signed(&signed).unwrap());
+    assert_eq!(signed_body(&signed).unwrap(), &data[..]);
+    assert_eq!(
+        verify_bytes(&signed, &signer.verifying_key()).unwrap(),
+        SignatureStatus::Valid
+    );
+    assert_eq!(
+        verify_bytes(&signed, &key(2).verifying_key()).unwrap(),
+        SignatureStatus::Invalid
+    );
+}
+
+#[test]
+fn test_tampered_body_is_invalid() {
+    let signer = key(1);
+    let mut signed = sign_bytes(&encoded_graph(16), &signer).unwrap();
+    // Flip a byte inside the node table, well before the signature section.
+    signed[80] ^= 0xFF;
+    assert_eq!(
+        verify_bytes(&signed, &signer.verifying_key()).unwrap(),
+        SignatureStatus::Invalid
+    );
+}
+
+#[test]
+fn test_unsigned_file_reports_unsigned() {
+    let data = encoded_graph(16);
+    assert!(!is_signed(&data).unwrap());
+    assert_eq!(
+        verify_bytes(&data, &key(1).verifying_key()).unwrap(),
+        SignatureStatus::Unsigned
+    );
+}
+
+#[test]
+fn test_signed_file_still_reads() {
+    let signed = sign_bytes(&encoded_graph(16), &key(1)).unwrap();
+    let graph = AmemReader::read_from(&mut Cursor::new(&signed)).unwrap();
+    assert_eq!(graph.node_count(), 2);
+    assert_eq!(graph.edge_count(), 1);
+    assert_eq!(graph.get_node(0).unwrap().content, "user prefers Rust");
+}
+
+#[test]
+fn test_resigning_replaces_trailer() {
+    let data = encoded_graph(16);
+    let first = sign_bytes(&data, &key(1)).unwrap();
+    let second = sign_bytes(&first, &key(2)).unwrap();
+
+    assert_eq!(second.len(), first.len());
+    assert_eq!(
+        verify_bytes(&second, &key(2).verifying_key()).unwrap(),
+        SignatureStatus::Valid
+    );
+    assert_eq!(
+        verify_bytes(&second, &key(1).verifying_key()).unwrap(),
+        SignatureStatus::Invalid
+    );
+}
+
+#[test]
+fn test_writer_with_key_signs_every_write() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("signed.amem");
+    let signer = key(3);
+
+    let mut graph = MemoryGraph::new(16);
+    graph
+        .add_node(
+            CognitiveEventBuilder::new(EventType::Fact, "first")
+                .feature_vec(vec![0.0; 16])
+                .build(),
+        )
+        .unwrap();
+    let writer = AmemWriter::new(16).with_signing_key(signer.clone());
+    writer.write_to_file(&graph, &path).unwrap();
+    assert_eq!(
+        verify_file(&path, &signer.verifying_key()).unwrap(),
+        SignatureStatus::Valid
+    );
+
+    // A plain writer drops the signature; sign_file restores it.
+    graph
+        .add_node(
+            CognitiveEventBuilder::new(EventType::Fact, "second")
+                .feature_vec(vec![0.0; 16])
+                .build(),
+        )
+        .unwrap();
+    AmemWriter::new(16).write_to_file(&graph, &path).unwrap();
+    assert_eq!(
+        verify_file(&path, &signer.verifying_key()).unwrap(),
+        SignatureStatus::Unsigned
+    );
+    sign_file(&path, &signer).unwrap();
+    assert_eq!(
+        verify_file(&path, &signer.verifying_key()).unwrap(),
+        SignatureStatus::Valid
+    );
+    assert_eq!(AmemReader::read_from_file(&path).unwrap().node_count(), 2);
+}
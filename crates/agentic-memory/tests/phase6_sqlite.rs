@@ -0,0 +1,180 @@
+//! Phase 6: SQLite graph backend (`sqlite-backend` feature).
+#![cfg(feature = "sqlite-backend")]
+
+use agentic_memory::format::AmemReader;
+use agentic_memory::graph::{GraphBackend, MemoryGraph, SqliteGraph};
+use agentic_memory::types::edge::{Edge, EdgeType};
+use agentic_memory::types::error::AmemError;
+use agentic_memory::types::event::{CognitiveEventBuilder, EventType};
+
+#[test]
+fn test_sqlite_graph_nodes_and_edges() {
+    let mut db = SqliteGraph::open_in_memory(4).unwrap();
+    let a = db
+        .add_node(
+            CognitiveEventBuilder::new(EventType::Fact, "user prefers Rust")
+                .tag("project", "cli")
+                .feature_vec(vec![1.0, 0.0, 0.0, 0.5])
+                .build(),
+        )
+        .unwrap();
+    // The store pads an empty vector to its own dimension.
+    let mut decision =
+        CognitiveEventBuilder::new(EventType::Decision, "use Rust for the CLI").build();
+    decision.feature_vec.clear();
+    let b = db.add_node(decision).unwrap();
+    assert_eq!((a, b), (0, 1));
+    db.add_edge(Edge::new(b, a, EdgeType::CausedBy, 0.9))
+        .unwrap();
+
+    assert_eq!(db.node_count().unwrap(), 2);
+    assert_eq!(db.edge_count().unwrap(), 1);
+    let node = db.get_node(a).unwrap().unwrap();
+    assert_eq!(node.content, "user prefers Rust");
+    assert_eq!(node.tags["project"], "cli");
+    assert_eq!(node.feature_vec, vec![1.0, 0.0, 0.0, 0.5]);
+    assert_eq!(db.get_node(b).unwrap().unwrap().feature_vec, vec![0.0; 4]);
+    assert!(db.get_node(7).unwrap().is_none());
+
+    assert_eq!(db.edges_from(b).unwrap()[0].target_id, a);
+    assert_eq!(db.edges_to(a).unwrap()[0].edge_type, EdgeType::CausedBy);
+    assert!(db.edges_from(a).unwrap().is_empty());
+
+    assert!(matches!(
+        db.add_edge(Edge::new(a, a, EdgeType::RelatedTo, 1.0)),
+        Err(AmemError::SelfEdge(0))
+    ));
+    assert!(matches!(
+        db.add_edge(Edge::new(a, 9, EdgeType::RelatedTo, 1.0)),
+        Err(AmemError::InvalidEdgeTarget(9))
+    ));
+    assert!(matches!(
+        db.add_node(
+            CognitiveEventBuilder::new(EventType::Fact, "wide")
+                .feature_vec(vec![0.0; 8])
+                .build()
+        ),
+        Err(AmemError::DimensionMismatch { .. })
+    ));
+}
+
+#[test]
+fn test_sqlite_graph_persists_and_round_trips() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("brain.sqlite");
+
+    let mut graph = MemoryGraph::new(8);
+    for content in ["first", "second", "third"] {
+        graph
+            .add_node(
+                CognitiveEventBuilder::new(EventType::Fact, content)
+                    .feature_vec(vec![0.0; 8])
+                    .build(),
+            )
+            .unwrap();
+    }
+    graph
+        .add_edge(Edge::new(2, 0, EdgeType::Supports, 0.5))
+        .unwrap();
+    SqliteGraph::from_graph(&graph, &path).unwrap();
+
+    let mut reopened = AmemReader::open_sqlite(&path).unwrap();
+    assert_eq!(reopened.dimension(), 8);
+    assert_eq!(reopened.node_count().unwrap(), 3);
+    let id = reopened
+        .add_node(
+            CognitiveEventBuilder::new(EventType::Fact, "fourth")
+                .feature_vec(vec![0.0; 8])
+                .build(),
+        )
+        .unwrap();
+    assert_eq!(id, 3);
+
+    let loaded = reopened.to_memory_graph().unwrap();
+    assert_eq!(loaded.node_count(), 4);
+    assert_eq!(loaded.edge_count(), 1);
+    assert_eq!(loaded.get_node(1).unwrap().content, "second");
+    assert_eq!(loaded.edges_from(2)[0].target_id, 0);
+
+    assert!(matches!(
+        SqliteGraph::open_with_dimension(&path, 16),
+        Err(AmemError::DimensionMismatch {
+            expected: 16,
+            got: 8
+        })
+    ));
+}
+
+#[test]
+fn test_sqlite_graph_apply_changes_writes_only_changes() {
+    let mut graph = MemoryGraph::new(4);
+    for content in ["first", "second", "third"] {
+        graph
+            .add_node(
+                CognitiveEventBuilder::new(EventType::Fact, content)
+                    .feature_vec(vec![0.0; 4])
+                    .build(),
+            )
+            .unwrap();
+    }
+    graph
+        .add_edge(Edge::new(2, 0, EdgeType::Supports, 0.5))
+        .unwrap();
+    let mut db = SqliteGraph::open_in_memory(4).unwrap();
+    db.store(&graph).unwrap();
+
+    graph.track_changes();
+    graph.get_node_mut(1).unwrap().content = "second, revised".to_string();
+    graph.remove_node(0).unwrap();
+    let id = graph
+        .add_node(
+            CognitiveEventBuilder::new(EventType::Decision, "fourth")
+                .feature_vec(vec![1.0; 4])
+                .build(),
+        )
+        .unwrap();
+    let implements = graph.register_edge_type("implements").unwrap();
+    graph.add_edge(Edge::new(id, 1, implements, 0.7)).unwrap();
+
+    let changes = graph.take_changes();
+    assert_eq!(
+        changes.nodes.iter().copied().collect::<Vec<_>>(),
+        vec![1, id]
+    );
+    assert_eq!(
+        changes.removed_nodes.iter().copied().collect::<Vec<_>>(),
+        vec![0]
+    );
+    assert!(changes.edge_sources.contains(&2) && changes.edge_sources.contains(&id));
+    assert!(graph.changes().unwrap().is_empty());
+
+    db.apply_changes(&graph, &changes).unwrap();
+    let stored: Vec<(u64, String)> = db
+        .nodes()
+        .unwrap()
+        .into_iter()
+        .map(|n| (n.id, n.content))
+        .collect();
+    assert_eq!(
+        stored,
+        vec![
+            (1, "second, revised".to_string()),
+            (2, "third".to_string()),
+            (id, "fourth".to_string())
+        ]
+    );
+    assert_eq!(db.edge_count().unwrap(), 1);
+    let edge = db.edges_from(id).unwrap()[0];
+    assert_eq!((edge.target_id, edge.weight), (1, 0.7));
+    assert_eq!(db.edge_type_names().name(edge.edge_type), "implements");
+
+    // New nodes keep getting fresh IDs after changes are applied.
+    let next = db
+        .add_node(
+            CognitiveEventBuilder::new(EventType::Fact, "fifth")
+                .feature_vec(vec![0.0; 4])
+                .build(),
+        )
+        .unwrap();
+    assert_eq!(next, id + 1);
+}
@@ -65,7 +65,7 @@ fn main() {
     for _ in 0..10 {
         let _ = qe.text_search(&graph, Some(&ti), Some(&dl), TextSearchParams {
             query: "API rate limit".into(), max_results: 10,
-            event_types: vec![], session_ids: vec![], min_score: 0.0,
+            event_types: vec![], session_ids: vec![], min_score: 0.0, confidence_weight: 0.0,
         });
     }
     println!("bm25_fast_10k (avg 10): {:?}", s.elapsed() / 10);
@@ -75,7 +75,7 @@ fn main() {
     for _ in 0..10 {
         let _ = qe.text_search(&graph, None, None, TextSearchParams {
             query: "API rate limit".into(), max_results: 10,
-            event_types: vec![], session_ids: vec![], min_score: 0.0,
+            event_types: vec![], session_ids: vec![], min_score: 0.0, confidence_weight: 0.0,
         });
     }
     println!("bm25_slow_10k (avg 10): {:?}", s.elapsed() / 10);
@@ -111,6 +111,7 @@ fn main() -> AmemResult<()> {
             created_after: None,
             created_before: None,
             min_decay_score: None,
+            required_tags: Default::default(),
             max_results: 50,
             sort_by: PatternSort::MostRecent,
         },